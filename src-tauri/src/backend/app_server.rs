@@ -1,28 +1,30 @@
 use serde_json::{json, Value};
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::env;
 use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, RwLock, Weak};
+use std::time::{Duration, Instant};
 
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::{Child, ChildStdin, Command};
 use tokio::sync::{mpsc, oneshot, Mutex};
-use tokio::time::timeout;
+use tokio::time::{sleep, timeout};
 
 use crate::backend::events::{AppServerEvent, EventSink};
+use crate::backend::protocol_recorder::{Direction, ProtocolRecorder};
 use crate::codex::args::parse_codex_args;
+use crate::shared::core_error::CoreError;
 use crate::shared::process_core::{kill_child_process_tree, tokio_command};
-use crate::types::WorkspaceEntry;
+use crate::types::{RequestTimeoutSettings, WorkspaceEntry};
 
 #[cfg(target_os = "windows")]
 use crate::shared::process_core::{build_cmd_c_command, resolve_windows_executable};
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 
-fn extract_thread_id(value: &Value) -> Option<String> {
+pub(crate) fn extract_thread_id(value: &Value) -> Option<String> {
     fn extract_from_container(container: Option<&Value>) -> Option<String> {
         let container = container?;
         container
@@ -469,6 +471,9 @@ fn resolve_routed_workspace_id(
 pub(crate) struct RequestContext {
     workspace_id: String,
     method: String,
+    /// Set for `turn/start`, so the stdout reader can register the retry
+    /// context before it handles an `error` sent right behind the response.
+    turn_start_params: Option<Value>,
 }
 
 fn build_initialize_params(client_version: &str) -> Value {
@@ -484,8 +489,188 @@ fn build_initialize_params(client_version: &str) -> Value {
     })
 }
 
-const REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+/// `AppSettings::request_timeouts`, shared by the sessions and remote
+/// connections of one app or daemon so saved settings apply to them at once.
+#[derive(Clone, Default)]
+pub(crate) struct RequestTimeouts(Arc<RwLock<RequestTimeoutSettings>>);
+
+impl RequestTimeouts {
+    /// Settings that fail validation, e.g. a hand-edited zero, fall back to
+    /// the defaults.
+    pub(crate) fn new(settings: &RequestTimeoutSettings) -> Self {
+        if let Err(err) = settings.validate() {
+            tracing::warn!("ignoring request timeouts from settings: {err}");
+            return Self::default();
+        }
+        Self(Arc::new(RwLock::new(settings.clone())))
+    }
+
+    /// Applies settings that already passed `RequestTimeoutSettings::validate`.
+    pub(crate) fn update(&self, settings: &RequestTimeoutSettings) {
+        *self
+            .0
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = settings.clone();
+    }
+
+    pub(crate) fn timeout_for(&self, method: &str) -> Duration {
+        self.0
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .timeout_for(method)
+    }
+
+    fn cancel_notifications_enabled(&self) -> bool {
+        self.0
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .send_cancel_notifications
+    }
+}
+
+const CANCEL_REQUEST_METHOD: &str = "$/cancelRequest";
 const MAX_TURN_START_RETRY_ATTEMPTS: u8 = 1;
+const STDERR_TAIL_LINES: usize = 20;
+/// Log target for app-server stderr, tagged with the workspace that spawned it.
+const APP_SERVER_STDERR_TARGET: &str = "app_server::stderr";
+const EXIT_STATUS_POLLS: usize = 40;
+const EXIT_STATUS_POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// Requests that leave a thread loaded in the app-server process.
+const THREAD_LOADING_METHODS: &[&str] =
+    &["thread/start", "thread/resume", "thread/fork", "turn/start"];
+
+/// What the session supervisor needs to know about a child once it is gone.
+pub(crate) struct SessionSupervision {
+    /// Thread id -> workspace id for every thread loaded in this process, so a
+    /// replacement process can resume them.
+    loaded_threads: Mutex<HashMap<String, String>>,
+    /// Thread id -> workspace id for turns that started and have not completed.
+    running_turns: Mutex<HashMap<String, String>>,
+    /// Workspace id -> when it last sent a request or received an event.
+    last_activity: Mutex<HashMap<String, Instant>>,
+    stderr_tail: Mutex<VecDeque<String>>,
+    started_at: Instant,
+    /// Restarts in a row that followed a crash soon after start.
+    pub(crate) crash_restarts: AtomicU32,
+}
+
+impl Default for SessionSupervision {
+    fn default() -> Self {
+        Self {
+            loaded_threads: Mutex::new(HashMap::new()),
+            running_turns: Mutex::new(HashMap::new()),
+            last_activity: Mutex::new(HashMap::new()),
+            stderr_tail: Mutex::new(VecDeque::new()),
+            started_at: Instant::now(),
+            crash_restarts: AtomicU32::new(0),
+        }
+    }
+}
+
+impl SessionSupervision {
+    pub(crate) fn uptime(&self) -> Duration {
+        self.started_at.elapsed()
+    }
+
+    async fn record_stderr(&self, line: &str) {
+        let mut tail = self.stderr_tail.lock().await;
+        if tail.len() == STDERR_TAIL_LINES {
+            tail.pop_front();
+        }
+        tail.push_back(line.to_string());
+    }
+
+    async fn stderr_tail_snapshot(&self) -> Vec<String> {
+        self.stderr_tail.lock().await.iter().cloned().collect()
+    }
+
+    async fn record_loaded_thread(&self, workspace_id: &str, params: &Value, response: &Value) {
+        if response.get("error").is_some() {
+            return;
+        }
+        let thread_id =
+            extract_thread_id(response).or_else(|| extract_thread_id(&json!({ "params": params })));
+        if let Some(thread_id) = thread_id {
+            self.loaded_threads
+                .lock()
+                .await
+                .insert(thread_id, workspace_id.to_string());
+        }
+    }
+
+    async fn forget_thread(&self, thread_id: &str) {
+        self.loaded_threads.lock().await.remove(thread_id);
+        self.running_turns.lock().await.remove(thread_id);
+    }
+
+    pub(crate) async fn record_activity(&self, workspace_id: &str) {
+        self.last_activity
+            .lock()
+            .await
+            .insert(workspace_id.to_string(), Instant::now());
+    }
+
+    /// Time since `workspace_id` last saw a request or event, or since the
+    /// process started if it has seen neither.
+    pub(crate) async fn idle_for(&self, workspace_id: &str) -> Duration {
+        self.last_activity
+            .lock()
+            .await
+            .get(workspace_id)
+            .copied()
+            .unwrap_or(self.started_at)
+            .elapsed()
+    }
+
+    pub(crate) async fn record_turn_event(
+        &self,
+        method: &str,
+        thread_id: &str,
+        workspace_id: &str,
+    ) {
+        let mut running_turns = self.running_turns.lock().await;
+        match method {
+            "turn/started" => {
+                running_turns.insert(thread_id.to_string(), workspace_id.to_string());
+            }
+            "turn/completed" => {
+                running_turns.remove(thread_id);
+            }
+            _ => {}
+        }
+    }
+
+    pub(crate) async fn has_running_turn(&self, workspace_id: &str) -> bool {
+        self.running_turns
+            .lock()
+            .await
+            .values()
+            .any(|candidate| candidate == workspace_id)
+    }
+
+    /// `(thread id, workspace id)` pairs, sorted by thread id.
+    pub(crate) async fn loaded_threads_snapshot(&self) -> Vec<(String, String)> {
+        let mut threads: Vec<(String, String)> = self
+            .loaded_threads
+            .lock()
+            .await
+            .iter()
+            .map(|(thread_id, workspace_id)| (thread_id.clone(), workspace_id.clone()))
+            .collect();
+        threads.sort();
+        threads
+    }
+}
+
+/// Sent once a session's stdout closes, whether the child crashed or was
+/// stopped on purpose; the supervisor tells the two apart.
+pub(crate) struct SessionExit {
+    pub(crate) session: Arc<WorkspaceSession>,
+    pub(crate) exit_code: Option<i32>,
+    pub(crate) stderr_tail: Vec<String>,
+}
+
+pub(crate) type SessionExitSender = mpsc::UnboundedSender<SessionExit>;
 
 pub(crate) struct WorkspaceSession {
     pub(crate) codex_args: Option<String>,
@@ -501,6 +686,35 @@ pub(crate) struct WorkspaceSession {
     pub(crate) workspace_ids: Mutex<HashSet<String>>,
     pub(crate) workspace_roots: Mutex<HashMap<String, String>>,
     pub(crate) turn_start_retry_context: Mutex<HashMap<String, TurnStartRetryContext>>,
+    pub(crate) supervision: SessionSupervision,
+    /// Set when `CODEX_MONITOR_RECORD_PROTOCOL_DIR` is; see `protocol_recorder`.
+    pub(crate) recorder: Option<ProtocolRecorder>,
+    /// Ids of requests whose callers stopped waiting; drained by the task
+    /// started in `spawn_workspace_session`.
+    pub(crate) request_cancellations: mpsc::UnboundedSender<u64>,
+    pub(crate) request_timeouts: RequestTimeouts,
+}
+
+/// Cancels an in-flight request if the future awaiting it is dropped before a
+/// response arrives.
+struct PendingRequestGuard<'a> {
+    session: &'a WorkspaceSession,
+    id: u64,
+    armed: bool,
+}
+
+impl PendingRequestGuard<'_> {
+    fn disarm(&mut self) {
+        self.armed = false;
+    }
+}
+
+impl Drop for PendingRequestGuard<'_> {
+    fn drop(&mut self) {
+        if self.armed {
+            let _ = self.session.request_cancellations.send(self.id);
+        }
+    }
 }
 
 impl WorkspaceSession {
@@ -593,17 +807,24 @@ impl WorkspaceSession {
             .retain(|_, ctx| ctx.thread_id != thread_id);
     }
 
-    async fn write_message(&self, value: Value) -> Result<(), String> {
+    async fn write_message(&self, value: Value) -> Result<(), CoreError> {
         let mut stdin = self.stdin.lock().await;
         let mut line = serde_json::to_string(&value).map_err(|e| e.to_string())?;
+        if let Some(recorder) = &self.recorder {
+            recorder.record(Direction::Send, &line);
+        }
         line.push('\n');
         stdin
             .write_all(line.as_bytes())
             .await
-            .map_err(|e| e.to_string())
+            .map_err(|e| CoreError::internal(e.to_string()))
     }
 
-    pub(crate) async fn send_request(&self, method: &str, params: Value) -> Result<Value, String> {
+    pub(crate) async fn send_request(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> Result<Value, CoreError> {
         self.send_request_for_workspace(self.owner_workspace_id.as_str(), method, params)
             .await
     }
@@ -613,16 +834,18 @@ impl WorkspaceSession {
         workspace_id: &str,
         method: &str,
         params: Value,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, CoreError> {
         let id = self.next_id.fetch_add(1, Ordering::SeqCst);
         let (tx, rx) = oneshot::channel();
         self.register_workspace(workspace_id).await;
+        self.supervision.record_activity(workspace_id).await;
         self.pending.lock().await.insert(id, tx);
         self.request_context.lock().await.insert(
             id,
             RequestContext {
                 workspace_id: workspace_id.to_string(),
                 method: method.to_string(),
+                turn_start_params: (method == "turn/start").then(|| params.clone()),
             },
         );
         if let Some(thread_id) = extract_thread_id(&json!({ "params": params.clone() })) {
@@ -639,31 +862,62 @@ impl WorkspaceSession {
             self.request_context.lock().await.remove(&id);
             return Err(error);
         }
-        match timeout(REQUEST_TIMEOUT, rx).await {
+        let mut guard = PendingRequestGuard {
+            session: self,
+            id,
+            armed: true,
+        };
+        let request_timeout = self.request_timeouts.timeout_for(method);
+        let result = timeout(request_timeout, rx).await;
+        guard.disarm();
+        match result {
             Ok(Ok(value)) => {
-                if method == "turn/start" {
-                    self.register_turn_start_retry_context(workspace_id, &params, &value)
+                if THREAD_LOADING_METHODS.contains(&method) {
+                    self.supervision
+                        .record_loaded_thread(workspace_id, &params, &value)
                         .await;
                 }
                 Ok(value)
             }
-            Ok(Err(_)) => Err("request canceled".to_string()),
+            Ok(Err(_)) => Err(CoreError::internal("request canceled")),
             Err(_) => {
-                self.pending.lock().await.remove(&id);
-                self.request_context.lock().await.remove(&id);
-                Err(format!(
+                self.cancel_request(id).await;
+                Err(CoreError::timeout(format!(
                     "request timed out after {} seconds",
-                    REQUEST_TIMEOUT.as_secs()
-                ))
+                    request_timeout.as_secs()
+                )))
             }
         }
     }
 
+    /// True while `workspace_id` has a request in flight or a turn running.
+    pub(crate) async fn is_busy(&self, workspace_id: &str) -> bool {
+        let has_pending_request = self
+            .request_context
+            .lock()
+            .await
+            .values()
+            .any(|context| context.workspace_id == workspace_id);
+        has_pending_request || self.supervision.has_running_turn(workspace_id).await
+    }
+
+    /// Forgets a request nobody is waiting for and, when enabled, tells the
+    /// app-server to stop working on it. No-op once the response has arrived.
+    pub(crate) async fn cancel_request(&self, id: u64) {
+        let was_pending = self.pending.lock().await.remove(&id).is_some();
+        self.request_context.lock().await.remove(&id);
+        if was_pending && self.request_timeouts.cancel_notifications_enabled() {
+            let _ = self
+                .send_notification(CANCEL_REQUEST_METHOD, Some(json!({ "id": id })))
+                .await;
+        }
+    }
+
     pub(crate) async fn send_notification(
         &self,
         method: &str,
         params: Option<Value>,
-    ) -> Result<(), String> {
+    ) -> Result<(), CoreError> {
         let value = if let Some(params) = params {
             json!({ "method": method, "params": params })
         } else {
@@ -672,7 +926,7 @@ impl WorkspaceSession {
         self.write_message(value).await
     }
 
-    pub(crate) async fn send_response(&self, id: Value, result: Value) -> Result<(), String> {
+    pub(crate) async fn send_response(&self, id: Value, result: Value) -> Result<(), CoreError> {
         self.write_message(json!({ "id": id, "result": result }))
             .await
     }
@@ -864,6 +1118,37 @@ pub(crate) async fn check_codex_installation(
     })
 }
 
+/// Exit code of a child whose stdout has closed. `None` when it was killed by
+/// a signal or has not exited yet.
+async fn wait_for_exit_code(session: &WorkspaceSession) -> Option<i32> {
+    for _ in 0..EXIT_STATUS_POLLS {
+        match session.child.lock().await.try_wait() {
+            Ok(Some(status)) => return status.code(),
+            Ok(None) => {}
+            Err(_) => return None,
+        }
+        sleep(EXIT_STATUS_POLL_INTERVAL).await;
+    }
+    None
+}
+
+/// Holds only a weak reference so the task ends once the session is dropped
+/// and the channel closes.
+fn spawn_request_cancellation_task(
+    session: Weak<WorkspaceSession>,
+    mut cancellations: mpsc::UnboundedReceiver<u64>,
+) {
+    tokio::spawn(async move {
+        while let Some(id) = cancellations.recv().await {
+            let Some(session) = session.upgrade() else {
+                break;
+            };
+            session.cancel_request(id).await;
+        }
+    });
+}
+
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn spawn_workspace_session<E: EventSink>(
     entry: WorkspaceEntry,
     default_codex_bin: Option<String>,
@@ -871,7 +1156,9 @@ pub(crate) async fn spawn_workspace_session<E: EventSink>(
     codex_home: Option<PathBuf>,
     client_version: String,
     event_sink: E,
-) -> Result<Arc<WorkspaceSession>, String> {
+    session_exits: SessionExitSender,
+    request_timeouts: RequestTimeouts,
+) -> Result<Arc<WorkspaceSession>, CoreError> {
     let codex_bin = default_codex_bin;
     let _ = check_codex_installation(codex_bin.clone()).await?;
 
@@ -889,10 +1176,20 @@ pub(crate) async fn spawn_workspace_session<E: EventSink>(
     command.stderr(std::process::Stdio::piped());
 
     let mut child = command.spawn().map_err(|e| e.to_string())?;
-    let stdin = child.stdin.take().ok_or("missing stdin")?;
-    let stdout = child.stdout.take().ok_or("missing stdout")?;
-    let stderr = child.stderr.take().ok_or("missing stderr")?;
-
+    let stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| CoreError::internal("missing stdin"))?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| CoreError::internal("missing stdout"))?;
+    let stderr = child
+        .stderr
+        .take()
+        .ok_or_else(|| CoreError::internal("missing stderr"))?;
+
+    let (request_cancellations, request_cancellation_rx) = mpsc::unbounded_channel();
     let session = Arc::new(WorkspaceSession {
         codex_args,
         child: Mutex::new(child),
@@ -909,7 +1206,12 @@ pub(crate) async fn spawn_workspace_session<E: EventSink>(
             normalize_path_for_matching(&entry.path),
         )])),
         turn_start_retry_context: Mutex::new(HashMap::new()),
+        supervision: SessionSupervision::default(),
+        recorder: ProtocolRecorder::from_env(&entry.id),
+        request_cancellations,
+        request_timeouts,
     });
+    spawn_request_cancellation_task(Arc::downgrade(&session), request_cancellation_rx);
 
     let session_clone = Arc::clone(&session);
     let fallback_workspace_id = entry.id.clone();
@@ -920,6 +1222,9 @@ pub(crate) async fn spawn_workspace_session<E: EventSink>(
             if line.trim().is_empty() {
                 continue;
             }
+            if let Some(recorder) = &session_clone.recorder {
+                recorder.record(Direction::Recv, &line);
+            }
             let mut value: Value = match serde_json::from_str(&line) {
                 Ok(value) => value,
                 Err(err) => {
@@ -951,6 +1256,15 @@ pub(crate) async fn spawn_workspace_session<E: EventSink>(
             if let Some(id) = maybe_id {
                 if has_result_or_error {
                     if let Some(context) = session_clone.request_context.lock().await.remove(&id) {
+                        if let Some(params) = context.turn_start_params {
+                            session_clone
+                                .register_turn_start_retry_context(
+                                    &context.workspace_id,
+                                    &params,
+                                    &value,
+                                )
+                                .await;
+                        }
                         request_workspace = Some(context.workspace_id);
                         request_method = Some(context.method);
                     }
@@ -1024,6 +1338,16 @@ pub(crate) async fn spawn_workspace_session<E: EventSink>(
             ) else {
                 continue;
             };
+            session_clone
+                .supervision
+                .record_activity(&routed_workspace_id)
+                .await;
+            if let (Some(method), Some(tid)) = (method_name.as_deref(), thread_id.as_deref()) {
+                session_clone
+                    .supervision
+                    .record_turn_event(method, tid, &routed_workspace_id)
+                    .await;
+            }
 
             if method_name.as_deref() == Some("thread/archived") {
                 if let Some(ref tid) = thread_id {
@@ -1031,6 +1355,7 @@ pub(crate) async fn spawn_workspace_session<E: EventSink>(
                     session_clone
                         .clear_turn_start_retry_contexts_for_thread(tid)
                         .await;
+                    session_clone.supervision.forget_thread(tid).await;
                 }
             }
             if method_name.as_deref() == Some("turn/completed") {
@@ -1070,7 +1395,7 @@ pub(crate) async fn spawn_workspace_session<E: EventSink>(
                                         .await;
                                     let retry_error = match retry_result {
                                         Ok(response) => extract_response_error_message(&response),
-                                        Err(error) => Some(error),
+                                        Err(error) => Some(error.message),
                                     };
                                     if let Some(error_message) = retry_error {
                                         session_for_retry
@@ -1201,16 +1526,31 @@ pub(crate) async fn spawn_workspace_session<E: EventSink>(
         session_clone.pending.lock().await.clear();
         session_clone.request_context.lock().await.clear();
         session_clone.turn_start_retry_context.lock().await.clear();
+
+        let exit_code = wait_for_exit_code(&session_clone).await;
+        let stderr_tail = session_clone.supervision.stderr_tail_snapshot().await;
+        let _ = session_exits.send(SessionExit {
+            session: session_clone,
+            exit_code,
+            stderr_tail,
+        });
     });
 
     let workspace_id = entry.id.clone();
     let event_sink_clone = event_sink.clone();
+    let session_for_stderr = Arc::clone(&session);
     tokio::spawn(async move {
         let mut lines = BufReader::new(stderr).lines();
         while let Ok(Some(line)) = lines.next_line().await {
             if line.trim().is_empty() {
                 continue;
             }
+            session_for_stderr.supervision.record_stderr(&line).await;
+            tracing::info!(
+                target: APP_SERVER_STDERR_TARGET,
+                workspace_id = %workspace_id,
+                "{line}"
+            );
             let payload = AppServerEvent {
                 workspace_id: workspace_id.clone(),
                 message: json!({
@@ -1233,10 +1573,9 @@ pub(crate) async fn spawn_workspace_session<E: EventSink>(
         Err(_) => {
             let mut child = session.child.lock().await;
             kill_child_process_tree(&mut child).await;
-            return Err(
-                "Codex app-server did not respond to initialize. Check that `codex app-server` works in Terminal."
-                    .to_string(),
-            );
+            return Err(CoreError::timeout(
+                "Codex app-server did not respond to initialize. Check that `codex app-server` works in Terminal.",
+            ));
         }
     };
     init_response?;
@@ -1262,10 +1601,22 @@ mod tests {
         extract_thread_spawn_parent_thread_id, extract_turn_error_details, extract_turn_id,
         normalize_path_for_matching, normalize_root_path, resolve_routed_workspace_id,
         resolve_spawned_thread_workspace, resolve_started_thread_workspace,
-        resolve_workspace_for_cwd, set_turn_error_will_retry, TurnErrorDetails,
+        resolve_workspace_for_cwd, set_turn_error_will_retry, SessionSupervision, TurnErrorDetails,
+        STDERR_TAIL_LINES,
+    };
+    use super::{
+        kill_child_process_tree, spawn_request_cancellation_task, RequestTimeoutSettings,
+        RequestTimeouts, WorkspaceSession,
     };
+    use crate::backend::test_support::session;
+    use serde_json::{json, Value};
     use std::collections::HashMap;
-    use serde_json::json;
+    use std::sync::atomic::AtomicU64;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::io::{AsyncBufReadExt, BufReader};
+    use tokio::sync::mpsc;
+    use tokio::time::timeout;
 
     #[test]
     fn extract_thread_id_reads_camel_case() {
@@ -1699,4 +2050,90 @@ mod tests {
             Some("nope")
         );
     }
+
+    #[test]
+    fn session_supervision_tracks_loaded_threads_and_stderr_tail() {
+        tokio::runtime::Runtime::new().unwrap().block_on(async {
+            let supervision = SessionSupervision::default();
+            supervision
+                .record_loaded_thread(
+                    "ws-1",
+                    &json!({ "cwd": "/tmp" }),
+                    &json!({ "id": 1, "result": { "thread": { "id": "t-1" } } }),
+                )
+                .await;
+            supervision
+                .record_loaded_thread(
+                    "ws-2",
+                    &json!({ "threadId": "t-2", "input": [] }),
+                    &json!({ "id": 2, "result": { "turn": { "id": "turn-1" } } }),
+                )
+                .await;
+            supervision
+                .record_loaded_thread(
+                    "ws-2",
+                    &json!({ "threadId": "t-3" }),
+                    &json!({ "id": 3, "error": { "message": "no rollout" } }),
+                )
+                .await;
+            supervision.forget_thread("t-1").await;
+            assert_eq!(
+                supervision.loaded_threads_snapshot().await,
+                vec![("t-2".to_string(), "ws-2".to_string())]
+            );
+
+            for index in 0..STDERR_TAIL_LINES + 5 {
+                supervision.record_stderr(&format!("line {index}")).await;
+            }
+            let tail = supervision.stderr_tail_snapshot().await;
+            assert_eq!(tail.len(), STDERR_TAIL_LINES);
+            assert_eq!(tail.first().map(String::as_str), Some("line 5"));
+        });
+    }
+
+    #[test]
+    fn dropped_requests_are_forgotten_and_cancelled() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("runtime");
+        runtime.block_on(async {
+            let mut echo = session("ws-1");
+            let stdout = echo.child.get_mut().stdout.take().expect("child stdout");
+            let (request_cancellations, request_cancellation_rx) = mpsc::unbounded_channel();
+            let session = Arc::new(WorkspaceSession {
+                next_id: AtomicU64::new(7),
+                request_cancellations,
+                request_timeouts: RequestTimeouts::new(&RequestTimeoutSettings {
+                    send_cancel_notifications: true,
+                    ..RequestTimeoutSettings::default()
+                }),
+                ..echo
+            });
+            spawn_request_cancellation_task(Arc::downgrade(&session), request_cancellation_rx);
+
+            let abandoned = timeout(
+                Duration::from_millis(50),
+                session.send_request("thread/list", json!({})),
+            )
+            .await;
+            assert!(abandoned.is_err());
+
+            let mut lines = BufReader::new(stdout).lines();
+            let request = lines.next_line().await.expect("read").expect("request");
+            let request: Value = serde_json::from_str(&request).expect("request json");
+            assert_eq!(request["id"], json!(7));
+            let cancel = lines.next_line().await.expect("read").expect("cancel");
+            let cancel: Value = serde_json::from_str(&cancel).expect("cancel json");
+            assert_eq!(
+                cancel,
+                json!({ "method": "$/cancelRequest", "params": { "id": 7 } })
+            );
+            assert!(session.pending.lock().await.is_empty());
+            assert!(session.request_context.lock().await.is_empty());
+
+            let mut child = session.child.lock().await;
+            kill_child_process_tree(&mut child).await;
+        });
+    }
 }
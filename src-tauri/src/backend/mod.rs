@@ -1,2 +1,5 @@
 pub(crate) mod app_server;
 pub(crate) mod events;
+pub(crate) mod protocol_recorder;
+#[cfg(test)]
+pub(crate) mod test_support;
@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Directory that app-server sessions record their stdin/stdout traffic into.
+/// Recording is off unless this is set.
+pub(crate) const RECORD_DIR_ENV: &str = "CODEX_MONITOR_RECORD_PROTOCOL_DIR";
+
+const RECORDING_EXTENSION: &str = "jsonl";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Direction {
+    /// CodexMonitor -> app-server (stdin).
+    Send,
+    /// App-server -> CodexMonitor (stdout).
+    Recv,
+}
+
+/// One line of a recording. Lines that were not JSON are kept as a string
+/// `message` so parse errors replay too.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct RecordedLine {
+    pub(crate) ts: i64,
+    pub(crate) dir: Direction,
+    pub(crate) message: Value,
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+fn recording_file_stem(workspace_id: &str) -> String {
+    let plain = !workspace_id.is_empty()
+        && workspace_id.len() <= 128
+        && workspace_id
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_');
+    let prefix = if plain { workspace_id } else { "session" };
+    format!("{prefix}-{}", now_ms())
+}
+
+/// Appends every line exchanged with one app-server child to
+/// `<dir>/<workspace id>-<unix ms>.jsonl`, one `RecordedLine` per line.
+/// `codex_monitor_mock_app_server --recording` plays these files back.
+pub(crate) struct ProtocolRecorder {
+    file: Mutex<File>,
+}
+
+impl ProtocolRecorder {
+    pub(crate) fn from_env(workspace_id: &str) -> Option<Self> {
+        let dir = std::env::var_os(RECORD_DIR_ENV)?;
+        if dir.is_empty() {
+            return None;
+        }
+        Self::create(Path::new(&dir), workspace_id).ok()
+    }
+
+    fn create(dir: &Path, workspace_id: &str) -> std::io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let path = dir
+            .join(recording_file_stem(workspace_id))
+            .with_extension(RECORDING_EXTENSION);
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+
+    pub(crate) fn record(&self, dir: Direction, line: &str) {
+        let message = serde_json::from_str::<Value>(line.trim())
+            .unwrap_or_else(|_| Value::String(line.to_string()));
+        let entry = RecordedLine {
+            ts: now_ms(),
+            dir,
+            message,
+        };
+        let Ok(serialized) = serde_json::to_string(&entry) else {
+            return;
+        };
+        let Ok(mut file) = self.file.lock() else {
+            return;
+        };
+        let _ = writeln!(file, "{serialized}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::path::PathBuf;
+
+    fn temp_dir(prefix: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "codex-monitor-{prefix}-{}-{}",
+            std::process::id(),
+            uuid::Uuid::new_v4()
+        ));
+        fs::create_dir_all(&dir).expect("create temp dir");
+        dir
+    }
+
+    #[test]
+    fn recorder_writes_one_entry_per_line_in_order() {
+        let dir = temp_dir("protocol-recorder");
+        let recorder = ProtocolRecorder::create(&dir, "ws-1").expect("create recorder");
+        recorder.record(
+            Direction::Send,
+            r#"{"id":1,"method":"initialize","params":{}}"#,
+        );
+        recorder.record(Direction::Recv, r#"{"id":1,"result":{}}"#);
+        recorder.record(Direction::Recv, "not json");
+
+        let entries: Vec<PathBuf> = fs::read_dir(&dir)
+            .expect("read dir")
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .collect();
+        assert_eq!(entries.len(), 1);
+        let file_name = entries[0].file_name().and_then(|name| name.to_str());
+        assert!(file_name.is_some_and(|name| name.starts_with("ws-1-") && name.ends_with(".jsonl")));
+
+        let contents = fs::read_to_string(&entries[0]).expect("read recording");
+        let lines: Vec<RecordedLine> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("recorded line"))
+            .collect();
+        let summary: Vec<(Direction, Value)> = lines
+            .into_iter()
+            .map(|line| (line.dir, line.message))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    Direction::Send,
+                    json!({ "id": 1, "method": "initialize", "params": {} })
+                ),
+                (Direction::Recv, json!({ "id": 1, "result": {} })),
+                (Direction::Recv, json!("not json")),
+            ]
+        );
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn recording_file_stem_falls_back_for_unsafe_workspace_ids() {
+        assert!(recording_file_stem("ws_1").starts_with("ws_1-"));
+        assert!(recording_file_stem("../etc").starts_with("session-"));
+        assert!(recording_file_stem("").starts_with("session-"));
+    }
+}
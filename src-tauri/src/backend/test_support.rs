@@ -0,0 +1,85 @@
+//! Fixtures shared by the session tests in `backend` and `shared`.
+
+use std::collections::{HashMap, HashSet};
+use std::process::Stdio;
+use std::sync::atomic::AtomicU64;
+use std::sync::Arc;
+
+use serde_json::Value;
+use tokio::process::Command;
+use tokio::sync::{mpsc, Mutex};
+
+use super::app_server::{RequestTimeouts, SessionSupervision, WorkspaceSession};
+use super::events::{AppServerEvent, EventSink, TerminalExit, TerminalOutput};
+
+/// A session whose "app-server" is `cat` (`more` on Windows): whatever is
+/// written to its stdin comes back on the child's piped stdout. Override
+/// fields with struct update syntax, e.g. `WorkspaceSession { codex_args,
+/// ..session("ws-1") }`.
+pub(crate) fn session(owner_workspace_id: &str) -> WorkspaceSession {
+    let mut cmd = if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", "more"]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "cat"]);
+        cmd
+    };
+    cmd.stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+
+    let mut child = cmd.spawn().expect("spawn dummy child");
+    let stdin = child.stdin.take().expect("dummy child stdin");
+
+    WorkspaceSession {
+        codex_args: None,
+        child: Mutex::new(child),
+        stdin: Mutex::new(stdin),
+        pending: Mutex::new(HashMap::new()),
+        request_context: Mutex::new(HashMap::new()),
+        thread_workspace: Mutex::new(HashMap::new()),
+        next_id: AtomicU64::new(0),
+        background_thread_callbacks: Mutex::new(HashMap::new()),
+        owner_workspace_id: owner_workspace_id.to_string(),
+        workspace_ids: Mutex::new(HashSet::from([owner_workspace_id.to_string()])),
+        workspace_roots: Mutex::new(HashMap::new()),
+        turn_start_retry_context: Mutex::new(HashMap::new()),
+        supervision: SessionSupervision::default(),
+        recorder: None,
+        request_cancellations: mpsc::unbounded_channel().0,
+        request_timeouts: RequestTimeouts::default(),
+    }
+}
+
+/// Keeps every app-server event; terminal events are dropped.
+#[derive(Clone, Default)]
+pub(crate) struct RecordingSink {
+    events: Arc<std::sync::Mutex<Vec<AppServerEvent>>>,
+}
+
+impl RecordingSink {
+    pub(crate) fn events(&self) -> Vec<AppServerEvent> {
+        self.events.lock().expect("events lock").clone()
+    }
+
+    /// `(workspace_id, params)` of each recorded event with `method`.
+    pub(crate) fn with_method(&self, method: &str) -> Vec<(String, Value)> {
+        self.events()
+            .into_iter()
+            .filter(|event| event.message["method"] == method)
+            .map(|event| (event.workspace_id, event.message["params"].clone()))
+            .collect()
+    }
+}
+
+impl EventSink for RecordingSink {
+    fn emit_app_server_event(&self, event: AppServerEvent) {
+        self.events.lock().expect("events lock").push(event);
+    }
+
+    fn emit_terminal_output(&self, _event: TerminalOutput) {}
+
+    fn emit_terminal_exit(&self, _event: TerminalExit) {}
+}
@@ -0,0 +1,983 @@
+#[path = "codex_monitor_cli/client.rs"]
+mod client;
+#[path = "../shared/core_error.rs"]
+mod core_error;
+#[path = "../shared/rpc_schema.rs"]
+mod rpc_schema;
+#[allow(dead_code)]
+#[path = "../storage.rs"]
+mod storage;
+#[path = "../shared/tls_core.rs"]
+mod tls_core;
+#[allow(dead_code)]
+#[path = "../types.rs"]
+mod types;
+
+use client::{is_websocket_url, ConnectOptions, DaemonClient};
+use serde_json::{json, Map, Value};
+use std::env;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::time::{timeout, Instant};
+
+use types::AppSettings;
+
+const DEFAULT_HOST: &str = "127.0.0.1:4732";
+const APP_IDENTIFIER: &str = "com.dimillian.codexmonitor";
+const PREVIEW_MAX_CHARS: usize = 80;
+
+#[derive(Debug, Clone)]
+struct CliArgs {
+    command: CliCommand,
+    host: Option<String>,
+    token: Option<String>,
+    tls: bool,
+    tls_fingerprint: Option<String>,
+    data_dir: Option<PathBuf>,
+    json: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CliCommand {
+    WorkspacesList,
+    ThreadsList {
+        workspace_id: String,
+        limit: Option<u32>,
+    },
+    ThreadStart {
+        workspace_id: String,
+    },
+    Send(SendCommand),
+    Respond {
+        workspace_id: String,
+        request_id: String,
+        decision: Decision,
+    },
+    Diff {
+        workspace_id: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SendCommand {
+    workspace_id: String,
+    thread_id: Option<String>,
+    text: String,
+    model: Option<String>,
+    effort: Option<String>,
+    access_mode: Option<String>,
+    approvals: ApprovalMode,
+    timeout: Option<Duration>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Decision {
+    Accept,
+    Decline,
+}
+
+impl Decision {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Accept => "accept",
+            Self::Decline => "decline",
+        }
+    }
+}
+
+/// What `send` does with approval requests raised during the turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ApprovalMode {
+    /// Print the request id and keep waiting for `approve`/`deny`.
+    Wait,
+    Respond(Decision),
+}
+
+impl ApprovalMode {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "wait" => Ok(Self::Wait),
+            "accept" => Ok(Self::Respond(Decision::Accept)),
+            "decline" => Ok(Self::Respond(Decision::Decline)),
+            other => Err(format!(
+                "--approvals expects wait, accept or decline (got `{other}`)"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TurnEvent {
+    Delta { item_id: String, delta: String },
+    Approval { request_id: Value, summary: String },
+    Error { message: String, will_retry: bool },
+    Completed { error: Option<String> },
+    Other,
+}
+
+fn main() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build();
+    let result = match runtime {
+        Ok(runtime) => runtime.block_on(run()),
+        Err(err) => Err(format!("Failed to initialize async runtime: {err}")),
+    };
+    if let Err(err) = result {
+        eprintln!("{err}");
+        std::process::exit(1);
+    }
+}
+
+async fn run() -> Result<(), String> {
+    let args = parse_args()?;
+    let data_dir = args.data_dir.clone().unwrap_or_else(default_app_data_dir);
+    let settings = load_settings(&data_dir);
+    let options = resolve_connect_options(&args, settings.as_ref())?;
+    let mut client = DaemonClient::connect(&options).await?;
+
+    match &args.command {
+        CliCommand::WorkspacesList => {
+            let workspaces = client.call("list_workspaces", json!({})).await?;
+            if args.json {
+                return print_json(&workspaces);
+            }
+            for workspace in workspaces.as_array().into_iter().flatten() {
+                let connected = workspace
+                    .get("connected")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false);
+                println!(
+                    "{}\t{}\t{}\t{}",
+                    string_field(workspace, "id"),
+                    string_field(workspace, "name"),
+                    if connected { "connected" } else { "idle" },
+                    string_field(workspace, "path"),
+                );
+            }
+        }
+        CliCommand::ThreadsList {
+            workspace_id,
+            limit,
+        } => {
+            connect_workspace(&mut client, workspace_id).await?;
+            let mut params = json!({ "workspaceId": workspace_id });
+            if let Some(limit) = limit {
+                params["limit"] = json!(limit);
+            }
+            let response = client.call("list_threads", params).await?;
+            if args.json {
+                return print_json(&response);
+            }
+            for (id, preview) in thread_rows(&response) {
+                println!("{id}\t{preview}");
+            }
+        }
+        CliCommand::ThreadStart { workspace_id } => {
+            connect_workspace(&mut client, workspace_id).await?;
+            let response = client
+                .call("start_thread", json!({ "workspaceId": workspace_id }))
+                .await?;
+            if args.json {
+                return print_json(&response);
+            }
+            let thread_id = response_thread_id(&response)
+                .ok_or("start_thread response did not include a thread id")?;
+            println!("{thread_id}");
+        }
+        CliCommand::Send(command) => run_send(&mut client, command, args.json).await?,
+        CliCommand::Respond {
+            workspace_id,
+            request_id,
+            decision,
+        } => {
+            let result = client
+                .call(
+                    "respond_to_server_request",
+                    json!({
+                        "workspaceId": workspace_id,
+                        "requestId": request_id_value(request_id),
+                        "result": { "decision": decision.as_str() },
+                    }),
+                )
+                .await?;
+            if args.json {
+                return print_json(&result);
+            }
+        }
+        CliCommand::Diff { workspace_id } => {
+            let diffs = client
+                .call("get_git_diffs", json!({ "workspaceId": workspace_id }))
+                .await?;
+            if args.json {
+                return print_json(&diffs);
+            }
+            for diff in diffs.as_array().into_iter().flatten() {
+                let patch = string_field(diff, "diff");
+                if patch.is_empty() {
+                    println!("Binary or empty change: {}", string_field(diff, "path"));
+                } else if patch.ends_with('\n') {
+                    print!("{patch}");
+                } else {
+                    println!("{patch}");
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn connect_workspace(client: &mut DaemonClient, workspace_id: &str) -> Result<(), String> {
+    client
+        .call("connect_workspace", json!({ "id": workspace_id }))
+        .await
+        .map(|_| ())
+}
+
+/// Sends one message and streams the turn until it completes. Text mode
+/// writes agent message deltas to stdout and everything else to stderr;
+/// `--json` writes each app-server event for the thread as one JSON line.
+async fn run_send(
+    client: &mut DaemonClient,
+    command: &SendCommand,
+    as_json: bool,
+) -> Result<(), String> {
+    let workspace_id = command.workspace_id.as_str();
+    connect_workspace(client, workspace_id).await?;
+    let thread_id = match command.thread_id.clone() {
+        Some(thread_id) => thread_id,
+        None => {
+            let response = client
+                .call("start_thread", json!({ "workspaceId": workspace_id }))
+                .await?;
+            let thread_id = response_thread_id(&response)
+                .ok_or("start_thread response did not include a thread id")?;
+            eprintln!("thread: {thread_id}");
+            thread_id
+        }
+    };
+    client
+        .call(
+            "subscribe_events",
+            json!({ "workspaceIds": [workspace_id], "threadIds": [thread_id] }),
+        )
+        .await?;
+
+    let mut params = Map::new();
+    params.insert("workspaceId".to_string(), json!(workspace_id));
+    params.insert("threadId".to_string(), json!(thread_id));
+    params.insert("text".to_string(), json!(command.text));
+    for (key, value) in [
+        ("model", &command.model),
+        ("effort", &command.effort),
+        ("accessMode", &command.access_mode),
+    ] {
+        if let Some(value) = value {
+            params.insert(key.to_string(), json!(value));
+        }
+    }
+    let response = client
+        .call("send_user_message", Value::Object(params))
+        .await?;
+    let turn_id = response_turn_id(&response);
+
+    let deadline = command.timeout.map(|limit| Instant::now() + limit);
+    let mut stdout = std::io::stdout();
+    let mut last_item_id: Option<String> = None;
+    let mut ends_with_newline = true;
+    loop {
+        let notification = match deadline {
+            Some(deadline) => {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                timeout(remaining, client.next_notification())
+                    .await
+                    .map_err(|_| "Timed out waiting for the turn to complete".to_string())??
+            }
+            None => client.next_notification().await?,
+        };
+        let Some(message) = app_server_message(&notification, workspace_id) else {
+            continue;
+        };
+        if as_json {
+            println!(
+                "{}",
+                serde_json::to_string(message).map_err(|err| err.to_string())?
+            );
+        }
+
+        match turn_event(message, &thread_id, turn_id.as_deref()) {
+            TurnEvent::Delta { item_id, delta } => {
+                if as_json {
+                    continue;
+                }
+                if last_item_id.as_deref().is_some_and(|last| last != item_id) {
+                    let separator = if ends_with_newline { "\n" } else { "\n\n" };
+                    let _ = stdout.write_all(separator.as_bytes());
+                }
+                let _ = stdout.write_all(delta.as_bytes());
+                let _ = stdout.flush();
+                ends_with_newline = delta.ends_with('\n');
+                last_item_id = Some(item_id);
+            }
+            TurnEvent::Approval {
+                request_id,
+                summary,
+            } => match command.approvals {
+                ApprovalMode::Wait => {
+                    let request_id = request_id_text(&request_id);
+                    eprintln!(
+                        "\napproval needed: {summary}\n  codex-monitor-cli approve --workspace {workspace_id} {request_id}\n  codex-monitor-cli deny --workspace {workspace_id} {request_id}"
+                    );
+                }
+                ApprovalMode::Respond(decision) => {
+                    eprintln!("\n{}: {summary}", decision.as_str());
+                    client
+                        .call(
+                            "respond_to_server_request",
+                            json!({
+                                "workspaceId": workspace_id,
+                                "requestId": request_id,
+                                "result": { "decision": decision.as_str() },
+                            }),
+                        )
+                        .await?;
+                }
+            },
+            TurnEvent::Error {
+                message,
+                will_retry: true,
+            } => eprintln!("\nretrying: {message}"),
+            TurnEvent::Error { message, .. }
+            | TurnEvent::Completed {
+                error: Some(message),
+            } => {
+                return Err(message);
+            }
+            TurnEvent::Completed { error: None } => {
+                if !as_json && !ends_with_newline {
+                    println!();
+                }
+                return Ok(());
+            }
+            TurnEvent::Other => {}
+        }
+    }
+}
+
+/// The app-server message carried by an `app-server-event` notification for
+/// `workspace_id`.
+fn app_server_message<'a>(notification: &'a Value, workspace_id: &str) -> Option<&'a Value> {
+    if notification.get("method").and_then(Value::as_str) != Some("app-server-event") {
+        return None;
+    }
+    let params = notification.get("params")?;
+    if params.get("workspace_id").and_then(Value::as_str) != Some(workspace_id) {
+        return None;
+    }
+    params.get("message")
+}
+
+fn turn_event(message: &Value, thread_id: &str, turn_id: Option<&str>) -> TurnEvent {
+    let method = message.get("method").and_then(Value::as_str).unwrap_or("");
+    let params = message.get("params").unwrap_or(&Value::Null);
+    let turn = params.get("turn");
+    let event_thread_id =
+        str_at(params, "threadId").or_else(|| turn.and_then(|turn| str_at(turn, "threadId")));
+    if event_thread_id.is_some_and(|id| id != thread_id) {
+        return TurnEvent::Other;
+    }
+    let event_turn_id =
+        str_at(params, "turnId").or_else(|| turn.and_then(|turn| str_at(turn, "id")));
+    if let (Some(expected), Some(actual)) = (turn_id, event_turn_id) {
+        if expected != actual {
+            return TurnEvent::Other;
+        }
+    }
+
+    if let Some(request_id) = message.get("id") {
+        if method.ends_with("requestApproval") {
+            let summary = str_at(params, "command")
+                .or_else(|| str_at(params, "reason"))
+                .map(str::to_string)
+                .unwrap_or_else(|| method.to_string());
+            return TurnEvent::Approval {
+                request_id: request_id.clone(),
+                summary,
+            };
+        }
+        return TurnEvent::Other;
+    }
+
+    match method {
+        "item/agentMessage/delta" => match (str_at(params, "itemId"), str_at(params, "delta")) {
+            (Some(item_id), Some(delta)) => TurnEvent::Delta {
+                item_id: item_id.to_string(),
+                delta: delta.to_string(),
+            },
+            _ => TurnEvent::Other,
+        },
+        "error" => TurnEvent::Error {
+            message: params
+                .get("error")
+                .and_then(|error| str_at(error, "message"))
+                .unwrap_or("Turn failed")
+                .to_string(),
+            will_retry: params
+                .get("willRetry")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+        },
+        "turn/completed" => {
+            let status = turn.and_then(|turn| str_at(turn, "status"));
+            let error = match status {
+                Some("failed") => Some(
+                    turn.and_then(|turn| turn.get("error"))
+                        .and_then(|error| str_at(error, "message"))
+                        .unwrap_or("Turn failed")
+                        .to_string(),
+                ),
+                Some("interrupted") => Some("Turn was interrupted".to_string()),
+                _ => None,
+            };
+            TurnEvent::Completed { error }
+        }
+        _ => TurnEvent::Other,
+    }
+}
+
+fn str_at<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value.get(key).and_then(Value::as_str)
+}
+
+fn string_field<'a>(value: &'a Value, key: &str) -> &'a str {
+    str_at(value, key).unwrap_or("")
+}
+
+/// `start_thread` returns the raw app-server response.
+fn response_thread_id(response: &Value) -> Option<String> {
+    let result = response.get("result").unwrap_or(response);
+    result
+        .get("thread")
+        .and_then(|thread| str_at(thread, "id"))
+        .map(str::to_string)
+}
+
+fn response_turn_id(response: &Value) -> Option<String> {
+    let result = response.get("result").unwrap_or(response);
+    result
+        .get("turn")
+        .and_then(|turn| str_at(turn, "id"))
+        .map(str::to_string)
+}
+
+fn thread_rows(response: &Value) -> Vec<(String, String)> {
+    let result = response.get("result").unwrap_or(response);
+    result
+        .get("data")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|thread| {
+            let id = str_at(thread, "id")?;
+            let preview = string_field(thread, "preview")
+                .lines()
+                .next()
+                .unwrap_or("")
+                .chars()
+                .take(PREVIEW_MAX_CHARS)
+                .collect();
+            Some((id.to_string(), preview))
+        })
+        .collect()
+}
+
+/// App-server request ids are usually numbers; keep anything else as a string.
+fn request_id_value(request_id: &str) -> Value {
+    request_id
+        .parse::<u64>()
+        .map(Value::from)
+        .unwrap_or_else(|_| Value::String(request_id.to_string()))
+}
+
+fn request_id_text(request_id: &Value) -> String {
+    match request_id {
+        Value::String(value) => value.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn parse_args() -> Result<CliArgs, String> {
+    parse_cli_args(env::args().skip(1))
+}
+
+fn parse_cli_args(args: impl IntoIterator<Item = String>) -> Result<CliArgs, String> {
+    let mut args = args.into_iter();
+
+    let Some(first) = args.next() else {
+        return Err(usage());
+    };
+
+    if matches!(first.as_str(), "-h" | "--help" | "help") {
+        print!("{}", usage());
+        std::process::exit(0);
+    }
+
+    let action = match first.as_str() {
+        "workspaces" | "threads" | "thread" => Some(
+            args.next()
+                .ok_or_else(|| format!("{first} requires a subcommand\n\n{}", usage()))?,
+        ),
+        "send" | "approve" | "deny" | "diff" => None,
+        _ => return Err(format!("Unknown command: {first}\n\n{}", usage())),
+    };
+
+    let mut host: Option<String> = None;
+    let mut token: Option<String> = None;
+    let mut tls = false;
+    let mut tls_fingerprint: Option<String> = None;
+    let mut data_dir: Option<PathBuf> = None;
+    let mut json = false;
+    let mut workspace_id: Option<String> = None;
+    let mut thread_id: Option<String> = None;
+    let mut limit: Option<u32> = None;
+    let mut model: Option<String> = None;
+    let mut effort: Option<String> = None;
+    let mut access_mode: Option<String> = None;
+    let mut approvals = ApprovalMode::Wait;
+    let mut turn_timeout: Option<Duration> = None;
+    let mut positional: Vec<String> = Vec::new();
+
+    while let Some(arg) = args.next() {
+        let mut value_for = |flag: &str| -> Result<String, String> {
+            let value = args
+                .next()
+                .ok_or_else(|| format!("{flag} requires a value"))?;
+            let trimmed = value.trim();
+            if trimmed.is_empty() {
+                return Err(format!("{flag} requires a non-empty value"));
+            }
+            Ok(trimmed.to_string())
+        };
+        match arg.as_str() {
+            "--host" => host = Some(value_for("--host")?),
+            "--token" => token = Some(value_for("--token")?),
+            "--tls" => tls = true,
+            "--tls-fingerprint" => tls_fingerprint = Some(value_for("--tls-fingerprint")?),
+            "--data-dir" => data_dir = Some(PathBuf::from(value_for("--data-dir")?)),
+            "--json" => json = true,
+            "--workspace" => workspace_id = Some(value_for("--workspace")?),
+            "--thread" => thread_id = Some(value_for("--thread")?),
+            "--limit" => {
+                let value = value_for("--limit")?;
+                limit = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("--limit expects a number (got `{value}`)"))?,
+                );
+            }
+            "--model" => model = Some(value_for("--model")?),
+            "--effort" => effort = Some(value_for("--effort")?),
+            "--access-mode" => access_mode = Some(value_for("--access-mode")?),
+            "--approvals" => approvals = ApprovalMode::parse(&value_for("--approvals")?)?,
+            "--timeout" => {
+                let value = value_for("--timeout")?;
+                let seconds: u64 = value
+                    .parse()
+                    .map_err(|_| format!("--timeout expects seconds (got `{value}`)"))?;
+                turn_timeout = Some(Duration::from_secs(seconds));
+            }
+            "-h" | "--help" => {
+                print!("{}", usage());
+                std::process::exit(0);
+            }
+            _ if !arg.starts_with("--") => positional.push(arg),
+            _ => return Err(format!("Unknown argument: {arg}\n\n{}", usage())),
+        }
+    }
+
+    let require_workspace = |command: &str| {
+        workspace_id
+            .clone()
+            .ok_or_else(|| format!("{command} requires --workspace <id>"))
+    };
+    let command = match (first.as_str(), action.as_deref()) {
+        ("workspaces", Some("list")) => CliCommand::WorkspacesList,
+        ("threads", Some("list")) => CliCommand::ThreadsList {
+            workspace_id: require_workspace("threads list")?,
+            limit,
+        },
+        ("thread", Some("start")) => CliCommand::ThreadStart {
+            workspace_id: require_workspace("thread start")?,
+        },
+        ("send", _) => {
+            let [text] = positional.as_slice() else {
+                return Err("send requires exactly one <message>".to_string());
+            };
+            if text.trim().is_empty() {
+                return Err("send requires a non-empty <message>".to_string());
+            }
+            CliCommand::Send(SendCommand {
+                workspace_id: require_workspace("send")?,
+                thread_id,
+                text: text.clone(),
+                model,
+                effort,
+                access_mode,
+                approvals,
+                timeout: turn_timeout,
+            })
+        }
+        ("approve" | "deny", _) => {
+            let [request_id] = positional.as_slice() else {
+                return Err(format!("{first} requires exactly one <request-id>"));
+            };
+            CliCommand::Respond {
+                workspace_id: require_workspace(&first)?,
+                request_id: request_id.clone(),
+                decision: if first == "approve" {
+                    Decision::Accept
+                } else {
+                    Decision::Decline
+                },
+            }
+        }
+        ("diff", _) => CliCommand::Diff {
+            workspace_id: require_workspace("diff")?,
+        },
+        (_, action) => {
+            let action = action.unwrap_or_default();
+            return Err(format!("Unknown command: {first} {action}\n\n{}", usage()));
+        }
+    };
+    if !matches!(command, CliCommand::Send(_) | CliCommand::Respond { .. })
+        && !positional.is_empty()
+    {
+        return Err(format!("Unexpected argument: {}", positional[0]));
+    }
+
+    Ok(CliArgs {
+        command,
+        host,
+        token,
+        tls,
+        tls_fingerprint,
+        data_dir,
+        json,
+    })
+}
+
+fn usage() -> String {
+    format!(
+        "\
+USAGE:\n  codex-monitor-cli <command> [options]\n\n\
+COMMANDS:\n  workspaces list                        List workspaces known to the daemon\n  threads list --workspace <id> [--limit <n>]\n                                         List threads in a workspace\n  thread start --workspace <id>          Start a thread and print its id\n  send --workspace <id> [--thread <id>] <message>\n                                         Send a message and stream the reply to stdout\n  approve --workspace <id> <request-id>  Accept a pending approval request\n  deny --workspace <id> <request-id>     Decline a pending approval request\n  diff --workspace <id>                  Print the workspace's uncommitted diff\n\n\
+SEND OPTIONS:\n  --model <model>        Model override\n  --effort <effort>      Reasoning effort override\n  --access-mode <mode>   read-only, current or full-access\n  --approvals <mode>     wait (default), accept or decline approval requests\n  --timeout <seconds>    Give up if the turn has not completed in time\n\n\
+OPTIONS:\n  --host <host:port|url>     Daemon address, or a ws:// or wss:// URL (default from settings, fallback: {DEFAULT_HOST})\n  --token <token>            Daemon token override\n  --tls                      Connect over TLS\n  --tls-fingerprint <sha256> Pin the daemon certificate (implies --tls)\n  --data-dir <path>          App data dir (contains settings.json)\n  --json                     Print JSON output (send prints one event per line)\n  -h, --help                 Show this help\n\n\
+NOTES:\n  - Defaults read host/token/TLS from the active remote backend in <data-dir>/settings.json\n  - The token can also come from CODEX_MONITOR_DAEMON_TOKEN\n"
+    )
+}
+
+fn resolve_connect_options(
+    args: &CliArgs,
+    settings: Option<&AppSettings>,
+) -> Result<ConnectOptions, String> {
+    let active = settings.and_then(AppSettings::active_remote_backend);
+    let host = match args.host.as_deref() {
+        Some(host) => host.to_string(),
+        None => active
+            .map(|target| target.host.clone())
+            .or_else(|| settings.map(|value| value.remote_backend_host.clone()))
+            .and_then(|host| trim_non_empty(Some(&host)))
+            .unwrap_or_else(|| DEFAULT_HOST.to_string()),
+    };
+    if host.contains("://") && !is_websocket_url(&host) {
+        return Err(format!(
+            "Daemon host must be host:port or a ws:// or wss:// URL (got `{host}`)"
+        ));
+    }
+    // TLS settings only carry over when the host came from settings too.
+    let active = active.filter(|_| args.host.is_none());
+    let tls_fingerprint = args.tls_fingerprint.clone().or_else(|| {
+        active.and_then(|target| trim_non_empty(target.tls_cert_fingerprint.as_deref()))
+    });
+    let tls = args.tls || tls_fingerprint.is_some() || active.is_some_and(|target| target.tls);
+    let token = trim_non_empty(args.token.as_deref())
+        .or_else(|| trim_non_empty(env::var("CODEX_MONITOR_DAEMON_TOKEN").ok().as_deref()))
+        .or_else(|| active.and_then(|target| trim_non_empty(target.token.as_deref())))
+        .or_else(|| {
+            settings.and_then(|value| trim_non_empty(value.remote_backend_token.as_deref()))
+        });
+
+    Ok(ConnectOptions {
+        host,
+        token,
+        tls,
+        tls_fingerprint,
+    })
+}
+
+fn trim_non_empty(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(str::to_string)
+}
+
+fn load_settings(data_dir: &Path) -> Option<AppSettings> {
+    let settings_path = data_dir.join("settings.json");
+    storage::read_settings(&settings_path).ok()
+}
+
+fn default_app_data_dir() -> PathBuf {
+    #[cfg(target_os = "macos")]
+    {
+        let home = env::var("HOME").unwrap_or_else(|_| ".".to_string());
+        return PathBuf::from(home)
+            .join("Library")
+            .join("Application Support")
+            .join(APP_IDENTIFIER);
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        if let Ok(appdata) = env::var("APPDATA") {
+            let trimmed = appdata.trim();
+            if !trimmed.is_empty() {
+                return PathBuf::from(trimmed).join(APP_IDENTIFIER);
+            }
+        }
+        let user = env::var("USERPROFILE").unwrap_or_else(|_| ".".to_string());
+        return PathBuf::from(user)
+            .join("AppData")
+            .join("Roaming")
+            .join(APP_IDENTIFIER);
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        if let Ok(xdg) = env::var("XDG_DATA_HOME") {
+            let trimmed = xdg.trim();
+            if !trimmed.is_empty() {
+                return PathBuf::from(trimmed).join(APP_IDENTIFIER);
+            }
+        }
+        let home = env::var("HOME").unwrap_or_else(|_| ".".to_string());
+        PathBuf::from(home)
+            .join(".local")
+            .join("share")
+            .join(APP_IDENTIFIER)
+    }
+}
+
+fn print_json(value: &Value) -> Result<(), String> {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(value).map_err(|err| err.to_string())?
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        app_server_message, parse_cli_args, request_id_value, resolve_connect_options, thread_rows,
+        turn_event, ApprovalMode, CliCommand, Decision, SendCommand, TurnEvent,
+    };
+    use crate::types::AppSettings;
+    use serde_json::json;
+    use std::time::Duration;
+
+    fn parse(args: &[&str]) -> Result<CliCommand, String> {
+        parse_cli_args(args.iter().map(|arg| arg.to_string())).map(|args| args.command)
+    }
+
+    #[test]
+    fn parses_commands() {
+        assert_eq!(
+            parse(&["workspaces", "list"]),
+            Ok(CliCommand::WorkspacesList)
+        );
+        assert_eq!(
+            parse(&["threads", "list", "--workspace", "ws-1", "--limit", "5"]),
+            Ok(CliCommand::ThreadsList {
+                workspace_id: "ws-1".to_string(),
+                limit: Some(5),
+            })
+        );
+        assert_eq!(
+            parse(&[
+                "send",
+                "--workspace",
+                "ws-1",
+                "fix the tests",
+                "--approvals",
+                "accept",
+                "--timeout",
+                "90",
+            ]),
+            Ok(CliCommand::Send(SendCommand {
+                workspace_id: "ws-1".to_string(),
+                thread_id: None,
+                text: "fix the tests".to_string(),
+                model: None,
+                effort: None,
+                access_mode: None,
+                approvals: ApprovalMode::Respond(Decision::Accept),
+                timeout: Some(Duration::from_secs(90)),
+            }))
+        );
+        assert_eq!(
+            parse(&["deny", "--workspace", "ws-1", "7"]),
+            Ok(CliCommand::Respond {
+                workspace_id: "ws-1".to_string(),
+                request_id: "7".to_string(),
+                decision: Decision::Decline,
+            })
+        );
+        assert!(parse(&["send", "--workspace", "ws-1"]).is_err());
+        assert!(parse(&["send", "hello"]).is_err());
+        assert!(parse(&["thread", "start"]).is_err());
+        assert!(parse(&["threads", "delete", "--workspace", "ws-1"]).is_err());
+        assert!(parse(&["diff", "--workspace", "ws-1", "extra"]).is_err());
+        assert!(parse(&["send", "--workspace", "ws-1", "--approvals", "maybe", "hi"]).is_err());
+    }
+
+    #[test]
+    fn connect_options_follow_the_active_remote_backend() {
+        let mut settings: AppSettings = serde_json::from_value(json!({
+            "remoteBackendHost": "10.0.0.2:4732",
+            "remoteBackendToken": "legacy",
+            "remoteBackends": [{
+                "id": "home",
+                "name": "Home",
+                "provider": "tcp",
+                "host": "home.example.ts.net:4732",
+                "token": "secret",
+                "tls": true,
+            }],
+            "activeRemoteBackendId": "home",
+        }))
+        .expect("settings");
+        let args = parse_cli_args(["workspaces", "list"].map(str::to_string)).expect("args");
+        let options = resolve_connect_options(&args, Some(&settings)).expect("options");
+        assert_eq!(options.host, "home.example.ts.net:4732");
+        assert!(options.tls);
+
+        let args =
+            parse_cli_args(["workspaces", "list", "--host", "127.0.0.1:5000"].map(str::to_string))
+                .expect("args");
+        let options = resolve_connect_options(&args, Some(&settings)).expect("options");
+        assert_eq!(options.host, "127.0.0.1:5000");
+        assert!(!options.tls);
+
+        settings.active_remote_backend_id = None;
+        let args = parse_cli_args(["workspaces", "list"].map(str::to_string)).expect("args");
+        let options = resolve_connect_options(&args, Some(&settings)).expect("options");
+        assert_eq!(options.host, "10.0.0.2:4732");
+
+        let args = parse_cli_args(
+            ["workspaces", "list", "--host", "wss://proxy.example/codex"].map(str::to_string),
+        )
+        .expect("args");
+        let options = resolve_connect_options(&args, Some(&settings)).expect("options");
+        assert_eq!(options.host, "wss://proxy.example/codex");
+
+        let args = parse_cli_args(
+            ["workspaces", "list", "--host", "https://127.0.0.1:4732"].map(str::to_string),
+        )
+        .expect("args");
+        assert!(resolve_connect_options(&args, None).is_err());
+    }
+
+    #[test]
+    fn turn_events_are_scoped_to_the_thread_and_turn() {
+        let notification = json!({
+            "method": "app-server-event",
+            "params": {
+                "workspace_id": "ws-1",
+                "message": {
+                    "method": "item/agentMessage/delta",
+                    "params": { "threadId": "t-1", "turnId": "turn-1", "itemId": "i-1", "delta": "Hi" },
+                },
+            },
+        });
+        assert!(app_server_message(&notification, "ws-2").is_none());
+        let message = app_server_message(&notification, "ws-1").expect("message");
+        assert_eq!(
+            turn_event(message, "t-1", Some("turn-1")),
+            TurnEvent::Delta {
+                item_id: "i-1".to_string(),
+                delta: "Hi".to_string(),
+            }
+        );
+        assert_eq!(turn_event(message, "t-2", None), TurnEvent::Other);
+        assert_eq!(turn_event(message, "t-1", Some("turn-0")), TurnEvent::Other);
+
+        let approval = json!({
+            "id": 4,
+            "method": "item/commandExecution/requestApproval",
+            "params": { "threadId": "t-1", "turnId": "turn-1", "command": "cargo test" },
+        });
+        assert_eq!(
+            turn_event(&approval, "t-1", Some("turn-1")),
+            TurnEvent::Approval {
+                request_id: json!(4),
+                summary: "cargo test".to_string(),
+            }
+        );
+
+        let retrying = json!({
+            "method": "error",
+            "params": { "threadId": "t-1", "error": { "message": "overloaded" }, "willRetry": true },
+        });
+        assert_eq!(
+            turn_event(&retrying, "t-1", None),
+            TurnEvent::Error {
+                message: "overloaded".to_string(),
+                will_retry: true,
+            }
+        );
+
+        let completed = json!({
+            "method": "turn/completed",
+            "params": { "threadId": "t-1", "turn": { "id": "turn-1", "status": "completed" } },
+        });
+        assert_eq!(
+            turn_event(&completed, "t-1", Some("turn-1")),
+            TurnEvent::Completed { error: None }
+        );
+        let failed = json!({
+            "method": "turn/completed",
+            "params": {
+                "threadId": "t-1",
+                "turn": { "id": "turn-1", "status": "failed", "error": { "message": "quota" } },
+            },
+        });
+        assert_eq!(
+            turn_event(&failed, "t-1", Some("turn-1")),
+            TurnEvent::Completed {
+                error: Some("quota".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn reads_thread_rows_and_request_ids() {
+        let response = json!({
+            "result": {
+                "data": [
+                    { "id": "t-1", "preview": "first line\nsecond line" },
+                    { "preview": "no id" },
+                ],
+            },
+        });
+        assert_eq!(
+            thread_rows(&response),
+            vec![("t-1".to_string(), "first line".to_string())]
+        );
+        assert_eq!(request_id_value("12"), json!(12));
+        assert_eq!(request_id_value("req-1"), json!("req-1"));
+    }
+}
@@ -0,0 +1,279 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader, Lines};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+use tokio_rustls::TlsConnector;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{Connector, MaybeTlsStream, WebSocketStream};
+
+use crate::rpc_schema;
+use crate::tls_core::{build_client_config, server_name_for_host};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const RPC_TIMEOUT: Duration = Duration::from_secs(120);
+
+type DaemonReader = Box<dyn AsyncRead + Unpin + Send>;
+type DaemonWriter = Box<dyn AsyncWrite + Unpin + Send>;
+
+#[derive(Debug, Clone)]
+pub(crate) struct ConnectOptions {
+    /// `host:port`, or a `ws://`/`wss://` URL for the daemon's WebSocket
+    /// listener.
+    pub(crate) host: String,
+    pub(crate) token: Option<String>,
+    pub(crate) tls: bool,
+    pub(crate) tls_fingerprint: Option<String>,
+}
+
+pub(crate) fn is_websocket_url(host: &str) -> bool {
+    let lower = host.trim().to_ascii_lowercase();
+    lower.starts_with("ws://") || lower.starts_with("wss://")
+}
+
+/// One JSON message per line over TCP or TLS, or one per text frame over
+/// WebSocket.
+enum DaemonConnection {
+    Stream {
+        lines: Lines<BufReader<DaemonReader>>,
+        writer: DaemonWriter,
+    },
+    WebSocket(Box<WebSocketStream<MaybeTlsStream<TcpStream>>>),
+}
+
+impl DaemonConnection {
+    async fn send(&mut self, mut line: String) -> Result<(), String> {
+        match self {
+            Self::Stream { writer, .. } => {
+                line.push('\n');
+                writer
+                    .write_all(line.as_bytes())
+                    .await
+                    .map_err(|err| err.to_string())?;
+                writer.flush().await.map_err(|err| err.to_string())
+            }
+            Self::WebSocket(stream) => stream
+                .send(Message::Text(line))
+                .await
+                .map_err(|err| err.to_string()),
+        }
+    }
+
+    /// The next message, or `None` once the daemon closed the connection.
+    async fn next(&mut self) -> Result<Option<String>, String> {
+        match self {
+            Self::Stream { lines, .. } => lines.next_line().await.map_err(|err| err.to_string()),
+            Self::WebSocket(stream) => loop {
+                match stream.next().await {
+                    Some(Ok(Message::Text(text))) => return Ok(Some(text)),
+                    Some(Ok(Message::Close(_))) | None => return Ok(None),
+                    Some(Ok(_)) => continue,
+                    Some(Err(err)) => return Err(err.to_string()),
+                }
+            },
+        }
+    }
+}
+
+/// JSON-RPC connection to the daemon. Notifications that arrive while a call
+/// is waiting for its response are queued and handed out by
+/// `next_notification` in order.
+pub(crate) struct DaemonClient {
+    connection: DaemonConnection,
+    next_id: u64,
+    notifications: VecDeque<Value>,
+}
+
+impl DaemonClient {
+    pub(crate) async fn connect(options: &ConnectOptions) -> Result<Self, String> {
+        let connection = if is_websocket_url(&options.host) {
+            connect_websocket(options).await?
+        } else {
+            connect_stream(options).await?
+        };
+        let mut client = Self {
+            connection,
+            next_id: 0,
+            notifications: VecDeque::new(),
+        };
+        if let Some(token) = options.token.as_deref() {
+            client
+                .call("auth", json!({ "token": token }))
+                .await
+                .map_err(|err| format!("Daemon rejected the token: {err}"))?;
+        }
+        Ok(client)
+    }
+
+    pub(crate) async fn call(&mut self, method: &str, params: Value) -> Result<Value, String> {
+        self.next_id += 1;
+        let id = self.next_id;
+        let line = rpc_schema::request_line(id, method, params)?;
+        self.connection
+            .send(line)
+            .await
+            .map_err(|err| format!("Failed to send `{method}`: {err}"))?;
+
+        loop {
+            let message = timeout(RPC_TIMEOUT, self.read_message())
+                .await
+                .map_err(|_| format!("Timed out waiting for `{method}`"))??;
+            if message.get("method").is_some() {
+                self.notifications.push_back(message);
+                continue;
+            }
+            if message.get("id").and_then(Value::as_u64) == Some(id) {
+                return rpc_schema::response_result(&message).map_err(String::from);
+            }
+        }
+    }
+
+    pub(crate) async fn next_notification(&mut self) -> Result<Value, String> {
+        if let Some(notification) = self.notifications.pop_front() {
+            return Ok(notification);
+        }
+        loop {
+            let message = self.read_message().await?;
+            if message.get("method").is_some() {
+                return Ok(message);
+            }
+        }
+    }
+
+    async fn read_message(&mut self) -> Result<Value, String> {
+        loop {
+            let line = self
+                .connection
+                .next()
+                .await
+                .map_err(|err| format!("Failed to read from daemon: {err}"))?
+                .ok_or_else(|| "Daemon closed the connection".to_string())?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            return serde_json::from_str(&line)
+                .map_err(|err| format!("Invalid message from daemon: {err}"));
+        }
+    }
+}
+
+async fn connect_stream(options: &ConnectOptions) -> Result<DaemonConnection, String> {
+    let host = options.host.as_str();
+    let stream = timeout(CONNECT_TIMEOUT, TcpStream::connect(host))
+        .await
+        .map_err(|_| format!("Timed out connecting to daemon at {host}"))?
+        .map_err(|err| format!("Failed to connect to daemon at {host}: {err}"))?;
+    let (reader, writer): (DaemonReader, DaemonWriter) = if options.tls {
+        let config = build_client_config(options.tls_fingerprint.as_deref())?;
+        let server_name = server_name_for_host(host)?;
+        let stream = timeout(
+            CONNECT_TIMEOUT,
+            TlsConnector::from(Arc::new(config)).connect(server_name, stream),
+        )
+        .await
+        .map_err(|_| format!("Timed out during TLS handshake with {host}"))?
+        .map_err(|err| format!("TLS handshake with {host} failed: {err}"))?;
+        let (reader, writer) = tokio::io::split(stream);
+        (Box::new(reader), Box::new(writer))
+    } else {
+        let (reader, writer) = stream.into_split();
+        (Box::new(reader), Box::new(writer))
+    };
+    Ok(DaemonConnection::Stream {
+        lines: BufReader::new(reader).lines(),
+        writer,
+    })
+}
+
+/// The URL's scheme decides TLS; a pinned fingerprint applies to `wss://`.
+async fn connect_websocket(options: &ConnectOptions) -> Result<DaemonConnection, String> {
+    let url = options.host.trim();
+    let connector = if url.to_ascii_lowercase().starts_with("wss://") {
+        let config = build_client_config(options.tls_fingerprint.as_deref())?;
+        Some(Connector::Rustls(Arc::new(config)))
+    } else {
+        None
+    };
+    let (stream, _) = timeout(
+        CONNECT_TIMEOUT,
+        tokio_tungstenite::connect_async_tls_with_config(url, None, false, connector),
+    )
+    .await
+    .map_err(|_| format!("Timed out connecting to daemon at {url}"))?
+    .map_err(|err| format!("Failed to connect to daemon at {url}: {err}"))?;
+    Ok(DaemonConnection::WebSocket(Box::new(stream)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_websocket_url, ConnectOptions, DaemonClient};
+    use futures_util::{SinkExt, StreamExt};
+    use serde_json::{json, Value};
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::Message;
+
+    #[test]
+    fn websocket_urls_are_told_apart_from_host_port() {
+        assert!(is_websocket_url("ws://127.0.0.1:4732"));
+        assert!(is_websocket_url(" WSS://proxy.example/codex-monitor"));
+        assert!(!is_websocket_url("127.0.0.1:4732"));
+        assert!(!is_websocket_url("https://proxy.example"));
+    }
+
+    #[test]
+    fn calls_go_over_websocket_for_ws_urls() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("runtime");
+        runtime.block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+            let addr = listener.local_addr().expect("addr");
+            let server = tokio::spawn(async move {
+                let (socket, _) = listener.accept().await.expect("accept");
+                let mut stream = tokio_tungstenite::accept_async(socket)
+                    .await
+                    .expect("upgrade");
+                let mut methods = Vec::new();
+                while let Some(Ok(Message::Text(text))) = stream.next().await {
+                    let request: Value = serde_json::from_str(&text).expect("request");
+                    methods.push(request["method"].as_str().unwrap_or_default().to_string());
+                    let reply = json!({ "method": "terminal-output", "params": {} });
+                    stream
+                        .send(Message::Text(reply.to_string()))
+                        .await
+                        .expect("notify");
+                    let reply = json!({ "id": request["id"], "result": { "ok": true } });
+                    stream
+                        .send(Message::Text(reply.to_string()))
+                        .await
+                        .expect("reply");
+                }
+                methods
+            });
+
+            let mut client = DaemonClient::connect(&ConnectOptions {
+                host: format!("ws://{addr}/"),
+                token: Some("secret".to_string()),
+                tls: false,
+                tls_fingerprint: None,
+            })
+            .await
+            .expect("connect");
+            assert_eq!(
+                client.call("ping", json!({})).await.expect("ping"),
+                json!({ "ok": true })
+            );
+            assert_eq!(
+                client.next_notification().await.expect("notification")["method"],
+                "terminal-output"
+            );
+            drop(client);
+            assert_eq!(server.await.expect("server"), ["auth", "ping"]);
+        });
+    }
+}
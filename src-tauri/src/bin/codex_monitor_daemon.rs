@@ -1,4 +1,7 @@
 #[allow(dead_code)]
+#[path = "codex_monitor_daemon/auth_tokens.rs"]
+mod auth_tokens;
+#[allow(dead_code)]
 #[path = "../backend/mod.rs"]
 mod backend;
 #[path = "../codex/args.rs"]
@@ -7,6 +10,10 @@ mod codex_args;
 mod codex_config;
 #[path = "../codex/home.rs"]
 mod codex_home;
+#[path = "codex_monitor_daemon/event_replay.rs"]
+mod event_replay;
+#[path = "codex_monitor_daemon/event_subscription.rs"]
+mod event_subscription;
 #[path = "../files/io.rs"]
 mod file_io;
 #[path = "../files/ops.rs"]
@@ -15,6 +22,8 @@ mod file_ops;
 mod file_policy;
 #[path = "../git_utils.rs"]
 mod git_utils;
+#[path = "codex_monitor_daemon/metrics.rs"]
+mod metrics;
 #[path = "codex_monitor_daemon/push.rs"]
 mod push;
 #[path = "codex_monitor_daemon/rpc.rs"]
@@ -23,8 +32,12 @@ mod rpc;
 mod rules;
 #[path = "../shared/mod.rs"]
 mod shared;
+#[path = "codex_monitor_daemon/shutdown.rs"]
+mod shutdown;
 #[path = "../storage.rs"]
 mod storage;
+#[path = "codex_monitor_daemon/tls.rs"]
+mod tls;
 #[path = "codex_monitor_daemon/transport.rs"]
 mod transport;
 #[allow(dead_code)]
@@ -32,6 +45,8 @@ mod transport;
 mod types;
 #[path = "../utils.rs"]
 mod utils;
+#[path = "codex_monitor_daemon/websocket.rs"]
+mod websocket;
 #[path = "../workspaces/macos.rs"]
 mod workspace_macos;
 #[path = "../workspaces/settings.rs"]
@@ -64,6 +79,7 @@ mod files {
 
 use base64::engine::general_purpose::STANDARD;
 use base64::Engine as _;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::collections::{HashMap, HashSet};
@@ -75,18 +91,24 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 use ignore::WalkBuilder;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::net::{TcpListener, TcpStream};
+use tokio::io::{AsyncRead, AsyncWrite, BufReader};
+use tokio::net::TcpListener;
 use tokio::sync::{broadcast, mpsc, Mutex, Semaphore};
 
-use backend::app_server::{spawn_workspace_session, WorkspaceSession};
+use backend::app_server::{
+    spawn_workspace_session, RequestTimeouts, SessionExit, SessionExitSender, WorkspaceSession,
+};
 use backend::events::{AppServerEvent, EventSink, TerminalExit, TerminalOutput};
+use event_replay::{EventReplayBuffer, SequencedEvent, EVENT_REPLAY_CAPACITY_PER_WORKSPACE};
 use shared::codex_core::CodexLoginCancelState;
+use shared::core_error::CoreError;
+use shared::logging_core;
 use shared::process_core::kill_child_process_tree;
 use shared::prompts_core::{self, CustomPromptEntry};
 use shared::{
     agents_config_core, codex_aux_core, codex_core, files_core, git_core, git_ui_core,
-    local_usage_core, settings_core, transcription_chatgpt_core, workspaces_core, worktree_core,
+    local_usage_core, settings_core, terminal_core, terminal_rpc, transcription_chatgpt_core,
+    workspaces_core, worktree_core,
 };
 use storage::{read_settings, read_workspaces};
 use types::{
@@ -97,17 +119,21 @@ use types::{
 use workspace_settings::apply_workspace_settings_update;
 
 const DEFAULT_LISTEN_ADDR: &str = "127.0.0.1:4732";
+const DEFAULT_WS_PATH: &str = "/";
 const MAX_IN_FLIGHT_RPC_PER_CONNECTION: usize = 32;
 const DAEMON_NAME: &str = "codex-monitor-daemon";
 
+#[allow(clippy::too_many_arguments)]
 fn spawn_with_client(
     event_sink: DaemonEventSink,
+    session_exits: SessionExitSender,
+    request_timeouts: RequestTimeouts,
     client_version: String,
     entry: WorkspaceEntry,
     default_bin: Option<String>,
     codex_args: Option<String>,
     codex_home: Option<PathBuf>,
-) -> impl std::future::Future<Output = Result<Arc<WorkspaceSession>, String>> {
+) -> impl std::future::Future<Output = Result<Arc<WorkspaceSession>, CoreError>> {
     spawn_workspace_session(
         entry,
         default_bin,
@@ -115,34 +141,37 @@ fn spawn_with_client(
         codex_home,
         client_version,
         event_sink,
+        session_exits,
+        request_timeouts,
     )
 }
 
 #[derive(Clone)]
 struct DaemonEventSink {
-    tx: broadcast::Sender<DaemonEvent>,
+    tx: broadcast::Sender<SequencedEvent>,
+    replay: Arc<EventReplayBuffer>,
 }
 
 #[derive(Clone)]
 enum DaemonEvent {
     AppServer(AppServerEvent),
-    #[allow(dead_code)]
     TerminalOutput(TerminalOutput),
-    #[allow(dead_code)]
     TerminalExit(TerminalExit),
 }
 
 impl EventSink for DaemonEventSink {
     fn emit_app_server_event(&self, event: AppServerEvent) {
-        let _ = self.tx.send(DaemonEvent::AppServer(event));
+        self.replay.publish(DaemonEvent::AppServer(event), &self.tx);
     }
 
     fn emit_terminal_output(&self, event: TerminalOutput) {
-        let _ = self.tx.send(DaemonEvent::TerminalOutput(event));
+        self.replay
+            .publish(DaemonEvent::TerminalOutput(event), &self.tx);
     }
 
     fn emit_terminal_exit(&self, event: TerminalExit) {
-        let _ = self.tx.send(DaemonEvent::TerminalExit(event));
+        self.replay
+            .publish(DaemonEvent::TerminalExit(event), &self.tx);
     }
 }
 
@@ -150,37 +179,67 @@ struct DaemonConfig {
     listen: SocketAddr,
     token: Option<String>,
     data_dir: PathBuf,
+    tls: Option<tls::DaemonTlsConfig>,
+    ws_listen: Option<SocketAddr>,
+    ws_path: String,
+    metrics_listen: Option<SocketAddr>,
 }
 
 struct DaemonState {
     data_dir: PathBuf,
     workspaces: Mutex<HashMap<String, WorkspaceEntry>>,
     sessions: Mutex<HashMap<String, Arc<WorkspaceSession>>>,
+    terminal_sessions: terminal_core::TerminalSessions,
     storage_path: PathBuf,
     settings_path: PathBuf,
     app_settings: Mutex<AppSettings>,
     event_sink: DaemonEventSink,
+    session_exits: SessionExitSender,
+    request_timeouts: RequestTimeouts,
     push_broker: Arc<push::PushBroker>,
+    metrics: Arc<metrics::DaemonMetrics>,
+    shutdown: shutdown::ShutdownSignal,
     codex_login_cancels: Mutex<HashMap<String, CodexLoginCancelState>>,
     daemon_binary_path: Option<String>,
+    auth_tokens: auth_tokens::AuthTokenStore,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 struct WorkspaceFileResponse {
     content: String,
     truncated: bool,
 }
 
+/// `daemon_info` result.
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct DaemonInfo {
+    name: &'static str,
+    version: &'static str,
+    pid: u32,
+    mode: &'static str,
+    protocol_version: u32,
+    binary_path: Option<String>,
+    compression: Vec<&'static str>,
+}
+
 impl DaemonState {
     fn load(
         config: &DaemonConfig,
         event_sink: DaemonEventSink,
+        session_exits: SessionExitSender,
         push_broker: Arc<push::PushBroker>,
     ) -> Self {
         let storage_path = config.data_dir.join("workspaces.json");
         let settings_path = config.data_dir.join("settings.json");
         let workspaces = read_workspaces(&storage_path).unwrap_or_default();
         let app_settings = read_settings(&settings_path).unwrap_or_default();
+        let request_timeouts = RequestTimeouts::new(&app_settings.request_timeouts);
+        logging_core::init_logging(
+            Some(&config.data_dir.join(logging_core::LOG_DIR_NAME)),
+            "daemon",
+            &app_settings.logging,
+        );
         let daemon_binary_path = std::env::current_exe()
             .ok()
             .and_then(|path| path.to_str().map(str::to_string));
@@ -188,32 +247,79 @@ impl DaemonState {
             data_dir: config.data_dir.clone(),
             workspaces: Mutex::new(workspaces),
             sessions: Mutex::new(HashMap::new()),
+            terminal_sessions: Arc::new(Mutex::new(HashMap::new())),
             storage_path,
             settings_path,
             app_settings: Mutex::new(app_settings),
             event_sink,
+            session_exits,
+            request_timeouts,
             push_broker,
+            metrics: Arc::new(metrics::DaemonMetrics::default()),
+            shutdown: shutdown::ShutdownSignal::default(),
             codex_login_cancels: Mutex::new(HashMap::new()),
             daemon_binary_path,
+            auth_tokens: auth_tokens::AuthTokenStore::in_data_dir(&config.data_dir),
         }
     }
 
-    fn daemon_info(&self) -> Value {
-        json!({
-            "name": DAEMON_NAME,
-            "version": env!("CARGO_PKG_VERSION"),
-            "pid": std::process::id(),
-            "mode": "tcp",
-            "binaryPath": self.daemon_binary_path,
-        })
+    fn daemon_info(&self) -> DaemonInfo {
+        DaemonInfo {
+            name: DAEMON_NAME,
+            version: env!("CARGO_PKG_VERSION"),
+            pid: std::process::id(),
+            mode: "tcp",
+            protocol_version: shared::rpc_schema::PROTOCOL_VERSION,
+            binary_path: self.daemon_binary_path.clone(),
+            compression: shared::wire_compression_core::WireCompression::advertised(),
+        }
+    }
+
+    async fn metrics_report(&self) -> metrics::MetricsReport {
+        let sessions: Vec<(String, Arc<WorkspaceSession>)> = self
+            .sessions
+            .lock()
+            .await
+            .iter()
+            .map(|(workspace_id, session)| (workspace_id.clone(), Arc::clone(session)))
+            .collect();
+        let mut processes: Vec<&Arc<WorkspaceSession>> = Vec::new();
+        let mut workspace_sessions = Vec::with_capacity(sessions.len());
+        for (workspace_id, session) in &sessions {
+            if !processes
+                .iter()
+                .any(|existing| Arc::ptr_eq(existing, session))
+            {
+                processes.push(session);
+            }
+            workspace_sessions.push(metrics::WorkspaceSessionMetrics {
+                workspace_id: workspace_id.clone(),
+                pid: session.child.lock().await.id(),
+                uptime: session.supervision.uptime(),
+                crash_restarts: session
+                    .supervision
+                    .crash_restarts
+                    .load(std::sync::atomic::Ordering::SeqCst),
+            });
+        }
+        workspace_sessions.sort_by(|a, b| a.workspace_id.cmp(&b.workspace_id));
+        let push_stats = self.push_broker.delivery_stats();
+        metrics::MetricsReport {
+            counters: self.metrics.counters(),
+            in_flight_limit_per_connection: MAX_IN_FLIGHT_RPC_PER_CONNECTION,
+            live_sessions: processes.len(),
+            workspace_sessions,
+            push_attempted: push_stats.attempted(),
+            push_failed: push_stats.failed(),
+        }
     }
 
     async fn sync_workspaces_from_storage(&self) {
         let stored = match read_workspaces(&self.storage_path) {
             Ok(stored) => stored,
             Err(err) => {
-                eprintln!(
-                    "daemon: failed to read workspaces from {}: {err}",
+                tracing::error!(
+                    "failed to read workspaces from {}: {err}",
                     self.storage_path.display()
                 );
                 return;
@@ -244,7 +350,7 @@ impl DaemonState {
         for (workspace_id, session) in stale_sessions {
             let mut child = session.child.lock().await;
             kill_child_process_tree(&mut child).await;
-            eprintln!("daemon: pruned stale session for removed workspace {workspace_id}");
+            tracing::info!(workspace_id = %workspace_id, "pruned stale session for removed workspace");
         }
     }
 
@@ -261,7 +367,7 @@ impl DaemonState {
         &self,
         path: String,
         client_version: String,
-    ) -> Result<WorkspaceInfo, String> {
+    ) -> Result<WorkspaceInfo, CoreError> {
         let client_version = client_version.clone();
         workspaces_core::add_workspace_core(
             path,
@@ -272,6 +378,8 @@ impl DaemonState {
             move |entry, default_bin, codex_args, codex_home| {
                 spawn_with_client(
                     self.event_sink.clone(),
+                    self.session_exits.clone(),
+                    self.request_timeouts.clone(),
                     client_version.clone(),
                     entry,
                     default_bin,
@@ -289,7 +397,7 @@ impl DaemonState {
         destination_path: String,
         target_folder_name: Option<String>,
         client_version: String,
-    ) -> Result<WorkspaceInfo, String> {
+    ) -> Result<WorkspaceInfo, CoreError> {
         let client_version = client_version.clone();
         workspaces_core::add_workspace_from_git_url_core(
             url,
@@ -302,6 +410,8 @@ impl DaemonState {
             move |entry, default_bin, codex_args, codex_home| {
                 spawn_with_client(
                     self.event_sink.clone(),
+                    self.session_exits.clone(),
+                    self.request_timeouts.clone(),
                     client_version.clone(),
                     entry,
                     default_bin,
@@ -320,7 +430,7 @@ impl DaemonState {
         name: Option<String>,
         copy_agents_md: bool,
         client_version: String,
-    ) -> Result<WorkspaceInfo, String> {
+    ) -> Result<WorkspaceInfo, CoreError> {
         let client_version = client_version.clone();
         workspaces_core::add_worktree_core(
             parent_id,
@@ -350,6 +460,8 @@ impl DaemonState {
             move |entry, default_bin, codex_args, codex_home| {
                 spawn_with_client(
                     self.event_sink.clone(),
+                    self.session_exits.clone(),
+                    self.request_timeouts.clone(),
                     client_version.clone(),
                     entry,
                     default_bin,
@@ -364,12 +476,12 @@ impl DaemonState {
     async fn worktree_setup_status(
         &self,
         workspace_id: String,
-    ) -> Result<WorktreeSetupStatus, String> {
+    ) -> Result<WorktreeSetupStatus, CoreError> {
         workspaces_core::worktree_setup_status_core(&self.workspaces, &workspace_id, &self.data_dir)
             .await
     }
 
-    async fn worktree_setup_mark_ran(&self, workspace_id: String) -> Result<(), String> {
+    async fn worktree_setup_mark_ran(&self, workspace_id: String) -> Result<(), CoreError> {
         workspaces_core::worktree_setup_mark_ran_core(
             &self.workspaces,
             &workspace_id,
@@ -378,8 +490,41 @@ impl DaemonState {
         .await
     }
 
-    async fn remove_workspace(&self, id: String) -> Result<(), String> {
-        workspaces_core::remove_workspace_core(
+    /// The workspace and its worktrees, to compare against what is left after
+    /// a removal.
+    async fn workspace_and_children(&self, id: &str) -> Vec<String> {
+        let workspaces = self.workspaces.lock().await;
+        workspaces
+            .values()
+            .filter(|entry| entry.id == id || entry.parent_id.as_deref() == Some(id))
+            .map(|entry| entry.id.clone())
+            .collect()
+    }
+
+    /// Releases per-workspace daemon state, shells and scrollback for every
+    /// id in `candidates` that is no longer registered.
+    async fn forget_removed_workspaces(&self, candidates: Vec<String>) {
+        let removed: Vec<String> = {
+            let workspaces = self.workspaces.lock().await;
+            candidates
+                .into_iter()
+                .filter(|workspace_id| !workspaces.contains_key(workspace_id))
+                .collect()
+        };
+        for workspace_id in removed {
+            self.event_sink.replay.forget_workspace(&workspace_id);
+            terminal_core::terminal_close_workspace_core(
+                &self.terminal_sessions,
+                &terminal_core::scrollback_dir(&self.data_dir),
+                &workspace_id,
+            )
+            .await;
+        }
+    }
+
+    async fn remove_workspace(&self, id: String) -> Result<(), CoreError> {
+        let candidates = self.workspace_and_children(&id).await;
+        let result = workspaces_core::remove_workspace_core(
             id,
             &self.workspaces,
             &self.sessions,
@@ -387,19 +532,22 @@ impl DaemonState {
             |root, args| {
                 workspaces_core::run_git_command_unit(root, args, git_core::run_git_command_owned)
             },
-            |error| git_core::is_missing_worktree_error(error),
             |path| {
-                std::fs::remove_dir_all(path)
-                    .map_err(|err| format!("Failed to remove worktree folder: {err}"))
+                std::fs::remove_dir_all(path).map_err(|err| {
+                    CoreError::internal(format!("Failed to remove worktree folder: {err}"))
+                })
             },
             true,
             true,
         )
-        .await
+        .await;
+        self.forget_removed_workspaces(candidates).await;
+        result
     }
 
-    async fn remove_worktree(&self, id: String) -> Result<(), String> {
-        workspaces_core::remove_worktree_core(
+    async fn remove_worktree(&self, id: String) -> Result<(), CoreError> {
+        let candidates = vec![id.clone()];
+        let result = workspaces_core::remove_worktree_core(
             id,
             &self.workspaces,
             &self.sessions,
@@ -407,13 +555,15 @@ impl DaemonState {
             |root, args| {
                 workspaces_core::run_git_command_unit(root, args, git_core::run_git_command_owned)
             },
-            |error| git_core::is_missing_worktree_error(error),
             |path| {
-                std::fs::remove_dir_all(path)
-                    .map_err(|err| format!("Failed to remove worktree folder: {err}"))
+                std::fs::remove_dir_all(path).map_err(|err| {
+                    CoreError::internal(format!("Failed to remove worktree folder: {err}"))
+                })
             },
         )
-        .await
+        .await;
+        self.forget_removed_workspaces(candidates).await;
+        result
     }
 
     async fn rename_worktree(
@@ -421,7 +571,7 @@ impl DaemonState {
         id: String,
         branch: String,
         client_version: String,
-    ) -> Result<WorkspaceInfo, String> {
+    ) -> Result<WorkspaceInfo, CoreError> {
         let client_version = client_version.clone();
         workspaces_core::rename_worktree_core(
             id,
@@ -451,6 +601,8 @@ impl DaemonState {
             move |entry, default_bin, codex_args, codex_home| {
                 spawn_with_client(
                     self.event_sink.clone(),
+                    self.session_exits.clone(),
+                    self.request_timeouts.clone(),
                     client_version.clone(),
                     entry,
                     default_bin,
@@ -467,7 +619,7 @@ impl DaemonState {
         id: String,
         old_branch: String,
         new_branch: String,
-    ) -> Result<(), String> {
+    ) -> Result<(), CoreError> {
         workspaces_core::rename_worktree_upstream_core(
             id,
             old_branch,
@@ -509,7 +661,7 @@ impl DaemonState {
         id: String,
         settings: WorkspaceSettings,
         client_version: String,
-    ) -> Result<WorkspaceInfo, String> {
+    ) -> Result<WorkspaceInfo, CoreError> {
         let client_version = client_version.clone();
         workspaces_core::update_workspace_settings_core(
             id,
@@ -524,6 +676,8 @@ impl DaemonState {
             move |entry, default_bin, codex_args, codex_home| {
                 spawn_with_client(
                     self.event_sink.clone(),
+                    self.session_exits.clone(),
+                    self.request_timeouts.clone(),
                     client_version.clone(),
                     entry,
                     default_bin,
@@ -535,7 +689,46 @@ impl DaemonState {
         .await
     }
 
-    async fn connect_workspace(&self, id: String, client_version: String) -> Result<(), String> {
+    async fn handle_session_exit(&self, exit: SessionExit) {
+        let client_version = format!("daemon-{}", env!("CARGO_PKG_VERSION"));
+        // Sessions closed on purpose are out of the map before their reader ends.
+        let was_live = self
+            .sessions
+            .lock()
+            .await
+            .values()
+            .any(|session| Arc::ptr_eq(session, &exit.session));
+        if was_live {
+            self.metrics.record_app_server_exit();
+        }
+        workspaces_core::handle_session_exit_core(
+            exit,
+            &self.workspaces,
+            &self.sessions,
+            &self.app_settings,
+            &self.event_sink,
+            |entry, default_bin, codex_args, codex_home| {
+                let spawned = spawn_with_client(
+                    self.event_sink.clone(),
+                    self.session_exits.clone(),
+                    self.request_timeouts.clone(),
+                    client_version.clone(),
+                    entry,
+                    default_bin,
+                    codex_args,
+                    codex_home,
+                );
+                async move {
+                    let session = spawned.await?;
+                    self.metrics.record_app_server_restart();
+                    Ok(session)
+                }
+            },
+        )
+        .await
+    }
+
+    async fn connect_workspace(&self, id: String, client_version: String) -> Result<(), CoreError> {
         {
             let sessions = self.sessions.lock().await;
             if sessions.contains_key(&id) {
@@ -552,6 +745,8 @@ impl DaemonState {
             move |entry, default_bin, codex_args, codex_home| {
                 spawn_with_client(
                     self.event_sink.clone(),
+                    self.session_exits.clone(),
+                    self.request_timeouts.clone(),
                     client_version.clone(),
                     entry,
                     default_bin,
@@ -560,7 +755,18 @@ impl DaemonState {
                 )
             },
         )
-        .await
+        .await?;
+        self.enforce_session_pool().await;
+        Ok(())
+    }
+
+    async fn enforce_session_pool(&self) {
+        workspaces_core::enforce_session_pool_core(
+            &self.sessions,
+            &self.app_settings,
+            &self.event_sink,
+        )
+        .await;
     }
 
     async fn set_workspace_runtime_codex_args(
@@ -568,7 +774,7 @@ impl DaemonState {
         workspace_id: String,
         codex_args: Option<String>,
         client_version: String,
-    ) -> Result<workspaces_core::WorkspaceRuntimeCodexArgsResult, String> {
+    ) -> Result<workspaces_core::WorkspaceRuntimeCodexArgsResult, CoreError> {
         workspaces_core::set_workspace_runtime_codex_args_core(
             workspace_id,
             codex_args,
@@ -578,6 +784,8 @@ impl DaemonState {
             move |entry, default_bin, next_args, codex_home| {
                 spawn_with_client(
                     self.event_sink.clone(),
+                    self.session_exits.clone(),
+                    self.request_timeouts.clone(),
                     client_version.clone(),
                     entry,
                     default_bin,
@@ -593,52 +801,59 @@ impl DaemonState {
         settings_core::get_app_settings_core(&self.app_settings).await
     }
 
-    async fn update_app_settings(&self, settings: AppSettings) -> Result<AppSettings, String> {
-        settings_core::update_app_settings_core(settings, &self.app_settings, &self.settings_path)
-            .await
+    async fn update_app_settings(&self, settings: AppSettings) -> Result<AppSettings, CoreError> {
+        settings_core::update_app_settings_core(
+            settings,
+            &self.app_settings,
+            &self.settings_path,
+            &self.request_timeouts,
+        )
+        .await
     }
 
     async fn set_codex_feature_flag(
         &self,
         feature_key: String,
         enabled: bool,
-    ) -> Result<(), String> {
+    ) -> Result<(), CoreError> {
         codex_config::write_feature_enabled(feature_key.as_str(), enabled)
     }
 
-    async fn get_agents_settings(&self) -> Result<agents_config_core::AgentsSettingsDto, String> {
+    async fn get_agents_settings(
+        &self,
+    ) -> Result<agents_config_core::AgentsSettingsDto, CoreError> {
         agents_config_core::get_agents_settings_core()
     }
 
     async fn set_agents_core_settings(
         &self,
         input: agents_config_core::SetAgentsCoreInput,
-    ) -> Result<agents_config_core::AgentsSettingsDto, String> {
+    ) -> Result<agents_config_core::AgentsSettingsDto, CoreError> {
         agents_config_core::set_agents_core_settings_core(input)
     }
 
     async fn create_agent(
         &self,
         input: agents_config_core::CreateAgentInput,
-    ) -> Result<agents_config_core::AgentsSettingsDto, String> {
+    ) -> Result<agents_config_core::AgentsSettingsDto, CoreError> {
         agents_config_core::create_agent_core(input)
     }
 
     async fn update_agent(
         &self,
         input: agents_config_core::UpdateAgentInput,
-    ) -> Result<agents_config_core::AgentsSettingsDto, String> {
+    ) -> Result<agents_config_core::AgentsSettingsDto, CoreError> {
         agents_config_core::update_agent_core(input)
     }
 
     async fn delete_agent(
         &self,
         input: agents_config_core::DeleteAgentInput,
-    ) -> Result<agents_config_core::AgentsSettingsDto, String> {
+    ) -> Result<agents_config_core::AgentsSettingsDto, CoreError> {
         agents_config_core::delete_agent_core(input)
     }
 
-    async fn read_agent_config_toml(&self, agent_name: String) -> Result<String, String> {
+    async fn read_agent_config_toml(&self, agent_name: String) -> Result<String, CoreError> {
         agents_config_core::read_agent_config_toml_core(agent_name.as_str())
     }
 
@@ -646,11 +861,11 @@ impl DaemonState {
         &self,
         agent_name: String,
         content: String,
-    ) -> Result<(), String> {
+    ) -> Result<(), CoreError> {
         agents_config_core::write_agent_config_toml_core(agent_name.as_str(), content.as_str())
     }
 
-    async fn list_workspace_files(&self, workspace_id: String) -> Result<Vec<String>, String> {
+    async fn list_workspace_files(&self, workspace_id: String) -> Result<Vec<String>, CoreError> {
         workspaces_core::list_workspace_files_core(&self.workspaces, &workspace_id, |root| {
             list_workspace_files_inner(root, 20000)
         })
@@ -661,7 +876,7 @@ impl DaemonState {
         &self,
         workspace_id: String,
         path: String,
-    ) -> Result<WorkspaceFileResponse, String> {
+    ) -> Result<WorkspaceFileResponse, CoreError> {
         workspaces_core::read_workspace_file_core(
             &self.workspaces,
             &workspace_id,
@@ -676,7 +891,7 @@ impl DaemonState {
         scope: file_policy::FileScope,
         kind: file_policy::FileKind,
         workspace_id: Option<String>,
-    ) -> Result<file_io::TextFileResponse, String> {
+    ) -> Result<file_io::TextFileResponse, CoreError> {
         files_core::file_read_core(&self.workspaces, scope, kind, workspace_id).await
     }
 
@@ -686,23 +901,23 @@ impl DaemonState {
         kind: file_policy::FileKind,
         workspace_id: Option<String>,
         content: String,
-    ) -> Result<(), String> {
+    ) -> Result<(), CoreError> {
         files_core::file_write_core(&self.workspaces, scope, kind, workspace_id, content).await
     }
 
-    async fn read_image_as_data_url(&self, path: String) -> Result<String, String> {
+    async fn read_image_as_data_url(&self, path: String) -> Result<String, CoreError> {
         let trimmed_path = path.trim();
         if trimmed_path.is_empty() {
-            return Err("Image path is required".to_string());
+            return Err(CoreError::invalid_params("Image path is required"));
         }
         let normalized = codex_core::normalize_file_path(trimmed_path);
         if normalized.is_empty() {
-            return Err("Image path is required".to_string());
+            return Err(CoreError::invalid_params("Image path is required"));
         }
         codex_core::read_image_as_data_url_core(&normalized)
     }
 
-    async fn start_thread(&self, workspace_id: String) -> Result<Value, String> {
+    async fn start_thread(&self, workspace_id: String) -> Result<Value, CoreError> {
         codex_core::start_thread_core(&self.sessions, &self.workspaces, workspace_id).await
     }
 
@@ -710,7 +925,7 @@ impl DaemonState {
         &self,
         workspace_id: String,
         thread_id: String,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, CoreError> {
         codex_core::resume_thread_core(&self.sessions, workspace_id, thread_id).await
     }
 
@@ -718,7 +933,7 @@ impl DaemonState {
         &self,
         workspace_id: String,
         thread_id: String,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, CoreError> {
         codex_core::thread_live_subscribe_core(
             &self.sessions,
             workspace_id.clone(),
@@ -747,7 +962,7 @@ impl DaemonState {
         &self,
         workspace_id: String,
         thread_id: String,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, CoreError> {
         codex_core::thread_live_unsubscribe_core(
             &self.sessions,
             workspace_id.clone(),
@@ -768,7 +983,11 @@ impl DaemonState {
         Ok(json!({ "ok": true }))
     }
 
-    async fn fork_thread(&self, workspace_id: String, thread_id: String) -> Result<Value, String> {
+    async fn fork_thread(
+        &self,
+        workspace_id: String,
+        thread_id: String,
+    ) -> Result<Value, CoreError> {
         codex_core::fork_thread_core(&self.sessions, workspace_id, thread_id).await
     }
 
@@ -778,7 +997,7 @@ impl DaemonState {
         cursor: Option<String>,
         limit: Option<u32>,
         sort_key: Option<String>,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, CoreError> {
         codex_core::list_threads_core(&self.sessions, workspace_id, cursor, limit, sort_key).await
     }
 
@@ -787,7 +1006,7 @@ impl DaemonState {
         workspace_id: String,
         cursor: Option<String>,
         limit: Option<u32>,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, CoreError> {
         codex_core::list_mcp_server_status_core(&self.sessions, workspace_id, cursor, limit).await
     }
 
@@ -795,7 +1014,7 @@ impl DaemonState {
         &self,
         workspace_id: String,
         thread_id: String,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, CoreError> {
         codex_core::archive_thread_core(&self.sessions, workspace_id, thread_id).await
     }
 
@@ -803,7 +1022,7 @@ impl DaemonState {
         &self,
         workspace_id: String,
         thread_id: String,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, CoreError> {
         codex_core::compact_thread_core(&self.sessions, workspace_id, thread_id).await
     }
 
@@ -812,7 +1031,7 @@ impl DaemonState {
         workspace_id: String,
         thread_id: String,
         name: String,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, CoreError> {
         codex_core::set_thread_name_core(&self.sessions, workspace_id, thread_id, name).await
     }
 
@@ -827,7 +1046,7 @@ impl DaemonState {
         images: Option<Vec<String>>,
         app_mentions: Option<Vec<Value>>,
         collaboration_mode: Option<Value>,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, CoreError> {
         codex_core::send_user_message_core(
             &self.sessions,
             &self.workspaces,
@@ -848,11 +1067,104 @@ impl DaemonState {
         &self,
         workspace_id: String,
         command: String,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, CoreError> {
         codex_core::run_bang_command_core(&self.sessions, &self.workspaces, workspace_id, command)
             .await
     }
 
+    async fn terminal_open(
+        &self,
+        workspace_id: String,
+        terminal_id: String,
+        cols: u16,
+        rows: u16,
+    ) -> Result<terminal_rpc::TerminalSessionInfo, CoreError> {
+        let request = terminal_rpc::TerminalOpenRequest {
+            workspace_id,
+            terminal_id,
+            cols,
+            rows,
+        };
+        let id = terminal_core::terminal_open_core(
+            &self.terminal_sessions,
+            &self.workspaces,
+            &terminal_core::scrollback_dir(&self.data_dir),
+            request,
+            self.event_sink.clone(),
+        )
+        .await?;
+        Ok(terminal_rpc::TerminalSessionInfo { id })
+    }
+
+    async fn terminal_attach(
+        &self,
+        workspace_id: String,
+        terminal_id: String,
+    ) -> Result<terminal_rpc::TerminalAttachInfo, CoreError> {
+        terminal_core::terminal_attach_core(
+            &self.terminal_sessions,
+            &terminal_core::scrollback_dir(&self.data_dir),
+            &workspace_id,
+            &terminal_id,
+        )
+        .await
+    }
+
+    async fn terminal_list(&self, workspace_id: String) -> Vec<terminal_rpc::TerminalListEntry> {
+        terminal_core::terminal_list_core(
+            &self.terminal_sessions,
+            &terminal_core::scrollback_dir(&self.data_dir),
+            &workspace_id,
+        )
+        .await
+    }
+
+    async fn terminal_write(
+        &self,
+        workspace_id: String,
+        terminal_id: String,
+        data: String,
+    ) -> Result<(), CoreError> {
+        terminal_core::terminal_write_core(
+            &self.terminal_sessions,
+            &workspace_id,
+            &terminal_id,
+            data,
+        )
+        .await
+    }
+
+    async fn terminal_resize(
+        &self,
+        workspace_id: String,
+        terminal_id: String,
+        cols: u16,
+        rows: u16,
+    ) -> Result<(), CoreError> {
+        terminal_core::terminal_resize_core(
+            &self.terminal_sessions,
+            &workspace_id,
+            &terminal_id,
+            cols,
+            rows,
+        )
+        .await
+    }
+
+    async fn terminal_close(
+        &self,
+        workspace_id: String,
+        terminal_id: String,
+    ) -> Result<(), CoreError> {
+        terminal_core::terminal_close_core(
+            &self.terminal_sessions,
+            &terminal_core::scrollback_dir(&self.data_dir),
+            &workspace_id,
+            &terminal_id,
+        )
+        .await
+    }
+
     async fn turn_steer(
         &self,
         workspace_id: String,
@@ -861,7 +1173,7 @@ impl DaemonState {
         text: String,
         images: Option<Vec<String>>,
         app_mentions: Option<Vec<Value>>,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, CoreError> {
         codex_core::turn_steer_core(
             &self.sessions,
             workspace_id,
@@ -879,7 +1191,7 @@ impl DaemonState {
         workspace_id: String,
         thread_id: String,
         turn_id: String,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, CoreError> {
         codex_core::turn_interrupt_core(&self.sessions, workspace_id, thread_id, turn_id).await
     }
 
@@ -889,12 +1201,12 @@ impl DaemonState {
         thread_id: String,
         target: Value,
         delivery: Option<String>,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, CoreError> {
         codex_core::start_review_core(&self.sessions, workspace_id, thread_id, target, delivery)
             .await
     }
 
-    async fn model_list(&self, workspace_id: String) -> Result<Value, String> {
+    async fn model_list(&self, workspace_id: String) -> Result<Value, CoreError> {
         codex_core::model_list_core(&self.sessions, workspace_id).await
     }
 
@@ -903,27 +1215,27 @@ impl DaemonState {
         workspace_id: String,
         cursor: Option<String>,
         limit: Option<u32>,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, CoreError> {
         codex_core::experimental_feature_list_core(&self.sessions, workspace_id, cursor, limit)
             .await
     }
 
-    async fn collaboration_mode_list(&self, workspace_id: String) -> Result<Value, String> {
+    async fn collaboration_mode_list(&self, workspace_id: String) -> Result<Value, CoreError> {
         codex_core::collaboration_mode_list_core(&self.sessions, workspace_id).await
     }
 
-    async fn account_rate_limits(&self, workspace_id: String) -> Result<Value, String> {
+    async fn account_rate_limits(&self, workspace_id: String) -> Result<Value, CoreError> {
         codex_core::account_rate_limits_core(&self.sessions, workspace_id).await
     }
 
-    async fn account_read(&self, workspace_id: String) -> Result<Value, String> {
+    async fn account_read(&self, workspace_id: String) -> Result<Value, CoreError> {
         codex_core::account_read_core(&self.sessions, &self.workspaces, workspace_id).await
     }
 
     async fn dictation_auth_status(
         &self,
         workspace_id: Option<String>,
-    ) -> Result<transcription_chatgpt_core::DictationAuthStatus, String> {
+    ) -> Result<transcription_chatgpt_core::DictationAuthStatus, CoreError> {
         Ok(transcription_chatgpt_core::dictation_auth_status_core(
             &self.sessions,
             workspace_id,
@@ -937,10 +1249,10 @@ impl DaemonState {
         audio_base64: String,
         mime_type: String,
         language: Option<String>,
-    ) -> Result<Value, String> {
-        let audio = STANDARD
-            .decode(audio_base64.as_bytes())
-            .map_err(|error| format!("Invalid dictation audio payload: {error}"))?;
+    ) -> Result<Value, CoreError> {
+        let audio = STANDARD.decode(audio_base64.as_bytes()).map_err(|error| {
+            CoreError::internal(format!("Invalid dictation audio payload: {error}"))
+        })?;
         let text = transcription_chatgpt_core::dictation_transcribe_chatgpt_core(
             &self.sessions,
             workspace_id,
@@ -952,16 +1264,16 @@ impl DaemonState {
         Ok(json!({ "text": text }))
     }
 
-    async fn codex_login(&self, workspace_id: String) -> Result<Value, String> {
+    async fn codex_login(&self, workspace_id: String) -> Result<Value, CoreError> {
         codex_core::codex_login_core(&self.sessions, &self.codex_login_cancels, workspace_id).await
     }
 
-    async fn codex_login_cancel(&self, workspace_id: String) -> Result<Value, String> {
+    async fn codex_login_cancel(&self, workspace_id: String) -> Result<Value, CoreError> {
         codex_core::codex_login_cancel_core(&self.sessions, &self.codex_login_cancels, workspace_id)
             .await
     }
 
-    async fn skills_list(&self, workspace_id: String) -> Result<Value, String> {
+    async fn skills_list(&self, workspace_id: String) -> Result<Value, CoreError> {
         codex_core::skills_list_core(&self.sessions, &self.workspaces, workspace_id).await
     }
 
@@ -971,7 +1283,7 @@ impl DaemonState {
         cursor: Option<String>,
         limit: Option<u32>,
         thread_id: Option<String>,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, CoreError> {
         codex_core::apps_list_core(&self.sessions, workspace_id, cursor, limit, thread_id).await
     }
 
@@ -980,7 +1292,7 @@ impl DaemonState {
         workspace_id: String,
         request_id: Value,
         result: Value,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, CoreError> {
         codex_core::respond_to_server_request_core(
             &self.sessions,
             workspace_id,
@@ -995,11 +1307,11 @@ impl DaemonState {
         &self,
         workspace_id: String,
         command: Vec<String>,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, CoreError> {
         codex_core::remember_approval_rule_core(&self.workspaces, workspace_id, command).await
     }
 
-    async fn get_config_model(&self, workspace_id: String) -> Result<Value, String> {
+    async fn get_config_model(&self, workspace_id: String) -> Result<Value, CoreError> {
         codex_core::get_config_model_core(&self.workspaces, workspace_id).await
     }
 
@@ -1009,7 +1321,7 @@ impl DaemonState {
         copies_folder: String,
         copy_name: String,
         client_version: String,
-    ) -> Result<WorkspaceInfo, String> {
+    ) -> Result<WorkspaceInfo, CoreError> {
         workspaces_core::add_clone_core(
             source_workspace_id,
             copy_name,
@@ -1021,6 +1333,8 @@ impl DaemonState {
             |entry, default_bin, codex_args, codex_home| {
                 spawn_with_client(
                     self.event_sink.clone(),
+                    self.session_exits.clone(),
+                    self.request_timeouts.clone(),
                     client_version.clone(),
                     entry,
                     default_bin,
@@ -1032,7 +1346,7 @@ impl DaemonState {
         .await
     }
 
-    async fn apply_worktree_changes(&self, workspace_id: String) -> Result<(), String> {
+    async fn apply_worktree_changes(&self, workspace_id: String) -> Result<(), CoreError> {
         workspaces_core::apply_worktree_changes_core(&self.workspaces, workspace_id).await
     }
 
@@ -1042,11 +1356,11 @@ impl DaemonState {
         app: Option<String>,
         args: Vec<String>,
         command: Option<String>,
-    ) -> Result<(), String> {
+    ) -> Result<(), CoreError> {
         workspaces_core::open_workspace_in_core(path, app, args, command).await
     }
 
-    async fn get_open_app_icon(&self, app_name: String) -> Result<Option<String>, String> {
+    async fn get_open_app_icon(&self, app_name: String) -> Result<Option<String>, CoreError> {
         #[cfg(target_os = "macos")]
         {
             return workspaces_core::get_open_app_icon_core(app_name, |name| {
@@ -1061,7 +1375,7 @@ impl DaemonState {
         }
     }
 
-    async fn get_git_status(&self, workspace_id: String) -> Result<Value, String> {
+    async fn get_git_status(&self, workspace_id: String) -> Result<Value, CoreError> {
         git_ui_core::get_git_status_core(&self.workspaces, workspace_id).await
     }
 
@@ -1070,7 +1384,7 @@ impl DaemonState {
         workspace_id: String,
         branch: String,
         force: bool,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, CoreError> {
         git_ui_core::init_git_repo_core(&self.workspaces, workspace_id, branch, force).await
     }
 
@@ -1080,7 +1394,7 @@ impl DaemonState {
         repo: String,
         visibility: String,
         branch: Option<String>,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, CoreError> {
         git_ui_core::create_github_repo_core(
             &self.workspaces,
             workspace_id,
@@ -1095,11 +1409,11 @@ impl DaemonState {
         &self,
         workspace_id: String,
         depth: Option<usize>,
-    ) -> Result<Vec<String>, String> {
+    ) -> Result<Vec<String>, CoreError> {
         git_ui_core::list_git_roots_core(&self.workspaces, workspace_id, depth).await
     }
 
-    async fn get_git_diffs(&self, workspace_id: String) -> Result<Vec<GitFileDiff>, String> {
+    async fn get_git_diffs(&self, workspace_id: String) -> Result<Vec<GitFileDiff>, CoreError> {
         git_ui_core::get_git_diffs_core(&self.workspaces, &self.app_settings, workspace_id).await
     }
 
@@ -1107,7 +1421,7 @@ impl DaemonState {
         &self,
         workspace_id: String,
         limit: Option<usize>,
-    ) -> Result<GitLogResponse, String> {
+    ) -> Result<GitLogResponse, CoreError> {
         git_ui_core::get_git_log_core(&self.workspaces, workspace_id, limit).await
     }
 
@@ -1115,7 +1429,7 @@ impl DaemonState {
         &self,
         workspace_id: String,
         sha: String,
-    ) -> Result<Vec<GitCommitDiff>, String> {
+    ) -> Result<Vec<GitCommitDiff>, CoreError> {
         git_ui_core::get_git_commit_diff_core(
             &self.workspaces,
             &self.app_settings,
@@ -1125,61 +1439,61 @@ impl DaemonState {
         .await
     }
 
-    async fn get_git_remote(&self, workspace_id: String) -> Result<Option<String>, String> {
+    async fn get_git_remote(&self, workspace_id: String) -> Result<Option<String>, CoreError> {
         git_ui_core::get_git_remote_core(&self.workspaces, workspace_id).await
     }
 
-    async fn stage_git_file(&self, workspace_id: String, path: String) -> Result<(), String> {
+    async fn stage_git_file(&self, workspace_id: String, path: String) -> Result<(), CoreError> {
         git_ui_core::stage_git_file_core(&self.workspaces, workspace_id, path).await
     }
 
-    async fn stage_git_all(&self, workspace_id: String) -> Result<(), String> {
+    async fn stage_git_all(&self, workspace_id: String) -> Result<(), CoreError> {
         git_ui_core::stage_git_all_core(&self.workspaces, workspace_id).await
     }
 
-    async fn unstage_git_file(&self, workspace_id: String, path: String) -> Result<(), String> {
+    async fn unstage_git_file(&self, workspace_id: String, path: String) -> Result<(), CoreError> {
         git_ui_core::unstage_git_file_core(&self.workspaces, workspace_id, path).await
     }
 
-    async fn revert_git_file(&self, workspace_id: String, path: String) -> Result<(), String> {
+    async fn revert_git_file(&self, workspace_id: String, path: String) -> Result<(), CoreError> {
         git_ui_core::revert_git_file_core(&self.workspaces, workspace_id, path).await
     }
 
-    async fn revert_git_all(&self, workspace_id: String) -> Result<(), String> {
+    async fn revert_git_all(&self, workspace_id: String) -> Result<(), CoreError> {
         git_ui_core::revert_git_all_core(&self.workspaces, workspace_id).await
     }
 
-    async fn commit_git(&self, workspace_id: String, message: String) -> Result<(), String> {
+    async fn commit_git(&self, workspace_id: String, message: String) -> Result<(), CoreError> {
         git_ui_core::commit_git_core(&self.workspaces, workspace_id, message).await
     }
 
-    async fn push_git(&self, workspace_id: String) -> Result<(), String> {
+    async fn push_git(&self, workspace_id: String) -> Result<(), CoreError> {
         git_ui_core::push_git_core(&self.workspaces, workspace_id).await
     }
 
-    async fn pull_git(&self, workspace_id: String) -> Result<(), String> {
+    async fn pull_git(&self, workspace_id: String) -> Result<(), CoreError> {
         git_ui_core::pull_git_core(&self.workspaces, workspace_id).await
     }
 
-    async fn fetch_git(&self, workspace_id: String) -> Result<(), String> {
+    async fn fetch_git(&self, workspace_id: String) -> Result<(), CoreError> {
         git_ui_core::fetch_git_core(&self.workspaces, workspace_id).await
     }
 
-    async fn sync_git(&self, workspace_id: String) -> Result<(), String> {
+    async fn sync_git(&self, workspace_id: String) -> Result<(), CoreError> {
         git_ui_core::sync_git_core(&self.workspaces, workspace_id).await
     }
 
     async fn get_github_issues(
         &self,
         workspace_id: String,
-    ) -> Result<GitHubIssuesResponse, String> {
+    ) -> Result<GitHubIssuesResponse, CoreError> {
         git_ui_core::get_github_issues_core(&self.workspaces, workspace_id).await
     }
 
     async fn get_github_pull_requests(
         &self,
         workspace_id: String,
-    ) -> Result<GitHubPullRequestsResponse, String> {
+    ) -> Result<GitHubPullRequestsResponse, CoreError> {
         git_ui_core::get_github_pull_requests_core(&self.workspaces, workspace_id).await
     }
 
@@ -1187,7 +1501,7 @@ impl DaemonState {
         &self,
         workspace_id: String,
         pr_number: u64,
-    ) -> Result<Vec<GitHubPullRequestDiff>, String> {
+    ) -> Result<Vec<GitHubPullRequestDiff>, CoreError> {
         git_ui_core::get_github_pull_request_diff_core(&self.workspaces, workspace_id, pr_number)
             .await
     }
@@ -1196,7 +1510,7 @@ impl DaemonState {
         &self,
         workspace_id: String,
         pr_number: u64,
-    ) -> Result<Vec<GitHubPullRequestComment>, String> {
+    ) -> Result<Vec<GitHubPullRequestComment>, CoreError> {
         git_ui_core::get_github_pull_request_comments_core(
             &self.workspaces,
             workspace_id,
@@ -1209,28 +1523,35 @@ impl DaemonState {
         &self,
         workspace_id: String,
         pr_number: u64,
-    ) -> Result<(), String> {
+    ) -> Result<(), CoreError> {
         git_ui_core::checkout_github_pull_request_core(&self.workspaces, workspace_id, pr_number)
             .await
     }
 
-    async fn list_git_branches(&self, workspace_id: String) -> Result<Value, String> {
+    async fn list_git_branches(&self, workspace_id: String) -> Result<Value, CoreError> {
         git_ui_core::list_git_branches_core(&self.workspaces, workspace_id).await
     }
 
-    async fn checkout_git_branch(&self, workspace_id: String, name: String) -> Result<(), String> {
+    async fn checkout_git_branch(
+        &self,
+        workspace_id: String,
+        name: String,
+    ) -> Result<(), CoreError> {
         git_ui_core::checkout_git_branch_core(&self.workspaces, workspace_id, name).await
     }
 
-    async fn create_git_branch(&self, workspace_id: String, name: String) -> Result<(), String> {
+    async fn create_git_branch(&self, workspace_id: String, name: String) -> Result<(), CoreError> {
         git_ui_core::create_git_branch_core(&self.workspaces, workspace_id, name).await
     }
 
-    async fn prompts_list(&self, workspace_id: String) -> Result<Vec<CustomPromptEntry>, String> {
+    async fn prompts_list(
+        &self,
+        workspace_id: String,
+    ) -> Result<Vec<CustomPromptEntry>, CoreError> {
         prompts_core::prompts_list_core(&self.workspaces, &self.settings_path, workspace_id).await
     }
 
-    async fn prompts_workspace_dir(&self, workspace_id: String) -> Result<String, String> {
+    async fn prompts_workspace_dir(&self, workspace_id: String) -> Result<String, CoreError> {
         prompts_core::prompts_workspace_dir_core(
             &self.workspaces,
             &self.settings_path,
@@ -1239,7 +1560,7 @@ impl DaemonState {
         .await
     }
 
-    async fn prompts_global_dir(&self, workspace_id: String) -> Result<String, String> {
+    async fn prompts_global_dir(&self, workspace_id: String) -> Result<String, CoreError> {
         prompts_core::prompts_global_dir_core(&self.workspaces, workspace_id).await
     }
 
@@ -1251,7 +1572,7 @@ impl DaemonState {
         description: Option<String>,
         argument_hint: Option<String>,
         content: String,
-    ) -> Result<CustomPromptEntry, String> {
+    ) -> Result<CustomPromptEntry, CoreError> {
         prompts_core::prompts_create_core(
             &self.workspaces,
             &self.settings_path,
@@ -1273,7 +1594,7 @@ impl DaemonState {
         description: Option<String>,
         argument_hint: Option<String>,
         content: String,
-    ) -> Result<CustomPromptEntry, String> {
+    ) -> Result<CustomPromptEntry, CoreError> {
         prompts_core::prompts_update_core(
             &self.workspaces,
             &self.settings_path,
@@ -1287,7 +1608,7 @@ impl DaemonState {
         .await
     }
 
-    async fn prompts_delete(&self, workspace_id: String, path: String) -> Result<(), String> {
+    async fn prompts_delete(&self, workspace_id: String, path: String) -> Result<(), CoreError> {
         prompts_core::prompts_delete_core(&self.workspaces, &self.settings_path, workspace_id, path)
             .await
     }
@@ -1297,7 +1618,7 @@ impl DaemonState {
         workspace_id: String,
         path: String,
         scope: String,
-    ) -> Result<CustomPromptEntry, String> {
+    ) -> Result<CustomPromptEntry, CoreError> {
         prompts_core::prompts_move_core(
             &self.workspaces,
             &self.settings_path,
@@ -1312,7 +1633,7 @@ impl DaemonState {
         &self,
         codex_bin: Option<String>,
         codex_args: Option<String>,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, CoreError> {
         codex_aux_core::codex_doctor_core(&self.app_settings, codex_bin, codex_args).await
     }
 
@@ -1320,7 +1641,7 @@ impl DaemonState {
         &self,
         workspace_id: String,
         commit_message_model_id: Option<String>,
-    ) -> Result<String, String> {
+    ) -> Result<String, CoreError> {
         let repo_root = git_ui_core::resolve_repo_root_for_workspace_core(
             &self.workspaces,
             workspace_id.clone(),
@@ -1349,7 +1670,7 @@ impl DaemonState {
         &self,
         workspace_id: String,
         prompt: String,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, CoreError> {
         codex_aux_core::generate_run_metadata_core(
             &self.sessions,
             &self.workspaces,
@@ -1366,7 +1687,7 @@ impl DaemonState {
         &self,
         workspace_id: String,
         description: String,
-    ) -> Result<codex_aux_core::GeneratedAgentConfiguration, String> {
+    ) -> Result<codex_aux_core::GeneratedAgentConfiguration, CoreError> {
         codex_aux_core::generate_agent_description_core(
             &self.sessions,
             &self.workspaces,
@@ -1383,11 +1704,11 @@ impl DaemonState {
         &self,
         days: Option<u32>,
         workspace_path: Option<String>,
-    ) -> Result<LocalUsageSnapshot, String> {
+    ) -> Result<LocalUsageSnapshot, CoreError> {
         local_usage_core::local_usage_snapshot_core(&self.workspaces, days, workspace_path).await
     }
 
-    async fn menu_set_accelerators(&self, _updates: Vec<Value>) -> Result<(), String> {
+    async fn menu_set_accelerators(&self, _updates: Vec<Value>) -> Result<(), CoreError> {
         // Daemon has no native menu runtime; treat as no-op for remote parity.
         Ok(())
     }
@@ -1396,38 +1717,59 @@ impl DaemonState {
         cfg!(all(target_os = "macos", debug_assertions))
     }
 
-    async fn send_notification_fallback(&self, title: String, body: String) -> Result<(), String> {
+    async fn send_notification_fallback(
+        &self,
+        title: String,
+        body: String,
+    ) -> Result<(), CoreError> {
         send_notification_fallback_inner(title, body)
     }
 
-    async fn presence_heartbeat(&self, input: push::PresenceHeartbeatInput) -> Result<(), String> {
+    async fn presence_heartbeat(
+        &self,
+        input: push::PresenceHeartbeatInput,
+    ) -> Result<(), CoreError> {
         self.push_broker.record_presence(input).await
     }
 
     async fn push_register_device(
         &self,
         input: push::PushDeviceRegistrationInput,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, CoreError> {
         let device = self.push_broker.register_device(input).await?;
-        serde_json::to_value(device).map_err(|err| err.to_string())
+        serde_json::to_value(device).map_err(|err| CoreError::internal(err.to_string()))
     }
 
-    async fn push_unregister_device(&self, device_id: String) -> Result<(), String> {
+    async fn push_unregister_device(&self, device_id: String) -> Result<(), CoreError> {
         self.push_broker.unregister_device(device_id).await
     }
 
-    async fn push_notification_config_get(&self) -> Result<Value, String> {
+    async fn push_device_rules_get(&self, device_id: String) -> Result<Value, CoreError> {
+        let rules = self.push_broker.device_rules(device_id).await?;
+        serde_json::to_value(rules).map_err(|err| CoreError::internal(err.to_string()))
+    }
+
+    async fn push_device_rules_patch(
+        &self,
+        device_id: String,
+        patch: push::PushDeviceRulesPatch,
+    ) -> Result<Value, CoreError> {
+        let rules = self.push_broker.patch_device_rules(device_id, patch).await?;
+        serde_json::to_value(rules).map_err(|err| CoreError::internal(err.to_string()))
+    }
+
+    async fn push_notification_config_get(&self) -> Result<Value, CoreError> {
         Ok(self.push_broker.config_snapshot().await)
     }
 
     async fn push_notification_config_patch(
         &self,
         patch: push::PushNotificationConfigPatch,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, CoreError> {
         self.push_broker.patch_config(patch).await
     }
 
-    async fn push_notification_state(&self) -> Result<Value, String> {
+    async fn push_notification_state(&self) -> Result<Value, CoreError> {
         Ok(self.push_broker.state_snapshot().await)
     }
 
@@ -1468,7 +1810,7 @@ fn emit_background_thread_hide(event_sink: &DaemonEventSink, workspace_id: &str,
     });
 }
 
-fn send_notification_fallback_inner(title: String, body: String) -> Result<(), String> {
+fn send_notification_fallback_inner(title: String, body: String) -> Result<(), CoreError> {
     #[cfg(all(target_os = "macos", debug_assertions))]
     {
         let escape = |value: &str| value.replace('\\', "\\\\").replace('"', "\\\"");
@@ -1482,18 +1824,22 @@ fn send_notification_fallback_inner(title: String, body: String) -> Result<(), S
             .arg("-e")
             .arg(script)
             .status()
-            .map_err(|error| format!("Failed to run osascript: {error}"))?;
+            .map_err(|error| CoreError::internal(format!("Failed to run osascript: {error}")))?;
 
         if status.success() {
             return Ok(());
         }
-        return Err(format!("osascript exited with status: {status}"));
+        return Err(CoreError::internal(format!(
+            "osascript exited with status: {status}"
+        )));
     }
 
     #[cfg(not(all(target_os = "macos", debug_assertions)))]
     {
         let _ = (title, body);
-        Err("Notification fallback is only available on macOS debug builds.".to_string())
+        Err(CoreError::internal(
+            "Notification fallback is only available on macOS debug builds.",
+        ))
     }
 }
 
@@ -1543,35 +1889,37 @@ const MAX_WORKSPACE_FILE_BYTES: u64 = 400_000;
 fn read_workspace_file_inner(
     root: &PathBuf,
     relative_path: &str,
-) -> Result<WorkspaceFileResponse, String> {
+) -> Result<WorkspaceFileResponse, CoreError> {
     let canonical_root = root
         .canonicalize()
-        .map_err(|err| format!("Failed to resolve workspace root: {err}"))?;
+        .map_err(|err| CoreError::internal(format!("Failed to resolve workspace root: {err}")))?;
     let candidate = canonical_root.join(relative_path);
     let canonical_path = candidate
         .canonicalize()
-        .map_err(|err| format!("Failed to open file: {err}"))?;
+        .map_err(|err| CoreError::internal(format!("Failed to open file: {err}")))?;
     if !canonical_path.starts_with(&canonical_root) {
-        return Err("Invalid file path".to_string());
+        return Err(CoreError::invalid_params("Invalid file path"));
     }
     let metadata = std::fs::metadata(&canonical_path)
-        .map_err(|err| format!("Failed to read file metadata: {err}"))?;
+        .map_err(|err| CoreError::internal(format!("Failed to read file metadata: {err}")))?;
     if !metadata.is_file() {
-        return Err("Path is not a file".to_string());
+        return Err(CoreError::invalid_params("Path is not a file"));
     }
 
-    let file = File::open(&canonical_path).map_err(|err| format!("Failed to open file: {err}"))?;
+    let file = File::open(&canonical_path)
+        .map_err(|err| CoreError::internal(format!("Failed to open file: {err}")))?;
     let mut buffer = Vec::new();
     file.take(MAX_WORKSPACE_FILE_BYTES + 1)
         .read_to_end(&mut buffer)
-        .map_err(|err| format!("Failed to read file: {err}"))?;
+        .map_err(|err| CoreError::internal(format!("Failed to read file: {err}")))?;
 
     let truncated = buffer.len() > MAX_WORKSPACE_FILE_BYTES as usize;
     if truncated {
         buffer.truncate(MAX_WORKSPACE_FILE_BYTES as usize);
     }
 
-    let content = String::from_utf8(buffer).map_err(|_| "File is not valid UTF-8".to_string())?;
+    let content =
+        String::from_utf8(buffer).map_err(|_| CoreError::internal("File is not valid UTF-8"))?;
     Ok(WorkspaceFileResponse { content, truncated })
 }
 
@@ -1592,8 +1940,10 @@ fn default_data_dir() -> PathBuf {
 fn usage() -> String {
     format!(
         "\
-USAGE:\n  codex-monitor-daemon [--listen <addr>] [--data-dir <path>] [--token <token> | --insecure-no-auth]\n\n\
-OPTIONS:\n  --listen <addr>          Bind address (default: {DEFAULT_LISTEN_ADDR})\n  --data-dir <path>        Data dir holding workspaces.json/settings.json\n  --token <token>          Shared token required by TCP clients\n  --insecure-no-auth       Disable TCP auth (dev only)\n  -h, --help               Show this help\n"
+USAGE:\n  codex-monitor-daemon [--listen <addr>] [--data-dir <path>] [--token <token> | --insecure-no-auth] [--tls | --tls-cert <path> --tls-key <path>] [--ws-listen <addr> [--ws-path <path>]] [--metrics-listen <addr>]\n\n\
+OPTIONS:\n  --listen <addr>          Bind address (default: {DEFAULT_LISTEN_ADDR})\n  --data-dir <path>        Data dir holding workspaces.json/settings.json\n  --token <token>          Shared admin token required by TCP clients\n  --insecure-no-auth       Disable TCP auth (dev only; ignored once named tokens exist)\n  --tls                    Serve TLS using a self-signed cert kept in <data-dir>/tls\n  --tls-cert <path>        PEM certificate chain to serve (generated if missing)\n  --tls-key <path>         PEM private key for --tls-cert (generated if missing)\n  --ws-listen <addr>       Also serve the protocol over WebSocket on this address\n  --ws-path <path>         WebSocket upgrade path (default: {DEFAULT_WS_PATH})\n  --metrics-listen <addr>  Serve Prometheus /metrics and /healthz over plain HTTP (no auth; keep it on loopback)\n  -h, --help               Show this help\n\n\
+NOTES:\n  - Named tokens with roles and workspace allowlists are read from <data-dir>/{}\n    and managed with `codex-monitor-daemonctl token`\n",
+        auth_tokens::AUTH_TOKENS_FILE_NAME
     )
 }
 
@@ -1607,6 +1957,12 @@ fn parse_args() -> Result<DaemonConfig, String> {
         .filter(|value| !value.is_empty());
     let mut insecure_no_auth = false;
     let mut data_dir: Option<PathBuf> = None;
+    let mut tls_enabled = false;
+    let mut tls_cert: Option<PathBuf> = None;
+    let mut tls_key: Option<PathBuf> = None;
+    let mut ws_listen: Option<SocketAddr> = None;
+    let mut metrics_listen: Option<SocketAddr> = None;
+    let mut ws_path = DEFAULT_WS_PATH.to_string();
 
     let mut args = env::args().skip(1);
     while let Some(arg) = args.next() {
@@ -1639,38 +1995,86 @@ fn parse_args() -> Result<DaemonConfig, String> {
                 insecure_no_auth = true;
                 token = None;
             }
+            "--tls" => {
+                tls_enabled = true;
+            }
+            "--tls-cert" => {
+                let value = args.next().ok_or("--tls-cert requires a value")?;
+                let trimmed = value.trim();
+                if trimmed.is_empty() {
+                    return Err("--tls-cert requires a non-empty value".to_string());
+                }
+                tls_cert = Some(PathBuf::from(trimmed));
+            }
+            "--tls-key" => {
+                let value = args.next().ok_or("--tls-key requires a value")?;
+                let trimmed = value.trim();
+                if trimmed.is_empty() {
+                    return Err("--tls-key requires a non-empty value".to_string());
+                }
+                tls_key = Some(PathBuf::from(trimmed));
+            }
+            "--ws-listen" => {
+                let value = args.next().ok_or("--ws-listen requires a value")?;
+                ws_listen = Some(value.parse::<SocketAddr>().map_err(|err| err.to_string())?);
+            }
+            "--ws-path" => {
+                let value = args.next().ok_or("--ws-path requires a value")?;
+                ws_path = websocket::normalize_ws_path(&value)?;
+            }
+            "--metrics-listen" => {
+                let value = args.next().ok_or("--metrics-listen requires a value")?;
+                metrics_listen = Some(value.parse::<SocketAddr>().map_err(|err| err.to_string())?);
+            }
             _ => return Err(format!("Unknown argument: {arg}")),
         }
     }
 
-    if token.is_none() && !insecure_no_auth {
+    let data_dir = data_dir.unwrap_or_else(default_data_dir);
+    let has_named_tokens =
+        !auth_tokens::read_auth_tokens(&auth_tokens::auth_tokens_path(&data_dir))?.is_empty();
+    if token.is_none() && !insecure_no_auth && !has_named_tokens {
         return Err(
-            "Missing --token (or set CODEX_MONITOR_DAEMON_TOKEN). Use --insecure-no-auth for local dev only."
+            "Missing --token (or set CODEX_MONITOR_DAEMON_TOKEN). Create a named token with `codex-monitor-daemonctl token create`, or use --insecure-no-auth for local dev only."
                 .to_string(),
         );
     }
+    let tls = match (tls_cert, tls_key) {
+        (Some(cert_path), Some(key_path)) => Some(tls::DaemonTlsConfig {
+            cert_path,
+            key_path,
+        }),
+        (None, None) if tls_enabled => Some(tls::DaemonTlsConfig::in_data_dir(&data_dir)),
+        (None, None) => None,
+        _ => return Err("--tls-cert and --tls-key must be provided together".to_string()),
+    };
 
     Ok(DaemonConfig {
         listen,
         token,
-        data_dir: data_dir.unwrap_or_else(default_data_dir),
+        data_dir,
+        tls,
+        ws_listen,
+        ws_path,
+        metrics_listen,
     })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backend::test_support::session;
+    use crate::shared::core_error::ErrorCode;
     use crate::shared::process_core::kill_child_process_tree;
     use crate::storage::write_workspaces;
     use crate::types::WorkspaceKind;
     use serde_json::json;
     use std::future::Future;
     use std::path::PathBuf;
-    use std::process::Stdio;
-    use std::sync::atomic::AtomicU64;
     use std::sync::Arc;
     use std::time::{Duration, SystemTime, UNIX_EPOCH};
-    use tokio::process::Command;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+    use tracing_subscriber::layer::SubscriberExt;
 
     fn run_async_test<F>(future: F)
     where
@@ -1697,19 +2101,29 @@ mod tests {
     }
 
     fn test_state(data_dir: &std::path::Path) -> DaemonState {
-        let (tx, _rx) = broadcast::channel::<DaemonEvent>(32);
+        let (tx, _rx) = broadcast::channel::<SequencedEvent>(32);
+        let (session_exits, _session_exits_rx) = mpsc::unbounded_channel();
         let push_broker = Arc::new(push::PushBroker::load(&data_dir.to_path_buf()));
         DaemonState {
             data_dir: data_dir.to_path_buf(),
             workspaces: Mutex::new(HashMap::new()),
             sessions: Mutex::new(HashMap::new()),
+            terminal_sessions: Arc::new(Mutex::new(HashMap::new())),
             storage_path: data_dir.join("workspaces.json"),
             settings_path: data_dir.join("settings.json"),
             app_settings: Mutex::new(AppSettings::default()),
-            event_sink: DaemonEventSink { tx },
+            event_sink: DaemonEventSink {
+                tx,
+                replay: Arc::new(EventReplayBuffer::new(EVENT_REPLAY_CAPACITY_PER_WORKSPACE)),
+            },
+            session_exits,
+            request_timeouts: RequestTimeouts::default(),
             push_broker,
+            metrics: Arc::new(metrics::DaemonMetrics::default()),
+            shutdown: shutdown::ShutdownSignal::default(),
             codex_login_cancels: Mutex::new(HashMap::new()),
             daemon_binary_path: Some("/tmp/codex-monitor-daemon".to_string()),
+            auth_tokens: auth_tokens::AuthTokenStore::in_data_dir(data_dir),
         }
     }
 
@@ -1745,38 +2159,7 @@ mod tests {
     }
 
     fn make_session(entry: WorkspaceEntry) -> Arc<WorkspaceSession> {
-        let owner_workspace_id = entry.id;
-        let mut cmd = if cfg!(windows) {
-            let mut cmd = Command::new("cmd");
-            cmd.args(["/C", "more"]);
-            cmd
-        } else {
-            let mut cmd = Command::new("sh");
-            cmd.args(["-c", "cat"]);
-            cmd
-        };
-
-        cmd.stdin(Stdio::piped())
-            .stdout(Stdio::null())
-            .stderr(Stdio::null());
-
-        let mut child = cmd.spawn().expect("spawn dummy child");
-        let stdin = child.stdin.take().expect("dummy child stdin");
-
-        Arc::new(WorkspaceSession {
-            codex_args: None,
-            child: Mutex::new(child),
-            stdin: Mutex::new(stdin),
-            pending: Mutex::new(HashMap::new()),
-            request_context: Mutex::new(HashMap::new()),
-            thread_workspace: Mutex::new(HashMap::new()),
-            next_id: AtomicU64::new(0),
-            background_thread_callbacks: Mutex::new(HashMap::new()),
-            workspace_ids: Mutex::new(HashSet::from([owner_workspace_id.clone()])),
-            workspace_roots: Mutex::new(HashMap::new()),
-            turn_start_retry_context: Mutex::new(HashMap::new()),
-            owner_workspace_id,
-        })
+        Arc::new(session(&entry.id))
     }
 
     #[test]
@@ -1798,7 +2181,7 @@ mod tests {
             .await
             .expect_err("expected validation error");
 
-            assert_eq!(err, "Copy name is required.");
+            assert_eq!(err.message, "Copy name is required.");
             let _ = std::fs::remove_dir_all(&tmp);
         });
     }
@@ -1886,83 +2269,417 @@ mod tests {
                 result.get("version").and_then(Value::as_str),
                 Some(env!("CARGO_PKG_VERSION"))
             );
+            assert_eq!(
+                result.get("protocolVersion").and_then(Value::as_u64),
+                Some(u64::from(shared::rpc_schema::PROTOCOL_VERSION))
+            );
             let _ = std::fs::remove_dir_all(&tmp);
         });
     }
+
     #[test]
-    fn list_workspaces_syncs_from_storage_file() {
+    fn terminal_rpcs_validate_workspace_and_session() {
         run_async_test(async {
-            let tmp = make_temp_dir("list-workspaces-sync");
+            let tmp = make_temp_dir("terminal-rpc");
             let state = test_state(&tmp);
 
-            let persisted = vec![WorkspaceEntry {
-                id: "ws-sync".to_string(),
-                name: "Synced Workspace".to_string(),
-                path: tmp.join("workspace").to_string_lossy().to_string(),
-                kind: WorkspaceKind::Main,
-                parent_id: None,
-                worktree: None,
-                settings: WorkspaceSettings::default(),
-            }];
-            write_workspaces(&state.storage_path, &persisted).expect("write workspaces");
+            let err = rpc::handle_rpc_request(
+                &state,
+                "terminal_open",
+                json!({ "workspaceId": "ws-missing", "terminalId": "t-1", "cols": 80, "rows": 24 }),
+                "daemon-test".to_string(),
+            )
+            .await
+            .expect_err("unknown workspace should be rejected");
+            assert_eq!(err.code, ErrorCode::WorkspaceNotFound);
 
-            let listed = state.list_workspaces().await;
-            assert!(
-                listed.iter().any(|workspace| workspace.id == "ws-sync"),
-                "expected daemon list_workspaces to include workspace added on disk"
+            let err = rpc::handle_rpc_request(
+                &state,
+                "terminal_open",
+                json!({ "workspaceId": "ws-1", "terminalId": "t-1" }),
+                "daemon-test".to_string(),
+            )
+            .await
+            .expect_err("missing size should be rejected");
+            assert!(err.message.contains("missing `cols`"), "{err}");
+
+            for (method, params) in [
+                (
+                    "terminal_write",
+                    json!({ "workspaceId": "ws-1", "terminalId": "t-1", "data": "ls\n" }),
+                ),
+                (
+                    "terminal_resize",
+                    json!({ "workspaceId": "ws-1", "terminalId": "t-1", "cols": 100, "rows": 30 }),
+                ),
+            ] {
+                let err =
+                    rpc::handle_rpc_request(&state, method, params, "daemon-test".to_string())
+                        .await
+                        .expect_err("write/resize need an open session");
+                assert_eq!(err.message, "Terminal session not found", "{method}");
+            }
+        });
+    }
+
+    #[test]
+    fn terminal_scrollback_outlives_the_session_until_closed() {
+        run_async_test(async {
+            let tmp = make_temp_dir("terminal-scrollback");
+            let state = test_state(&tmp);
+            let log_dir = terminal_core::scrollback_dir(&tmp).join("ws-1");
+            std::fs::create_dir_all(&log_dir).expect("create log dir");
+            std::fs::write(log_dir.join("t-1.log"), "$ cargo test\nok\n").expect("write log");
+
+            let listed = rpc::handle_rpc_request(
+                &state,
+                "terminal_list",
+                json!({ "workspaceId": "ws-1" }),
+                "daemon-test".to_string(),
+            )
+            .await
+            .expect("terminal_list");
+            assert_eq!(listed, json!([{ "terminalId": "t-1", "running": false }]));
+
+            let attached = rpc::handle_rpc_request(
+                &state,
+                "terminal_attach",
+                json!({ "workspaceId": "ws-1", "terminalId": "t-1" }),
+                "daemon-test".to_string(),
+            )
+            .await
+            .expect("terminal_attach");
+            assert_eq!(
+                attached,
+                json!({ "running": false, "scrollback": "$ cargo test\nok\n" })
             );
 
-            let _ = std::fs::remove_dir_all(&tmp);
+            let closed = rpc::handle_rpc_request(
+                &state,
+                "terminal_close",
+                json!({ "workspaceId": "ws-1", "terminalId": "t-1" }),
+                "daemon-test".to_string(),
+            )
+            .await
+            .expect("closing saved scrollback succeeds");
+            assert_eq!(closed, json!({ "ok": true }));
+            assert!(!log_dir.join("t-1.log").exists());
+            let listed = rpc::handle_rpc_request(
+                &state,
+                "terminal_list",
+                json!({ "workspaceId": "ws-1" }),
+                "daemon-test".to_string(),
+            )
+            .await
+            .expect("terminal_list");
+            assert_eq!(listed, json!([]));
         });
     }
 
     #[test]
-    fn list_workspaces_sync_prunes_stale_sessions() {
+    fn removing_a_workspace_deletes_its_terminal_scrollback() {
         run_async_test(async {
-            let tmp = make_temp_dir("list-workspaces-sync-prune");
+            let tmp = make_temp_dir("terminal-scrollback-remove");
             let state = test_state(&tmp);
-            let keep_path = tmp.join("workspace-keep");
-            let stale_path = tmp.join("workspace-stale");
+            insert_workspace(&state, "ws-1", &tmp.to_string_lossy()).await;
+            let log_dir = terminal_core::scrollback_dir(&tmp).join("ws-1");
+            std::fs::create_dir_all(&log_dir).expect("create log dir");
+            std::fs::write(log_dir.join("t-1.log"), "$ ls\n").expect("write log");
 
-            let persisted = vec![make_workspace_entry(
-                "ws-keep",
-                &keep_path.to_string_lossy(),
-            )];
-            write_workspaces(&state.storage_path, &persisted).expect("write workspaces");
+            rpc::handle_rpc_request(
+                &state,
+                "remove_workspace",
+                json!({ "id": "ws-1" }),
+                "daemon-test".to_string(),
+            )
+            .await
+            .expect("remove_workspace");
 
-            let keep_session = make_session(make_workspace_entry(
-                "ws-keep",
-                &keep_path.to_string_lossy(),
-            ));
-            let stale_session = make_session(make_workspace_entry(
-                "ws-stale",
-                &stale_path.to_string_lossy(),
-            ));
-            {
-                let mut sessions = state.sessions.lock().await;
-                sessions.insert("ws-keep".to_string(), keep_session);
-                sessions.insert("ws-stale".to_string(), stale_session.clone());
-            }
+            assert!(!log_dir.exists());
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
 
-            let listed = state.list_workspaces().await;
-            assert!(
-                listed.iter().any(|workspace| workspace.id == "ws-keep"),
-                "expected daemon list_workspaces to include persisted workspace"
-            );
+    #[test]
+    fn rpc_describe_serves_catalog_and_params_are_checked_against_it() {
+        run_async_test(async {
+            let tmp = make_temp_dir("rpc-describe");
+            let state = test_state(&tmp);
 
-            {
-                let sessions = state.sessions.lock().await;
-                assert!(
-                    sessions.contains_key("ws-keep"),
-                    "expected connected persisted workspace session to remain"
-                );
-                assert!(
-                    !sessions.contains_key("ws-stale"),
-                    "expected stale session to be removed"
-                );
-            }
+            let catalog = rpc::handle_rpc_request(
+                &state,
+                "rpc_describe",
+                Value::Null,
+                "daemon-test".to_string(),
+            )
+            .await
+            .expect("rpc_describe should succeed");
+            assert_eq!(
+                catalog.get("protocolVersion").and_then(Value::as_u64),
+                Some(u64::from(shared::rpc_schema::PROTOCOL_VERSION))
+            );
+            let send = catalog["methods"]
+                .as_array()
+                .and_then(|methods| {
+                    methods
+                        .iter()
+                        .find(|method| method["name"] == "send_user_message")
+                })
+                .expect("send_user_message in catalog");
+            assert_eq!(send["retryAfterDisconnect"], json!(false));
+            assert!(send["params"]
+                .as_array()
+                .expect("params")
+                .contains(&json!({ "name": "text", "type": "string", "required": true })));
 
-            let stale_session_exited = tokio::time::timeout(Duration::from_secs(2), async {
+            let err = rpc::handle_rpc_request(
+                &state,
+                "resume_thread",
+                json!({ "workspaceId": "ws-1" }),
+                "daemon-test".to_string(),
+            )
+            .await
+            .expect_err("missing threadId should be rejected");
+            assert_eq!(err.code, ErrorCode::InvalidParams);
+            assert_eq!(
+                err.message,
+                "invalid params for `resume_thread`: missing `threadId` (string)"
+            );
+
+            let err = rpc::handle_rpc_request(
+                &state,
+                "thread_from_the_future",
+                json!({}),
+                "daemon-test".to_string(),
+            )
+            .await
+            .expect_err("unknown method should be rejected");
+            assert_eq!(err.code, ErrorCode::UnknownMethod);
+            assert!(err
+                .message
+                .starts_with("unknown method: thread_from_the_future"));
+            assert!(err.message.contains("daemon protocol v"));
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
+
+    #[test]
+    fn daemon_logs_tail_returns_recent_records_for_a_workspace() {
+        run_async_test(async {
+            let tmp = make_temp_dir("daemon-logs-tail");
+            let state = test_state(&tmp);
+            let _subscriber = tracing::subscriber::set_default(
+                tracing_subscriber::registry().with(logging_core::RecentLayer),
+            );
+            tracing::warn!(workspace_id = "ws-logs", "app-server said hello");
+            tracing::info!("unrelated record");
+
+            let tail = rpc::handle_rpc_request(
+                &state,
+                "daemon_logs_tail",
+                json!({ "workspaceId": "ws-logs", "level": "warn" }),
+                "daemon-test".to_string(),
+            )
+            .await
+            .expect("daemon_logs_tail should succeed");
+            let entries = tail["entries"].as_array().expect("entries");
+            assert_eq!(entries.len(), 1);
+            assert_eq!(entries[0]["message"], json!("app-server said hello"));
+            assert_eq!(entries[0]["level"], json!("warn"));
+            assert_eq!(entries[0]["target"], json!("codex_monitor_daemon::tests"));
+
+            let err = rpc::handle_rpc_request(
+                &state,
+                "daemon_logs_tail",
+                json!({ "level": "loud" }),
+                "daemon-test".to_string(),
+            )
+            .await
+            .expect_err("unknown levels are rejected");
+            assert!(err.message.contains("invalid log level"));
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
+
+    #[test]
+    fn daemon_metrics_reports_counters_and_in_flight_limit() {
+        run_async_test(async {
+            let tmp = make_temp_dir("daemon-metrics");
+            let state = test_state(&tmp);
+            let _client = state.metrics.client_connected();
+            state
+                .metrics
+                .record_rpc("ping", std::time::Duration::from_millis(1), true);
+            state.metrics.record_event_lag(3);
+
+            let report = rpc::handle_rpc_request(
+                &state,
+                "daemon_metrics",
+                Value::Null,
+                "daemon-test".to_string(),
+            )
+            .await
+            .expect("daemon_metrics should succeed");
+            assert_eq!(report["connectedClients"], json!(1));
+            assert_eq!(
+                report["rpc"]["inFlightLimitPerConnection"],
+                json!(MAX_IN_FLIGHT_RPC_PER_CONNECTION)
+            );
+            assert_eq!(report["rpc"]["methods"]["ping"]["count"], json!(1));
+            assert_eq!(report["events"]["lagDropped"], json!(3));
+            assert_eq!(report["push"], json!({ "attempted": 0, "failed": 0 }));
+            assert_eq!(report["appServer"]["liveSessions"], json!(0));
+        });
+    }
+
+    #[test]
+    fn daemon_shutdown_drains_and_saves_restart_state() {
+        use crate::auth_tokens::TokenRole;
+        use rpc::scope::ClientScope;
+
+        run_async_test(async {
+            let tmp = make_temp_dir("daemon-shutdown");
+            let state = test_state(&tmp);
+            insert_workspace(&state, "ws-1", tmp.to_string_lossy().as_ref()).await;
+            let mut events = state.event_sink.tx.subscribe();
+            let admin = ClientScope {
+                token_id: None,
+                role: TokenRole::Admin,
+                workspace_ids: None,
+            };
+
+            let ack = rpc::handle_scoped_rpc_request(
+                &state,
+                &admin,
+                "daemon_shutdown",
+                json!({ "deadlineSecs": 1, "restart": true }),
+                "daemon-test".to_string(),
+            )
+            .await
+            .expect("daemon_shutdown should succeed");
+            assert_eq!(ack["deadlineSecs"], json!(1));
+            assert_eq!(ack["runningTurns"], json!([]));
+            let err = rpc::handle_rpc_request(
+                &state,
+                "daemon_shutdown",
+                json!({ "deadlineSecs": 60, "restart": false }),
+                "daemon-test".to_string(),
+            )
+            .await
+            .expect_err("a shutdown is already under way");
+            assert_eq!(err.code, ErrorCode::ShuttingDown);
+            assert_eq!(state.shutdown.requested().await.deadline.as_secs(), 1);
+
+            let ping = rpc::handle_scoped_rpc_request(
+                &state,
+                &admin,
+                "ping",
+                Value::Null,
+                "daemon-test".to_string(),
+            )
+            .await;
+            assert!(ping.is_ok(), "read-only methods are still served");
+            let err = rpc::handle_scoped_rpc_request(
+                &state,
+                &admin,
+                "connect_workspace",
+                json!({ "id": "ws-1" }),
+                "daemon-test".to_string(),
+            )
+            .await
+            .expect_err("draining daemon refuses new work");
+            assert_eq!(err.code, ErrorCode::ShuttingDown);
+
+            let request = state.shutdown.requested().await;
+            shutdown::drain(&state, request).await;
+            let event = events.recv().await.expect("shutting down event");
+            let DaemonEvent::AppServer(event) = event.event else {
+                panic!("expected an app-server event");
+            };
+            assert_eq!(event.workspace_id, "ws-1");
+            assert_eq!(event.message["method"], json!("daemon/shuttingDown"));
+            assert_eq!(event.message["params"]["restart"], json!(true));
+            assert!(tmp.join("restart_state.json").exists());
+            assert!(tmp.join("push_notifications.json").exists());
+
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
+
+    #[test]
+    fn list_workspaces_syncs_from_storage_file() {
+        run_async_test(async {
+            let tmp = make_temp_dir("list-workspaces-sync");
+            let state = test_state(&tmp);
+
+            let persisted = vec![WorkspaceEntry {
+                id: "ws-sync".to_string(),
+                name: "Synced Workspace".to_string(),
+                path: tmp.join("workspace").to_string_lossy().to_string(),
+                kind: WorkspaceKind::Main,
+                parent_id: None,
+                worktree: None,
+                settings: WorkspaceSettings::default(),
+            }];
+            write_workspaces(&state.storage_path, &persisted).expect("write workspaces");
+
+            let listed = state.list_workspaces().await;
+            assert!(
+                listed.iter().any(|workspace| workspace.id == "ws-sync"),
+                "expected daemon list_workspaces to include workspace added on disk"
+            );
+
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
+
+    #[test]
+    fn list_workspaces_sync_prunes_stale_sessions() {
+        run_async_test(async {
+            let tmp = make_temp_dir("list-workspaces-sync-prune");
+            let state = test_state(&tmp);
+            let keep_path = tmp.join("workspace-keep");
+            let stale_path = tmp.join("workspace-stale");
+
+            let persisted = vec![make_workspace_entry(
+                "ws-keep",
+                &keep_path.to_string_lossy(),
+            )];
+            write_workspaces(&state.storage_path, &persisted).expect("write workspaces");
+
+            let keep_session = make_session(make_workspace_entry(
+                "ws-keep",
+                &keep_path.to_string_lossy(),
+            ));
+            let stale_session = make_session(make_workspace_entry(
+                "ws-stale",
+                &stale_path.to_string_lossy(),
+            ));
+            {
+                let mut sessions = state.sessions.lock().await;
+                sessions.insert("ws-keep".to_string(), keep_session);
+                sessions.insert("ws-stale".to_string(), stale_session.clone());
+            }
+
+            let listed = state.list_workspaces().await;
+            assert!(
+                listed.iter().any(|workspace| workspace.id == "ws-keep"),
+                "expected daemon list_workspaces to include persisted workspace"
+            );
+
+            {
+                let sessions = state.sessions.lock().await;
+                assert!(
+                    sessions.contains_key("ws-keep"),
+                    "expected connected persisted workspace session to remain"
+                );
+                assert!(
+                    !sessions.contains_key("ws-stale"),
+                    "expected stale session to be removed"
+                );
+            }
+
+            let stale_session_exited = tokio::time::timeout(Duration::from_secs(2), async {
                 loop {
                     let exited = stale_session
                         .child
@@ -2003,6 +2720,949 @@ mod tests {
             let _ = std::fs::remove_dir_all(&tmp);
         });
     }
+
+    async fn tls_ping_roundtrip(pin_server_cert: bool) -> Result<Value, String> {
+        use crate::shared::tls_core::{build_client_config, server_name_for_host};
+
+        let tmp = make_temp_dir("tls-roundtrip");
+        let tls_config = tls::DaemonTlsConfig::in_data_dir(&tmp);
+        let loaded = tls::load_tls(&tls_config, "127.0.0.1:0".parse().expect("addr"))?;
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("local addr");
+        let config = Arc::new(DaemonConfig {
+            listen: addr,
+            token: Some("secret".to_string()),
+            data_dir: tmp.clone(),
+            tls: Some(tls_config),
+            ws_listen: None,
+            metrics_listen: None,
+            ws_path: DEFAULT_WS_PATH.to_string(),
+        });
+        let state = Arc::new(test_state(&tmp));
+        let (events, _events_rx) = broadcast::channel::<SequencedEvent>(8);
+        let acceptor = loaded.acceptor;
+        let server = tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.expect("accept");
+            if let Ok(stream) = acceptor.accept(socket).await {
+                transport::handle_client(stream, config, state, events).await;
+            }
+        });
+
+        let cert_fingerprint = if pin_server_cert {
+            loaded.fingerprint.clone()
+        } else {
+            "00".repeat(32)
+        };
+        let client_config = build_client_config(Some(&cert_fingerprint))?;
+        let connector = tokio_rustls::TlsConnector::from(Arc::new(client_config));
+        let socket = tokio::net::TcpStream::connect(addr)
+            .await
+            .map_err(|err| err.to_string())?;
+        let result = async {
+            let stream = connector
+                .connect(server_name_for_host("localhost")?, socket)
+                .await
+                .map_err(|err| err.to_string())?;
+            let (reader, mut writer) = tokio::io::split(stream);
+            let mut lines = BufReader::new(reader).lines();
+            for request in [
+                json!({ "id": 1, "method": "auth", "params": { "token": "secret" } }),
+                json!({ "id": 2, "method": "ping" }),
+            ] {
+                writer
+                    .write_all(format!("{request}\n").as_bytes())
+                    .await
+                    .map_err(|err| err.to_string())?;
+                writer.flush().await.map_err(|err| err.to_string())?;
+            }
+            let mut last = Value::Null;
+            for _ in 0..2 {
+                let line = lines
+                    .next_line()
+                    .await
+                    .map_err(|err| err.to_string())?
+                    .ok_or("connection closed")?;
+                last = serde_json::from_str(&line).map_err(|err| err.to_string())?;
+            }
+            Ok(last)
+        }
+        .await;
+
+        server.abort();
+        let _ = std::fs::remove_dir_all(&tmp);
+        result
+    }
+
+    #[test]
+    fn tls_generates_self_signed_cert_once() {
+        let tmp = make_temp_dir("tls-generate");
+        let tls_config = tls::DaemonTlsConfig::in_data_dir(&tmp);
+        let listen = "127.0.0.1:4732".parse().expect("addr");
+
+        let first = tls::load_tls(&tls_config, listen).expect("generate tls");
+        assert!(first.generated);
+        let second = tls::load_tls(&tls_config, listen).expect("reload tls");
+        assert!(!second.generated);
+        assert_eq!(first.fingerprint, second.fingerprint);
+
+        std::fs::remove_file(&tls_config.key_path).expect("remove key");
+        let err = match tls::load_tls(&tls_config, listen) {
+            Ok(_) => panic!("expected missing key error"),
+            Err(err) => err,
+        };
+        assert!(err.contains("TLS key not found"), "unexpected error: {err}");
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn tls_client_with_pinned_fingerprint_reaches_rpc() {
+        run_async_test(async {
+            let response = tls_ping_roundtrip(true).await.expect("tls round trip");
+            assert_eq!(response["id"], 2);
+            assert_eq!(response["result"]["ok"], true);
+        });
+    }
+
+    #[test]
+    fn tls_client_rejects_mismatched_fingerprint() {
+        run_async_test(async {
+            let err = tls_ping_roundtrip(false)
+                .await
+                .expect_err("mismatched pin should fail");
+            assert!(
+                err.contains("fingerprint mismatch"),
+                "unexpected error: {err}"
+            );
+        });
+    }
+
+    async fn spawn_websocket_listener(tmp: &std::path::Path, path: &str) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("local addr");
+        let config = Arc::new(DaemonConfig {
+            listen: addr,
+            token: Some("secret".to_string()),
+            data_dir: tmp.to_path_buf(),
+            tls: None,
+            ws_listen: Some(addr),
+            metrics_listen: None,
+            ws_path: path.to_string(),
+        });
+        let state = Arc::new(test_state(tmp));
+        let (events, _events_rx) = broadcast::channel::<SequencedEvent>(8);
+        tokio::spawn(transport::run_listener(
+            listener,
+            transport::ConnectionProtocol::WebSocket {
+                path: path.to_string(),
+            },
+            None,
+            config,
+            state,
+            events,
+        ));
+        addr
+    }
+
+    #[test]
+    fn websocket_listener_serves_rpc_on_configured_path() {
+        use futures_util::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message;
+
+        run_async_test(async {
+            let tmp = make_temp_dir("ws-rpc");
+            let addr = spawn_websocket_listener(&tmp, "/rpc").await;
+
+            let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{addr}/rpc"))
+                .await
+                .expect("connect websocket");
+            for request in [
+                json!({ "id": 1, "method": "ping" }),
+                json!({ "id": 2, "method": "auth", "params": { "token": "secret" } }),
+                json!({ "id": 3, "method": "ping" }),
+            ] {
+                socket
+                    .send(Message::Text(request.to_string()))
+                    .await
+                    .expect("send");
+            }
+            let mut responses = Vec::new();
+            while responses.len() < 3 {
+                let message = socket.next().await.expect("frame").expect("message");
+                if let Message::Text(text) = message {
+                    responses.push(serde_json::from_str::<Value>(&text).expect("json"));
+                }
+            }
+            assert_eq!(responses[0]["error"]["message"], "unauthorized");
+            assert_eq!(
+                responses[0]["error"]["code"],
+                json!(ErrorCode::Unauthorized.as_i64())
+            );
+            assert_eq!(responses[1]["result"]["ok"], true);
+            assert_eq!(responses[2]["id"], 3);
+            assert_eq!(responses[2]["result"]["ok"], true);
+
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
+
+    #[test]
+    fn websocket_listener_rejects_other_paths() {
+        run_async_test(async {
+            let tmp = make_temp_dir("ws-path");
+            let addr = spawn_websocket_listener(&tmp, "/rpc").await;
+
+            let err = tokio_tungstenite::connect_async(format!("ws://{addr}/other"))
+                .await
+                .expect_err("unexpected upgrade");
+            assert!(err.to_string().contains("404"), "unexpected error: {err}");
+
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
+
+    #[test]
+    fn websocket_path_is_normalized() {
+        assert_eq!(websocket::normalize_ws_path("rpc").as_deref(), Ok("/rpc"));
+        assert_eq!(
+            websocket::normalize_ws_path(" /daemon/ws ").as_deref(),
+            Ok("/daemon/ws")
+        );
+        assert!(websocket::normalize_ws_path("  ").is_err());
+        assert!(websocket::normalize_ws_path("/rpc?x=1").is_err());
+    }
+
+    #[test]
+    fn scoped_rpc_enforces_role_and_workspace_allowlist() {
+        use crate::auth_tokens::TokenRole;
+        use rpc::scope::ClientScope;
+
+        run_async_test(async {
+            let tmp = make_temp_dir("scoped-rpc");
+            let state = test_state(&tmp);
+            let client_version = "daemon-test".to_string();
+            let observer = ClientScope {
+                token_id: None,
+                role: TokenRole::Observer,
+                workspace_ids: None,
+            };
+            let operator_on_a = ClientScope {
+                token_id: None,
+                role: TokenRole::Operator,
+                workspace_ids: Some(HashSet::from(["ws-a".to_string()])),
+            };
+
+            let err = rpc::handle_scoped_rpc_request(
+                &state,
+                &observer,
+                "send_user_message",
+                json!({ "workspaceId": "ws-a", "threadId": "t", "text": "hi" }),
+                client_version.clone(),
+            )
+            .await
+            .expect_err("observer cannot send messages");
+            assert_eq!(err.code, ErrorCode::Forbidden);
+            assert_eq!(err.data, Some(json!({ "requiredRole": "operator" })));
+            assert!(err.message.contains("requires the operator role"), "{err}");
+
+            let err = rpc::handle_scoped_rpc_request(
+                &state,
+                &operator_on_a,
+                "daemon_shutdown",
+                Value::Null,
+                client_version.clone(),
+            )
+            .await
+            .expect_err("operator cannot shut down the daemon");
+            assert!(err.message.contains("requires the admin role"), "{err}");
+
+            let err = rpc::handle_scoped_rpc_request(
+                &state,
+                &operator_on_a,
+                "get_git_status",
+                json!({ "workspaceId": "ws-b" }),
+                client_version.clone(),
+            )
+            .await
+            .expect_err("workspace outside allowlist");
+            assert!(err.message.contains("outside this token's scope"), "{err}");
+
+            let err = rpc::handle_scoped_rpc_request(
+                &state,
+                &operator_on_a,
+                "prompts_create",
+                json!({ "scope": "global", "name": "p", "content": "" }),
+                client_version.clone(),
+            )
+            .await
+            .expect_err("workspace-scoped token cannot change global state");
+            assert!(err.message.contains("workspace-scoped"), "{err}");
+
+            let persisted = ["ws-a", "ws-b"]
+                .into_iter()
+                .map(|id| make_workspace_entry(id, &tmp.join(id).to_string_lossy()))
+                .collect::<Vec<_>>();
+            write_workspaces(&state.storage_path, &persisted).expect("write workspaces");
+            let listed = rpc::handle_scoped_rpc_request(
+                &state,
+                &operator_on_a,
+                "list_workspaces",
+                Value::Null,
+                client_version,
+            )
+            .await
+            .expect("list workspaces");
+            let ids = listed
+                .as_array()
+                .expect("array")
+                .iter()
+                .filter_map(|entry| entry["id"].as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(ids, vec!["ws-a"]);
+
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
+
+    #[test]
+    fn named_token_connection_is_scoped_and_closed_on_revoke() {
+        use crate::auth_tokens::{
+            auth_tokens_path, create_auth_token, revoke_auth_token, TokenRole,
+        };
+
+        run_async_test(async {
+            let tmp = make_temp_dir("named-token");
+            let tokens_path = auth_tokens_path(&tmp);
+            let (record, secret) =
+                create_auth_token(&tokens_path, "phone", TokenRole::Observer, None)
+                    .expect("create token");
+            let config = Arc::new(DaemonConfig {
+                listen: "127.0.0.1:0".parse().expect("addr"),
+                token: Some("shared".to_string()),
+                data_dir: tmp.clone(),
+                tls: None,
+                ws_listen: None,
+                metrics_listen: None,
+                ws_path: DEFAULT_WS_PATH.to_string(),
+            });
+            let state = Arc::new(test_state(&tmp));
+            let (events, _events_rx) = broadcast::channel::<SequencedEvent>(8);
+            let (client, server) = tokio::io::duplex(64 * 1024);
+            let server_task = tokio::spawn(transport::handle_client(server, config, state, events));
+
+            let (reader, mut writer) = tokio::io::split(client);
+            let mut lines = BufReader::new(reader).lines();
+            let mut responses = Vec::new();
+            for request in [
+                json!({ "id": 1, "method": "auth", "params": { "token": secret } }),
+                json!({ "id": 2, "method": "ping" }),
+                json!({ "id": 3, "method": "daemon_shutdown" }),
+            ] {
+                writer
+                    .write_all(format!("{request}\n").as_bytes())
+                    .await
+                    .expect("write");
+                let line = lines.next_line().await.expect("read").expect("line");
+                responses.push(serde_json::from_str::<Value>(&line).expect("json"));
+            }
+            assert_eq!(responses[0]["result"]["role"], "observer");
+            assert_eq!(responses[1]["result"]["ok"], true);
+            assert!(responses[2]["error"]["message"]
+                .as_str()
+                .is_some_and(|message| message.contains("requires the admin role")));
+
+            revoke_auth_token(&tokens_path, &record.id).expect("revoke token");
+            writer
+                .write_all(b"{\"id\":4,\"method\":\"ping\"}\n")
+                .await
+                .expect("write");
+            let line = lines.next_line().await.expect("read").expect("line");
+            let revoked: Value = serde_json::from_str(&line).expect("json");
+            assert_eq!(revoked["error"]["message"], "token revoked");
+            tokio::time::timeout(Duration::from_secs(5), server_task)
+                .await
+                .expect("connection closes after revoke")
+                .expect("server task");
+
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
+
+    #[test]
+    fn quiet_connection_stops_streaming_once_its_token_is_revoked() {
+        use crate::auth_tokens::{
+            auth_tokens_path, create_auth_token, revoke_auth_token, TokenRole,
+        };
+
+        run_async_test(async {
+            let tmp = make_temp_dir("named-token-events");
+            let tokens_path = auth_tokens_path(&tmp);
+            let (record, secret) =
+                create_auth_token(&tokens_path, "phone", TokenRole::Observer, None)
+                    .expect("create token");
+            let config = Arc::new(DaemonConfig {
+                listen: "127.0.0.1:0".parse().expect("addr"),
+                token: Some("shared".to_string()),
+                data_dir: tmp.clone(),
+                tls: None,
+                ws_listen: None,
+                metrics_listen: None,
+                ws_path: DEFAULT_WS_PATH.to_string(),
+            });
+            let state = Arc::new(test_state(&tmp));
+            let (client, server) = tokio::io::duplex(64 * 1024);
+            let server_task = tokio::spawn(transport::handle_client(
+                server,
+                config,
+                Arc::clone(&state),
+                state.event_sink.tx.clone(),
+            ));
+
+            let (reader, mut writer) = tokio::io::split(client);
+            let mut lines = BufReader::new(reader).lines();
+            let auth = json!({ "id": 1, "method": "auth", "params": { "token": secret } });
+            writer
+                .write_all(format!("{auth}\n").as_bytes())
+                .await
+                .expect("write");
+            assert_eq!(read_json_line(&mut lines).await["result"]["ok"], true);
+
+            revoke_auth_token(&tokens_path, &record.id).expect("revoke token");
+            tokio::time::sleep(Duration::from_millis(1100)).await;
+            state
+                .event_sink
+                .emit_app_server_event(app_server_event("ws-1", "turn/started"));
+            tokio::time::timeout(Duration::from_secs(5), server_task)
+                .await
+                .expect("connection closes after revoke")
+                .expect("server task");
+            assert!(lines.next_line().await.expect("read").is_none());
+
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
+
+    async fn read_json_line<R: AsyncRead + Unpin>(
+        lines: &mut tokio::io::Lines<BufReader<R>>,
+    ) -> Value {
+        let line = lines.next_line().await.expect("read").expect("line");
+        serde_json::from_str(&line).expect("json")
+    }
+
+    fn app_server_event(workspace_id: &str, method: &str) -> AppServerEvent {
+        AppServerEvent {
+            workspace_id: workspace_id.to_string(),
+            message: json!({ "method": method }),
+        }
+    }
+
+    #[test]
+    fn event_replay_buffer_keeps_recent_events_per_workspace() {
+        let replay = EventReplayBuffer::new(2);
+        let (tx, _rx) = broadcast::channel::<SequencedEvent>(8);
+        for (workspace_id, method) in [
+            ("ws-a", "a1"),
+            ("ws-b", "b1"),
+            ("ws-a", "a2"),
+            ("ws-a", "a3"),
+        ] {
+            replay.publish(
+                DaemonEvent::AppServer(app_server_event(workspace_id, method)),
+                &tx,
+            );
+        }
+
+        let snapshot = replay.replay_since(1);
+        let seqs = snapshot
+            .events
+            .iter()
+            .map(|event| event.seq)
+            .collect::<Vec<_>>();
+        assert_eq!(seqs, vec![2, 3, 4]);
+        assert_eq!(snapshot.last_seq, 4);
+        assert!(!snapshot.truncated);
+
+        let snapshot = replay.replay_since(0);
+        assert!(snapshot.truncated, "a1 was evicted from ws-a");
+        assert!(replay.replay_since(9).truncated, "seq from a previous run");
+        assert!(replay.live_snapshot().events.is_empty());
+
+        replay.forget_workspace("ws-a");
+        let snapshot = replay.replay_since(0);
+        let seqs = snapshot
+            .events
+            .iter()
+            .map(|event| event.seq)
+            .collect::<Vec<_>>();
+        assert_eq!(seqs, vec![2]);
+        assert!(!snapshot.truncated);
+        assert_eq!(snapshot.last_seq, 4);
+    }
+
+    #[test]
+    fn auth_with_resume_from_seq_replays_missed_events_before_live_ones() {
+        run_async_test(async {
+            let tmp = make_temp_dir("event-replay");
+            let state = Arc::new(test_state(&tmp));
+            let events = state.event_sink.tx.clone();
+            for method in ["turn/started", "item/delta", "turn/completed"] {
+                state
+                    .event_sink
+                    .emit_app_server_event(app_server_event("ws-1", method));
+            }
+            let config = Arc::new(DaemonConfig {
+                listen: "127.0.0.1:0".parse().expect("addr"),
+                token: Some("secret".to_string()),
+                data_dir: tmp.clone(),
+                tls: None,
+                ws_listen: None,
+                metrics_listen: None,
+                ws_path: DEFAULT_WS_PATH.to_string(),
+            });
+            let stream_id = state.event_sink.replay.stream_id().to_string();
+            let (client, server) = tokio::io::duplex(64 * 1024);
+            tokio::spawn(transport::handle_client(
+                server,
+                config,
+                Arc::clone(&state),
+                events,
+            ));
+
+            let (reader, mut writer) = tokio::io::split(client);
+            let mut lines = BufReader::new(reader).lines();
+            let auth = json!({
+                "id": 1,
+                "method": "auth",
+                "params": { "token": "secret", "resumeFromSeq": 1, "streamId": stream_id },
+            });
+            writer
+                .write_all(format!("{auth}\n").as_bytes())
+                .await
+                .expect("write");
+            let response = read_json_line(&mut lines).await;
+            assert_eq!(response["result"]["resumed"], true);
+            assert_eq!(response["result"]["lastSeq"], 3);
+            assert_eq!(response["result"]["replayTruncated"], false);
+            for (seq, method) in [(2, "item/delta"), (3, "turn/completed")] {
+                let event = read_json_line(&mut lines).await;
+                assert_eq!(event["method"], "app-server-event");
+                assert_eq!(event["seq"], seq);
+                assert_eq!(event["params"]["message"]["method"], method);
+            }
+
+            state
+                .event_sink
+                .emit_app_server_event(app_server_event("ws-1", "turn/started"));
+            let live = read_json_line(&mut lines).await;
+            assert_eq!(live["seq"], 4);
+
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
+
+    #[test]
+    fn auth_without_a_token_still_resumes_missed_events() {
+        run_async_test(async {
+            let tmp = make_temp_dir("event-replay-tokenless");
+            let state = Arc::new(test_state(&tmp));
+            let events = state.event_sink.tx.clone();
+            for method in ["turn/started", "item/delta", "turn/completed"] {
+                state
+                    .event_sink
+                    .emit_app_server_event(app_server_event("ws-1", method));
+            }
+            let config = Arc::new(DaemonConfig {
+                listen: "127.0.0.1:0".parse().expect("addr"),
+                token: None,
+                data_dir: tmp.clone(),
+                tls: None,
+                ws_listen: None,
+                metrics_listen: None,
+                ws_path: DEFAULT_WS_PATH.to_string(),
+            });
+            let stream_id = state.event_sink.replay.stream_id().to_string();
+            let (client, server) = tokio::io::duplex(64 * 1024);
+            tokio::spawn(transport::handle_client(
+                server,
+                config,
+                Arc::clone(&state),
+                events,
+            ));
+            let (reader, mut writer) = tokio::io::split(client);
+            let mut lines = BufReader::new(reader).lines();
+
+            // Live events flow before any auth.
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            state
+                .event_sink
+                .emit_app_server_event(app_server_event("ws-1", "turn/started"));
+            let live = read_json_line(&mut lines).await;
+            assert_eq!(live["seq"], 4);
+
+            let auth = json!({
+                "id": 1,
+                "method": "auth",
+                "params": { "resumeFromSeq": 1, "streamId": stream_id },
+            });
+            writer
+                .write_all(format!("{auth}\n").as_bytes())
+                .await
+                .expect("write");
+            for seq in [2, 3] {
+                let event = read_json_line(&mut lines).await;
+                assert_eq!(event["seq"], seq);
+            }
+            let response = read_json_line(&mut lines).await;
+            assert_eq!(response["result"]["resumed"], true);
+            assert_eq!(response["result"]["lastSeq"], 4);
+
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
+
+    #[test]
+    fn event_subscription_method_globs() {
+        use event_subscription::method_matches;
+
+        assert!(method_matches("turn/completed", "turn/completed"));
+        assert!(!method_matches("turn/completed", "turn/completed/x"));
+        assert!(method_matches("turn/*", "turn/started"));
+        assert!(method_matches(
+            "*requestApproval",
+            "item/commandExecution/requestApproval"
+        ));
+        assert!(method_matches("item/*/delta", "item/agentMessage/delta"));
+        assert!(!method_matches("item/*/delta", "item/started"));
+        assert!(method_matches("*", ""));
+        assert!(!method_matches("a*a", "a"));
+    }
+
+    #[test]
+    fn subscribe_events_filters_forwarded_events() {
+        run_async_test(async {
+            let tmp = make_temp_dir("subscribe-events");
+            let state = Arc::new(test_state(&tmp));
+            let config = Arc::new(DaemonConfig {
+                listen: "127.0.0.1:0".parse().expect("addr"),
+                token: Some("secret".to_string()),
+                data_dir: tmp.clone(),
+                tls: None,
+                ws_listen: None,
+                metrics_listen: None,
+                ws_path: DEFAULT_WS_PATH.to_string(),
+            });
+            let (client, server) = tokio::io::duplex(64 * 1024);
+            tokio::spawn(transport::handle_client(
+                server,
+                config,
+                Arc::clone(&state),
+                state.event_sink.tx.clone(),
+            ));
+
+            let (reader, mut writer) = tokio::io::split(client);
+            let mut lines = BufReader::new(reader).lines();
+            for request in [
+                json!({ "id": 1, "method": "auth", "params": { "token": "secret" } }),
+                json!({
+                    "id": 2,
+                    "method": "subscribe_events",
+                    "params": {
+                        "threadIds": ["thread-1"],
+                        "methods": ["turn/completed", "*requestApproval"],
+                    },
+                }),
+                json!({ "id": 3, "method": "subscribe_events", "params": { "methods": "turn/*" } }),
+            ] {
+                writer
+                    .write_all(format!("{request}\n").as_bytes())
+                    .await
+                    .expect("write");
+            }
+            assert_eq!(read_json_line(&mut lines).await["result"]["ok"], true);
+            let subscribed = read_json_line(&mut lines).await;
+            assert_eq!(subscribed["result"]["threadIds"], json!(["thread-1"]));
+            let rejected = read_json_line(&mut lines).await;
+            assert_eq!(
+                rejected["error"]["message"],
+                "`methods` must be an array of strings"
+            );
+
+            let thread_event = |method: &str, thread_id: &str| AppServerEvent {
+                workspace_id: "ws-1".to_string(),
+                message: json!({ "method": method, "params": { "threadId": thread_id } }),
+            };
+            for event in [
+                thread_event("item/agentMessage/delta", "thread-1"),
+                thread_event("turn/completed", "thread-2"),
+                thread_event("item/commandExecution/requestApproval", "thread-1"),
+                thread_event("turn/completed", "thread-1"),
+            ] {
+                state.event_sink.emit_app_server_event(event);
+            }
+
+            let approval = read_json_line(&mut lines).await;
+            assert_eq!(
+                approval["params"]["message"]["method"],
+                "item/commandExecution/requestApproval"
+            );
+            let completed = read_json_line(&mut lines).await;
+            assert_eq!(completed["params"]["message"]["method"], "turn/completed");
+            assert_eq!(
+                completed["params"]["message"]["params"]["threadId"],
+                "thread-1"
+            );
+
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
+
+    #[test]
+    fn auth_subscription_filters_the_replayed_backlog() {
+        run_async_test(async {
+            let tmp = make_temp_dir("auth-subscription");
+            let state = Arc::new(test_state(&tmp));
+            for (workspace_id, method) in [
+                ("ws-1", "turn/started"),
+                ("ws-2", "item/agentMessage/delta"),
+                ("ws-1", "item/agentMessage/delta"),
+                ("ws-1", "turn/completed"),
+            ] {
+                state
+                    .event_sink
+                    .emit_app_server_event(app_server_event(workspace_id, method));
+            }
+            let config = Arc::new(DaemonConfig {
+                listen: "127.0.0.1:0".parse().expect("addr"),
+                token: Some("secret".to_string()),
+                data_dir: tmp.clone(),
+                tls: None,
+                ws_listen: None,
+                metrics_listen: None,
+                ws_path: DEFAULT_WS_PATH.to_string(),
+            });
+            let stream_id = state.event_sink.replay.stream_id().to_string();
+            let (client, server) = tokio::io::duplex(64 * 1024);
+            tokio::spawn(transport::handle_client(
+                server,
+                config,
+                Arc::clone(&state),
+                state.event_sink.tx.clone(),
+            ));
+
+            let (reader, mut writer) = tokio::io::split(client);
+            let mut lines = BufReader::new(reader).lines();
+            for request in [
+                json!({
+                    "id": 1,
+                    "method": "auth",
+                    "params": { "token": "secret", "subscription": { "methods": "turn/*" } },
+                }),
+                json!({
+                    "id": 2,
+                    "method": "auth",
+                    "params": {
+                        "token": "secret",
+                        "resumeFromSeq": 0,
+                        "streamId": stream_id,
+                        "subscription": { "workspaceIds": ["ws-1"], "methods": ["turn/*"] },
+                    },
+                }),
+            ] {
+                writer
+                    .write_all(format!("{request}\n").as_bytes())
+                    .await
+                    .expect("write");
+            }
+            let rejected = read_json_line(&mut lines).await;
+            assert_eq!(rejected["error"]["code"], json!(-32602));
+            let response = read_json_line(&mut lines).await;
+            assert_eq!(response["result"]["resumed"], true);
+            for seq in [1, 4] {
+                let event = read_json_line(&mut lines).await;
+                assert_eq!(event["seq"], seq);
+            }
+
+            state
+                .event_sink
+                .emit_app_server_event(app_server_event("ws-2", "turn/started"));
+            state
+                .event_sink
+                .emit_app_server_event(app_server_event("ws-1", "turn/started"));
+            let live = read_json_line(&mut lines).await;
+            assert_eq!(live["seq"], 6);
+
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
+
+    #[test]
+    fn protocol_upgrade_switches_line_connection_to_compressed_frames() {
+        use crate::shared::wire_compression_core::{
+            read_message, write_message, FrameDecoder, FrameEncoder, WireCompression,
+        };
+
+        run_async_test(async {
+            let tmp = make_temp_dir("protocol-upgrade");
+            let state = Arc::new(test_state(&tmp));
+            let config = Arc::new(DaemonConfig {
+                listen: "127.0.0.1:0".parse().expect("addr"),
+                token: Some("secret".to_string()),
+                data_dir: tmp.clone(),
+                tls: None,
+                ws_listen: None,
+                metrics_listen: None,
+                ws_path: DEFAULT_WS_PATH.to_string(),
+            });
+            let (client, server) = tokio::io::duplex(64 * 1024);
+            tokio::spawn(transport::handle_client(
+                server,
+                config,
+                Arc::clone(&state),
+                state.event_sink.tx.clone(),
+            ));
+
+            let (reader, mut writer) = tokio::io::split(client);
+            let mut reader = BufReader::new(reader);
+            async fn read_json(
+                reader: &mut BufReader<tokio::io::ReadHalf<tokio::io::DuplexStream>>,
+                decoder: Option<&mut FrameDecoder>,
+            ) -> Value {
+                let message = read_message(reader, decoder)
+                    .await
+                    .expect("read")
+                    .expect("message");
+                serde_json::from_str(&message).expect("json")
+            }
+
+            for request in [
+                json!({ "id": 1, "method": "auth", "params": { "token": "secret" } }),
+                json!({ "id": 2, "method": "daemon_info" }),
+            ] {
+                write_message(&mut writer, None, &request.to_string())
+                    .await
+                    .expect("write");
+            }
+            assert_eq!(read_json(&mut reader, None).await["result"]["ok"], true);
+            let info = read_json(&mut reader, None).await;
+            assert_eq!(info["result"]["compression"], json!(["zstd", "deflate"]));
+
+            let upgrade = json!({ "id": 3, "method": "protocol_upgrade", "params": { "compression": "zstd" } });
+            write_message(&mut writer, None, &upgrade.to_string())
+                .await
+                .expect("write");
+            let upgraded = read_json(&mut reader, None).await;
+            assert_eq!(upgraded["result"]["compression"], "zstd");
+
+            let mut encoder = FrameEncoder::new(WireCompression::Zstd).expect("encoder");
+            let mut decoder = FrameDecoder::new(WireCompression::Zstd).expect("decoder");
+            let ping = json!({ "id": 4, "method": "ping" }).to_string();
+            write_message(&mut writer, Some(&mut encoder), &ping)
+                .await
+                .expect("write");
+            let pong = read_json(&mut reader, Some(&mut decoder)).await;
+            assert_eq!(pong["id"], 4);
+            assert_eq!(pong["result"]["ok"], true);
+
+            state
+                .event_sink
+                .emit_app_server_event(app_server_event("ws-1", "turn/completed"));
+            let event = read_json(&mut reader, Some(&mut decoder)).await;
+            assert_eq!(event["params"]["message"]["method"], "turn/completed");
+
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
+
+    #[test]
+    fn protocol_upgrade_uses_binary_websocket_frames() {
+        use crate::shared::wire_compression_core::{FrameDecoder, FrameEncoder, WireCompression};
+        use futures_util::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message;
+
+        run_async_test(async {
+            let tmp = make_temp_dir("ws-protocol-upgrade");
+            let addr = spawn_websocket_listener(&tmp, "/rpc").await;
+            let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{addr}/rpc"))
+                .await
+                .expect("connect websocket");
+
+            for request in [
+                json!({ "id": 1, "method": "auth", "params": { "token": "secret" } }),
+                json!({ "id": 2, "method": "protocol_upgrade", "params": { "compression": "deflate" } }),
+            ] {
+                socket
+                    .send(Message::Text(request.to_string()))
+                    .await
+                    .expect("send");
+            }
+            let mut encoder = FrameEncoder::new(WireCompression::Deflate).expect("encoder");
+            let ping = json!({ "id": 3, "method": "ping" }).to_string();
+            socket
+                .send(Message::Binary(
+                    encoder.encode(ping.as_bytes()).expect("encode"),
+                ))
+                .await
+                .expect("send");
+
+            let mut decoder = FrameDecoder::new(WireCompression::Deflate).expect("decoder");
+            let mut responses = Vec::new();
+            while responses.len() < 3 {
+                let text = match socket.next().await.expect("frame").expect("message") {
+                    Message::Text(text) => text,
+                    Message::Binary(bytes) => decoder.decode(&bytes).expect("decode"),
+                    _ => continue,
+                };
+                responses.push(serde_json::from_str::<Value>(&text).expect("json"));
+            }
+            assert_eq!(responses[0]["result"]["ok"], true);
+            assert_eq!(responses[1]["result"]["compression"], "deflate");
+            assert_eq!(responses[2]["id"], 3);
+            assert_eq!(responses[2]["result"]["ok"], true);
+
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
+
+    #[test]
+    fn protocol_upgrade_rejects_unknown_compression_and_closes() {
+        run_async_test(async {
+            let tmp = make_temp_dir("protocol-upgrade-unknown");
+            let state = Arc::new(test_state(&tmp));
+            let config = Arc::new(DaemonConfig {
+                listen: "127.0.0.1:0".parse().expect("addr"),
+                token: None,
+                data_dir: tmp.clone(),
+                tls: None,
+                ws_listen: None,
+                metrics_listen: None,
+                ws_path: DEFAULT_WS_PATH.to_string(),
+            });
+            let (client, server) = tokio::io::duplex(64 * 1024);
+            tokio::spawn(transport::handle_client(
+                server,
+                config,
+                Arc::clone(&state),
+                state.event_sink.tx.clone(),
+            ));
+
+            let (reader, mut writer) = tokio::io::split(client);
+            let mut lines = BufReader::new(reader).lines();
+            let request =
+                json!({ "id": 1, "method": "protocol_upgrade", "params": { "compression": "br" } });
+            writer
+                .write_all(format!("{request}\n").as_bytes())
+                .await
+                .expect("write");
+            let response = read_json_line(&mut lines).await;
+            assert_eq!(
+                response["error"]["message"],
+                "unsupported compression; expected one of: zstd, deflate"
+            );
+            assert!(lines.next_line().await.expect("read").is_none());
+
+            let _ = std::fs::remove_dir_all(&tmp);
+        });
+    }
 }
 
 fn main() {
@@ -2020,14 +3680,53 @@ fn main() {
         .expect("failed to build tokio runtime");
 
     runtime.block_on(async move {
-        let (events_tx, _events_rx) = broadcast::channel::<DaemonEvent>(2048);
+        let (events_tx, _events_rx) = broadcast::channel::<SequencedEvent>(2048);
         let event_sink = DaemonEventSink {
             tx: events_tx.clone(),
+            replay: Arc::new(EventReplayBuffer::new(EVENT_REPLAY_CAPACITY_PER_WORKSPACE)),
         };
         let push_broker = Arc::new(push::PushBroker::load(&config.data_dir));
-        let state = Arc::new(DaemonState::load(&config, event_sink, push_broker));
+        let (session_exits_tx, mut session_exits_rx) = mpsc::unbounded_channel();
+        let state = Arc::new(DaemonState::load(
+            &config,
+            event_sink,
+            session_exits_tx,
+            push_broker,
+        ));
         let config = Arc::new(config);
 
+        {
+            let state_for_supervisor = Arc::clone(&state);
+            tokio::spawn(async move {
+                while let Some(exit) = session_exits_rx.recv().await {
+                    state_for_supervisor.handle_session_exit(exit).await;
+                }
+            });
+        }
+
+        {
+            let state_for_pool = Arc::clone(&state);
+            tokio::spawn(async move {
+                loop {
+                    tokio::time::sleep(workspaces_core::SESSION_POOL_SWEEP_INTERVAL).await;
+                    state_for_pool.enforce_session_pool().await;
+                }
+            });
+        }
+
+        {
+            let push_broker = Arc::clone(&state.push_broker);
+            tokio::spawn(async move {
+                push_broker.run_outbox().await;
+            });
+        }
+        {
+            let push_broker = Arc::clone(&state.push_broker);
+            tokio::spawn(async move {
+                push_broker.run_digest().await;
+            });
+        }
+
         {
             let state_for_push = Arc::clone(&state);
             let mut push_rx = events_tx.subscribe();
@@ -2038,21 +3737,44 @@ fn main() {
                         Err(broadcast::error::RecvError::Lagged(_)) => continue,
                         Err(broadcast::error::RecvError::Closed) => break,
                     };
-                    if let DaemonEvent::AppServer(payload) = event {
+                    if let DaemonEvent::AppServer(payload) = event.event {
                         state_for_push.handle_push_candidate_event(payload).await;
                     }
                 }
             });
         }
 
+        let tls_acceptor = match config.tls.as_ref() {
+            Some(tls_config) => match tls::load_tls(tls_config, config.listen) {
+                Ok(loaded) => {
+                    if loaded.generated {
+                        tracing::info!(
+                            "generated self-signed TLS certificate at {}",
+                            tls_config.cert_path.display()
+                        );
+                    }
+                    tracing::info!(
+                        "TLS certificate SHA-256 fingerprint: {}",
+                        loaded.fingerprint
+                    );
+                    Some(loaded.acceptor)
+                }
+                Err(err) => {
+                    tracing::error!("failed to configure TLS: {err}");
+                    std::process::exit(2);
+                }
+            },
+            None => None,
+        };
+
         let listener = match TcpListener::bind(config.listen).await {
             Ok(listener) => listener,
             Err(err) => {
-                eprintln!("failed to bind {}: {err}", config.listen);
+                tracing::error!("failed to bind {}: {err}", config.listen);
                 std::process::exit(2);
             }
         };
-        eprintln!(
+        tracing::info!(
             "codex-monitor-daemon listening on {} (data dir: {})",
             config.listen,
             state
@@ -2062,18 +3784,74 @@ fn main() {
                 .display()
         );
 
-        loop {
-            match listener.accept().await {
-                Ok((socket, _addr)) => {
-                    let config = Arc::clone(&config);
-                    let state = Arc::clone(&state);
-                    let events = events_tx.clone();
-                    tokio::spawn(async move {
-                        transport::handle_client(socket, config, state, events).await;
-                    });
+        let mut listeners = Vec::new();
+        if let Some(ws_listen) = config.ws_listen {
+            let ws_listener = match TcpListener::bind(ws_listen).await {
+                Ok(listener) => listener,
+                Err(err) => {
+                    tracing::error!("failed to bind {ws_listen}: {err}");
+                    std::process::exit(2);
                 }
-                Err(_) => continue,
+            };
+            tracing::info!(
+                "codex-monitor-daemon serving WebSocket on {ws_listen}{}",
+                config.ws_path
+            );
+            listeners.push(tokio::spawn(transport::run_listener(
+                ws_listener,
+                transport::ConnectionProtocol::WebSocket {
+                    path: config.ws_path.clone(),
+                },
+                tls_acceptor.clone(),
+                Arc::clone(&config),
+                Arc::clone(&state),
+                events_tx.clone(),
+            )));
+        }
+
+        if let Some(metrics_listen) = config.metrics_listen {
+            let metrics_listener = match TcpListener::bind(metrics_listen).await {
+                Ok(listener) => listener,
+                Err(err) => {
+                    tracing::error!("failed to bind {metrics_listen}: {err}");
+                    std::process::exit(2);
+                }
+            };
+            if !metrics_listen.ip().is_loopback() {
+                tracing::warn!("metrics on {metrics_listen} are served without auth");
             }
+            tracing::info!(
+                "codex-monitor-daemon serving metrics on http://{metrics_listen}/metrics"
+            );
+            listeners.push(tokio::spawn(metrics::run_metrics_listener(
+                metrics_listener,
+                Arc::clone(&state),
+            )));
+        }
+
+        listeners.push(tokio::spawn(transport::run_listener(
+            listener,
+            transport::ConnectionProtocol::Lines,
+            tls_acceptor,
+            config,
+            Arc::clone(&state),
+            events_tx,
+        )));
+
+        {
+            let state_for_resume = Arc::clone(&state);
+            tokio::spawn(async move {
+                shutdown::resume_after_restart(&state_for_resume).await;
+            });
+        }
+
+        let request = state.shutdown.requested().await;
+        // Connections already open keep receiving events until the process exits.
+        for listener in listeners {
+            listener.abort();
         }
+        shutdown::drain(&state, request).await;
+        tracing::info!("codex-monitor-daemon stopped");
+        std::process::exit(0);
     });
 }
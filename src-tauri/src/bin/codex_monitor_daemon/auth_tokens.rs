@@ -0,0 +1,216 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::storage::write_private_file;
+
+pub(crate) const AUTH_TOKENS_FILE_NAME: &str = "auth_tokens.json";
+const TOKEN_SECRET_PREFIX: &str = "cmd_";
+
+/// Roles are ordered so that a higher role can call everything a lower one can.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum TokenRole {
+    Observer,
+    Operator,
+    Admin,
+}
+
+impl TokenRole {
+    pub(crate) fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "observer" => Ok(Self::Observer),
+            "operator" => Ok(Self::Operator),
+            "admin" => Ok(Self::Admin),
+            other => Err(format!(
+                "Unknown token role: {other} (expected observer, operator or admin)"
+            )),
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Observer => "observer",
+            Self::Operator => "operator",
+            Self::Admin => "admin",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AuthTokenRecord {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) role: TokenRole,
+    /// `None` grants every workspace; `Some` limits the token to the listed ids.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) workspace_ids: Option<Vec<String>>,
+    pub(crate) token_sha256: String,
+    pub(crate) created_at_ms: i64,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct AuthTokensFile {
+    #[serde(default)]
+    tokens: Vec<AuthTokenRecord>,
+}
+
+pub(crate) fn auth_tokens_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(AUTH_TOKENS_FILE_NAME)
+}
+
+pub(crate) fn hash_token(secret: &str) -> String {
+    Sha256::digest(secret.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+pub(crate) fn read_auth_tokens(path: &Path) -> Result<Vec<AuthTokenRecord>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = fs::read_to_string(path)
+        .map_err(|err| format!("Failed to read {}: {err}", path.display()))?;
+    let file: AuthTokensFile = serde_json::from_str(&data)
+        .map_err(|err| format!("Failed to parse {}: {err}", path.display()))?;
+    Ok(file.tokens)
+}
+
+pub(crate) fn write_auth_tokens(path: &Path, tokens: &[AuthTokenRecord]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    let data = serde_json::to_string_pretty(&AuthTokensFile {
+        tokens: tokens.to_vec(),
+    })
+    .map_err(|err| err.to_string())?;
+    write_private_file(path, data.as_bytes())
+}
+
+/// Adds a token and returns its record together with the plaintext secret.
+/// Only the SHA-256 of the secret is persisted, so it cannot be shown again.
+pub(crate) fn create_auth_token(
+    path: &Path,
+    name: &str,
+    role: TokenRole,
+    workspace_ids: Option<Vec<String>>,
+) -> Result<(AuthTokenRecord, String), String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Token name must not be empty".to_string());
+    }
+    let mut tokens = read_auth_tokens(path)?;
+    if tokens.iter().any(|token| token.name == name) {
+        return Err(format!("A token named `{name}` already exists"));
+    }
+
+    let secret = format!(
+        "{TOKEN_SECRET_PREFIX}{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    );
+    let record = AuthTokenRecord {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: name.to_string(),
+        role,
+        workspace_ids: workspace_ids.map(|ids| {
+            let mut ids: Vec<String> = ids
+                .into_iter()
+                .map(|id| id.trim().to_string())
+                .filter(|id| !id.is_empty())
+                .collect();
+            ids.sort();
+            ids.dedup();
+            ids
+        }),
+        token_sha256: hash_token(&secret),
+        created_at_ms: now_ms(),
+    };
+    tokens.push(record.clone());
+    write_auth_tokens(path, &tokens)?;
+    Ok((record, secret))
+}
+
+/// Removes the token whose id or name matches `id_or_name`.
+pub(crate) fn revoke_auth_token(path: &Path, id_or_name: &str) -> Result<AuthTokenRecord, String> {
+    let needle = id_or_name.trim();
+    let mut tokens = read_auth_tokens(path)?;
+    let index = tokens
+        .iter()
+        .position(|token| token.id == needle || token.name == needle)
+        .ok_or_else(|| format!("No token matches `{needle}`"))?;
+    let removed = tokens.remove(index);
+    write_auth_tokens(path, &tokens)?;
+    Ok(removed)
+}
+
+/// Caches the tokens file and re-reads it whenever its modification time or
+/// size changes, so `daemonctl token` edits apply without a daemon restart.
+pub(crate) struct AuthTokenStore {
+    path: PathBuf,
+    cache: std::sync::Mutex<CachedTokens>,
+}
+
+#[derive(Default)]
+struct CachedTokens {
+    stamp: Option<(SystemTime, u64)>,
+    tokens: Vec<AuthTokenRecord>,
+}
+
+impl AuthTokenStore {
+    pub(crate) fn in_data_dir(data_dir: &Path) -> Self {
+        Self {
+            path: auth_tokens_path(data_dir),
+            cache: std::sync::Mutex::new(CachedTokens::default()),
+        }
+    }
+
+    pub(crate) fn tokens(&self) -> Vec<AuthTokenRecord> {
+        let stamp = fs::metadata(&self.path)
+            .ok()
+            .and_then(|meta| Some((meta.modified().ok()?, meta.len())));
+        let mut cache = self
+            .cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if cache.stamp != stamp {
+            cache.tokens = match read_auth_tokens(&self.path) {
+                Ok(tokens) => tokens,
+                Err(err) => {
+                    // Fail closed: an unreadable file grants nothing.
+                    tracing::warn!("{err}");
+                    Vec::new()
+                }
+            };
+            cache.stamp = stamp;
+        }
+        cache.tokens.clone()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.tokens().is_empty()
+    }
+
+    pub(crate) fn find_by_secret(&self, secret: &str) -> Option<AuthTokenRecord> {
+        let hashed = hash_token(secret);
+        self.tokens()
+            .into_iter()
+            .find(|token| token.token_sha256 == hashed)
+    }
+
+    pub(crate) fn find_by_id(&self, id: &str) -> Option<AuthTokenRecord> {
+        self.tokens().into_iter().find(|token| token.id == id)
+    }
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or(0)
+}
@@ -0,0 +1,129 @@
+use std::collections::{HashMap, VecDeque};
+
+use super::*;
+
+/// Events kept per workspace for clients that reconnect with `resumeFromSeq`.
+pub(super) const EVENT_REPLAY_CAPACITY_PER_WORKSPACE: usize = 1024;
+
+#[derive(Clone)]
+pub(super) struct SequencedEvent {
+    pub(super) seq: u64,
+    pub(super) event: DaemonEvent,
+}
+
+pub(super) struct ReplaySnapshot {
+    pub(super) events: Vec<SequencedEvent>,
+    pub(super) last_seq: u64,
+    /// True when events after the requested seq were already evicted.
+    pub(super) truncated: bool,
+}
+
+/// Assigns daemon-wide sequence numbers to events and keeps the most recent
+/// ones per workspace. Sequence numbers restart with the process, so each
+/// buffer carries a `stream_id` that clients echo back when resuming.
+pub(super) struct EventReplayBuffer {
+    stream_id: String,
+    capacity: usize,
+    inner: std::sync::Mutex<ReplayState>,
+}
+
+#[derive(Default)]
+struct ReplayState {
+    last_seq: u64,
+    workspaces: HashMap<String, WorkspaceRing>,
+}
+
+#[derive(Default)]
+struct WorkspaceRing {
+    events: VecDeque<SequencedEvent>,
+    evicted_through: u64,
+}
+
+impl EventReplayBuffer {
+    pub(super) fn new(capacity: usize) -> Self {
+        Self {
+            stream_id: uuid::Uuid::new_v4().to_string(),
+            capacity: capacity.max(1),
+            inner: std::sync::Mutex::new(ReplayState::default()),
+        }
+    }
+
+    pub(super) fn stream_id(&self) -> &str {
+        &self.stream_id
+    }
+
+    /// Records `event` and broadcasts it. Both happen under one lock so live
+    /// subscribers always observe events in sequence order.
+    pub(super) fn publish(&self, event: DaemonEvent, tx: &broadcast::Sender<SequencedEvent>) {
+        let mut state = self.lock();
+        state.last_seq += 1;
+        let sequenced = SequencedEvent {
+            seq: state.last_seq,
+            event,
+        };
+        let ring = state
+            .workspaces
+            .entry(event_workspace_id(&sequenced.event).to_string())
+            .or_default();
+        ring.events.push_back(sequenced.clone());
+        while ring.events.len() > self.capacity {
+            if let Some(evicted) = ring.events.pop_front() {
+                ring.evicted_through = evicted.seq;
+            }
+        }
+        let _ = tx.send(sequenced);
+    }
+
+    /// Returns the buffered events with a sequence number above `after_seq`,
+    /// oldest first.
+    pub(super) fn replay_since(&self, after_seq: u64) -> ReplaySnapshot {
+        let state = self.lock();
+        let mut events: Vec<SequencedEvent> = state
+            .workspaces
+            .values()
+            .flat_map(|ring| ring.events.iter().filter(|event| event.seq > after_seq))
+            .cloned()
+            .collect();
+        events.sort_by_key(|event| event.seq);
+        let truncated = after_seq > state.last_seq
+            || state
+                .workspaces
+                .values()
+                .any(|ring| ring.evicted_through > after_seq);
+        ReplaySnapshot {
+            events,
+            last_seq: state.last_seq,
+            truncated,
+        }
+    }
+
+    /// Drops the buffered events of a removed workspace. A client resuming
+    /// from before them is not told, since the workspace no longer exists.
+    pub(super) fn forget_workspace(&self, workspace_id: &str) {
+        self.lock().workspaces.remove(workspace_id);
+    }
+
+    /// An empty snapshot positioned at the newest event, for clients that
+    /// only want live events.
+    pub(super) fn live_snapshot(&self) -> ReplaySnapshot {
+        ReplaySnapshot {
+            events: Vec::new(),
+            last_seq: self.lock().last_seq,
+            truncated: false,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ReplayState> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+pub(super) fn event_workspace_id(event: &DaemonEvent) -> &str {
+    match event {
+        DaemonEvent::AppServer(payload) => &payload.workspace_id,
+        DaemonEvent::TerminalOutput(payload) => &payload.workspace_id,
+        DaemonEvent::TerminalExit(payload) => &payload.workspace_id,
+    }
+}
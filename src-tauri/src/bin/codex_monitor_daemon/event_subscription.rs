@@ -0,0 +1,128 @@
+use backend::app_server::extract_thread_id;
+
+use super::*;
+
+/// `subscribe_events` params, and its result with the filter now in effect.
+/// A missing or `null` list matches everything.
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct SubscribeEventsFilter {
+    pub(super) workspace_ids: Option<Vec<String>>,
+    pub(super) thread_ids: Option<Vec<String>>,
+    /// App-server method patterns where `*` matches any run of characters.
+    pub(super) methods: Option<Vec<String>>,
+}
+
+/// Per-connection event filter set through `subscribe_events`. Each `None`
+/// field matches everything, so a fresh connection receives every event.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(super) struct EventSubscription {
+    workspace_ids: Option<HashSet<String>>,
+    thread_ids: Option<HashSet<String>>,
+    methods: Option<Vec<String>>,
+}
+
+impl EventSubscription {
+    pub(super) fn from_params(params: &Value) -> Result<Self, String> {
+        if !matches!(params, Value::Null | Value::Object(_)) {
+            return Err("subscribe_events expects an object".to_string());
+        }
+        Ok(Self {
+            workspace_ids: parse_string_set(params, "workspaceIds")?,
+            thread_ids: parse_string_set(params, "threadIds")?,
+            methods: parse_string_set(params, "methods")?.map(|methods| {
+                let mut methods = methods.into_iter().collect::<Vec<_>>();
+                methods.sort();
+                methods
+            }),
+        })
+    }
+
+    pub(super) fn to_value(&self) -> Value {
+        fn sorted(values: &Option<HashSet<String>>) -> Option<Vec<String>> {
+            values.as_ref().map(|values| {
+                let mut values = values.iter().cloned().collect::<Vec<_>>();
+                values.sort();
+                values
+            })
+        }
+        json!(SubscribeEventsFilter {
+            workspace_ids: sorted(&self.workspace_ids),
+            thread_ids: sorted(&self.thread_ids),
+            methods: self.methods.clone(),
+        })
+    }
+
+    /// Thread filters only apply to events that belong to a thread; terminal
+    /// output and account-level notifications pass them untouched.
+    pub(super) fn matches(&self, event: &DaemonEvent) -> bool {
+        if let Some(workspace_ids) = &self.workspace_ids {
+            if !workspace_ids.contains(event_replay::event_workspace_id(event)) {
+                return false;
+            }
+        }
+        let (method, thread_id) = match event {
+            DaemonEvent::AppServer(payload) => (
+                payload
+                    .message
+                    .get("method")
+                    .and_then(Value::as_str)
+                    .unwrap_or(""),
+                extract_thread_id(&payload.message),
+            ),
+            DaemonEvent::TerminalOutput(_) => ("terminal-output", None),
+            DaemonEvent::TerminalExit(_) => ("terminal-exit", None),
+        };
+        if let (Some(thread_ids), Some(thread_id)) = (&self.thread_ids, thread_id) {
+            if !thread_ids.contains(&thread_id) {
+                return false;
+            }
+        }
+        match &self.methods {
+            Some(patterns) => patterns
+                .iter()
+                .any(|pattern| method_matches(pattern, method)),
+            None => true,
+        }
+    }
+}
+
+fn parse_string_set(params: &Value, key: &str) -> Result<Option<HashSet<String>>, String> {
+    match params.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Array(values)) => values
+            .iter()
+            .map(|value| {
+                value
+                    .as_str()
+                    .map(|value| value.trim().to_string())
+                    .filter(|value| !value.is_empty())
+                    .ok_or_else(|| format!("`{key}` must contain non-empty strings"))
+            })
+            .collect::<Result<HashSet<_>, _>>()
+            .map(Some),
+        Some(_) => Err(format!("`{key}` must be an array of strings")),
+    }
+}
+
+/// Matches an app-server method against a pattern where `*` stands for any
+/// run of characters, e.g. `turn/*` or `*requestApproval`.
+pub(super) fn method_matches(pattern: &str, method: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = method.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts = parts.collect::<Vec<_>>();
+    let Some(last) = parts.pop() else {
+        // No `*` in the pattern: it has to match exactly.
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
@@ -0,0 +1,572 @@
+//! Counters behind the `daemon_metrics` RPC and the optional Prometheus
+//! listener (`--metrics-listen`).
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use super::DaemonState;
+
+/// Upper bounds, in seconds, of the RPC latency histogram buckets.
+const LATENCY_BUCKETS_SECS: [f64; 12] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
+/// Methods outside the RPC catalog share one histogram so unknown names
+/// cannot grow the map.
+const OTHER_METHOD: &str = "other";
+const METRIC_PREFIX: &str = "codex_monitor_daemon";
+const HTTP_READ_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_HTTP_REQUEST_BYTES: usize = 8 * 1024;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct LatencyHistogram {
+    /// Calls per bucket, not cumulative; calls slower than the last bound
+    /// only show up in `count`.
+    buckets: [u64; LATENCY_BUCKETS_SECS.len()],
+    count: u64,
+    errors: u64,
+    sum_secs: f64,
+}
+
+impl LatencyHistogram {
+    fn observe(&mut self, elapsed: Duration, ok: bool) {
+        let secs = elapsed.as_secs_f64();
+        if let Some(index) = LATENCY_BUCKETS_SECS.iter().position(|bound| secs <= *bound) {
+            self.buckets[index] += 1;
+        }
+        self.count += 1;
+        self.sum_secs += secs;
+        if !ok {
+            self.errors += 1;
+        }
+    }
+
+    fn cumulative_buckets(&self) -> impl Iterator<Item = (f64, u64)> + '_ {
+        LATENCY_BUCKETS_SECS
+            .iter()
+            .zip(self.buckets.iter())
+            .scan(0, |total, (bound, count)| {
+                *total += count;
+                Some((*bound, *total))
+            })
+    }
+}
+
+/// Process-wide counters. Gauges are kept up to date by the RAII guards below
+/// so tasks that are dropped mid-call still release them.
+pub(crate) struct DaemonMetrics {
+    started_at: Instant,
+    connected_clients: AtomicU64,
+    rpc_in_flight: AtomicU64,
+    rpc_throttled: AtomicU64,
+    event_lag_dropped: AtomicU64,
+    app_server_exits: AtomicU64,
+    app_server_restarts: AtomicU64,
+    rpc_latency: std::sync::Mutex<BTreeMap<&'static str, LatencyHistogram>>,
+}
+
+impl Default for DaemonMetrics {
+    fn default() -> Self {
+        Self {
+            started_at: Instant::now(),
+            connected_clients: AtomicU64::new(0),
+            rpc_in_flight: AtomicU64::new(0),
+            rpc_throttled: AtomicU64::new(0),
+            event_lag_dropped: AtomicU64::new(0),
+            app_server_exits: AtomicU64::new(0),
+            app_server_restarts: AtomicU64::new(0),
+            rpc_latency: std::sync::Mutex::new(BTreeMap::new()),
+        }
+    }
+}
+
+impl DaemonMetrics {
+    pub(crate) fn client_connected(self: &Arc<Self>) -> GaugeGuard {
+        GaugeGuard::increment(Arc::clone(self), |metrics| &metrics.connected_clients)
+    }
+
+    pub(crate) fn rpc_started(self: &Arc<Self>) -> GaugeGuard {
+        GaugeGuard::increment(Arc::clone(self), |metrics| &metrics.rpc_in_flight)
+    }
+
+    /// A request that had to wait because its connection already had
+    /// `MAX_IN_FLIGHT_RPC_PER_CONNECTION` calls running.
+    pub(crate) fn record_rpc_throttled(&self) {
+        self.rpc_throttled.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_rpc(&self, method: &str, elapsed: Duration, ok: bool) {
+        let method = crate::rpc::catalog::find_method(method)
+            .map(|method| method.name)
+            .unwrap_or(OTHER_METHOD);
+        self.rpc_latency
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .entry(method)
+            .or_default()
+            .observe(elapsed, ok);
+    }
+
+    pub(crate) fn record_event_lag(&self, skipped: u64) {
+        self.event_lag_dropped.fetch_add(skipped, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_app_server_exit(&self) {
+        self.app_server_exits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_app_server_restart(&self) {
+        self.app_server_restarts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn counters(&self) -> MetricsCounters {
+        MetricsCounters {
+            uptime: self.started_at.elapsed(),
+            connected_clients: self.connected_clients.load(Ordering::Relaxed),
+            rpc_in_flight: self.rpc_in_flight.load(Ordering::Relaxed),
+            rpc_throttled: self.rpc_throttled.load(Ordering::Relaxed),
+            event_lag_dropped: self.event_lag_dropped.load(Ordering::Relaxed),
+            app_server_exits: self.app_server_exits.load(Ordering::Relaxed),
+            app_server_restarts: self.app_server_restarts.load(Ordering::Relaxed),
+            rpc_latency: self
+                .rpc_latency
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .clone(),
+        }
+    }
+}
+
+/// Decrements its gauge when dropped.
+pub(crate) struct GaugeGuard {
+    metrics: Arc<DaemonMetrics>,
+    gauge: fn(&DaemonMetrics) -> &AtomicU64,
+}
+
+impl GaugeGuard {
+    fn increment(metrics: Arc<DaemonMetrics>, gauge: fn(&DaemonMetrics) -> &AtomicU64) -> Self {
+        gauge(&metrics).fetch_add(1, Ordering::Relaxed);
+        Self { metrics, gauge }
+    }
+}
+
+impl Drop for GaugeGuard {
+    fn drop(&mut self) {
+        (self.gauge)(&self.metrics).fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct MetricsCounters {
+    uptime: Duration,
+    connected_clients: u64,
+    rpc_in_flight: u64,
+    rpc_throttled: u64,
+    event_lag_dropped: u64,
+    app_server_exits: u64,
+    app_server_restarts: u64,
+    rpc_latency: BTreeMap<&'static str, LatencyHistogram>,
+}
+
+/// One connected workspace and the app-server process serving it.
+#[derive(Debug, Clone)]
+pub(crate) struct WorkspaceSessionMetrics {
+    pub(crate) workspace_id: String,
+    pub(crate) pid: Option<u32>,
+    pub(crate) uptime: Duration,
+    pub(crate) crash_restarts: u32,
+}
+
+/// Everything `daemon_metrics` and `/metrics` report, gathered at one point.
+#[derive(Debug, Clone)]
+pub(crate) struct MetricsReport {
+    pub(crate) counters: MetricsCounters,
+    pub(crate) in_flight_limit_per_connection: usize,
+    /// Distinct app-server processes; workspaces can share one.
+    pub(crate) live_sessions: usize,
+    pub(crate) workspace_sessions: Vec<WorkspaceSessionMetrics>,
+    pub(crate) push_attempted: u64,
+    pub(crate) push_failed: u64,
+}
+
+impl MetricsReport {
+    pub(crate) fn to_json(&self) -> Value {
+        let counters = &self.counters;
+        let methods: serde_json::Map<String, Value> = counters
+            .rpc_latency
+            .iter()
+            .map(|(method, histogram)| {
+                let buckets: Vec<Value> = histogram
+                    .cumulative_buckets()
+                    .map(|(le, count)| json!({ "le": le, "count": count }))
+                    .collect();
+                (
+                    method.to_string(),
+                    json!({
+                        "count": histogram.count,
+                        "errors": histogram.errors,
+                        "sumSecs": histogram.sum_secs,
+                        "buckets": buckets,
+                    }),
+                )
+            })
+            .collect();
+        let workspaces: Vec<Value> = self
+            .workspace_sessions
+            .iter()
+            .map(|session| {
+                json!({
+                    "workspaceId": session.workspace_id,
+                    "pid": session.pid,
+                    "uptimeSecs": session.uptime.as_secs(),
+                    "crashRestarts": session.crash_restarts,
+                })
+            })
+            .collect();
+        json!({
+            "uptimeSecs": counters.uptime.as_secs(),
+            "connectedClients": counters.connected_clients,
+            "rpc": {
+                "inFlight": counters.rpc_in_flight,
+                "inFlightLimitPerConnection": self.in_flight_limit_per_connection,
+                "throttled": counters.rpc_throttled,
+                "methods": methods,
+            },
+            "events": {
+                "lagDropped": counters.event_lag_dropped,
+            },
+            "push": {
+                "attempted": self.push_attempted,
+                "failed": self.push_failed,
+            },
+            "appServer": {
+                "liveSessions": self.live_sessions,
+                "exits": counters.app_server_exits,
+                "restarts": counters.app_server_restarts,
+                "workspaces": workspaces,
+            },
+        })
+    }
+
+    /// Prometheus text exposition format, version 0.0.4.
+    pub(crate) fn to_prometheus(&self) -> String {
+        let counters = &self.counters;
+        let mut out = String::new();
+        let mut scalar = |name: &str, kind: &str, help: &str, value: String| {
+            let _ = writeln!(out, "# HELP {METRIC_PREFIX}_{name} {help}");
+            let _ = writeln!(out, "# TYPE {METRIC_PREFIX}_{name} {kind}");
+            let _ = writeln!(out, "{METRIC_PREFIX}_{name} {value}");
+        };
+        scalar(
+            "uptime_seconds",
+            "gauge",
+            "Seconds since the daemon started.",
+            counters.uptime.as_secs().to_string(),
+        );
+        scalar(
+            "connected_clients",
+            "gauge",
+            "Open client connections.",
+            counters.connected_clients.to_string(),
+        );
+        scalar(
+            "rpc_in_flight",
+            "gauge",
+            "RPCs currently executing across all connections.",
+            counters.rpc_in_flight.to_string(),
+        );
+        scalar(
+            "rpc_in_flight_limit_per_connection",
+            "gauge",
+            "Concurrent RPCs allowed per connection before requests queue.",
+            self.in_flight_limit_per_connection.to_string(),
+        );
+        scalar(
+            "rpc_throttled_total",
+            "counter",
+            "RPCs that queued behind the per-connection in-flight limit.",
+            counters.rpc_throttled.to_string(),
+        );
+        scalar(
+            "event_lag_dropped_total",
+            "counter",
+            "Events a slow client fell behind on in the broadcast channel.",
+            counters.event_lag_dropped.to_string(),
+        );
+        scalar(
+            "push_deliveries_total",
+            "counter",
+            "Push deliveries attempted.",
+            self.push_attempted.to_string(),
+        );
+        scalar(
+            "push_delivery_failures_total",
+            "counter",
+            "Push deliveries that failed after retries.",
+            self.push_failed.to_string(),
+        );
+        scalar(
+            "app_server_live_sessions",
+            "gauge",
+            "Running app-server processes.",
+            self.live_sessions.to_string(),
+        );
+        scalar(
+            "app_server_exits_total",
+            "counter",
+            "App-server processes that exited unexpectedly.",
+            counters.app_server_exits.to_string(),
+        );
+        scalar(
+            "app_server_restarts_total",
+            "counter",
+            "App-server processes restarted after an unexpected exit.",
+            counters.app_server_restarts.to_string(),
+        );
+
+        let _ = writeln!(
+            out,
+            "# HELP {METRIC_PREFIX}_workspace_session_uptime_seconds Uptime of the app-server serving each connected workspace."
+        );
+        let _ = writeln!(
+            out,
+            "# TYPE {METRIC_PREFIX}_workspace_session_uptime_seconds gauge"
+        );
+        for session in &self.workspace_sessions {
+            let _ = writeln!(
+                out,
+                "{METRIC_PREFIX}_workspace_session_uptime_seconds{{workspace_id=\"{}\"}} {}",
+                escape_label(&session.workspace_id),
+                session.uptime.as_secs()
+            );
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP {METRIC_PREFIX}_rpc_duration_seconds RPC latency by method."
+        );
+        let _ = writeln!(out, "# TYPE {METRIC_PREFIX}_rpc_duration_seconds histogram");
+        for (method, histogram) in &counters.rpc_latency {
+            for (le, count) in histogram.cumulative_buckets() {
+                let _ = writeln!(
+                    out,
+                    "{METRIC_PREFIX}_rpc_duration_seconds_bucket{{method=\"{method}\",le=\"{le}\"}} {count}"
+                );
+            }
+            let _ = writeln!(
+                out,
+                "{METRIC_PREFIX}_rpc_duration_seconds_bucket{{method=\"{method}\",le=\"+Inf\"}} {}",
+                histogram.count
+            );
+            let _ = writeln!(
+                out,
+                "{METRIC_PREFIX}_rpc_duration_seconds_sum{{method=\"{method}\"}} {}",
+                histogram.sum_secs
+            );
+            let _ = writeln!(
+                out,
+                "{METRIC_PREFIX}_rpc_duration_seconds_count{{method=\"{method}\"}} {}",
+                histogram.count
+            );
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP {METRIC_PREFIX}_rpc_errors_total RPCs that returned an error, by method."
+        );
+        let _ = writeln!(out, "# TYPE {METRIC_PREFIX}_rpc_errors_total counter");
+        for (method, histogram) in &counters.rpc_latency {
+            let _ = writeln!(
+                out,
+                "{METRIC_PREFIX}_rpc_errors_total{{method=\"{method}\"}} {}",
+                histogram.errors
+            );
+        }
+        out
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Serves `GET /metrics` (Prometheus text) and `GET /healthz` over plain HTTP.
+/// There is no auth, so bind it to loopback or a private interface.
+pub(crate) async fn run_metrics_listener(listener: TcpListener, state: Arc<DaemonState>) {
+    loop {
+        let socket = match listener.accept().await {
+            Ok((socket, _addr)) => socket,
+            Err(_) => continue,
+        };
+        let state = Arc::clone(&state);
+        tokio::spawn(async move {
+            serve_metrics_request(socket, &state).await;
+        });
+    }
+}
+
+async fn serve_metrics_request(mut socket: TcpStream, state: &DaemonState) {
+    let Ok(Some(request_line)) =
+        tokio::time::timeout(HTTP_READ_TIMEOUT, read_request_line(&mut socket))
+            .await
+            .map(Result::ok)
+            .map(Option::flatten)
+    else {
+        return;
+    };
+    let (status, content_type, body) = match parse_request_target(&request_line) {
+        Some(("GET", "/metrics")) => (
+            "200 OK",
+            "text/plain; version=0.0.4; charset=utf-8",
+            state.metrics_report().await.to_prometheus(),
+        ),
+        Some(("GET", "/healthz")) => {
+            let report = state.metrics_report().await;
+            let body = json!({
+                "status": "ok",
+                "uptimeSecs": report.counters.uptime.as_secs(),
+                "liveSessions": report.live_sessions,
+            });
+            ("200 OK", "application/json", format!("{body}\n"))
+        }
+        Some(("GET", _)) => ("404 Not Found", "text/plain", "not found\n".to_string()),
+        Some(_) => (
+            "405 Method Not Allowed",
+            "text/plain",
+            "method not allowed\n".to_string(),
+        ),
+        None => ("400 Bad Request", "text/plain", "bad request\n".to_string()),
+    };
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    let _ = socket.write_all(response.as_bytes()).await;
+    let _ = socket.shutdown().await;
+}
+
+/// Reads until the end of the request head and returns its first line.
+async fn read_request_line(socket: &mut TcpStream) -> std::io::Result<Option<String>> {
+    let mut head = Vec::new();
+    let mut chunk = [0u8; 1024];
+    while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+        if head.len() > MAX_HTTP_REQUEST_BYTES {
+            return Ok(None);
+        }
+        let read = socket.read(&mut chunk).await?;
+        if read == 0 {
+            break;
+        }
+        head.extend_from_slice(&chunk[..read]);
+    }
+    let head = String::from_utf8_lossy(&head);
+    Ok(head.lines().next().map(str::to_string))
+}
+
+/// Splits `GET /metrics?x=1 HTTP/1.1` into `("GET", "/metrics")`.
+fn parse_request_target(request_line: &str) -> Option<(&str, &str)> {
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next()?;
+    let target = parts.next()?;
+    parts.next()?.starts_with("HTTP/").then_some(())?;
+    let path = target.split('?').next().unwrap_or(target);
+    Some((method, path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histograms_bucket_latency_and_count_errors() {
+        let metrics = DaemonMetrics::default();
+        metrics.record_rpc("list_threads", Duration::from_millis(3), true);
+        metrics.record_rpc("list_threads", Duration::from_millis(40), false);
+        metrics.record_rpc("list_threads", Duration::from_secs(90), true);
+        metrics.record_rpc("made_up_method", Duration::from_millis(1), true);
+
+        let counters = metrics.counters();
+        let histogram = &counters.rpc_latency["list_threads"];
+        assert_eq!(histogram.count, 3);
+        assert_eq!(histogram.errors, 1);
+        let cumulative: Vec<(f64, u64)> = histogram.cumulative_buckets().collect();
+        assert_eq!(cumulative[0], (0.005, 1));
+        assert_eq!(cumulative[3], (0.05, 2));
+        assert_eq!(cumulative.last(), Some(&(30.0, 2)));
+        assert_eq!(counters.rpc_latency[OTHER_METHOD].count, 1);
+        assert!(!counters.rpc_latency.contains_key("made_up_method"));
+    }
+
+    #[test]
+    fn gauges_are_released_when_guards_drop() {
+        let metrics = Arc::new(DaemonMetrics::default());
+        let client = metrics.client_connected();
+        let first = metrics.rpc_started();
+        let second = metrics.rpc_started();
+        assert_eq!(metrics.counters().connected_clients, 1);
+        assert_eq!(metrics.counters().rpc_in_flight, 2);
+        drop(first);
+        drop(client);
+        assert_eq!(metrics.counters().connected_clients, 0);
+        assert_eq!(metrics.counters().rpc_in_flight, 1);
+        drop(second);
+        assert_eq!(metrics.counters().rpc_in_flight, 0);
+    }
+
+    #[test]
+    fn prometheus_output_escapes_labels_and_ends_histograms_with_inf() {
+        let metrics = DaemonMetrics::default();
+        metrics.record_rpc("ping", Duration::from_millis(2), true);
+        metrics.record_event_lag(7);
+        let report = MetricsReport {
+            counters: metrics.counters(),
+            in_flight_limit_per_connection: 32,
+            live_sessions: 1,
+            workspace_sessions: vec![WorkspaceSessionMetrics {
+                workspace_id: "ws\"1".to_string(),
+                pid: Some(42),
+                uptime: Duration::from_secs(5),
+                crash_restarts: 0,
+            }],
+            push_attempted: 3,
+            push_failed: 1,
+        };
+
+        let text = report.to_prometheus();
+        assert!(text.contains("codex_monitor_daemon_event_lag_dropped_total 7\n"));
+        assert!(text.contains("codex_monitor_daemon_push_delivery_failures_total 1\n"));
+        assert!(text.contains(
+            "codex_monitor_daemon_workspace_session_uptime_seconds{workspace_id=\"ws\\\"1\"} 5\n"
+        ));
+        assert!(text.contains(
+            "codex_monitor_daemon_rpc_duration_seconds_bucket{method=\"ping\",le=\"+Inf\"} 1\n"
+        ));
+
+        let value = report.to_json();
+        assert_eq!(value["rpc"]["inFlightLimitPerConnection"], json!(32));
+        assert_eq!(value["rpc"]["methods"]["ping"]["count"], json!(1));
+        assert_eq!(value["appServer"]["workspaces"][0]["pid"], json!(42));
+    }
+
+    #[test]
+    fn parse_request_target_strips_queries() {
+        assert_eq!(
+            parse_request_target("GET /metrics?name=x HTTP/1.1"),
+            Some(("GET", "/metrics"))
+        );
+        assert_eq!(
+            parse_request_target("POST /healthz HTTP/1.0").map(|t| t.0),
+            Some("POST")
+        );
+        assert_eq!(parse_request_target("garbage"), None);
+    }
+}
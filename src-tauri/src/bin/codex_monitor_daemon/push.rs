@@ -1,34 +1,60 @@
+use futures_util::future::join_all;
 use reqwest::Client;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Map, Value};
 use std::collections::HashMap;
+use std::future::Future;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
-use tokio::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, Notify};
 
+use crate::shared::core_error::CoreError;
+
+#[path = "push_channels.rs"]
+mod push_channels;
 #[path = "push_delivery.rs"]
 mod push_delivery;
+#[path = "push_digest.rs"]
+mod push_digest;
 #[path = "push_fcm.rs"]
 mod push_fcm;
+#[path = "push_outbox.rs"]
+mod push_outbox;
+#[path = "push_rules.rs"]
+mod push_rules;
 #[path = "push_support.rs"]
 mod push_support;
 
+use push_channels::{GotifyChannel, NtfyChannel, PushChannels, WebhookChannel};
 use push_delivery::PreparedDelivery;
+use push_digest::{is_digestible, MAX_DIGEST_WINDOW_MS};
 use push_fcm::DirectFcmSender;
+use push_outbox::{FcmDispatch, OutboxTarget, PushOutbox};
+use push_rules::PushDeviceRules;
+pub(crate) use push_rules::PushDeviceRulesPatch;
 use push_support::{
-    clamp_preview, config_snapshot_value, default_client_kind, default_true, deliver_to_relay,
-    has_non_afk_desktop_for_workspace, make_dedupe_key, make_thread_key, normalize_client_kind,
+    approval_preview, clamp_preview, config_snapshot_value, dedupe_window_ms, default_client_kind,
+    default_true, deliver_to_channel, file_change_preview, has_non_afk_desktop_for_workspace,
+    is_approval_request, make_dedupe_key, make_thread_key, normalize_client_kind,
     normalize_optional_non_empty, normalize_platform, now_ms, parse_thread_id, parse_turn_id,
-    prune_stale_entries, read_state_file, redact_token_preview,
+    prune_stale_entries, read_state_file, redact_token_preview, PushDeliveryStats,
 };
 
 const PUSH_STATE_FILE: &str = "push_notifications.json";
 pub(super) const HEARTBEAT_STALE_MS: i64 = 45_000;
 pub(super) const DEDUPE_WINDOW_MS: i64 = 5_000;
+/// Approval requests are deduped by request id for as long as one can
+/// plausibly stay unanswered.
+pub(super) const APPROVAL_DEDUPE_WINDOW_MS: i64 = 60 * 60 * 1_000;
 pub(super) const MAX_PREVIEW_CHARS: usize = 200;
+/// Turns are timed for as long as one can plausibly keep running.
+pub(super) const TURN_START_STALE_MS: i64 = 24 * 60 * 60 * 1_000;
+const FLUSH_POLL_INTERVAL: Duration = Duration::from_millis(50);
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct PresenceHeartbeatInput {
     pub(crate) client_id: String,
@@ -46,7 +72,7 @@ pub(crate) struct PresenceHeartbeatInput {
     pub(crate) active_workspace_ids: Vec<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct PushDeviceRegistrationInput {
     pub(crate) device_id: String,
@@ -56,13 +82,22 @@ pub(crate) struct PushDeviceRegistrationInput {
     pub(crate) label: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct PushNotificationConfigPatch {
     #[serde(default)]
     pub(crate) relay_url: Option<Option<String>>,
     #[serde(default)]
     pub(crate) relay_auth_token: Option<Option<String>>,
+    #[serde(default)]
+    pub(crate) webhook: Option<WebhookChannel>,
+    #[serde(default)]
+    pub(crate) ntfy: Option<NtfyChannel>,
+    #[serde(default)]
+    pub(crate) gotify: Option<GotifyChannel>,
+    /// `0` turns digest mode off.
+    #[serde(default)]
+    pub(crate) digest_window_ms: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -77,6 +112,15 @@ pub(crate) struct PushDeviceRegistration {
     pub(crate) enabled: bool,
     #[serde(default)]
     pub(crate) last_seen_at_ms: Option<i64>,
+    #[serde(default)]
+    pub(crate) rules: PushDeviceRules,
+    /// When a push for this device last reached the relay or FCM.
+    #[serde(default)]
+    pub(crate) last_delivered_at_ms: Option<i64>,
+    #[serde(default)]
+    pub(crate) last_failed_at_ms: Option<i64>,
+    #[serde(default)]
+    pub(crate) last_error: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -88,6 +132,10 @@ pub(super) struct PersistedPushState {
     relay_auth_token: Option<String>,
     #[serde(default)]
     devices: Vec<PushDeviceRegistration>,
+    #[serde(default)]
+    channels: PushChannels,
+    #[serde(default)]
+    digest_window_ms: Option<u64>,
 }
 
 #[derive(Debug, Clone)]
@@ -109,22 +157,50 @@ pub(super) struct PushEvent {
     workspace_name: Option<String>,
     thread_id: String,
     turn_id: Option<String>,
+    /// Server request id to pass to `respond_to_server_request`; set for
+    /// `turn.approvalRequired` only.
+    request_id: Option<Value>,
+    /// How long the turn ran, when its `turn/started` was seen; set for
+    /// `turn.completed` only.
+    turn_duration_ms: Option<i64>,
+    preview: String,
+}
+
+struct CapturedFileChange {
     preview: String,
+    captured_at_ms: i64,
 }
 
 pub(super) struct PushBrokerState {
     relay_url: Option<String>,
     relay_auth_token: Option<String>,
     devices: HashMap<String, PushDeviceRegistration>,
+    channels: PushChannels,
+    /// With a window set, completions and errors wait in `digest` and go out
+    /// as one summary push.
+    digest_window_ms: Option<u64>,
+    digest: Vec<PushEvent>,
     presence_by_client: HashMap<String, PresenceRecord>,
     last_message_by_thread: HashMap<String, String>,
-    dedupe_sent_at: HashMap<String, i64>,
+    /// When the running turn of each thread started, for
+    /// `PushDeviceRules::min_turn_duration_ms`.
+    turn_started_at_by_thread: HashMap<String, i64>,
+    /// Changed paths of in-progress `fileChange` items, for approval previews.
+    file_changes_by_item: HashMap<String, CapturedFileChange>,
+    /// When each dedupe key may push again.
+    dedupe_until_ms: HashMap<String, i64>,
 }
 
 pub(crate) struct PushBroker {
     state_path: PathBuf,
     http_client: Client,
-    direct_fcm: Arc<DirectFcmSender>,
+    direct_fcm: DirectFcmSender,
+    outbox: PushOutbox,
+    delivery_stats: Arc<PushDeliveryStats>,
+    /// Deliveries spawned and not finished yet; `flush` waits for them.
+    in_flight: Arc<AtomicUsize>,
+    /// Wakes `run_digest` when the first event of a digest arrives.
+    digest_wake: Notify,
     state: Mutex<PushBrokerState>,
 }
 
@@ -142,28 +218,56 @@ impl PushBroker {
             .timeout(Duration::from_secs(10))
             .build()
             .unwrap_or_else(|_| Client::new());
+        let delivery_stats = Arc::new(PushDeliveryStats::default());
         Self {
             state_path,
-            direct_fcm: Arc::new(DirectFcmSender::new(data_dir.clone(), http_client.clone())),
+            direct_fcm: DirectFcmSender::new(data_dir.clone(), http_client.clone()),
+            outbox: PushOutbox::load(data_dir),
+            delivery_stats,
+            in_flight: Arc::new(AtomicUsize::new(0)),
+            digest_wake: Notify::new(),
             http_client,
             state: Mutex::new(PushBrokerState {
                 relay_url: normalize_optional_non_empty(persisted.relay_url),
                 relay_auth_token: normalize_optional_non_empty(persisted.relay_auth_token),
                 devices,
+                channels: persisted.channels,
+                digest_window_ms: persisted.digest_window_ms.filter(|window| *window > 0),
+                digest: Vec::new(),
                 presence_by_client: HashMap::new(),
                 last_message_by_thread: HashMap::new(),
-                dedupe_sent_at: HashMap::new(),
+                turn_started_at_by_thread: HashMap::new(),
+                file_changes_by_item: HashMap::new(),
+                dedupe_until_ms: HashMap::new(),
             }),
         }
     }
 
+    pub(crate) fn delivery_stats(&self) -> &PushDeliveryStats {
+        &self.delivery_stats
+    }
+
+    /// Sends any pending digest, waits up to `timeout` for spawned deliveries
+    /// and due outbox sends to finish, then writes the registrations to disk.
+    /// Sends still waiting on a retry stay in the outbox for the next start.
+    /// Called on shutdown.
+    pub(crate) async fn flush(&self, timeout: Duration) -> Result<(), String> {
+        self.flush_digest().await;
+        let started = Instant::now();
+        while self.in_flight.load(Ordering::SeqCst) > 0 && started.elapsed() < timeout {
+            tokio::time::sleep(FLUSH_POLL_INTERVAL).await;
+        }
+        let state = self.state.lock().await;
+        self.persist_state(&state)
+    }
+
     pub(crate) async fn record_presence(
         &self,
         input: PresenceHeartbeatInput,
-    ) -> Result<(), String> {
+    ) -> Result<(), CoreError> {
         let client_id = input.client_id.trim().to_string();
         if client_id.is_empty() {
-            return Err("missing `clientId`".to_string());
+            return Err(CoreError::invalid_params("missing `clientId`"));
         }
         let now_ms = now_ms();
         let mut state = self.state.lock().await;
@@ -192,18 +296,19 @@ impl PushBroker {
     pub(crate) async fn register_device(
         &self,
         input: PushDeviceRegistrationInput,
-    ) -> Result<PushDeviceRegistration, String> {
+    ) -> Result<PushDeviceRegistration, CoreError> {
         let device_id = input.device_id.trim().to_string();
         if device_id.is_empty() {
-            return Err("missing `deviceId`".to_string());
+            return Err(CoreError::invalid_params("missing `deviceId`"));
         }
         let token = input.token.trim().to_string();
         if token.is_empty() {
-            return Err("missing `token`".to_string());
+            return Err(CoreError::invalid_params("missing `token`"));
         }
-        let platform = normalize_platform(input.platform)?;
+        let platform = normalize_platform(input.platform).map_err(CoreError::invalid_params)?;
         let now_ms = now_ms();
         let mut state = self.state.lock().await;
+        let previous = state.devices.get(&device_id);
         let device = PushDeviceRegistration {
             device_id: device_id.clone(),
             platform,
@@ -211,16 +316,22 @@ impl PushBroker {
             label: normalize_optional_non_empty(input.label),
             enabled: true,
             last_seen_at_ms: Some(now_ms),
+            rules: previous
+                .map(|device| device.rules.clone())
+                .unwrap_or_default(),
+            last_delivered_at_ms: previous.and_then(|device| device.last_delivered_at_ms),
+            last_failed_at_ms: previous.and_then(|device| device.last_failed_at_ms),
+            last_error: previous.and_then(|device| device.last_error.clone()),
         };
         state.devices.insert(device_id, device.clone());
         self.persist_state(&state)?;
         Ok(device)
     }
 
-    pub(crate) async fn unregister_device(&self, device_id: String) -> Result<(), String> {
+    pub(crate) async fn unregister_device(&self, device_id: String) -> Result<(), CoreError> {
         let device_id = device_id.trim().to_string();
         if device_id.is_empty() {
-            return Err("missing `deviceId`".to_string());
+            return Err(CoreError::invalid_params("missing `deviceId`"));
         }
         let mut state = self.state.lock().await;
         state.devices.remove(&device_id);
@@ -228,10 +339,72 @@ impl PushBroker {
         Ok(())
     }
 
+    pub(crate) async fn device_rules(
+        &self,
+        device_id: String,
+    ) -> Result<PushDeviceRules, CoreError> {
+        let device_id = device_id.trim().to_string();
+        if device_id.is_empty() {
+            return Err(CoreError::invalid_params("missing `deviceId`"));
+        }
+        let state = self.state.lock().await;
+        state
+            .devices
+            .get(&device_id)
+            .map(|device| device.rules.clone())
+            .ok_or_else(|| CoreError::invalid_params(format!("unknown device `{device_id}`")))
+    }
+
+    pub(crate) async fn patch_device_rules(
+        &self,
+        device_id: String,
+        patch: PushDeviceRulesPatch,
+    ) -> Result<PushDeviceRules, CoreError> {
+        let device_id = device_id.trim().to_string();
+        if device_id.is_empty() {
+            return Err(CoreError::invalid_params("missing `deviceId`"));
+        }
+        let mut state = self.state.lock().await;
+        let device = state
+            .devices
+            .get_mut(&device_id)
+            .ok_or_else(|| CoreError::invalid_params(format!("unknown device `{device_id}`")))?;
+        device
+            .rules
+            .apply(patch)
+            .map_err(CoreError::invalid_params)?;
+        let rules = device.rules.clone();
+        self.persist_state(&state)?;
+        Ok(rules)
+    }
+
     pub(crate) async fn patch_config(
         &self,
         patch: PushNotificationConfigPatch,
-    ) -> Result<Value, String> {
+    ) -> Result<Value, CoreError> {
+        let webhook = patch
+            .webhook
+            .map(WebhookChannel::normalized)
+            .transpose()
+            .map_err(CoreError::invalid_params)?;
+        let ntfy = patch
+            .ntfy
+            .map(NtfyChannel::normalized)
+            .transpose()
+            .map_err(CoreError::invalid_params)?;
+        let gotify = patch
+            .gotify
+            .map(GotifyChannel::normalized)
+            .transpose()
+            .map_err(CoreError::invalid_params)?;
+        if patch
+            .digest_window_ms
+            .is_some_and(|window| window > MAX_DIGEST_WINDOW_MS)
+        {
+            return Err(CoreError::invalid_params(format!(
+                "`digestWindowMs` must be at most {MAX_DIGEST_WINDOW_MS}"
+            )));
+        }
         let mut state = self.state.lock().await;
         if let Some(next_relay_url) = patch.relay_url {
             state.relay_url = normalize_optional_non_empty(next_relay_url);
@@ -239,6 +412,18 @@ impl PushBroker {
         if let Some(next_relay_auth_token) = patch.relay_auth_token {
             state.relay_auth_token = normalize_optional_non_empty(next_relay_auth_token);
         }
+        if let Some(webhook) = webhook {
+            state.channels.webhook = webhook;
+        }
+        if let Some(ntfy) = ntfy {
+            state.channels.ntfy = ntfy;
+        }
+        if let Some(gotify) = gotify {
+            state.channels.gotify = gotify;
+        }
+        if let Some(window) = patch.digest_window_ms {
+            state.digest_window_ms = (window > 0).then_some(window);
+        }
         self.persist_state(&state)?;
         Ok(config_snapshot_value(&state))
     }
@@ -263,6 +448,10 @@ impl PushBroker {
                     "enabled": device.enabled,
                     "lastSeenAtMs": device.last_seen_at_ms,
                     "tokenPreview": redact_token_preview(&device.token),
+                    "rules": device.rules,
+                    "lastDeliveredAtMs": device.last_delivered_at_ms,
+                    "lastFailedAtMs": device.last_failed_at_ms,
+                    "lastError": device.last_error,
                 })
             })
             .collect::<Vec<_>>();
@@ -307,9 +496,21 @@ impl PushBroker {
             .cloned()
             .unwrap_or_default();
 
-        if method == "item/completed" {
-            self.capture_last_agent_message(workspace_id, &params).await;
-            return;
+        match method {
+            "turn/started" => {
+                self.capture_turn_start(workspace_id, &params).await;
+                return;
+            }
+            "item/started" => {
+                self.capture_file_change(workspace_id, &params).await;
+                return;
+            }
+            "item/completed" => {
+                self.forget_file_change(workspace_id, &params).await;
+                self.capture_last_agent_message(workspace_id, &params).await;
+                return;
+            }
+            _ => {}
         }
 
         let candidate = match method {
@@ -321,6 +522,13 @@ impl PushBroker {
                 self.build_turn_error_event(workspace_id, workspace_name, &params)
                     .await
             }
+            _ if is_approval_request(method) => match message_obj.get("id") {
+                Some(request_id) => {
+                    self.build_approval_event(workspace_id, workspace_name, request_id, &params)
+                        .await
+                }
+                None => None,
+            },
             _ => None,
         };
 
@@ -329,22 +537,55 @@ impl PushBroker {
         };
 
         if let Some(delivery) = self.prepare_delivery(candidate).await {
-            if delivery.relay_url.is_some() {
-                let client = self.http_client.clone();
-                tokio::spawn(async move {
-                    if let Some(dispatch) = delivery.into_relay_dispatch() {
-                        deliver_to_relay(client, dispatch).await;
-                    }
-                });
-            } else {
-                let direct_fcm = Arc::clone(&self.direct_fcm);
-                tokio::spawn(async move {
-                    direct_fcm.deliver(delivery).await;
-                });
-            }
+            self.dispatch(delivery).await;
         }
     }
 
+    /// Sends a delivery to every configured channel, and queues it for the
+    /// registered devices through the relay or direct FCM.
+    async fn dispatch(&self, delivery: PreparedDelivery) {
+        let delivery = Arc::new(delivery);
+        if !delivery.channels.is_empty() {
+            let client = self.http_client.clone();
+            let stats = Arc::clone(&self.delivery_stats);
+            let delivery = Arc::clone(&delivery);
+            self.spawn_delivery(async move {
+                let sends = delivery
+                    .channels
+                    .configured()
+                    .into_iter()
+                    .map(|channel| deliver_to_channel(&client, channel, &delivery, &stats));
+                join_all(sends).await;
+            });
+        }
+        if delivery.devices.is_empty() {
+            return;
+        }
+        let targets = match delivery.relay_dispatch() {
+            Some(dispatch) => vec![OutboxTarget::Relay(dispatch)],
+            None => delivery
+                .devices
+                .iter()
+                .map(|device| {
+                    OutboxTarget::Fcm(FcmDispatch {
+                        device_id: device.device_id.clone(),
+                        message: DirectFcmSender::message(&delivery, device),
+                    })
+                })
+                .collect(),
+        };
+        self.outbox.enqueue(targets).await;
+    }
+
+    fn spawn_delivery(&self, task: impl Future<Output = ()> + Send + 'static) {
+        let in_flight = Arc::clone(&self.in_flight);
+        in_flight.fetch_add(1, Ordering::SeqCst);
+        tokio::spawn(async move {
+            task.await;
+            in_flight.fetch_sub(1, Ordering::SeqCst);
+        });
+    }
+
     async fn capture_last_agent_message(&self, workspace_id: &str, params: &Map<String, Value>) {
         let thread_id = parse_thread_id(params);
         let Some(thread_id) = thread_id else {
@@ -372,6 +613,51 @@ impl PushBroker {
             .insert(thread_key, clamp_preview(text.to_string()));
     }
 
+    async fn capture_turn_start(&self, workspace_id: &str, params: &Map<String, Value>) {
+        let Some(thread_id) = parse_thread_id(params) else {
+            return;
+        };
+        let thread_key = make_thread_key(workspace_id, &thread_id);
+        let mut state = self.state.lock().await;
+        state.turn_started_at_by_thread.insert(thread_key, now_ms());
+    }
+
+    async fn capture_file_change(&self, workspace_id: &str, params: &Map<String, Value>) {
+        let Some(item) = params.get("item").and_then(Value::as_object) else {
+            return;
+        };
+        if item.get("type").and_then(Value::as_str) != Some("fileChange") {
+            return;
+        }
+        let item_id = item.get("id").and_then(Value::as_str);
+        let preview = item.get("changes").and_then(file_change_preview);
+        let (Some(item_id), Some(preview)) = (item_id, preview) else {
+            return;
+        };
+        let item_key = make_thread_key(workspace_id, item_id);
+        let mut state = self.state.lock().await;
+        state.file_changes_by_item.insert(
+            item_key,
+            CapturedFileChange {
+                preview,
+                captured_at_ms: now_ms(),
+            },
+        );
+    }
+
+    async fn forget_file_change(&self, workspace_id: &str, params: &Map<String, Value>) {
+        let item_id = params
+            .get("item")
+            .and_then(|item| item.get("id"))
+            .and_then(Value::as_str);
+        let Some(item_id) = item_id else {
+            return;
+        };
+        let item_key = make_thread_key(workspace_id, item_id);
+        let mut state = self.state.lock().await;
+        state.file_changes_by_item.remove(&item_key);
+    }
+
     async fn build_turn_completed_event(
         &self,
         workspace_id: &str,
@@ -386,12 +672,18 @@ impl PushBroker {
             .last_message_by_thread
             .remove(&thread_key)
             .unwrap_or_else(|| "Your agent finished a task.".to_string());
+        let turn_duration_ms = state
+            .turn_started_at_by_thread
+            .remove(&thread_key)
+            .map(|started_at_ms| now_ms() - started_at_ms);
         Some(PushEvent {
             kind: "turn.completed",
             workspace_id: workspace_id.to_string(),
             workspace_name,
             thread_id,
             turn_id,
+            request_id: None,
+            turn_duration_ms,
             preview: clamp_preview(preview),
         })
     }
@@ -426,10 +718,52 @@ impl PushBroker {
             workspace_name,
             thread_id,
             turn_id,
+            request_id: None,
+            turn_duration_ms: None,
             preview: clamp_preview(message.to_string()),
         })
     }
 
+    /// Builds a `turn.approvalRequired` event from an exec or patch approval
+    /// request. Legacy requests name the thread `conversationId` and carry
+    /// their file changes inline; newer ones point at a `fileChange` item.
+    async fn build_approval_event(
+        &self,
+        workspace_id: &str,
+        workspace_name: Option<String>,
+        request_id: &Value,
+        params: &Map<String, Value>,
+    ) -> Option<PushEvent> {
+        let thread_id = parse_thread_id(params).or_else(|| {
+            params
+                .get("conversationId")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        })?;
+        let turn_id = parse_turn_id(params);
+        let file_changes = match params.get("itemId").and_then(Value::as_str) {
+            Some(item_id) => {
+                let item_key = make_thread_key(workspace_id, item_id);
+                let mut state = self.state.lock().await;
+                state
+                    .file_changes_by_item
+                    .remove(&item_key)
+                    .map(|change| change.preview)
+            }
+            None => params.get("fileChanges").and_then(file_change_preview),
+        };
+        Some(PushEvent {
+            kind: "turn.approvalRequired",
+            workspace_id: workspace_id.to_string(),
+            workspace_name,
+            thread_id,
+            turn_id,
+            request_id: Some(request_id.clone()),
+            turn_duration_ms: None,
+            preview: clamp_preview(approval_preview(params, file_changes)),
+        })
+    }
+
     async fn prepare_delivery(&self, event: PushEvent) -> Option<PreparedDelivery> {
         let now_ms = now_ms();
         let mut state = self.state.lock().await;
@@ -440,39 +774,70 @@ impl PushBroker {
         let devices = state
             .devices
             .values()
-            .filter(|device| device.enabled)
+            .filter(|device| device.enabled && device.rules.allows(&event, now_ms))
             .cloned()
             .collect::<Vec<_>>();
-        if devices.is_empty() {
+        if devices.is_empty() && state.channels.is_empty() {
             return None;
         }
         let dedupe_key = make_dedupe_key(&event);
-        let previous_sent_at = state.dedupe_sent_at.get(&dedupe_key).copied().unwrap_or(0);
-        if now_ms - previous_sent_at < DEDUPE_WINDOW_MS {
+        let dedupe_until = state.dedupe_until_ms.get(&dedupe_key).copied().unwrap_or(0);
+        if now_ms < dedupe_until {
             return None;
         }
-        state.dedupe_sent_at.insert(dedupe_key, now_ms);
-        let title = match event.kind {
-            "turn.error" => event
-                .workspace_name
-                .as_ref()
-                .map(|name| format!("Agent Error — {name}"))
-                .unwrap_or_else(|| "Agent Error".to_string()),
-            _ => event
-                .workspace_name
-                .as_ref()
-                .map(|name| format!("Agent Complete — {name}"))
-                .unwrap_or_else(|| "Agent Complete".to_string()),
-        };
-        Some(PreparedDelivery {
-            relay_url: state.relay_url.clone(),
-            relay_auth_token: state.relay_auth_token.clone(),
-            body: event.preview.clone(),
-            event,
-            title,
-            devices,
-            timestamp_ms: now_ms,
-        })
+        state
+            .dedupe_until_ms
+            .insert(dedupe_key, now_ms + dedupe_window_ms(event.kind));
+        if state.digest_window_ms.is_some() && is_digestible(event.kind) {
+            if state.digest.is_empty() {
+                self.digest_wake.notify_one();
+            }
+            state.digest.push(event);
+            return None;
+        }
+        Some(PreparedDelivery::new(&state, event, devices, now_ms))
+    }
+
+    /// Records the outcome of a send on each device it was for.
+    async fn record_receipt(&self, device_ids: &[String], error: Option<String>, now_ms: i64) {
+        let mut state = self.state.lock().await;
+        for device_id in device_ids {
+            let Some(device) = state.devices.get_mut(device_id) else {
+                continue;
+            };
+            match error.as_ref() {
+                None => device.last_delivered_at_ms = Some(now_ms),
+                Some(error) => {
+                    device.last_failed_at_ms = Some(now_ms);
+                    device.last_error = Some(error.clone());
+                }
+            }
+        }
+        if let Err(err) = self.persist_state(&state) {
+            tracing::warn!("push state write failed: {err}");
+        }
+    }
+
+    /// Drops a device whose token FCM reports as unregistered, unless it has
+    /// registered a new token since the send was queued.
+    async fn prune_unregistered_device(&self, dispatch: &FcmDispatch) {
+        let token = dispatch.message["message"]["token"].as_str();
+        let mut state = self.state.lock().await;
+        let matches = state
+            .devices
+            .get(&dispatch.device_id)
+            .is_some_and(|device| Some(device.token.as_str()) == token);
+        if !matches {
+            return;
+        }
+        state.devices.remove(&dispatch.device_id);
+        tracing::warn!(
+            "push device `{}` pruned: FCM reports its token unregistered",
+            dispatch.device_id
+        );
+        if let Err(err) = self.persist_state(&state) {
+            tracing::warn!("push state write failed: {err}");
+        }
     }
 
     fn persist_state(&self, state: &PushBrokerState) -> Result<(), String> {
@@ -480,6 +845,8 @@ impl PushBroker {
             relay_url: state.relay_url.clone(),
             relay_auth_token: state.relay_auth_token.clone(),
             devices: state.devices.values().cloned().collect(),
+            channels: state.channels.clone(),
+            digest_window_ms: state.digest_window_ms,
         };
         if let Some(parent) = self.state_path.parent() {
             std::fs::create_dir_all(parent).map_err(|err| err.to_string())?;
@@ -0,0 +1,314 @@
+//! Push channels that deliver without a registered device: signed webhooks,
+//! ntfy topics and Gotify servers.
+
+use hmac::{Hmac, Mac};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::{Client, RequestBuilder};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::Sha256;
+
+use super::push_delivery::{PreparedDelivery, PushChannel};
+use super::push_support::normalize_optional_non_empty;
+
+pub(super) const WEBHOOK_SIGNATURE_HEADER: &str = "X-CodexMonitor-Signature";
+pub(super) const WEBHOOK_EVENT_HEADER: &str = "X-CodexMonitor-Event";
+const GOTIFY_KEY_HEADER: &str = "X-Gotify-Key";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PushChannels {
+    #[serde(default)]
+    pub(crate) webhook: Option<WebhookChannel>,
+    #[serde(default)]
+    pub(crate) ntfy: Option<NtfyChannel>,
+    #[serde(default)]
+    pub(crate) gotify: Option<GotifyChannel>,
+}
+
+impl PushChannels {
+    pub(super) fn is_empty(&self) -> bool {
+        self.configured().is_empty()
+    }
+
+    pub(super) fn configured(&self) -> Vec<&dyn PushChannel> {
+        let mut channels: Vec<&dyn PushChannel> = Vec::new();
+        if let Some(webhook) = self.webhook.as_ref() {
+            channels.push(webhook);
+        }
+        if let Some(ntfy) = self.ntfy.as_ref() {
+            channels.push(ntfy);
+        }
+        if let Some(gotify) = self.gotify.as_ref() {
+            channels.push(gotify);
+        }
+        channels
+    }
+
+    /// The channel config without secrets, for `push_notification_config_get`.
+    pub(super) fn snapshot(&self) -> Value {
+        json!({
+            "webhook": self.webhook.as_ref().map(|webhook| json!({
+                "url": webhook.url,
+                "hasSecret": webhook.secret.is_some(),
+            })),
+            "ntfy": self.ntfy.as_ref().map(|ntfy| json!({
+                "url": ntfy.url,
+                "topic": ntfy.topic,
+                "hasToken": ntfy.token.is_some(),
+            })),
+            "gotify": self.gotify.as_ref().map(|gotify| json!({
+                "url": gotify.url,
+            })),
+        })
+    }
+}
+
+/// POSTs the notification payload as JSON. With a secret, the exact body is
+/// signed with HMAC-SHA256 and sent as `X-CodexMonitor-Signature: sha256=<hex>`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WebhookChannel {
+    pub(crate) url: String,
+    #[serde(default)]
+    pub(crate) secret: Option<String>,
+}
+
+/// Publishes to an ntfy topic through ntfy's JSON API.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct NtfyChannel {
+    /// Required: notifications carry previews, so they never go to a public
+    /// server by default.
+    #[serde(default)]
+    pub(crate) url: String,
+    #[serde(default)]
+    pub(crate) topic: String,
+    #[serde(default)]
+    pub(crate) token: Option<String>,
+}
+
+/// Posts to a Gotify server with an application token.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GotifyChannel {
+    pub(crate) url: String,
+    #[serde(default)]
+    pub(crate) token: String,
+}
+
+impl WebhookChannel {
+    /// Validates a patch. An empty `url` removes the channel.
+    pub(super) fn normalized(self) -> Result<Option<Self>, String> {
+        let Some(url) = normalize_url("webhook", self.url)? else {
+            return Ok(None);
+        };
+        Ok(Some(Self {
+            url,
+            secret: normalize_optional_non_empty(self.secret),
+        }))
+    }
+}
+
+impl NtfyChannel {
+    /// Validates a patch. An empty `topic` removes the channel.
+    pub(super) fn normalized(self) -> Result<Option<Self>, String> {
+        let topic = self.topic.trim().to_string();
+        if topic.is_empty() {
+            return Ok(None);
+        }
+        let Some(url) = normalize_url("ntfy", self.url)? else {
+            return Err("missing `ntfy.url`".to_string());
+        };
+        Ok(Some(Self {
+            url,
+            topic,
+            token: normalize_optional_non_empty(self.token),
+        }))
+    }
+}
+
+impl GotifyChannel {
+    /// Validates a patch. An empty `url` removes the channel.
+    pub(super) fn normalized(self) -> Result<Option<Self>, String> {
+        let Some(url) = normalize_url("gotify", self.url)? else {
+            return Ok(None);
+        };
+        let token = self.token.trim().to_string();
+        if token.is_empty() {
+            return Err("missing `gotify.token`".to_string());
+        }
+        Ok(Some(Self { url, token }))
+    }
+}
+
+impl PushChannel for WebhookChannel {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn request(&self, client: &Client, delivery: &PreparedDelivery) -> RequestBuilder {
+        let body = delivery.notification_payload().to_string();
+        let request = client
+            .post(self.url.as_str())
+            .header(CONTENT_TYPE, "application/json")
+            .header(WEBHOOK_EVENT_HEADER, delivery.event.kind);
+        let request = match self.secret.as_deref() {
+            Some(secret) => request.header(
+                WEBHOOK_SIGNATURE_HEADER,
+                webhook_signature(secret, body.as_bytes()),
+            ),
+            None => request,
+        };
+        request.body(body)
+    }
+}
+
+impl PushChannel for NtfyChannel {
+    fn name(&self) -> &'static str {
+        "ntfy"
+    }
+
+    fn request(&self, client: &Client, delivery: &PreparedDelivery) -> RequestBuilder {
+        let priority = if is_urgent(delivery.event.kind) { 4 } else { 3 };
+        let request = client.post(self.url.as_str()).json(&json!({
+            "topic": self.topic,
+            "title": delivery.title,
+            "message": delivery.body,
+            "priority": priority,
+        }));
+        match self.token.as_deref() {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+}
+
+impl PushChannel for GotifyChannel {
+    fn name(&self) -> &'static str {
+        "gotify"
+    }
+
+    fn request(&self, client: &Client, delivery: &PreparedDelivery) -> RequestBuilder {
+        let priority = if is_urgent(delivery.event.kind) { 8 } else { 5 };
+        client
+            .post(format!("{}/message", self.url))
+            .header(GOTIFY_KEY_HEADER, self.token.as_str())
+            .json(&json!({
+                "title": delivery.title,
+                "message": delivery.body,
+                "priority": priority,
+                "extras": {
+                    "codexmonitor::event": delivery.notification_payload(),
+                },
+            }))
+    }
+}
+
+/// `sha256=<hex>` HMAC of a webhook body, as sent in
+/// `X-CodexMonitor-Signature`.
+pub(super) fn webhook_signature(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    let digest = mac.finalize().into_bytes();
+    let hex = digest
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    format!("sha256={hex}")
+}
+
+/// Errors and approvals need someone; completed turns can wait.
+fn is_urgent(kind: &str) -> bool {
+    kind != "turn.completed"
+}
+
+fn normalize_url(channel: &str, url: String) -> Result<Option<String>, String> {
+    let url = url.trim().trim_end_matches('/').to_string();
+    if url.is_empty() {
+        return Ok(None);
+    }
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err(format!("`{channel}.url` must be an http(s) URL"));
+    }
+    Ok(Some(url))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{webhook_signature, GotifyChannel, NtfyChannel, WebhookChannel};
+
+    #[test]
+    fn webhook_signature_is_hmac_sha256_of_the_body() {
+        // RFC 4231, test case 2.
+        assert_eq!(
+            webhook_signature("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn channel_patches_are_validated_and_empty_ones_remove_the_channel() {
+        let webhook = WebhookChannel {
+            url: " https://hooks.example/codex/ ".to_string(),
+            secret: Some("  ".to_string()),
+        }
+        .normalized()
+        .expect("valid")
+        .expect("configured");
+        assert_eq!(webhook.url, "https://hooks.example/codex");
+        assert_eq!(webhook.secret, None);
+
+        let err = WebhookChannel {
+            url: "hooks.example".to_string(),
+            secret: None,
+        }
+        .normalized()
+        .expect_err("scheme required");
+        assert_eq!(err, "`webhook.url` must be an http(s) URL");
+
+        let ntfy = NtfyChannel {
+            url: "https://ntfy.example/".to_string(),
+            topic: " codex ".to_string(),
+            token: None,
+        }
+        .normalized()
+        .expect("valid")
+        .expect("configured");
+        assert_eq!(ntfy.url, "https://ntfy.example");
+        assert_eq!(ntfy.topic, "codex");
+        let err = NtfyChannel {
+            url: " ".to_string(),
+            topic: "codex".to_string(),
+            token: None,
+        }
+        .normalized()
+        .expect_err("url required");
+        assert_eq!(err, "missing `ntfy.url`");
+        assert!(NtfyChannel {
+            url: "https://ntfy.example".to_string(),
+            topic: " ".to_string(),
+            token: None,
+        }
+        .normalized()
+        .expect("valid")
+        .is_none());
+
+        let err = GotifyChannel {
+            url: "https://gotify.example".to_string(),
+            token: String::new(),
+        }
+        .normalized()
+        .expect_err("token required");
+        assert_eq!(err, "missing `gotify.token`");
+        assert!(GotifyChannel {
+            url: String::new(),
+            token: String::new(),
+        }
+        .normalized()
+        .expect("valid")
+        .is_none());
+    }
+}
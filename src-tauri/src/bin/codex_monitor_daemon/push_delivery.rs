@@ -1,7 +1,9 @@
-use serde_json::json;
+use reqwest::{Client, RequestBuilder};
+use serde_json::{json, Value};
 
+use super::push_channels::PushChannels;
 use super::push_support::RelayDispatch;
-use super::{PushDeviceRegistration, PushEvent};
+use super::{PushBrokerState, PushDeviceRegistration, PushEvent};
 
 pub(super) struct PreparedDelivery {
     pub(super) relay_url: Option<String>,
@@ -10,33 +12,84 @@ pub(super) struct PreparedDelivery {
     pub(super) title: String,
     pub(super) body: String,
     pub(super) devices: Vec<PushDeviceRegistration>,
+    pub(super) channels: PushChannels,
     pub(super) timestamp_ms: i64,
 }
 
+/// A delivery backend that is not tied to registered devices, such as a
+/// webhook or an ntfy topic. Every prepared delivery goes to every configured
+/// channel.
+pub(super) trait PushChannel: Send + Sync {
+    /// Short name used in logs.
+    fn name(&self) -> &'static str;
+
+    /// Builds the request for one attempt. Called again for each retry.
+    fn request(&self, client: &Client, delivery: &PreparedDelivery) -> RequestBuilder;
+}
+
 impl PreparedDelivery {
-    pub(super) fn into_relay_dispatch(self) -> Option<RelayDispatch> {
-        let relay_url = self.relay_url?;
-        let payload = json!({
+    pub(super) fn new(
+        state: &PushBrokerState,
+        event: PushEvent,
+        devices: Vec<PushDeviceRegistration>,
+        timestamp_ms: i64,
+    ) -> Self {
+        let title = match event.kind {
+            "turn.error" => "Agent Error",
+            "turn.approvalRequired" => "Approval Needed",
+            "turn.digest" => "Agent Digest",
+            _ => "Agent Complete",
+        };
+        let title = event
+            .workspace_name
+            .as_ref()
+            .map(|name| format!("{title} — {name}"))
+            .unwrap_or_else(|| title.to_string());
+        Self {
+            relay_url: state.relay_url.clone(),
+            relay_auth_token: state.relay_auth_token.clone(),
+            body: event.preview.clone(),
+            event,
+            title,
+            devices,
+            channels: state.channels.clone(),
+            timestamp_ms,
+        }
+    }
+
+    /// The event as sent to the relay and to webhooks, without devices.
+    pub(super) fn notification_payload(&self) -> Value {
+        json!({
             "kind": self.event.kind,
             "workspaceId": self.event.workspace_id,
             "threadId": self.event.thread_id,
             "turnId": self.event.turn_id,
+            "requestId": self.event.request_id,
             "title": self.title,
             "body": self.body,
             "preview": self.event.preview,
             "timestampMs": self.timestamp_ms,
-            "devices": self.devices.into_iter().map(|device| {
+        })
+    }
+
+    pub(super) fn relay_dispatch(&self) -> Option<RelayDispatch> {
+        let relay_url = self.relay_url.clone()?;
+        let mut payload = self.notification_payload();
+        payload["devices"] = self
+            .devices
+            .iter()
+            .map(|device| {
                 json!({
                     "deviceId": device.device_id,
                     "platform": device.platform,
                     "token": device.token,
                     "label": device.label,
                 })
-            }).collect::<Vec<_>>()
-        });
+            })
+            .collect();
         Some(RelayDispatch {
             relay_url,
-            relay_auth_token: self.relay_auth_token,
+            relay_auth_token: self.relay_auth_token.clone(),
             payload,
         })
     }
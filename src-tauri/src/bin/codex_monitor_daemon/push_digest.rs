@@ -0,0 +1,270 @@
+//! Digest mode: with `digestWindowMs` set, completions and errors are held for
+//! the window and sent as one summary push, such as "5 agents finished,
+//! 1 failed in repo-x". Approvals always go out at once.
+
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use super::push_channels::PushChannels;
+use super::push_support::{clamp_preview, now_ms};
+use super::{PreparedDelivery, PushBroker, PushDeviceRegistration, PushEvent};
+
+/// Longest window `push_notification_config_patch` accepts.
+pub(super) const MAX_DIGEST_WINDOW_MS: u64 = 60 * 60 * 1_000;
+
+pub(super) fn is_digestible(kind: &str) -> bool {
+    matches!(kind, "turn.completed" | "turn.error")
+}
+
+/// One push for `events`, in arrival order. A single event is sent as is;
+/// otherwise a `turn.digest` summary links to the most recent thread. A turn
+/// that errored counts as failed even though it also completed.
+pub(super) fn summarize_digest(events: &[PushEvent]) -> Option<PushEvent> {
+    let latest = events.last()?;
+    if events.len() == 1 {
+        return Some(latest.clone());
+    }
+    let mut failed_by_turn: HashMap<(&str, &str, Option<&str>), bool> = HashMap::new();
+    for event in events {
+        let key = (
+            event.workspace_id.as_str(),
+            event.thread_id.as_str(),
+            event.turn_id.as_deref(),
+        );
+        *failed_by_turn.entry(key).or_default() |= event.kind == "turn.error";
+    }
+    let failed = failed_by_turn.values().filter(|failed| **failed).count();
+    let finished = failed_by_turn.len() - failed;
+    if failed_by_turn.len() == 1 {
+        return Some(latest.clone());
+    }
+
+    let mut counts = Vec::new();
+    if finished > 0 {
+        counts.push(format!("{finished} {} finished", agents(finished)));
+    }
+    if failed > 0 && finished > 0 {
+        counts.push(format!("{failed} failed"));
+    } else if failed > 0 {
+        counts.push(format!("{failed} {} failed", agents(failed)));
+    }
+    let workspaces = events
+        .iter()
+        .map(|event| event.workspace_id.as_str())
+        .collect::<HashSet<_>>();
+    let (workspace_name, location) = if workspaces.len() == 1 {
+        let name = latest
+            .workspace_name
+            .clone()
+            .unwrap_or_else(|| latest.workspace_id.clone());
+        (Some(name.clone()), name)
+    } else {
+        (None, format!("{} workspaces", workspaces.len()))
+    };
+    Some(PushEvent {
+        kind: "turn.digest",
+        workspace_id: latest.workspace_id.clone(),
+        workspace_name,
+        thread_id: latest.thread_id.clone(),
+        turn_id: latest.turn_id.clone(),
+        request_id: None,
+        turn_duration_ms: None,
+        preview: clamp_preview(format!("{} in {location}", counts.join(", "))),
+    })
+}
+
+/// Splits a digest by device rules: each enabled device gets a summary of only
+/// the events its rules allow, and devices allowed the same events share one
+/// push. Devices allowed none of them get nothing.
+pub(super) fn digests_by_device<'a>(
+    events: &[PushEvent],
+    devices: impl IntoIterator<Item = &'a PushDeviceRegistration>,
+    now_ms: i64,
+) -> Vec<(PushEvent, Vec<PushDeviceRegistration>)> {
+    let mut groups: Vec<(Vec<usize>, Vec<PushDeviceRegistration>)> = Vec::new();
+    for device in devices.into_iter().filter(|device| device.enabled) {
+        let allowed = events
+            .iter()
+            .enumerate()
+            .filter(|(_, event)| device.rules.allows(event, now_ms))
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        if allowed.is_empty() {
+            continue;
+        }
+        match groups.iter_mut().find(|(indices, _)| *indices == allowed) {
+            Some((_, group)) => group.push(device.clone()),
+            None => groups.push((allowed, vec![device.clone()])),
+        }
+    }
+    groups
+        .into_iter()
+        .filter_map(|(indices, devices)| {
+            let events = indices
+                .into_iter()
+                .map(|index| events[index].clone())
+                .collect::<Vec<_>>();
+            Some((summarize_digest(&events)?, devices))
+        })
+        .collect()
+}
+
+fn agents(count: usize) -> &'static str {
+    if count == 1 {
+        "agent"
+    } else {
+        "agents"
+    }
+}
+
+impl PushBroker {
+    /// Sends the digest once its window has passed since the first event.
+    /// Runs for the life of the daemon.
+    pub(crate) async fn run_digest(&self) {
+        loop {
+            self.digest_wake.notified().await;
+            let window_ms = self.state.lock().await.digest_window_ms.unwrap_or(0);
+            tokio::time::sleep(Duration::from_millis(window_ms)).await;
+            self.flush_digest().await;
+        }
+    }
+
+    /// Sends whatever the digest holds. Channels get a summary of all of it;
+    /// each device only of the events its rules allow.
+    pub(super) async fn flush_digest(&self) {
+        let deliveries = {
+            let now_ms = now_ms();
+            let mut state = self.state.lock().await;
+            let events = std::mem::take(&mut state.digest);
+            let mut deliveries = Vec::new();
+            if !state.channels.is_empty() {
+                if let Some(event) = summarize_digest(&events) {
+                    deliveries.push(PreparedDelivery::new(&state, event, Vec::new(), now_ms));
+                }
+            }
+            for (event, devices) in digests_by_device(&events, state.devices.values(), now_ms) {
+                let mut delivery = PreparedDelivery::new(&state, event, devices, now_ms);
+                delivery.channels = PushChannels::default();
+                deliveries.push(delivery);
+            }
+            deliveries
+        };
+        for delivery in deliveries {
+            self.dispatch(delivery).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{digests_by_device, summarize_digest, PushEvent};
+    use crate::push::push_rules::PushDeviceRules;
+    use crate::push::PushDeviceRegistration;
+
+    fn event(kind: &'static str, workspace: &str, thread_id: &str) -> PushEvent {
+        PushEvent {
+            kind,
+            workspace_id: format!("{workspace}-id"),
+            workspace_name: Some(workspace.to_string()),
+            thread_id: thread_id.to_string(),
+            turn_id: Some(format!("{thread_id}-turn")),
+            request_id: None,
+            turn_duration_ms: None,
+            preview: format!("{thread_id} done"),
+        }
+    }
+
+    #[test]
+    fn digest_counts_finished_and_failed_turns_and_links_the_latest() {
+        let events = vec![
+            event("turn.completed", "repo-x", "thread-1"),
+            event("turn.error", "repo-x", "thread-2"),
+            event("turn.completed", "repo-x", "thread-2"),
+            event("turn.completed", "repo-x", "thread-3"),
+        ];
+        let digest = summarize_digest(&events).expect("digest");
+        assert_eq!(digest.kind, "turn.digest");
+        assert_eq!(digest.preview, "2 agents finished, 1 failed in repo-x");
+        assert_eq!(digest.workspace_name.as_deref(), Some("repo-x"));
+        assert_eq!(digest.thread_id, "thread-3");
+
+        let events = vec![
+            event("turn.error", "repo-x", "thread-1"),
+            event("turn.completed", "repo-y", "thread-2"),
+        ];
+        let digest = summarize_digest(&events).expect("digest");
+        assert_eq!(digest.preview, "1 agent finished, 1 failed in 2 workspaces");
+        assert_eq!(digest.workspace_name, None);
+        assert_eq!(digest.workspace_id, "repo-y-id");
+
+        let events = vec![
+            event("turn.error", "repo-x", "thread-1"),
+            event("turn.error", "repo-x", "thread-2"),
+        ];
+        let digest = summarize_digest(&events).expect("digest");
+        assert_eq!(digest.preview, "2 agents failed in repo-x");
+    }
+
+    #[test]
+    fn a_lone_turn_is_pushed_as_is() {
+        assert!(summarize_digest(&[]).is_none());
+        let events = vec![
+            event("turn.error", "repo-x", "thread-1"),
+            event("turn.completed", "repo-x", "thread-1"),
+        ];
+        let digest = summarize_digest(&events).expect("digest");
+        assert_eq!(digest.kind, "turn.completed");
+        assert_eq!(digest.preview, "thread-1 done");
+    }
+
+    fn device(device_id: &str, workspace_ids: &[&str]) -> PushDeviceRegistration {
+        PushDeviceRegistration {
+            device_id: device_id.to_string(),
+            platform: "android".to_string(),
+            token: format!("{device_id}-token"),
+            label: None,
+            enabled: true,
+            last_seen_at_ms: None,
+            rules: PushDeviceRules {
+                workspace_ids: Some(workspace_ids.iter().map(|id| id.to_string()).collect()),
+                ..PushDeviceRules::default()
+            },
+            last_delivered_at_ms: None,
+            last_failed_at_ms: None,
+            last_error: None,
+        }
+    }
+
+    #[test]
+    fn each_device_gets_a_digest_of_only_its_workspaces() {
+        let events = vec![
+            event("turn.completed", "repo-x", "thread-1"),
+            event("turn.error", "repo-y", "thread-2"),
+            event("turn.completed", "repo-x", "thread-3"),
+            event("turn.completed", "repo-y", "thread-4"),
+        ];
+        let devices = [
+            device("phone", &["repo-x-id"]),
+            device("tablet", &["repo-y-id"]),
+            device("watch", &["repo-x-id"]),
+            device("laptop", &["repo-z-id"]),
+        ];
+        let digests = digests_by_device(&events, &devices, 0);
+        assert_eq!(digests.len(), 2);
+
+        let (digest, devices_x) = &digests[0];
+        let ids = devices_x
+            .iter()
+            .map(|device| device.device_id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, ["phone", "watch"]);
+        assert_eq!(digest.preview, "2 agents finished in repo-x");
+        assert_eq!(digest.thread_id, "thread-3");
+
+        let (digest, devices_y) = &digests[1];
+        assert_eq!(devices_y.len(), 1);
+        assert_eq!(devices_y[0].device_id, "tablet");
+        assert_eq!(digest.preview, "1 agent finished, 1 failed in repo-y");
+        assert_eq!(digest.workspace_name.as_deref(), Some("repo-y"));
+    }
+}
@@ -1,12 +1,13 @@
 use gcp_auth::{provider as gcp_provider, CustomServiceAccount, TokenProvider};
 use reqwest::Client;
-use serde_json::json;
+use serde_json::{json, Value};
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
-use super::PreparedDelivery;
+use super::push_support::{status_outcome, SendOutcome};
+use super::{PreparedDelivery, PushDeviceRegistration};
 
 const FCM_SCOPE: &str = "https://www.googleapis.com/auth/firebase.messaging";
 const DEFAULT_SERVICE_ACCOUNT_FILE: &str = "firebase-service-account.json";
@@ -33,22 +34,59 @@ impl DirectFcmSender {
         }
     }
 
-    pub(super) async fn deliver(&self, delivery: PreparedDelivery) {
+    /// The `messages:send` body for one device.
+    pub(super) fn message(delivery: &PreparedDelivery, device: &PushDeviceRegistration) -> Value {
+        let mut data = serde_json::Map::new();
+        data.insert("kind".to_string(), json!(delivery.event.kind));
+        data.insert(
+            "workspaceId".to_string(),
+            json!(delivery.event.workspace_id),
+        );
+        data.insert("threadId".to_string(), json!(delivery.event.thread_id));
+        data.insert(
+            "timestampMs".to_string(),
+            json!(delivery.timestamp_ms.to_string()),
+        );
+        if let Some(turn_id) = delivery.event.turn_id.as_ref() {
+            data.insert("turnId".to_string(), json!(turn_id));
+        }
+        // FCM data values are strings, so the id is sent JSON-encoded to
+        // keep numbers and strings apart.
+        if let Some(request_id) = delivery.event.request_id.as_ref() {
+            data.insert("requestId".to_string(), json!(request_id.to_string()));
+        }
+
+        json!({
+            "message": {
+                "token": device.token,
+                "notification": {
+                    "title": delivery.title,
+                    "body": delivery.body,
+                },
+                "data": data,
+                "android": {
+                    "priority": "HIGH"
+                },
+            }
+        })
+    }
+
+    pub(super) async fn send(&self, message: &Value) -> SendOutcome {
         let state = match self.ensure_state().await {
             Ok(state) => state,
             Err(err) => {
                 if !self.warned_unavailable.swap(true, Ordering::Relaxed) {
-                    eprintln!("daemon direct FCM unavailable: {err}");
+                    tracing::warn!("direct FCM unavailable: {err}");
                 }
-                return;
+                return SendOutcome::Skipped(format!("direct FCM unavailable: {err}"));
             }
         };
 
         let token = match state.provider.token(&[FCM_SCOPE]).await {
             Ok(token) => token,
             Err(err) => {
-                eprintln!("daemon direct FCM auth token failed: {err}");
-                return;
+                tracing::error!("direct FCM auth token failed: {err}");
+                return SendOutcome::Retry(format!("auth token failed: {err}"));
             }
         };
 
@@ -56,54 +94,21 @@ impl DirectFcmSender {
             "https://fcm.googleapis.com/v1/projects/{}/messages:send",
             state.project_id
         );
-        let access_token = token.as_str().to_string();
-
-        for device in delivery
-            .devices
-            .iter()
-            .filter(|device| matches!(device.platform.as_str(), "android" | "ios"))
+        match self
+            .http_client
+            .post(endpoint)
+            .bearer_auth(token.as_str())
+            .json(message)
+            .send()
+            .await
         {
-            let mut data = serde_json::Map::new();
-            data.insert("kind".to_string(), json!(delivery.event.kind));
-            data.insert("workspaceId".to_string(), json!(delivery.event.workspace_id));
-            data.insert("threadId".to_string(), json!(delivery.event.thread_id));
-            data.insert("timestampMs".to_string(), json!(delivery.timestamp_ms.to_string()));
-            if let Some(turn_id) = delivery.event.turn_id.as_ref() {
-                data.insert("turnId".to_string(), json!(turn_id));
-            }
-
-            let payload = json!({
-                "message": {
-                    "token": device.token,
-                    "notification": {
-                        "title": delivery.title,
-                        "body": delivery.body,
-                    },
-                    "data": data,
-                    "android": {
-                        "priority": "HIGH"
-                    },
-                }
-            });
-
-            match self
-                .http_client
-                .post(endpoint.clone())
-                .bearer_auth(access_token.as_str())
-                .json(&payload)
-                .send()
-                .await
-            {
-                Ok(response) if response.status().is_success() => {}
-                Ok(response) => {
-                    let status = response.status().as_u16();
-                    let body = response.text().await.unwrap_or_default();
-                    eprintln!("daemon direct FCM send failed: status {status}, body {body}");
-                }
-                Err(err) => {
-                    eprintln!("daemon direct FCM request failed: {err}");
-                }
+            Ok(response) if response.status().is_success() => SendOutcome::Delivered,
+            Ok(response) => {
+                let status = response.status().as_u16();
+                let body = response.text().await.unwrap_or_default();
+                fcm_outcome(status, body)
             }
+            Err(err) => SendOutcome::Retry(err.to_string()),
         }
     }
 
@@ -153,3 +158,39 @@ impl DirectFcmSender {
         })
     }
 }
+
+/// FCM answers `404 NOT_FOUND` with an `UNREGISTERED` error code once a token
+/// is gone for good; the device has to register again.
+fn fcm_outcome(status: u16, body: String) -> SendOutcome {
+    let error = format!("status {status}, body {body}");
+    if status == 404 || body.contains("UNREGISTERED") {
+        SendOutcome::Unregistered(error)
+    } else {
+        status_outcome(status, error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fcm_outcome, SendOutcome};
+
+    #[test]
+    fn unregistered_tokens_are_told_apart_from_other_failures() {
+        let unregistered = r#"{"error":{"code":404,"status":"NOT_FOUND","details":[{"errorCode":"UNREGISTERED"}]}}"#;
+        assert!(matches!(
+            fcm_outcome(404, unregistered.to_string()),
+            SendOutcome::Unregistered(_)
+        ));
+        assert!(matches!(
+            fcm_outcome(503, String::new()),
+            SendOutcome::Retry(_)
+        ));
+        assert!(matches!(
+            fcm_outcome(
+                400,
+                r#"{"error":{"status":"INVALID_ARGUMENT"}}"#.to_string()
+            ),
+            SendOutcome::Rejected(_)
+        ));
+    }
+}
@@ -0,0 +1,284 @@
+//! Persistent queue of relay and direct FCM sends. Every send is written to
+//! `push_outbox.json` before the first attempt and retried with exponential
+//! backoff, so a network blip or a daemon restart does not lose a push.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tokio::sync::{Mutex, Notify};
+
+use super::push_support::{now_ms, send_to_relay, RelayDispatch, SendOutcome};
+use super::PushBroker;
+use crate::storage::write_private_file;
+
+const PUSH_OUTBOX_FILE: &str = "push_outbox.json";
+const BACKOFF_BASE_MS: i64 = 1_000;
+const BACKOFF_MAX_MS: i64 = 10 * 60 * 1_000;
+/// Roughly half an hour of retries before a send is given up.
+const MAX_ATTEMPTS: u32 = 12;
+/// Oldest sends are dropped past this, so a long outage cannot grow the file
+/// without bound.
+const MAX_QUEUED: usize = 500;
+const IDLE_WAIT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "target", rename_all = "camelCase")]
+pub(super) enum OutboxTarget {
+    Relay(RelayDispatch),
+    Fcm(FcmDispatch),
+}
+
+/// One direct FCM send: the full `messages:send` body for one device.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct FcmDispatch {
+    pub(super) device_id: String,
+    pub(super) message: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct OutboxEntry {
+    id: String,
+    target: OutboxTarget,
+    #[serde(default)]
+    attempts: u32,
+    next_attempt_at_ms: i64,
+}
+
+pub(super) struct PushOutbox {
+    path: PathBuf,
+    entries: Mutex<Vec<OutboxEntry>>,
+    wake: Notify,
+}
+
+impl OutboxTarget {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Relay(_) => "relay",
+            Self::Fcm(_) => "direct FCM",
+        }
+    }
+
+    /// Devices this send is for, to record receipts against.
+    fn device_ids(&self) -> Vec<String> {
+        match self {
+            Self::Relay(dispatch) => dispatch
+                .payload
+                .get("devices")
+                .and_then(Value::as_array)
+                .map(|devices| {
+                    devices
+                        .iter()
+                        .filter_map(|device| device.get("deviceId").and_then(Value::as_str))
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            Self::Fcm(dispatch) => vec![dispatch.device_id.clone()],
+        }
+    }
+}
+
+impl PushOutbox {
+    pub(super) fn load(data_dir: &Path) -> Self {
+        let path = data_dir.join(PUSH_OUTBOX_FILE);
+        let entries = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|data| serde_json::from_str::<Vec<OutboxEntry>>(&data).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            entries: Mutex::new(entries),
+            wake: Notify::new(),
+        }
+    }
+
+    pub(super) async fn enqueue(&self, targets: Vec<OutboxTarget>) {
+        if targets.is_empty() {
+            return;
+        }
+        let now_ms = now_ms();
+        let mut entries = self.entries.lock().await;
+        entries.extend(targets.into_iter().map(|target| OutboxEntry {
+            id: uuid::Uuid::new_v4().to_string(),
+            target,
+            attempts: 0,
+            next_attempt_at_ms: now_ms,
+        }));
+        if entries.len() > MAX_QUEUED {
+            let dropped = entries.len() - MAX_QUEUED;
+            entries.drain(..dropped);
+            tracing::warn!("push outbox full, dropped {dropped} oldest sends");
+        }
+        self.persist(&entries);
+        drop(entries);
+        self.wake.notify_one();
+    }
+
+    async fn due(&self, now_ms: i64) -> Vec<OutboxEntry> {
+        let entries = self.entries.lock().await;
+        entries
+            .iter()
+            .filter(|entry| entry.next_attempt_at_ms <= now_ms)
+            .cloned()
+            .collect()
+    }
+
+    /// How long until the next send is due, if any is queued.
+    async fn next_wait(&self, now_ms: i64) -> Option<Duration> {
+        let entries = self.entries.lock().await;
+        entries
+            .iter()
+            .map(|entry| entry.next_attempt_at_ms)
+            .min()
+            .map(|due_ms| Duration::from_millis(due_ms.saturating_sub(now_ms).max(0) as u64))
+    }
+
+    /// Removes a finished send, or schedules the next attempt. Returns whether
+    /// the send is still queued.
+    async fn settle(&self, id: &str, retry: bool, now_ms: i64) -> bool {
+        let mut entries = self.entries.lock().await;
+        let Some(index) = entries.iter().position(|entry| entry.id == id) else {
+            return false;
+        };
+        let entry = &mut entries[index];
+        entry.attempts += 1;
+        let requeued = retry && entry.attempts < MAX_ATTEMPTS;
+        if requeued {
+            entry.next_attempt_at_ms = now_ms + backoff_ms(entry.attempts);
+        } else {
+            entries.remove(index);
+        }
+        self.persist(&entries);
+        requeued
+    }
+
+    fn persist(&self, entries: &[OutboxEntry]) {
+        let result = serde_json::to_string_pretty(entries)
+            .map_err(|err| err.to_string())
+            .and_then(|rendered| {
+                if let Some(parent) = self.path.parent() {
+                    std::fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+                }
+                // Entries carry device tokens and relay credentials.
+                write_private_file(&self.path, rendered.as_bytes())
+            });
+        if let Err(err) = result {
+            tracing::warn!("push outbox write failed: {err}");
+        }
+    }
+}
+
+/// Delay before retry number `attempts`: 1s, 2s, 4s, ... capped at 10 minutes.
+pub(super) fn backoff_ms(attempts: u32) -> i64 {
+    BACKOFF_BASE_MS
+        .saturating_mul(1_i64 << attempts.saturating_sub(1).min(20))
+        .min(BACKOFF_MAX_MS)
+}
+
+impl PushBroker {
+    /// Sends queued pushes as they come due. Runs for the life of the daemon.
+    pub(crate) async fn run_outbox(&self) {
+        loop {
+            let due = self.outbox.due(now_ms()).await;
+            if !due.is_empty() {
+                self.in_flight.fetch_add(1, Ordering::SeqCst);
+                futures_util::future::join_all(due.into_iter().map(|entry| self.attempt(entry)))
+                    .await;
+                self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            }
+            let wait = self.outbox.next_wait(now_ms()).await.unwrap_or(IDLE_WAIT);
+            let _ = tokio::time::timeout(wait, self.outbox.wake.notified()).await;
+        }
+    }
+
+    async fn attempt(&self, entry: OutboxEntry) {
+        let outcome = match &entry.target {
+            OutboxTarget::Relay(dispatch) => send_to_relay(&self.http_client, dispatch).await,
+            OutboxTarget::Fcm(dispatch) => self.direct_fcm.send(&dispatch.message).await,
+        };
+        let now_ms = now_ms();
+        let retry = matches!(outcome, SendOutcome::Retry(_));
+        let requeued = self.outbox.settle(&entry.id, retry, now_ms).await;
+        let error = match &outcome {
+            SendOutcome::Delivered => None,
+            SendOutcome::Skipped(error) => Some(error.clone()),
+            SendOutcome::Retry(error)
+            | SendOutcome::Rejected(error)
+            | SendOutcome::Unregistered(error) => {
+                if !requeued {
+                    tracing::warn!("push {} failed: {error}", entry.target.name());
+                }
+                Some(error.clone())
+            }
+        };
+        if !requeued && !matches!(outcome, SendOutcome::Skipped(_)) {
+            self.delivery_stats.record(error.is_none());
+        }
+        if let (SendOutcome::Unregistered(_), OutboxTarget::Fcm(dispatch)) =
+            (&outcome, &entry.target)
+        {
+            self.prune_unregistered_device(dispatch).await;
+            return;
+        }
+        self.record_receipt(&entry.target.device_ids(), error, now_ms)
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{backoff_ms, FcmDispatch, OutboxTarget, PushOutbox, PUSH_OUTBOX_FILE};
+    use serde_json::json;
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        assert_eq!(backoff_ms(1), 1_000);
+        assert_eq!(backoff_ms(2), 2_000);
+        assert_eq!(backoff_ms(5), 16_000);
+        assert_eq!(backoff_ms(10), 512_000);
+        assert_eq!(backoff_ms(11), 600_000);
+        assert_eq!(backoff_ms(64), 600_000);
+    }
+
+    #[test]
+    fn queued_sends_survive_a_reload_until_settled() {
+        let dir = std::env::temp_dir().join(format!("push-outbox-{}", uuid::Uuid::new_v4()));
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("runtime");
+        runtime.block_on(async {
+            let outbox = PushOutbox::load(&dir);
+            outbox
+                .enqueue(vec![OutboxTarget::Fcm(FcmDispatch {
+                    device_id: "phone-1".to_string(),
+                    message: json!({ "message": { "token": "fcm-token" } }),
+                })])
+                .await;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                let mode = std::fs::metadata(dir.join(PUSH_OUTBOX_FILE))
+                    .expect("metadata")
+                    .permissions()
+                    .mode();
+                assert_eq!(mode & 0o777, 0o600);
+            }
+
+            let reloaded = PushOutbox::load(&dir);
+            let due = reloaded.due(i64::MAX).await;
+            assert_eq!(due.len(), 1);
+            assert!(reloaded.settle(&due[0].id, true, 0).await);
+            assert!(reloaded.due(0).await.is_empty());
+            assert_eq!(reloaded.due(1_000).await.len(), 1);
+
+            assert!(!reloaded.settle(&due[0].id, false, 0).await);
+            assert!(PushOutbox::load(&dir).due(i64::MAX).await.is_empty());
+        });
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,349 @@
+//! Per-device notification rules, evaluated by `prepare_delivery` for each
+//! registered device. Channels are not devices and ignore these rules.
+
+use chrono::{TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use super::push_support::default_true;
+use super::PushEvent;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PushDeviceRules {
+    /// Workspaces this device wants pushes for; `None` means all of them.
+    #[serde(default)]
+    pub(crate) workspace_ids: Option<Vec<String>>,
+    #[serde(default)]
+    pub(crate) kinds: PushKindToggles,
+    #[serde(default)]
+    pub(crate) quiet_hours: Option<QuietHours>,
+    /// Turns that finish faster than this do not push `turn.completed`.
+    #[serde(default)]
+    pub(crate) min_turn_duration_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PushKindToggles {
+    #[serde(default = "default_true")]
+    pub(crate) completed: bool,
+    #[serde(default = "default_true")]
+    pub(crate) error: bool,
+    #[serde(default = "default_true")]
+    pub(crate) approval: bool,
+}
+
+impl Default for PushKindToggles {
+    fn default() -> Self {
+        Self {
+            completed: true,
+            error: true,
+            approval: true,
+        }
+    }
+}
+
+/// A daily `HH:MM` window, in the device's time zone, with no pushes at all.
+/// `end` before `start` wraps past midnight.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct QuietHours {
+    pub(crate) start: String,
+    pub(crate) end: String,
+    /// IANA name such as `Europe/Berlin`, so the window follows DST changes.
+    #[serde(default)]
+    pub(crate) time_zone: String,
+}
+
+/// `push_device_rules_patch` input. Omitted fields are left alone; `null`
+/// clears `workspaceIds`, `quietHours` and `minTurnDurationMs`.
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PushDeviceRulesPatch {
+    #[serde(default, deserialize_with = "patch_field")]
+    pub(crate) workspace_ids: Option<Option<Vec<String>>>,
+    #[serde(default)]
+    pub(crate) kinds: Option<PushKindTogglesPatch>,
+    #[serde(default, deserialize_with = "patch_field")]
+    pub(crate) quiet_hours: Option<Option<QuietHours>>,
+    #[serde(default, deserialize_with = "patch_field")]
+    pub(crate) min_turn_duration_ms: Option<Option<u64>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PushKindTogglesPatch {
+    #[serde(default)]
+    pub(crate) completed: Option<bool>,
+    #[serde(default)]
+    pub(crate) error: Option<bool>,
+    #[serde(default)]
+    pub(crate) approval: Option<bool>,
+}
+
+impl PushDeviceRules {
+    /// Applies `patch`, leaving the rules untouched when it is invalid.
+    pub(super) fn apply(&mut self, patch: PushDeviceRulesPatch) -> Result<(), String> {
+        let mut next = self.clone();
+        if let Some(workspace_ids) = patch.workspace_ids {
+            next.workspace_ids = workspace_ids
+                .map(|ids| {
+                    ids.into_iter()
+                        .map(|id| id.trim().to_string())
+                        .filter(|id| !id.is_empty())
+                        .collect::<Vec<_>>()
+                })
+                .filter(|ids| !ids.is_empty());
+        }
+        if let Some(kinds) = patch.kinds {
+            next.kinds.completed = kinds.completed.unwrap_or(next.kinds.completed);
+            next.kinds.error = kinds.error.unwrap_or(next.kinds.error);
+            next.kinds.approval = kinds.approval.unwrap_or(next.kinds.approval);
+        }
+        if let Some(quiet_hours) = patch.quiet_hours {
+            next.quiet_hours = quiet_hours.map(QuietHours::normalized).transpose()?;
+        }
+        if let Some(min_turn_duration_ms) = patch.min_turn_duration_ms {
+            next.min_turn_duration_ms = min_turn_duration_ms.filter(|value| *value > 0);
+        }
+        *self = next;
+        Ok(())
+    }
+
+    /// Whether a device with these rules should get `event` at `now_ms`.
+    pub(super) fn allows(&self, event: &PushEvent, now_ms: i64) -> bool {
+        let kind_enabled = match event.kind {
+            "turn.completed" => self.kinds.completed,
+            "turn.error" => self.kinds.error,
+            "turn.approvalRequired" => self.kinds.approval,
+            _ => true,
+        };
+        if !kind_enabled {
+            return false;
+        }
+        if let Some(workspace_ids) = self.workspace_ids.as_ref() {
+            if !workspace_ids.contains(&event.workspace_id) {
+                return false;
+            }
+        }
+        if event.kind == "turn.completed" {
+            if let (Some(min), Some(duration)) = (self.min_turn_duration_ms, event.turn_duration_ms)
+            {
+                if duration < min as i64 {
+                    return false;
+                }
+            }
+        }
+        !self
+            .quiet_hours
+            .as_ref()
+            .is_some_and(|quiet_hours| quiet_hours.contains(now_ms))
+    }
+}
+
+impl QuietHours {
+    fn normalized(self) -> Result<Self, String> {
+        let start = parse_minute_of_day(&self.start)
+            .ok_or_else(|| "`quietHours.start` must be `HH:MM`".to_string())?;
+        let end = parse_minute_of_day(&self.end)
+            .ok_or_else(|| "`quietHours.end` must be `HH:MM`".to_string())?;
+        if start == end {
+            return Err("`quietHours.start` and `quietHours.end` must differ".to_string());
+        }
+        let time_zone = self.time_zone.trim();
+        if time_zone.is_empty() {
+            return Err("missing `quietHours.timeZone`".to_string());
+        }
+        let time_zone = time_zone.parse::<Tz>().map_err(|_| {
+            format!("`quietHours.timeZone` must be an IANA time zone, not `{time_zone}`")
+        })?;
+        Ok(Self {
+            start: format_minute_of_day(start),
+            end: format_minute_of_day(end),
+            time_zone: time_zone.name().to_string(),
+        })
+    }
+
+    fn contains(&self, now_ms: i64) -> bool {
+        let (Some(start), Some(end)) = (
+            parse_minute_of_day(&self.start),
+            parse_minute_of_day(&self.end),
+        ) else {
+            return false;
+        };
+        let (Ok(time_zone), Some(now)) = (
+            self.time_zone.parse::<Tz>(),
+            Utc.timestamp_millis_opt(now_ms).single(),
+        ) else {
+            return false;
+        };
+        let now = now.with_timezone(&time_zone);
+        let local_minute = i64::from(now.hour() * 60 + now.minute());
+        if start < end {
+            start <= local_minute && local_minute < end
+        } else {
+            local_minute >= start || local_minute < end
+        }
+    }
+}
+
+fn parse_minute_of_day(value: &str) -> Option<i64> {
+    let (hours, minutes) = value.trim().split_once(':')?;
+    let hours = hours.parse::<i64>().ok()?;
+    let minutes = minutes.parse::<i64>().ok()?;
+    ((0..24).contains(&hours) && (0..60).contains(&minutes)).then_some(hours * 60 + minutes)
+}
+
+fn format_minute_of_day(minute: i64) -> String {
+    format!("{:02}:{:02}", minute / 60, minute % 60)
+}
+
+/// Keeps an explicit `null` apart from a missing field: `Some(None)` clears.
+fn patch_field<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PushDeviceRules, PushDeviceRulesPatch, PushEvent};
+    use serde_json::json;
+
+    fn event(kind: &'static str, workspace_id: &str, turn_duration_ms: Option<i64>) -> PushEvent {
+        PushEvent {
+            kind,
+            workspace_id: workspace_id.to_string(),
+            workspace_name: None,
+            thread_id: "thread-1".to_string(),
+            turn_id: None,
+            request_id: None,
+            turn_duration_ms,
+            preview: String::new(),
+        }
+    }
+
+    fn patch(value: serde_json::Value) -> PushDeviceRulesPatch {
+        serde_json::from_value(value).expect("patch")
+    }
+
+    // 2026-01-01T23:30:00Z
+    const LATE_EVENING_UTC_MS: i64 = 1_767_310_200_000;
+
+    #[test]
+    fn default_rules_allow_everything() {
+        let rules = PushDeviceRules::default();
+        for kind in ["turn.completed", "turn.error", "turn.approvalRequired"] {
+            assert!(rules.allows(&event(kind, "ws-1", Some(10)), LATE_EVENING_UTC_MS));
+        }
+    }
+
+    #[test]
+    fn rules_filter_by_workspace_kind_and_turn_duration() {
+        let mut rules = PushDeviceRules::default();
+        rules
+            .apply(patch(json!({
+                "workspaceIds": ["ws-1", " "],
+                "kinds": { "error": false },
+                "minTurnDurationMs": 60_000,
+            })))
+            .expect("valid patch");
+        assert_eq!(rules.workspace_ids, Some(vec!["ws-1".to_string()]));
+
+        let now = LATE_EVENING_UTC_MS;
+        assert!(!rules.allows(&event("turn.completed", "ws-2", None), now));
+        assert!(!rules.allows(&event("turn.error", "ws-1", None), now));
+        assert!(rules.allows(&event("turn.approvalRequired", "ws-1", None), now));
+        assert!(!rules.allows(&event("turn.completed", "ws-1", Some(5_000)), now));
+        assert!(rules.allows(&event("turn.completed", "ws-1", Some(90_000)), now));
+        assert!(rules.allows(&event("turn.completed", "ws-1", None), now));
+
+        rules
+            .apply(patch(
+                json!({ "workspaceIds": null, "minTurnDurationMs": null }),
+            ))
+            .expect("valid patch");
+        assert_eq!(rules.workspace_ids, None);
+        assert_eq!(rules.min_turn_duration_ms, None);
+        assert!(!rules.kinds.error);
+    }
+
+    #[test]
+    fn quiet_hours_use_the_device_time_zone_and_wrap_past_midnight() {
+        let mut rules = PushDeviceRules::default();
+        rules
+            .apply(patch(json!({
+                "quietHours": { "start": "22:00", "end": "7:00", "timeZone": "UTC" },
+            })))
+            .expect("valid patch");
+        assert_eq!(
+            rules.quiet_hours.as_ref().expect("quiet hours").end,
+            "07:00"
+        );
+        let completed = event("turn.completed", "ws-1", None);
+        assert!(!rules.allows(&completed, LATE_EVENING_UTC_MS));
+
+        // 23:30 UTC is 15:30 in Los Angeles in January.
+        rules
+            .apply(patch(json!({
+                "quietHours": { "start": "22:00", "end": "07:00", "timeZone": "America/Los_Angeles" },
+            })))
+            .expect("valid patch");
+        assert!(rules.allows(&completed, LATE_EVENING_UTC_MS));
+
+        let err = rules
+            .apply(patch(
+                json!({ "quietHours": { "start": "25:00", "end": "07:00", "timeZone": "UTC" } }),
+            ))
+            .expect_err("invalid start");
+        assert_eq!(err, "`quietHours.start` must be `HH:MM`");
+        let err = rules
+            .apply(patch(
+                json!({ "quietHours": { "start": "22:00", "end": "07:00" } }),
+            ))
+            .expect_err("missing time zone");
+        assert_eq!(err, "missing `quietHours.timeZone`");
+        let err = rules
+            .apply(patch(
+                json!({ "quietHours": { "start": "22:00", "end": "07:00", "timeZone": "UTC-7" } }),
+            ))
+            .expect_err("offsets are not zones");
+        assert_eq!(
+            err,
+            "`quietHours.timeZone` must be an IANA time zone, not `UTC-7`"
+        );
+        assert_eq!(
+            rules.quiet_hours.as_ref().expect("quiet hours").time_zone,
+            "America/Los_Angeles"
+        );
+
+        rules
+            .apply(patch(json!({ "quietHours": null })))
+            .expect("valid patch");
+        assert_eq!(rules.quiet_hours, None);
+    }
+
+    #[test]
+    fn quiet_hours_follow_daylight_saving_changes() {
+        let mut rules = PushDeviceRules::default();
+        rules
+            .apply(patch(json!({
+                "quietHours": { "start": "22:00", "end": "07:00", "timeZone": "Europe/Berlin" },
+            })))
+            .expect("valid patch");
+        let completed = event("turn.completed", "ws-1", None);
+
+        // 2026-03-29T05:30:00Z, after clocks went forward: 07:30 CEST.
+        assert!(rules.allows(&completed, 1_774_762_200_000));
+        // 2026-03-28T05:30:00Z: still CET, 06:30 local, so muted.
+        assert!(!rules.allows(&completed, 1_774_675_800_000));
+        // 2026-10-25T05:30:00Z: back on CET after 01:00 UTC, 06:30 local.
+        assert!(!rules.allows(&completed, 1_792_906_200_000));
+        // 2026-10-24T05:30:00Z: still CEST, 07:30 local.
+        assert!(rules.allows(&completed, 1_792_819_800_000));
+    }
+}
@@ -1,36 +1,110 @@
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder};
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Map, Value};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use super::push_delivery::{PreparedDelivery, PushChannel};
 use super::{
-    PersistedPushState, PushBrokerState, PushEvent, DEDUPE_WINDOW_MS, HEARTBEAT_STALE_MS,
-    MAX_PREVIEW_CHARS,
+    PersistedPushState, PushBrokerState, PushEvent, APPROVAL_DEDUPE_WINDOW_MS, DEDUPE_WINDOW_MS,
+    HEARTBEAT_STALE_MS, MAX_PREVIEW_CHARS, TURN_START_STALE_MS,
 };
 
 const RETRY_DELAYS_MS: [u64; 3] = [250, 1_000, 3_000];
+const MAX_PREVIEW_PATHS: usize = 3;
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub(super) struct RelayDispatch {
     pub(super) relay_url: String,
     pub(super) relay_auth_token: Option<String>,
     pub(super) payload: Value,
 }
 
-pub(super) async fn deliver_to_relay(client: Client, dispatch: RelayDispatch) {
+/// The result of one send attempt.
+pub(super) enum SendOutcome {
+    Delivered,
+    /// A network error or a status worth retrying: 408, 429 or 5xx.
+    Retry(String),
+    /// Any other rejection; retrying would not help.
+    Rejected(String),
+    /// FCM no longer knows the device token.
+    Unregistered(String),
+    /// Nothing could be sent, e.g. direct FCM has no credentials. Not counted
+    /// in the delivery stats and already logged once by the sender.
+    Skipped(String),
+}
+
+/// Delivery counts reported by `daemon_metrics`. A queued or channel send
+/// counts once however many retries it takes.
+#[derive(Default)]
+pub(crate) struct PushDeliveryStats {
+    attempted: AtomicU64,
+    failed: AtomicU64,
+}
+
+impl PushDeliveryStats {
+    pub(super) fn record(&self, delivered: bool) {
+        self.attempted.fetch_add(1, Ordering::Relaxed);
+        if !delivered {
+            self.failed.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn attempted(&self) -> u64 {
+        self.attempted.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn failed(&self) -> u64 {
+        self.failed.load(Ordering::Relaxed)
+    }
+}
+
+pub(super) async fn send_to_relay(client: &Client, dispatch: &RelayDispatch) -> SendOutcome {
+    let request = client
+        .post(dispatch.relay_url.clone())
+        .json(&dispatch.payload);
+    let request = if let Some(token) = dispatch.relay_auth_token.as_deref() {
+        request.bearer_auth(token)
+    } else {
+        request
+    };
+    match request.send().await {
+        Ok(response) if response.status().is_success() => SendOutcome::Delivered,
+        Ok(response) => {
+            let status = response.status().as_u16();
+            status_outcome(status, format!("relay status {status}"))
+        }
+        Err(err) => SendOutcome::Retry(err.to_string()),
+    }
+}
+
+pub(super) fn status_outcome(status: u16, error: String) -> SendOutcome {
+    if status == 408 || status == 429 || status >= 500 {
+        SendOutcome::Retry(error)
+    } else {
+        SendOutcome::Rejected(error)
+    }
+}
+
+pub(super) async fn deliver_to_channel(
+    client: &Client,
+    channel: &dyn PushChannel,
+    delivery: &PreparedDelivery,
+    stats: &PushDeliveryStats,
+) {
+    let delivered = send_with_retries(channel.name(), || channel.request(client, delivery)).await;
+    stats.record(delivered);
+}
+
+async fn send_with_retries(name: &str, build_request: impl Fn() -> RequestBuilder) -> bool {
     let mut last_error: Option<String> = None;
     for (attempt, delay_ms) in RETRY_DELAYS_MS.iter().enumerate() {
-        let request = client
-            .post(dispatch.relay_url.clone())
-            .json(&dispatch.payload);
-        let request = if let Some(token) = dispatch.relay_auth_token.as_deref() {
-            request.bearer_auth(token)
-        } else {
-            request
-        };
-        match request.send().await {
-            Ok(response) if response.status().is_success() => return,
+        match build_request().send().await {
+            Ok(response) if response.status().is_success() => return true,
             Ok(response) => {
-                last_error = Some(format!("relay status {}", response.status().as_u16()));
+                last_error = Some(format!("{name} status {}", response.status().as_u16()));
             }
             Err(err) => {
                 last_error = Some(err.to_string());
@@ -41,8 +115,9 @@ pub(super) async fn deliver_to_relay(client: Client, dispatch: RelayDispatch) {
         }
     }
     if let Some(last_error) = last_error {
-        eprintln!("daemon push relay failed: {last_error}");
+        tracing::warn!("push {name} failed: {last_error}");
     }
+    false
 }
 
 pub(super) fn read_state_file(path: &PathBuf) -> Result<PersistedPushState, String> {
@@ -58,8 +133,24 @@ pub(super) fn prune_stale_entries(state: &mut PushBrokerState, now_ms: i64) {
         .presence_by_client
         .retain(|_, entry| now_ms - entry.last_seen_at_ms <= HEARTBEAT_STALE_MS);
     state
-        .dedupe_sent_at
-        .retain(|_, sent_at_ms| now_ms - *sent_at_ms <= DEDUPE_WINDOW_MS);
+        .dedupe_until_ms
+        .retain(|_, until_ms| now_ms < *until_ms);
+    // Items whose completion was never seen, e.g. the app-server restarted.
+    state
+        .file_changes_by_item
+        .retain(|_, change| now_ms - change.captured_at_ms <= APPROVAL_DEDUPE_WINDOW_MS);
+    // Turns whose completion was never seen.
+    state
+        .turn_started_at_by_thread
+        .retain(|_, started_at_ms| now_ms - *started_at_ms <= TURN_START_STALE_MS);
+}
+
+pub(super) fn dedupe_window_ms(kind: &str) -> i64 {
+    if kind == "turn.approvalRequired" {
+        APPROVAL_DEDUPE_WINDOW_MS
+    } else {
+        DEDUPE_WINDOW_MS
+    }
 }
 
 pub(super) fn has_non_afk_desktop_for_workspace(
@@ -128,13 +219,84 @@ pub(super) fn make_thread_key(workspace_id: &str, thread_id: &str) -> String {
 }
 
 pub(super) fn make_dedupe_key(event: &PushEvent) -> String {
-    format!(
+    let mut key = format!(
         "{}:{}:{}:{}",
         event.kind,
         event.workspace_id,
         event.thread_id,
         event.turn_id.clone().unwrap_or_else(|| "-".to_string())
-    )
+    );
+    if let Some(request_id) = event.request_id.as_ref() {
+        key.push(':');
+        key.push_str(&request_id.to_string());
+    }
+    key
+}
+
+/// Exec and patch approval requests: `item/*/requestApproval` and the legacy
+/// `execCommandApproval`/`applyPatchApproval`.
+pub(super) fn is_approval_request(method: &str) -> bool {
+    method.ends_with("requestApproval")
+        || method == "execCommandApproval"
+        || method == "applyPatchApproval"
+}
+
+/// Summarizes `fileChange` changes (`[{ path, .. }]`) or legacy
+/// `fileChanges` (`{ path: change }`) as a short list of paths.
+pub(super) fn file_change_preview(changes: &Value) -> Option<String> {
+    let paths: Vec<&str> = match changes {
+        Value::Array(entries) => entries
+            .iter()
+            .filter_map(|entry| entry.get("path").and_then(Value::as_str))
+            .collect(),
+        Value::Object(entries) => entries.keys().map(String::as_str).collect(),
+        _ => return None,
+    };
+    if paths.is_empty() {
+        return None;
+    }
+    let listed = paths
+        .iter()
+        .take(MAX_PREVIEW_PATHS)
+        .copied()
+        .collect::<Vec<_>>()
+        .join(", ");
+    Some(match paths.len().checked_sub(MAX_PREVIEW_PATHS) {
+        Some(more) if more > 0 => format!("Apply changes to {listed} and {more} more"),
+        _ => format!("Apply changes to {listed}"),
+    })
+}
+
+/// The command to run, else the patch summary, else the agent's reason.
+pub(super) fn approval_preview(
+    params: &Map<String, Value>,
+    file_changes: Option<String>,
+) -> String {
+    let command = match params.get("command") {
+        Some(Value::String(command)) => Some(command.trim().to_string()),
+        Some(Value::Array(tokens)) => Some(
+            tokens
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+        _ => None,
+    }
+    .filter(|command| !command.is_empty());
+    if let Some(command) = command {
+        return format!("Run `{command}`");
+    }
+    if let Some(file_changes) = file_changes {
+        return file_changes;
+    }
+    params
+        .get("reason")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|reason| !reason.is_empty())
+        .unwrap_or("Your agent is waiting for approval.")
+        .to_string()
 }
 
 pub(super) fn config_snapshot_value(state: &PushBrokerState) -> Value {
@@ -146,6 +308,8 @@ pub(super) fn config_snapshot_value(state: &PushBrokerState) -> Value {
             .map(|value| !value.trim().is_empty())
             .unwrap_or(false),
         "registeredDeviceCount": state.devices.len(),
+        "channels": state.channels.snapshot(),
+        "digestWindowMs": state.digest_window_ms,
     })
 }
 
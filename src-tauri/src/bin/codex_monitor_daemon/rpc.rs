@@ -1,5 +1,7 @@
 use super::*;
 
+#[path = "rpc/catalog.rs"]
+pub(super) mod catalog;
 #[path = "rpc/codex.rs"]
 mod codex;
 #[path = "rpc/daemon.rs"]
@@ -10,17 +12,37 @@ mod dispatcher;
 mod git;
 #[path = "rpc/prompts.rs"]
 mod prompts;
+#[path = "rpc/scope.rs"]
+pub(super) mod scope;
+#[path = "rpc/terminal.rs"]
+mod terminal;
 #[path = "rpc/workspace.rs"]
 mod workspace;
 
-pub(super) fn build_error_response(id: Option<u64>, message: &str) -> Option<String> {
+use crate::auth_tokens::TokenRole;
+use crate::shared::core_error::{CoreError, ErrorCode};
+use crate::shared::rpc_schema;
+use event_replay::ReplaySnapshot;
+use event_subscription::EventSubscription;
+use scope::ClientScope;
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+use transport::OutboundMessage;
+
+/// Result of methods that only report success.
+#[derive(Debug, Serialize, JsonSchema)]
+pub(super) struct OkResponse {
+    ok: bool,
+}
+
+impl OkResponse {
+    pub(super) const OK: Self = Self { ok: true };
+}
+
+pub(super) fn build_error_response(id: Option<u64>, error: &CoreError) -> Option<String> {
     let id = id?;
     Some(
-        serde_json::to_string(&json!({
-            "id": id,
-            "error": { "message": message }
-        }))
-        .unwrap_or_else(|_| {
+        serde_json::to_string(&rpc_schema::error_response(id, error)).unwrap_or_else(|_| {
             "{\"id\":0,\"error\":{\"message\":\"serialization failed\"}}".to_string()
         }),
     )
@@ -29,52 +51,34 @@ pub(super) fn build_error_response(id: Option<u64>, message: &str) -> Option<Str
 pub(super) fn build_result_response(id: Option<u64>, result: Value) -> Option<String> {
     let id = id?;
     Some(
-        serde_json::to_string(&json!({ "id": id, "result": result })).unwrap_or_else(|_| {
+        serde_json::to_string(&rpc_schema::result_response(id, result)).unwrap_or_else(|_| {
             "{\"id\":0,\"error\":{\"message\":\"serialization failed\"}}".to_string()
         }),
     )
 }
 
-fn build_event_notification(event: DaemonEvent) -> Option<String> {
-    let payload = match event {
+fn build_event_notification(event: SequencedEvent) -> Option<String> {
+    let seq = event.seq;
+    let payload = match event.event {
         DaemonEvent::AppServer(payload) => json!({
             "method": "app-server-event",
             "params": payload,
+            "seq": seq,
         }),
         DaemonEvent::TerminalOutput(payload) => json!({
             "method": "terminal-output",
             "params": payload,
+            "seq": seq,
         }),
         DaemonEvent::TerminalExit(payload) => json!({
             "method": "terminal-exit",
             "params": payload,
+            "seq": seq,
         }),
     };
     serde_json::to_string(&payload).ok()
 }
 
-pub(super) fn parse_auth_token(params: &Value) -> Option<String> {
-    match params {
-        Value::String(value) => Some(value.clone()),
-        Value::Object(map) => map
-            .get("token")
-            .and_then(|value| value.as_str())
-            .map(|v| v.to_string()),
-        _ => None,
-    }
-}
-
-pub(super) fn parse_string(value: &Value, key: &str) -> Result<String, String> {
-    match value {
-        Value::Object(map) => map
-            .get(key)
-            .and_then(|value| value.as_str())
-            .map(|value| value.to_string())
-            .ok_or_else(|| format!("missing or invalid `{key}`")),
-        _ => Err(format!("missing `{key}`")),
-    }
-}
-
 pub(super) fn parse_optional_string(value: &Value, key: &str) -> Option<String> {
     match value {
         Value::Object(map) => map
@@ -85,102 +89,148 @@ pub(super) fn parse_optional_string(value: &Value, key: &str) -> Option<String>
     }
 }
 
-pub(super) fn parse_optional_u32(value: &Value, key: &str) -> Option<u32> {
-    match value {
-        Value::Object(map) => map.get(key).and_then(|value| value.as_u64()).and_then(|v| {
-            if v > u32::MAX as u64 {
-                None
-            } else {
-                Some(v as u32)
-            }
-        }),
-        _ => None,
-    }
-}
-
-pub(super) fn parse_optional_bool(value: &Value, key: &str) -> Option<bool> {
-    match value {
-        Value::Object(map) => map.get(key).and_then(|value| value.as_bool()),
-        _ => None,
-    }
-}
-
-pub(super) fn parse_optional_string_array(value: &Value, key: &str) -> Option<Vec<String>> {
-    match value {
-        Value::Object(map) => map
-            .get(key)
-            .and_then(|value| value.as_array())
-            .map(|items| {
-                items
-                    .iter()
-                    .filter_map(|item| item.as_str().map(|value| value.to_string()))
-                    .collect::<Vec<_>>()
-            }),
-        _ => None,
-    }
-}
-
-pub(super) fn parse_string_array(value: &Value, key: &str) -> Result<Vec<String>, String> {
-    parse_optional_string_array(value, key).ok_or_else(|| format!("missing `{key}`"))
-}
-
-pub(super) fn parse_optional_value(value: &Value, key: &str) -> Option<Value> {
-    match value {
-        Value::Object(map) => map.get(key).cloned(),
-        _ => None,
+pub(super) async fn handle_rpc_request(
+    state: &DaemonState,
+    method: &str,
+    params: Value,
+    client_version: String,
+) -> Result<Value, CoreError> {
+    if let Some(schema) = catalog::find_method(method) {
+        catalog::check_params(schema, &params)
+            .map_err(|message| CoreError::new(ErrorCode::InvalidParams, message))?;
     }
+    dispatcher::dispatch_rpc_request(state, method, &params, &client_version).await
 }
 
-pub(super) async fn handle_rpc_request(
+/// Rejects methods outside the connection's token scope before dispatching.
+pub(super) async fn handle_scoped_rpc_request(
     state: &DaemonState,
+    client_scope: &ClientScope,
     method: &str,
     params: Value,
     client_version: String,
-) -> Result<Value, String> {
-    dispatcher::dispatch_rpc_request(state, method, &params, &client_version).await
+) -> Result<Value, CoreError> {
+    scope::authorize(client_scope, method, &params)?;
+    // While draining, only what an observer could do is still served.
+    if state.shutdown.is_draining() && scope::required_role(method) > TokenRole::Observer {
+        return Err(CoreError::shutting_down());
+    }
+    let result = handle_rpc_request(state, method, params, client_version).await?;
+    Ok(scope::filter_rpc_result(client_scope, method, result))
 }
 
+/// How often a streaming connection re-reads its token, so a revoked or
+/// narrowed token stops receiving events even while the client stays quiet.
+const EVENT_SCOPE_RECHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Streams events to one connection. `backlog` holds replayed events that
+/// precede anything received on `rx`; live events already covered by the
+/// backlog are skipped. If the receiver lags, the gap is refilled from the
+/// replay buffer when it still has the missed events. Once the connection's
+/// token is revoked, `revoked` is notified and streaming stops.
 pub(super) async fn forward_events(
-    mut rx: broadcast::Receiver<DaemonEvent>,
-    out_tx_events: mpsc::UnboundedSender<String>,
+    mut rx: broadcast::Receiver<SequencedEvent>,
+    out_tx_events: mpsc::UnboundedSender<OutboundMessage>,
+    client_scope: Arc<std::sync::RwLock<ClientScope>>,
+    subscription: Arc<std::sync::RwLock<EventSubscription>>,
+    state: Arc<DaemonState>,
+    backlog: ReplaySnapshot,
+    revoked: Arc<Notify>,
 ) {
+    let replay = &state.event_sink.replay;
+    let mut scope_checked_at = Instant::now();
+    let mut last_sent_seq = backlog.last_seq;
+    for event in backlog.events {
+        if !send_scoped_event(&out_tx_events, &client_scope, &subscription, event) {
+            return;
+        }
+    }
+
     loop {
-        let event = match rx.recv().await {
-            Ok(event) => event,
-            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+        let events = match rx.recv().await {
+            Ok(event) => vec![event],
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                state.metrics.record_event_lag(skipped);
+                replay.replay_since(last_sent_seq).events
+            }
             Err(broadcast::error::RecvError::Closed) => break,
         };
 
-        let Some(payload) = build_event_notification(event) else {
-            continue;
-        };
+        // The token store only re-reads the file when its mtime changes.
+        if scope_checked_at.elapsed() >= EVENT_SCOPE_RECHECK_INTERVAL {
+            scope_checked_at = Instant::now();
+            if scope::refresh_client_scope(&state.auth_tokens, &client_scope).is_none() {
+                revoked.notify_one();
+                return;
+            }
+        }
 
-        if out_tx_events.send(payload).is_err() {
-            break;
+        for event in events {
+            if event.seq <= last_sent_seq {
+                continue;
+            }
+            last_sent_seq = event.seq;
+            if !send_scoped_event(&out_tx_events, &client_scope, &subscription, event) {
+                return;
+            }
         }
     }
 }
 
+/// Returns false once the connection's outbound channel is closed.
+pub(super) fn send_scoped_event(
+    out_tx_events: &mpsc::UnboundedSender<OutboundMessage>,
+    client_scope: &std::sync::RwLock<ClientScope>,
+    subscription: &std::sync::RwLock<EventSubscription>,
+    event: SequencedEvent,
+) -> bool {
+    let in_scope = client_scope
+        .read()
+        .map(|client_scope| scope::event_in_scope(&client_scope, &event.event))
+        .unwrap_or(false);
+    let subscribed = subscription
+        .read()
+        .map(|subscription| subscription.matches(&event.event))
+        .unwrap_or(true);
+    if !in_scope || !subscribed {
+        return true;
+    }
+    let Some(payload) = build_event_notification(event) else {
+        return true;
+    };
+    out_tx_events.send(payload.into()).is_ok()
+}
+
 pub(super) fn spawn_rpc_response_task(
     state: Arc<DaemonState>,
-    out_tx: mpsc::UnboundedSender<String>,
+    client_scope: ClientScope,
+    out_tx: mpsc::UnboundedSender<OutboundMessage>,
     id: Option<u64>,
     method: String,
     params: Value,
-    client_version: String,
     request_limiter: Arc<Semaphore>,
 ) {
+    let client_version = format!("daemon-{}", env!("CARGO_PKG_VERSION"));
     tokio::spawn(async move {
+        if request_limiter.available_permits() == 0 {
+            state.metrics.record_rpc_throttled();
+        }
         let Ok(_permit) = request_limiter.acquire_owned().await else {
             return;
         };
-        let result = handle_rpc_request(&state, &method, params, client_version).await;
+        let _in_flight = state.metrics.rpc_started();
+        let started = Instant::now();
+        let result =
+            handle_scoped_rpc_request(&state, &client_scope, &method, params, client_version).await;
+        state
+            .metrics
+            .record_rpc(&method, started.elapsed(), result.is_ok());
         let response = match result {
             Ok(result) => build_result_response(id, result),
-            Err(message) => build_error_response(id, &message),
+            Err(error) => build_error_response(id, &error),
         };
         if let Some(response) = response {
-            let _ = out_tx.send(response);
+            let _ = out_tx.send(response.into());
         }
     });
 }
@@ -0,0 +1,644 @@
+//! Every method the daemon answers, with the request and response types its
+//! handler uses. `rpc_describe` and the param check before dispatch are
+//! derived from their JSON schemas, so the catalog cannot drift from what the
+//! handlers actually parse.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{InstanceType, Schema, SchemaObject, SingleOrVec};
+
+use super::daemon::{
+    DeviceIdRequest, MenuSetAcceleratorsRequest, NotificationFallbackRequest,
+    PushDeviceRulesPatchRequest,
+};
+use super::*;
+use crate::event_subscription::SubscribeEventsFilter;
+use crate::files::io::TextFileResponse;
+use crate::shared::agents_config_core::{
+    AgentsSettingsDto, CreateAgentInput, DeleteAgentInput, SetAgentsCoreInput, UpdateAgentInput,
+};
+use crate::shared::codex_aux_core::GeneratedAgentConfiguration;
+use crate::shared::rpc_schema;
+use crate::shared::terminal_rpc::{TerminalAttachInfo, TerminalListEntry, TerminalSessionInfo};
+use crate::shared::transcription_chatgpt_core::DictationAuthStatus;
+use crate::shared::workspaces_core::WorkspaceRuntimeCodexArgsResult;
+use crate::shared::{codex_rpc, git_rpc, terminal_rpc, workspace_rpc};
+use crate::transport::{
+    AuthRequest, AuthResponse, ProtocolUpgradeRequest, ProtocolUpgradeResponse,
+};
+use crate::types::GitHubIssuesResponse;
+
+/// Params of methods that take none.
+#[derive(Debug, JsonSchema)]
+pub(crate) struct NoParams {}
+
+/// Result passed through from the workspace's app-server, or assembled by the
+/// daemon from state that has no fixed shape.
+type Passthrough = Value;
+
+pub(crate) struct RpcMethod {
+    pub(crate) name: &'static str,
+    params_schema: fn(&mut SchemaGenerator) -> Schema,
+    result_schema: fn(&mut SchemaGenerator) -> Schema,
+}
+
+impl RpcMethod {
+    /// Safe to resend on a fresh connection when the first attempt was cut off.
+    pub(crate) fn retry_after_disconnect(&self) -> bool {
+        rpc_schema::can_retry_after_disconnect(self.name)
+    }
+
+    /// Top-level params, in declaration order.
+    pub(crate) fn params(&self) -> &'static [RpcParam] {
+        static PARAMS: OnceLock<HashMap<&'static str, Vec<RpcParam>>> = OnceLock::new();
+        PARAMS
+            .get_or_init(|| {
+                METHODS
+                    .iter()
+                    .map(|method| (method.name, top_level_params(method)))
+                    .collect()
+            })
+            .get(self.name)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+fn schema_for<T: JsonSchema>(gen: &mut SchemaGenerator) -> Schema {
+    gen.subschema_for::<T>()
+}
+
+macro_rules! rpc_methods {
+    ($($name:literal: $params:ty => $result:ty,)*) => {
+        pub(crate) const METHODS: &[RpcMethod] = &[$(
+            RpcMethod {
+                name: $name,
+                params_schema: schema_for::<$params>,
+                result_schema: schema_for::<$result>,
+            },
+        )*];
+    };
+}
+
+rpc_methods! {
+    // Connection
+    "auth": AuthRequest => AuthResponse,
+    "subscribe_events": SubscribeEventsFilter => SubscribeEventsFilter,
+    "protocol_upgrade": ProtocolUpgradeRequest => ProtocolUpgradeResponse,
+    // Daemon
+    "ping": NoParams => OkResponse,
+    "daemon_info": NoParams => DaemonInfo,
+    "daemon_metrics": NoParams => Passthrough,
+    "daemon_logs_tail": logging_core::LogTailQuery => Passthrough,
+    "rpc_describe": NoParams => Passthrough,
+    "daemon_shutdown": shutdown::ShutdownParams => shutdown::ShutdownResponse,
+    "menu_set_accelerators": MenuSetAcceleratorsRequest => OkResponse,
+    "is_macos_debug_build": NoParams => bool,
+    "send_notification_fallback": NotificationFallbackRequest => OkResponse,
+    "presence_heartbeat": push::PresenceHeartbeatInput => OkResponse,
+    "push_register_device": push::PushDeviceRegistrationInput => Passthrough,
+    "push_unregister_device": DeviceIdRequest => OkResponse,
+    "push_device_rules_get": DeviceIdRequest => Passthrough,
+    "push_device_rules_patch": PushDeviceRulesPatchRequest => Passthrough,
+    "push_notification_config_get": NoParams => Passthrough,
+    "push_notification_config_patch": push::PushNotificationConfigPatch => Passthrough,
+    "push_notification_state": NoParams => Passthrough,
+    // Workspaces
+    "list_workspaces": NoParams => Vec<WorkspaceInfo>,
+    "is_workspace_path_dir": workspace_rpc::IsWorkspacePathDirRequest => bool,
+    "add_workspace": workspace_rpc::AddWorkspaceRequest => WorkspaceInfo,
+    "add_workspace_from_git_url": workspace_rpc::AddWorkspaceFromGitUrlRequest => WorkspaceInfo,
+    "add_worktree": workspace_rpc::AddWorktreeRequest => WorkspaceInfo,
+    "worktree_setup_status": workspace_rpc::WorkspaceIdRequest => WorktreeSetupStatus,
+    "worktree_setup_mark_ran": workspace_rpc::WorkspaceIdRequest => OkResponse,
+    "connect_workspace": workspace_rpc::IdRequest => OkResponse,
+    "set_workspace_runtime_codex_args":
+        workspace_rpc::SetWorkspaceRuntimeCodexArgsRequest => WorkspaceRuntimeCodexArgsResult,
+    "remove_workspace": workspace_rpc::IdRequest => OkResponse,
+    "remove_worktree": workspace_rpc::IdRequest => OkResponse,
+    "rename_worktree": workspace_rpc::RenameWorktreeRequest => WorkspaceInfo,
+    "rename_worktree_upstream": workspace_rpc::RenameWorktreeUpstreamRequest => OkResponse,
+    "update_workspace_settings": workspace_rpc::UpdateWorkspaceSettingsRequest => WorkspaceInfo,
+    "list_workspace_files": workspace_rpc::WorkspaceIdRequest => Vec<String>,
+    "read_workspace_file": workspace_rpc::ReadWorkspaceFileRequest => WorkspaceFileResponse,
+    "add_clone": workspace_rpc::AddCloneRequest => WorkspaceInfo,
+    "file_read": workspace_rpc::FileReadRequest => TextFileResponse,
+    "file_write": workspace_rpc::FileWriteRequest => OkResponse,
+    "read_image_as_data_url": workspace_rpc::ReadImageAsDataUrlRequest => String,
+    "get_app_settings": NoParams => AppSettings,
+    "update_app_settings": workspace_rpc::UpdateAppSettingsRequest => AppSettings,
+    "apply_worktree_changes": workspace_rpc::WorkspaceIdRequest => OkResponse,
+    "open_workspace_in": workspace_rpc::OpenWorkspaceInRequest => OkResponse,
+    "get_open_app_icon": workspace_rpc::GetOpenAppIconRequest => Option<String>,
+    "local_usage_snapshot": workspace_rpc::LocalUsageSnapshotRequest => LocalUsageSnapshot,
+    // Codex
+    "get_codex_config_path": NoParams => String,
+    "get_config_model": codex_rpc::WorkspaceIdRequest => Passthrough,
+    "start_thread": codex_rpc::WorkspaceIdRequest => Passthrough,
+    "resume_thread": codex_rpc::ThreadRequest => Passthrough,
+    "thread_live_subscribe": codex_rpc::ThreadRequest => Passthrough,
+    "thread_live_unsubscribe": codex_rpc::ThreadRequest => Passthrough,
+    "fork_thread": codex_rpc::ThreadRequest => Passthrough,
+    "list_threads": codex_rpc::ListThreadsRequest => Passthrough,
+    "list_mcp_server_status": codex_rpc::WorkspacePageRequest => Passthrough,
+    "archive_thread": codex_rpc::ThreadRequest => Passthrough,
+    "compact_thread": codex_rpc::ThreadRequest => Passthrough,
+    "set_thread_name": codex_rpc::SetThreadNameRequest => Passthrough,
+    "send_user_message": codex_rpc::SendUserMessageRequest => Passthrough,
+    "run_bang_command": codex_rpc::RunBangCommandRequest => Passthrough,
+    "turn_interrupt": codex_rpc::TurnInterruptRequest => Passthrough,
+    "turn_steer": codex_rpc::TurnSteerRequest => Passthrough,
+    "start_review": codex_rpc::StartReviewRequest => Passthrough,
+    "model_list": codex_rpc::WorkspaceIdRequest => Passthrough,
+    "experimental_feature_list": codex_rpc::WorkspacePageRequest => Passthrough,
+    "collaboration_mode_list": codex_rpc::WorkspaceIdRequest => Passthrough,
+    "set_codex_feature_flag": codex_rpc::SetCodexFeatureFlagRequest => OkResponse,
+    "get_agents_settings": NoParams => AgentsSettingsDto,
+    "set_agents_core_settings": codex_rpc::InputRequest<SetAgentsCoreInput> => AgentsSettingsDto,
+    "create_agent": codex_rpc::InputRequest<CreateAgentInput> => AgentsSettingsDto,
+    "update_agent": codex_rpc::InputRequest<UpdateAgentInput> => AgentsSettingsDto,
+    "delete_agent": codex_rpc::InputRequest<DeleteAgentInput> => AgentsSettingsDto,
+    "read_agent_config_toml": codex_rpc::AgentNameRequest => String,
+    "write_agent_config_toml": codex_rpc::WriteAgentConfigTomlRequest => OkResponse,
+    "account_rate_limits": codex_rpc::WorkspaceIdRequest => Passthrough,
+    "account_read": codex_rpc::WorkspaceIdRequest => Passthrough,
+    "dictation_auth_status": codex_rpc::OptionalWorkspaceIdRequest => DictationAuthStatus,
+    "dictation_transcribe": codex_rpc::DictationTranscribeRequest => Passthrough,
+    "codex_login": codex_rpc::WorkspaceIdRequest => Passthrough,
+    "codex_login_cancel": codex_rpc::WorkspaceIdRequest => Passthrough,
+    "skills_list": codex_rpc::WorkspaceIdRequest => Passthrough,
+    "apps_list": codex_rpc::AppsListRequest => Passthrough,
+    "respond_to_server_request": codex_rpc::RespondToServerRequestRequest => Passthrough,
+    "remember_approval_rule": codex_rpc::RememberApprovalRuleRequest => Passthrough,
+    "codex_doctor": codex_rpc::CodexDoctorRequest => Passthrough,
+    "generate_run_metadata": codex_rpc::GenerateRunMetadataRequest => Passthrough,
+    "generate_agent_description":
+        codex_rpc::GenerateAgentDescriptionRequest => GeneratedAgentConfiguration,
+    // Git
+    "get_git_status": git_rpc::WorkspaceIdRequest => Passthrough,
+    "init_git_repo": git_rpc::InitGitRepoRequest => Passthrough,
+    "create_github_repo": git_rpc::CreateGitHubRepoRequest => Passthrough,
+    "list_git_roots": git_rpc::ListGitRootsRequest => Vec<String>,
+    "get_git_diffs": git_rpc::WorkspaceIdRequest => Vec<GitFileDiff>,
+    "get_git_log": git_rpc::GetGitLogRequest => GitLogResponse,
+    "get_git_commit_diff": git_rpc::WorkspaceShaRequest => Vec<GitCommitDiff>,
+    "get_git_remote": git_rpc::WorkspaceIdRequest => Option<String>,
+    "stage_git_file": git_rpc::WorkspacePathRequest => OkResponse,
+    "stage_git_all": git_rpc::WorkspaceIdRequest => OkResponse,
+    "unstage_git_file": git_rpc::WorkspacePathRequest => OkResponse,
+    "revert_git_file": git_rpc::WorkspacePathRequest => OkResponse,
+    "revert_git_all": git_rpc::WorkspaceIdRequest => OkResponse,
+    "commit_git": git_rpc::WorkspaceMessageRequest => OkResponse,
+    "push_git": git_rpc::WorkspaceIdRequest => OkResponse,
+    "pull_git": git_rpc::WorkspaceIdRequest => OkResponse,
+    "fetch_git": git_rpc::WorkspaceIdRequest => OkResponse,
+    "sync_git": git_rpc::WorkspaceIdRequest => OkResponse,
+    "get_github_issues": git_rpc::WorkspaceIdRequest => GitHubIssuesResponse,
+    "get_github_pull_requests": git_rpc::WorkspaceIdRequest => GitHubPullRequestsResponse,
+    "get_github_pull_request_diff":
+        git_rpc::GitHubPullRequestRequest => Vec<GitHubPullRequestDiff>,
+    "get_github_pull_request_comments":
+        git_rpc::GitHubPullRequestRequest => Vec<GitHubPullRequestComment>,
+    "checkout_github_pull_request": git_rpc::GitHubPullRequestRequest => OkResponse,
+    "list_git_branches": git_rpc::WorkspaceIdRequest => Passthrough,
+    "checkout_git_branch": git_rpc::WorkspaceNameRequest => OkResponse,
+    "create_git_branch": git_rpc::WorkspaceNameRequest => OkResponse,
+    "generate_commit_message": git_rpc::GenerateCommitMessageRequest => String,
+    // Prompts
+    "prompts_list": codex_rpc::WorkspaceIdRequest => Vec<CustomPromptEntry>,
+    "prompts_workspace_dir": codex_rpc::WorkspaceIdRequest => String,
+    "prompts_global_dir": codex_rpc::WorkspaceIdRequest => String,
+    "prompts_create": codex_rpc::PromptsCreateRequest => CustomPromptEntry,
+    "prompts_update": codex_rpc::PromptsUpdateRequest => CustomPromptEntry,
+    "prompts_delete": codex_rpc::PromptPathRequest => OkResponse,
+    "prompts_move": codex_rpc::PromptsMoveRequest => CustomPromptEntry,
+    // Terminal
+    "terminal_open": terminal_rpc::TerminalOpenRequest => TerminalSessionInfo,
+    "terminal_write": terminal_rpc::TerminalWriteRequest => OkResponse,
+    "terminal_resize": terminal_rpc::TerminalResizeRequest => OkResponse,
+    "terminal_close": terminal_rpc::TerminalIdRequest => OkResponse,
+    "terminal_attach": terminal_rpc::TerminalIdRequest => TerminalAttachInfo,
+    "terminal_list": terminal_rpc::TerminalListRequest => Vec<TerminalListEntry>,
+}
+
+pub(crate) fn find_method(name: &str) -> Option<&'static RpcMethod> {
+    METHODS.iter().find(|method| method.name == name)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ParamKind {
+    String,
+    Integer,
+    Number,
+    Boolean,
+    StringArray,
+    Array,
+    Object,
+    Any,
+}
+
+impl ParamKind {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::String => "string",
+            Self::Integer => "integer",
+            Self::Number => "number",
+            Self::Boolean => "boolean",
+            Self::StringArray => "string[]",
+            Self::Array => "array",
+            Self::Object => "object",
+            Self::Any => "any",
+        }
+    }
+
+    fn accepts(self, value: &Value) -> bool {
+        match self {
+            Self::String => value.is_string(),
+            Self::Integer => value.is_i64() || value.is_u64(),
+            Self::Number => value.is_number(),
+            Self::Boolean => value.is_boolean(),
+            Self::StringArray => value
+                .as_array()
+                .is_some_and(|items| items.iter().all(Value::is_string)),
+            Self::Array => value.is_array(),
+            Self::Object => value.is_object(),
+            Self::Any => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RpcParam {
+    pub(crate) name: String,
+    pub(crate) kind: ParamKind,
+    pub(crate) required: bool,
+}
+
+fn top_level_params(method: &RpcMethod) -> Vec<RpcParam> {
+    let mut gen = SchemaGenerator::default();
+    let schema = (method.params_schema)(&mut gen);
+    let definitions = gen.take_definitions();
+    let Some(object) = resolve(&schema, &definitions).and_then(|schema| schema.object.as_ref())
+    else {
+        return Vec::new();
+    };
+    object
+        .properties
+        .iter()
+        .map(|(name, schema)| RpcParam {
+            name: name.clone(),
+            kind: kind_of(schema, &definitions),
+            required: object.required.contains(name),
+        })
+        .collect()
+}
+
+/// Follows `$ref`s and the single-entry `allOf` schemars wraps documented
+/// fields in.
+fn resolve<'a>(
+    schema: &'a Schema,
+    definitions: &'a schemars::Map<String, Schema>,
+) -> Option<&'a SchemaObject> {
+    let Schema::Object(object) = schema else {
+        return None;
+    };
+    if let Some(name) = object
+        .reference
+        .as_deref()
+        .and_then(|reference| reference.strip_prefix("#/definitions/"))
+    {
+        return resolve(definitions.get(name)?, definitions);
+    }
+    match object
+        .subschemas
+        .as_ref()
+        .and_then(|subschemas| subschemas.all_of.as_deref())
+    {
+        Some([single]) => resolve(single, definitions),
+        _ => Some(object),
+    }
+}
+
+fn kind_of(schema: &Schema, definitions: &schemars::Map<String, Schema>) -> ParamKind {
+    let Some(object) = resolve(schema, definitions) else {
+        return ParamKind::Any;
+    };
+    // `Option<Struct>` is `anyOf: [{ $ref }, { type: null }]`.
+    if let Some(any_of) = object
+        .subschemas
+        .as_ref()
+        .and_then(|subschemas| subschemas.any_of.as_ref())
+    {
+        let kinds = any_of
+            .iter()
+            .filter(|schema| !is_null(schema))
+            .map(|schema| kind_of(schema, definitions))
+            .collect::<Vec<_>>();
+        return match kinds.as_slice() {
+            [kind] => *kind,
+            _ => ParamKind::Any,
+        };
+    }
+    let types = match &object.instance_type {
+        Some(SingleOrVec::Single(instance_type)) => vec![**instance_type],
+        Some(SingleOrVec::Vec(types)) => types.clone(),
+        None => return ParamKind::Any,
+    };
+    let types = types
+        .into_iter()
+        .filter(|instance_type| *instance_type != InstanceType::Null)
+        .collect::<Vec<_>>();
+    match types.as_slice() {
+        [InstanceType::String] => ParamKind::String,
+        [InstanceType::Integer] => ParamKind::Integer,
+        [InstanceType::Number] => ParamKind::Number,
+        [InstanceType::Boolean] => ParamKind::Boolean,
+        [InstanceType::Object] => ParamKind::Object,
+        [InstanceType::Array] => match object.array.as_ref().and_then(|array| array.items.as_ref())
+        {
+            Some(SingleOrVec::Single(items))
+                if kind_of(items, definitions) == ParamKind::String =>
+            {
+                ParamKind::StringArray
+            }
+            _ => ParamKind::Array,
+        },
+        _ => ParamKind::Any,
+    }
+}
+
+fn is_null(schema: &Schema) -> bool {
+    matches!(
+        schema,
+        Schema::Object(SchemaObject {
+            instance_type: Some(SingleOrVec::Single(instance_type)),
+            ..
+        }) if **instance_type == InstanceType::Null
+    )
+}
+
+/// Validates params against the catalog entry. `null` counts as missing.
+pub(crate) fn check_params(method: &RpcMethod, params: &Value) -> Result<(), String> {
+    let expected = method.params();
+    if expected.is_empty() {
+        return Ok(());
+    }
+    let empty = serde_json::Map::new();
+    let map = match params {
+        Value::Object(map) => map,
+        Value::Null => &empty,
+        _ => {
+            return Err(format!(
+                "invalid params for `{}`: expected an object",
+                method.name
+            ))
+        }
+    };
+    for param in expected {
+        match map.get(&param.name).filter(|value| !value.is_null()) {
+            None if param.required => {
+                return Err(format!(
+                    "invalid params for `{}`: missing `{}` ({})",
+                    method.name,
+                    param.name,
+                    param.kind.as_str()
+                ));
+            }
+            Some(value) if !param.kind.accepts(value) => {
+                return Err(format!(
+                    "invalid params for `{}`: `{}` must be {}",
+                    method.name,
+                    param.name,
+                    param.kind.as_str()
+                ));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// The catalog served by `rpc_describe`: each method's top-level params, plus
+/// full JSON schemas for its params and result. Named types are shared through
+/// `definitions`.
+pub(crate) fn describe() -> &'static Value {
+    static CATALOG: OnceLock<Value> = OnceLock::new();
+    CATALOG.get_or_init(|| {
+        let mut gen = SchemaGenerator::default();
+        let methods = METHODS
+            .iter()
+            .map(|method| {
+                let params = method
+                    .params()
+                    .iter()
+                    .map(|param| {
+                        json!({
+                            "name": param.name,
+                            "type": param.kind.as_str(),
+                            "required": param.required,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                json!({
+                    "name": method.name,
+                    "params": params,
+                    "retryAfterDisconnect": method.retry_after_disconnect(),
+                    "paramsSchema": (method.params_schema)(&mut gen),
+                    "resultSchema": (method.result_schema)(&mut gen),
+                })
+            })
+            .collect::<Vec<_>>();
+        json!({
+            "protocolVersion": rpc_schema::PROTOCOL_VERSION,
+            "methods": methods,
+            "definitions": gen.take_definitions(),
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    /// Handler sources, scanned for the method names they match on.
+    const DISPATCHER_SOURCES: &[&str] = &[
+        include_str!("codex.rs"),
+        include_str!("daemon.rs"),
+        include_str!("git.rs"),
+        include_str!("prompts.rs"),
+        include_str!("terminal.rs"),
+        include_str!("workspace.rs"),
+    ];
+    /// Where the `METHOD_*` constants used as match arms are defined.
+    const CONSTANT_SOURCES: &[(&str, &str)] = &[
+        ("git_rpc", include_str!("../../../shared/git_rpc.rs")),
+        ("rpc_schema", include_str!("../../../shared/rpc_schema.rs")),
+        (
+            "terminal_rpc",
+            include_str!("../../../shared/terminal_rpc.rs"),
+        ),
+    ];
+
+    fn method_constant(module: &str, constant: &str) -> String {
+        let source = CONSTANT_SOURCES
+            .iter()
+            .find(|(name, _)| *name == module)
+            .map(|(_, source)| *source)
+            .unwrap_or_else(|| panic!("no source for {module}::{constant}"));
+        let prefix = format!("pub(crate) const {constant}: &str = \"");
+        source
+            .lines()
+            .find_map(|line| line.trim().strip_prefix(prefix.as_str()))
+            .and_then(|rest| rest.strip_suffix("\";"))
+            .unwrap_or_else(|| panic!("cannot resolve {module}::{constant}"))
+            .to_string()
+    }
+
+    /// Method names matched by the dispatcher arms, plus the connection-level
+    /// methods the transport answers itself.
+    fn handled_methods() -> HashSet<String> {
+        let mut methods = HashSet::new();
+        for source in DISPATCHER_SOURCES {
+            for line in source.lines() {
+                let Some((pattern, _)) = line.trim().split_once(" =>") else {
+                    continue;
+                };
+                if let Some(name) = pattern
+                    .strip_prefix('"')
+                    .and_then(|rest| rest.strip_suffix('"'))
+                {
+                    methods.insert(name.to_string());
+                } else if let Some((module, constant)) = pattern.split_once("::") {
+                    if constant.starts_with("METHOD_") {
+                        methods.insert(method_constant(module, constant));
+                    }
+                }
+            }
+        }
+        for line in include_str!("../transport.rs").lines() {
+            if let Some((_, rest)) = line.split_once("if method == \"") {
+                methods.insert(rest.split('"').next().unwrap_or_default().to_string());
+            }
+        }
+        methods
+    }
+
+    #[test]
+    fn every_dispatcher_arm_has_a_catalog_entry_and_back() {
+        let handled = handled_methods();
+        let cataloged = METHODS
+            .iter()
+            .map(|method| method.name.to_string())
+            .collect::<HashSet<_>>();
+        let mut uncataloged = handled.difference(&cataloged).collect::<Vec<_>>();
+        uncataloged.sort();
+        assert!(
+            uncataloged.is_empty(),
+            "missing from the catalog: {uncataloged:?}"
+        );
+        let mut unhandled = cataloged.difference(&handled).collect::<Vec<_>>();
+        unhandled.sort();
+        assert!(unhandled.is_empty(), "no dispatcher arm: {unhandled:?}");
+        assert_eq!(cataloged.len(), METHODS.len(), "duplicate catalog entries");
+    }
+
+    #[test]
+    fn retryable_methods_are_cataloged() {
+        for name in rpc_schema::RETRY_AFTER_DISCONNECT {
+            assert!(find_method(name).is_some(), "{name} is not a daemon method");
+        }
+    }
+
+    #[test]
+    fn params_follow_the_request_structs() {
+        let send = find_method("send_user_message").expect("send_user_message");
+        let param = |name: &str| {
+            send.params()
+                .iter()
+                .find(|param| param.name == name)
+                .cloned()
+                .unwrap_or_else(|| panic!("no param {name}"))
+        };
+        assert_eq!(send.params()[0].name, "workspaceId");
+        assert_eq!(param("text").kind, ParamKind::String);
+        assert!(param("text").required);
+        assert_eq!(param("images").kind, ParamKind::StringArray);
+        assert!(!param("images").required);
+        assert_eq!(param("appMentions").kind, ParamKind::Array);
+        assert_eq!(param("collaborationMode").kind, ParamKind::Any);
+
+        let file_read = find_method("file_read").expect("file_read");
+        assert_eq!(file_read.params()[0].kind, ParamKind::String);
+        let settings = find_method("update_workspace_settings").expect("settings");
+        assert_eq!(settings.params()[1].kind, ParamKind::Object);
+        assert!(find_method("ping").expect("ping").params().is_empty());
+    }
+
+    #[test]
+    fn check_params_reports_missing_and_mistyped_params() {
+        let send = find_method("send_user_message").expect("send_user_message");
+        assert_eq!(
+            check_params(send, &json!({ "workspaceId": "ws-1", "threadId": "t-1" })),
+            Err("invalid params for `send_user_message`: missing `text` (string)".to_string())
+        );
+        assert_eq!(
+            check_params(
+                send,
+                &json!({ "workspaceId": "ws-1", "threadId": "t-1", "text": "hi", "images": [1] })
+            ),
+            Err("invalid params for `send_user_message`: `images` must be string[]".to_string())
+        );
+        assert!(check_params(
+            send,
+            &json!({ "workspaceId": "ws-1", "threadId": "t-1", "text": "hi", "model": null })
+        )
+        .is_ok());
+
+        let doctor = find_method("codex_doctor").expect("codex_doctor");
+        assert!(check_params(doctor, &Value::Null).is_ok());
+        assert_eq!(
+            check_params(doctor, &json!("codex")),
+            Err("invalid params for `codex_doctor`: expected an object".to_string())
+        );
+    }
+
+    #[test]
+    fn describe_carries_param_and_result_schemas() {
+        let catalog = describe();
+        assert_eq!(
+            catalog["protocolVersion"],
+            json!(rpc_schema::PROTOCOL_VERSION)
+        );
+        let names = rpc_schema::described_method_names(catalog);
+        assert_eq!(names.len(), METHODS.len());
+        assert!(names.contains(rpc_schema::METHOD_RPC_DESCRIBE));
+
+        let methods = catalog["methods"].as_array().expect("methods");
+        let list_threads = methods
+            .iter()
+            .find(|method| method["name"] == "list_threads")
+            .expect("list_threads");
+        assert_eq!(list_threads["retryAfterDisconnect"], json!(true));
+        assert_eq!(
+            list_threads["params"][0],
+            json!({ "name": "workspaceId", "type": "string", "required": true })
+        );
+        let list_workspaces = methods
+            .iter()
+            .find(|method| method["name"] == "list_workspaces")
+            .expect("list_workspaces");
+        assert_eq!(list_workspaces["retryAfterDisconnect"], json!(true));
+        assert_eq!(
+            list_workspaces["resultSchema"]["items"]["$ref"],
+            json!("#/definitions/WorkspaceInfo")
+        );
+        assert!(catalog["definitions"]["WorkspaceInfo"]["properties"]["id"].is_object());
+        assert_eq!(
+            catalog["definitions"]["OkResponse"]["required"],
+            json!(["ok"])
+        );
+    }
+}
@@ -1,20 +1,25 @@
 use super::*;
+use crate::shared::codex_rpc;
 use serde::de::DeserializeOwned;
 
-fn parse_input<T: DeserializeOwned>(params: &Value) -> Result<T, String> {
-    let input_value = params
-        .as_object()
-        .and_then(|map| map.get("input"))
-        .cloned()
-        .ok_or_else(|| "missing `input`".to_string())?;
-    serde_json::from_value(input_value).map_err(|err| err.to_string())
+fn parse_codex_request<T: DeserializeOwned>(params: &Value) -> Result<T, CoreError> {
+    codex_rpc::from_params(params)
+}
+
+macro_rules! parse_request_or_err {
+    ($params:expr, $ty:ty) => {
+        match parse_codex_request::<$ty>($params) {
+            Ok(value) => value,
+            Err(err) => return Some(Err(err)),
+        }
+    };
 }
 
 pub(super) async fn try_handle(
     state: &DaemonState,
     method: &str,
     params: &Value,
-) -> Option<Result<Value, String>> {
+) -> Option<Result<Value, CoreError>> {
     match method {
         "get_codex_config_path" => {
             let path = match settings_core::get_codex_config_path_core() {
@@ -24,512 +29,350 @@ pub(super) async fn try_handle(
             Some(Ok(Value::String(path)))
         }
         "get_config_model" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.get_config_model(workspace_id).await)
+            let request = parse_request_or_err!(params, codex_rpc::WorkspaceIdRequest);
+            Some(state.get_config_model(request.workspace_id).await)
         }
         "start_thread" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.start_thread(workspace_id).await)
+            let request = parse_request_or_err!(params, codex_rpc::WorkspaceIdRequest);
+            Some(state.start_thread(request.workspace_id).await)
         }
         "resume_thread" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let thread_id = match parse_string(params, "threadId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.resume_thread(workspace_id, thread_id).await)
+            let request = parse_request_or_err!(params, codex_rpc::ThreadRequest);
+            Some(
+                state
+                    .resume_thread(request.workspace_id, request.thread_id)
+                    .await,
+            )
         }
         "thread_live_subscribe" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let thread_id = match parse_string(params, "threadId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.thread_live_subscribe(workspace_id, thread_id).await)
+            let request = parse_request_or_err!(params, codex_rpc::ThreadRequest);
+            Some(
+                state
+                    .thread_live_subscribe(request.workspace_id, request.thread_id)
+                    .await,
+            )
         }
         "thread_live_unsubscribe" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let thread_id = match parse_string(params, "threadId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.thread_live_unsubscribe(workspace_id, thread_id).await)
+            let request = parse_request_or_err!(params, codex_rpc::ThreadRequest);
+            Some(
+                state
+                    .thread_live_unsubscribe(request.workspace_id, request.thread_id)
+                    .await,
+            )
         }
         "fork_thread" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let thread_id = match parse_string(params, "threadId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.fork_thread(workspace_id, thread_id).await)
+            let request = parse_request_or_err!(params, codex_rpc::ThreadRequest);
+            Some(
+                state
+                    .fork_thread(request.workspace_id, request.thread_id)
+                    .await,
+            )
         }
         "list_threads" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let cursor = parse_optional_string(params, "cursor");
-            let limit = parse_optional_u32(params, "limit");
-            let sort_key = parse_optional_string(params, "sortKey");
+            let request = parse_request_or_err!(params, codex_rpc::ListThreadsRequest);
             Some(
                 state
-                    .list_threads(workspace_id, cursor, limit, sort_key)
+                    .list_threads(
+                        request.workspace_id,
+                        request.cursor,
+                        request.limit,
+                        request.sort_key,
+                    )
                     .await,
             )
         }
         "list_mcp_server_status" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let cursor = parse_optional_string(params, "cursor");
-            let limit = parse_optional_u32(params, "limit");
+            let request = parse_request_or_err!(params, codex_rpc::WorkspacePageRequest);
             Some(
                 state
-                    .list_mcp_server_status(workspace_id, cursor, limit)
+                    .list_mcp_server_status(request.workspace_id, request.cursor, request.limit)
                     .await,
             )
         }
         "archive_thread" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let thread_id = match parse_string(params, "threadId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.archive_thread(workspace_id, thread_id).await)
+            let request = parse_request_or_err!(params, codex_rpc::ThreadRequest);
+            Some(
+                state
+                    .archive_thread(request.workspace_id, request.thread_id)
+                    .await,
+            )
         }
         "compact_thread" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let thread_id = match parse_string(params, "threadId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.compact_thread(workspace_id, thread_id).await)
+            let request = parse_request_or_err!(params, codex_rpc::ThreadRequest);
+            Some(
+                state
+                    .compact_thread(request.workspace_id, request.thread_id)
+                    .await,
+            )
         }
         "set_thread_name" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let thread_id = match parse_string(params, "threadId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let name = match parse_string(params, "name") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.set_thread_name(workspace_id, thread_id, name).await)
+            let request = parse_request_or_err!(params, codex_rpc::SetThreadNameRequest);
+            Some(
+                state
+                    .set_thread_name(request.workspace_id, request.thread_id, request.name)
+                    .await,
+            )
         }
         "send_user_message" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let thread_id = match parse_string(params, "threadId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let text = match parse_string(params, "text") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let model = parse_optional_string(params, "model");
-            let effort = parse_optional_string(params, "effort");
-            let access_mode = parse_optional_string(params, "accessMode");
-            let images = parse_optional_string_array(params, "images");
-            let app_mentions = parse_optional_value(params, "appMentions")
-                .and_then(|value| value.as_array().cloned());
-            let collaboration_mode = parse_optional_value(params, "collaborationMode");
+            let request = parse_request_or_err!(params, codex_rpc::SendUserMessageRequest);
             Some(
                 state
                     .send_user_message(
-                        workspace_id,
-                        thread_id,
-                        text,
-                        model,
-                        effort,
-                        access_mode,
-                        images,
-                        app_mentions,
-                        collaboration_mode,
+                        request.workspace_id,
+                        request.thread_id,
+                        request.text,
+                        request.model,
+                        request.effort,
+                        request.access_mode,
+                        request.images,
+                        request.app_mentions,
+                        request.collaboration_mode,
                     )
                     .await,
             )
         }
         "run_bang_command" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let command = match parse_string(params, "command") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.run_bang_command(workspace_id, command).await)
+            let request = parse_request_or_err!(params, codex_rpc::RunBangCommandRequest);
+            Some(
+                state
+                    .run_bang_command(request.workspace_id, request.command)
+                    .await,
+            )
         }
         "turn_interrupt" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let thread_id = match parse_string(params, "threadId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let turn_id = match parse_string(params, "turnId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.turn_interrupt(workspace_id, thread_id, turn_id).await)
+            let request = parse_request_or_err!(params, codex_rpc::TurnInterruptRequest);
+            Some(
+                state
+                    .turn_interrupt(request.workspace_id, request.thread_id, request.turn_id)
+                    .await,
+            )
         }
         "turn_steer" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let thread_id = match parse_string(params, "threadId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let turn_id = match parse_string(params, "turnId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let text = match parse_string(params, "text") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let images = parse_optional_string_array(params, "images");
-            let app_mentions = parse_optional_value(params, "appMentions")
-                .and_then(|value| value.as_array().cloned());
+            let request = parse_request_or_err!(params, codex_rpc::TurnSteerRequest);
             Some(
                 state
-                    .turn_steer(workspace_id, thread_id, turn_id, text, images, app_mentions)
+                    .turn_steer(
+                        request.workspace_id,
+                        request.thread_id,
+                        request.turn_id,
+                        request.text,
+                        request.images,
+                        request.app_mentions,
+                    )
                     .await,
             )
         }
         "start_review" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let thread_id = match parse_string(params, "threadId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let target = match params
-                .as_object()
-                .and_then(|map| map.get("target"))
-                .cloned()
-                .ok_or("missing `target`")
-            {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err.to_string())),
-            };
-            let delivery = parse_optional_string(params, "delivery");
+            let request = parse_request_or_err!(params, codex_rpc::StartReviewRequest);
             Some(
                 state
-                    .start_review(workspace_id, thread_id, target, delivery)
+                    .start_review(
+                        request.workspace_id,
+                        request.thread_id,
+                        request.target,
+                        request.delivery,
+                    )
                     .await,
             )
         }
         "model_list" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.model_list(workspace_id).await)
+            let request = parse_request_or_err!(params, codex_rpc::WorkspaceIdRequest);
+            Some(state.model_list(request.workspace_id).await)
         }
         "experimental_feature_list" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let cursor = parse_optional_string(params, "cursor");
-            let limit = parse_optional_u32(params, "limit");
+            let request = parse_request_or_err!(params, codex_rpc::WorkspacePageRequest);
             Some(
                 state
-                    .experimental_feature_list(workspace_id, cursor, limit)
+                    .experimental_feature_list(request.workspace_id, request.cursor, request.limit)
                     .await,
             )
         }
         "collaboration_mode_list" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.collaboration_mode_list(workspace_id).await)
+            let request = parse_request_or_err!(params, codex_rpc::WorkspaceIdRequest);
+            Some(state.collaboration_mode_list(request.workspace_id).await)
         }
         "set_codex_feature_flag" => {
-            let feature_key = match parse_string(params, "featureKey") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let enabled = match parse_optional_bool(params, "enabled") {
-                Some(value) => value,
-                None => return Some(Err("missing or invalid `enabled`".to_string())),
-            };
+            let request = parse_request_or_err!(params, codex_rpc::SetCodexFeatureFlagRequest);
             Some(
                 state
-                    .set_codex_feature_flag(feature_key, enabled)
+                    .set_codex_feature_flag(request.feature_key, request.enabled)
                     .await
-                    .map(|_| json!({ "ok": true })),
+                    .map(|_| json!(OkResponse::OK)),
             )
         }
-        "get_agents_settings" => Some(
-            state
-                .get_agents_settings()
-                .await
-                .and_then(|value| serde_json::to_value(value).map_err(|err| err.to_string())),
-        ),
+        "get_agents_settings" => Some(state.get_agents_settings().await.and_then(|value| {
+            serde_json::to_value(value).map_err(|err| CoreError::internal(err.to_string()))
+        })),
         "set_agents_core_settings" => {
-            let input = match parse_input::<agents_config_core::SetAgentsCoreInput>(params) {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
+            let request = parse_request_or_err!(
+                params,
+                codex_rpc::InputRequest<agents_config_core::SetAgentsCoreInput>
+            );
             Some(
                 state
-                    .set_agents_core_settings(input)
+                    .set_agents_core_settings(request.input)
                     .await
-                    .and_then(|value| serde_json::to_value(value).map_err(|err| err.to_string())),
+                    .and_then(|value| {
+                        serde_json::to_value(value)
+                            .map_err(|err| CoreError::internal(err.to_string()))
+                    }),
             )
         }
         "create_agent" => {
-            let input = match parse_input::<agents_config_core::CreateAgentInput>(params) {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(
-                state
-                    .create_agent(input)
-                    .await
-                    .and_then(|value| serde_json::to_value(value).map_err(|err| err.to_string())),
-            )
+            let request = parse_request_or_err!(
+                params,
+                codex_rpc::InputRequest<agents_config_core::CreateAgentInput>
+            );
+            Some(state.create_agent(request.input).await.and_then(|value| {
+                serde_json::to_value(value).map_err(|err| CoreError::internal(err.to_string()))
+            }))
         }
         "update_agent" => {
-            let input = match parse_input::<agents_config_core::UpdateAgentInput>(params) {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(
-                state
-                    .update_agent(input)
-                    .await
-                    .and_then(|value| serde_json::to_value(value).map_err(|err| err.to_string())),
-            )
+            let request = parse_request_or_err!(
+                params,
+                codex_rpc::InputRequest<agents_config_core::UpdateAgentInput>
+            );
+            Some(state.update_agent(request.input).await.and_then(|value| {
+                serde_json::to_value(value).map_err(|err| CoreError::internal(err.to_string()))
+            }))
         }
         "delete_agent" => {
-            let input = match parse_input::<agents_config_core::DeleteAgentInput>(params) {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(
-                state
-                    .delete_agent(input)
-                    .await
-                    .and_then(|value| serde_json::to_value(value).map_err(|err| err.to_string())),
-            )
+            let request = parse_request_or_err!(
+                params,
+                codex_rpc::InputRequest<agents_config_core::DeleteAgentInput>
+            );
+            Some(state.delete_agent(request.input).await.and_then(|value| {
+                serde_json::to_value(value).map_err(|err| CoreError::internal(err.to_string()))
+            }))
         }
         "read_agent_config_toml" => {
-            let agent_name = match parse_string(params, "agentName") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
+            let request = parse_request_or_err!(params, codex_rpc::AgentNameRequest);
             Some(
                 state
-                    .read_agent_config_toml(agent_name)
+                    .read_agent_config_toml(request.agent_name)
                     .await
-                    .and_then(|value| serde_json::to_value(value).map_err(|err| err.to_string())),
+                    .and_then(|value| {
+                        serde_json::to_value(value)
+                            .map_err(|err| CoreError::internal(err.to_string()))
+                    }),
             )
         }
         "write_agent_config_toml" => {
-            let agent_name = match parse_string(params, "agentName") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let content = match parse_string(params, "content") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
+            let request = parse_request_or_err!(params, codex_rpc::WriteAgentConfigTomlRequest);
             Some(
                 state
-                    .write_agent_config_toml(agent_name, content)
+                    .write_agent_config_toml(request.agent_name, request.content)
                     .await
-                    .map(|_| json!({ "ok": true })),
+                    .map(|_| json!(OkResponse::OK)),
             )
         }
         "account_rate_limits" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.account_rate_limits(workspace_id).await)
+            let request = parse_request_or_err!(params, codex_rpc::WorkspaceIdRequest);
+            Some(state.account_rate_limits(request.workspace_id).await)
         }
         "account_read" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.account_read(workspace_id).await)
+            let request = parse_request_or_err!(params, codex_rpc::WorkspaceIdRequest);
+            Some(state.account_read(request.workspace_id).await)
         }
         "dictation_auth_status" => {
-            let workspace_id = parse_optional_string(params, "workspaceId");
+            let request = parse_request_or_err!(params, codex_rpc::OptionalWorkspaceIdRequest);
             Some(
                 state
-                    .dictation_auth_status(workspace_id)
+                    .dictation_auth_status(request.workspace_id)
                     .await
-                    .and_then(|value| serde_json::to_value(value).map_err(|err| err.to_string())),
+                    .and_then(|value| {
+                        serde_json::to_value(value)
+                            .map_err(|err| CoreError::internal(err.to_string()))
+                    }),
             )
         }
         "dictation_transcribe" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let audio = match parse_string(params, "audio") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let mime_type = match parse_string(params, "mimeType") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let language = parse_optional_string(params, "language");
+            let request = parse_request_or_err!(params, codex_rpc::DictationTranscribeRequest);
             Some(
                 state
-                    .dictation_transcribe(workspace_id, audio, mime_type, language)
+                    .dictation_transcribe(
+                        request.workspace_id,
+                        request.audio,
+                        request.mime_type,
+                        request.language,
+                    )
                     .await,
             )
         }
         "codex_login" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.codex_login(workspace_id).await)
+            let request = parse_request_or_err!(params, codex_rpc::WorkspaceIdRequest);
+            Some(state.codex_login(request.workspace_id).await)
         }
         "codex_login_cancel" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.codex_login_cancel(workspace_id).await)
+            let request = parse_request_or_err!(params, codex_rpc::WorkspaceIdRequest);
+            Some(state.codex_login_cancel(request.workspace_id).await)
         }
         "skills_list" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.skills_list(workspace_id).await)
+            let request = parse_request_or_err!(params, codex_rpc::WorkspaceIdRequest);
+            Some(state.skills_list(request.workspace_id).await)
         }
         "apps_list" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let cursor = parse_optional_string(params, "cursor");
-            let limit = parse_optional_u32(params, "limit");
-            let thread_id = parse_optional_string(params, "threadId");
+            let request = parse_request_or_err!(params, codex_rpc::AppsListRequest);
             Some(
                 state
-                    .apps_list(workspace_id, cursor, limit, thread_id)
+                    .apps_list(
+                        request.workspace_id,
+                        request.cursor,
+                        request.limit,
+                        request.thread_id,
+                    )
                     .await,
             )
         }
         "respond_to_server_request" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let map = match params.as_object().ok_or("missing requestId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err.to_string())),
-            };
-            let request_id = match map
-                .get("requestId")
-                .cloned()
-                .filter(|value| value.is_number() || value.is_string())
-                .ok_or("missing requestId")
-            {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err.to_string())),
-            };
-            let result = match map.get("result").cloned().ok_or("missing `result`") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err.to_string())),
-            };
+            let request = parse_request_or_err!(params, codex_rpc::RespondToServerRequestRequest);
+            if !request.request_id.is_number() && !request.request_id.is_string() {
+                return Some(Err(CoreError::invalid_params("missing requestId")));
+            }
             Some(
                 state
-                    .respond_to_server_request(workspace_id, request_id, result)
+                    .respond_to_server_request(
+                        request.workspace_id,
+                        request.request_id,
+                        request.result,
+                    )
                     .await,
             )
         }
         "remember_approval_rule" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let command = match parse_string_array(params, "command") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.remember_approval_rule(workspace_id, command).await)
+            let request = parse_request_or_err!(params, codex_rpc::RememberApprovalRuleRequest);
+            Some(
+                state
+                    .remember_approval_rule(request.workspace_id, request.command)
+                    .await,
+            )
         }
         "codex_doctor" => {
-            let codex_bin = parse_optional_string(params, "codexBin");
-            let codex_args = parse_optional_string(params, "codexArgs");
-            Some(state.codex_doctor(codex_bin, codex_args).await)
+            let request = parse_request_or_err!(params, codex_rpc::CodexDoctorRequest);
+            Some(
+                state
+                    .codex_doctor(request.codex_bin, request.codex_args)
+                    .await,
+            )
         }
         "generate_run_metadata" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let prompt = match parse_string(params, "prompt") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(state.generate_run_metadata(workspace_id, prompt).await)
+            let request = parse_request_or_err!(params, codex_rpc::GenerateRunMetadataRequest);
+            Some(
+                state
+                    .generate_run_metadata(request.workspace_id, request.prompt)
+                    .await,
+            )
         }
         "generate_agent_description" => {
-            let workspace_id = match parse_string(params, "workspaceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let description = match parse_string(params, "description") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
+            let request = parse_request_or_err!(params, codex_rpc::GenerateAgentDescriptionRequest);
             Some(
                 state
-                    .generate_agent_description(workspace_id, description)
+                    .generate_agent_description(request.workspace_id, request.description)
                     .await
-                    .and_then(|value| serde_json::to_value(value).map_err(|err| err.to_string())),
+                    .and_then(|value| {
+                        serde_json::to_value(value)
+                            .map_err(|err| CoreError::internal(err.to_string()))
+                    }),
             )
         }
         _ => None,
@@ -1,38 +1,82 @@
 use super::*;
+use crate::shared::codex_rpc;
+use crate::shared::rpc_schema;
+use serde::de::DeserializeOwned;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct DeviceIdRequest {
+    pub(super) device_id: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct PushDeviceRulesPatchRequest {
+    pub(super) device_id: String,
+    pub(super) rules: push::PushDeviceRulesPatch,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(super) struct MenuSetAcceleratorsRequest {
+    #[serde(default)]
+    pub(super) updates: Vec<Value>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(super) struct NotificationFallbackRequest {
+    pub(super) title: String,
+    pub(super) body: String,
+}
+
+fn parse_daemon_request<T: DeserializeOwned>(params: &Value) -> Result<T, CoreError> {
+    codex_rpc::from_params(params)
+}
+
+macro_rules! parse_request_or_err {
+    ($params:expr, $ty:ty) => {
+        match parse_daemon_request::<$ty>($params) {
+            Ok(value) => value,
+            Err(err) => return Some(Err(err)),
+        }
+    };
+}
 
 pub(super) async fn try_handle(
     state: &DaemonState,
     method: &str,
     params: &Value,
-) -> Option<Result<Value, String>> {
+) -> Option<Result<Value, CoreError>> {
     match method {
-        "ping" => Some(Ok(json!({ "ok": true }))),
-        "daemon_info" => Some(Ok(state.daemon_info())),
+        "ping" => Some(Ok(json!(OkResponse::OK))),
+        "daemon_info" => Some(Ok(json!(state.daemon_info()))),
+        rpc_schema::METHOD_RPC_DESCRIBE => Some(Ok(catalog::describe().clone())),
+        "daemon_metrics" => Some(Ok(state.metrics_report().await.to_json())),
+        "daemon_logs_tail" => {
+            let query = parse_request_or_err!(params, logging_core::LogTailQuery);
+            Some(logging_core::tail_value(&query))
+        }
         "daemon_shutdown" => {
-            tokio::spawn(async {
-                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-                std::process::exit(0);
-            });
-            Some(Ok(json!({ "ok": true })))
+            let request = match shutdown::ShutdownRequest::from_params(params) {
+                Ok(request) => request,
+                Err(err) => return Some(Err(err)),
+            };
+            if !state.shutdown.request(request) {
+                return Some(Err(CoreError::shutting_down()));
+            }
+            Some(Ok(json!(shutdown::ShutdownResponse {
+                ok: true,
+                deadline_secs: request.deadline.as_secs(),
+                restart: request.restart,
+                running_turns: shutdown::running_turn_workspaces(state).await,
+            })))
         }
         "menu_set_accelerators" => {
-            let updates: Vec<Value> = match params {
-                Value::Object(map) => match map
-                    .get("updates")
-                    .cloned()
-                    .map(serde_json::from_value)
-                    .transpose()
-                {
-                    Ok(value) => value.unwrap_or_default(),
-                    Err(err) => return Some(Err(err.to_string())),
-                },
-                _ => Vec::new(),
-            };
+            let request = parse_request_or_err!(params, MenuSetAcceleratorsRequest);
             Some(
                 state
-                    .menu_set_accelerators(updates)
+                    .menu_set_accelerators(request.updates)
                     .await
-                    .map(|_| json!({ "ok": true })),
+                    .map(|_| json!(OkResponse::OK)),
             )
         }
         "is_macos_debug_build" => {
@@ -40,61 +84,51 @@ pub(super) async fn try_handle(
             Some(Ok(Value::Bool(is_debug)))
         }
         "send_notification_fallback" => {
-            let title = match parse_string(params, "title") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            let body = match parse_string(params, "body") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
+            let request = parse_request_or_err!(params, NotificationFallbackRequest);
             Some(
                 state
-                    .send_notification_fallback(title, body)
+                    .send_notification_fallback(request.title, request.body)
                     .await
-                    .map(|_| json!({ "ok": true })),
+                    .map(|_| json!(OkResponse::OK)),
             )
         }
         "presence_heartbeat" => {
-            let input = match serde_json::from_value::<push::PresenceHeartbeatInput>(params.clone())
-            {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err.to_string())),
-            };
+            let input = parse_request_or_err!(params, push::PresenceHeartbeatInput);
             Some(
                 state
                     .presence_heartbeat(input)
                     .await
-                    .map(|_| json!({ "ok": true })),
+                    .map(|_| json!(OkResponse::OK)),
             )
         }
         "push_register_device" => {
-            let input =
-                match serde_json::from_value::<push::PushDeviceRegistrationInput>(params.clone()) {
-                    Ok(value) => value,
-                    Err(err) => return Some(Err(err.to_string())),
-                };
+            let input = parse_request_or_err!(params, push::PushDeviceRegistrationInput);
             Some(state.push_register_device(input).await)
         }
         "push_unregister_device" => {
-            let device_id = match parse_string(params, "deviceId") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
+            let request = parse_request_or_err!(params, DeviceIdRequest);
             Some(
                 state
-                    .push_unregister_device(device_id)
+                    .push_unregister_device(request.device_id)
                     .await
-                    .map(|_| json!({ "ok": true })),
+                    .map(|_| json!(OkResponse::OK)),
+            )
+        }
+        "push_device_rules_get" => {
+            let request = parse_request_or_err!(params, DeviceIdRequest);
+            Some(state.push_device_rules_get(request.device_id).await)
+        }
+        "push_device_rules_patch" => {
+            let request = parse_request_or_err!(params, PushDeviceRulesPatchRequest);
+            Some(
+                state
+                    .push_device_rules_patch(request.device_id, request.rules)
+                    .await,
             )
         }
         "push_notification_config_get" => Some(state.push_notification_config_get().await),
         "push_notification_config_patch" => {
-            let patch =
-                match serde_json::from_value::<push::PushNotificationConfigPatch>(params.clone()) {
-                    Ok(value) => value,
-                    Err(err) => return Some(Err(err.to_string())),
-                };
+            let patch = parse_request_or_err!(params, push::PushNotificationConfigPatch);
             Some(state.push_notification_config_patch(patch).await)
         }
         "push_notification_state" => Some(state.push_notification_state().await),
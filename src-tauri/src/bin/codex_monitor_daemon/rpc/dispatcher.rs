@@ -1,11 +1,13 @@
 use super::*;
+use crate::shared::core_error::CoreError;
+use crate::shared::rpc_schema;
 
 pub(super) async fn dispatch_rpc_request(
     state: &DaemonState,
     method: &str,
     params: &Value,
     client_version: &str,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     if let Some(result) = daemon::try_handle(state, method, params).await {
         return result;
     }
@@ -26,5 +28,13 @@ pub(super) async fn dispatch_rpc_request(
         return result;
     }
 
-    Err(format!("unknown method: {method}"))
+    if let Some(result) = terminal::try_handle(state, method, params).await {
+        return result;
+    }
+
+    Err(CoreError::unknown_method(format!(
+        "unknown method: {method} (daemon protocol v{})",
+        rpc_schema::PROTOCOL_VERSION
+    ))
+    .with_data(json!({ "protocolVersion": rpc_schema::PROTOCOL_VERSION })))
 }
@@ -4,27 +4,27 @@ use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::future::Future;
 
-fn parse_git_request<T: DeserializeOwned>(params: &Value) -> Result<T, String> {
+fn parse_git_request<T: DeserializeOwned>(params: &Value) -> Result<T, CoreError> {
     git_rpc::from_params(params)
 }
 
-fn serialize_value<T: Serialize>(value: T) -> Result<Value, String> {
-    serde_json::to_value(value).map_err(|err| err.to_string())
+fn serialize_value<T: Serialize>(value: T) -> Result<Value, CoreError> {
+    serde_json::to_value(value).map_err(|err| CoreError::internal(err.to_string()))
 }
 
-async fn serialize_result<T, Fut>(future: Fut) -> Result<Value, String>
+async fn serialize_result<T, Fut>(future: Fut) -> Result<Value, CoreError>
 where
     T: Serialize,
-    Fut: Future<Output = Result<T, String>>,
+    Fut: Future<Output = Result<T, CoreError>>,
 {
     future.await.and_then(serialize_value)
 }
 
-async fn serialize_ok<Fut>(future: Fut) -> Result<Value, String>
+async fn serialize_ok<Fut>(future: Fut) -> Result<Value, CoreError>
 where
-    Fut: Future<Output = Result<(), String>>,
+    Fut: Future<Output = Result<(), CoreError>>,
 {
-    future.await.map(|_| json!({ "ok": true }))
+    future.await.map(|_| json!(OkResponse::OK))
 }
 
 macro_rules! parse_request_or_err {
@@ -40,38 +40,40 @@ pub(super) async fn try_handle(
     state: &DaemonState,
     method: &str,
     params: &Value,
-) -> Option<Result<Value, String>> {
+) -> Option<Result<Value, CoreError>> {
     match method {
         git_rpc::METHOD_GET_GIT_STATUS => {
             let request = parse_request_or_err!(params, git_rpc::WorkspaceIdRequest);
             Some(state.get_git_status(request.workspace_id).await)
         }
         git_rpc::METHOD_INIT_GIT_REPO => {
-            let request = parse_request_or_err!(params, git_rpc::InitGitRepoRequiredRequest);
-            let force = parse_optional_bool(params, "force").unwrap_or(false);
+            let request = parse_request_or_err!(params, git_rpc::InitGitRepoRequest);
             Some(
                 state
-                    .init_git_repo(request.workspace_id, request.branch, force)
+                    .init_git_repo(
+                        request.workspace_id,
+                        request.branch,
+                        request.force.unwrap_or(false),
+                    )
                     .await,
             )
         }
         git_rpc::METHOD_CREATE_GITHUB_REPO => {
-            let request = parse_request_or_err!(params, git_rpc::CreateGitHubRepoRequiredRequest);
-            let branch = parse_optional_string(params, "branch");
+            let request = parse_request_or_err!(params, git_rpc::CreateGitHubRepoRequest);
             Some(
                 state
                     .create_github_repo(
                         request.workspace_id,
                         request.repo,
                         request.visibility,
-                        branch,
+                        request.branch,
                     )
                     .await,
             )
         }
         git_rpc::METHOD_LIST_GIT_ROOTS => {
-            let request = parse_request_or_err!(params, git_rpc::WorkspaceIdRequest);
-            let depth = parse_optional_u32(params, "depth").map(|value| value as usize);
+            let request = parse_request_or_err!(params, git_rpc::ListGitRootsRequest);
+            let depth = request.depth.map(|value| value as usize);
             Some(serialize_result(state.list_git_roots(request.workspace_id, depth)).await)
         }
         git_rpc::METHOD_GET_GIT_DIFFS => {
@@ -79,8 +81,8 @@ pub(super) async fn try_handle(
             Some(serialize_result(state.get_git_diffs(request.workspace_id)).await)
         }
         git_rpc::METHOD_GET_GIT_LOG => {
-            let request = parse_request_or_err!(params, git_rpc::WorkspaceIdRequest);
-            let limit = parse_optional_u32(params, "limit").map(|value| value as usize);
+            let request = parse_request_or_err!(params, git_rpc::GetGitLogRequest);
+            let limit = request.limit.map(|value| value as usize);
             Some(serialize_result(state.get_git_log(request.workspace_id, limit)).await)
         }
         git_rpc::METHOD_GET_GIT_COMMIT_DIFF => {
@@ -182,11 +184,10 @@ pub(super) async fn try_handle(
             Some(serialize_ok(state.create_git_branch(request.workspace_id, request.name)).await)
         }
         git_rpc::METHOD_GENERATE_COMMIT_MESSAGE => {
-            let request = parse_request_or_err!(params, git_rpc::WorkspaceIdRequest);
-            let commit_message_model_id = parse_optional_string(params, "commitMessageModelId");
+            let request = parse_request_or_err!(params, git_rpc::GenerateCommitMessageRequest);
             Some(
                 state
-                    .generate_commit_message(request.workspace_id, commit_message_model_id)
+                    .generate_commit_message(request.workspace_id, request.commit_message_model_id)
                     .await
                     .map(Value::String),
             )
@@ -0,0 +1,266 @@
+use super::*;
+use crate::auth_tokens::{AuthTokenRecord, AuthTokenStore, TokenRole};
+use crate::event_replay::event_workspace_id;
+use crate::shared::core_error::{CoreError, ErrorCode};
+use crate::shared::git_rpc;
+
+/// Read-only methods: listing and inspecting workspaces, threads and git state.
+const OBSERVER_METHODS: &[&str] = &[
+    "ping",
+    "daemon_info",
+    "rpc_describe",
+    "is_macos_debug_build",
+    "presence_heartbeat",
+    "list_workspaces",
+    "worktree_setup_status",
+    "list_workspace_files",
+    "read_workspace_file",
+    "file_read",
+    "get_open_app_icon",
+    "get_config_model",
+    "resume_thread",
+    "thread_live_subscribe",
+    "thread_live_unsubscribe",
+    "list_threads",
+    "list_mcp_server_status",
+    "model_list",
+    "experimental_feature_list",
+    "collaboration_mode_list",
+    "get_agents_settings",
+    "account_rate_limits",
+    "account_read",
+    "dictation_auth_status",
+    "skills_list",
+    "apps_list",
+    "prompts_list",
+    "prompts_workspace_dir",
+    "prompts_global_dir",
+    git_rpc::METHOD_GET_GIT_STATUS,
+    git_rpc::METHOD_LIST_GIT_ROOTS,
+    git_rpc::METHOD_GET_GIT_DIFFS,
+    git_rpc::METHOD_GET_GIT_LOG,
+    git_rpc::METHOD_GET_GIT_COMMIT_DIFF,
+    git_rpc::METHOD_GET_GIT_REMOTE,
+    git_rpc::METHOD_GET_GITHUB_ISSUES,
+    git_rpc::METHOD_GET_GITHUB_PULL_REQUESTS,
+    git_rpc::METHOD_GET_GITHUB_PULL_REQUEST_DIFF,
+    git_rpc::METHOD_GET_GITHUB_PULL_REQUEST_COMMENTS,
+    git_rpc::METHOD_LIST_GIT_BRANCHES,
+];
+
+/// Day-to-day work inside existing workspaces: driving threads, answering
+/// approvals, editing prompts and running git operations.
+const OPERATOR_METHODS: &[&str] = &[
+    "is_workspace_path_dir",
+    "read_image_as_data_url",
+    "local_usage_snapshot",
+    "get_codex_config_path",
+    "read_agent_config_toml",
+    "codex_doctor",
+    "connect_workspace",
+    "worktree_setup_mark_ran",
+    "add_worktree",
+    "remove_worktree",
+    "rename_worktree",
+    "rename_worktree_upstream",
+    "update_workspace_settings",
+    "apply_worktree_changes",
+    "start_thread",
+    "fork_thread",
+    "archive_thread",
+    "compact_thread",
+    "set_thread_name",
+    "send_user_message",
+    "turn_interrupt",
+    "turn_steer",
+    "start_review",
+    "respond_to_server_request",
+    "remember_approval_rule",
+    "dictation_transcribe",
+    "generate_run_metadata",
+    "generate_agent_description",
+    "prompts_create",
+    "prompts_update",
+    "prompts_delete",
+    "prompts_move",
+    "send_notification_fallback",
+    "push_register_device",
+    "push_unregister_device",
+    "push_device_rules_get",
+    "push_device_rules_patch",
+    "push_notification_state",
+    git_rpc::METHOD_INIT_GIT_REPO,
+    git_rpc::METHOD_CREATE_GITHUB_REPO,
+    git_rpc::METHOD_STAGE_GIT_FILE,
+    git_rpc::METHOD_STAGE_GIT_ALL,
+    git_rpc::METHOD_UNSTAGE_GIT_FILE,
+    git_rpc::METHOD_REVERT_GIT_FILE,
+    git_rpc::METHOD_REVERT_GIT_ALL,
+    git_rpc::METHOD_COMMIT_GIT,
+    git_rpc::METHOD_PUSH_GIT,
+    git_rpc::METHOD_PULL_GIT,
+    git_rpc::METHOD_FETCH_GIT,
+    git_rpc::METHOD_SYNC_GIT,
+    git_rpc::METHOD_CHECKOUT_GITHUB_PULL_REQUEST,
+    git_rpc::METHOD_CHECKOUT_GIT_BRANCH,
+    git_rpc::METHOD_CREATE_GIT_BRANCH,
+    git_rpc::METHOD_GENERATE_COMMIT_MESSAGE,
+];
+
+/// Methods whose `id` param is a workspace id rather than some other entity.
+const WORKSPACE_ID_METHODS: &[&str] = &[
+    "connect_workspace",
+    "remove_workspace",
+    "remove_worktree",
+    "rename_worktree",
+    "rename_worktree_upstream",
+    "update_workspace_settings",
+];
+
+const WORKSPACE_PARAM_KEYS: &[&str] = &["workspaceId", "parentId", "sourceWorkspaceId"];
+
+/// What an authenticated connection may do. The shared `--token` and
+/// unauthenticated dev mode map to an unrestricted admin scope.
+#[derive(Clone, Debug)]
+pub(crate) struct ClientScope {
+    pub(crate) token_id: Option<String>,
+    pub(crate) role: TokenRole,
+    pub(crate) workspace_ids: Option<HashSet<String>>,
+}
+
+impl ClientScope {
+    pub(crate) fn unrestricted() -> Self {
+        Self {
+            token_id: None,
+            role: TokenRole::Admin,
+            workspace_ids: None,
+        }
+    }
+
+    pub(crate) fn from_record(record: &AuthTokenRecord) -> Self {
+        Self {
+            token_id: Some(record.id.clone()),
+            role: record.role,
+            workspace_ids: record
+                .workspace_ids
+                .as_ref()
+                .map(|ids| ids.iter().cloned().collect()),
+        }
+    }
+
+    pub(crate) fn allows_workspace(&self, workspace_id: &str) -> bool {
+        self.workspace_ids
+            .as_ref()
+            .is_none_or(|ids| ids.contains(workspace_id))
+    }
+}
+
+/// Methods not listed for observers or operators require admin, so newly
+/// added methods stay locked down until they are classified.
+pub(super) fn required_role(method: &str) -> TokenRole {
+    if OBSERVER_METHODS.contains(&method) {
+        TokenRole::Observer
+    } else if OPERATOR_METHODS.contains(&method) {
+        TokenRole::Operator
+    } else {
+        TokenRole::Admin
+    }
+}
+
+fn requested_workspace_ids(method: &str, params: &Value) -> Vec<String> {
+    let mut ids: Vec<String> = WORKSPACE_PARAM_KEYS
+        .iter()
+        .filter_map(|key| parse_optional_string(params, key))
+        .collect();
+    if WORKSPACE_ID_METHODS.contains(&method) {
+        ids.extend(parse_optional_string(params, "id"));
+    }
+    ids
+}
+
+pub(super) fn authorize(
+    scope: &ClientScope,
+    method: &str,
+    params: &Value,
+) -> Result<(), CoreError> {
+    let required = required_role(method);
+    if scope.role < required {
+        return Err(CoreError::new(
+            ErrorCode::Forbidden,
+            format!(
+                "forbidden: `{method}` requires the {} role",
+                required.as_str()
+            ),
+        )
+        .with_data(json!({ "requiredRole": required.as_str() })));
+    }
+    if scope.workspace_ids.is_none() {
+        return Ok(());
+    }
+    let workspace_ids = requested_workspace_ids(method, params);
+    if workspace_ids.is_empty() {
+        // Workspace-scoped tokens may read daemon-wide state but not change it.
+        if required > TokenRole::Observer {
+            return Err(CoreError::new(
+                ErrorCode::Forbidden,
+                format!("forbidden: `{method}` is not available to workspace-scoped tokens"),
+            ));
+        }
+        return Ok(());
+    }
+    match workspace_ids
+        .iter()
+        .find(|workspace_id| !scope.allows_workspace(workspace_id))
+    {
+        Some(workspace_id) => Err(CoreError::new(
+            ErrorCode::Forbidden,
+            format!("forbidden: workspace {workspace_id} is outside this token's scope"),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Drops entries for workspaces the scope cannot see from list results.
+pub(super) fn filter_rpc_result(scope: &ClientScope, method: &str, result: Value) -> Value {
+    if scope.workspace_ids.is_none() || method != "list_workspaces" {
+        return result;
+    }
+    match result {
+        Value::Array(entries) => Value::Array(
+            entries
+                .into_iter()
+                .filter(|entry| {
+                    entry
+                        .get("id")
+                        .and_then(Value::as_str)
+                        .is_some_and(|id| scope.allows_workspace(id))
+                })
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+pub(super) fn event_in_scope(scope: &ClientScope, event: &DaemonEvent) -> bool {
+    scope.allows_workspace(event_workspace_id(event))
+}
+
+/// Re-reads a named token's record so role changes and revocations apply to
+/// open connections. Returns `None` once the token no longer exists.
+pub(crate) fn refresh_client_scope(
+    auth_tokens: &AuthTokenStore,
+    scope: &std::sync::RwLock<ClientScope>,
+) -> Option<ClientScope> {
+    let current = scope
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone();
+    let Some(token_id) = current.token_id.as_deref() else {
+        return Some(current);
+    };
+    let refreshed = ClientScope::from_record(&auth_tokens.find_by_id(token_id)?);
+    *scope
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = refreshed.clone();
+    Some(refreshed)
+}
@@ -0,0 +1,92 @@
+use super::*;
+use crate::shared::terminal_rpc;
+use serde::de::DeserializeOwned;
+
+fn parse_terminal_request<T: DeserializeOwned>(params: &Value) -> Result<T, CoreError> {
+    terminal_rpc::from_params(params)
+}
+
+macro_rules! parse_request_or_err {
+    ($params:expr, $ty:ty) => {
+        match parse_terminal_request::<$ty>($params) {
+            Ok(value) => value,
+            Err(err) => return Some(Err(err)),
+        }
+    };
+}
+
+pub(super) async fn try_handle(
+    state: &DaemonState,
+    method: &str,
+    params: &Value,
+) -> Option<Result<Value, CoreError>> {
+    match method {
+        terminal_rpc::METHOD_TERMINAL_OPEN => {
+            let request = parse_request_or_err!(params, terminal_rpc::TerminalOpenRequest);
+            let opened = match state
+                .terminal_open(
+                    request.workspace_id,
+                    request.terminal_id,
+                    request.cols,
+                    request.rows,
+                )
+                .await
+            {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            Some(serde_json::to_value(opened).map_err(|err| CoreError::internal(err.to_string())))
+        }
+        terminal_rpc::METHOD_TERMINAL_WRITE => {
+            let request = parse_request_or_err!(params, terminal_rpc::TerminalWriteRequest);
+            Some(
+                state
+                    .terminal_write(request.workspace_id, request.terminal_id, request.data)
+                    .await
+                    .map(|_| json!(OkResponse::OK)),
+            )
+        }
+        terminal_rpc::METHOD_TERMINAL_RESIZE => {
+            let request = parse_request_or_err!(params, terminal_rpc::TerminalResizeRequest);
+            Some(
+                state
+                    .terminal_resize(
+                        request.workspace_id,
+                        request.terminal_id,
+                        request.cols,
+                        request.rows,
+                    )
+                    .await
+                    .map(|_| json!(OkResponse::OK)),
+            )
+        }
+        terminal_rpc::METHOD_TERMINAL_ATTACH => {
+            let request = parse_request_or_err!(params, terminal_rpc::TerminalIdRequest);
+            let attached = match state
+                .terminal_attach(request.workspace_id, request.terminal_id)
+                .await
+            {
+                Ok(value) => value,
+                Err(err) => return Some(Err(err)),
+            };
+            Some(serde_json::to_value(attached).map_err(|err| CoreError::internal(err.to_string())))
+        }
+        terminal_rpc::METHOD_TERMINAL_LIST => {
+            let request = parse_request_or_err!(params, terminal_rpc::TerminalListRequest);
+            let terminals = state.terminal_list(request.workspace_id).await;
+            Some(
+                serde_json::to_value(terminals).map_err(|err| CoreError::internal(err.to_string())),
+            )
+        }
+        terminal_rpc::METHOD_TERMINAL_CLOSE => {
+            let request = parse_request_or_err!(params, terminal_rpc::TerminalIdRequest);
+            Some(
+                state
+                    .terminal_close(request.workspace_id, request.terminal_id)
+                    .await
+                    .map(|_| json!(OkResponse::OK)),
+            )
+        }
+        _ => None,
+    }
+}
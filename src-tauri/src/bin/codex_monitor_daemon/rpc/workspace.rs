@@ -4,52 +4,27 @@ use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::future::Future;
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct FileReadRequest {
-    scope: file_policy::FileScope,
-    kind: file_policy::FileKind,
-    workspace_id: Option<String>,
-}
-
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct FileWriteRequest {
-    scope: file_policy::FileScope,
-    kind: file_policy::FileKind,
-    workspace_id: Option<String>,
-    content: String,
-}
-
-fn parse_file_read_request(params: &Value) -> Result<FileReadRequest, String> {
-    serde_json::from_value(params.clone()).map_err(|err| err.to_string())
-}
-
-fn parse_file_write_request(params: &Value) -> Result<FileWriteRequest, String> {
-    serde_json::from_value(params.clone()).map_err(|err| err.to_string())
-}
-
-fn parse_workspace_request<T: DeserializeOwned>(params: &Value) -> Result<T, String> {
+fn parse_workspace_request<T: DeserializeOwned>(params: &Value) -> Result<T, CoreError> {
     workspace_rpc::from_params(params)
 }
 
-fn serialize_value<T: Serialize>(value: T) -> Result<Value, String> {
-    serde_json::to_value(value).map_err(|err| err.to_string())
+fn serialize_value<T: Serialize>(value: T) -> Result<Value, CoreError> {
+    serde_json::to_value(value).map_err(|err| CoreError::internal(err.to_string()))
 }
 
-async fn serialize_result<T, Fut>(future: Fut) -> Result<Value, String>
+async fn serialize_result<T, Fut>(future: Fut) -> Result<Value, CoreError>
 where
     T: Serialize,
-    Fut: Future<Output = Result<T, String>>,
+    Fut: Future<Output = Result<T, CoreError>>,
 {
     future.await.and_then(serialize_value)
 }
 
-async fn serialize_ok<Fut>(future: Fut) -> Result<Value, String>
+async fn serialize_ok<Fut>(future: Fut) -> Result<Value, CoreError>
 where
-    Fut: Future<Output = Result<(), String>>,
+    Fut: Future<Output = Result<(), CoreError>>,
 {
-    future.await.map(|_| json!({ "ok": true }))
+    future.await.map(|_| json!(OkResponse::OK))
 }
 
 macro_rules! parse_request_or_err {
@@ -66,7 +41,7 @@ pub(super) async fn try_handle(
     method: &str,
     params: &Value,
     client_version: &str,
-) -> Option<Result<Value, String>> {
+) -> Option<Result<Value, CoreError>> {
     match method {
         "list_workspaces" => Some(serialize_value(state.list_workspaces().await)),
         "is_workspace_path_dir" => {
@@ -201,10 +176,7 @@ pub(super) async fn try_handle(
             )
         }
         "file_read" => {
-            let request = match parse_file_read_request(params) {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
+            let request = parse_request_or_err!(params, workspace_rpc::FileReadRequest);
             Some(
                 serialize_result(state.file_read(
                     request.scope,
@@ -215,10 +187,7 @@ pub(super) async fn try_handle(
             )
         }
         "file_write" => {
-            let request = match parse_file_write_request(params) {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
+            let request = parse_request_or_err!(params, workspace_rpc::FileWriteRequest);
             Some(
                 serialize_ok(state.file_write(
                     request.scope,
@@ -230,23 +199,13 @@ pub(super) async fn try_handle(
             )
         }
         "read_image_as_data_url" => {
-            let path = match parse_string(params, "path") {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err)),
-            };
-            Some(serialize_result(state.read_image_as_data_url(path)).await)
+            let request = parse_request_or_err!(params, workspace_rpc::ReadImageAsDataUrlRequest);
+            Some(serialize_result(state.read_image_as_data_url(request.path)).await)
         }
         "get_app_settings" => Some(serialize_value(state.get_app_settings().await)),
         "update_app_settings" => {
-            let settings_value = match params {
-                Value::Object(map) => map.get("settings").cloned().unwrap_or(Value::Null),
-                _ => Value::Null,
-            };
-            let settings: AppSettings = match serde_json::from_value(settings_value) {
-                Ok(value) => value,
-                Err(err) => return Some(Err(err.to_string())),
-            };
-            Some(serialize_result(state.update_app_settings(settings)).await)
+            let request = parse_request_or_err!(params, workspace_rpc::UpdateAppSettingsRequest);
+            Some(serialize_result(state.update_app_settings(request.settings)).await)
         }
         "apply_worktree_changes" => {
             let request = parse_request_or_err!(params, workspace_rpc::WorkspaceIdRequest);
@@ -269,9 +228,11 @@ pub(super) async fn try_handle(
             Some(serialize_result(state.get_open_app_icon(request.app_name)).await)
         }
         "local_usage_snapshot" => {
-            let days = parse_optional_u32(params, "days");
-            let workspace_path = parse_optional_string(params, "workspacePath");
-            Some(serialize_result(state.local_usage_snapshot(days, workspace_path)).await)
+            let request = parse_request_or_err!(params, workspace_rpc::LocalUsageSnapshotRequest);
+            Some(
+                serialize_result(state.local_usage_snapshot(request.days, request.workspace_path))
+                    .await,
+            )
         }
         _ => None,
     }
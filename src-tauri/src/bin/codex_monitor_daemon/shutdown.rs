@@ -0,0 +1,422 @@
+//! Graceful `daemon_shutdown` and the handoff to the next process on restart.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::sync::watch;
+
+use super::DaemonState;
+use crate::backend::app_server::WorkspaceSession;
+use crate::backend::events::{AppServerEvent, EventSink};
+use crate::shared::codex_rpc;
+use crate::shared::core_error::CoreError;
+use crate::shared::process_core::terminate_child_process_tree;
+use crate::shared::terminal_core;
+
+/// How long `daemon_shutdown` waits for running turns unless told otherwise.
+pub(super) const DEFAULT_SHUTDOWN_DEADLINE: Duration = Duration::from_secs(30);
+const MAX_SHUTDOWN_DEADLINE: Duration = Duration::from_secs(10 * 60);
+const RUNNING_TURN_POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// Time each app-server gets to exit on SIGTERM before it is killed.
+const CHILD_EXIT_GRACE: Duration = Duration::from_secs(3);
+const RESTART_STATE_FILE: &str = "restart_state.json";
+/// A restart that takes longer than this is treated as a plain stop.
+const RESTART_STATE_MAX_AGE_MS: i64 = 10 * 60 * 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct ShutdownRequest {
+    pub(super) deadline: Duration,
+    /// Save live workspaces and threads so the next process resumes them.
+    pub(super) restart: bool,
+}
+
+/// `daemon_shutdown` params.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ShutdownParams {
+    deadline_secs: Option<u64>,
+    restart: Option<bool>,
+}
+
+/// `daemon_shutdown` result.
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct ShutdownResponse {
+    pub(super) ok: bool,
+    pub(super) deadline_secs: u64,
+    pub(super) restart: bool,
+    /// Workspaces with a turn the daemon is waiting for.
+    pub(super) running_turns: Vec<String>,
+}
+
+impl ShutdownRequest {
+    pub(super) fn from_params(params: &Value) -> Result<Self, CoreError> {
+        let params: ShutdownParams = codex_rpc::from_params(params)?;
+        Ok(Self {
+            deadline: params
+                .deadline_secs
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_SHUTDOWN_DEADLINE)
+                .min(MAX_SHUTDOWN_DEADLINE),
+            restart: params.restart.unwrap_or(false),
+        })
+    }
+}
+
+/// Set once by `daemon_shutdown`; `main` waits on it and drains the daemon.
+pub(super) struct ShutdownSignal {
+    requested: watch::Sender<Option<ShutdownRequest>>,
+}
+
+impl Default for ShutdownSignal {
+    fn default() -> Self {
+        Self {
+            requested: watch::Sender::new(None),
+        }
+    }
+}
+
+impl ShutdownSignal {
+    /// Returns false if a shutdown was already under way; the first request wins.
+    pub(super) fn request(&self, request: ShutdownRequest) -> bool {
+        self.requested.send_if_modified(|current| {
+            if current.is_some() {
+                return false;
+            }
+            *current = Some(request);
+            true
+        })
+    }
+
+    pub(super) fn is_draining(&self) -> bool {
+        self.requested.borrow().is_some()
+    }
+
+    pub(super) async fn requested(&self) -> ShutdownRequest {
+        let mut rx = self.requested.subscribe();
+        loop {
+            if let Some(request) = *rx.borrow_and_update() {
+                return request;
+            }
+            // The sender lives in `self`, so this only returns on a change.
+            let _ = rx.changed().await;
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RestartThread {
+    workspace_id: String,
+    thread_id: String,
+}
+
+/// What a restarting daemon hands to its successor in the same data dir.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RestartState {
+    saved_at_ms: i64,
+    workspace_ids: Vec<String>,
+    threads: Vec<RestartThread>,
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+fn restart_state_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(RESTART_STATE_FILE)
+}
+
+fn write_restart_state(data_dir: &Path, restart_state: &RestartState) -> Result<(), String> {
+    let rendered =
+        serde_json::to_string_pretty(restart_state).map_err(|err| format!("serialize: {err}"))?;
+    std::fs::write(restart_state_path(data_dir), rendered).map_err(|err| format!("write: {err}"))
+}
+
+/// Reads and removes the state left by a restart, ignoring it once stale.
+fn take_restart_state(data_dir: &Path, now_ms: i64) -> Option<RestartState> {
+    let path = restart_state_path(data_dir);
+    let raw = std::fs::read_to_string(&path).ok()?;
+    let _ = std::fs::remove_file(&path);
+    let restart_state: RestartState = match serde_json::from_str(&raw) {
+        Ok(restart_state) => restart_state,
+        Err(err) => {
+            tracing::warn!("ignoring unreadable {}: {err}", path.display());
+            return None;
+        }
+    };
+    if now_ms.saturating_sub(restart_state.saved_at_ms) > RESTART_STATE_MAX_AGE_MS {
+        tracing::info!("ignoring stale restart state from {}", path.display());
+        return None;
+    }
+    Some(restart_state)
+}
+
+async fn live_sessions(state: &DaemonState) -> Vec<(String, Arc<WorkspaceSession>)> {
+    let mut sessions: Vec<(String, Arc<WorkspaceSession>)> = state
+        .sessions
+        .lock()
+        .await
+        .iter()
+        .map(|(workspace_id, session)| (workspace_id.clone(), Arc::clone(session)))
+        .collect();
+    sessions.sort_by(|(left, _), (right, _)| left.cmp(right));
+    sessions
+}
+
+/// Workspaces that still have a turn running.
+pub(super) async fn running_turn_workspaces(state: &DaemonState) -> Vec<String> {
+    let mut running = Vec::new();
+    for (workspace_id, session) in live_sessions(state).await {
+        if session.supervision.has_running_turn(&workspace_id).await {
+            running.push(workspace_id);
+        }
+    }
+    running
+}
+
+fn emit_shutting_down(state: &DaemonState, workspace_ids: &[String], request: ShutdownRequest) {
+    for workspace_id in workspace_ids {
+        state.event_sink.emit_app_server_event(AppServerEvent {
+            workspace_id: workspace_id.clone(),
+            message: json!({
+                "method": "daemon/shuttingDown",
+                "params": {
+                    "workspaceId": workspace_id,
+                    "deadlineMs": request.deadline.as_millis() as u64,
+                    "restart": request.restart,
+                }
+            }),
+        });
+    }
+}
+
+async fn capture_restart_state(state: &DaemonState) -> RestartState {
+    let sessions = live_sessions(state).await;
+    let mut threads = Vec::new();
+    let mut seen: Vec<&Arc<WorkspaceSession>> = Vec::new();
+    for (_, session) in &sessions {
+        if seen.iter().any(|existing| Arc::ptr_eq(existing, session)) {
+            continue;
+        }
+        seen.push(session);
+        for (thread_id, workspace_id) in session.supervision.loaded_threads_snapshot().await {
+            threads.push(RestartThread {
+                workspace_id,
+                thread_id,
+            });
+        }
+    }
+    RestartState {
+        saved_at_ms: now_ms(),
+        workspace_ids: sessions
+            .into_iter()
+            .map(|(workspace_id, _)| workspace_id)
+            .collect(),
+        threads,
+    }
+}
+
+/// Everything `daemon_shutdown` promises once listeners are closed: tell
+/// clients, let running turns finish until the deadline, hand live threads to
+/// the next process on restart, then stop app-servers and terminals and flush
+/// push state.
+pub(super) async fn drain(state: &DaemonState, request: ShutdownRequest) {
+    let mut workspace_ids: Vec<String> = state.workspaces.lock().await.keys().cloned().collect();
+    workspace_ids.sort();
+    emit_shutting_down(state, &workspace_ids, request);
+    tracing::info!(
+        "shutting down (deadline {}s, restart: {})",
+        request.deadline.as_secs(),
+        request.restart
+    );
+
+    let started = Instant::now();
+    loop {
+        let running = running_turn_workspaces(state).await;
+        if running.is_empty() {
+            break;
+        }
+        if started.elapsed() >= request.deadline {
+            tracing::warn!(
+                "shutdown deadline passed with turns still running in {}",
+                running.join(", ")
+            );
+            break;
+        }
+        tokio::time::sleep(RUNNING_TURN_POLL_INTERVAL).await;
+    }
+
+    if request.restart {
+        let restart_state = capture_restart_state(state).await;
+        match write_restart_state(&state.data_dir, &restart_state) {
+            Ok(()) => tracing::info!(
+                "saved {} workspaces and {} threads for restart",
+                restart_state.workspace_ids.len(),
+                restart_state.threads.len()
+            ),
+            Err(err) => tracing::error!("failed to save restart state: {err}"),
+        }
+    }
+
+    // Out of the map first, so the supervisor does not restart them.
+    let sessions: Vec<Arc<WorkspaceSession>> = state
+        .sessions
+        .lock()
+        .await
+        .drain()
+        .map(|(_, session)| session)
+        .collect();
+    let mut stopped: Vec<Arc<WorkspaceSession>> = Vec::new();
+    for session in sessions {
+        if stopped
+            .iter()
+            .any(|existing| Arc::ptr_eq(existing, &session))
+        {
+            continue;
+        }
+        {
+            let mut child = session.child.lock().await;
+            terminate_child_process_tree(&mut child, CHILD_EXIT_GRACE).await;
+        }
+        stopped.push(session);
+    }
+    terminal_core::terminal_close_all_core(&state.terminal_sessions).await;
+
+    if let Err(err) = state.push_broker.flush(request.deadline).await {
+        tracing::error!("failed to persist push state: {err}");
+    }
+    tracing::info!("stopped {} app-server processes", stopped.len());
+}
+
+/// Reconnects the workspaces a restarting predecessor left behind and resumes
+/// their threads.
+pub(super) async fn resume_after_restart(state: &DaemonState) {
+    let Some(restart_state) = take_restart_state(&state.data_dir, now_ms()) else {
+        return;
+    };
+    let client_version = format!("daemon-{}", env!("CARGO_PKG_VERSION"));
+    for workspace_id in &restart_state.workspace_ids {
+        if let Err(err) = state
+            .connect_workspace(workspace_id.clone(), client_version.clone())
+            .await
+        {
+            tracing::warn!(workspace_id = %workspace_id, "failed to reconnect after restart: {err}");
+        }
+    }
+    let mut resumed = 0;
+    for thread in restart_state.threads {
+        match state
+            .resume_thread(thread.workspace_id.clone(), thread.thread_id.clone())
+            .await
+        {
+            Ok(_) => resumed += 1,
+            Err(err) => tracing::warn!(
+                workspace_id = %thread.workspace_id,
+                "failed to resume thread {} after restart: {err}",
+                thread.thread_id
+            ),
+        }
+    }
+    tracing::info!(
+        "resumed {resumed} threads in {} workspaces after restart",
+        restart_state.workspace_ids.len()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(prefix: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "codex-monitor-{prefix}-{}-{}",
+            std::process::id(),
+            uuid::Uuid::new_v4()
+        ));
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        dir
+    }
+
+    #[test]
+    fn shutdown_request_reads_params_and_caps_the_deadline() {
+        assert_eq!(
+            ShutdownRequest::from_params(&Value::Null).expect("defaults"),
+            ShutdownRequest {
+                deadline: DEFAULT_SHUTDOWN_DEADLINE,
+                restart: false,
+            }
+        );
+        assert_eq!(
+            ShutdownRequest::from_params(&json!({ "deadlineSecs": 5, "restart": true }))
+                .expect("params"),
+            ShutdownRequest {
+                deadline: Duration::from_secs(5),
+                restart: true,
+            }
+        );
+        assert_eq!(
+            ShutdownRequest::from_params(&json!({ "deadlineSecs": 86_400 }))
+                .expect("capped")
+                .deadline,
+            MAX_SHUTDOWN_DEADLINE
+        );
+        assert!(ShutdownRequest::from_params(&json!({ "deadlineSecs": -1 })).is_err());
+        assert!(ShutdownRequest::from_params(&json!({ "restart": "yes" })).is_err());
+    }
+
+    #[test]
+    fn shutdown_signal_keeps_the_first_request() {
+        let signal = ShutdownSignal::default();
+        assert!(!signal.is_draining());
+        let first = ShutdownRequest {
+            deadline: Duration::from_secs(1),
+            restart: true,
+        };
+        assert!(signal.request(first));
+        assert!(!signal.request(ShutdownRequest {
+            deadline: Duration::ZERO,
+            restart: false,
+        }));
+        assert!(signal.is_draining());
+        let requested = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .expect("runtime")
+            .block_on(signal.requested());
+        assert_eq!(requested, first);
+    }
+
+    #[test]
+    fn restart_state_is_taken_once_and_ignored_when_stale() {
+        let dir = temp_dir("restart-state");
+        let restart_state = RestartState {
+            saved_at_ms: 1_000,
+            workspace_ids: vec!["ws-1".to_string()],
+            threads: vec![RestartThread {
+                workspace_id: "ws-1".to_string(),
+                thread_id: "thread-1".to_string(),
+            }],
+        };
+
+        write_restart_state(&dir, &restart_state).expect("write");
+        assert_eq!(take_restart_state(&dir, 2_000), Some(restart_state.clone()));
+        assert_eq!(take_restart_state(&dir, 2_000), None);
+
+        write_restart_state(&dir, &restart_state).expect("write");
+        assert_eq!(
+            take_restart_state(&dir, 1_000 + RESTART_STATE_MAX_AGE_MS + 1),
+            None
+        );
+        assert!(!restart_state_path(&dir).exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,112 @@
+use std::fs;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::ServerConfig;
+use tokio_rustls::TlsAcceptor;
+
+use crate::shared::tls_core::{certificate_fingerprint, crypto_provider};
+use crate::storage::write_private_file;
+
+const TLS_DIR_NAME: &str = "tls";
+const DEFAULT_CERT_FILE_NAME: &str = "daemon-cert.pem";
+const DEFAULT_KEY_FILE_NAME: &str = "daemon-key.pem";
+
+#[derive(Clone, Debug)]
+pub(super) struct DaemonTlsConfig {
+    pub(super) cert_path: PathBuf,
+    pub(super) key_path: PathBuf,
+}
+
+impl DaemonTlsConfig {
+    pub(super) fn in_data_dir(data_dir: &Path) -> Self {
+        let tls_dir = data_dir.join(TLS_DIR_NAME);
+        Self {
+            cert_path: tls_dir.join(DEFAULT_CERT_FILE_NAME),
+            key_path: tls_dir.join(DEFAULT_KEY_FILE_NAME),
+        }
+    }
+}
+
+pub(super) struct LoadedTls {
+    pub(super) acceptor: TlsAcceptor,
+    pub(super) fingerprint: String,
+    pub(super) generated: bool,
+}
+
+/// Loads the configured certificate/key pair, generating a self-signed pair
+/// first when neither file exists yet.
+pub(super) fn load_tls(config: &DaemonTlsConfig, listen: SocketAddr) -> Result<LoadedTls, String> {
+    let cert_exists = config.cert_path.exists();
+    let key_exists = config.key_path.exists();
+    let generated = match (cert_exists, key_exists) {
+        (true, true) => false,
+        (false, false) => {
+            generate_self_signed(config, listen)?;
+            true
+        }
+        (true, false) => {
+            return Err(format!(
+                "TLS key not found at {} (certificate exists at {})",
+                config.key_path.display(),
+                config.cert_path.display()
+            ))
+        }
+        (false, true) => {
+            return Err(format!(
+                "TLS certificate not found at {} (key exists at {})",
+                config.cert_path.display(),
+                config.key_path.display()
+            ))
+        }
+    };
+
+    let certs = CertificateDer::pem_file_iter(&config.cert_path)
+        .map_err(|err| format!("Failed to read {}: {err}", config.cert_path.display()))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| format!("Failed to parse {}: {err}", config.cert_path.display()))?;
+    let leaf = certs
+        .first()
+        .ok_or_else(|| format!("No certificates found in {}", config.cert_path.display()))?;
+    let fingerprint = certificate_fingerprint(leaf.as_ref());
+    let key = PrivateKeyDer::from_pem_file(&config.key_path)
+        .map_err(|err| format!("Failed to read {}: {err}", config.key_path.display()))?;
+
+    let server_config = ServerConfig::builder_with_provider(crypto_provider())
+        .with_safe_default_protocol_versions()
+        .map_err(|err| err.to_string())?
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|err| format!("Invalid TLS certificate/key pair: {err}"))?;
+
+    Ok(LoadedTls {
+        acceptor: TlsAcceptor::from(Arc::new(server_config)),
+        fingerprint,
+        generated,
+    })
+}
+
+fn generate_self_signed(config: &DaemonTlsConfig, listen: SocketAddr) -> Result<(), String> {
+    let mut subject_alt_names = vec!["localhost".to_string(), "127.0.0.1".to_string()];
+    let listen_ip = listen.ip();
+    if !listen_ip.is_unspecified() && !listen_ip.is_loopback() {
+        subject_alt_names.push(listen_ip.to_string());
+    }
+    let certified = rcgen::generate_simple_self_signed(subject_alt_names)
+        .map_err(|err| format!("Failed to generate self-signed certificate: {err}"))?;
+
+    for path in [&config.cert_path, &config.key_path] {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+        }
+    }
+    write_private_file(
+        &config.key_path,
+        certified.key_pair.serialize_pem().as_bytes(),
+    )?;
+    fs::write(&config.cert_path, certified.cert.pem())
+        .map_err(|err| format!("Failed to write {}: {err}", config.cert_path.display()))
+}
@@ -1,42 +1,293 @@
+use std::sync::OnceLock;
+
+use futures_util::{Stream, StreamExt};
+use tokio_rustls::TlsAcceptor;
+
+use super::event_subscription::{EventSubscription, SubscribeEventsFilter};
+use super::rpc::scope::{self, ClientScope};
 use super::rpc::{
-    build_error_response, build_result_response, forward_events, parse_auth_token,
+    build_error_response, build_result_response, forward_events, send_scoped_event,
     spawn_rpc_response_task,
 };
+use super::websocket::handle_websocket_client;
 use super::*;
+use crate::shared::codex_rpc;
+use crate::shared::core_error::{CoreError, ErrorCode};
+use crate::shared::wire_compression_core::{
+    read_message, write_message, FrameDecoder, FrameEncoder, WireCompression,
+};
+
+const HANDSHAKE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+/// How long a closing connection waits for already-queued messages to go out.
+pub(super) const WRITE_DRAIN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// An item queued for a connection's writer. `StartCompression` follows the
+/// `protocol_upgrade` response, so every message after it is compressed.
+pub(super) enum OutboundMessage {
+    Json(String),
+    StartCompression(WireCompression),
+}
+
+impl From<String> for OutboundMessage {
+    fn from(message: String) -> Self {
+        Self::Json(message)
+    }
+}
+
+/// Set once a client asks for compression; the reader checks it before taking
+/// the next message off the wire.
+pub(super) type ReadCompression = Arc<OnceLock<WireCompression>>;
+
+/// `auth` params: `{ token?, resumeFromSeq?, streamId?, subscription? }`, or
+/// the bare token.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct AuthRequest {
+    pub(super) token: Option<String>,
+    pub(super) resume_from_seq: Option<u64>,
+    pub(super) stream_id: Option<String>,
+    /// Same filter as `subscribe_events`, applied before any replayed event
+    /// is sent so a reconnect does not pull in a backlog it would drop.
+    #[schemars(with = "Option<SubscribeEventsFilter>")]
+    pub(super) subscription: Option<Value>,
+}
+
+impl AuthRequest {
+    fn from_params(params: &Value) -> Self {
+        match params {
+            Value::String(token) => Self {
+                token: Some(token.clone()),
+                ..Self::default()
+            },
+            _ => codex_rpc::from_params(params).unwrap_or_default(),
+        }
+    }
+
+    /// Replaces the connection's filter when one was sent with `auth`.
+    fn apply_subscription(
+        &self,
+        subscription: &std::sync::RwLock<EventSubscription>,
+    ) -> Result<(), String> {
+        if let Some(params) = &self.subscription {
+            set_subscription(subscription, EventSubscription::from_params(params)?);
+        }
+        Ok(())
+    }
+
+    /// A resume request only applies to the stream it was recorded on; after
+    /// a daemon restart the client's sequence numbers mean nothing.
+    fn resume_from(&self, stream_id: &str) -> Option<u64> {
+        match self.stream_id.as_deref() {
+            Some(requested) if requested != stream_id => None,
+            _ => self.resume_from_seq,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct AuthResponse {
+    pub(super) ok: bool,
+    pub(super) role: &'static str,
+    pub(super) stream_id: String,
+    /// Sequence of the newest event covered by this response; later events
+    /// arrive live.
+    pub(super) last_seq: u64,
+    pub(super) resumed: bool,
+    /// Some of the requested events had already left the replay buffer.
+    pub(super) replay_truncated: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(super) struct ProtocolUpgradeRequest {
+    pub(super) compression: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub(super) struct ProtocolUpgradeResponse {
+    pub(super) compression: &'static str,
+}
+
+/// Wire framing spoken on a listener. Both carry the same JSON-RPC envelope.
+#[derive(Clone, Debug)]
+pub(super) enum ConnectionProtocol {
+    Lines,
+    WebSocket { path: String },
+}
 
-pub(super) async fn handle_client(
-    socket: TcpStream,
+pub(super) async fn run_listener(
+    listener: TcpListener,
+    protocol: ConnectionProtocol,
+    tls_acceptor: Option<TlsAcceptor>,
     config: Arc<DaemonConfig>,
     state: Arc<DaemonState>,
-    events: broadcast::Sender<DaemonEvent>,
+    events: broadcast::Sender<SequencedEvent>,
 ) {
-    let (reader, mut writer) = socket.into_split();
-    let mut lines = BufReader::new(reader).lines();
+    loop {
+        let socket = match listener.accept().await {
+            Ok((socket, _addr)) => socket,
+            Err(_) => continue,
+        };
+        let protocol = protocol.clone();
+        let tls_acceptor = tls_acceptor.clone();
+        let config = Arc::clone(&config);
+        let state = Arc::clone(&state);
+        let events = events.clone();
+        tokio::spawn(async move {
+            let Some(acceptor) = tls_acceptor else {
+                serve_socket(socket, protocol, config, state, events).await;
+                return;
+            };
+            match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(socket)).await {
+                Ok(Ok(stream)) => serve_socket(stream, protocol, config, state, events).await,
+                Ok(Err(err)) => tracing::warn!("TLS handshake failed: {err}"),
+                Err(_) => tracing::warn!("TLS handshake timed out"),
+            }
+        });
+    }
+}
 
-    let (out_tx, mut out_rx) = mpsc::unbounded_channel::<String>();
-    let write_task = tokio::spawn(async move {
+async fn serve_socket<S>(
+    socket: S,
+    protocol: ConnectionProtocol,
+    config: Arc<DaemonConfig>,
+    state: Arc<DaemonState>,
+    events: broadcast::Sender<SequencedEvent>,
+) where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    match protocol {
+        ConnectionProtocol::Lines => handle_client(socket, config, state, events).await,
+        ConnectionProtocol::WebSocket { path } => {
+            handle_websocket_client(socket, &path, HANDSHAKE_TIMEOUT, config, state, events).await
+        }
+    }
+}
+
+pub(super) async fn handle_client<S>(
+    socket: S,
+    config: Arc<DaemonConfig>,
+    state: Arc<DaemonState>,
+    events: broadcast::Sender<SequencedEvent>,
+) where
+    S: AsyncRead + AsyncWrite + Send + 'static,
+{
+    let (reader, mut writer) = tokio::io::split(socket);
+
+    let (out_tx, mut out_rx) = mpsc::unbounded_channel::<OutboundMessage>();
+    let mut write_task = tokio::spawn(async move {
+        let mut encoder: Option<FrameEncoder> = None;
         while let Some(message) = out_rx.recv().await {
-            if writer.write_all(message.as_bytes()).await.is_err() {
-                break;
-            }
-            if writer.write_all(b"\n").await.is_err() {
+            let message = match message {
+                OutboundMessage::Json(message) => message,
+                OutboundMessage::StartCompression(compression) => {
+                    match FrameEncoder::new(compression) {
+                        Ok(created) => encoder = Some(created),
+                        Err(err) => {
+                            tracing::warn!("{err}");
+                            break;
+                        }
+                    }
+                    continue;
+                }
+            };
+            if write_message(&mut writer, encoder.as_mut(), &message)
+                .await
+                .is_err()
+            {
                 break;
             }
         }
     });
 
-    let mut authenticated = config.token.is_none();
+    let read_compression = ReadCompression::default();
+    let incoming = line_or_frame_stream(BufReader::new(reader), Arc::clone(&read_compression));
+    serve_messages(incoming, out_tx, read_compression, config, state, events).await;
+    let _ = tokio::time::timeout(WRITE_DRAIN_TIMEOUT, &mut write_task).await;
+    write_task.abort();
+}
+
+/// Reads newline-delimited messages until `read_compression` is set, then
+/// length-prefixed compressed frames.
+fn line_or_frame_stream<R>(
+    reader: BufReader<R>,
+    read_compression: ReadCompression,
+) -> impl Stream<Item = String>
+where
+    R: AsyncRead + Unpin,
+{
+    futures_util::stream::unfold(
+        (reader, None::<FrameDecoder>),
+        move |(mut reader, mut decoder)| {
+            let read_compression = Arc::clone(&read_compression);
+            async move {
+                if decoder.is_none() {
+                    if let Some(compression) = read_compression.get() {
+                        decoder = Some(FrameDecoder::new(*compression).ok()?);
+                    }
+                }
+                match read_message(&mut reader, decoder.as_mut()).await {
+                    Ok(Some(message)) => Some((message, (reader, decoder))),
+                    Ok(None) => None,
+                    Err(err) => {
+                        if decoder.is_some() {
+                            tracing::warn!("{err}");
+                        }
+                        None
+                    }
+                }
+            }
+        },
+    )
+}
+
+/// Runs the auth gate and RPC dispatch for one connection, independent of how
+/// messages are framed on the wire. Returns once `incoming` ends.
+pub(super) async fn serve_messages<I>(
+    incoming: I,
+    out_tx: mpsc::UnboundedSender<OutboundMessage>,
+    read_compression: ReadCompression,
+    config: Arc<DaemonConfig>,
+    state: Arc<DaemonState>,
+    events: broadcast::Sender<SequencedEvent>,
+) where
+    I: Stream<Item = String>,
+{
+    let mut client_scope: Option<Arc<std::sync::RwLock<ClientScope>>> = None;
     let mut events_task: Option<tokio::task::JoinHandle<()>> = None;
+    // Set when the daemon needs no token: the head of the stream when live
+    // events started flowing, so a later `auth` can still resume.
+    let mut implicit_auth_seq: Option<u64> = None;
     let request_limiter = Arc::new(Semaphore::new(MAX_IN_FLIGHT_RPC_PER_CONNECTION));
-    let client_version = format!("daemon-{}", env!("CARGO_PKG_VERSION"));
+    let _connected = state.metrics.client_connected();
+
+    let replay = Arc::clone(&state.event_sink.replay);
+    let subscription = Arc::new(std::sync::RwLock::new(EventSubscription::default()));
+    let revoked = Arc::new(tokio::sync::Notify::new());
+    // Ends the connection once the event stream sees the token revoked.
+    let revoked_signal = Arc::clone(&revoked);
+    let incoming = incoming.take_until(async move { revoked_signal.notified().await });
+    let mut incoming = std::pin::pin!(incoming);
 
-    if authenticated {
+    if config.token.is_none() && state.auth_tokens.is_empty() {
+        let scope = Arc::new(std::sync::RwLock::new(ClientScope::unrestricted()));
         let rx = events.subscribe();
         let out_tx_events = out_tx.clone();
-        events_task = Some(tokio::spawn(forward_events(rx, out_tx_events)));
+        let live = replay.live_snapshot();
+        implicit_auth_seq = Some(live.last_seq);
+        events_task = Some(tokio::spawn(forward_events(
+            rx,
+            out_tx_events,
+            Arc::clone(&scope),
+            Arc::clone(&subscription),
+            Arc::clone(&state),
+            live,
+            Arc::clone(&revoked),
+        )));
+        client_scope = Some(scope);
     }
 
-    while let Ok(Some(line)) = lines.next_line().await {
+    while let Some(line) = incoming.next().await {
         let line = line.trim();
         if line.is_empty() {
             continue;
@@ -55,42 +306,184 @@ pub(super) async fn handle_client(
             .to_string();
         let params = message.get("params").cloned().unwrap_or(Value::Null);
 
-        if !authenticated {
+        let Some(scope) = client_scope.as_ref() else {
             if method != "auth" {
-                if let Some(response) = build_error_response(id, "unauthorized") {
-                    let _ = out_tx.send(response);
+                if let Some(response) = build_error_response(
+                    id,
+                    &CoreError::new(ErrorCode::Unauthorized, "unauthorized"),
+                ) {
+                    let _ = out_tx.send(response.into());
                 }
                 continue;
             }
 
-            let expected = config.token.clone().unwrap_or_default();
-            let provided = parse_auth_token(&params).unwrap_or_default();
-            if expected != provided {
-                if let Some(response) = build_error_response(id, "invalid token") {
-                    let _ = out_tx.send(response);
+            let auth = AuthRequest::from_params(&params);
+            let provided = auth.token.as_deref().unwrap_or_default();
+            let Some(authenticated) = authenticate_client(&config, &state, provided) else {
+                if let Some(response) = build_error_response(
+                    id,
+                    &CoreError::new(ErrorCode::InvalidToken, "invalid token"),
+                ) {
+                    let _ = out_tx.send(response.into());
+                }
+                continue;
+            };
+            if let Err(message) = auth.apply_subscription(&subscription) {
+                if let Some(response) =
+                    build_error_response(id, &CoreError::invalid_params(message))
+                {
+                    let _ = out_tx.send(response.into());
                 }
                 continue;
             }
 
-            authenticated = true;
-            if let Some(response) = build_result_response(id, json!({ "ok": true })) {
-                let _ = out_tx.send(response);
+            // Subscribe before taking the snapshot so no event falls between them.
+            let rx = events.subscribe();
+            let resume_from = auth.resume_from(replay.stream_id());
+            let backlog = match resume_from {
+                Some(seq) => replay.replay_since(seq),
+                None => replay.live_snapshot(),
+            };
+            let result = AuthResponse {
+                ok: true,
+                role: authenticated.role.as_str(),
+                stream_id: replay.stream_id().to_string(),
+                last_seq: backlog.last_seq,
+                resumed: resume_from.is_some(),
+                replay_truncated: backlog.truncated,
+            };
+            if let Some(response) = build_result_response(id, json!(result)) {
+                let _ = out_tx.send(response.into());
             }
 
-            let rx = events.subscribe();
+            let scope = Arc::new(std::sync::RwLock::new(authenticated));
             let out_tx_events = out_tx.clone();
-            events_task = Some(tokio::spawn(forward_events(rx, out_tx_events)));
+            events_task = Some(tokio::spawn(forward_events(
+                rx,
+                out_tx_events,
+                Arc::clone(&scope),
+                Arc::clone(&subscription),
+                Arc::clone(&state),
+                backlog,
+                Arc::clone(&revoked),
+            )));
+            client_scope = Some(scope);
 
             continue;
+        };
+
+        let Some(current_scope) = scope::refresh_client_scope(&state.auth_tokens, scope) else {
+            if let Some(response) = build_error_response(
+                id,
+                &CoreError::new(ErrorCode::TokenRevoked, "token revoked"),
+            ) {
+                let _ = out_tx.send(response.into());
+            }
+            break;
+        };
+
+        // Without a token the connection is already streaming live events;
+        // `auth` then only fills in what was missed before it connected.
+        if method == "auth" {
+            if let Some(connected_at_seq) = implicit_auth_seq {
+                let auth = AuthRequest::from_params(&params);
+                if let Err(message) = auth.apply_subscription(&subscription) {
+                    if let Some(response) =
+                        build_error_response(id, &CoreError::invalid_params(message))
+                    {
+                        let _ = out_tx.send(response.into());
+                    }
+                    continue;
+                }
+                let resume_from = auth.resume_from(replay.stream_id());
+                let backlog = match resume_from {
+                    Some(seq) => replay.replay_since(seq),
+                    None => replay.live_snapshot(),
+                };
+                for event in backlog
+                    .events
+                    .into_iter()
+                    .filter(|event| event.seq <= connected_at_seq)
+                {
+                    send_scoped_event(&out_tx, scope, &subscription, event);
+                }
+                let result = AuthResponse {
+                    ok: true,
+                    role: current_scope.role.as_str(),
+                    stream_id: replay.stream_id().to_string(),
+                    last_seq: backlog.last_seq,
+                    resumed: resume_from.is_some(),
+                    replay_truncated: backlog.truncated,
+                };
+                if let Some(response) = build_result_response(id, json!(result)) {
+                    let _ = out_tx.send(response.into());
+                }
+                continue;
+            }
+        }
+
+        // The subscription is per connection, so it is answered here rather
+        // than by the shared dispatcher.
+        if method == "subscribe_events" {
+            let response = match EventSubscription::from_params(&params) {
+                Ok(updated) => {
+                    let result = updated.to_value();
+                    set_subscription(&subscription, updated);
+                    build_result_response(id, result)
+                }
+                Err(message) => build_error_response(id, &CoreError::invalid_params(message)),
+            };
+            if let Some(response) = response {
+                let _ = out_tx.send(response.into());
+            }
+            continue;
+        }
+
+        // Compression covers the rest of the connection in both directions:
+        // the client compresses everything after this request, and the
+        // writer compresses everything after this response.
+        if method == "protocol_upgrade" {
+            let requested = codex_rpc::from_params::<ProtocolUpgradeRequest>(&params)
+                .ok()
+                .and_then(|request| WireCompression::parse(&request.compression));
+            let upgrade = match requested {
+                Some(compression) if read_compression.set(compression).is_ok() => Ok(compression),
+                Some(_) => Err("compression is already enabled".to_string()),
+                None => Err(format!(
+                    "unsupported compression; expected one of: {}",
+                    WireCompression::advertised().join(", ")
+                )),
+            };
+            match upgrade {
+                Ok(compression) => {
+                    let result = ProtocolUpgradeResponse {
+                        compression: compression.as_str(),
+                    };
+                    if let Some(response) = build_result_response(id, json!(result)) {
+                        let _ = out_tx.send(response.into());
+                    }
+                    let _ = out_tx.send(OutboundMessage::StartCompression(compression));
+                    continue;
+                }
+                Err(message) => {
+                    // The client already switched its own framing, so the
+                    // stream can no longer be read reliably.
+                    let error = CoreError::new(ErrorCode::InvalidParams, message);
+                    if let Some(response) = build_error_response(id, &error) {
+                        let _ = out_tx.send(response.into());
+                    }
+                    break;
+                }
+            }
         }
 
         spawn_rpc_response_task(
             Arc::clone(&state),
+            current_scope,
             out_tx.clone(),
             id,
             method,
             params,
-            client_version.clone(),
             Arc::clone(&request_limiter),
         );
     }
@@ -99,5 +492,31 @@ pub(super) async fn handle_client(
     if let Some(task) = events_task {
         task.abort();
     }
-    write_task.abort();
+}
+
+fn set_subscription(
+    subscription: &std::sync::RwLock<EventSubscription>,
+    updated: EventSubscription,
+) {
+    *subscription
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = updated;
+}
+
+/// The shared `--token` grants full access; named tokens carry their own scope.
+fn authenticate_client(
+    config: &DaemonConfig,
+    state: &DaemonState,
+    provided: &str,
+) -> Option<ClientScope> {
+    if provided.is_empty() {
+        return None;
+    }
+    if config.token.as_deref() == Some(provided) {
+        return Some(ClientScope::unrestricted());
+    }
+    state
+        .auth_tokens
+        .find_by_secret(provided)
+        .map(|record| ClientScope::from_record(&record))
 }
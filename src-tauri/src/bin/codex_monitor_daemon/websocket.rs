@@ -0,0 +1,138 @@
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::StatusCode;
+use tokio_tungstenite::tungstenite::Message;
+
+use super::transport::{serve_messages, OutboundMessage, ReadCompression, WRITE_DRAIN_TIMEOUT};
+use super::*;
+use crate::shared::wire_compression_core::{FrameDecoder, FrameEncoder};
+
+pub(super) fn normalize_ws_path(value: &str) -> Result<String, String> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        return Err("--ws-path requires a non-empty value".to_string());
+    }
+    if trimmed.contains('?') || trimmed.contains('#') {
+        return Err("--ws-path must not include a query or fragment".to_string());
+    }
+    if trimmed.starts_with('/') {
+        Ok(trimmed.to_string())
+    } else {
+        Ok(format!("/{trimmed}"))
+    }
+}
+
+/// Upgrades an HTTP connection on `path` and serves the daemon protocol with
+/// one JSON-RPC message per text frame, or per binary frame once the client
+/// enables compression.
+pub(super) async fn handle_websocket_client<S>(
+    socket: S,
+    path: &str,
+    handshake_timeout: Duration,
+    config: Arc<DaemonConfig>,
+    state: Arc<DaemonState>,
+    events: broadcast::Sender<SequencedEvent>,
+) where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let expected_path = path.to_string();
+    // The callback signature is fixed by tungstenite, so the large error type is unavoidable.
+    #[allow(clippy::result_large_err)]
+    let check_path = move |request: &Request, response: Response| {
+        if request.uri().path() == expected_path {
+            return Ok(response);
+        }
+        let mut rejection = ErrorResponse::new(Some("not found".to_string()));
+        *rejection.status_mut() = StatusCode::NOT_FOUND;
+        Err(rejection)
+    };
+    let upgrade = tokio_tungstenite::accept_hdr_async(socket, check_path);
+    let stream = match tokio::time::timeout(handshake_timeout, upgrade).await {
+        Ok(Ok(stream)) => stream,
+        Ok(Err(err)) => {
+            tracing::warn!("websocket handshake failed: {err}");
+            return;
+        }
+        Err(_) => {
+            tracing::warn!("websocket handshake timed out");
+            return;
+        }
+    };
+    let (mut sink, source) = stream.split();
+
+    let (out_tx, mut out_rx) = mpsc::unbounded_channel::<OutboundMessage>();
+    let mut write_task = tokio::spawn(async move {
+        let mut encoder: Option<FrameEncoder> = None;
+        while let Some(message) = out_rx.recv().await {
+            let frame = match (message, encoder.as_mut()) {
+                (OutboundMessage::StartCompression(compression), _) => {
+                    match FrameEncoder::new(compression) {
+                        Ok(created) => encoder = Some(created),
+                        Err(err) => {
+                            tracing::warn!("{err}");
+                            break;
+                        }
+                    }
+                    continue;
+                }
+                (OutboundMessage::Json(message), Some(encoder)) => {
+                    match encoder.encode(message.as_bytes()) {
+                        Ok(encoded) => Message::Binary(encoded),
+                        Err(err) => {
+                            tracing::warn!("{err}");
+                            break;
+                        }
+                    }
+                }
+                (OutboundMessage::Json(message), None) => Message::Text(message),
+            };
+            if sink.send(frame).await.is_err() {
+                break;
+            }
+        }
+        let _ = sink.close().await;
+    });
+
+    let read_compression = ReadCompression::default();
+    let reader_compression = Arc::clone(&read_compression);
+    let incoming = futures_util::stream::unfold(
+        (source, None::<FrameDecoder>),
+        move |(mut source, mut decoder)| {
+            let read_compression = Arc::clone(&reader_compression);
+            async move {
+                loop {
+                    let message = source.next().await?.ok()?;
+                    if decoder.is_none() {
+                        if let Some(compression) = read_compression.get() {
+                            decoder = Some(FrameDecoder::new(*compression).ok()?);
+                        }
+                    }
+                    let text = match message {
+                        Message::Text(text) => text,
+                        Message::Binary(bytes) => match decoder.as_mut() {
+                            Some(decoder) => match decoder.decode(&bytes) {
+                                Ok(text) => text,
+                                Err(err) => {
+                                    tracing::warn!("{err}");
+                                    return None;
+                                }
+                            },
+                            None => match String::from_utf8(bytes) {
+                                Ok(text) => text,
+                                Err(_) => continue,
+                            },
+                        },
+                        Message::Close(_) => return None,
+                        _ => continue,
+                    };
+                    return Some((text, (source, decoder)));
+                }
+            }
+        },
+    );
+    serve_messages(incoming, out_tx, read_compression, config, state, events).await;
+    let _ = tokio::time::timeout(WRITE_DRAIN_TIMEOUT, &mut write_task).await;
+    write_task.abort();
+}
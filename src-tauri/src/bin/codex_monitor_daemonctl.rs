@@ -1,5 +1,12 @@
+#[allow(dead_code)]
+#[path = "codex_monitor_daemon/auth_tokens.rs"]
+mod auth_tokens;
+#[path = "../shared/core_error.rs"]
+mod core_error;
 #[path = "../daemon_binary.rs"]
 mod daemon_binary;
+#[path = "../shared/rpc_schema.rs"]
+mod rpc_schema;
 #[allow(dead_code)]
 #[path = "../storage.rs"]
 mod storage;
@@ -7,6 +14,8 @@ mod storage;
 #[path = "../types.rs"]
 mod types;
 
+use auth_tokens::{auth_tokens_path, AuthTokenRecord, TokenRole};
+use core_error::CoreError;
 use daemon_binary::resolve_daemon_binary_path;
 use serde_json::{json, Value};
 use std::env;
@@ -29,6 +38,10 @@ const DEFAULT_LISTEN_ADDR: &str = "0.0.0.0:4732";
 const REMOTE_TOKEN_PLACEHOLDER: &str = "<remote-backend-token>";
 const APP_IDENTIFIER: &str = "com.dimillian.codexmonitor";
 const DAEMON_RPC_TIMEOUT: Duration = Duration::from_millis(700);
+/// Matches the daemon's default wait for running turns on `daemon_shutdown`.
+const DEFAULT_SHUTDOWN_DEADLINE_SECS: u64 = 30;
+/// Time on top of the deadline for the daemon to stop its app-servers and exit.
+const SHUTDOWN_EXIT_GRACE: Duration = Duration::from_secs(10);
 
 #[derive(Debug, Clone)]
 struct CliArgs {
@@ -37,16 +50,32 @@ struct CliArgs {
     token: Option<String>,
     data_dir: Option<PathBuf>,
     daemon_path: Option<PathBuf>,
+    deadline_secs: Option<u64>,
     json: bool,
     insecure_no_auth: bool,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 enum CliCommand {
     Start,
     Stop,
+    Restart,
     Status,
     CommandPreview,
+    Token(TokenCommand),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TokenCommand {
+    Create {
+        name: String,
+        role: TokenRole,
+        workspace_ids: Option<Vec<String>>,
+    },
+    List,
+    Revoke {
+        id_or_name: String,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -88,6 +117,9 @@ fn main() {
 async fn run() -> Result<(), String> {
     let args = parse_args()?;
     let data_dir = resolve_data_dir(args.data_dir);
+    if let CliCommand::Token(command) = &args.command {
+        return run_token_command(command, &data_dir, args.json);
+    }
     let settings = load_settings(&data_dir);
 
     let listen_addr = resolve_listen_addr(args.listen.as_deref(), settings.as_ref())?;
@@ -121,7 +153,7 @@ async fn run() -> Result<(), String> {
             print_status(&status, args.json)?;
         }
         CliCommand::Stop => {
-            let status = daemon_stop(&listen_addr, token.as_deref()).await;
+            let status = daemon_stop(&listen_addr, token.as_deref(), args.deadline_secs).await;
             print_status(&status, args.json)?;
             if !matches!(status.state, TcpDaemonState::Stopped) {
                 return Err(status
@@ -129,6 +161,7 @@ async fn run() -> Result<(), String> {
                     .unwrap_or_else(|| "Daemon is still running after stop attempt.".to_string()));
             }
         }
+        CliCommand::Token(_) => {}
         CliCommand::Start => {
             let daemon_path = resolve_daemon_path(args.daemon_path.as_deref())?;
             let status = daemon_start(
@@ -137,6 +170,20 @@ async fn run() -> Result<(), String> {
                 args.insecure_no_auth,
                 &data_dir,
                 &daemon_path,
+                args.deadline_secs,
+            )
+            .await?;
+            print_status(&status, args.json)?;
+        }
+        CliCommand::Restart => {
+            let daemon_path = resolve_daemon_path(args.daemon_path.as_deref())?;
+            let status = daemon_restart(
+                &listen_addr,
+                token.as_deref(),
+                args.insecure_no_auth,
+                &data_dir,
+                &daemon_path,
+                args.deadline_secs,
             )
             .await?;
             print_status(&status, args.json)?;
@@ -147,7 +194,11 @@ async fn run() -> Result<(), String> {
 }
 
 fn parse_args() -> Result<CliArgs, String> {
-    let mut args = env::args().skip(1);
+    parse_cli_args(env::args().skip(1))
+}
+
+fn parse_cli_args(args: impl IntoIterator<Item = String>) -> Result<CliArgs, String> {
+    let mut args = args.into_iter();
 
     let Some(first) = args.next() else {
         return Err(usage());
@@ -158,20 +209,31 @@ fn parse_args() -> Result<CliArgs, String> {
         std::process::exit(0);
     }
 
-    let command = match first.as_str() {
-        "start" => CliCommand::Start,
-        "stop" => CliCommand::Stop,
-        "status" => CliCommand::Status,
-        "command-preview" => CliCommand::CommandPreview,
-        _ => return Err(format!("Unknown command: {first}\n\n{}", usage())),
+    if !matches!(
+        first.as_str(),
+        "start" | "stop" | "restart" | "status" | "command-preview" | "token"
+    ) {
+        return Err(format!("Unknown command: {first}\n\n{}", usage()));
+    }
+    let token_action = if first == "token" {
+        Some(
+            args.next()
+                .ok_or_else(|| format!("token requires create, list or revoke\n\n{}", usage()))?,
+        )
+    } else {
+        None
     };
 
     let mut listen: Option<String> = None;
     let mut token: Option<String> = None;
     let mut data_dir: Option<PathBuf> = None;
     let mut daemon_path: Option<PathBuf> = None;
+    let mut deadline_secs: Option<u64> = None;
     let mut json = false;
     let mut insecure_no_auth = false;
+    let mut role: Option<TokenRole> = None;
+    let mut workspace_ids: Vec<String> = Vec::new();
+    let mut positional: Vec<String> = Vec::new();
 
     while let Some(arg) = args.next() {
         match arg.as_str() {
@@ -207,6 +269,12 @@ fn parse_args() -> Result<CliArgs, String> {
                 }
                 daemon_path = Some(PathBuf::from(trimmed));
             }
+            "--deadline" => {
+                let value = args.next().ok_or("--deadline requires a value")?;
+                deadline_secs = Some(value.trim().parse::<u64>().map_err(|_| {
+                    format!("--deadline requires a number of seconds, got `{value}`")
+                })?);
+            }
             "--json" => {
                 json = true;
             }
@@ -214,20 +282,70 @@ fn parse_args() -> Result<CliArgs, String> {
                 insecure_no_auth = true;
                 token = None;
             }
+            "--role" if token_action.is_some() => {
+                let value = args.next().ok_or("--role requires a value")?;
+                role = Some(TokenRole::parse(&value)?);
+            }
+            "--workspace" if token_action.is_some() => {
+                let value = args.next().ok_or("--workspace requires a value")?;
+                let trimmed = value.trim();
+                if trimmed.is_empty() {
+                    return Err("--workspace requires a non-empty value".to_string());
+                }
+                workspace_ids.push(trimmed.to_string());
+            }
             "-h" | "--help" => {
                 print!("{}", usage());
                 std::process::exit(0);
             }
+            _ if token_action.is_some() && !arg.starts_with('-') => positional.push(arg),
             _ => return Err(format!("Unknown argument: {arg}\n\n{}", usage())),
         }
     }
 
+    let command = match (first.as_str(), token_action.as_deref()) {
+        ("start", _) => CliCommand::Start,
+        ("stop", _) => CliCommand::Stop,
+        ("restart", _) => CliCommand::Restart,
+        ("status", _) => CliCommand::Status,
+        ("command-preview", _) => CliCommand::CommandPreview,
+        (_, Some("create")) => {
+            let [name] = positional.as_slice() else {
+                return Err("token create requires exactly one <name>".to_string());
+            };
+            CliCommand::Token(TokenCommand::Create {
+                name: name.clone(),
+                role: role.ok_or("token create requires --role <observer|operator|admin>")?,
+                workspace_ids: (!workspace_ids.is_empty()).then_some(workspace_ids),
+            })
+        }
+        (_, Some("list")) => {
+            if !positional.is_empty() || role.is_some() || !workspace_ids.is_empty() {
+                return Err("token list takes no arguments".to_string());
+            }
+            CliCommand::Token(TokenCommand::List)
+        }
+        (_, Some("revoke")) => {
+            let [id_or_name] = positional.as_slice() else {
+                return Err("token revoke requires exactly one <id|name>".to_string());
+            };
+            CliCommand::Token(TokenCommand::Revoke {
+                id_or_name: id_or_name.clone(),
+            })
+        }
+        (_, other) => {
+            let other = other.unwrap_or_default();
+            return Err(format!("Unknown token command: {other}\n\n{}", usage()));
+        }
+    };
+
     Ok(CliArgs {
         command,
         listen,
         token,
         data_dir,
         daemon_path,
+        deadline_secs,
         json,
         insecure_no_auth,
     })
@@ -237,9 +355,9 @@ fn usage() -> String {
     format!(
         "\
 USAGE:\n  codex-monitor-daemonctl <command> [options]\n\n\
-COMMANDS:\n  start              Start daemon (auto-restarts mismatched daemon if safe)\n  stop               Stop daemon\n  status             Show daemon status\n  command-preview    Print equivalent daemon start command\n\n\
-OPTIONS:\n  --listen <addr>        Bind/listen address (default derived from settings, fallback: {DEFAULT_LISTEN_ADDR})\n  --token <token>        Remote backend token override\n  --data-dir <path>      App data dir (contains settings.json/workspaces.json)\n  --daemon-path <path>   Explicit path to codex-monitor-daemon binary\n  --insecure-no-auth     Start/probe daemon without auth token (dev only)\n  --json                 Print JSON output\n  -h, --help             Show this help\n\n\
-NOTES:\n  - Defaults read token/host from <data-dir>/settings.json\n  - If no --data-dir is provided, default app data dir is used for this platform\n"
+COMMANDS:\n  start              Start daemon (auto-restarts mismatched daemon if safe)\n  stop               Stop daemon after running turns finish\n  restart            Stop daemon and start the current binary, resuming live threads\n  status             Show daemon status\n  command-preview    Print equivalent daemon start command\n  token create <name> --role <observer|operator|admin> [--workspace <id>]...\n                     Create a named daemon token and print its secret once\n  token list         List named daemon tokens\n  token revoke <id|name>\n                     Revoke a named daemon token\n\n\
+OPTIONS:\n  --listen <addr>        Bind/listen address (default derived from settings, fallback: {DEFAULT_LISTEN_ADDR})\n  --token <token>        Remote backend token override\n  --data-dir <path>      App data dir (contains settings.json/workspaces.json)\n  --daemon-path <path>   Explicit path to codex-monitor-daemon binary\n  --deadline <secs>      Max wait for running turns on stop/restart (default: {DEFAULT_SHUTDOWN_DEADLINE_SECS})\n  --insecure-no-auth     Start/probe daemon without auth token (dev only)\n  --json                 Print JSON output\n  -h, --help             Show this help\n\n\
+NOTES:\n  - Defaults read token/host from <data-dir>/settings.json\n  - Named tokens live in <data-dir>/auth_tokens.json; the daemon picks up changes without a restart\n  - If no --data-dir is provided, default app data dir is used for this platform\n"
     )
 }
 
@@ -438,19 +556,6 @@ fn trim_non_empty(value: Option<&str>) -> Option<String> {
         .map(str::to_string)
 }
 
-fn parse_daemon_error_message(response: &Value) -> Option<String> {
-    response
-        .get("error")
-        .and_then(|error| error.get("message"))
-        .and_then(Value::as_str)
-        .map(str::to_string)
-}
-
-fn is_auth_error_message(message: &str) -> bool {
-    let lower = message.to_ascii_lowercase();
-    lower.contains("unauthorized") || lower.contains("invalid token")
-}
-
 fn parse_daemon_info(value: &Value) -> Result<DaemonInfo, String> {
     let name = value
         .get("name")
@@ -499,12 +604,7 @@ async fn send_rpc_request(
     method: &str,
     params: Value,
 ) -> Result<(), String> {
-    let mut payload = serde_json::to_string(&json!({
-        "id": id,
-        "method": method,
-        "params": params,
-    }))
-    .map_err(|err| err.to_string())?;
+    let mut payload = rpc_schema::request_line(id, method, params)?;
     payload.push('\n');
     writer
         .write_all(payload.as_bytes())
@@ -545,16 +645,10 @@ async fn send_and_expect_result(
     id: u64,
     method: &str,
     params: Value,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     send_rpc_request(writer, id, method, params).await?;
     let response = read_rpc_response(lines, id).await?;
-    if let Some(message) = parse_daemon_error_message(&response) {
-        return Err(message);
-    }
-    response
-        .get("result")
-        .cloned()
-        .ok_or_else(|| "daemon response missing result".to_string())
+    rpc_schema::response_result(&response)
 }
 
 async fn request_daemon_info(
@@ -586,7 +680,7 @@ async fn probe_daemon(listen_addr: &str, token: Option<&str>) -> DaemonProbe {
             info: request_daemon_info(&mut writer, &mut lines, 2).await.ok(),
         },
         Err(message) => {
-            if !is_auth_error_message(&message) {
+            if !message.code.is_auth_failure() {
                 return DaemonProbe::NotDaemon;
             }
 
@@ -632,7 +726,7 @@ async fn probe_daemon(listen_addr: &str, token: Option<&str>) -> DaemonProbe {
                     }
                 }
                 Err(auth_error) => {
-                    if is_auth_error_message(&auth_error) {
+                    if auth_error.code.is_auth_failure() {
                         DaemonProbe::Running {
                             auth_ok: false,
                             auth_error: Some(format!(
@@ -649,7 +743,24 @@ async fn probe_daemon(listen_addr: &str, token: Option<&str>) -> DaemonProbe {
     }
 }
 
-async fn request_daemon_shutdown(listen_addr: &str, token: Option<&str>) -> Result<(), String> {
+fn shutdown_params(deadline_secs: Option<u64>, restart: bool) -> Value {
+    let mut params = json!({ "restart": restart });
+    if let Some(deadline_secs) = deadline_secs {
+        params["deadlineSecs"] = json!(deadline_secs);
+    }
+    params
+}
+
+fn shutdown_wait(deadline_secs: Option<u64>) -> Duration {
+    Duration::from_secs(deadline_secs.unwrap_or(DEFAULT_SHUTDOWN_DEADLINE_SECS))
+        + SHUTDOWN_EXIT_GRACE
+}
+
+async fn request_daemon_shutdown(
+    listen_addr: &str,
+    token: Option<&str>,
+    params: Value,
+) -> Result<(), String> {
     let Some(connect_addr) = daemon_connect_addr(listen_addr) else {
         return Err("invalid daemon listen address".to_string());
     };
@@ -664,7 +775,7 @@ async fn request_daemon_shutdown(listen_addr: &str, token: Option<&str>) -> Resu
 
     match send_and_expect_result(&mut writer, &mut lines, 1, "ping", json!({})).await {
         Ok(_) => {}
-        Err(message) if is_auth_error_message(&message) => {
+        Err(message) if message.code.is_auth_failure() => {
             let auth_token = token
                 .map(str::trim)
                 .filter(|value| !value.is_empty())
@@ -686,23 +797,35 @@ async fn request_daemon_shutdown(listen_addr: &str, token: Option<&str>) -> Resu
         }
     }
 
-    send_and_expect_result(&mut writer, &mut lines, 3, "daemon_shutdown", json!({}))
+    send_and_expect_result(&mut writer, &mut lines, 3, "daemon_shutdown", params)
         .await
         .map(|_| ())
         .map_err(|err| format!("Daemon shutdown request failed: {err}"))
 }
 
-async fn wait_for_daemon_shutdown(listen_addr: &str, token: Option<&str>) -> bool {
-    for _ in 0..20 {
-        if matches!(
-            probe_daemon(listen_addr, token).await,
-            DaemonProbe::NotReachable
-        ) {
+/// The daemon stops listening as soon as it starts draining, so this also
+/// waits for `pid` to exit when it is known.
+async fn wait_for_daemon_shutdown(
+    listen_addr: &str,
+    token: Option<&str>,
+    pid: Option<u32>,
+    wait: Duration,
+) -> bool {
+    let deadline = Instant::now() + wait;
+    loop {
+        if daemon_process_exited(pid)
+            && matches!(
+                probe_daemon(listen_addr, token).await,
+                DaemonProbe::NotReachable
+            )
+        {
             return true;
         }
+        if Instant::now() >= deadline {
+            return false;
+        }
         sleep(Duration::from_millis(100)).await;
     }
-    false
 }
 
 fn is_managed_daemon(info: &DaemonInfo) -> bool {
@@ -959,6 +1082,17 @@ async fn kill_pid_gracefully(_pid: u32) -> Result<(), String> {
     Err("Stopping external daemon by pid is not supported on this platform.".to_string())
 }
 
+/// True once `pid` is gone, or when there is no pid to check.
+#[cfg(unix)]
+fn daemon_process_exited(pid: Option<u32>) -> bool {
+    pid.is_none_or(|pid| !is_pid_running(pid))
+}
+
+#[cfg(not(unix))]
+fn daemon_process_exited(_pid: Option<u32>) -> bool {
+    true
+}
+
 fn safe_force_stop_pid(pid: u32) -> Option<u32> {
     if pid <= 1 {
         None
@@ -996,6 +1130,7 @@ async fn daemon_start(
     insecure_no_auth: bool,
     data_dir: &Path,
     daemon_binary: &Path,
+    deadline_secs: Option<u64>,
 ) -> Result<TcpDaemonStatus, String> {
     if !insecure_no_auth && token.is_none() {
         return Err("Set a Remote backend token before starting mobile access daemon (or pass --insecure-no-auth for development).".to_string());
@@ -1036,7 +1171,12 @@ async fn daemon_start(
 
             let force_kill_allowed = can_force_stop_daemon(auth_ok, info.as_ref());
             let pid_for_control = pid;
-            if let Err(shutdown_error) = request_daemon_shutdown(listen_addr, token).await {
+            // Restart-required daemons are usually older versions; hand their
+            // threads over to the binary started below.
+            if let Err(shutdown_error) =
+                request_daemon_shutdown(listen_addr, token, shutdown_params(deadline_secs, true))
+                    .await
+            {
                 if !force_kill_allowed {
                     return Err(format!(
                         "{}; automatic restart aborted because daemon ownership could not be verified: {}",
@@ -1061,7 +1201,14 @@ async fn daemon_start(
                 }
             }
 
-            if !wait_for_daemon_shutdown(listen_addr, token).await {
+            if !wait_for_daemon_shutdown(
+                listen_addr,
+                token,
+                expected_pid,
+                shutdown_wait(deadline_secs),
+            )
+            .await
+            {
                 if !force_kill_allowed {
                     return Err(format!(
                         "{}; daemon acknowledged shutdown but is still reachable",
@@ -1125,7 +1272,59 @@ async fn daemon_start(
     })
 }
 
-async fn daemon_stop(listen_addr: &str, token: Option<&str>) -> TcpDaemonStatus {
+/// Drains the running daemon with `restart` set, waits for it to exit and
+/// starts `daemon_binary`, which resumes the threads the old process had live.
+async fn daemon_restart(
+    listen_addr: &str,
+    token: Option<&str>,
+    insecure_no_auth: bool,
+    data_dir: &Path,
+    daemon_binary: &Path,
+    deadline_secs: Option<u64>,
+) -> Result<TcpDaemonStatus, String> {
+    if let DaemonProbe::Running {
+        auth_ok,
+        auth_error,
+        info,
+    } = probe_daemon(listen_addr, token).await
+    {
+        if !auth_ok {
+            return Err(auth_error
+                .unwrap_or_else(|| "Daemon is running but authentication failed.".to_string()));
+        }
+        let expected_pid = info.as_ref().and_then(|value| value.pid);
+        request_daemon_shutdown(listen_addr, token, shutdown_params(deadline_secs, true)).await?;
+        if !wait_for_daemon_shutdown(
+            listen_addr,
+            token,
+            expected_pid,
+            shutdown_wait(deadline_secs),
+        )
+        .await
+        {
+            return Err(
+                "Daemon acknowledged the restart but did not exit in time; run `stop` to force it."
+                    .to_string(),
+            );
+        }
+    }
+
+    daemon_start(
+        listen_addr,
+        token,
+        insecure_no_auth,
+        data_dir,
+        daemon_binary,
+        deadline_secs,
+    )
+    .await
+}
+
+async fn daemon_stop(
+    listen_addr: &str,
+    token: Option<&str>,
+    deadline_secs: Option<u64>,
+) -> TcpDaemonStatus {
     let mut stop_error: Option<String> = None;
 
     if let Some(port) = parse_port_from_remote_host(listen_addr) {
@@ -1133,7 +1332,13 @@ async fn daemon_stop(listen_addr: &str, token: Option<&str>) -> TcpDaemonStatus
             DaemonProbe::Running { auth_ok, info, .. } => {
                 let force_kill_allowed = can_force_stop_daemon(auth_ok, info.as_ref());
                 let expected_pid = info.as_ref().and_then(|value| value.pid);
-                if let Err(shutdown_error) = request_daemon_shutdown(listen_addr, token).await {
+                if let Err(shutdown_error) = request_daemon_shutdown(
+                    listen_addr,
+                    token,
+                    shutdown_params(deadline_secs, false),
+                )
+                .await
+                {
                     let pid = resolve_daemon_pid(listen_addr, expected_pid).await;
                     if let Some(pid) = pid {
                         if force_kill_allowed {
@@ -1150,7 +1355,14 @@ async fn daemon_stop(listen_addr: &str, token: Option<&str>) -> TcpDaemonStatus
                     } else {
                         stop_error = Some(shutdown_error);
                     }
-                } else if !wait_for_daemon_shutdown(listen_addr, token).await {
+                } else if !wait_for_daemon_shutdown(
+                    listen_addr,
+                    token,
+                    expected_pid,
+                    shutdown_wait(deadline_secs),
+                )
+                .await
+                {
                     if force_kill_allowed {
                         let pid = resolve_daemon_pid(listen_addr, expected_pid).await;
                         if let Some(pid) = pid {
@@ -1275,13 +1487,196 @@ fn print_status(status: &TcpDaemonStatus, as_json: bool) -> Result<(), String> {
     Ok(())
 }
 
+fn run_token_command(command: &TokenCommand, data_dir: &Path, as_json: bool) -> Result<(), String> {
+    let path = auth_tokens_path(data_dir);
+    match command {
+        TokenCommand::Create {
+            name,
+            role,
+            workspace_ids,
+        } => {
+            let (record, secret) =
+                auth_tokens::create_auth_token(&path, name, *role, workspace_ids.clone())?;
+            if as_json {
+                let mut value = token_record_json(&record);
+                value["token"] = Value::String(secret);
+                print_json(&value)?;
+            } else {
+                println!("created token {} ({})", record.name, record.id);
+                println!("role: {}", record.role.as_str());
+                println!("workspaces: {}", format_token_workspaces(&record));
+                println!("token: {secret}");
+                println!("Store this token now; it cannot be shown again.");
+            }
+        }
+        TokenCommand::List => {
+            let tokens = auth_tokens::read_auth_tokens(&path)?;
+            if as_json {
+                print_json(&Value::Array(
+                    tokens.iter().map(token_record_json).collect(),
+                ))?;
+            } else if tokens.is_empty() {
+                println!("no tokens in {}", path.display());
+            } else {
+                for record in &tokens {
+                    println!(
+                        "{}  {}  {}  {}",
+                        record.id,
+                        record.name,
+                        record.role.as_str(),
+                        format_token_workspaces(record)
+                    );
+                }
+            }
+        }
+        TokenCommand::Revoke { id_or_name } => {
+            let record = auth_tokens::revoke_auth_token(&path, id_or_name)?;
+            if as_json {
+                print_json(&token_record_json(&record))?;
+            } else {
+                println!("revoked token {} ({})", record.name, record.id);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn token_record_json(record: &AuthTokenRecord) -> Value {
+    json!({
+        "id": record.id,
+        "name": record.name,
+        "role": record.role.as_str(),
+        "workspaceIds": record.workspace_ids,
+        "createdAtMs": record.created_at_ms,
+    })
+}
+
+fn format_token_workspaces(record: &AuthTokenRecord) -> String {
+    match record.workspace_ids.as_deref() {
+        None => "all".to_string(),
+        Some([]) => "none".to_string(),
+        Some(ids) => ids.join(","),
+    }
+}
+
+fn print_json(value: &Value) -> Result<(), String> {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(value).map_err(|err| err.to_string())?
+    );
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
-        daemon_connect_addr, daemon_listen_addr, local_listener_port, parse_netstat_listener_pid,
-        parse_port_from_remote_host, parse_ss_listener_pid, resolve_listen_addr, safe_force_stop_pid,
-        shell_quote,
+        auth_tokens, auth_tokens_path, daemon_connect_addr, daemon_listen_addr,
+        local_listener_port, parse_cli_args, parse_netstat_listener_pid,
+        parse_port_from_remote_host, parse_ss_listener_pid, resolve_listen_addr,
+        safe_force_stop_pid, shell_quote, shutdown_params, CliCommand, TokenCommand, TokenRole,
     };
+    use serde_json::json;
+
+    fn parse(args: &[&str]) -> Result<CliCommand, String> {
+        parse_cli_args(args.iter().map(|arg| arg.to_string())).map(|args| args.command)
+    }
+
+    #[test]
+    fn parses_token_subcommands() {
+        assert_eq!(
+            parse(&[
+                "token",
+                "create",
+                "phone",
+                "--role",
+                "Operator",
+                "--workspace",
+                "ws-1",
+                "--workspace",
+                "ws-2",
+            ]),
+            Ok(CliCommand::Token(TokenCommand::Create {
+                name: "phone".to_string(),
+                role: TokenRole::Operator,
+                workspace_ids: Some(vec!["ws-1".to_string(), "ws-2".to_string()]),
+            }))
+        );
+        assert_eq!(
+            parse(&["token", "list", "--json"]),
+            Ok(CliCommand::Token(TokenCommand::List))
+        );
+        assert_eq!(
+            parse(&["token", "revoke", "phone"]),
+            Ok(CliCommand::Token(TokenCommand::Revoke {
+                id_or_name: "phone".to_string(),
+            }))
+        );
+        assert!(parse(&["token", "create", "phone"]).is_err());
+        assert!(parse(&["token", "create", "phone", "--role", "root"]).is_err());
+        assert!(parse(&["token", "rotate"]).is_err());
+        assert!(parse(&["status", "--role", "admin"]).is_err());
+    }
+
+    #[test]
+    fn parses_restart_with_deadline() {
+        let args = parse_cli_args(
+            ["restart", "--deadline", "5"]
+                .iter()
+                .map(|arg| arg.to_string()),
+        )
+        .expect("restart args");
+        assert_eq!(args.command, CliCommand::Restart);
+        assert_eq!(args.deadline_secs, Some(5));
+        assert!(parse(&["stop", "--deadline", "soon"]).is_err());
+        assert_eq!(
+            shutdown_params(Some(5), true),
+            json!({ "restart": true, "deadlineSecs": 5 })
+        );
+        assert_eq!(shutdown_params(None, false), json!({ "restart": false }));
+    }
+
+    #[test]
+    fn token_store_create_and_revoke_round_trip() {
+        let dir = std::env::temp_dir().join(format!(
+            "codex-monitor-daemonctl-tokens-{}-{}",
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .expect("time")
+                .as_nanos()
+        ));
+        let path = auth_tokens_path(&dir);
+
+        let (record, secret) =
+            auth_tokens::create_auth_token(&path, "phone", TokenRole::Observer, None)
+                .expect("create token");
+        assert!(auth_tokens::create_auth_token(&path, "phone", TokenRole::Admin, None).is_err());
+        let raw = std::fs::read_to_string(&path).expect("tokens file");
+        assert!(
+            !raw.contains(&secret),
+            "secret must not be stored in plaintext"
+        );
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path)
+                .expect("metadata")
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let store = auth_tokens::AuthTokenStore::in_data_dir(&dir);
+        assert_eq!(
+            store.find_by_secret(&secret).map(|found| found.id),
+            Some(record.id.clone())
+        );
+        auth_tokens::revoke_auth_token(&path, "phone").expect("revoke token");
+        assert!(store.find_by_secret(&secret).is_none());
+        assert!(auth_tokens::revoke_auth_token(&path, &record.id).is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 
     #[test]
     fn parses_listen_port_from_host() {
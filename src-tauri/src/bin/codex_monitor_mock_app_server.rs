@@ -0,0 +1,615 @@
+#[allow(dead_code)]
+#[path = "../backend/protocol_recorder.rs"]
+mod protocol_recorder;
+#[path = "codex_monitor_mock_app_server/replay.rs"]
+mod replay;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::env;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+const SCRIPT_ENV: &str = "CODEX_MONITOR_MOCK_SCRIPT";
+const RECORDING_ENV: &str = "CODEX_MONITOR_REPLAY_FILE";
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// What the mock answers with. Read from the JSON file named by `--script`
+/// or `CODEX_MONITOR_MOCK_SCRIPT`; every field is optional.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct MockScript {
+    /// Agent message deltas streamed for every turn.
+    reply_deltas: Vec<String>,
+    /// When set, turns fail with this message instead of replying.
+    turn_error: Option<String>,
+    /// Threads returned by `thread/list` in addition to started ones.
+    threads: Vec<MockThread>,
+    /// When set, turns ask for approval to run this command and reply only
+    /// once the approval request is answered.
+    approval_command: Option<String>,
+}
+
+impl Default for MockScript {
+    fn default() -> Self {
+        Self {
+            reply_deltas: vec![
+                "Hello from ".to_string(),
+                "the mock app-server.".to_string(),
+            ],
+            turn_error: None,
+            threads: Vec::new(),
+            approval_command: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MockThread {
+    id: String,
+    #[serde(default)]
+    preview: String,
+    #[serde(default)]
+    cwd: Option<String>,
+}
+
+impl MockThread {
+    fn to_value(&self) -> Value {
+        json!({
+            "id": self.id,
+            "preview": self.preview,
+            "cwd": self.cwd,
+            "modelProvider": "mock",
+            "createdAt": 0,
+            "updatedAt": 0,
+        })
+    }
+}
+
+/// A turn paused on an approval request the client has not answered yet.
+struct PendingApproval {
+    request_id: Value,
+    thread_id: String,
+    turn_id: String,
+    turn: u64,
+}
+
+/// Plays the server side of the app-server protocol for one session. Every
+/// request yields its response first, followed by any notifications.
+struct MockAppServer {
+    script: MockScript,
+    threads: Vec<MockThread>,
+    next_thread: u64,
+    next_turn: u64,
+    next_request: u64,
+    pending_approvals: Vec<PendingApproval>,
+}
+
+impl MockAppServer {
+    fn new(script: MockScript) -> Self {
+        Self {
+            threads: script.threads.clone(),
+            script,
+            next_thread: 1,
+            next_turn: 1,
+            next_request: 0,
+            pending_approvals: Vec::new(),
+        }
+    }
+
+    fn handle(&mut self, message: &Value) -> Vec<Value> {
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            return self.answer_approval(message);
+        };
+        let Some(id) = message.get("id").cloned() else {
+            // Notifications such as `initialized` need no answer.
+            return Vec::new();
+        };
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        match method {
+            "initialize" => vec![result(id, json!({ "userAgent": "codex-monitor-mock" }))],
+            "thread/start" => self.start_thread(id, &params),
+            "thread/resume" => self.resume_thread(id, &params),
+            "thread/list" => {
+                let data: Vec<Value> = self.threads.iter().map(MockThread::to_value).collect();
+                vec![result(id, json!({ "data": data, "nextCursor": null }))]
+            }
+            "turn/start" => self.start_turn(id, &params),
+            _ => vec![error(
+                id,
+                METHOD_NOT_FOUND,
+                &format!("mock app-server does not implement `{method}`"),
+            )],
+        }
+    }
+
+    fn start_thread(&mut self, id: Value, params: &Value) -> Vec<Value> {
+        let thread = MockThread {
+            id: format!("mock-thread-{}", self.next_thread),
+            preview: String::new(),
+            cwd: params
+                .get("cwd")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+        };
+        self.next_thread += 1;
+        let value = thread.to_value();
+        self.threads.push(thread);
+        vec![
+            result(id, json!({ "thread": value.clone() })),
+            notification("thread/started", json!({ "thread": value })),
+        ]
+    }
+
+    fn resume_thread(&mut self, id: Value, params: &Value) -> Vec<Value> {
+        let thread_id = params.get("threadId").and_then(Value::as_str);
+        match self
+            .threads
+            .iter()
+            .find(|thread| Some(thread.id.as_str()) == thread_id)
+        {
+            Some(thread) => vec![result(id, json!({ "thread": thread.to_value() }))],
+            None => vec![error(id, INVALID_PARAMS, "thread not found")],
+        }
+    }
+
+    fn start_turn(&mut self, id: Value, params: &Value) -> Vec<Value> {
+        let Some(thread_id) = params.get("threadId").and_then(Value::as_str) else {
+            return vec![error(id, INVALID_PARAMS, "missing threadId")];
+        };
+        let turn = self.next_turn;
+        let turn_id = format!("mock-turn-{turn}");
+        self.next_turn += 1;
+
+        let mut messages = vec![
+            result(
+                id,
+                json!({ "turn": { "id": turn_id, "status": "inProgress", "items": [] } }),
+            ),
+            notification(
+                "turn/started",
+                json!({
+                    "threadId": thread_id,
+                    "turn": { "id": turn_id, "status": "inProgress", "items": [] },
+                }),
+            ),
+        ];
+        if let Some(turn_error) = self.script.turn_error.as_deref() {
+            messages.push(notification(
+                "error",
+                json!({
+                    "threadId": thread_id,
+                    "turnId": turn_id,
+                    "error": { "message": turn_error },
+                    "willRetry": false,
+                }),
+            ));
+            messages.push(notification(
+                "turn/completed",
+                json!({
+                    "threadId": thread_id,
+                    "turn": { "id": turn_id, "status": "failed", "items": [] },
+                }),
+            ));
+            return messages;
+        }
+        if let Some(command) = self.script.approval_command.clone() {
+            let request_id = json!(self.next_request);
+            self.next_request += 1;
+            let item_id = format!("mock-command-{turn}");
+            messages.push(notification(
+                "item/started",
+                json!({
+                    "threadId": thread_id,
+                    "turnId": turn_id,
+                    "item": { "type": "commandExecution", "id": item_id, "command": command },
+                }),
+            ));
+            messages.push(json!({
+                "id": request_id,
+                "method": "item/commandExecution/requestApproval",
+                "params": {
+                    "threadId": thread_id,
+                    "turnId": turn_id,
+                    "itemId": item_id,
+                    "command": command,
+                },
+            }));
+            self.pending_approvals.push(PendingApproval {
+                request_id,
+                thread_id: thread_id.to_string(),
+                turn_id,
+                turn,
+            });
+            return messages;
+        }
+
+        messages.extend(self.reply(thread_id, &turn_id, turn));
+        messages
+    }
+
+    /// Resumes the turn waiting on the approval `message` answers.
+    fn answer_approval(&mut self, message: &Value) -> Vec<Value> {
+        let Some(index) = self
+            .pending_approvals
+            .iter()
+            .position(|pending| message.get("id") == Some(&pending.request_id))
+        else {
+            return Vec::new();
+        };
+        let pending = self.pending_approvals.remove(index);
+        let accepted = message["result"]["decision"] == "accept";
+        let mut messages = vec![notification(
+            "item/completed",
+            json!({
+                "threadId": pending.thread_id,
+                "turnId": pending.turn_id,
+                "item": {
+                    "type": "commandExecution",
+                    "id": format!("mock-command-{}", pending.turn),
+                    "status": if accepted { "completed" } else { "declined" },
+                },
+            }),
+        )];
+        messages.extend(self.reply(&pending.thread_id, &pending.turn_id, pending.turn));
+        messages
+    }
+
+    /// Streams the scripted agent message and completes the turn.
+    fn reply(&self, thread_id: &str, turn_id: &str, turn: u64) -> Vec<Value> {
+        let item_id = format!("mock-item-{turn}");
+        let mut messages = vec![notification(
+            "item/started",
+            json!({
+                "threadId": thread_id,
+                "turnId": turn_id,
+                "item": { "type": "agentMessage", "id": item_id, "text": "" },
+            }),
+        )];
+        for delta in &self.script.reply_deltas {
+            messages.push(notification(
+                "item/agentMessage/delta",
+                json!({
+                    "threadId": thread_id,
+                    "turnId": turn_id,
+                    "itemId": item_id,
+                    "delta": delta,
+                }),
+            ));
+        }
+        messages.push(notification(
+            "item/completed",
+            json!({
+                "threadId": thread_id,
+                "turnId": turn_id,
+                "item": {
+                    "type": "agentMessage",
+                    "id": item_id,
+                    "text": self.script.reply_deltas.concat(),
+                },
+            }),
+        ));
+        messages.push(notification(
+            "turn/completed",
+            json!({
+                "threadId": thread_id,
+                "turn": { "id": turn_id, "status": "completed", "items": [] },
+            }),
+        ));
+        messages
+    }
+}
+
+fn result(id: Value, result: Value) -> Value {
+    json!({ "id": id, "result": result })
+}
+
+fn error(id: Value, code: i64, message: &str) -> Value {
+    json!({ "id": id, "error": { "code": code, "message": message } })
+}
+
+fn notification(method: &str, params: Value) -> Value {
+    json!({ "method": method, "params": params })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum MockCommand {
+    Version,
+    AppServer { script: Option<PathBuf> },
+    Replay { recording: PathBuf },
+}
+
+/// `--script`/`--recording`, falling back to the matching environment
+/// variable.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct MockSources {
+    script: Option<PathBuf>,
+    recording: Option<PathBuf>,
+}
+
+const USAGE: &str = "\
+USAGE: codex-monitor-mock-app-server [--script <file.json> | --recording <file.jsonl>] app-server
+
+Answers with scripted replies, or plays back a protocol recording written by
+CodexMonitor when CODEX_MONITOR_RECORD_PROTOCOL_DIR is set.";
+
+/// Accepts the argv CodexMonitor uses for `codex`: codex args first, then
+/// `app-server`. Codex args other than `--script` and `--recording` are
+/// ignored.
+fn parse_args(
+    args: impl IntoIterator<Item = String>,
+    env_sources: MockSources,
+) -> Result<MockCommand, String> {
+    let mut args = args.into_iter();
+    let mut sources = MockSources::default();
+    let mut app_server = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--version" => return Ok(MockCommand::Version),
+            "--script" => {
+                let value = args.next().ok_or("--script requires a path")?;
+                sources.script = Some(PathBuf::from(value));
+            }
+            "--recording" => {
+                let value = args.next().ok_or("--recording requires a path")?;
+                sources.recording = Some(PathBuf::from(value));
+            }
+            "app-server" => app_server = true,
+            _ => {}
+        }
+    }
+    if !app_server {
+        return Err(USAGE.to_string());
+    }
+    let script = sources.script.or(env_sources.script);
+    match sources.recording.or(env_sources.recording) {
+        Some(_) if script.is_some() => {
+            Err("--script and --recording cannot be combined".to_string())
+        }
+        Some(recording) => Ok(MockCommand::Replay { recording }),
+        None => Ok(MockCommand::AppServer { script }),
+    }
+}
+
+fn load_script(path: Option<PathBuf>) -> Result<MockScript, String> {
+    let Some(path) = path else {
+        return Ok(MockScript::default());
+    };
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|err| format!("Failed to read mock script {}: {err}", path.display()))?;
+    serde_json::from_str(&contents)
+        .map_err(|err| format!("Invalid mock script {}: {err}", path.display()))
+}
+
+fn serve(script: MockScript) -> Result<(), String> {
+    let mut server = MockAppServer::new(script);
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout().lock();
+    for line in stdin.lock().lines() {
+        let line = line.map_err(|err| format!("Failed to read stdin: {err}"))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Ok(message) = serde_json::from_str::<Value>(&line) else {
+            eprintln!("mock app-server ignored a non-JSON line");
+            continue;
+        };
+        for reply in server.handle(&message) {
+            writeln!(stdout, "{reply}").map_err(|err| format!("Failed to write stdout: {err}"))?;
+        }
+        stdout
+            .flush()
+            .map_err(|err| format!("Failed to write stdout: {err}"))?;
+    }
+    Ok(())
+}
+
+fn main() {
+    let env_sources = MockSources {
+        script: env::var_os(SCRIPT_ENV).map(PathBuf::from),
+        recording: env::var_os(RECORDING_ENV).map(PathBuf::from),
+    };
+    let result = parse_args(env::args().skip(1), env_sources).and_then(|command| match command {
+        MockCommand::Version => {
+            println!(
+                "codex-monitor-mock-app-server {}",
+                env!("CARGO_PKG_VERSION")
+            );
+            Ok(())
+        }
+        MockCommand::AppServer { script } => serve(load_script(script)?),
+        MockCommand::Replay { recording } => replay::replay_recording(
+            replay::load_recording(&recording)?,
+            std::io::stdin().lock(),
+            std::io::stdout().lock(),
+        ),
+    });
+    if let Err(err) = result {
+        eprintln!("{err}");
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(id: u64, method: &str, params: Value) -> Value {
+        json!({ "id": id, "method": method, "params": params })
+    }
+
+    fn methods(messages: &[Value]) -> Vec<&str> {
+        messages
+            .iter()
+            .map(|message| {
+                message
+                    .get("method")
+                    .and_then(Value::as_str)
+                    .unwrap_or("<response>")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn turn_start_streams_reply_deltas_then_completes() {
+        let mut server = MockAppServer::new(MockScript::default());
+        let started = server.handle(&request(1, "thread/start", json!({ "cwd": "/tmp/ws" })));
+        let thread_id = started[0]["result"]["thread"]["id"]
+            .as_str()
+            .expect("thread id")
+            .to_string();
+
+        let messages = server.handle(&request(
+            2,
+            "turn/start",
+            json!({ "threadId": thread_id, "input": [] }),
+        ));
+
+        assert_eq!(
+            methods(&messages),
+            vec![
+                "<response>",
+                "turn/started",
+                "item/started",
+                "item/agentMessage/delta",
+                "item/agentMessage/delta",
+                "item/completed",
+                "turn/completed",
+            ]
+        );
+        assert_eq!(messages[0]["id"], json!(2));
+        assert_eq!(
+            messages[5]["params"]["item"]["text"],
+            "Hello from the mock app-server."
+        );
+        assert_eq!(messages[6]["params"]["threadId"], json!(thread_id));
+    }
+
+    #[test]
+    fn scripted_turn_error_fails_the_turn() {
+        let script: MockScript =
+            serde_json::from_value(json!({ "turnError": "quota exceeded" })).expect("script");
+        let mut server = MockAppServer::new(script);
+
+        let messages = server.handle(&request(1, "turn/start", json!({ "threadId": "t-1" })));
+
+        assert_eq!(
+            methods(&messages),
+            vec!["<response>", "turn/started", "error", "turn/completed"]
+        );
+        assert_eq!(messages[2]["params"]["error"]["message"], "quota exceeded");
+        assert_eq!(messages[3]["params"]["turn"]["status"], "failed");
+    }
+
+    #[test]
+    fn scripted_approval_pauses_the_turn_until_answered() {
+        let script: MockScript =
+            serde_json::from_value(json!({ "approvalCommand": "cargo test" })).expect("script");
+        let mut server = MockAppServer::new(script);
+
+        let messages = server.handle(&request(1, "turn/start", json!({ "threadId": "t-1" })));
+        assert_eq!(
+            methods(&messages),
+            vec![
+                "<response>",
+                "turn/started",
+                "item/started",
+                "item/commandExecution/requestApproval",
+            ]
+        );
+        let approval = &messages[3];
+        assert_eq!(approval["params"]["command"], "cargo test");
+        assert!(server
+            .handle(&json!({ "id": 99, "result": { "decision": "accept" } }))
+            .is_empty());
+
+        let messages = server.handle(&json!({
+            "id": approval["id"],
+            "result": { "decision": "decline" },
+        }));
+        assert_eq!(messages[0]["params"]["item"]["status"], "declined");
+        assert_eq!(methods(&messages).last(), Some(&"turn/completed"));
+    }
+
+    #[test]
+    fn thread_list_includes_scripted_and_started_threads() {
+        let script: MockScript = serde_json::from_value(json!({
+            "threads": [{ "id": "seeded", "preview": "old work", "cwd": "/tmp/ws" }]
+        }))
+        .expect("script");
+        let mut server = MockAppServer::new(script);
+        server.handle(&request(1, "thread/start", json!({ "cwd": "/tmp/ws" })));
+
+        let messages = server.handle(&request(2, "thread/list", json!({})));
+
+        let ids: Vec<&str> = messages[0]["result"]["data"]
+            .as_array()
+            .expect("data")
+            .iter()
+            .filter_map(|thread| thread["id"].as_str())
+            .collect();
+        assert_eq!(ids, vec!["seeded", "mock-thread-1"]);
+    }
+
+    #[test]
+    fn notifications_get_no_reply_and_unknown_requests_get_an_error() {
+        let mut server = MockAppServer::new(MockScript::default());
+
+        assert!(server
+            .handle(&json!({ "method": "initialized" }))
+            .is_empty());
+        let messages = server.handle(&request(4, "model/list", json!({})));
+
+        assert_eq!(messages[0]["error"]["code"], json!(METHOD_NOT_FOUND));
+    }
+
+    #[test]
+    fn parse_args_prefers_flags_over_env() {
+        let args = |values: &[&str]| {
+            values
+                .iter()
+                .map(|value| value.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let env = |script: Option<&str>, recording: Option<&str>| MockSources {
+            script: script.map(PathBuf::from),
+            recording: recording.map(PathBuf::from),
+        };
+
+        assert_eq!(
+            parse_args(args(&["--version"]), env(None, None)),
+            Ok(MockCommand::Version)
+        );
+        assert_eq!(
+            parse_args(
+                args(&["--script", "/tmp/a.json", "app-server"]),
+                env(Some("/tmp/env.json"), None)
+            ),
+            Ok(MockCommand::AppServer {
+                script: Some(PathBuf::from("/tmp/a.json"))
+            })
+        );
+        assert_eq!(
+            parse_args(args(&["app-server"]), env(None, None)),
+            Ok(MockCommand::AppServer { script: None })
+        );
+        assert_eq!(
+            parse_args(
+                args(&["-c", "model=x", "app-server"]),
+                env(None, Some("/tmp/env.jsonl"))
+            ),
+            Ok(MockCommand::Replay {
+                recording: PathBuf::from("/tmp/env.jsonl")
+            })
+        );
+        assert!(parse_args(
+            args(&["--recording", "/tmp/a.jsonl", "app-server"]),
+            env(Some("/tmp/env.json"), None)
+        )
+        .is_err());
+        assert!(parse_args(args(&[]), env(None, None)).is_err());
+    }
+}
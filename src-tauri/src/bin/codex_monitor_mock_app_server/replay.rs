@@ -0,0 +1,298 @@
+//! Replay mode: plays a protocol recording back instead of the scripted
+//! replies.
+
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{BufRead, Lines, Write};
+use std::path::Path;
+
+use crate::protocol_recorder::{Direction, RecordedLine};
+
+const UNSCRIPTED_REQUEST_ERROR_CODE: i64 = -32000;
+
+pub(crate) fn load_recording(path: &Path) -> Result<Vec<RecordedLine>, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|err| format!("Failed to read recording {}: {err}", path.display()))?;
+    parse_recording(&contents)
+}
+
+fn parse_recording(contents: &str) -> Result<Vec<RecordedLine>, String> {
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            serde_json::from_str(line)
+                .map_err(|err| format!("Invalid recording line {}: {err}", index + 1))
+        })
+        .collect()
+}
+
+fn message_method(message: &Value) -> Option<&str> {
+    message.get("method").and_then(Value::as_str)
+}
+
+fn is_request(message: &Value) -> bool {
+    message.get("id").is_some() && message.get("method").is_some()
+}
+
+fn is_response(message: &Value) -> bool {
+    message.get("id").is_some() && message.get("method").is_none()
+}
+
+fn describe(message: &Value) -> String {
+    match message_method(message) {
+        Some(method) => format!("`{method}`"),
+        None if is_response(message) => "a response".to_string(),
+        None => "a non-JSON-RPC line".to_string(),
+    }
+}
+
+fn read_client_message<R: BufRead>(input: &mut Lines<R>) -> Result<Option<Value>, String> {
+    loop {
+        let Some(line) = input.next() else {
+            return Ok(None);
+        };
+        let line = line.map_err(|err| format!("Failed to read client input: {err}"))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let message =
+            serde_json::from_str(&line).unwrap_or_else(|_| Value::String(line.to_string()));
+        return Ok(Some(message));
+    }
+}
+
+fn write_server_message<W: Write>(output: &mut W, message: &Value) -> Result<(), String> {
+    let mut line = match message {
+        Value::String(raw) => raw.clone(),
+        other => other.to_string(),
+    };
+    line.push('\n');
+    output
+        .write_all(line.as_bytes())
+        .map_err(|err| format!("Failed to write replay output: {err}"))?;
+    output
+        .flush()
+        .map_err(|err| format!("Failed to write replay output: {err}"))
+}
+
+/// Recorded `send` lines are awaited from `input` in order and must carry the
+/// same method; `recv` lines are written to `output` with response ids
+/// rewritten to the live request ids. Timing is not reproduced. Once the
+/// recording runs out, further requests get an error response until `input`
+/// closes, so the client sees a live server rather than a crash.
+pub(crate) fn replay_recording<R, W>(
+    recording: Vec<RecordedLine>,
+    input: R,
+    mut output: W,
+) -> Result<(), String>
+where
+    R: BufRead,
+    W: Write,
+{
+    let mut input = input.lines();
+    let mut live_ids: HashMap<String, Value> = HashMap::new();
+
+    for (index, recorded) in recording.into_iter().enumerate() {
+        let step = index + 1;
+        match recorded.dir {
+            Direction::Send => {
+                let expected = describe(&recorded.message);
+                let Some(live) = read_client_message(&mut input)? else {
+                    return Err(format!(
+                        "Client closed before recorded step {step} ({expected})"
+                    ));
+                };
+                if message_method(&live) != message_method(&recorded.message) {
+                    return Err(format!(
+                        "Replay diverged at step {step}: expected {expected}, got {}",
+                        describe(&live)
+                    ));
+                }
+                if is_request(&recorded.message) {
+                    if let (Some(recorded_id), Some(live_id)) =
+                        (recorded.message.get("id"), live.get("id"))
+                    {
+                        live_ids.insert(recorded_id.to_string(), live_id.clone());
+                    }
+                }
+            }
+            Direction::Recv => {
+                let mut message = recorded.message;
+                if is_response(&message) {
+                    let live_id = message
+                        .get("id")
+                        .and_then(|id| live_ids.remove(&id.to_string()));
+                    if let Some(live_id) = live_id {
+                        message["id"] = live_id;
+                    }
+                }
+                write_server_message(&mut output, &message)?;
+            }
+        }
+    }
+
+    while let Some(live) = read_client_message(&mut input)? {
+        if !is_request(&live) {
+            continue;
+        }
+        let method = message_method(&live).unwrap_or_default();
+        let response = json!({
+            "id": live["id"].clone(),
+            "error": {
+                "code": UNSCRIPTED_REQUEST_ERROR_CODE,
+                "message": format!("Recording has no response for `{method}`"),
+            },
+        });
+        write_server_message(&mut output, &response)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recorded(dir: Direction, message: Value) -> RecordedLine {
+        RecordedLine {
+            ts: 0,
+            dir,
+            message,
+        }
+    }
+
+    fn run_replay(
+        recording: Vec<RecordedLine>,
+        client_lines: &[Value],
+    ) -> (Result<(), String>, Vec<Value>) {
+        let input: String = client_lines
+            .iter()
+            .map(|line| format!("{line}\n"))
+            .collect();
+        let mut output = Vec::new();
+        let result = replay_recording(recording, input.as_bytes(), &mut output);
+        let messages = String::from_utf8(output)
+            .expect("replay output is utf-8")
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("replay output is json"))
+            .collect();
+        (result, messages)
+    }
+
+    #[test]
+    fn parse_recording_reports_the_bad_line() {
+        let contents = concat!(
+            r#"{"ts":1,"dir":"send","message":{"method":"initialized"}}"#,
+            "\n\n",
+            r#"{"ts":2,"dir":"sideways","message":{}}"#,
+            "\n",
+        );
+
+        let error = parse_recording(contents).expect_err("invalid dir");
+
+        assert!(error.starts_with("Invalid recording line 3"));
+    }
+
+    #[test]
+    fn replay_rewrites_response_ids_and_streams_notifications() {
+        let recording = vec![
+            recorded(
+                Direction::Send,
+                json!({ "id": 7, "method": "initialize", "params": {} }),
+            ),
+            recorded(
+                Direction::Recv,
+                json!({ "id": 7, "result": { "userAgent": "codex" } }),
+            ),
+            recorded(Direction::Send, json!({ "method": "initialized" })),
+            recorded(
+                Direction::Send,
+                json!({ "id": 8, "method": "thread/start", "params": {} }),
+            ),
+            recorded(
+                Direction::Recv,
+                json!({ "id": 8, "result": { "thread": { "id": "thread-1" } } }),
+            ),
+            recorded(
+                Direction::Recv,
+                json!({ "method": "thread/started", "params": { "thread": { "id": "thread-1" } } }),
+            ),
+        ];
+        let client = [
+            json!({ "id": 1, "method": "initialize", "params": {} }),
+            json!({ "method": "initialized" }),
+            json!({ "id": 2, "method": "thread/start", "params": {} }),
+        ];
+
+        let (result, output) = run_replay(recording, &client);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(output.len(), 3);
+        assert_eq!(output[0]["id"], json!(1));
+        assert_eq!(output[1]["id"], json!(2));
+        assert_eq!(output[1]["result"]["thread"]["id"], "thread-1");
+        assert_eq!(output[2]["method"], "thread/started");
+    }
+
+    #[test]
+    fn replay_keeps_server_request_ids() {
+        let recording = vec![
+            recorded(
+                Direction::Recv,
+                json!({ "id": 0, "method": "item/commandExecution/requestApproval", "params": {} }),
+            ),
+            recorded(
+                Direction::Send,
+                json!({ "id": 0, "result": { "decision": "accept" } }),
+            ),
+            recorded(
+                Direction::Recv,
+                json!({ "method": "turn/completed", "params": {} }),
+            ),
+        ];
+        let client = [json!({ "id": 0, "result": { "decision": "accept" } })];
+
+        let (result, output) = run_replay(recording, &client);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(output[0]["id"], json!(0));
+        assert_eq!(output[1]["method"], "turn/completed");
+    }
+
+    #[test]
+    fn replay_reports_divergence_from_recording() {
+        let recording = vec![
+            recorded(
+                Direction::Send,
+                json!({ "id": 1, "method": "thread/start", "params": {} }),
+            ),
+            recorded(Direction::Recv, json!({ "id": 1, "result": {} })),
+        ];
+        let client = [json!({ "id": 1, "method": "turn/start", "params": {} })];
+
+        let (result, output) = run_replay(recording, &client);
+
+        let error = result.expect_err("divergence");
+        assert!(error.contains("expected `thread/start`, got `turn/start`"));
+        assert!(output.is_empty());
+    }
+
+    #[test]
+    fn replay_answers_unscripted_requests_with_an_error() {
+        let client = [
+            json!({ "method": "initialized" }),
+            json!({ "id": 3, "method": "thread/list", "params": {} }),
+        ];
+
+        let (result, output) = run_replay(Vec::new(), &client);
+
+        assert_eq!(result, Ok(()));
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0]["id"], json!(3));
+        assert_eq!(
+            output[0]["error"]["code"],
+            json!(UNSCRIPTED_REQUEST_ERROR_CODE)
+        );
+    }
+}
@@ -1,24 +1,25 @@
 use std::path::PathBuf;
 
 use crate::shared::config_toml_core;
+use crate::shared::core_error::CoreError;
 
-pub(crate) fn read_steer_enabled() -> Result<Option<bool>, String> {
+pub(crate) fn read_steer_enabled() -> Result<Option<bool>, CoreError> {
     read_feature_flag("steer")
 }
 
-pub(crate) fn read_collaboration_modes_enabled() -> Result<Option<bool>, String> {
+pub(crate) fn read_collaboration_modes_enabled() -> Result<Option<bool>, CoreError> {
     read_feature_flag("collaboration_modes")
 }
 
-pub(crate) fn read_unified_exec_enabled() -> Result<Option<bool>, String> {
+pub(crate) fn read_unified_exec_enabled() -> Result<Option<bool>, CoreError> {
     read_feature_flag("unified_exec")
 }
 
-pub(crate) fn read_apps_enabled() -> Result<Option<bool>, String> {
+pub(crate) fn read_apps_enabled() -> Result<Option<bool>, CoreError> {
     read_feature_flag("apps")
 }
 
-pub(crate) fn read_personality() -> Result<Option<String>, String> {
+pub(crate) fn read_personality() -> Result<Option<String>, CoreError> {
     let Some(root) = resolve_default_codex_home() else {
         return Ok(None);
     };
@@ -26,34 +27,36 @@ pub(crate) fn read_personality() -> Result<Option<String>, String> {
     Ok(read_personality_from_document(&document))
 }
 
-pub(crate) fn write_steer_enabled(enabled: bool) -> Result<(), String> {
+pub(crate) fn write_steer_enabled(enabled: bool) -> Result<(), CoreError> {
     write_feature_flag("steer", enabled)
 }
 
-pub(crate) fn write_collaboration_modes_enabled(enabled: bool) -> Result<(), String> {
+pub(crate) fn write_collaboration_modes_enabled(enabled: bool) -> Result<(), CoreError> {
     write_feature_flag("collaboration_modes", enabled)
 }
 
-pub(crate) fn write_unified_exec_enabled(enabled: bool) -> Result<(), String> {
+pub(crate) fn write_unified_exec_enabled(enabled: bool) -> Result<(), CoreError> {
     write_feature_flag("unified_exec", enabled)
 }
 
-pub(crate) fn write_apps_enabled(enabled: bool) -> Result<(), String> {
+pub(crate) fn write_apps_enabled(enabled: bool) -> Result<(), CoreError> {
     write_feature_flag("apps", enabled)
 }
 
-pub(crate) fn write_feature_enabled(feature_key: &str, enabled: bool) -> Result<(), String> {
+pub(crate) fn write_feature_enabled(feature_key: &str, enabled: bool) -> Result<(), CoreError> {
     let key = feature_key.trim();
     if key.is_empty() {
-        return Err("feature key is empty".to_string());
+        return Err(CoreError::invalid_params("feature key is empty"));
     }
     if key.eq_ignore_ascii_case("collab") {
-        return Err("feature key `collab` is no longer supported; use `multi_agent`".to_string());
+        return Err(CoreError::invalid_params(
+            "feature key `collab` is no longer supported; use `multi_agent`",
+        ));
     }
     write_feature_flag(key, enabled)
 }
 
-pub(crate) fn write_personality(personality: &str) -> Result<(), String> {
+pub(crate) fn write_personality(personality: &str) -> Result<(), CoreError> {
     let Some(root) = resolve_default_codex_home() else {
         return Ok(());
     };
@@ -63,7 +66,7 @@ pub(crate) fn write_personality(personality: &str) -> Result<(), String> {
     config_toml_core::persist_global_config_document(&root, &document)
 }
 
-fn read_feature_flag(key: &str) -> Result<Option<bool>, String> {
+fn read_feature_flag(key: &str) -> Result<Option<bool>, CoreError> {
     let Some(root) = resolve_default_codex_home() else {
         return Ok(None);
     };
@@ -71,7 +74,7 @@ fn read_feature_flag(key: &str) -> Result<Option<bool>, String> {
     Ok(config_toml_core::read_feature_flag(&document, key))
 }
 
-fn write_feature_flag(key: &str, enabled: bool) -> Result<(), String> {
+fn write_feature_flag(key: &str, enabled: bool) -> Result<(), CoreError> {
     let Some(root) = resolve_default_codex_home() else {
         return Ok(());
     };
@@ -84,10 +87,10 @@ pub(crate) fn config_toml_path() -> Option<PathBuf> {
     resolve_default_codex_home().map(|home| home.join("config.toml"))
 }
 
-pub(crate) fn read_config_model(codex_home: Option<PathBuf>) -> Result<Option<String>, String> {
+pub(crate) fn read_config_model(codex_home: Option<PathBuf>) -> Result<Option<String>, CoreError> {
     let root = codex_home.or_else(resolve_default_codex_home);
     let Some(root) = root else {
-        return Err("Unable to resolve CODEX_HOME".to_string());
+        return Err(CoreError::internal("Unable to resolve CODEX_HOME"));
     };
     let (_, document) = config_toml_core::load_global_config_document(&root)?;
     Ok(config_toml_core::read_top_level_string(&document, "model"))
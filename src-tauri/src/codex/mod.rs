@@ -2,19 +2,23 @@ use serde_json::{json, Map, Value};
 use std::path::PathBuf;
 use std::sync::Arc;
 
-use tauri::{AppHandle, Emitter, State};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::mpsc;
 
 pub(crate) mod args;
 pub(crate) mod config;
 pub(crate) mod home;
 
 use crate::backend::app_server::spawn_workspace_session as spawn_workspace_session_inner;
+use crate::backend::app_server::SessionExit;
 pub(crate) use crate::backend::app_server::WorkspaceSession;
 use crate::backend::events::AppServerEvent;
 use crate::event_sink::TauriEventSink;
 use crate::remote_backend;
 use crate::shared::agents_config_core;
 use crate::shared::codex_core;
+use crate::shared::core_error::CoreError;
+use crate::shared::workspaces_core;
 use crate::state::AppState;
 use crate::types::WorkspaceEntry;
 
@@ -51,8 +55,12 @@ pub(crate) async fn spawn_workspace_session(
     codex_args: Option<String>,
     app_handle: AppHandle,
     codex_home: Option<PathBuf>,
-) -> Result<Arc<WorkspaceSession>, String> {
+) -> Result<Arc<WorkspaceSession>, CoreError> {
     let client_version = app_handle.package_info().version.to_string();
+    let (session_exits, request_timeouts) = {
+        let state = app_handle.state::<AppState>();
+        (state.session_exits.clone(), state.request_timeouts.clone())
+    };
     let event_sink = TauriEventSink::new(app_handle);
     spawn_workspace_session_inner(
         entry,
@@ -61,10 +69,47 @@ pub(crate) async fn spawn_workspace_session(
         codex_home,
         client_version,
         event_sink,
+        session_exits,
+        request_timeouts,
     )
     .await
 }
 
+/// Restarts app-server sessions that exit while workspaces still use them.
+pub(crate) async fn supervise_workspace_sessions(
+    app: AppHandle,
+    mut session_exits: mpsc::UnboundedReceiver<SessionExit>,
+) {
+    while let Some(exit) = session_exits.recv().await {
+        let state = app.state::<AppState>();
+        workspaces_core::handle_session_exit_core(
+            exit,
+            &state.workspaces,
+            &state.sessions,
+            &state.app_settings,
+            &TauriEventSink::new(app.clone()),
+            |entry, default_bin, codex_args, codex_home| {
+                spawn_workspace_session(entry, default_bin, codex_args, app.clone(), codex_home)
+            },
+        )
+        .await;
+    }
+}
+
+/// Puts idle workspaces to sleep per `AppSettings::session_pool`.
+pub(crate) async fn sweep_workspace_session_pool(app: AppHandle) {
+    loop {
+        tokio::time::sleep(workspaces_core::SESSION_POOL_SWEEP_INTERVAL).await;
+        let state = app.state::<AppState>();
+        workspaces_core::enforce_session_pool_core(
+            &state.sessions,
+            &state.app_settings,
+            &TauriEventSink::new(app.clone()),
+        )
+        .await;
+    }
+}
+
 #[tauri::command]
 pub(crate) async fn codex_doctor(
     codex_bin: Option<String>,
@@ -73,6 +118,7 @@ pub(crate) async fn codex_doctor(
 ) -> Result<Value, String> {
     crate::shared::codex_aux_core::codex_doctor_core(&state.app_settings, codex_bin, codex_args)
         .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -83,6 +129,7 @@ pub(crate) async fn codex_update(
 ) -> Result<Value, String> {
     crate::shared::codex_update_core::codex_update_core(&state.app_settings, codex_bin, codex_args)
         .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -101,7 +148,9 @@ pub(crate) async fn start_thread(
         .await;
     }
 
-    codex_core::start_thread_core(&state.sessions, &state.workspaces, workspace_id).await
+    codex_core::start_thread_core(&state.sessions, &state.workspaces, workspace_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -121,7 +170,9 @@ pub(crate) async fn resume_thread(
         .await;
     }
 
-    codex_core::resume_thread_core(&state.sessions, workspace_id, thread_id).await
+    codex_core::resume_thread_core(&state.sessions, workspace_id, thread_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -200,6 +251,31 @@ pub(crate) async fn thread_live_unsubscribe(
     Ok(json!({ "ok": true }))
 }
 
+/// Limits which daemon events reach this client in remote mode. Local mode
+/// receives events in-process, so there is nothing to filter.
+#[tauri::command]
+pub(crate) async fn subscribe_events(
+    workspace_ids: Option<Vec<String>>,
+    thread_ids: Option<Vec<String>>,
+    methods: Option<Vec<String>>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Value, String> {
+    if !remote_backend::is_remote_mode(&*state).await {
+        return Ok(json!({ "ok": true, "skipped": "local_mode" }));
+    }
+    remote_backend::subscribe_remote_events(
+        &*state,
+        app,
+        json!({
+            "workspaceIds": workspace_ids,
+            "threadIds": thread_ids,
+            "methods": methods,
+        }),
+    )
+    .await
+}
+
 #[tauri::command]
 pub(crate) async fn fork_thread(
     workspace_id: String,
@@ -217,7 +293,9 @@ pub(crate) async fn fork_thread(
         .await;
     }
 
-    codex_core::fork_thread_core(&state.sessions, workspace_id, thread_id).await
+    codex_core::fork_thread_core(&state.sessions, workspace_id, thread_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -244,7 +322,9 @@ pub(crate) async fn list_threads(
         .await;
     }
 
-    codex_core::list_threads_core(&state.sessions, workspace_id, cursor, limit, sort_key).await
+    codex_core::list_threads_core(&state.sessions, workspace_id, cursor, limit, sort_key)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -265,7 +345,9 @@ pub(crate) async fn list_mcp_server_status(
         .await;
     }
 
-    codex_core::list_mcp_server_status_core(&state.sessions, workspace_id, cursor, limit).await
+    codex_core::list_mcp_server_status_core(&state.sessions, workspace_id, cursor, limit)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -285,7 +367,9 @@ pub(crate) async fn archive_thread(
         .await;
     }
 
-    codex_core::archive_thread_core(&state.sessions, workspace_id, thread_id).await
+    codex_core::archive_thread_core(&state.sessions, workspace_id, thread_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -305,7 +389,9 @@ pub(crate) async fn compact_thread(
         .await;
     }
 
-    codex_core::compact_thread_core(&state.sessions, workspace_id, thread_id).await
+    codex_core::compact_thread_core(&state.sessions, workspace_id, thread_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -326,7 +412,9 @@ pub(crate) async fn set_thread_name(
         .await;
     }
 
-    codex_core::set_thread_name_core(&state.sessions, workspace_id, thread_id, name).await
+    codex_core::set_thread_name_core(&state.sessions, workspace_id, thread_id, name)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -387,6 +475,7 @@ pub(crate) async fn send_user_message(
         collaboration_mode,
     )
     .await
+    .map_err(String::from)
 }
 
 #[tauri::command]
@@ -409,6 +498,7 @@ pub(crate) async fn run_bang_command(
 
     codex_core::run_bang_command_core(&state.sessions, &state.workspaces, workspace_id, command)
         .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -455,6 +545,7 @@ pub(crate) async fn turn_steer(
         app_mentions,
     )
     .await
+    .map_err(String::from)
 }
 
 #[cfg(test)]
@@ -491,7 +582,9 @@ pub(crate) async fn collaboration_mode_list(
         .await;
     }
 
-    codex_core::collaboration_mode_list_core(&state.sessions, workspace_id).await
+    codex_core::collaboration_mode_list_core(&state.sessions, workspace_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -512,7 +605,9 @@ pub(crate) async fn turn_interrupt(
         .await;
     }
 
-    codex_core::turn_interrupt_core(&state.sessions, workspace_id, thread_id, turn_id).await
+    codex_core::turn_interrupt_core(&state.sessions, workspace_id, thread_id, turn_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -539,7 +634,9 @@ pub(crate) async fn start_review(
         .await;
     }
 
-    codex_core::start_review_core(&state.sessions, workspace_id, thread_id, target, delivery).await
+    codex_core::start_review_core(&state.sessions, workspace_id, thread_id, target, delivery)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -558,7 +655,9 @@ pub(crate) async fn model_list(
         .await;
     }
 
-    codex_core::model_list_core(&state.sessions, workspace_id).await
+    codex_core::model_list_core(&state.sessions, workspace_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -583,7 +682,9 @@ pub(crate) async fn experimental_feature_list(
         .await;
     }
 
-    codex_core::experimental_feature_list_core(&state.sessions, workspace_id, cursor, limit).await
+    codex_core::experimental_feature_list_core(&state.sessions, workspace_id, cursor, limit)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -607,7 +708,7 @@ pub(crate) async fn set_codex_feature_flag(
         return Ok(());
     }
 
-    config::write_feature_enabled(feature_key.as_str(), enabled)
+    config::write_feature_enabled(feature_key.as_str(), enabled).map_err(String::from)
 }
 
 #[tauri::command]
@@ -621,7 +722,7 @@ pub(crate) async fn get_agents_settings(
         return serde_json::from_value(response).map_err(|err| err.to_string());
     }
 
-    agents_config_core::get_agents_settings_core()
+    agents_config_core::get_agents_settings_core().map_err(String::from)
 }
 
 #[tauri::command]
@@ -641,7 +742,7 @@ pub(crate) async fn set_agents_core_settings(
         return serde_json::from_value(response).map_err(|err| err.to_string());
     }
 
-    agents_config_core::set_agents_core_settings_core(input)
+    agents_config_core::set_agents_core_settings_core(input).map_err(String::from)
 }
 
 #[tauri::command]
@@ -657,7 +758,7 @@ pub(crate) async fn create_agent(
         return serde_json::from_value(response).map_err(|err| err.to_string());
     }
 
-    agents_config_core::create_agent_core(input)
+    agents_config_core::create_agent_core(input).map_err(String::from)
 }
 
 #[tauri::command]
@@ -673,7 +774,7 @@ pub(crate) async fn update_agent(
         return serde_json::from_value(response).map_err(|err| err.to_string());
     }
 
-    agents_config_core::update_agent_core(input)
+    agents_config_core::update_agent_core(input).map_err(String::from)
 }
 
 #[tauri::command]
@@ -689,7 +790,7 @@ pub(crate) async fn delete_agent(
         return serde_json::from_value(response).map_err(|err| err.to_string());
     }
 
-    agents_config_core::delete_agent_core(input)
+    agents_config_core::delete_agent_core(input).map_err(String::from)
 }
 
 #[tauri::command]
@@ -709,7 +810,7 @@ pub(crate) async fn read_agent_config_toml(
         return serde_json::from_value(response).map_err(|err| err.to_string());
     }
 
-    agents_config_core::read_agent_config_toml_core(agent_name.as_str())
+    agents_config_core::read_agent_config_toml_core(agent_name.as_str()).map_err(String::from)
 }
 
 #[tauri::command]
@@ -734,6 +835,7 @@ pub(crate) async fn write_agent_config_toml(
     }
 
     agents_config_core::write_agent_config_toml_core(agent_name.as_str(), content.as_str())
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -752,7 +854,9 @@ pub(crate) async fn account_rate_limits(
         .await;
     }
 
-    codex_core::account_rate_limits_core(&state.sessions, workspace_id).await
+    codex_core::account_rate_limits_core(&state.sessions, workspace_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -771,7 +875,9 @@ pub(crate) async fn account_read(
         .await;
     }
 
-    codex_core::account_read_core(&state.sessions, &state.workspaces, workspace_id).await
+    codex_core::account_read_core(&state.sessions, &state.workspaces, workspace_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -790,7 +896,9 @@ pub(crate) async fn codex_login(
         .await;
     }
 
-    codex_core::codex_login_core(&state.sessions, &state.codex_login_cancels, workspace_id).await
+    codex_core::codex_login_core(&state.sessions, &state.codex_login_cancels, workspace_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -811,6 +919,7 @@ pub(crate) async fn codex_login_cancel(
 
     codex_core::codex_login_cancel_core(&state.sessions, &state.codex_login_cancels, workspace_id)
         .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -829,7 +938,9 @@ pub(crate) async fn skills_list(
         .await;
     }
 
-    codex_core::skills_list_core(&state.sessions, &state.workspaces, workspace_id).await
+    codex_core::skills_list_core(&state.sessions, &state.workspaces, workspace_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -856,7 +967,9 @@ pub(crate) async fn apps_list(
         .await;
     }
 
-    codex_core::apps_list_core(&state.sessions, workspace_id, cursor, limit, thread_id).await
+    codex_core::apps_list_core(&state.sessions, workspace_id, cursor, limit, thread_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -880,6 +993,7 @@ pub(crate) async fn respond_to_server_request(
 
     codex_core::respond_to_server_request_core(&state.sessions, workspace_id, request_id, result)
         .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -888,7 +1002,9 @@ pub(crate) async fn remember_approval_rule(
     command: Vec<String>,
     state: State<'_, AppState>,
 ) -> Result<Value, String> {
-    codex_core::remember_approval_rule_core(&state.workspaces, workspace_id, command).await
+    codex_core::remember_approval_rule_core(&state.workspaces, workspace_id, command)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -907,7 +1023,9 @@ pub(crate) async fn get_config_model(
         .await;
     }
 
-    codex_core::get_config_model_core(&state.workspaces, workspace_id).await
+    codex_core::get_config_model_core(&state.workspaces, workspace_id)
+        .await
+        .map_err(String::from)
 }
 
 /// Generates a commit message in the background without showing in the main chat
@@ -962,6 +1080,7 @@ pub(crate) async fn generate_commit_message(
         },
     )
     .await
+    .map_err(String::from)
 }
 
 #[tauri::command]
@@ -1003,6 +1122,7 @@ pub(crate) async fn generate_run_metadata(
         },
     )
     .await
+    .map_err(String::from)
 }
 
 #[tauri::command]
@@ -1045,4 +1165,5 @@ pub(crate) async fn generate_agent_description(
         },
     )
     .await
+    .map_err(String::from)
 }
@@ -1532,9 +1532,11 @@ fn start_capture_thread(
         }
     });
 
-    eprintln!(
+    tracing::debug!(
         "dictation: capture started (rate={}Hz, channels={}, format={:?})",
-        sample_rate, channels, sample_format
+        sample_rate,
+        channels,
+        sample_format
     );
     let _ = ready_tx.send(Ok(sample_rate));
     let _ = stop_rx.recv();
@@ -1644,7 +1646,7 @@ fn transcribe_audio(
             *value = (*value * gain).clamp(-1.0, 1.0);
         }
     }
-    eprintln!(
+    tracing::debug!(
         "dictation: captured {} samples ({:.2}s), max={:.4}, rms={:.4}, gain={:.2}",
         samples.len(),
         duration,
@@ -1711,7 +1713,7 @@ fn transcribe_audio(
     let segments = state
         .full_n_segments()
         .map_err(|error| format!("Failed to read segments: {error}"))?;
-    eprintln!("dictation: whisper segments={}", segments);
+    tracing::debug!("dictation: whisper segments={}", segments);
     let mut transcript = String::new();
     for index in 0..segments {
         let segment = state
@@ -1721,9 +1723,12 @@ fn transcribe_audio(
     }
     let cleaned = transcript.trim().to_string();
     if cleaned.is_empty() {
-        eprintln!(
+        tracing::debug!(
             "dictation: no speech detected (rms={:.4}, max={:.4}, duration={:.2}s, segments={})",
-            rms, max, duration, segments
+            rms,
+            max,
+            duration,
+            segments
         );
         return Ok(String::new());
     }
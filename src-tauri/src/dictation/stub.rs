@@ -197,6 +197,7 @@ pub(crate) async fn dictation_transcribe_audio(
         language,
     )
     .await
+    .map_err(String::from)
 }
 
 #[tauri::command]
@@ -31,7 +31,9 @@ async fn file_read_impl(
         return serde_json::from_value(response).map_err(|err| err.to_string());
     }
 
-    file_read_core(&state.workspaces, scope, kind, workspace_id).await
+    file_read_core(&state.workspaces, scope, kind, workspace_id)
+        .await
+        .map_err(String::from)
 }
 
 async fn file_write_impl(
@@ -58,7 +60,9 @@ async fn file_write_impl(
         return Ok(());
     }
 
-    file_write_core(&state.workspaces, scope, kind, workspace_id, content).await
+    file_write_core(&state.workspaces, scope, kind, workspace_id, content)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -121,7 +125,7 @@ pub(crate) async fn read_image_as_data_url(
         return Err("Image path is required".to_string());
     }
 
-    codex_core::read_image_as_data_url_core(&normalized)
+    codex_core::read_image_as_data_url_core(&normalized).map_err(String::from)
 }
 
 #[tauri::command]
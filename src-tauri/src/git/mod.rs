@@ -12,7 +12,7 @@ use crate::types::{
 };
 
 fn git_remote_params<T: Serialize>(request: &T) -> Result<Value, String> {
-    git_rpc::to_params(request)
+    git_rpc::to_params(request).map_err(String::from)
 }
 
 fn optional_usize_to_u32(value: Option<usize>) -> Option<u32> {
@@ -93,7 +93,9 @@ pub(crate) async fn get_git_status(
         git_rpc::METHOD_GET_GIT_STATUS,
         git_remote_params(&request)?
     );
-    git_ui_core::get_git_status_core(&state.workspaces, workspace_id).await
+    git_ui_core::get_git_status_core(&state.workspaces, workspace_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -122,6 +124,7 @@ pub(crate) async fn init_git_repo(
         force.unwrap_or(false),
     )
     .await
+    .map_err(String::from)
 }
 
 #[tauri::command]
@@ -147,6 +150,7 @@ pub(crate) async fn create_github_repo(
     );
     git_ui_core::create_github_repo_core(&state.workspaces, workspace_id, repo, visibility, branch)
         .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -166,7 +170,9 @@ pub(crate) async fn stage_git_file(
         git_rpc::METHOD_STAGE_GIT_FILE,
         git_remote_params(&request)?
     );
-    git_ui_core::stage_git_file_core(&state.workspaces, workspace_id, path).await
+    git_ui_core::stage_git_file_core(&state.workspaces, workspace_id, path)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -184,7 +190,9 @@ pub(crate) async fn stage_git_all(
         git_rpc::METHOD_STAGE_GIT_ALL,
         git_remote_params(&request)?
     );
-    git_ui_core::stage_git_all_core(&state.workspaces, workspace_id).await
+    git_ui_core::stage_git_all_core(&state.workspaces, workspace_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -204,7 +212,9 @@ pub(crate) async fn unstage_git_file(
         git_rpc::METHOD_UNSTAGE_GIT_FILE,
         git_remote_params(&request)?
     );
-    git_ui_core::unstage_git_file_core(&state.workspaces, workspace_id, path).await
+    git_ui_core::unstage_git_file_core(&state.workspaces, workspace_id, path)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -224,7 +234,9 @@ pub(crate) async fn revert_git_file(
         git_rpc::METHOD_REVERT_GIT_FILE,
         git_remote_params(&request)?
     );
-    git_ui_core::revert_git_file_core(&state.workspaces, workspace_id, path).await
+    git_ui_core::revert_git_file_core(&state.workspaces, workspace_id, path)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -242,7 +254,9 @@ pub(crate) async fn revert_git_all(
         git_rpc::METHOD_REVERT_GIT_ALL,
         git_remote_params(&request)?
     );
-    git_ui_core::revert_git_all_core(&state.workspaces, workspace_id).await
+    git_ui_core::revert_git_all_core(&state.workspaces, workspace_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -262,7 +276,9 @@ pub(crate) async fn commit_git(
         git_rpc::METHOD_COMMIT_GIT,
         git_remote_params(&request)?
     );
-    git_ui_core::commit_git_core(&state.workspaces, workspace_id, message).await
+    git_ui_core::commit_git_core(&state.workspaces, workspace_id, message)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -280,7 +296,9 @@ pub(crate) async fn push_git(
         git_rpc::METHOD_PUSH_GIT,
         git_remote_params(&request)?
     );
-    git_ui_core::push_git_core(&state.workspaces, workspace_id).await
+    git_ui_core::push_git_core(&state.workspaces, workspace_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -298,7 +316,9 @@ pub(crate) async fn pull_git(
         git_rpc::METHOD_PULL_GIT,
         git_remote_params(&request)?
     );
-    git_ui_core::pull_git_core(&state.workspaces, workspace_id).await
+    git_ui_core::pull_git_core(&state.workspaces, workspace_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -316,7 +336,9 @@ pub(crate) async fn fetch_git(
         git_rpc::METHOD_FETCH_GIT,
         git_remote_params(&request)?
     );
-    git_ui_core::fetch_git_core(&state.workspaces, workspace_id).await
+    git_ui_core::fetch_git_core(&state.workspaces, workspace_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -334,7 +356,9 @@ pub(crate) async fn sync_git(
         git_rpc::METHOD_SYNC_GIT,
         git_remote_params(&request)?
     );
-    git_ui_core::sync_git_core(&state.workspaces, workspace_id).await
+    git_ui_core::sync_git_core(&state.workspaces, workspace_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -355,7 +379,9 @@ pub(crate) async fn list_git_roots(
         git_remote_params(&request)?,
         Vec<String>
     );
-    git_ui_core::list_git_roots_core(&state.workspaces, workspace_id, depth).await
+    git_ui_core::list_git_roots_core(&state.workspaces, workspace_id, depth)
+        .await
+        .map_err(String::from)
 }
 
 /// Helper function to get the combined diff for a workspace (used by commit message generation)
@@ -368,7 +394,7 @@ pub(crate) async fn get_workspace_diff(
         workspace_id.to_string(),
     )
     .await?;
-    git_ui_core::collect_workspace_diff_core(&repo_root)
+    git_ui_core::collect_workspace_diff_core(&repo_root).map_err(String::from)
 }
 
 #[tauri::command]
@@ -387,7 +413,9 @@ pub(crate) async fn get_git_diffs(
         git_remote_params(&request)?,
         Vec<GitFileDiff>
     );
-    git_ui_core::get_git_diffs_core(&state.workspaces, &state.app_settings, workspace_id).await
+    git_ui_core::get_git_diffs_core(&state.workspaces, &state.app_settings, workspace_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -408,7 +436,9 @@ pub(crate) async fn get_git_log(
         git_remote_params(&request)?,
         GitLogResponse
     );
-    git_ui_core::get_git_log_core(&state.workspaces, workspace_id, limit).await
+    git_ui_core::get_git_log_core(&state.workspaces, workspace_id, limit)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -431,6 +461,7 @@ pub(crate) async fn get_git_commit_diff(
     );
     git_ui_core::get_git_commit_diff_core(&state.workspaces, &state.app_settings, workspace_id, sha)
         .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -449,7 +480,9 @@ pub(crate) async fn get_git_remote(
         git_remote_params(&request)?,
         Option<String>
     );
-    git_ui_core::get_git_remote_core(&state.workspaces, workspace_id).await
+    git_ui_core::get_git_remote_core(&state.workspaces, workspace_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -468,7 +501,9 @@ pub(crate) async fn get_github_issues(
         git_remote_params(&request)?,
         GitHubIssuesResponse
     );
-    git_ui_core::get_github_issues_core(&state.workspaces, workspace_id).await
+    git_ui_core::get_github_issues_core(&state.workspaces, workspace_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -487,7 +522,9 @@ pub(crate) async fn get_github_pull_requests(
         git_remote_params(&request)?,
         GitHubPullRequestsResponse
     );
-    git_ui_core::get_github_pull_requests_core(&state.workspaces, workspace_id).await
+    git_ui_core::get_github_pull_requests_core(&state.workspaces, workspace_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -508,7 +545,9 @@ pub(crate) async fn get_github_pull_request_diff(
         git_remote_params(&request)?,
         Vec<GitHubPullRequestDiff>
     );
-    git_ui_core::get_github_pull_request_diff_core(&state.workspaces, workspace_id, pr_number).await
+    git_ui_core::get_github_pull_request_diff_core(&state.workspaces, workspace_id, pr_number)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -531,6 +570,7 @@ pub(crate) async fn get_github_pull_request_comments(
     );
     git_ui_core::get_github_pull_request_comments_core(&state.workspaces, workspace_id, pr_number)
         .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -550,7 +590,9 @@ pub(crate) async fn checkout_github_pull_request(
         git_rpc::METHOD_CHECKOUT_GITHUB_PULL_REQUEST,
         git_remote_params(&request)?
     );
-    git_ui_core::checkout_github_pull_request_core(&state.workspaces, workspace_id, pr_number).await
+    git_ui_core::checkout_github_pull_request_core(&state.workspaces, workspace_id, pr_number)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -568,7 +610,9 @@ pub(crate) async fn list_git_branches(
         git_rpc::METHOD_LIST_GIT_BRANCHES,
         git_remote_params(&request)?
     );
-    git_ui_core::list_git_branches_core(&state.workspaces, workspace_id).await
+    git_ui_core::list_git_branches_core(&state.workspaces, workspace_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -588,7 +632,9 @@ pub(crate) async fn checkout_git_branch(
         git_rpc::METHOD_CHECKOUT_GIT_BRANCH,
         git_remote_params(&request)?
     );
-    git_ui_core::checkout_git_branch_core(&state.workspaces, workspace_id, name).await
+    git_ui_core::checkout_git_branch_core(&state.workspaces, workspace_id, name)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -608,5 +654,7 @@ pub(crate) async fn create_git_branch(
         git_rpc::METHOD_CREATE_GIT_BRANCH,
         git_remote_params(&request)?
     );
-    git_ui_core::create_git_branch_core(&state.workspaces, workspace_id, name).await
+    git_ui_core::create_git_branch_core(&state.workspaces, workspace_id, name)
+        .await
+        .map_err(String::from)
 }
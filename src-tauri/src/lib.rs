@@ -15,6 +15,7 @@ mod files;
 mod git;
 mod git_utils;
 mod local_usage;
+mod logs;
 #[cfg(desktop)]
 mod menu;
 #[cfg(not(desktop))]
@@ -36,6 +37,7 @@ mod terminal;
 #[cfg(not(desktop))]
 #[path = "terminal_mobile.rs"]
 mod terminal;
+mod terminal_remote;
 mod types;
 mod utils;
 mod window;
@@ -114,8 +116,14 @@ pub fn run() {
             }
         })
         .setup(|app| {
-            let state = state::AppState::load(&app.handle());
+            let (session_exits_tx, session_exits_rx) = tokio::sync::mpsc::unbounded_channel();
+            let state = state::AppState::load(&app.handle(), session_exits_tx);
             app.manage(state);
+            tauri::async_runtime::spawn(codex::supervise_workspace_sessions(
+                app.handle().clone(),
+                session_exits_rx,
+            ));
+            tauri::async_runtime::spawn(codex::sweep_workspace_session_pool(app.handle().clone()));
             #[cfg(target_os = "windows")]
             {
                 if let Some(main_window) = app.get_webview_window("main") {
@@ -220,6 +228,7 @@ pub fn run() {
             codex::resume_thread,
             codex::thread_live_subscribe,
             codex::thread_live_unsubscribe,
+            codex::subscribe_events,
             codex::fork_thread,
             codex::list_threads,
             codex::list_mcp_server_status,
@@ -285,6 +294,8 @@ pub fn run() {
             terminal::terminal_write,
             terminal::terminal_resize,
             terminal::terminal_close,
+            terminal::terminal_attach,
+            terminal::terminal_list,
             dictation::dictation_model_status,
             dictation::dictation_download_model,
             dictation::dictation_cancel_download,
@@ -296,12 +307,15 @@ pub fn run() {
             dictation::dictation_stop,
             dictation::dictation_cancel,
             local_usage::local_usage_snapshot,
+            logs::daemon_logs_tail,
             notifications::is_macos_debug_build,
             notifications::app_build_type,
             notifications::send_notification_fallback,
             push_notifications::send_presence_heartbeat,
             push_notifications::push_register_device,
             push_notifications::push_unregister_device,
+            push_notifications::push_device_rules_get,
+            push_notifications::push_device_rules_patch,
             push_notifications::push_notification_config_get,
             push_notifications::push_notification_config_patch,
             push_notifications::push_notification_state,
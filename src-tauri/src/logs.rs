@@ -0,0 +1,41 @@
+use serde_json::{json, Value};
+use tauri::{AppHandle, State};
+
+use crate::remote_backend;
+use crate::shared::logging_core::{self, LogTailQuery};
+use crate::state::AppState;
+
+/// Recent backend log records. In remote mode these come from the daemon,
+/// otherwise from this app's own backend.
+#[tauri::command]
+pub(crate) async fn daemon_logs_tail(
+    limit: Option<usize>,
+    level: Option<String>,
+    target: Option<String>,
+    workspace_id: Option<String>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Value, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        return remote_backend::call_remote(
+            &*state,
+            app,
+            "daemon_logs_tail",
+            json!({
+                "limit": limit,
+                "level": level,
+                "target": target,
+                "workspaceId": workspace_id,
+            }),
+        )
+        .await;
+    }
+
+    logging_core::tail_value(&LogTailQuery {
+        limit,
+        level,
+        target,
+        workspace_id,
+    })
+    .map_err(String::from)
+}
@@ -22,7 +22,9 @@ pub(crate) async fn prompts_list(
         return serde_json::from_value(response).map_err(|err| err.to_string());
     }
 
-    prompts_core::prompts_list_core(&state.workspaces, &state.settings_path, workspace_id).await
+    prompts_core::prompts_list_core(&state.workspaces, &state.settings_path, workspace_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -44,6 +46,7 @@ pub(crate) async fn prompts_workspace_dir(
 
     prompts_core::prompts_workspace_dir_core(&state.workspaces, &state.settings_path, workspace_id)
         .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -63,7 +66,9 @@ pub(crate) async fn prompts_global_dir(
         return serde_json::from_value(response).map_err(|err| err.to_string());
     }
 
-    prompts_core::prompts_global_dir_core(&state.workspaces, workspace_id).await
+    prompts_core::prompts_global_dir_core(&state.workspaces, workspace_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -106,6 +111,7 @@ pub(crate) async fn prompts_create(
         content,
     )
     .await
+    .map_err(String::from)
 }
 
 #[tauri::command]
@@ -148,6 +154,7 @@ pub(crate) async fn prompts_update(
         content,
     )
     .await
+    .map_err(String::from)
 }
 
 #[tauri::command]
@@ -170,6 +177,7 @@ pub(crate) async fn prompts_delete(
 
     prompts_core::prompts_delete_core(&state.workspaces, &state.settings_path, workspace_id, path)
         .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -199,4 +207,5 @@ pub(crate) async fn prompts_move(
         scope,
     )
     .await
+    .map_err(String::from)
 }
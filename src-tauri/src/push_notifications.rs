@@ -82,6 +82,48 @@ pub(crate) async fn push_unregister_device(
     .await
 }
 
+#[tauri::command]
+pub(crate) async fn push_device_rules_get(
+    device_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Value, String> {
+    if !remote_backend::is_remote_mode(&*state).await {
+        return Ok(json!({
+            "workspaceIds": null,
+            "kinds": { "completed": true, "error": true, "approval": true },
+            "quietHours": null,
+            "minTurnDurationMs": null
+        }));
+    }
+    remote_backend::call_remote(
+        &*state,
+        app,
+        "push_device_rules_get",
+        json!({ "deviceId": device_id }),
+    )
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn push_device_rules_patch(
+    device_id: String,
+    rules: Value,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Value, String> {
+    if !remote_backend::is_remote_mode(&*state).await {
+        return Ok(json!({ "ok": true, "skipped": "local_mode" }));
+    }
+    remote_backend::call_remote(
+        &*state,
+        app,
+        "push_device_rules_patch",
+        json!({ "deviceId": device_id, "rules": rules }),
+    )
+    .await
+}
+
 #[tauri::command]
 pub(crate) async fn push_notification_config_get(
     state: State<'_, AppState>,
@@ -91,7 +133,9 @@ pub(crate) async fn push_notification_config_get(
         return Ok(json!({
             "relayUrl": null,
             "hasRelayAuthToken": false,
-            "registeredDeviceCount": 0
+            "registeredDeviceCount": 0,
+            "channels": { "webhook": null, "ntfy": null, "gotify": null },
+            "digestWindowMs": null
         }));
     }
     remote_backend::call_remote(&*state, app, "push_notification_config_get", json!({})).await
@@ -101,6 +145,10 @@ pub(crate) async fn push_notification_config_get(
 pub(crate) async fn push_notification_config_patch(
     relay_url: Option<String>,
     relay_auth_token: Option<String>,
+    webhook: Option<Value>,
+    ntfy: Option<Value>,
+    gotify: Option<Value>,
+    digest_window_ms: Option<u64>,
     state: State<'_, AppState>,
     app: AppHandle,
 ) -> Result<Value, String> {
@@ -114,6 +162,10 @@ pub(crate) async fn push_notification_config_patch(
         json!({
             "relayUrl": relay_url,
             "relayAuthToken": relay_auth_token,
+            "webhook": webhook,
+            "ntfy": ntfy,
+            "gotify": gotify,
+            "digestWindowMs": digest_window_ms,
         }),
     )
     .await
@@ -152,7 +204,7 @@ pub(crate) async fn mobile_push_registration_info(
         match app.mobile_push().registration_info() {
             Ok(info) => Ok(Some(info)),
             Err(err) => {
-                eprintln!("mobile push registration lookup failed: {err}");
+                tracing::warn!("mobile push registration lookup failed: {err}");
                 Ok(None)
             }
         }
@@ -0,0 +1,379 @@
+//! Workspace id namespacing for `AppSettings::aggregate_remote_backends`.
+//!
+//! With aggregation on, the app talks to every saved remote at once. Each
+//! daemon's workspace ids are shown to the frontend as
+//! `<backendId>::<workspaceId>`, which is how calls find their way back to the
+//! daemon that owns the workspace.
+
+use serde_json::Value;
+
+use crate::shared::core_error::{CoreError, ErrorCode};
+use crate::types::{AppSettings, RemoteBackendTarget};
+
+pub(crate) const WORKSPACE_ID_SEPARATOR: &str = "::";
+
+/// Request params that name a workspace, matching the daemon's scope checks.
+const WORKSPACE_PARAM_PATHS: &[&[&str]] = &[
+    &["workspaceId"],
+    &["parentId"],
+    &["sourceWorkspaceId"],
+    &["settings", "cloneSourceWorkspaceId"],
+];
+
+/// Methods whose `id` param is a workspace id rather than some other entity.
+const WORKSPACE_ID_METHODS: &[&str] = &[
+    "connect_workspace",
+    "remove_workspace",
+    "remove_worktree",
+    "rename_worktree",
+    "rename_worktree_upstream",
+    "update_workspace_settings",
+];
+
+/// Methods that return a `WorkspaceInfo`, or a list of them, keyed by `id`.
+const WORKSPACE_INFO_METHODS: &[&str] = &[
+    "list_workspaces",
+    "add_workspace",
+    "add_workspace_from_git_url",
+    "add_clone",
+    "add_worktree",
+    "rename_worktree",
+    "update_workspace_settings",
+];
+
+/// Fields of a `WorkspaceInfo` that hold workspace ids.
+const WORKSPACE_INFO_ID_PATHS: &[&[&str]] = &[
+    &["id"],
+    &["parentId"],
+    &["settings", "cloneSourceWorkspaceId"],
+];
+
+/// Fields of a daemon notification that hold workspace ids: the event's own
+/// workspace, and the one in the params of the `codex/*` events the daemon
+/// emits itself. Whatever the app-server sends is left alone.
+const EVENT_ID_PATHS: &[&[&str]] = &[
+    &["workspace_id"],
+    &["workspaceId"],
+    &["message", "params", "workspaceId"],
+];
+
+/// The saved remotes to aggregate, or `None` when only the active remote is
+/// used.
+pub(crate) struct Aggregation {
+    pub(crate) targets: Vec<RemoteBackendTarget>,
+    default_id: Option<String>,
+}
+
+impl Aggregation {
+    pub(crate) fn from_settings(settings: &AppSettings) -> Option<Self> {
+        if !settings.aggregate_remote_backends || settings.remote_backends.is_empty() {
+            return None;
+        }
+        Some(Self {
+            targets: settings.remote_backends.clone(),
+            default_id: settings.active_remote_backend_id.clone(),
+        })
+    }
+
+    /// The active remote, or the first one when none is active. Calls that
+    /// name no workspace, or only un-namespaced ones, go here.
+    pub(crate) fn primary(&self) -> &RemoteBackendTarget {
+        self.default_id
+            .as_deref()
+            .and_then(|id| self.targets.iter().find(|target| target.id == id))
+            .unwrap_or(&self.targets[0])
+    }
+
+    /// The target named by `backend_id`, or the primary one for `None`.
+    pub(crate) fn target(
+        &self,
+        backend_id: Option<&str>,
+    ) -> Result<&RemoteBackendTarget, CoreError> {
+        match backend_id {
+            Some(backend_id) => self
+                .targets
+                .iter()
+                .find(|target| target.id == backend_id)
+                .ok_or_else(CoreError::workspace_not_found),
+            None => Ok(self.primary()),
+        }
+    }
+}
+
+pub(crate) fn namespace_workspace_id(backend_id: &str, workspace_id: &str) -> String {
+    format!("{backend_id}{WORKSPACE_ID_SEPARATOR}{workspace_id}")
+}
+
+pub(crate) fn split_workspace_id(id: &str) -> Option<(&str, &str)> {
+    id.split_once(WORKSPACE_ID_SEPARATOR)
+}
+
+/// Strips the namespace from the workspace ids in `params` and returns the
+/// backend they belong to, or `None` when the call names no workspace. Ids
+/// without a namespace, such as ones saved before aggregation was turned on,
+/// belong to `primary_id`.
+pub(crate) fn route_params(
+    method: &str,
+    params: &mut Value,
+    primary_id: &str,
+) -> Result<Option<String>, CoreError> {
+    let id_path: &[&str] = &["id"];
+    let id_path = WORKSPACE_ID_METHODS.contains(&method).then_some(id_path);
+    let mut backend: Option<String> = None;
+    for path in WORKSPACE_PARAM_PATHS.iter().copied().chain(id_path) {
+        let Some(Value::String(id)) = value_at_mut(params, path) else {
+            continue;
+        };
+        let backend_id = match split_workspace_id(id) {
+            Some((backend_id, workspace_id)) => {
+                let backend_id = backend_id.to_string();
+                *id = workspace_id.to_string();
+                backend_id
+            }
+            None => primary_id.to_string(),
+        };
+        match backend.as_deref() {
+            Some(previous) if previous != backend_id => {
+                return Err(CoreError::new(
+                    ErrorCode::InvalidParams,
+                    format!("invalid params: `{method}` names workspaces on different remotes"),
+                ));
+            }
+            Some(_) => {}
+            None => backend = Some(backend_id),
+        }
+    }
+    Ok(backend)
+}
+
+/// The part of a `subscribe_events` filter that applies to `backend_id`: its
+/// `workspaceIds` keep only the ones on that remote, without the namespace.
+/// A remote left with none receives no workspace events at all.
+pub(crate) fn subscription_for_target(filter: &Value, backend_id: &str, primary_id: &str) -> Value {
+    let mut filter = filter.clone();
+    if let Some(Value::Array(ids)) = filter.get_mut("workspaceIds") {
+        ids.retain_mut(|id| {
+            let Some(raw) = id.as_str() else {
+                return true;
+            };
+            let (owner, workspace_id) = split_workspace_id(raw).unwrap_or((primary_id, raw));
+            if owner != backend_id {
+                return false;
+            }
+            *id = Value::String(workspace_id.to_string());
+            true
+        });
+    }
+    filter
+}
+
+/// Namespaces the workspace ids in a daemon's response to `method`.
+pub(crate) fn namespace_result(method: &str, backend_id: &str, mut result: Value) -> Value {
+    if WORKSPACE_INFO_METHODS.contains(&method) {
+        match &mut result {
+            Value::Array(entries) => {
+                for entry in entries {
+                    namespace_paths(backend_id, entry, WORKSPACE_INFO_ID_PATHS);
+                }
+            }
+            entry => namespace_paths(backend_id, entry, WORKSPACE_INFO_ID_PATHS),
+        }
+    }
+    result
+}
+
+/// Namespaces the workspace ids in a notification's params, in place.
+pub(crate) fn namespace_event(backend_id: &str, params: &mut Value) {
+    namespace_paths(backend_id, params, EVENT_ID_PATHS);
+}
+
+fn namespace_paths(backend_id: &str, value: &mut Value, paths: &[&[&str]]) {
+    for path in paths {
+        if let Some(Value::String(id)) = value_at_mut(value, path) {
+            *id = namespace_workspace_id(backend_id, id);
+        }
+    }
+}
+
+fn value_at_mut<'a>(value: &'a mut Value, path: &[&str]) -> Option<&'a mut Value> {
+    path.iter()
+        .try_fold(value, |value, key| value.get_mut(*key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        namespace_event, namespace_result, route_params, subscription_for_target, Aggregation,
+    };
+    use crate::shared::core_error::ErrorCode;
+    use crate::types::{AppSettings, RemoteBackendProvider, RemoteBackendTarget};
+    use serde_json::json;
+
+    fn target(id: &str) -> RemoteBackendTarget {
+        RemoteBackendTarget {
+            id: id.to_string(),
+            name: id.to_string(),
+            provider: RemoteBackendProvider::Tcp,
+            host: format!("{id}.example:4732"),
+            token: None,
+            last_connected_at_ms: None,
+            tls: false,
+            tls_cert_fingerprint: None,
+        }
+    }
+
+    #[test]
+    fn aggregation_routes_unscoped_calls_to_the_active_remote() {
+        let mut settings = AppSettings {
+            remote_backends: vec![target("box-a"), target("box-b")],
+            active_remote_backend_id: Some("box-b".to_string()),
+            ..AppSettings::default()
+        };
+        assert!(Aggregation::from_settings(&settings).is_none());
+
+        settings.aggregate_remote_backends = true;
+        let aggregation = Aggregation::from_settings(&settings).expect("aggregation");
+        assert_eq!(aggregation.target(None).expect("default").id, "box-b");
+        assert_eq!(
+            aggregation.target(Some("box-a")).expect("named").id,
+            "box-a"
+        );
+        assert_eq!(
+            aggregation
+                .target(Some("box-c"))
+                .map_err(|err| err.code)
+                .err(),
+            Some(ErrorCode::WorkspaceNotFound)
+        );
+
+        settings.active_remote_backend_id = None;
+        let aggregation = Aggregation::from_settings(&settings).expect("aggregation");
+        assert_eq!(aggregation.target(None).expect("default").id, "box-a");
+    }
+
+    #[test]
+    fn route_params_strips_the_backend_namespace() {
+        let mut params = json!({ "workspaceId": "box-a::ws-1", "threadId": "thread-1" });
+        let backend = route_params("send_user_message", &mut params, "box-p").expect("routed");
+        assert_eq!(backend.as_deref(), Some("box-a"));
+        assert_eq!(
+            params,
+            json!({ "workspaceId": "ws-1", "threadId": "thread-1" })
+        );
+
+        let mut params = json!({ "id": "box-b::ws-2" });
+        let backend = route_params("remove_workspace", &mut params, "box-p").expect("routed");
+        assert_eq!(backend.as_deref(), Some("box-b"));
+        assert_eq!(params, json!({ "id": "ws-2" }));
+
+        let mut params = json!({ "path": "/srv/repo" });
+        assert_eq!(
+            route_params("add_workspace", &mut params, "box-p").expect("routed"),
+            None
+        );
+        let mut params = json!({ "id": "prompt-1" });
+        assert_eq!(
+            route_params("prompts_delete", &mut params, "box-p").expect("routed"),
+            None
+        );
+
+        let mut params = json!({ "workspaceId": "ws-1" });
+        let backend = route_params("list_threads", &mut params, "box-p").expect("routed");
+        assert_eq!(backend.as_deref(), Some("box-p"));
+        assert_eq!(params, json!({ "workspaceId": "ws-1" }));
+        let mut params = json!({ "workspaceId": "box-p::ws-1", "parentId": "ws-2" });
+        let backend = route_params("add_worktree", &mut params, "box-p").expect("routed");
+        assert_eq!(backend.as_deref(), Some("box-p"));
+
+        let mut params = json!({
+            "id": "box-a::ws-3",
+            "settings": { "cloneSourceWorkspaceId": "box-a::ws-1" },
+        });
+        let backend =
+            route_params("update_workspace_settings", &mut params, "box-p").expect("routed");
+        assert_eq!(backend.as_deref(), Some("box-a"));
+        assert_eq!(params["settings"]["cloneSourceWorkspaceId"], "ws-1");
+
+        let mut params = json!({ "workspaceId": "box-a::ws-1", "parentId": "box-b::ws-2" });
+        let err = route_params("add_worktree", &mut params, "box-p").expect_err("two remotes");
+        assert_eq!(err.code, ErrorCode::InvalidParams);
+        let mut params = json!({ "workspaceId": "box-a::ws-1", "parentId": "ws-2" });
+        let err = route_params("add_worktree", &mut params, "box-p").expect_err("two remotes");
+        assert_eq!(err.code, ErrorCode::InvalidParams);
+    }
+
+    #[test]
+    fn responses_and_events_are_namespaced() {
+        let workspaces = json!([
+            { "id": "ws-1", "name": "api", "parentId": null, "settings": {} },
+            {
+                "id": "ws-2",
+                "name": "api-wt",
+                "parentId": "ws-1",
+                "settings": { "cloneSourceWorkspaceId": "ws-1" },
+            },
+        ]);
+        assert_eq!(
+            namespace_result("list_workspaces", "box-a", workspaces),
+            json!([
+                { "id": "box-a::ws-1", "name": "api", "parentId": null, "settings": {} },
+                {
+                    "id": "box-a::ws-2",
+                    "name": "api-wt",
+                    "parentId": "box-a::ws-1",
+                    "settings": { "cloneSourceWorkspaceId": "box-a::ws-1" },
+                },
+            ])
+        );
+        assert_eq!(
+            namespace_result(
+                "list_threads",
+                "box-a",
+                json!({ "data": [{ "id": "thread-1" }] })
+            ),
+            json!({ "data": [{ "id": "thread-1" }] })
+        );
+
+        let mut event = json!({
+            "workspace_id": "ws-1",
+            "message": { "method": "codex/connected", "params": { "workspaceId": "ws-1" } },
+        });
+        namespace_event("box-a", &mut event);
+        assert_eq!(event["workspace_id"], "box-a::ws-1");
+        assert_eq!(event["message"]["params"]["workspaceId"], "box-a::ws-1");
+
+        // Ids the app-server reports, such as a thread's parent, stay as they are.
+        let mut event = json!({
+            "workspace_id": "ws-1",
+            "message": {
+                "method": "thread/started",
+                "params": { "thread": { "id": "thread-2", "parentId": "thread-1" } },
+            },
+        });
+        namespace_event("box-a", &mut event);
+        assert_eq!(event["message"]["params"]["thread"]["parentId"], "thread-1");
+        let mut event = json!({ "workspaceId": "ws-1", "terminalId": "t-1", "data": "ls" });
+        namespace_event("box-a", &mut event);
+        assert_eq!(event["workspaceId"], "box-a::ws-1");
+    }
+
+    #[test]
+    fn subscription_keeps_each_remotes_own_workspaces() {
+        let filter = json!({
+            "workspaceIds": ["box-a::ws-1", "box-b::ws-2", "ws-3"],
+            "methods": ["turn/*"],
+        });
+        assert_eq!(
+            subscription_for_target(&filter, "box-a", "box-p"),
+            json!({ "workspaceIds": ["ws-1"], "methods": ["turn/*"] })
+        );
+        assert_eq!(
+            subscription_for_target(&filter, "box-p", "box-p"),
+            json!({ "workspaceIds": ["ws-3"], "methods": ["turn/*"] })
+        );
+        assert_eq!(
+            subscription_for_target(&json!({ "threadIds": ["t-1"] }), "box-a", "box-p"),
+            json!({ "threadIds": ["t-1"] })
+        );
+    }
+}
@@ -0,0 +1,199 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde_json::{json, Map, Value};
+
+/// Position in the daemon's event stream, kept across reconnects so a new
+/// connection can ask the daemon to replay what was missed.
+#[derive(Default)]
+pub(crate) struct RemoteEventCursor {
+    stream_id: std::sync::Mutex<Option<String>>,
+    last_seq: AtomicU64,
+    /// Backend id the stream's workspace ids are namespaced with when several
+    /// daemons are aggregated.
+    workspace_namespace: Option<String>,
+    /// Last `subscribe_events` filter, re-sent with `auth` on every reconnect.
+    subscription: std::sync::Mutex<Option<Value>>,
+}
+
+/// Cursor state captured when a connection starts authenticating.
+pub(crate) struct ResumePoint {
+    stream_id: Option<String>,
+    last_seq: u64,
+    subscription: Option<Value>,
+}
+
+impl RemoteEventCursor {
+    pub(crate) fn namespaced(backend_id: &str) -> Self {
+        Self {
+            workspace_namespace: Some(backend_id.to_string()),
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn workspace_namespace(&self) -> Option<&str> {
+        self.workspace_namespace.as_deref()
+    }
+
+    pub(crate) fn observe(&self, seq: u64) {
+        self.last_seq.fetch_max(seq, Ordering::SeqCst);
+    }
+
+    /// Takes the current position and resets the live counter, so sequence
+    /// numbers seen from here on belong to the new connection.
+    pub(crate) fn begin_connection(&self) -> ResumePoint {
+        ResumePoint {
+            stream_id: self.lock_stream_id().clone(),
+            last_seq: self.last_seq.swap(0, Ordering::SeqCst),
+            subscription: self.lock_subscription().clone(),
+        }
+    }
+
+    pub(crate) fn set_subscription(&self, filter: Value) {
+        *self.lock_subscription() = Some(filter);
+    }
+
+    /// Adds `resumeFromSeq`/`streamId` to the auth params when there is a
+    /// previous position to resume from, and the event filter so the daemon
+    /// applies it before replaying. Daemons without a token take `auth`
+    /// without one, only to resume.
+    pub(crate) fn auth_params(resume: &ResumePoint, token: Option<&str>) -> Value {
+        let mut params = Map::new();
+        if let Some(token) = token {
+            params.insert("token".to_string(), json!(token));
+        }
+        if let Some(stream_id) = resume.stream_id.as_deref() {
+            params.insert("resumeFromSeq".to_string(), json!(resume.last_seq));
+            params.insert("streamId".to_string(), json!(stream_id));
+        }
+        if let Some(subscription) = &resume.subscription {
+            params.insert("subscription".to_string(), subscription.clone());
+        }
+        Value::Object(params)
+    }
+
+    /// Applies the daemon's auth response. When the daemon did not resume
+    /// (first connection or a restarted daemon), the cursor starts at its
+    /// current head; otherwise replayed events advance it as they arrive.
+    pub(crate) fn finish_auth(&self, resume: ResumePoint, response: &Value) {
+        let Some(stream_id) = response.get("streamId").and_then(Value::as_str) else {
+            self.abort_connection(resume);
+            return;
+        };
+        let resumed = response
+            .get("resumed")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        let head = response.get("lastSeq").and_then(Value::as_u64).unwrap_or(0);
+        *self.lock_stream_id() = Some(stream_id.to_string());
+        self.observe(if resumed { resume.last_seq } else { head });
+        if resumed
+            && response
+                .get("replayTruncated")
+                .and_then(Value::as_bool)
+                .unwrap_or(false)
+        {
+            tracing::warn!("some events were dropped before they could be replayed");
+        }
+    }
+
+    /// Restores the previous position after a connection failed to authenticate.
+    pub(crate) fn abort_connection(&self, resume: ResumePoint) {
+        self.observe(resume.last_seq);
+    }
+
+    fn lock_stream_id(&self) -> std::sync::MutexGuard<'_, Option<String>> {
+        self.stream_id
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn lock_subscription(&self) -> std::sync::MutexGuard<'_, Option<Value>> {
+        self.subscription
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RemoteEventCursor;
+    use serde_json::json;
+
+    #[test]
+    fn resumes_on_the_same_stream() {
+        let cursor = RemoteEventCursor::default();
+        let first = cursor.begin_connection();
+        assert_eq!(
+            RemoteEventCursor::auth_params(&first, Some("t")),
+            json!({ "token": "t" })
+        );
+        cursor.finish_auth(
+            first,
+            &json!({ "streamId": "s1", "lastSeq": 10, "resumed": false }),
+        );
+        cursor.observe(12);
+
+        let second = cursor.begin_connection();
+        assert_eq!(
+            RemoteEventCursor::auth_params(&second, Some("t")),
+            json!({ "token": "t", "resumeFromSeq": 12, "streamId": "s1" })
+        );
+        assert_eq!(
+            RemoteEventCursor::auth_params(&second, None),
+            json!({ "resumeFromSeq": 12, "streamId": "s1" })
+        );
+        // A replayed event can arrive before the auth response is handled.
+        cursor.observe(13);
+        cursor.finish_auth(
+            second,
+            &json!({ "streamId": "s1", "lastSeq": 15, "resumed": true }),
+        );
+        assert_eq!(cursor.begin_connection().last_seq, 13);
+    }
+
+    #[test]
+    fn restarted_daemon_starts_from_its_head() {
+        let cursor = RemoteEventCursor::default();
+        let first = cursor.begin_connection();
+        cursor.finish_auth(first, &json!({ "streamId": "s1", "lastSeq": 40 }));
+
+        let second = cursor.begin_connection();
+        cursor.finish_auth(
+            second,
+            &json!({ "streamId": "s2", "lastSeq": 3, "resumed": false }),
+        );
+        let third = cursor.begin_connection();
+        assert_eq!(third.stream_id.as_deref(), Some("s2"));
+        assert_eq!(third.last_seq, 3);
+    }
+
+    #[test]
+    fn failed_auth_keeps_previous_position() {
+        let cursor = RemoteEventCursor::default();
+        let first = cursor.begin_connection();
+        cursor.finish_auth(first, &json!({ "streamId": "s1", "lastSeq": 7 }));
+
+        let second = cursor.begin_connection();
+        cursor.abort_connection(second);
+        assert_eq!(cursor.begin_connection().last_seq, 7);
+    }
+
+    #[test]
+    fn reconnects_send_the_last_subscription() {
+        let cursor = RemoteEventCursor::default();
+        let first = cursor.begin_connection();
+        cursor.finish_auth(first, &json!({ "streamId": "s1", "lastSeq": 5 }));
+        cursor.set_subscription(json!({ "workspaceIds": ["ws-1"] }));
+
+        let second = cursor.begin_connection();
+        assert_eq!(
+            RemoteEventCursor::auth_params(&second, Some("t")),
+            json!({
+                "token": "t",
+                "resumeFromSeq": 5,
+                "streamId": "s1",
+                "subscription": { "workspaceIds": ["ws-1"] },
+            })
+        );
+    }
+}
@@ -1,7 +1,12 @@
+mod aggregate;
+mod event_cursor;
 mod protocol;
 mod tcp_transport;
+mod tls_transport;
 mod transport;
+mod websocket_transport;
 
+use futures_util::future::join_all;
 use serde_json::{json, Value};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
@@ -11,14 +16,23 @@ use tauri::AppHandle;
 use tokio::sync::Mutex;
 use tokio::time::timeout;
 
+use crate::backend::app_server::RequestTimeouts;
+use crate::shared::core_error::{CoreError, ErrorCode};
+use crate::shared::rpc_schema::{self, can_retry_after_disconnect, PROTOCOL_VERSION};
+use crate::shared::wire_compression_core::WireCompression;
 use crate::state::AppState;
-use crate::types::BackendMode;
+use crate::types::{BackendMode, RemoteBackendTarget};
 
-use self::protocol::{build_request_line, DEFAULT_REMOTE_HOST, DISCONNECTED_MESSAGE};
+use self::aggregate::Aggregation;
+pub(crate) use self::event_cursor::RemoteEventCursor;
+use self::protocol::{DaemonProtocol, DEFAULT_REMOTE_HOST};
 use self::tcp_transport::TcpTransport;
-use self::transport::{PendingMap, RemoteTransport, RemoteTransportConfig, RemoteTransportKind};
+use self::tls_transport::TlsTransport;
+use self::transport::{
+    OutboundMessage, PendingMap, RemoteTransport, RemoteTransportConfig, RemoteTransportKind,
+};
+use self::websocket_transport::WebSocketTransport;
 
-const REMOTE_REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
 const REMOTE_SEND_TIMEOUT: Duration = Duration::from_secs(15);
 
 pub(crate) fn normalize_path_for_remote(path: String) -> String {
@@ -61,49 +75,123 @@ pub(crate) struct RemoteBackend {
 }
 
 struct RemoteBackendInner {
-    out_tx: tokio::sync::mpsc::Sender<String>,
+    out_tx: tokio::sync::mpsc::Sender<OutboundMessage>,
     pending: Arc<Mutex<PendingMap>>,
     next_id: AtomicU64,
     connected: Arc<std::sync::atomic::AtomicBool>,
+    protocol: std::sync::OnceLock<DaemonProtocol>,
+    request_timeouts: RequestTimeouts,
 }
 
 impl RemoteBackend {
-    pub(crate) async fn call(&self, method: &str, params: Value) -> Result<Value, String> {
-        if !self.inner.connected.load(Ordering::SeqCst) {
-            return Err(DISCONNECTED_MESSAGE.to_string());
+    pub(crate) async fn call(&self, method: &str, params: Value) -> Result<Value, CoreError> {
+        let protocol = self.inner.protocol.get();
+        if let Some(protocol) = protocol {
+            protocol.check_method(method)?;
+        }
+        let id = self.inner.next_id.fetch_add(1, Ordering::SeqCst);
+        let message = rpc_schema::request_line(id, method, params)?;
+        let result = self
+            .send_request(
+                id,
+                OutboundMessage::Json(message),
+                self.inner.request_timeouts.timeout_for(method),
+            )
+            .await;
+        match protocol {
+            Some(protocol) => result.map_err(|err| protocol.annotate_error(err)),
+            None => result,
         }
+    }
 
+    /// Switches the connection to compressed framing. Everything sent after the
+    /// `protocol_upgrade` request, and received after its response, is compressed.
+    async fn enable_compression(&self, compression: WireCompression) -> Result<(), CoreError> {
         let id = self.inner.next_id.fetch_add(1, Ordering::SeqCst);
+        let line = rpc_schema::request_line(
+            id,
+            "protocol_upgrade",
+            json!({ "compression": compression.as_str() }),
+        )?;
+        let message = OutboundMessage::Upgrade {
+            line,
+            id,
+            compression,
+        };
+        let request_timeout = self.inner.request_timeouts.timeout_for("protocol_upgrade");
+        self.send_request(id, message, request_timeout)
+            .await
+            .map(|_| ())
+    }
+
+    async fn send_request(
+        &self,
+        id: u64,
+        message: OutboundMessage,
+        request_timeout: Duration,
+    ) -> Result<Value, CoreError> {
+        if !self.inner.connected.load(Ordering::SeqCst) {
+            return Err(CoreError::disconnected());
+        }
+
         let (tx, rx) = tokio::sync::oneshot::channel();
         self.inner.pending.lock().await.insert(id, tx);
+        // Every early return, timeout or dropped caller removes the entry.
+        let mut guard = PendingCallGuard {
+            pending: Arc::clone(&self.inner.pending),
+            id,
+            armed: true,
+        };
 
-        let message = build_request_line(id, method, params)?;
         match timeout(REMOTE_SEND_TIMEOUT, self.inner.out_tx.send(message)).await {
             Ok(Ok(())) => {}
-            Ok(Err(_)) => {
-                self.inner.pending.lock().await.remove(&id);
-                return Err(DISCONNECTED_MESSAGE.to_string());
-            }
+            Ok(Err(_)) => return Err(CoreError::disconnected()),
             Err(_) => {
-                self.inner.pending.lock().await.remove(&id);
-                return Err(format!(
+                return Err(CoreError::timeout(format!(
                     "remote backend request dispatch timed out after {} seconds",
                     REMOTE_SEND_TIMEOUT.as_secs()
-                ));
+                )));
             }
         }
 
-        match timeout(REMOTE_REQUEST_TIMEOUT, rx).await {
-            Ok(Ok(result)) => result,
-            Ok(Err(_)) => Err(DISCONNECTED_MESSAGE.to_string()),
-            Err(_) => {
-                self.inner.pending.lock().await.remove(&id);
-                Err(format!(
-                    "remote backend request timed out after {} seconds",
-                    REMOTE_REQUEST_TIMEOUT.as_secs()
-                ))
+        match timeout(request_timeout, rx).await {
+            Ok(Ok(result)) => {
+                guard.armed = false;
+                result
             }
+            Ok(Err(_)) => Err(CoreError::disconnected()),
+            Err(_) => Err(CoreError::timeout(format!(
+                "remote backend request timed out after {} seconds",
+                request_timeout.as_secs()
+            ))),
+        }
+    }
+}
+
+/// Removes a call's `pending` entry unless its response was delivered.
+struct PendingCallGuard {
+    pending: Arc<Mutex<PendingMap>>,
+    id: u64,
+    armed: bool,
+}
+
+impl Drop for PendingCallGuard {
+    fn drop(&mut self) {
+        if !self.armed {
+            return;
         }
+        if let Ok(mut pending) = self.pending.try_lock() {
+            pending.remove(&self.id);
+            return;
+        }
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let pending = Arc::clone(&self.pending);
+        let id = self.id;
+        handle.spawn(async move {
+            pending.lock().await.remove(&id);
+        });
     }
 }
 
@@ -112,105 +200,207 @@ pub(crate) async fn is_remote_mode(state: &AppState) -> bool {
     matches!(settings.backend_mode, BackendMode::Remote)
 }
 
+/// Which daemon a call goes to.
+enum BackendRoute {
+    /// The active remote, configured through `remote_backend_host`.
+    Active,
+    /// One of the remotes aggregated by `aggregate_remote_backends`.
+    Target(RemoteBackendTarget),
+}
+
+impl BackendRoute {
+    /// Key into `AppState::remote_backends` and `remote_event_cursors`.
+    fn key(&self) -> Option<String> {
+        match self {
+            BackendRoute::Active => None,
+            BackendRoute::Target(target) => Some(target.id.clone()),
+        }
+    }
+}
+
+/// Sends `method` to the daemon that owns the workspace it names. With
+/// aggregation off that is always the active remote; with it on, workspace
+/// ids carry their backend id and are translated in both directions.
 pub(crate) async fn call_remote(
     state: &AppState,
     app: AppHandle,
     method: &str,
+    mut params: Value,
+) -> Result<Value, String> {
+    let aggregation = Aggregation::from_settings(&*state.app_settings.lock().await);
+    let Some(aggregation) = aggregation else {
+        return call_backend(state, app, &BackendRoute::Active, method, params).await;
+    };
+    if method == "list_workspaces" {
+        return list_aggregated_workspaces(state, app, &aggregation.targets, params).await;
+    }
+    let backend_id = aggregate::route_params(method, &mut params, &aggregation.primary().id)?;
+    let target = aggregation.target(backend_id.as_deref())?.clone();
+    let route = BackendRoute::Target(target.clone());
+    let result = call_backend(state, app, &route, method, params).await?;
+    Ok(aggregate::namespace_result(method, &target.id, result))
+}
+
+/// Sets the `subscribe_events` filter for the remote event stream. The filter
+/// is kept on each remote's event cursor so a reconnect sends it with `auth`
+/// and the daemon never replays events it would filter out. With aggregation
+/// each remote gets the workspaces that live on it; remotes that cannot be
+/// reached pick the filter up when they reconnect.
+pub(crate) async fn subscribe_remote_events(
+    state: &AppState,
+    app: AppHandle,
+    filter: Value,
+) -> Result<Value, String> {
+    let aggregation = Aggregation::from_settings(&*state.app_settings.lock().await);
+    let Some(aggregation) = aggregation else {
+        let route = BackendRoute::Active;
+        remote_event_cursor(state, &route).set_subscription(filter.clone());
+        return call_backend(state, app, &route, "subscribe_events", filter).await;
+    };
+    let primary_id = aggregation.primary().id.clone();
+    for target in &aggregation.targets {
+        let route = BackendRoute::Target(target.clone());
+        let target_filter = aggregate::subscription_for_target(&filter, &target.id, &primary_id);
+        remote_event_cursor(state, &route).set_subscription(target_filter.clone());
+        if let Err(err) = call_backend(
+            state,
+            app.clone(),
+            &route,
+            "subscribe_events",
+            target_filter,
+        )
+        .await
+        {
+            tracing::warn!("remote backend {} is unavailable: {err}", target.name);
+        }
+    }
+    Ok(filter)
+}
+
+/// Merges `list_workspaces` from every aggregated remote. Remotes that cannot
+/// be reached are logged and left out so one offline machine does not hide
+/// the others; the call only fails when none answer.
+async fn list_aggregated_workspaces(
+    state: &AppState,
+    app: AppHandle,
+    targets: &[RemoteBackendTarget],
     params: Value,
 ) -> Result<Value, String> {
-    let client = ensure_remote_backend(state, app.clone()).await?;
+    let results = join_all(targets.iter().map(|target| {
+        let route = BackendRoute::Target(target.clone());
+        let app = app.clone();
+        let params = params.clone();
+        async move { call_backend(state, app, &route, "list_workspaces", params).await }
+    }))
+    .await;
+
+    let mut workspaces = Vec::new();
+    let mut last_error = None;
+    let mut answered = 0;
+    for (target, result) in targets.iter().zip(results) {
+        match result {
+            Ok(value) => {
+                answered += 1;
+                if let Value::Array(entries) =
+                    aggregate::namespace_result("list_workspaces", &target.id, value)
+                {
+                    workspaces.extend(entries);
+                }
+            }
+            Err(err) => {
+                tracing::warn!("remote backend {} is unavailable: {err}", target.name);
+                last_error = Some(err);
+            }
+        }
+    }
+    match last_error {
+        Some(err) if answered == 0 => Err(err),
+        _ => Ok(Value::Array(workspaces)),
+    }
+}
+
+async fn call_backend(
+    state: &AppState,
+    app: AppHandle,
+    route: &BackendRoute,
+    method: &str,
+    params: Value,
+) -> Result<Value, String> {
+    let client = ensure_remote_backend(state, app.clone(), route).await?;
     match client.call(method, params.clone()).await {
         Ok(value) => Ok(value),
-        Err(err) if err == DISCONNECTED_MESSAGE => {
-            *state.remote_backend.lock().await = None;
+        Err(err) if err.code == ErrorCode::Disconnected => {
+            forget_remote_backend(state, route).await;
             if !can_retry_after_disconnect(method) {
-                return Err(err);
+                return Err(err.into());
             }
-            let retry_client = ensure_remote_backend(state, app).await?;
+            let retry_client = ensure_remote_backend(state, app, route).await?;
             match retry_client.call(method, params).await {
                 Ok(value) => Ok(value),
                 Err(retry_err) => {
-                    *state.remote_backend.lock().await = None;
-                    Err(retry_err)
+                    forget_remote_backend(state, route).await;
+                    Err(retry_err.into())
                 }
             }
         }
         Err(err) => {
-            *state.remote_backend.lock().await = None;
-            Err(err)
+            forget_remote_backend(state, route).await;
+            Err(err.into())
         }
     }
 }
 
-fn can_retry_after_disconnect(method: &str) -> bool {
-    matches!(
-        method,
-        "account_rate_limits"
-            | "account_read"
-            | "apps_list"
-            | "collaboration_mode_list"
-            | "connect_workspace"
-            | "dictation_auth_status"
-            | "dictation_transcribe"
-            | "experimental_feature_list"
-            | "set_workspace_runtime_codex_args"
-            | "file_read"
-            | "get_agents_settings"
-            | "get_config_model"
-            | "get_git_commit_diff"
-            | "get_git_diffs"
-            | "get_git_log"
-            | "get_git_remote"
-            | "get_git_status"
-            | "get_github_issues"
-            | "get_github_pull_request_comments"
-            | "get_github_pull_request_diff"
-            | "get_github_pull_requests"
-            | "is_workspace_path_dir"
-            | "list_git_branches"
-            | "list_git_roots"
-            | "list_mcp_server_status"
-            | "list_threads"
-            | "local_usage_snapshot"
-            | "list_workspace_files"
-            | "list_workspaces"
-            | "model_list"
-            | "presence_heartbeat"
-            | "push_notification_config_get"
-            | "push_notification_config_patch"
-            | "push_notification_state"
-            | "push_register_device"
-            | "push_unregister_device"
-            | "read_image_as_data_url"
-            | "read_agent_config_toml"
-            | "read_workspace_file"
-            | "resume_thread"
-            | "thread_live_subscribe"
-            | "thread_live_unsubscribe"
-            | "skills_list"
-            | "worktree_setup_status"
-    )
+async fn forget_remote_backend(state: &AppState, route: &BackendRoute) {
+    state.remote_backends.lock().await.remove(&route.key());
 }
 
-async fn ensure_remote_backend(state: &AppState, app: AppHandle) -> Result<RemoteBackend, String> {
+/// The event cursor for `route`, kept across reconnects. Aggregated remotes
+/// namespace the workspace ids in their events.
+fn remote_event_cursor(state: &AppState, route: &BackendRoute) -> Arc<RemoteEventCursor> {
+    let mut cursors = state
+        .remote_event_cursors
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let cursor = cursors.entry(route.key()).or_insert_with(|| {
+        Arc::new(match route {
+            BackendRoute::Active => RemoteEventCursor::default(),
+            BackendRoute::Target(target) => RemoteEventCursor::namespaced(&target.id),
+        })
+    });
+    Arc::clone(cursor)
+}
+
+async fn ensure_remote_backend(
+    state: &AppState,
+    app: AppHandle,
+    route: &BackendRoute,
+) -> Result<RemoteBackend, String> {
     {
-        let guard = state.remote_backend.lock().await;
-        if let Some(client) = guard.as_ref() {
+        let guard = state.remote_backends.lock().await;
+        if let Some(client) = guard.get(&route.key()) {
             return Ok(client.clone());
         }
     }
 
-    let transport_config = {
-        let settings = state.app_settings.lock().await;
-        resolve_transport_config(&settings)?
+    let transport_config = match route {
+        BackendRoute::Active => {
+            let settings = state.app_settings.lock().await;
+            resolve_transport_config(&settings)?
+        }
+        BackendRoute::Target(target) => resolve_target_transport_config(target),
     };
     let transport_kind = transport_config.kind();
     let auth_token = transport_config.auth_token().map(|value| value.to_string());
 
     let transport: Box<dyn RemoteTransport> = match transport_config.kind() {
         RemoteTransportKind::Tcp => Box::new(TcpTransport),
+        RemoteTransportKind::Tls => Box::new(TlsTransport),
+        RemoteTransportKind::WebSocket => Box::new(WebSocketTransport),
     };
-    let connection = transport.connect(app, transport_config).await?;
+    let event_cursor = remote_event_cursor(state, route);
+    let connection = transport
+        .connect(app, transport_config, Arc::clone(&event_cursor))
+        .await?;
 
     let client = RemoteBackend {
         inner: Arc::new(RemoteBackendInner {
@@ -218,45 +408,130 @@ async fn ensure_remote_backend(state: &AppState, app: AppHandle) -> Result<Remot
             pending: connection.pending,
             next_id: AtomicU64::new(1),
             connected: connection.connected,
+            protocol: std::sync::OnceLock::new(),
+            request_timeouts: state.request_timeouts.clone(),
         }),
     };
 
-    if matches!(transport_kind, RemoteTransportKind::Tcp) {
-        if let Some(token) = auth_token {
-            client
-                .call("auth", json!({ "token": token }))
-                .await
-                .map(|_| ())?;
+    if matches!(
+        transport_kind,
+        RemoteTransportKind::Tcp | RemoteTransportKind::Tls | RemoteTransportKind::WebSocket
+    ) {
+        let resume = event_cursor.begin_connection();
+        let params = RemoteEventCursor::auth_params(&resume, auth_token.as_deref());
+        match client.call("auth", params).await {
+            Ok(response) => event_cursor.finish_auth(resume, &response),
+            // Older daemons reject `auth` when they need no token; the
+            // connection still works, it just cannot resume.
+            Err(_) if auth_token.is_none() => event_cursor.abort_connection(resume),
+            Err(err) => {
+                event_cursor.abort_connection(resume);
+                return Err(err.into());
+            }
         }
     }
 
+    negotiate_protocol(&client).await?;
+
     {
-        let mut guard = state.remote_backend.lock().await;
-        *guard = Some(client.clone());
+        let mut guard = state.remote_backends.lock().await;
+        guard.insert(route.key(), client.clone());
     }
 
     Ok(client)
 }
 
+/// Records the daemon's protocol version and enables compressed framing when
+/// the daemon advertises an algorithm we support. On a version mismatch the
+/// daemon's method catalog is fetched so unsupported calls fail up front.
+/// Daemons without `compression` in `daemon_info` stay on JSON lines.
+async fn negotiate_protocol(client: &RemoteBackend) -> Result<(), String> {
+    let Ok(info) = client.call("daemon_info", json!({})).await else {
+        return Ok(());
+    };
+    let version = DaemonProtocol::version_from_daemon_info(&info);
+    let methods = if version == PROTOCOL_VERSION {
+        None
+    } else {
+        client
+            .call(rpc_schema::METHOD_RPC_DESCRIBE, json!({}))
+            .await
+            .ok()
+            .map(|catalog| rpc_schema::described_method_names(&catalog))
+    };
+    let _ = client
+        .inner
+        .protocol
+        .set(DaemonProtocol { version, methods });
+    match info.get("compression").and_then(WireCompression::negotiate) {
+        Some(compression) => client
+            .enable_compression(compression)
+            .await
+            .map_err(String::from),
+        None => Ok(()),
+    }
+}
+
 fn resolve_transport_config(
     settings: &crate::types::AppSettings,
 ) -> Result<RemoteTransportConfig, String> {
-    let host = if settings.remote_backend_host.trim().is_empty() {
+    Ok(transport_config(
+        &settings.remote_backend_host,
+        settings.remote_backend_token.clone(),
+        settings.active_remote_backend(),
+    ))
+}
+
+fn resolve_target_transport_config(target: &RemoteBackendTarget) -> RemoteTransportConfig {
+    transport_config(&target.host, target.token.clone(), Some(target))
+}
+
+/// `security` supplies the TLS flag and pinned fingerprint.
+fn transport_config(
+    host: &str,
+    auth_token: Option<String>,
+    security: Option<&RemoteBackendTarget>,
+) -> RemoteTransportConfig {
+    let host = if host.trim().is_empty() {
         DEFAULT_REMOTE_HOST.to_string()
     } else {
-        settings.remote_backend_host.clone()
+        host.to_string()
     };
-    Ok(RemoteTransportConfig::Tcp {
-        host,
-        auth_token: settings.remote_backend_token.clone(),
-    })
+    if is_websocket_url(&host) {
+        return RemoteTransportConfig::WebSocket {
+            url: host.trim().to_string(),
+            auth_token,
+            cert_fingerprint: security.and_then(|target| target.tls_cert_fingerprint.clone()),
+        };
+    }
+    match security.filter(|target| target.tls) {
+        Some(target) => RemoteTransportConfig::Tls {
+            host,
+            auth_token,
+            cert_fingerprint: target.tls_cert_fingerprint.clone(),
+        },
+        None => RemoteTransportConfig::Tcp { host, auth_token },
+    }
+}
+
+fn is_websocket_url(host: &str) -> bool {
+    let lower = host.trim().to_ascii_lowercase();
+    lower.starts_with("ws://") || lower.starts_with("wss://")
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{can_retry_after_disconnect, resolve_transport_config};
+    use super::{
+        can_retry_after_disconnect, resolve_target_transport_config, resolve_transport_config,
+        PendingCallGuard,
+    };
+    use crate::remote_backend::protocol::{parse_incoming_line, DaemonProtocol, IncomingMessage};
+    use crate::remote_backend::transport::PendingMap;
     use crate::remote_backend::transport::RemoteTransportConfig;
-    use crate::types::AppSettings;
+    use crate::shared::core_error::{CoreError, ErrorCode};
+    use crate::types::{AppSettings, RemoteBackendProvider, RemoteBackendTarget};
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
 
     #[test]
     fn resolve_tcp_transport_uses_remote_host() {
@@ -264,10 +539,139 @@ mod tests {
         settings.remote_backend_host = "tcp.example:4732".to_string();
 
         let config = resolve_transport_config(&settings).expect("transport config");
-        let RemoteTransportConfig::Tcp { host, .. } = config;
+        let RemoteTransportConfig::Tcp { host, .. } = config else {
+            panic!("expected tcp transport");
+        };
         assert_eq!(host, "tcp.example:4732");
     }
 
+    #[test]
+    fn resolve_tls_transport_uses_active_target_fingerprint() {
+        let mut settings = AppSettings::default();
+        settings.remote_backend_host = "tls.example:4732".to_string();
+        settings.remote_backend_token = Some("token-1".to_string());
+        settings.remote_backends = vec![RemoteBackendTarget {
+            id: "remote-a".to_string(),
+            name: "Remote A".to_string(),
+            provider: RemoteBackendProvider::Tcp,
+            host: "tls.example:4732".to_string(),
+            token: Some("token-1".to_string()),
+            last_connected_at_ms: None,
+            tls: true,
+            tls_cert_fingerprint: Some("AB:CD".to_string()),
+        }];
+        settings.active_remote_backend_id = Some("remote-a".to_string());
+
+        let config = resolve_transport_config(&settings).expect("transport config");
+        let RemoteTransportConfig::Tls {
+            host,
+            auth_token,
+            cert_fingerprint,
+        } = config
+        else {
+            panic!("expected tls transport");
+        };
+        assert_eq!(host, "tls.example:4732");
+        assert_eq!(auth_token.as_deref(), Some("token-1"));
+        assert_eq!(cert_fingerprint.as_deref(), Some("AB:CD"));
+    }
+
+    #[test]
+    fn resolve_websocket_transport_for_ws_urls() {
+        let mut settings = AppSettings::default();
+        settings.remote_backend_host = " wss://proxy.example/codex-monitor ".to_string();
+        settings.remote_backend_token = Some("token-1".to_string());
+
+        let config = resolve_transport_config(&settings).expect("transport config");
+        let RemoteTransportConfig::WebSocket {
+            url,
+            auth_token,
+            cert_fingerprint,
+        } = config
+        else {
+            panic!("expected websocket transport");
+        };
+        assert_eq!(url, "wss://proxy.example/codex-monitor");
+        assert_eq!(auth_token.as_deref(), Some("token-1"));
+        assert_eq!(cert_fingerprint, None);
+    }
+
+    #[test]
+    fn aggregated_targets_use_their_own_host_and_token() {
+        let target = RemoteBackendTarget {
+            id: "box-b".to_string(),
+            name: "Box B".to_string(),
+            provider: RemoteBackendProvider::Tcp,
+            host: "box-b.example:4732".to_string(),
+            token: Some("token-b".to_string()),
+            last_connected_at_ms: None,
+            tls: true,
+            tls_cert_fingerprint: Some("EF:01".to_string()),
+        };
+
+        let RemoteTransportConfig::Tls {
+            host,
+            auth_token,
+            cert_fingerprint,
+        } = resolve_target_transport_config(&target)
+        else {
+            panic!("expected tls transport");
+        };
+        assert_eq!(host, "box-b.example:4732");
+        assert_eq!(auth_token.as_deref(), Some("token-b"));
+        assert_eq!(cert_fingerprint.as_deref(), Some("EF:01"));
+
+        let plain = RemoteBackendTarget {
+            host: " ".to_string(),
+            tls: false,
+            ..target
+        };
+        let RemoteTransportConfig::Tcp { host, .. } = resolve_target_transport_config(&plain)
+        else {
+            panic!("expected tcp transport");
+        };
+        assert_eq!(host, "127.0.0.1:4732");
+    }
+
+    #[test]
+    fn dropped_calls_remove_their_pending_entry() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .expect("runtime");
+        runtime.block_on(async {
+            let pending = Arc::new(Mutex::new(PendingMap::new()));
+            for id in [1, 2, 3] {
+                pending
+                    .lock()
+                    .await
+                    .insert(id, tokio::sync::oneshot::channel().0);
+            }
+
+            drop(PendingCallGuard {
+                pending: Arc::clone(&pending),
+                id: 1,
+                armed: true,
+            });
+            drop(PendingCallGuard {
+                pending: Arc::clone(&pending),
+                id: 2,
+                armed: false,
+            });
+            let held = pending.lock().await;
+            drop(PendingCallGuard {
+                pending: Arc::clone(&pending),
+                id: 3,
+                armed: true,
+            });
+            drop(held);
+            tokio::task::yield_now().await;
+
+            let mut remaining: Vec<u64> = pending.lock().await.keys().copied().collect();
+            remaining.sort();
+            assert_eq!(remaining, vec![2]);
+        });
+    }
+
     #[test]
     fn retries_only_retry_safe_methods_after_disconnect() {
         assert!(can_retry_after_disconnect("resume_thread"));
@@ -278,4 +682,48 @@ mod tests {
         assert!(!can_retry_after_disconnect("start_thread"));
         assert!(!can_retry_after_disconnect("remove_workspace"));
     }
+
+    #[test]
+    fn daemon_error_codes_survive_the_transport() {
+        let line = r#"{"id":7,"error":{"code":-32011,"message":"workspace not connected"}}"#;
+        let Some(IncomingMessage::Response { id, payload }) = parse_incoming_line(line) else {
+            panic!("expected a response");
+        };
+        assert_eq!(id, 7);
+        assert_eq!(payload, Err(CoreError::workspace_not_connected()));
+
+        let legacy = r#"{"id":8,"error":{"message":"remote backend disconnected"}}"#;
+        let Some(IncomingMessage::Response { payload, .. }) = parse_incoming_line(legacy) else {
+            panic!("expected a response");
+        };
+        assert_eq!(payload.map_err(|err| err.code), Err(ErrorCode::Internal));
+    }
+
+    #[test]
+    fn older_daemon_protocol_rejects_unlisted_methods_and_annotates_errors() {
+        let protocol = DaemonProtocol {
+            version: 0,
+            methods: Some(["list_threads".to_string()].into_iter().collect()),
+        };
+
+        assert!(protocol.check_method("list_threads").is_ok());
+        let err = protocol
+            .check_method("thread_live_subscribe")
+            .expect_err("unlisted method");
+        assert_eq!(err.code, ErrorCode::UnknownMethod);
+        assert!(err
+            .message
+            .starts_with("unknown method: thread_live_subscribe ("));
+        assert!(err.message.contains("update the daemon"));
+
+        assert_eq!(
+            protocol.annotate_error(CoreError::disconnected()),
+            CoreError::disconnected()
+        );
+        let annotated = protocol.annotate_error(CoreError::workspace_not_connected());
+        assert_eq!(annotated.code, ErrorCode::WorkspaceNotConnected);
+        assert!(annotated
+            .message
+            .starts_with("workspace not connected (daemon predates protocol versioning"));
+    }
 }
@@ -1,26 +1,81 @@
-use serde_json::{json, Value};
+use std::collections::HashSet;
+
+use serde_json::Value;
+
+use crate::shared::core_error::{CoreError, ErrorCode};
+use crate::shared::rpc_schema::PROTOCOL_VERSION;
 
 pub(crate) const DEFAULT_REMOTE_HOST: &str = "127.0.0.1:4732";
-pub(crate) const DISCONNECTED_MESSAGE: &str = "remote backend disconnected";
 
 pub(crate) enum IncomingMessage {
     Response {
         id: u64,
-        payload: Result<Value, String>,
+        payload: Result<Value, CoreError>,
     },
     Notification {
         method: String,
         params: Value,
+        seq: Option<u64>,
     },
 }
 
-pub(crate) fn build_request_line(id: u64, method: &str, params: Value) -> Result<String, String> {
-    let request = json!({
-        "id": id,
-        "method": method,
-        "params": params,
-    });
-    serde_json::to_string(&request).map_err(|err| err.to_string())
+/// What the connected daemon reported about its wire protocol after auth.
+pub(crate) struct DaemonProtocol {
+    /// `0` for daemons that predate protocol versioning.
+    pub(crate) version: u32,
+    /// The daemon's methods from `rpc_describe`, fetched only when versions differ.
+    pub(crate) methods: Option<HashSet<String>>,
+}
+
+impl DaemonProtocol {
+    pub(crate) fn version_from_daemon_info(info: &Value) -> u32 {
+        info.get("protocolVersion")
+            .and_then(Value::as_u64)
+            .and_then(|value| u32::try_from(value).ok())
+            .unwrap_or(0)
+    }
+
+    fn mismatch_hint(&self) -> Option<String> {
+        match self.version {
+            version if version == PROTOCOL_VERSION => None,
+            0 => Some(format!(
+                "daemon predates protocol versioning, app protocol v{PROTOCOL_VERSION}; update the daemon"
+            )),
+            version if version < PROTOCOL_VERSION => Some(format!(
+                "daemon protocol v{version}, app protocol v{PROTOCOL_VERSION}; update the daemon"
+            )),
+            version => Some(format!(
+                "daemon protocol v{version}, app protocol v{PROTOCOL_VERSION}; update the app"
+            )),
+        }
+    }
+
+    /// Fails fast for methods the daemon's catalog does not list.
+    pub(crate) fn check_method(&self, method: &str) -> Result<(), CoreError> {
+        let Some(methods) = &self.methods else {
+            return Ok(());
+        };
+        if methods.contains(method) {
+            return Ok(());
+        }
+        let hint = self
+            .mismatch_hint()
+            .map(|hint| format!(" ({hint})"))
+            .unwrap_or_default();
+        Err(CoreError::unknown_method(format!(
+            "unknown method: {method}{hint}"
+        )))
+    }
+
+    /// Adds the version mismatch to daemon errors so stale peers are obvious.
+    pub(crate) fn annotate_error(&self, mut err: CoreError) -> CoreError {
+        if let Some(hint) = self.mismatch_hint() {
+            if err.code != ErrorCode::Disconnected {
+                err.message = format!("{} ({hint})", err.message);
+            }
+        }
+        err
+    }
 }
 
 pub(crate) fn parse_incoming_line(line: &str) -> Option<IncomingMessage> {
@@ -28,14 +83,9 @@ pub(crate) fn parse_incoming_line(line: &str) -> Option<IncomingMessage> {
 
     if let Some(id) = message.get("id").and_then(|value| value.as_u64()) {
         if let Some(error) = message.get("error") {
-            let error_message = error
-                .get("message")
-                .and_then(|value| value.as_str())
-                .unwrap_or("remote error")
-                .to_string();
             return Some(IncomingMessage::Response {
                 id,
-                payload: Err(error_message),
+                payload: Err(CoreError::from_value(error)),
             });
         }
 
@@ -57,5 +107,6 @@ pub(crate) fn parse_incoming_line(line: &str) -> Option<IncomingMessage> {
     Some(IncomingMessage::Notification {
         method: method.to_string(),
         params,
+        seq: message.get("seq").and_then(|value| value.as_u64()),
     })
 }
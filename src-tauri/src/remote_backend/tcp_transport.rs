@@ -1,6 +1,9 @@
+use std::sync::Arc;
+
 use tauri::AppHandle;
 use tokio::net::TcpStream;
 
+use super::event_cursor::RemoteEventCursor;
 use super::transport::{
     spawn_transport_io, RemoteTransport, RemoteTransportConfig, TransportFuture,
 };
@@ -8,15 +11,22 @@ use super::transport::{
 pub(crate) struct TcpTransport;
 
 impl RemoteTransport for TcpTransport {
-    fn connect(&self, app: AppHandle, config: RemoteTransportConfig) -> TransportFuture {
+    fn connect(
+        &self,
+        app: AppHandle,
+        config: RemoteTransportConfig,
+        event_cursor: Arc<RemoteEventCursor>,
+    ) -> TransportFuture {
         Box::pin(async move {
-            let RemoteTransportConfig::Tcp { host, .. } = config;
+            let RemoteTransportConfig::Tcp { host, .. } = config else {
+                return Err("TCP transport requires a TCP remote config".to_string());
+            };
 
             let stream = TcpStream::connect(host.clone())
                 .await
                 .map_err(|err| format!("Failed to connect to remote backend at {host}: {err}"))?;
             let (reader, writer) = stream.into_split();
-            Ok(spawn_transport_io(app, reader, writer))
+            Ok(spawn_transport_io(app, event_cursor, reader, writer))
         })
     }
 }
@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use tauri::AppHandle;
+use tokio::net::TcpStream;
+use tokio_rustls::TlsConnector;
+
+use super::event_cursor::RemoteEventCursor;
+use super::transport::{
+    spawn_transport_io, RemoteTransport, RemoteTransportConfig, TransportFuture,
+};
+use crate::shared::tls_core::{build_client_config, server_name_for_host};
+
+pub(crate) struct TlsTransport;
+
+impl RemoteTransport for TlsTransport {
+    fn connect(
+        &self,
+        app: AppHandle,
+        config: RemoteTransportConfig,
+        event_cursor: Arc<RemoteEventCursor>,
+    ) -> TransportFuture {
+        Box::pin(async move {
+            let RemoteTransportConfig::Tls {
+                host,
+                cert_fingerprint,
+                ..
+            } = config
+            else {
+                return Err("TLS transport requires a TLS remote config".to_string());
+            };
+
+            let client_config = build_client_config(cert_fingerprint.as_deref())?;
+            let server_name = server_name_for_host(&host)?;
+            let stream = TcpStream::connect(host.clone())
+                .await
+                .map_err(|err| format!("Failed to connect to remote backend at {host}: {err}"))?;
+            let stream = TlsConnector::from(Arc::new(client_config))
+                .connect(server_name, stream)
+                .await
+                .map_err(|err| format!("TLS handshake with {host} failed: {err}"))?;
+            let (reader, writer) = tokio::io::split(stream);
+            Ok(spawn_transport_io(app, event_cursor, reader, writer))
+        })
+    }
+}
@@ -6,13 +6,35 @@ use std::sync::Arc;
 
 use serde_json::Value;
 use tauri::{AppHandle, Emitter};
-use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::io::{AsyncRead, AsyncWrite, BufReader};
 use tokio::sync::{mpsc, oneshot, Mutex};
 
-use super::protocol::{parse_incoming_line, IncomingMessage, DISCONNECTED_MESSAGE};
+use super::aggregate::namespace_event;
+use super::event_cursor::RemoteEventCursor;
+use super::protocol::{parse_incoming_line, IncomingMessage};
+use crate::shared::core_error::CoreError;
+use crate::shared::wire_compression_core::{
+    read_message, write_message, FrameDecoder, FrameEncoder, WireCompression,
+};
 
-pub(crate) type PendingMap = HashMap<u64, oneshot::Sender<Result<Value, String>>>;
-const OUTBOUND_QUEUE_CAPACITY: usize = 512;
+pub(crate) type PendingMap = HashMap<u64, oneshot::Sender<Result<Value, CoreError>>>;
+pub(super) const OUTBOUND_QUEUE_CAPACITY: usize = 512;
+
+/// A message queued for the transport writer.
+pub(crate) enum OutboundMessage {
+    Json(String),
+    /// The `protocol_upgrade` request. It is written uncompressed and every
+    /// later message is compressed; the reader switches once the response
+    /// to `id` arrives.
+    Upgrade {
+        line: String,
+        id: u64,
+        compression: WireCompression,
+    },
+}
+
+/// The `protocol_upgrade` request id the reader is waiting on, if any.
+pub(crate) type PendingUpgrade = Arc<std::sync::Mutex<Option<(u64, WireCompression)>>>;
 
 #[derive(Clone, Debug)]
 pub(crate) enum RemoteTransportConfig {
@@ -20,29 +42,45 @@ pub(crate) enum RemoteTransportConfig {
         host: String,
         auth_token: Option<String>,
     },
+    Tls {
+        host: String,
+        auth_token: Option<String>,
+        cert_fingerprint: Option<String>,
+    },
+    WebSocket {
+        url: String,
+        auth_token: Option<String>,
+        cert_fingerprint: Option<String>,
+    },
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub(crate) enum RemoteTransportKind {
     Tcp,
+    Tls,
+    WebSocket,
 }
 
 impl RemoteTransportConfig {
     pub(crate) fn kind(&self) -> RemoteTransportKind {
         match self {
             RemoteTransportConfig::Tcp { .. } => RemoteTransportKind::Tcp,
+            RemoteTransportConfig::Tls { .. } => RemoteTransportKind::Tls,
+            RemoteTransportConfig::WebSocket { .. } => RemoteTransportKind::WebSocket,
         }
     }
 
     pub(crate) fn auth_token(&self) -> Option<&str> {
         match self {
-            RemoteTransportConfig::Tcp { auth_token, .. } => auth_token.as_deref(),
+            RemoteTransportConfig::Tcp { auth_token, .. }
+            | RemoteTransportConfig::Tls { auth_token, .. }
+            | RemoteTransportConfig::WebSocket { auth_token, .. } => auth_token.as_deref(),
         }
     }
 }
 
 pub(crate) struct TransportConnection {
-    pub(crate) out_tx: mpsc::Sender<String>,
+    pub(crate) out_tx: mpsc::Sender<OutboundMessage>,
     pub(crate) pending: Arc<Mutex<PendingMap>>,
     pub(crate) connected: Arc<AtomicBool>,
 }
@@ -51,11 +89,17 @@ pub(crate) type TransportFuture =
     Pin<Box<dyn Future<Output = Result<TransportConnection, String>> + Send>>;
 
 pub(crate) trait RemoteTransport: Send + Sync {
-    fn connect(&self, app: AppHandle, config: RemoteTransportConfig) -> TransportFuture;
+    fn connect(
+        &self,
+        app: AppHandle,
+        config: RemoteTransportConfig,
+        event_cursor: Arc<RemoteEventCursor>,
+    ) -> TransportFuture;
 }
 
 pub(crate) fn spawn_transport_io<R, W>(
     app: AppHandle,
+    event_cursor: Arc<RemoteEventCursor>,
     reader: R,
     mut writer: W,
 ) -> TransportConnection
@@ -63,7 +107,7 @@ where
     R: AsyncRead + Unpin + Send + 'static,
     W: AsyncWrite + Unpin + Send + 'static,
 {
-    let (out_tx, mut out_rx) = mpsc::channel::<String>(OUTBOUND_QUEUE_CAPACITY);
+    let (out_tx, mut out_rx) = mpsc::channel::<OutboundMessage>(OUTBOUND_QUEUE_CAPACITY);
     let pending = Arc::new(Mutex::new(PendingMap::new()));
     let pending_for_writer = Arc::clone(&pending);
     let pending_for_reader = Arc::clone(&pending);
@@ -72,11 +116,21 @@ where
     let connected_for_writer = Arc::clone(&connected);
     let connected_for_reader = Arc::clone(&connected);
 
+    let pending_upgrade = PendingUpgrade::default();
+    let pending_upgrade_for_reader = Arc::clone(&pending_upgrade);
+
     tokio::spawn(async move {
+        let mut encoder: Option<FrameEncoder> = None;
         while let Some(message) = out_rx.recv().await {
-            if writer.write_all(message.as_bytes()).await.is_err()
-                || writer.write_all(b"\n").await.is_err()
-            {
+            let (line, upgrade) = begin_outbound(&pending_upgrade, message);
+            let written = write_message(&mut writer, encoder.as_mut(), &line).await;
+            let switched = match upgrade {
+                Some(compression) => FrameEncoder::new(compression).map(|created| {
+                    encoder = Some(created);
+                }),
+                None => Ok(()),
+            };
+            if written.is_err() || switched.is_err() {
                 mark_disconnected(&pending_for_writer, &connected_for_writer).await;
                 break;
             }
@@ -84,7 +138,15 @@ where
     });
 
     tokio::spawn(async move {
-        read_loop(app, reader, pending_for_reader, connected_for_reader).await;
+        read_loop(
+            app,
+            event_cursor,
+            reader,
+            pending_for_reader,
+            pending_upgrade_for_reader,
+            connected_for_reader,
+        )
+        .await;
     });
 
     TransportConnection {
@@ -96,28 +158,79 @@ where
 
 async fn read_loop<R>(
     app: AppHandle,
+    event_cursor: Arc<RemoteEventCursor>,
     reader: R,
     pending: Arc<Mutex<PendingMap>>,
+    pending_upgrade: PendingUpgrade,
     connected: Arc<AtomicBool>,
 ) where
     R: AsyncRead + Unpin + Send + 'static,
 {
-    let mut lines = BufReader::new(reader).lines();
+    let mut reader = BufReader::new(reader);
+    let mut decoder: Option<FrameDecoder> = None;
 
-    while let Ok(Some(line)) = lines.next_line().await {
+    while let Ok(Some(line)) = read_message(&mut reader, decoder.as_mut()).await {
         let trimmed = line.trim();
         if trimmed.is_empty() {
             continue;
         }
-        dispatch_incoming_line(&app, &pending, trimmed).await;
+        let upgrade = upgrade_answered_by(&pending_upgrade, trimmed);
+        dispatch_incoming_line(&app, &pending, &event_cursor, trimmed).await;
+        if let Some(compression) = upgrade {
+            match FrameDecoder::new(compression) {
+                Ok(created) => decoder = Some(created),
+                Err(_) => break,
+            }
+        }
     }
 
     mark_disconnected(&pending, &connected).await;
 }
 
+/// Unpacks a queued message for the writer. For an upgrade, the reader is told
+/// which response to wait for before the request goes out, and the returned
+/// compression applies to everything written after it.
+pub(crate) fn begin_outbound(
+    pending_upgrade: &PendingUpgrade,
+    message: OutboundMessage,
+) -> (String, Option<WireCompression>) {
+    match message {
+        OutboundMessage::Json(line) => (line, None),
+        OutboundMessage::Upgrade {
+            line,
+            id,
+            compression,
+        } => {
+            *pending_upgrade
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some((id, compression));
+            (line, Some(compression))
+        }
+    }
+}
+
+/// Returns the compression to switch the reader to when `line` is the
+/// response to the pending `protocol_upgrade` request.
+pub(crate) fn upgrade_answered_by(
+    pending_upgrade: &PendingUpgrade,
+    line: &str,
+) -> Option<WireCompression> {
+    let mut pending_upgrade = pending_upgrade
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let (id, compression) = (*pending_upgrade)?;
+    let message: Value = serde_json::from_str(line).ok()?;
+    if message.get("id").and_then(Value::as_u64) != Some(id) {
+        return None;
+    }
+    pending_upgrade.take();
+    Some(compression)
+}
+
 pub(crate) async fn dispatch_incoming_line(
     app: &AppHandle,
     pending: &Arc<Mutex<PendingMap>>,
+    event_cursor: &RemoteEventCursor,
     line: &str,
 ) {
     let Some(message) = parse_incoming_line(line) else {
@@ -131,18 +244,30 @@ pub(crate) async fn dispatch_incoming_line(
                 let _ = sender.send(payload);
             }
         }
-        IncomingMessage::Notification { method, params } => match method.as_str() {
-            "app-server-event" => {
-                let _ = app.emit("app-server-event", params);
+        IncomingMessage::Notification {
+            method,
+            mut params,
+            seq,
+        } => {
+            if let Some(backend_id) = event_cursor.workspace_namespace() {
+                namespace_event(backend_id, &mut params);
             }
-            "terminal-output" => {
-                let _ = app.emit("terminal-output", params);
+            match method.as_str() {
+                "app-server-event" => {
+                    let _ = app.emit("app-server-event", params);
+                }
+                "terminal-output" => {
+                    let _ = app.emit("terminal-output", params);
+                }
+                "terminal-exit" => {
+                    let _ = app.emit("terminal-exit", params);
+                }
+                _ => {}
             }
-            "terminal-exit" => {
-                let _ = app.emit("terminal-exit", params);
+            if let Some(seq) = seq {
+                event_cursor.observe(seq);
             }
-            _ => {}
-        },
+        }
     }
 }
 
@@ -153,6 +278,6 @@ pub(crate) async fn mark_disconnected(
     connected.store(false, Ordering::SeqCst);
     let mut pending = pending.lock().await;
     for (_, sender) in pending.drain() {
-        let _ = sender.send(Err(DISCONNECTED_MESSAGE.to_string()));
+        let _ = sender.send(Err(CoreError::disconnected()));
     }
 }
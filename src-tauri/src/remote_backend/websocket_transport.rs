@@ -0,0 +1,133 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use futures_util::{SinkExt, StreamExt};
+use tauri::AppHandle;
+use tokio::sync::{mpsc, Mutex};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::Connector;
+
+use super::event_cursor::RemoteEventCursor;
+use super::transport::{
+    begin_outbound, dispatch_incoming_line, mark_disconnected, upgrade_answered_by,
+    OutboundMessage, PendingMap, PendingUpgrade, RemoteTransport, RemoteTransportConfig,
+    TransportConnection, TransportFuture, OUTBOUND_QUEUE_CAPACITY,
+};
+use crate::shared::tls_core::build_client_config;
+use crate::shared::wire_compression_core::{FrameDecoder, FrameEncoder};
+
+pub(crate) struct WebSocketTransport;
+
+impl RemoteTransport for WebSocketTransport {
+    fn connect(
+        &self,
+        app: AppHandle,
+        config: RemoteTransportConfig,
+        event_cursor: Arc<RemoteEventCursor>,
+    ) -> TransportFuture {
+        Box::pin(async move {
+            let RemoteTransportConfig::WebSocket {
+                url,
+                cert_fingerprint,
+                ..
+            } = config
+            else {
+                return Err("WebSocket transport requires a WebSocket remote config".to_string());
+            };
+
+            let connector = if url.starts_with("wss://") {
+                let client_config = build_client_config(cert_fingerprint.as_deref())?;
+                Some(Connector::Rustls(Arc::new(client_config)))
+            } else {
+                None
+            };
+            let (stream, _) = tokio_tungstenite::connect_async_tls_with_config(
+                url.as_str(),
+                None,
+                false,
+                connector,
+            )
+            .await
+            .map_err(|err| format!("Failed to connect to remote backend at {url}: {err}"))?;
+            let (mut sink, mut source) = stream.split();
+
+            let (out_tx, mut out_rx) = mpsc::channel::<OutboundMessage>(OUTBOUND_QUEUE_CAPACITY);
+            let pending = Arc::new(Mutex::new(PendingMap::new()));
+            let pending_for_writer = Arc::clone(&pending);
+            let pending_for_reader = Arc::clone(&pending);
+
+            let connected = Arc::new(AtomicBool::new(true));
+            let connected_for_writer = Arc::clone(&connected);
+            let connected_for_reader = Arc::clone(&connected);
+
+            let pending_upgrade = PendingUpgrade::default();
+            let pending_upgrade_for_reader = Arc::clone(&pending_upgrade);
+
+            // Compressed messages travel as binary frames.
+            tokio::spawn(async move {
+                let mut encoder: Option<FrameEncoder> = None;
+                while let Some(message) = out_rx.recv().await {
+                    let (line, upgrade) = begin_outbound(&pending_upgrade, message);
+                    let frame = match encoder.as_mut() {
+                        Some(encoder) => encoder.encode(line.as_bytes()).map(Message::Binary),
+                        None => Ok(Message::Text(line)),
+                    };
+                    let sent = match frame {
+                        Ok(frame) => sink.send(frame).await.is_ok(),
+                        Err(_) => false,
+                    };
+                    let switched = match upgrade {
+                        Some(compression) => FrameEncoder::new(compression).map(|created| {
+                            encoder = Some(created);
+                        }),
+                        None => Ok(()),
+                    };
+                    if !sent || switched.is_err() {
+                        mark_disconnected(&pending_for_writer, &connected_for_writer).await;
+                        break;
+                    }
+                }
+            });
+
+            tokio::spawn(async move {
+                let mut decoder: Option<FrameDecoder> = None;
+                while let Some(Ok(message)) = source.next().await {
+                    let text = match message {
+                        Message::Text(text) => text,
+                        Message::Binary(bytes) => match decoder.as_mut() {
+                            Some(decoder) => match decoder.decode(&bytes) {
+                                Ok(text) => text,
+                                Err(_) => break,
+                            },
+                            None => match String::from_utf8(bytes) {
+                                Ok(text) => text,
+                                Err(_) => continue,
+                            },
+                        },
+                        Message::Close(_) => break,
+                        _ => continue,
+                    };
+                    let trimmed = text.trim();
+                    if trimmed.is_empty() {
+                        continue;
+                    }
+                    let upgrade = upgrade_answered_by(&pending_upgrade_for_reader, trimmed);
+                    dispatch_incoming_line(&app, &pending_for_reader, &event_cursor, trimmed).await;
+                    if let Some(compression) = upgrade {
+                        match FrameDecoder::new(compression) {
+                            Ok(created) => decoder = Some(created),
+                            Err(_) => break,
+                        }
+                    }
+                }
+                mark_disconnected(&pending_for_reader, &connected_for_reader).await;
+            });
+
+            Ok(TransportConnection {
+                out_tx,
+                pending,
+                connected,
+            })
+        })
+    }
+}
@@ -24,10 +24,15 @@ pub(crate) async fn update_app_settings(
     window: Window,
 ) -> Result<AppSettings, String> {
     let previous = state.app_settings.lock().await.clone();
-    let updated =
-        update_app_settings_core(settings, &state.app_settings, &state.settings_path).await?;
+    let updated = update_app_settings_core(
+        settings,
+        &state.app_settings,
+        &state.settings_path,
+        &state.request_timeouts,
+    )
+    .await?;
     if should_reset_remote_backend(&previous, &updated) {
-        *state.remote_backend.lock().await = None;
+        state.remote_backends.lock().await.clear();
     }
     ensure_remote_runtime_for_settings(&updated, state).await;
     let _ = window::apply_window_appearance(&window, updated.theme.as_str());
@@ -36,7 +41,7 @@ pub(crate) async fn update_app_settings(
 
 #[tauri::command]
 pub(crate) async fn get_codex_config_path() -> Result<String, String> {
-    get_codex_config_path_core()
+    get_codex_config_path_core().map_err(String::from)
 }
 
 fn should_reset_remote_backend(previous: &AppSettings, updated: &AppSettings) -> bool {
@@ -54,6 +59,38 @@ fn should_reset_remote_backend(previous: &AppSettings, updated: &AppSettings) ->
         || previous.remote_backend_provider != updated.remote_backend_provider
         || previous.remote_backend_host != updated.remote_backend_host
         || previous.remote_backend_token != updated.remote_backend_token
+        || remote_backend_tls_settings(previous) != remote_backend_tls_settings(updated)
+        || previous.aggregate_remote_backends != updated.aggregate_remote_backends
+        || aggregated_remote_backends(previous) != aggregated_remote_backends(updated)
+}
+
+fn remote_backend_tls_settings(settings: &AppSettings) -> Option<(bool, Option<&str>)> {
+    settings
+        .active_remote_backend()
+        .map(|target| (target.tls, target.tls_cert_fingerprint.as_deref()))
+}
+
+/// Id, host, token, TLS flag and pinned fingerprint of a remote.
+type RemoteConnectionSettings<'a> = (&'a str, &'a str, Option<&'a str>, bool, Option<&'a str>);
+
+/// Connection settings of every aggregated remote, in order.
+fn aggregated_remote_backends(settings: &AppSettings) -> Vec<RemoteConnectionSettings<'_>> {
+    if !settings.aggregate_remote_backends {
+        return Vec::new();
+    }
+    settings
+        .remote_backends
+        .iter()
+        .map(|target| {
+            (
+                target.id.as_str(),
+                target.host.as_str(),
+                target.token.as_deref(),
+                target.tls,
+                target.tls_cert_fingerprint.as_deref(),
+            )
+        })
+        .collect()
 }
 
 async fn ensure_remote_runtime_for_settings(settings: &AppSettings, state: State<'_, AppState>) {
@@ -70,7 +107,7 @@ async fn ensure_remote_runtime_for_settings(settings: &AppSettings, state: State
 #[cfg(test)]
 mod tests {
     use super::should_reset_remote_backend;
-    use crate::types::{AppSettings, BackendMode};
+    use crate::types::{AppSettings, BackendMode, RemoteBackendTarget};
 
     #[test]
     fn should_reset_remote_backend_when_provider_changes() {
@@ -89,6 +126,60 @@ mod tests {
         assert!(should_reset_remote_backend(&previous, &updated));
     }
 
+    #[test]
+    fn should_reset_remote_backend_when_active_target_tls_changes() {
+        let mut previous = AppSettings::default();
+        previous.remote_backends = vec![RemoteBackendTarget {
+            id: "remote-a".to_string(),
+            name: "Remote A".to_string(),
+            provider: crate::types::RemoteBackendProvider::Tcp,
+            host: previous.remote_backend_host.clone(),
+            token: None,
+            last_connected_at_ms: None,
+            tls: false,
+            tls_cert_fingerprint: None,
+        }];
+        previous.active_remote_backend_id = Some("remote-a".to_string());
+        let mut updated = previous.clone();
+        updated.remote_backends[0].tls = true;
+        assert!(should_reset_remote_backend(&previous, &updated));
+
+        let mut repinned = updated.clone();
+        repinned.remote_backends[0].tls_cert_fingerprint = Some("AB:CD".to_string());
+        assert!(should_reset_remote_backend(&updated, &repinned));
+    }
+
+    #[test]
+    fn should_reset_remote_backend_when_aggregated_targets_change() {
+        let previous = AppSettings {
+            remote_backends: vec![RemoteBackendTarget {
+                id: "remote-a".to_string(),
+                name: "Remote A".to_string(),
+                provider: crate::types::RemoteBackendProvider::Tcp,
+                host: "box-a.example:4732".to_string(),
+                token: None,
+                last_connected_at_ms: None,
+                tls: false,
+                tls_cert_fingerprint: None,
+            }],
+            ..AppSettings::default()
+        };
+        let mut aggregated = previous.clone();
+        aggregated.aggregate_remote_backends = true;
+        assert!(should_reset_remote_backend(&previous, &aggregated));
+
+        let mut added = aggregated.clone();
+        let mut second = added.remote_backends[0].clone();
+        second.id = "remote-b".to_string();
+        second.host = "box-b.example:4732".to_string();
+        added.remote_backends.push(second);
+        assert!(should_reset_remote_backend(&aggregated, &added));
+
+        let mut reconnected = added.clone();
+        reconnected.remote_backends[1].last_connected_at_ms = Some(1);
+        assert!(!should_reset_remote_backend(&added, &reconnected));
+    }
+
     #[test]
     fn should_not_reset_remote_backend_for_non_transport_setting_changes() {
         let previous = AppSettings::default();
@@ -1,3 +1,4 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::ffi::OsStr;
 use std::io::ErrorKind;
@@ -6,6 +7,7 @@ use toml_edit::{value, Document, Item, Table};
 
 use crate::codex::home as codex_home;
 use crate::shared::config_toml_core;
+use crate::shared::core_error::CoreError;
 
 pub(crate) const DEFAULT_AGENT_MAX_THREADS: u32 = 6;
 pub(crate) const DEFAULT_AGENT_MAX_DEPTH: u32 = 1;
@@ -22,7 +24,7 @@ const fn default_agent_max_depth() -> u32 {
     DEFAULT_AGENT_MAX_DEPTH
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct AgentSummaryDto {
     pub name: String,
@@ -34,7 +36,7 @@ pub(crate) struct AgentSummaryDto {
     pub file_exists: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct AgentsSettingsDto {
     pub config_path: String,
@@ -44,7 +46,7 @@ pub(crate) struct AgentsSettingsDto {
     pub agents: Vec<AgentSummaryDto>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct SetAgentsCoreInput {
     pub multi_agent_enabled: bool,
@@ -53,7 +55,7 @@ pub(crate) struct SetAgentsCoreInput {
     pub max_depth: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct CreateAgentInput {
     pub name: String,
@@ -64,7 +66,7 @@ pub(crate) struct CreateAgentInput {
     pub reasoning_effort: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct UpdateAgentInput {
     pub original_name: String,
@@ -74,19 +76,19 @@ pub(crate) struct UpdateAgentInput {
     pub rename_managed_file: Option<bool>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct DeleteAgentInput {
     pub name: String,
     pub delete_managed_file: Option<bool>,
 }
 
-pub(crate) fn get_agents_settings_core() -> Result<AgentsSettingsDto, String> {
+pub(crate) fn get_agents_settings_core() -> Result<AgentsSettingsDto, CoreError> {
     let codex_home = resolve_codex_home()?;
     let config_path = codex_home.join("config.toml");
     let config_path_string = config_path
         .to_str()
-        .ok_or_else(|| "Unable to resolve CODEX_HOME".to_string())?
+        .ok_or_else(|| CoreError::internal("Unable to resolve CODEX_HOME"))?
         .to_string();
 
     let (_, document) = config_toml_core::load_global_config_document(&codex_home)?;
@@ -107,7 +109,7 @@ pub(crate) fn get_agents_settings_core() -> Result<AgentsSettingsDto, String> {
 
 pub(crate) fn set_agents_core_settings_core(
     input: SetAgentsCoreInput,
-) -> Result<AgentsSettingsDto, String> {
+) -> Result<AgentsSettingsDto, CoreError> {
     validate_max_threads(input.max_threads)?;
     validate_max_depth(input.max_depth)?;
 
@@ -125,7 +127,7 @@ pub(crate) fn set_agents_core_settings_core(
     get_agents_settings_core()
 }
 
-pub(crate) fn create_agent_core(input: CreateAgentInput) -> Result<AgentsSettingsDto, String> {
+pub(crate) fn create_agent_core(input: CreateAgentInput) -> Result<AgentsSettingsDto, CoreError> {
     let name = normalize_agent_name(input.name.as_str())?;
     let description = normalize_optional_string(input.description.as_deref());
     let developer_instructions = normalize_optional_string(input.developer_instructions.as_deref());
@@ -136,17 +138,19 @@ pub(crate) fn create_agent_core(input: CreateAgentInput) -> Result<AgentsSetting
     {
         let agents = config_toml_core::ensure_table(&mut document, "agents")?;
         if has_agent_name_conflict(agents, &name, None) {
-            return Err(format!("agent '{name}' already exists"));
+            return Err(CoreError::invalid_params(format!(
+                "agent '{name}' already exists"
+            )));
         }
     }
 
     let relative_config_path = managed_relative_config_for_name(&name);
     let target_path = resolve_safe_managed_abs_path_for_write(&codex_home, &relative_config_path)?;
     if target_path.exists() {
-        return Err(format!(
+        return Err(CoreError::invalid_params(format!(
             "target config file already exists: {}",
             target_path.display()
-        ));
+        )));
     }
     let template_content = build_template_content(
         input.template.as_deref(),
@@ -155,7 +159,7 @@ pub(crate) fn create_agent_core(input: CreateAgentInput) -> Result<AgentsSetting
         developer_instructions.as_deref(),
     );
     std::fs::write(&target_path, template_content)
-        .map_err(|err| format!("Failed to create agent config file: {err}"))?;
+        .map_err(|err| CoreError::internal(format!("Failed to create agent config file: {err}")))?;
 
     {
         let agents = config_toml_core::ensure_table(&mut document, "agents")?;
@@ -175,7 +179,7 @@ pub(crate) fn create_agent_core(input: CreateAgentInput) -> Result<AgentsSetting
     get_agents_settings_core()
 }
 
-pub(crate) fn update_agent_core(input: UpdateAgentInput) -> Result<AgentsSettingsDto, String> {
+pub(crate) fn update_agent_core(input: UpdateAgentInput) -> Result<AgentsSettingsDto, CoreError> {
     let original_name = normalize_agent_lookup_name(input.original_name.as_str())?;
     let name = normalize_agent_name(input.name.as_str())?;
     let description = normalize_optional_string(input.description.as_deref());
@@ -192,11 +196,15 @@ pub(crate) fn update_agent_core(input: UpdateAgentInput) -> Result<AgentsSetting
     {
         let agents = config_toml_core::ensure_table(&mut document, "agents")?;
         if name != original_name && has_agent_name_conflict(agents, &name, Some(&original_name)) {
-            return Err(format!("agent '{name}' already exists"));
+            return Err(CoreError::invalid_params(format!(
+                "agent '{name}' already exists"
+            )));
         }
 
         let Some(existing_item) = agents.remove(&original_name) else {
-            return Err(format!("agent '{original_name}' not found"));
+            return Err(CoreError::invalid_params(format!(
+                "agent '{original_name}' not found"
+            )));
         };
 
         let mut next_config_file = read_role_config_file(&existing_item);
@@ -216,10 +224,10 @@ pub(crate) fn update_agent_core(input: UpdateAgentInput) -> Result<AgentsSetting
                             &new_relative_path,
                         )?;
                         if new_abs_path.exists() {
-                            return Err(format!(
+                            return Err(CoreError::invalid_params(format!(
                                 "target config file already exists: {}",
                                 new_abs_path.display()
-                            ));
+                            )));
                         }
                         if old_abs_path.exists() {
                             std::fs::rename(&old_abs_path, &new_abs_path).map_err(|err| {
@@ -270,9 +278,9 @@ pub(crate) fn update_agent_core(input: UpdateAgentInput) -> Result<AgentsSetting
                             let _ = std::fs::rename(new_path, old_path);
                         }
                     }
-                    return Err(format!(
+                    return Err(CoreError::invalid_params(format!(
                         "agent '{name}' config_file is external; edit that file directly to change developer_instructions"
-                    ));
+                    )));
                 }
             } else {
                 if let Some((old_path, new_path)) = maybe_renamed_paths.as_ref() {
@@ -280,9 +288,9 @@ pub(crate) fn update_agent_core(input: UpdateAgentInput) -> Result<AgentsSetting
                         let _ = std::fs::rename(new_path, old_path);
                     }
                 }
-                return Err(format!(
+                return Err(CoreError::invalid_params(format!(
                     "agent '{name}' does not define config_file; cannot update developer_instructions"
-                ));
+                )));
             }
         }
 
@@ -313,7 +321,7 @@ pub(crate) fn update_agent_core(input: UpdateAgentInput) -> Result<AgentsSetting
     get_agents_settings_core()
 }
 
-pub(crate) fn delete_agent_core(input: DeleteAgentInput) -> Result<AgentsSettingsDto, String> {
+pub(crate) fn delete_agent_core(input: DeleteAgentInput) -> Result<AgentsSettingsDto, CoreError> {
     let name = normalize_agent_lookup_name(input.name.as_str())?;
     let delete_managed_file = input.delete_managed_file.unwrap_or(false);
 
@@ -323,7 +331,9 @@ pub(crate) fn delete_agent_core(input: DeleteAgentInput) -> Result<AgentsSetting
     let removed_config_file = {
         let agents = config_toml_core::ensure_table(&mut document, "agents")?;
         let Some(existing_item) = agents.remove(&name) else {
-            return Err(format!("agent '{name}' not found"));
+            return Err(CoreError::invalid_params(format!(
+                "agent '{name}' not found"
+            )));
         };
         read_role_config_file(&existing_item)
     };
@@ -337,8 +347,9 @@ pub(crate) fn delete_agent_core(input: DeleteAgentInput) -> Result<AgentsSetting
                     let backup = std::fs::read(&target).map_err(|err| {
                         format!("Failed to read agent config file before delete: {err}")
                     })?;
-                    std::fs::remove_file(&target)
-                        .map_err(|err| format!("Failed to delete agent config file: {err}"))?;
+                    std::fs::remove_file(&target).map_err(|err| {
+                        CoreError::internal(format!("Failed to delete agent config file: {err}"))
+                    })?;
                     deleted_config_backup = Some((target, backup));
                 }
             }
@@ -350,12 +361,12 @@ pub(crate) fn delete_agent_core(input: DeleteAgentInput) -> Result<AgentsSetting
     {
         if let Some((path, backup)) = deleted_config_backup {
             if let Err(restore_error) = std::fs::write(&path, backup) {
-                return Err(format!(
+                return Err(CoreError::internal(format!(
                     "{} (also failed to restore deleted config file {}: {})",
                     persist_error,
                     path.display(),
                     restore_error
-                ));
+                )));
             }
         }
         return Err(persist_error);
@@ -364,24 +375,29 @@ pub(crate) fn delete_agent_core(input: DeleteAgentInput) -> Result<AgentsSetting
     get_agents_settings_core()
 }
 
-pub(crate) fn read_agent_config_toml_core(agent_name: &str) -> Result<String, String> {
+pub(crate) fn read_agent_config_toml_core(agent_name: &str) -> Result<String, CoreError> {
     let (codex_home, relative_path) = resolve_managed_agent_config_relative_path(agent_name)?;
     let path = resolve_safe_managed_abs_path_for_read(&codex_home, &relative_path)?;
     if !path.exists() {
         return Ok(String::new());
     }
-    std::fs::read_to_string(path).map_err(|err| format!("Failed to read agent config file: {err}"))
+    std::fs::read_to_string(path)
+        .map_err(|err| CoreError::internal(format!("Failed to read agent config file: {err}")))
 }
 
-pub(crate) fn write_agent_config_toml_core(agent_name: &str, content: &str) -> Result<(), String> {
+pub(crate) fn write_agent_config_toml_core(
+    agent_name: &str,
+    content: &str,
+) -> Result<(), CoreError> {
     let (codex_home, relative_path) = resolve_managed_agent_config_relative_path(agent_name)?;
     let path = resolve_safe_managed_abs_path_for_write(&codex_home, &relative_path)?;
-    std::fs::write(path, content).map_err(|err| format!("Failed to write agent config file: {err}"))
+    std::fs::write(path, content)
+        .map_err(|err| CoreError::internal(format!("Failed to write agent config file: {err}")))
 }
 
-fn resolve_codex_home() -> Result<PathBuf, String> {
+fn resolve_codex_home() -> Result<PathBuf, CoreError> {
     codex_home::resolve_default_codex_home()
-        .ok_or_else(|| "Unable to resolve CODEX_HOME".to_string())
+        .ok_or_else(|| CoreError::internal("Unable to resolve CODEX_HOME"))
 }
 
 fn read_multi_agent_enabled(document: &Document) -> bool {
@@ -473,7 +489,7 @@ fn resolve_config_file_path_for_display(codex_home: &Path, raw_value: &str) -> O
     Some(codex_home.join(normalized_relative))
 }
 
-fn normalize_agent_name(raw_name: &str) -> Result<String, String> {
+fn normalize_agent_name(raw_name: &str) -> Result<String, CoreError> {
     let mut name = String::new();
     let mut previous_was_space = false;
     for char in raw_name.trim().to_ascii_lowercase().chars() {
@@ -489,36 +505,40 @@ fn normalize_agent_name(raw_name: &str) -> Result<String, String> {
     }
 
     if name.is_empty() {
-        return Err("agent name is required".to_string());
+        return Err(CoreError::invalid_params("agent name is required"));
     }
     if name.len() > 32 {
-        return Err("agent name must be 32 characters or fewer".to_string());
+        return Err(CoreError::invalid_params(
+            "agent name must be 32 characters or fewer",
+        ));
     }
 
     let mut chars = name.chars();
     let Some(first) = chars.next() else {
-        return Err("agent name is required".to_string());
+        return Err(CoreError::invalid_params("agent name is required"));
     };
     if !first.is_ascii_lowercase() && !first.is_ascii_digit() {
-        return Err("agent name must start with a lowercase letter or digit".to_string());
+        return Err(CoreError::invalid_params(
+            "agent name must start with a lowercase letter or digit",
+        ));
     }
     for char in chars {
         if !char.is_ascii_lowercase() && !char.is_ascii_digit() && char != '_' && char != '-' {
-            return Err(
-                "agent name must use only lowercase letters, digits, '_' or '-'".to_string(),
-            );
+            return Err(CoreError::invalid_params(
+                "agent name must use only lowercase letters, digits, '_' or '-'",
+            ));
         }
     }
     if is_reserved_agents_key(name.as_str()) {
-        return Err("agent name is reserved".to_string());
+        return Err(CoreError::invalid_params("agent name is reserved"));
     }
     Ok(name.to_string())
 }
 
-fn normalize_agent_lookup_name(raw_name: &str) -> Result<String, String> {
+fn normalize_agent_lookup_name(raw_name: &str) -> Result<String, CoreError> {
     let name = raw_name.trim();
     if name.is_empty() {
-        return Err("agent name is required".to_string());
+        return Err(CoreError::invalid_params("agent name is required"));
     }
     Ok(name.to_string())
 }
@@ -532,25 +552,25 @@ fn normalize_optional_string(raw: Option<&str>) -> Option<String> {
     }
 }
 
-fn validate_max_threads(value: u32) -> Result<(), String> {
+fn validate_max_threads(value: u32) -> Result<(), CoreError> {
     if (MIN_AGENT_MAX_THREADS..=MAX_AGENT_MAX_THREADS).contains(&value) {
         Ok(())
     } else {
-        Err(format!(
+        Err(CoreError::invalid_params(format!(
             "agents.max_threads must be between {} and {}",
             MIN_AGENT_MAX_THREADS, MAX_AGENT_MAX_THREADS
-        ))
+        )))
     }
 }
 
-fn validate_max_depth(value: u32) -> Result<(), String> {
+fn validate_max_depth(value: u32) -> Result<(), CoreError> {
     if (MIN_AGENT_MAX_DEPTH..=MAX_AGENT_MAX_DEPTH).contains(&value) {
         Ok(())
     } else {
-        Err(format!(
+        Err(CoreError::invalid_params(format!(
             "agents.max_depth must be between {} and {}",
             MIN_AGENT_MAX_DEPTH, MAX_AGENT_MAX_DEPTH
-        ))
+        )))
     }
 }
 
@@ -575,9 +595,9 @@ fn has_agent_name_conflict(agents: &Table, name: &str, excluding: Option<&str>)
     })
 }
 
-fn clone_role_table(item: &Item) -> Result<Table, String> {
+fn clone_role_table(item: &Item) -> Result<Table, CoreError> {
     let Some(table_like) = item.as_table_like() else {
-        return Err("agent role must be a TOML table".to_string());
+        return Err(CoreError::internal("agent role must be a TOML table"));
     };
     let mut role = Table::new();
     for (key, value_item) in table_like.iter() {
@@ -660,7 +680,7 @@ fn managed_relative_path_from_config(raw_path: &str) -> Option<PathBuf> {
 
 fn resolve_managed_agent_config_relative_path(
     agent_name: &str,
-) -> Result<(PathBuf, PathBuf), String> {
+) -> Result<(PathBuf, PathBuf), CoreError> {
     let name = normalize_agent_lookup_name(agent_name)?;
     let codex_home = resolve_codex_home()?;
     let (_, document) = config_toml_core::load_global_config_document(&codex_home)?;
@@ -668,20 +688,22 @@ fn resolve_managed_agent_config_relative_path(
     let agents_table = document
         .get("agents")
         .and_then(Item::as_table_like)
-        .ok_or_else(|| "agents table not found in config.toml".to_string())?;
+        .ok_or_else(|| CoreError::internal("agents table not found in config.toml"))?;
 
     let role_item = agents_table
         .get(name.as_str())
-        .ok_or_else(|| format!("agent '{name}' not found"))?;
+        .ok_or_else(|| CoreError::invalid_params(format!("agent '{name}' not found")))?;
 
     let Some(config_file) = read_role_config_file(role_item) else {
-        return Err(format!("agent '{name}' does not define config_file"));
+        return Err(CoreError::invalid_params(format!(
+            "agent '{name}' does not define config_file"
+        )));
     };
 
     let Some(relative_path) = managed_relative_path_from_config(config_file.as_str()) else {
-        return Err(format!(
+        return Err(CoreError::invalid_params(format!(
             "agent '{name}' config_file is not managed by CodexMonitor"
-        ));
+        )));
     };
 
     Ok((codex_home, relative_path))
@@ -690,7 +712,7 @@ fn resolve_managed_agent_config_relative_path(
 fn resolve_safe_managed_abs_path_for_read(
     codex_home: &Path,
     relative_path: &Path,
-) -> Result<PathBuf, String> {
+) -> Result<PathBuf, CoreError> {
     let path = codex_home.join(relative_path);
     assert_managed_path_without_symlinks(codex_home, relative_path, true)?;
     Ok(path)
@@ -699,12 +721,13 @@ fn resolve_safe_managed_abs_path_for_read(
 fn resolve_safe_managed_abs_path_for_write(
     codex_home: &Path,
     relative_path: &Path,
-) -> Result<PathBuf, String> {
+) -> Result<PathBuf, CoreError> {
     let path = codex_home.join(relative_path);
     assert_managed_path_without_symlinks(codex_home, relative_path, true)?;
     if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)
-            .map_err(|err| format!("Failed to create agents directory: {err}"))?;
+        std::fs::create_dir_all(parent).map_err(|err| {
+            CoreError::internal(format!("Failed to create agents directory: {err}"))
+        })?;
     }
     assert_managed_path_without_symlinks(codex_home, relative_path, true)?;
     Ok(path)
@@ -714,7 +737,7 @@ fn assert_managed_path_without_symlinks(
     codex_home: &Path,
     relative_path: &Path,
     include_leaf: bool,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     let mut current = codex_home.to_path_buf();
     let mut components = relative_path.components().peekable();
     while let Some(component) = components.next() {
@@ -726,55 +749,58 @@ fn assert_managed_path_without_symlinks(
         match std::fs::symlink_metadata(&current) {
             Ok(metadata) => {
                 if metadata.file_type().is_symlink() {
-                    return Err(format!(
+                    return Err(CoreError::internal(format!(
                         "Managed agent config path may not contain symlinks: {}",
                         current.display()
-                    ));
+                    )));
                 }
             }
             Err(err) if err.kind() == ErrorKind::NotFound => break,
             Err(err) => {
-                return Err(format!(
+                return Err(CoreError::internal(format!(
                     "Failed to validate managed agent path {}: {}",
                     current.display(),
                     err
-                ))
+                )))
             }
         }
     }
     Ok(())
 }
 
-fn parse_agent_config_document(contents: &str) -> Result<Document, String> {
+fn parse_agent_config_document(contents: &str) -> Result<Document, CoreError> {
     if contents.trim().is_empty() {
         return Ok(Document::new());
     }
     contents
         .parse::<Document>()
-        .map_err(|err| format!("Failed to parse agent config file: {err}"))
+        .map_err(|err| CoreError::internal(format!("Failed to parse agent config file: {err}")))
 }
 
 fn upsert_developer_instructions_in_agent_config_file(
     path: &Path,
     developer_instructions: Option<&str>,
-) -> Result<Option<Vec<u8>>, String> {
+) -> Result<Option<Vec<u8>>, CoreError> {
     if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)
-            .map_err(|err| format!("Failed to create agents directory: {err}"))?;
+        std::fs::create_dir_all(parent).map_err(|err| {
+            CoreError::internal(format!("Failed to create agents directory: {err}"))
+        })?;
     }
 
     let previous = if path.exists() {
-        Some(
-            std::fs::read(path)
-                .map_err(|err| format!("Failed to read agent config file before update: {err}"))?,
-        )
+        Some(std::fs::read(path).map_err(|err| {
+            CoreError::internal(format!(
+                "Failed to read agent config file before update: {err}"
+            ))
+        })?)
     } else {
         None
     };
 
     let existing = if let Some(bytes) = previous.as_ref() {
-        std::str::from_utf8(bytes)
-            .map_err(|err| format!("Agent config file is not valid UTF-8: {err}"))?
+        std::str::from_utf8(bytes).map_err(|err| {
+            CoreError::internal(format!("Agent config file is not valid UTF-8: {err}"))
+        })?
     } else {
         ""
     };
@@ -795,7 +821,7 @@ fn upsert_developer_instructions_in_agent_config_file(
         rendered.push('\n');
     }
     std::fs::write(path, rendered)
-        .map_err(|err| format!("Failed to update agent config file: {err}"))?;
+        .map_err(|err| CoreError::internal(format!("Failed to update agent config file: {err}")))?;
     Ok(previous)
 }
 
@@ -835,10 +861,10 @@ fn build_template_content(
     }
 }
 
-fn pathbuf_to_string(path: &Path) -> Result<String, String> {
+fn pathbuf_to_string(path: &Path) -> Result<String, CoreError> {
     path.to_str()
         .map(str::to_string)
-        .ok_or_else(|| "invalid UTF-8 path".to_string())
+        .ok_or_else(|| CoreError::internal("invalid UTF-8 path"))
 }
 
 #[cfg(test)]
@@ -936,7 +962,7 @@ mod tests {
             std::path::Path::new("agents/researcher.toml"),
         )
         .expect_err("should reject symlink path");
-        assert!(err.contains("symlinks"));
+        assert!(err.message.contains("symlinks"));
 
         let _ = std::fs::remove_dir_all(&codex_home);
         let _ = std::fs::remove_dir_all(&outside);
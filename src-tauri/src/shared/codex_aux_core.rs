@@ -1,3 +1,4 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::collections::HashMap;
@@ -10,6 +11,7 @@ use tokio::time::timeout;
 use crate::backend::app_server::{
     build_codex_command_with_bin, build_codex_path_env, check_codex_installation, WorkspaceSession,
 };
+use crate::shared::core_error::CoreError;
 use crate::shared::process_core::tokio_command;
 use crate::types::{AppSettings, WorkspaceEntry};
 
@@ -20,7 +22,7 @@ Keep the summary line under 72 characters. \
 Only output the commit message, nothing else.\n\n\
 Changes:\n{diff}";
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct GeneratedAgentConfiguration {
     pub description: String,
@@ -43,9 +45,11 @@ pub(crate) fn build_commit_message_prompt(diff: &str, template: &str) -> String
 pub(crate) fn build_commit_message_prompt_for_diff(
     diff: &str,
     template: &str,
-) -> Result<String, String> {
+) -> Result<String, CoreError> {
     if diff.trim().is_empty() {
-        return Err("No changes to generate commit message for".to_string());
+        return Err(CoreError::invalid_params(
+            "No changes to generate commit message for",
+        ));
     }
     Ok(build_commit_message_prompt(diff, template))
 }
@@ -90,10 +94,10 @@ User prompt:\n\
 
 pub(crate) fn parse_agent_description_value(
     raw: &str,
-) -> Result<GeneratedAgentConfiguration, String> {
+) -> Result<GeneratedAgentConfiguration, CoreError> {
     let trimmed = raw.trim();
     if trimmed.is_empty() {
-        return Err("No agent configuration was generated".to_string());
+        return Err(CoreError::internal("No agent configuration was generated"));
     }
 
     let cleaned = trimmed
@@ -103,7 +107,7 @@ pub(crate) fn parse_agent_description_value(
         .collect::<Vec<_>>()
         .join("\n");
     if cleaned.trim().is_empty() {
-        return Err("No agent configuration was generated".to_string());
+        return Err(CoreError::internal("No agent configuration was generated"));
     }
 
     if let Some(json_value) = extract_json_value(cleaned.as_str()) {
@@ -195,29 +199,31 @@ pub(crate) fn parse_agent_description_value(
         });
     }
 
-    Err("No valid agent configuration was generated".to_string())
+    Err(CoreError::internal(
+        "No valid agent configuration was generated",
+    ))
 }
 
-pub(crate) fn parse_run_metadata_value(raw: &str) -> Result<Value, String> {
+pub(crate) fn parse_run_metadata_value(raw: &str) -> Result<Value, CoreError> {
     let trimmed = raw.trim();
     if trimmed.is_empty() {
-        return Err("No metadata was generated".to_string());
+        return Err(CoreError::internal("No metadata was generated"));
     }
-    let json_value =
-        extract_json_value(trimmed).ok_or_else(|| "Failed to parse metadata JSON".to_string())?;
+    let json_value = extract_json_value(trimmed)
+        .ok_or_else(|| CoreError::internal("Failed to parse metadata JSON"))?;
     let title = json_value
         .get("title")
         .and_then(|v| v.as_str())
         .map(|v| v.trim().to_string())
         .filter(|v| !v.is_empty())
-        .ok_or_else(|| "Missing title in metadata".to_string())?;
+        .ok_or_else(|| CoreError::internal("Missing title in metadata"))?;
     let worktree_name = json_value
         .get("worktreeName")
         .or_else(|| json_value.get("worktree_name"))
         .and_then(|v| v.as_str())
         .map(sanitize_run_worktree_name)
         .filter(|v| !v.is_empty())
-        .ok_or_else(|| "Missing worktree name in metadata".to_string())?;
+        .ok_or_else(|| CoreError::internal("Missing worktree name in metadata"))?;
 
     Ok(json!({
         "title": title,
@@ -290,7 +296,7 @@ pub(crate) async fn codex_doctor_core(
     app_settings: &Mutex<AppSettings>,
     codex_bin: Option<String>,
     codex_args: Option<String>,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     let (default_bin, default_args) = {
         let settings = app_settings.lock().await;
         (settings.codex_bin.clone(), settings.codex_args.clone())
@@ -401,20 +407,22 @@ pub(crate) async fn run_background_prompt_core<F>(
     on_hide_thread: F,
     timeout_error: &str,
     turn_error_fallback: &str,
-) -> Result<String, String>
+) -> Result<String, CoreError>
 where
     F: Fn(&str, &str),
 {
     let workspace_path = {
         let workspaces = workspaces.lock().await;
-        let entry = workspaces.get(&workspace_id).ok_or("workspace not found")?;
+        let entry = workspaces
+            .get(&workspace_id)
+            .ok_or_else(CoreError::workspace_not_found)?;
         entry.path.clone()
     };
     let session = {
         let sessions = sessions.lock().await;
         sessions
             .get(&workspace_id)
-            .ok_or("workspace not connected")?
+            .ok_or_else(CoreError::workspace_not_connected)?
             .clone()
     };
 
@@ -431,7 +439,7 @@ where
             .get("message")
             .and_then(|m| m.as_str())
             .unwrap_or("Unknown error starting thread");
-        return Err(error_msg.to_string());
+        return Err(CoreError::internal(error_msg));
     }
 
     let thread_id = thread_result
@@ -447,10 +455,10 @@ where
         .or_else(|| thread_result.get("thread").and_then(|t| t.get("id")))
         .and_then(|t| t.as_str())
         .ok_or_else(|| {
-            format!(
+            CoreError::internal(format!(
                 "Failed to get threadId from thread/start response: {:?}",
                 thread_result
-            )
+            ))
         })?
         .to_string();
 
@@ -503,14 +511,16 @@ where
         let _ = session
             .send_request_for_workspace(&workspace_id, "thread/archive", archive_params)
             .await;
-        return Err(error_msg.to_string());
+        return Err(CoreError::internal(error_msg));
     }
 
     let mut response_text = String::new();
     let collect_result = timeout(Duration::from_secs(60), async {
         loop {
             let Some(event) = rx.recv().await else {
-                return Err("Background response stream closed before completion".to_string());
+                return Err(CoreError::internal(
+                    "Background response stream closed before completion",
+                ));
             };
             let method = event.get("method").and_then(|m| m.as_str()).unwrap_or("");
             match method {
@@ -528,7 +538,7 @@ where
                         .and_then(|p| p.get("error"))
                         .and_then(|e| e.as_str())
                         .unwrap_or(turn_error_fallback);
-                    return Err(error_msg.to_string());
+                    return Err(CoreError::internal(error_msg));
                 }
                 _ => {}
             }
@@ -550,12 +560,12 @@ where
     match collect_result {
         Ok(Ok(())) => {}
         Ok(Err(error)) => return Err(error),
-        Err(_) => return Err(timeout_error.to_string()),
+        Err(_) => return Err(CoreError::timeout(timeout_error)),
     }
 
     let trimmed = response_text.trim().to_string();
     if trimmed.is_empty() {
-        return Err("No response was generated".to_string());
+        return Err(CoreError::internal("No response was generated"));
     }
 
     Ok(trimmed)
@@ -569,7 +579,7 @@ pub(crate) async fn generate_commit_message_core<F>(
     template: &str,
     model: Option<&str>,
     on_hide_thread: F,
-) -> Result<String, String>
+) -> Result<String, CoreError>
 where
     F: Fn(&str, &str),
 {
@@ -593,13 +603,13 @@ pub(crate) async fn generate_run_metadata_core<F>(
     workspace_id: String,
     prompt: &str,
     on_hide_thread: F,
-) -> Result<Value, String>
+) -> Result<Value, CoreError>
 where
     F: Fn(&str, &str),
 {
     let cleaned_prompt = prompt.trim();
     if cleaned_prompt.is_empty() {
-        return Err("Prompt is required.".to_string());
+        return Err(CoreError::invalid_params("Prompt is required."));
     }
 
     let metadata_prompt = build_run_metadata_prompt(cleaned_prompt);
@@ -624,13 +634,13 @@ pub(crate) async fn generate_agent_description_core<F>(
     workspace_id: String,
     description: &str,
     on_hide_thread: F,
-) -> Result<GeneratedAgentConfiguration, String>
+) -> Result<GeneratedAgentConfiguration, CoreError>
 where
     F: Fn(&str, &str),
 {
     let cleaned_description = description.trim();
     if cleaned_description.is_empty() {
-        return Err("Description is required.".to_string());
+        return Err(CoreError::invalid_params("Description is required."));
     }
 
     let prompt = build_agent_description_prompt(cleaned_description);
@@ -660,7 +670,7 @@ mod tests {
     fn build_commit_message_prompt_for_diff_requires_changes() {
         let result = build_commit_message_prompt_for_diff("   ", "{diff}");
         assert_eq!(
-            result.expect_err("should fail"),
+            result.expect_err("should fail").message,
             "No changes to generate commit message for"
         );
     }
@@ -679,7 +689,7 @@ mod tests {
         let raw = r#"{"worktreeName":"feat/example"}"#;
         let result = parse_run_metadata_value(raw);
         assert_eq!(
-            result.expect_err("should fail"),
+            result.expect_err("should fail").message,
             "Missing title in metadata"
         );
     }
@@ -16,6 +16,7 @@ use crate::codex::config as codex_config;
 use crate::codex::home::{resolve_default_codex_home, resolve_workspace_codex_home};
 use crate::rules;
 use crate::shared::account::{build_account_response, read_auth_account};
+use crate::shared::core_error::CoreError;
 use crate::types::WorkspaceEntry;
 
 const LOGIN_START_TIMEOUT: Duration = Duration::from_secs(30);
@@ -82,31 +83,45 @@ pub(crate) fn normalize_file_path(raw: &str) -> String {
 }
 
 #[allow(dead_code)]
-pub(crate) fn read_image_as_data_url_core(path: &str) -> Result<String, String> {
+pub(crate) fn read_image_as_data_url_core(path: &str) -> Result<String, CoreError> {
     let trimmed_path = normalize_file_path(path);
     if trimmed_path.is_empty() {
-        return Err("Image path is required".to_string());
+        return Err(CoreError::invalid_params("Image path is required"));
     }
     let mime_type = image_mime_type_for_path(&trimmed_path).ok_or_else(|| {
-        format!("Unsupported or missing image extension for path: {trimmed_path}")
+        CoreError::invalid_params(format!(
+            "Unsupported or missing image extension for path: {trimmed_path}"
+        ))
+    })?;
+    let metadata = std::fs::symlink_metadata(&trimmed_path).map_err(|err| {
+        CoreError::internal(format!(
+            "Failed to stat image file at {trimmed_path}: {err}"
+        ))
     })?;
-    let metadata = std::fs::symlink_metadata(&trimmed_path)
-        .map_err(|err| format!("Failed to stat image file at {trimmed_path}: {err}"))?;
     if metadata.file_type().is_symlink() {
-        return Err(format!("Image path must not be a symlink: {trimmed_path}"));
+        return Err(CoreError::invalid_params(format!(
+            "Image path must not be a symlink: {trimmed_path}"
+        )));
     }
     if !metadata.is_file() {
-        return Err(format!("Image path is not a file: {trimmed_path}"));
+        return Err(CoreError::invalid_params(format!(
+            "Image path is not a file: {trimmed_path}"
+        )));
     }
     if metadata.len() > MAX_INLINE_IMAGE_BYTES {
-        return Err(format!(
+        return Err(CoreError::invalid_params(format!(
             "Image file exceeds maximum size of {MAX_INLINE_IMAGE_BYTES} bytes: {trimmed_path}"
-        ));
+        )));
     }
-    let bytes = std::fs::read(&trimmed_path)
-        .map_err(|err| format!("Failed to read image file at {trimmed_path}: {err}"))?;
+    let bytes = std::fs::read(&trimmed_path).map_err(|err| {
+        CoreError::internal(format!(
+            "Failed to read image file at {trimmed_path}: {err}"
+        ))
+    })?;
     if bytes.is_empty() {
-        return Err(format!("Image file is empty: {trimmed_path}"));
+        return Err(CoreError::invalid_params(format!(
+            "Image file is empty: {trimmed_path}"
+        )));
     }
     let encoded = STANDARD.encode(bytes);
     Ok(format!("data:{mime_type};base64,{encoded}"))
@@ -120,23 +135,23 @@ pub(crate) enum CodexLoginCancelState {
 async fn get_session_clone(
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     workspace_id: &str,
-) -> Result<Arc<WorkspaceSession>, String> {
+) -> Result<Arc<WorkspaceSession>, CoreError> {
     let sessions = sessions.lock().await;
     sessions
         .get(workspace_id)
         .cloned()
-        .ok_or_else(|| "workspace not connected".to_string())
+        .ok_or_else(CoreError::workspace_not_connected)
 }
 
 async fn resolve_workspace_and_parent(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: &str,
-) -> Result<(WorkspaceEntry, Option<WorkspaceEntry>), String> {
+) -> Result<(WorkspaceEntry, Option<WorkspaceEntry>), CoreError> {
     let workspaces = workspaces.lock().await;
     let entry = workspaces
         .get(workspace_id)
         .cloned()
-        .ok_or_else(|| "workspace not found".to_string())?;
+        .ok_or_else(CoreError::workspace_not_found)?;
     let parent_entry = entry
         .parent_id
         .as_ref()
@@ -148,21 +163,21 @@ async fn resolve_workspace_and_parent(
 async fn resolve_codex_home_for_workspace_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: &str,
-) -> Result<PathBuf, String> {
+) -> Result<PathBuf, CoreError> {
     let (entry, parent_entry) = resolve_workspace_and_parent(workspaces, workspace_id).await?;
     resolve_workspace_codex_home(&entry, parent_entry.as_ref())
         .or_else(resolve_default_codex_home)
-        .ok_or_else(|| "Unable to resolve CODEX_HOME".to_string())
+        .ok_or_else(|| CoreError::internal("Unable to resolve CODEX_HOME"))
 }
 
 async fn resolve_workspace_path_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: &str,
-) -> Result<String, String> {
+) -> Result<String, CoreError> {
     let workspaces = workspaces.lock().await;
     let entry = workspaces
         .get(workspace_id)
-        .ok_or_else(|| "workspace not found".to_string())?;
+        .ok_or_else(CoreError::workspace_not_found)?;
     Ok(entry.path.clone())
 }
 
@@ -170,7 +185,7 @@ pub(crate) async fn start_thread_core(
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     let workspace_path = resolve_workspace_path_core(workspaces, &workspace_id).await?;
     let params = json!({
@@ -186,7 +201,7 @@ pub(crate) async fn resume_thread_core(
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     workspace_id: String,
     thread_id: String,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     let params = json!({ "threadId": thread_id });
     session
@@ -198,9 +213,9 @@ pub(crate) async fn thread_live_subscribe_core(
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     workspace_id: String,
     thread_id: String,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     if thread_id.trim().is_empty() {
-        return Err("threadId is required".to_string());
+        return Err(CoreError::invalid_params("threadId is required"));
     }
     let _ = get_session_clone(sessions, &workspace_id).await?;
     Ok(())
@@ -210,9 +225,9 @@ pub(crate) async fn thread_live_unsubscribe_core(
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     workspace_id: String,
     thread_id: String,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     if thread_id.trim().is_empty() {
-        return Err("threadId is required".to_string());
+        return Err(CoreError::invalid_params("threadId is required"));
     }
     let _ = get_session_clone(sessions, &workspace_id).await?;
     Ok(())
@@ -222,7 +237,7 @@ pub(crate) async fn fork_thread_core(
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     workspace_id: String,
     thread_id: String,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     let params = json!({ "threadId": thread_id });
     session
@@ -236,7 +251,7 @@ pub(crate) async fn list_threads_core(
     cursor: Option<String>,
     limit: Option<u32>,
     sort_key: Option<String>,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     let params = json!({
         "cursor": cursor,
@@ -266,7 +281,7 @@ pub(crate) async fn list_mcp_server_status_core(
     workspace_id: String,
     cursor: Option<String>,
     limit: Option<u32>,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     let params = json!({ "cursor": cursor, "limit": limit });
     session
@@ -278,7 +293,7 @@ pub(crate) async fn archive_thread_core(
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     workspace_id: String,
     thread_id: String,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     let params = json!({ "threadId": thread_id });
     session
@@ -290,7 +305,7 @@ pub(crate) async fn compact_thread_core(
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     workspace_id: String,
     thread_id: String,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     let params = json!({ "threadId": thread_id });
     session
@@ -303,7 +318,7 @@ pub(crate) async fn set_thread_name_core(
     workspace_id: String,
     thread_id: String,
     name: String,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     let params = json!({ "threadId": thread_id, "name": name });
     session
@@ -315,7 +330,7 @@ fn build_turn_input_items(
     text: String,
     images: Option<Vec<String>>,
     app_mentions: Option<Vec<Value>>,
-) -> Result<Vec<Value>, String> {
+) -> Result<Vec<Value>, CoreError> {
     let trimmed_text = text.trim();
     let mut input: Vec<Value> = Vec::new();
     if !trimmed_text.is_empty() {
@@ -342,21 +357,21 @@ fn build_turn_input_items(
         for mention in mentions {
             let object = mention
                 .as_object()
-                .ok_or_else(|| "invalid app mention payload".to_string())?;
+                .ok_or_else(|| CoreError::invalid_params("invalid app mention payload"))?;
             let name = object
                 .get("name")
                 .and_then(Value::as_str)
                 .map(str::trim)
                 .filter(|value| !value.is_empty())
-                .ok_or_else(|| "invalid app mention name".to_string())?;
+                .ok_or_else(|| CoreError::invalid_params("invalid app mention name"))?;
             let path = object
                 .get("path")
                 .and_then(Value::as_str)
                 .map(str::trim)
                 .filter(|value| !value.is_empty())
-                .ok_or_else(|| "invalid app mention path".to_string())?;
+                .ok_or_else(|| CoreError::invalid_params("invalid app mention path"))?;
             if !path.starts_with("app://") || path.len() <= "app://".len() {
-                return Err("invalid app mention path".to_string());
+                return Err(CoreError::invalid_params("invalid app mention path"));
             }
             if !seen_paths.insert(path.to_string()) {
                 continue;
@@ -365,7 +380,7 @@ fn build_turn_input_items(
         }
     }
     if input.is_empty() {
-        return Err("empty user message".to_string());
+        return Err(CoreError::invalid_params("empty user message"));
     }
     Ok(input)
 }
@@ -382,14 +397,14 @@ fn build_bang_command_args(command: &str) -> Vec<String> {
     vec![shell, "-lc".to_string(), command.to_string()]
 }
 
-fn parse_bang_command_result(response: &Value) -> Result<Value, String> {
+fn parse_bang_command_result(response: &Value) -> Result<Value, CoreError> {
     if let Some(error) = response.get("error") {
         let message = error
             .get("message")
             .and_then(Value::as_str)
             .map(str::to_string)
             .unwrap_or_else(|| "Command execution failed".to_string());
-        return Err(message);
+        return Err(CoreError::internal(message));
     }
 
     let payload = response.get("result").unwrap_or(response);
@@ -397,7 +412,7 @@ fn parse_bang_command_result(response: &Value) -> Result<Value, String> {
         .get("exitCode")
         .or_else(|| payload.get("exit_code"))
         .and_then(Value::as_i64)
-        .ok_or_else(|| "missing exitCode in command/exec response".to_string())?;
+        .ok_or_else(|| CoreError::internal("missing exitCode in command/exec response"))?;
     let stdout = payload
         .get("stdout")
         .and_then(Value::as_str)
@@ -428,7 +443,7 @@ pub(crate) async fn send_user_message_core(
     images: Option<Vec<String>>,
     app_mentions: Option<Vec<Value>>,
     collaboration_mode: Option<Value>,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     let workspace_path = resolve_workspace_path_core(workspaces, &workspace_id).await?;
     let access_mode = access_mode.unwrap_or_else(|| "current".to_string());
@@ -473,10 +488,10 @@ pub(crate) async fn run_bang_command_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
     command: String,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     let trimmed = command.trim();
     if trimmed.is_empty() {
-        return Err("empty command".to_string());
+        return Err(CoreError::invalid_params("empty command"));
     }
 
     let session = get_session_clone(sessions, &workspace_id).await?;
@@ -502,9 +517,9 @@ pub(crate) async fn turn_steer_core(
     text: String,
     images: Option<Vec<String>>,
     app_mentions: Option<Vec<Value>>,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     if turn_id.trim().is_empty() {
-        return Err("missing active turn id".to_string());
+        return Err(CoreError::invalid_params("missing active turn id"));
     }
     let session = get_session_clone(sessions, &workspace_id).await?;
     let input = build_turn_input_items(text, images, app_mentions)?;
@@ -521,7 +536,7 @@ pub(crate) async fn turn_steer_core(
 pub(crate) async fn collaboration_mode_list_core(
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     workspace_id: String,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     session
         .send_request_for_workspace(&workspace_id, "collaborationMode/list", json!({}))
@@ -533,7 +548,7 @@ pub(crate) async fn turn_interrupt_core(
     workspace_id: String,
     thread_id: String,
     turn_id: String,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     let params = json!({ "threadId": thread_id, "turnId": turn_id });
     session
@@ -547,7 +562,7 @@ pub(crate) async fn start_review_core(
     thread_id: String,
     target: Value,
     delivery: Option<String>,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     let mut params = Map::new();
     params.insert("threadId".to_string(), json!(thread_id));
@@ -563,7 +578,7 @@ pub(crate) async fn start_review_core(
 pub(crate) async fn model_list_core(
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     workspace_id: String,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     session
         .send_request_for_workspace(&workspace_id, "model/list", json!({}))
@@ -575,7 +590,7 @@ pub(crate) async fn experimental_feature_list_core(
     workspace_id: String,
     cursor: Option<String>,
     limit: Option<u32>,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     let params = json!({ "cursor": cursor, "limit": limit });
     session
@@ -586,7 +601,7 @@ pub(crate) async fn experimental_feature_list_core(
 pub(crate) async fn account_rate_limits_core(
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     workspace_id: String,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     session
         .send_request_for_workspace(&workspace_id, "account/rateLimits/read", Value::Null)
@@ -597,7 +612,7 @@ pub(crate) async fn account_read_core(
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     let session = {
         let sessions = sessions.lock().await;
         sessions.get(&workspace_id).cloned()
@@ -623,7 +638,7 @@ pub(crate) async fn codex_login_core(
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     codex_login_cancels: &Mutex<HashMap<String, CodexLoginCancelState>>,
     workspace_id: String,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     let (cancel_tx, cancel_rx) = oneshot::channel::<()>();
     {
@@ -656,12 +671,12 @@ pub(crate) async fn codex_login_core(
             Ok(_) => {
                 let mut cancels = codex_login_cancels.lock().await;
                 cancels.remove(&workspace_id);
-                return Err("Codex login canceled.".to_string());
+                return Err(CoreError::internal("Codex login canceled."));
             }
             Err(TryRecvError::Closed) => {
                 let mut cancels = codex_login_cancels.lock().await;
                 cancels.remove(&workspace_id);
-                return Err("Codex login canceled.".to_string());
+                return Err(CoreError::internal("Codex login canceled."));
             }
             Err(TryRecvError::Empty) => {}
         }
@@ -670,7 +685,7 @@ pub(crate) async fn codex_login_core(
         if elapsed >= LOGIN_START_TIMEOUT {
             let mut cancels = codex_login_cancels.lock().await;
             cancels.remove(&workspace_id);
-            return Err("Codex login start timed out.".to_string());
+            return Err(CoreError::timeout("Codex login start timed out."));
         }
 
         let tick = Duration::from_millis(150);
@@ -689,13 +704,13 @@ pub(crate) async fn codex_login_core(
         .or_else(|| payload.get("login_id"))
         .and_then(Value::as_str)
         .map(str::to_string)
-        .ok_or_else(|| "missing login id in account/login/start response".to_string())?;
+        .ok_or_else(|| CoreError::internal("missing login id in account/login/start response"))?;
     let auth_url = payload
         .get("authUrl")
         .or_else(|| payload.get("auth_url"))
         .and_then(Value::as_str)
         .map(str::to_string)
-        .ok_or_else(|| "missing auth url in account/login/start response".to_string())?;
+        .ok_or_else(|| CoreError::internal("missing auth url in account/login/start response"))?;
 
     {
         let mut cancels = codex_login_cancels.lock().await;
@@ -716,7 +731,7 @@ pub(crate) async fn codex_login_cancel_core(
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     codex_login_cancels: &Mutex<HashMap<String, CodexLoginCancelState>>,
     workspace_id: String,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     let cancel_state = {
         let mut cancels = codex_login_cancels.lock().await;
         cancels.remove(&workspace_id)
@@ -766,7 +781,7 @@ pub(crate) async fn skills_list_core(
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     let workspace_path = resolve_workspace_path_core(workspaces, &workspace_id).await?;
     let params = json!({ "cwd": workspace_path });
@@ -781,7 +796,7 @@ pub(crate) async fn apps_list_core(
     cursor: Option<String>,
     limit: Option<u32>,
     thread_id: Option<String>,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     let params = json!({ "cursor": cursor, "limit": limit, "threadId": thread_id });
     session
@@ -794,7 +809,7 @@ pub(crate) async fn respond_to_server_request_core(
     workspace_id: String,
     request_id: Value,
     result: Value,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     let session = get_session_clone(sessions, &workspace_id).await?;
     session.send_response(request_id, result).await
 }
@@ -803,14 +818,14 @@ pub(crate) async fn remember_approval_rule_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
     command: Vec<String>,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     let command = command
         .into_iter()
         .map(|item| item.trim().to_string())
         .filter(|item| !item.is_empty())
         .collect::<Vec<_>>();
     if command.is_empty() {
-        return Err("empty command".to_string());
+        return Err(CoreError::invalid_params("empty command"));
     }
 
     let codex_home = resolve_codex_home_for_workspace_core(workspaces, &workspace_id).await?;
@@ -826,7 +841,7 @@ pub(crate) async fn remember_approval_rule_core(
 pub(crate) async fn get_config_model_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     let codex_home = resolve_codex_home_for_workspace_core(workspaces, &workspace_id).await?;
     let model = codex_config::read_config_model(Some(codex_home))?;
     Ok(json!({ "model": model }))
@@ -929,7 +944,7 @@ mod tests {
             "error": { "message": "denied" }
         });
         let err = parse_bang_command_result(&response).expect_err("expected error");
-        assert_eq!(err, "denied");
+        assert_eq!(err.message, "denied");
     }
 
     #[test]
@@ -938,10 +953,10 @@ mod tests {
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert!(
-            !err.contains("file://"),
+            !err.message.contains("file://"),
             "error should reference normalized path, got: {err}"
         );
-        assert!(err.contains("/nonexistent/photo.png"));
+        assert!(err.message.contains("/nonexistent/photo.png"));
     }
 
     #[test]
@@ -0,0 +1,232 @@
+#![allow(dead_code)]
+
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::shared::core_error::CoreError;
+
+pub(crate) fn to_params<T: Serialize>(request: &T) -> Result<Value, CoreError> {
+    serde_json::to_value(request).map_err(|err| CoreError::internal(err.to_string()))
+}
+
+/// Missing params are treated as an empty object so requests whose fields are
+/// all optional accept `null`.
+pub(crate) fn from_params<T: DeserializeOwned>(params: &Value) -> Result<T, CoreError> {
+    let params = if params.is_null() {
+        Value::Object(Default::default())
+    } else {
+        params.clone()
+    };
+    serde_json::from_value(params).map_err(|err| CoreError::invalid_params(err.to_string()))
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WorkspaceIdRequest {
+    pub(crate) workspace_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OptionalWorkspaceIdRequest {
+    pub(crate) workspace_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ThreadRequest {
+    pub(crate) workspace_id: String,
+    pub(crate) thread_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ListThreadsRequest {
+    pub(crate) workspace_id: String,
+    pub(crate) cursor: Option<String>,
+    pub(crate) limit: Option<u32>,
+    pub(crate) sort_key: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WorkspacePageRequest {
+    pub(crate) workspace_id: String,
+    pub(crate) cursor: Option<String>,
+    pub(crate) limit: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SetThreadNameRequest {
+    pub(crate) workspace_id: String,
+    pub(crate) thread_id: String,
+    pub(crate) name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SendUserMessageRequest {
+    pub(crate) workspace_id: String,
+    pub(crate) thread_id: String,
+    pub(crate) text: String,
+    pub(crate) model: Option<String>,
+    pub(crate) effort: Option<String>,
+    pub(crate) access_mode: Option<String>,
+    pub(crate) images: Option<Vec<String>>,
+    pub(crate) app_mentions: Option<Vec<Value>>,
+    pub(crate) collaboration_mode: Option<Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RunBangCommandRequest {
+    pub(crate) workspace_id: String,
+    pub(crate) command: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TurnInterruptRequest {
+    pub(crate) workspace_id: String,
+    pub(crate) thread_id: String,
+    pub(crate) turn_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TurnSteerRequest {
+    pub(crate) workspace_id: String,
+    pub(crate) thread_id: String,
+    pub(crate) turn_id: String,
+    pub(crate) text: String,
+    pub(crate) images: Option<Vec<String>>,
+    pub(crate) app_mentions: Option<Vec<Value>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct StartReviewRequest {
+    pub(crate) workspace_id: String,
+    pub(crate) thread_id: String,
+    pub(crate) target: Value,
+    pub(crate) delivery: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SetCodexFeatureFlagRequest {
+    pub(crate) feature_key: String,
+    pub(crate) enabled: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub(crate) struct InputRequest<T> {
+    pub(crate) input: T,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AgentNameRequest {
+    pub(crate) agent_name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WriteAgentConfigTomlRequest {
+    pub(crate) agent_name: String,
+    pub(crate) content: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DictationTranscribeRequest {
+    pub(crate) workspace_id: String,
+    pub(crate) audio: String,
+    pub(crate) mime_type: String,
+    pub(crate) language: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AppsListRequest {
+    pub(crate) workspace_id: String,
+    pub(crate) cursor: Option<String>,
+    pub(crate) limit: Option<u32>,
+    pub(crate) thread_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RespondToServerRequestRequest {
+    pub(crate) workspace_id: String,
+    pub(crate) request_id: Value,
+    pub(crate) result: Value,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RememberApprovalRuleRequest {
+    pub(crate) workspace_id: String,
+    pub(crate) command: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CodexDoctorRequest {
+    pub(crate) codex_bin: Option<String>,
+    pub(crate) codex_args: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GenerateRunMetadataRequest {
+    pub(crate) workspace_id: String,
+    pub(crate) prompt: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GenerateAgentDescriptionRequest {
+    pub(crate) workspace_id: String,
+    pub(crate) description: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PromptsCreateRequest {
+    pub(crate) workspace_id: String,
+    pub(crate) scope: String,
+    pub(crate) name: String,
+    pub(crate) description: Option<String>,
+    pub(crate) argument_hint: Option<String>,
+    pub(crate) content: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PromptsUpdateRequest {
+    pub(crate) workspace_id: String,
+    pub(crate) path: String,
+    pub(crate) name: String,
+    pub(crate) description: Option<String>,
+    pub(crate) argument_hint: Option<String>,
+    pub(crate) content: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PromptPathRequest {
+    pub(crate) workspace_id: String,
+    pub(crate) path: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PromptsMoveRequest {
+    pub(crate) workspace_id: String,
+    pub(crate) path: String,
+    pub(crate) scope: String,
+}
@@ -7,6 +7,7 @@ use tokio::sync::Mutex;
 use tokio::time::timeout;
 
 use crate::backend::app_server::check_codex_installation;
+use crate::shared::core_error::CoreError;
 use crate::shared::process_core::tokio_command;
 use crate::types::AppSettings;
 
@@ -34,7 +35,7 @@ fn trim_lines(value: &str, max_len: usize) -> String {
     shortened
 }
 
-async fn run_brew_check(args: &[&str]) -> Result<bool, String> {
+async fn run_brew_check(args: &[&str]) -> Result<bool, CoreError> {
     let mut command = tokio_command("brew");
     command.args(args);
     command.stdout(std::process::Stdio::piped());
@@ -47,7 +48,7 @@ async fn run_brew_check(args: &[&str]) -> Result<bool, String> {
                 if err.kind() == std::io::ErrorKind::NotFound {
                     return Ok(false);
                 }
-                return Err(err.to_string());
+                return Err(CoreError::internal(err.to_string()));
             }
         },
         Err(_) => return Ok(false),
@@ -56,15 +57,15 @@ async fn run_brew_check(args: &[&str]) -> Result<bool, String> {
     Ok(output.status.success())
 }
 
-async fn detect_brew_cask(name: &str) -> Result<bool, String> {
+async fn detect_brew_cask(name: &str) -> Result<bool, CoreError> {
     run_brew_check(&["list", "--cask", "--versions", name]).await
 }
 
-async fn detect_brew_formula(name: &str) -> Result<bool, String> {
+async fn detect_brew_formula(name: &str) -> Result<bool, CoreError> {
     run_brew_check(&["list", "--formula", "--versions", name]).await
 }
 
-async fn run_brew_upgrade(args: &[&str]) -> Result<(bool, String), String> {
+async fn run_brew_upgrade(args: &[&str]) -> Result<(bool, String), CoreError> {
     let mut command = tokio_command("brew");
     command.arg("upgrade");
     command.args(args);
@@ -73,7 +74,11 @@ async fn run_brew_upgrade(args: &[&str]) -> Result<(bool, String), String> {
 
     let output = match timeout(Duration::from_secs(60 * 10), command.output()).await {
         Ok(result) => result.map_err(|err| err.to_string())?,
-        Err(_) => return Err("Timed out while running `brew upgrade`.".to_string()),
+        Err(_) => {
+            return Err(CoreError::timeout(
+                "Timed out while running `brew upgrade`.",
+            ))
+        }
     };
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -99,7 +104,7 @@ fn brew_output_indicates_upgrade(output: &str) -> bool {
     false
 }
 
-async fn npm_has_package(package: &str) -> Result<bool, String> {
+async fn npm_has_package(package: &str) -> Result<bool, CoreError> {
     let mut command = tokio_command("npm");
     command.arg("list");
     command.arg("-g");
@@ -115,7 +120,7 @@ async fn npm_has_package(package: &str) -> Result<bool, String> {
                 if err.kind() == std::io::ErrorKind::NotFound {
                     return Ok(false);
                 }
-                return Err(err.to_string());
+                return Err(CoreError::internal(err.to_string()));
             }
         },
         Err(_) => return Ok(false),
@@ -124,7 +129,7 @@ async fn npm_has_package(package: &str) -> Result<bool, String> {
     Ok(output.status.success())
 }
 
-async fn run_npm_install_latest(package: &str) -> Result<(bool, String), String> {
+async fn run_npm_install_latest(package: &str) -> Result<(bool, String), CoreError> {
     let mut command = tokio_command("npm");
     command.arg("install");
     command.arg("-g");
@@ -134,7 +139,11 @@ async fn run_npm_install_latest(package: &str) -> Result<(bool, String), String>
 
     let output = match timeout(Duration::from_secs(60 * 10), command.output()).await {
         Ok(result) => result.map_err(|err| err.to_string())?,
-        Err(_) => return Err("Timed out while running `npm install -g`.".to_string()),
+        Err(_) => {
+            return Err(CoreError::timeout(
+                "Timed out while running `npm install -g`.",
+            ))
+        }
     };
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -147,7 +156,7 @@ pub(crate) async fn codex_update_core(
     app_settings: &Mutex<AppSettings>,
     codex_bin: Option<String>,
     codex_args: Option<String>,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     let (default_bin, default_args) = {
         let settings = app_settings.lock().await;
         (settings.codex_bin.clone(), settings.codex_args.clone())
@@ -216,7 +225,8 @@ pub(crate) async fn codex_update_core(
                     output: Some(trim_lines(&output, 8000)),
                     details: Some(err),
                 };
-                return serde_json::to_value(result).map_err(|e| e.to_string());
+                return serde_json::to_value(result)
+                    .map_err(|e| CoreError::internal(e.to_string()));
             }
         }
     };
@@ -240,5 +250,5 @@ pub(crate) async fn codex_update_core(
         details,
     };
 
-    serde_json::to_value(result).map_err(|err| err.to_string())
+    serde_json::to_value(result).map_err(|err| CoreError::internal(err.to_string()))
 }
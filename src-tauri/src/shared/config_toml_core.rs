@@ -4,8 +4,11 @@ use toml_edit::{value, Document, Item, Table};
 
 use crate::files::ops::{read_with_policy, write_with_policy};
 use crate::files::policy::{policy_for, FileKind, FileScope};
+use crate::shared::core_error::CoreError;
 
-pub(crate) fn load_global_config_document(codex_home: &Path) -> Result<(bool, Document), String> {
+pub(crate) fn load_global_config_document(
+    codex_home: &Path,
+) -> Result<(bool, Document), CoreError> {
     let policy = policy_for(FileScope::Global, FileKind::Config)?;
     let root = codex_home.to_path_buf();
     let response = read_with_policy(&root, policy)?;
@@ -20,35 +23,35 @@ pub(crate) fn load_global_config_document(codex_home: &Path) -> Result<(bool, Do
 pub(crate) fn persist_global_config_document(
     codex_home: &Path,
     document: &Document,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     let policy = policy_for(FileScope::Global, FileKind::Config)?;
     let root = codex_home.to_path_buf();
     let mut rendered = document.to_string();
     if !rendered.ends_with('\n') {
         rendered.push('\n');
     }
-    write_with_policy(&root, policy, rendered.as_str())
+    Ok(write_with_policy(&root, policy, rendered.as_str())?)
 }
 
-pub(crate) fn parse_document(contents: &str) -> Result<Document, String> {
+pub(crate) fn parse_document(contents: &str) -> Result<Document, CoreError> {
     if contents.trim().is_empty() {
         return Ok(Document::new());
     }
     contents
         .parse::<Document>()
-        .map_err(|err| format!("Failed to parse config.toml: {err}"))
+        .map_err(|err| CoreError::internal(format!("Failed to parse config.toml: {err}")))
 }
 
 pub(crate) fn ensure_table<'a>(
     document: &'a mut Document,
     key: &str,
-) -> Result<&'a mut Table, String> {
+) -> Result<&'a mut Table, CoreError> {
     if document.get(key).is_none() {
         document[key] = Item::Table(Table::new());
     }
     document[key]
         .as_table_mut()
-        .ok_or_else(|| format!("`{key}` must be a table in config.toml"))
+        .ok_or_else(|| CoreError::internal(format!("`{key}` must be a table in config.toml")))
 }
 
 pub(crate) fn read_feature_flag(document: &Document, key: &str) -> Option<bool> {
@@ -63,7 +66,7 @@ pub(crate) fn set_feature_flag(
     document: &mut Document,
     key: &str,
     enabled: bool,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     let features = ensure_table(document, "features")?;
     features[key] = value(enabled);
     Ok(())
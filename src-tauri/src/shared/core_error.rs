@@ -0,0 +1,255 @@
+#![allow(dead_code)]
+
+//! Errors with a stable machine-readable code, shared by the cores, the daemon
+//! and its clients. On the wire they become the JSON-RPC `error` object:
+//! `{"code": -32011, "message": "workspace not connected", "data": {...}}`.
+
+use std::fmt;
+
+use serde_json::{json, Map, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ErrorCode {
+    /// Anything without a more specific code.
+    Internal,
+    InvalidParams,
+    UnknownMethod,
+    Unauthorized,
+    InvalidToken,
+    TokenRevoked,
+    Forbidden,
+    WorkspaceNotFound,
+    WorkspaceNotConnected,
+    GitNotInstalled,
+    MissingWorktree,
+    Timeout,
+    /// Raised by clients when the daemon connection drops; never sent by the daemon.
+    Disconnected,
+    /// The daemon is draining and only serves read-only methods.
+    ShuttingDown,
+}
+
+impl ErrorCode {
+    const ALL: [ErrorCode; 14] = [
+        ErrorCode::Internal,
+        ErrorCode::InvalidParams,
+        ErrorCode::UnknownMethod,
+        ErrorCode::Unauthorized,
+        ErrorCode::InvalidToken,
+        ErrorCode::TokenRevoked,
+        ErrorCode::Forbidden,
+        ErrorCode::WorkspaceNotFound,
+        ErrorCode::WorkspaceNotConnected,
+        ErrorCode::GitNotInstalled,
+        ErrorCode::MissingWorktree,
+        ErrorCode::Timeout,
+        ErrorCode::Disconnected,
+        ErrorCode::ShuttingDown,
+    ];
+
+    /// The JSON-RPC `error.code`. Standard codes where JSON-RPC defines one,
+    /// the server range (-32000 to -32099) otherwise. Never renumber these.
+    pub(crate) fn as_i64(self) -> i64 {
+        match self {
+            Self::Internal => -32603,
+            Self::InvalidParams => -32602,
+            Self::UnknownMethod => -32601,
+            Self::Unauthorized => -32001,
+            Self::InvalidToken => -32002,
+            Self::TokenRevoked => -32003,
+            Self::Forbidden => -32004,
+            Self::WorkspaceNotFound => -32010,
+            Self::WorkspaceNotConnected => -32011,
+            Self::GitNotInstalled => -32020,
+            Self::MissingWorktree => -32021,
+            Self::Timeout => -32030,
+            Self::Disconnected => -32031,
+            Self::ShuttingDown => -32032,
+        }
+    }
+
+    pub(crate) fn from_i64(code: i64) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|candidate| candidate.as_i64() == code)
+    }
+
+    /// True when the peer rejected the connection's credentials.
+    pub(crate) fn is_auth_failure(self) -> bool {
+        matches!(
+            self,
+            Self::Unauthorized | Self::InvalidToken | Self::TokenRevoked
+        )
+    }
+}
+
+pub(crate) const WORKSPACE_NOT_FOUND: &str = "workspace not found";
+pub(crate) const WORKSPACE_NOT_CONNECTED: &str = "workspace not connected";
+pub(crate) const DISCONNECTED: &str = "remote backend disconnected";
+pub(crate) const SHUTTING_DOWN: &str = "daemon is shutting down";
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct CoreError {
+    pub(crate) code: ErrorCode,
+    pub(crate) message: String,
+    pub(crate) data: Option<Value>,
+}
+
+impl CoreError {
+    pub(crate) fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    pub(crate) fn with_data(mut self, data: Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    pub(crate) fn workspace_not_found() -> Self {
+        Self::new(ErrorCode::WorkspaceNotFound, WORKSPACE_NOT_FOUND)
+    }
+
+    pub(crate) fn workspace_not_connected() -> Self {
+        Self::new(ErrorCode::WorkspaceNotConnected, WORKSPACE_NOT_CONNECTED)
+    }
+
+    pub(crate) fn disconnected() -> Self {
+        Self::new(ErrorCode::Disconnected, DISCONNECTED)
+    }
+
+    pub(crate) fn shutting_down() -> Self {
+        Self::new(ErrorCode::ShuttingDown, SHUTTING_DOWN)
+    }
+
+    pub(crate) fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+
+    pub(crate) fn invalid_params(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidParams, message)
+    }
+
+    pub(crate) fn timeout(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Timeout, message)
+    }
+
+    pub(crate) fn unknown_method(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::UnknownMethod, message)
+    }
+
+    /// The JSON-RPC `error` object.
+    pub(crate) fn to_value(&self) -> Value {
+        let mut error = Map::new();
+        error.insert("code".to_string(), json!(self.code.as_i64()));
+        error.insert("message".to_string(), json!(self.message));
+        if let Some(data) = &self.data {
+            error.insert("data".to_string(), data.clone());
+        }
+        Value::Object(error)
+    }
+
+    /// Reads a JSON-RPC `error` object. Errors with codes this build does not
+    /// know are `Internal`. Daemons that predate codes send bare messages; the
+    /// two auth rejections they used are mapped so upgrades still recognize
+    /// an old daemon, everything else from them is `Internal`.
+    pub(crate) fn from_value(error: &Value) -> Self {
+        let message = error
+            .get("message")
+            .and_then(Value::as_str)
+            .or_else(|| error.as_str())
+            .unwrap_or("remote error")
+            .to_string();
+        let code = match error.get("code") {
+            Some(code) => code
+                .as_i64()
+                .and_then(ErrorCode::from_i64)
+                .unwrap_or(ErrorCode::Internal),
+            None => legacy_code(&message),
+        };
+        Self {
+            code,
+            message,
+            data: error.get("data").filter(|data| !data.is_null()).cloned(),
+        }
+    }
+}
+
+fn legacy_code(message: &str) -> ErrorCode {
+    match message.trim() {
+        "unauthorized" => ErrorCode::Unauthorized,
+        "invalid token" => ErrorCode::InvalidToken,
+        _ => ErrorCode::Internal,
+    }
+}
+
+impl fmt::Display for CoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for CoreError {}
+
+/// Lets `?` lift the `String` errors of helpers that only have a message to
+/// give, such as I/O and serde failures. Those are always `Internal`.
+impl From<String> for CoreError {
+    fn from(message: String) -> Self {
+        Self::internal(message)
+    }
+}
+
+/// Lets typed errors flow through `Result<_, String>` callers unchanged.
+impl From<CoreError> for String {
+    fn from(error: CoreError) -> Self {
+        error.message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_round_trip_through_the_wire_format() {
+        for code in ErrorCode::ALL {
+            assert_eq!(ErrorCode::from_i64(code.as_i64()), Some(code));
+        }
+        let error = CoreError::new(ErrorCode::MissingWorktree, "gone")
+            .with_data(json!({ "path": "/tmp/wt" }));
+        let value = error.to_value();
+        assert_eq!(value["code"], json!(-32021));
+        assert_eq!(CoreError::from_value(&value), error);
+        assert!(CoreError::workspace_not_found()
+            .to_value()
+            .get("data")
+            .is_none());
+    }
+
+    #[test]
+    fn uncoded_errors_are_internal_except_legacy_auth_rejections() {
+        let legacy = CoreError::from_value(&json!({ "message": "invalid token" }));
+        assert_eq!(legacy.code, ErrorCode::InvalidToken);
+        assert_eq!(legacy.message, "invalid token");
+        assert_eq!(
+            CoreError::from_value(&json!({ "message": "unauthorized" })).code,
+            ErrorCode::Unauthorized
+        );
+        assert_eq!(
+            CoreError::from_value(&json!({ "message": "unauthorized: token expired" })).code,
+            ErrorCode::Internal
+        );
+        assert_eq!(
+            CoreError::from_value(&json!({ "code": 1, "message": "workspace not connected" })).code,
+            ErrorCode::Internal
+        );
+        assert_eq!(
+            CoreError::from("request timed out after 300 seconds".to_string()).code,
+            ErrorCode::Internal
+        );
+        assert_eq!(String::from(CoreError::disconnected()), DISCONNECTED);
+    }
+}
@@ -7,21 +7,22 @@ use crate::codex::home as codex_home;
 use crate::files::io::TextFileResponse;
 use crate::files::ops::{read_with_policy, write_with_policy};
 use crate::files::policy::{policy_for, FileKind, FileScope};
+use crate::shared::core_error::CoreError;
 use crate::types::WorkspaceEntry;
 
-fn resolve_default_codex_home() -> Result<PathBuf, String> {
+fn resolve_default_codex_home() -> Result<PathBuf, CoreError> {
     codex_home::resolve_default_codex_home()
-        .ok_or_else(|| "Unable to resolve CODEX_HOME".to_string())
+        .ok_or_else(|| CoreError::internal("Unable to resolve CODEX_HOME"))
 }
 
 async fn resolve_workspace_root(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: &str,
-) -> Result<PathBuf, String> {
+) -> Result<PathBuf, CoreError> {
     let workspaces = workspaces.lock().await;
     let entry = workspaces
         .get(workspace_id)
-        .ok_or_else(|| "workspace not found".to_string())?;
+        .ok_or_else(CoreError::workspace_not_found)?;
     Ok(PathBuf::from(&entry.path))
 }
 
@@ -29,11 +30,12 @@ pub(crate) async fn resolve_root_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     scope: FileScope,
     workspace_id: Option<&str>,
-) -> Result<PathBuf, String> {
+) -> Result<PathBuf, CoreError> {
     match scope {
         FileScope::Global => resolve_default_codex_home(),
         FileScope::Workspace => {
-            let workspace_id = workspace_id.ok_or_else(|| "workspaceId is required".to_string())?;
+            let workspace_id =
+                workspace_id.ok_or_else(|| CoreError::invalid_params("workspaceId is required"))?;
             resolve_workspace_root(workspaces, workspace_id).await
         }
     }
@@ -44,10 +46,10 @@ pub(crate) async fn file_read_core(
     scope: FileScope,
     kind: FileKind,
     workspace_id: Option<String>,
-) -> Result<TextFileResponse, String> {
+) -> Result<TextFileResponse, CoreError> {
     let policy = policy_for(scope, kind)?;
     let root = resolve_root_core(workspaces, scope, workspace_id.as_deref()).await?;
-    read_with_policy(&root, policy)
+    Ok(read_with_policy(&root, policy)?)
 }
 
 pub(crate) async fn file_write_core(
@@ -56,8 +58,8 @@ pub(crate) async fn file_write_core(
     kind: FileKind,
     workspace_id: Option<String>,
     content: String,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     let policy = policy_for(scope, kind)?;
     let root = resolve_root_core(workspaces, scope, workspace_id.as_deref()).await?;
-    write_with_policy(&root, policy, &content)
+    Ok(write_with_policy(&root, policy, &content)?)
 }
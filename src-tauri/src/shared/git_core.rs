@@ -1,9 +1,26 @@
 use std::path::PathBuf;
 
+use crate::shared::core_error::{CoreError, ErrorCode};
 use crate::shared::process_core::tokio_command;
 use crate::utils::{git_env_path, resolve_git_binary};
 
-fn format_git_error(stdout: &[u8], stderr: &[u8]) -> String {
+/// The git binary, or `GitNotInstalled` when none is on `PATH`.
+pub(crate) fn git_binary() -> Result<PathBuf, CoreError> {
+    resolve_git_binary().map_err(|err| {
+        CoreError::new(
+            ErrorCode::GitNotInstalled,
+            format!("Failed to run git: {err}"),
+        )
+    })
+}
+
+pub(crate) fn git_spawn_error(err: std::io::Error) -> CoreError {
+    CoreError::internal(format!("Failed to run git: {err}"))
+}
+
+/// A failed git command. Git only reports failures as text, so this is where a
+/// path that is no longer a worktree becomes `MissingWorktree`.
+pub(crate) fn git_command_error(stdout: &[u8], stderr: &[u8]) -> CoreError {
     let stderr = String::from_utf8_lossy(stderr);
     let stdout = String::from_utf8_lossy(stdout);
     let detail = if stderr.trim().is_empty() {
@@ -12,31 +29,36 @@ fn format_git_error(stdout: &[u8], stderr: &[u8]) -> String {
         stderr.trim()
     };
     if detail.is_empty() {
-        "Git command failed.".to_string()
+        CoreError::internal("Git command failed.")
+    } else if detail.contains("is not a working tree") {
+        CoreError::new(ErrorCode::MissingWorktree, detail)
     } else {
-        detail.to_string()
+        CoreError::internal(detail)
     }
 }
 
-pub(crate) async fn run_git_command(repo_path: &PathBuf, args: &[&str]) -> Result<String, String> {
-    let git_bin = resolve_git_binary().map_err(|err| format!("Failed to run git: {err}"))?;
+pub(crate) async fn run_git_command(
+    repo_path: &PathBuf,
+    args: &[&str],
+) -> Result<String, CoreError> {
+    let git_bin = git_binary()?;
     let output = tokio_command(git_bin)
         .args(args)
         .current_dir(repo_path)
         .env("PATH", git_env_path())
         .output()
         .await
-        .map_err(|err| format!("Failed to run git: {err}"))?;
+        .map_err(git_spawn_error)?;
     if output.status.success() {
         return Ok(String::from_utf8_lossy(&output.stdout).trim().to_string());
     }
-    Err(format_git_error(&output.stdout, &output.stderr))
+    Err(git_command_error(&output.stdout, &output.stderr))
 }
 
 pub(crate) async fn run_git_command_owned(
     repo_path: PathBuf,
     args_owned: Vec<String>,
-) -> Result<String, String> {
+) -> Result<String, CoreError> {
     let arg_refs = args_owned
         .iter()
         .map(|value| value.as_str())
@@ -47,61 +69,63 @@ pub(crate) async fn run_git_command_owned(
 pub(crate) async fn run_git_command_bytes(
     repo_path: &PathBuf,
     args: &[&str],
-) -> Result<Vec<u8>, String> {
-    let git_bin = resolve_git_binary().map_err(|err| format!("Failed to run git: {err}"))?;
+) -> Result<Vec<u8>, CoreError> {
+    let git_bin = git_binary()?;
     let output = tokio_command(git_bin)
         .args(args)
         .current_dir(repo_path)
         .env("PATH", git_env_path())
         .output()
         .await
-        .map_err(|err| format!("Failed to run git: {err}"))?;
+        .map_err(git_spawn_error)?;
     if output.status.success() {
         return Ok(output.stdout);
     }
-    Err(format_git_error(&output.stdout, &output.stderr))
+    Err(git_command_error(&output.stdout, &output.stderr))
 }
 
-pub(crate) async fn run_git_diff(repo_path: &PathBuf, args: &[&str]) -> Result<Vec<u8>, String> {
-    let git_bin = resolve_git_binary().map_err(|err| format!("Failed to run git: {err}"))?;
+pub(crate) async fn run_git_diff(repo_path: &PathBuf, args: &[&str]) -> Result<Vec<u8>, CoreError> {
+    let git_bin = git_binary()?;
     let output = tokio_command(git_bin)
         .args(args)
         .current_dir(repo_path)
         .env("PATH", git_env_path())
         .output()
         .await
-        .map_err(|err| format!("Failed to run git: {err}"))?;
+        .map_err(git_spawn_error)?;
     if output.status.success() || output.status.code() == Some(1) {
         return Ok(output.stdout);
     }
-    Err(format_git_error(&output.stdout, &output.stderr))
-}
-
-pub(crate) fn is_missing_worktree_error(error: &str) -> bool {
-    error.contains("is not a working tree")
+    Err(git_command_error(&output.stdout, &output.stderr))
 }
 
-pub(crate) async fn git_branch_exists(repo_path: &PathBuf, branch: &str) -> Result<bool, String> {
-    let git_bin = resolve_git_binary().map_err(|err| format!("Failed to run git: {err}"))?;
+pub(crate) async fn git_branch_exists(
+    repo_path: &PathBuf,
+    branch: &str,
+) -> Result<bool, CoreError> {
+    let git_bin = git_binary()?;
     let status = tokio_command(git_bin)
         .args(["show-ref", "--verify", &format!("refs/heads/{branch}")])
         .current_dir(repo_path)
         .env("PATH", git_env_path())
         .status()
         .await
-        .map_err(|err| format!("Failed to run git: {err}"))?;
+        .map_err(git_spawn_error)?;
     Ok(status.success())
 }
 
-pub(crate) async fn git_remote_exists(repo_path: &PathBuf, remote: &str) -> Result<bool, String> {
-    let git_bin = resolve_git_binary().map_err(|err| format!("Failed to run git: {err}"))?;
+pub(crate) async fn git_remote_exists(
+    repo_path: &PathBuf,
+    remote: &str,
+) -> Result<bool, CoreError> {
+    let git_bin = git_binary()?;
     let status = tokio_command(git_bin)
         .args(["remote", "get-url", remote])
         .current_dir(repo_path)
         .env("PATH", git_env_path())
         .status()
         .await
-        .map_err(|err| format!("Failed to run git: {err}"))?;
+        .map_err(git_spawn_error)?;
     Ok(status.success())
 }
 
@@ -109,8 +133,8 @@ pub(crate) async fn git_remote_branch_exists_live(
     repo_path: &PathBuf,
     remote: &str,
     branch: &str,
-) -> Result<bool, String> {
-    let git_bin = resolve_git_binary().map_err(|err| format!("Failed to run git: {err}"))?;
+) -> Result<bool, CoreError> {
+    let git_bin = git_binary()?;
     let output = tokio_command(git_bin)
         .args([
             "ls-remote",
@@ -122,11 +146,11 @@ pub(crate) async fn git_remote_branch_exists_live(
         .env("PATH", git_env_path())
         .output()
         .await
-        .map_err(|err| format!("Failed to run git: {err}"))?;
+        .map_err(git_spawn_error)?;
     if output.status.success() {
         return Ok(!String::from_utf8_lossy(&output.stdout).trim().is_empty());
     }
-    Err(format_git_error(&output.stdout, &output.stderr))
+    Err(git_command_error(&output.stdout, &output.stderr))
 }
 
 // Used by daemon-only worktree orchestration paths.
@@ -135,8 +159,8 @@ pub(crate) async fn git_remote_branch_exists_local(
     repo_path: &PathBuf,
     remote: &str,
     branch: &str,
-) -> Result<bool, String> {
-    let git_bin = resolve_git_binary().map_err(|err| format!("Failed to run git: {err}"))?;
+) -> Result<bool, CoreError> {
+    let git_bin = git_binary()?;
     let status = tokio_command(git_bin)
         .args([
             "show-ref",
@@ -147,11 +171,11 @@ pub(crate) async fn git_remote_branch_exists_local(
         .env("PATH", git_env_path())
         .status()
         .await
-        .map_err(|err| format!("Failed to run git: {err}"))?;
+        .map_err(git_spawn_error)?;
     Ok(status.success())
 }
 
-pub(crate) async fn git_list_remotes(repo_path: &PathBuf) -> Result<Vec<String>, String> {
+pub(crate) async fn git_list_remotes(repo_path: &PathBuf) -> Result<Vec<String>, CoreError> {
     let output = run_git_command(repo_path, &["remote"]).await?;
     Ok(output
         .lines()
@@ -164,7 +188,7 @@ pub(crate) async fn git_list_remotes(repo_path: &PathBuf) -> Result<Vec<String>,
 pub(crate) async fn git_find_remote_for_branch_live(
     repo_path: &PathBuf,
     branch: &str,
-) -> Result<Option<String>, String> {
+) -> Result<Option<String>, CoreError> {
     if git_remote_exists(repo_path, "origin").await?
         && git_remote_branch_exists_live(repo_path, "origin", branch).await?
     {
@@ -188,7 +212,7 @@ pub(crate) async fn git_find_remote_for_branch_live(
 pub(crate) async fn git_find_remote_tracking_branch_local(
     repo_path: &PathBuf,
     branch: &str,
-) -> Result<Option<String>, String> {
+) -> Result<Option<String>, CoreError> {
     if git_remote_branch_exists_local(repo_path, "origin", branch).await? {
         return Ok(Some(format!("origin/{branch}")));
     }
@@ -209,7 +233,7 @@ pub(crate) async fn unique_branch_name_live(
     repo_path: &PathBuf,
     desired: &str,
     remote: Option<&str>,
-) -> Result<(String, bool), String> {
+) -> Result<(String, bool), CoreError> {
     let mut candidate = desired.to_string();
     if desired.is_empty() {
         return Ok((candidate, false));
@@ -233,7 +257,9 @@ pub(crate) async fn unique_branch_name_live(
             return Ok((candidate, true));
         }
     }
-    Err("Unable to find an available branch name.".to_string())
+    Err(CoreError::internal(
+        "Unable to find an available branch name.",
+    ))
 }
 
 pub(crate) async fn git_get_origin_url(repo_path: &PathBuf) -> Option<String> {
@@ -241,3 +267,19 @@ pub(crate) async fn git_get_origin_url(repo_path: &PathBuf) -> Option<String> {
         .await
         .ok()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn git_command_error_codes_missing_worktrees() {
+        let err = git_command_error(b"", b"fatal: '/tmp/wt' is not a working tree\n");
+        assert_eq!(err.code, ErrorCode::MissingWorktree);
+        assert_eq!(err.message, "fatal: '/tmp/wt' is not a working tree");
+
+        let err = git_command_error(b"", b"fatal: not a git repository\n");
+        assert_eq!(err.code, ErrorCode::Internal);
+        assert_eq!(git_command_error(b"", b"").message, "Git command failed.");
+    }
+}
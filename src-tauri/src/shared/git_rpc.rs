@@ -1,9 +1,12 @@
 #![allow(dead_code)]
 
+use schemars::JsonSchema;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::shared::core_error::CoreError;
+
 pub(crate) const METHOD_GET_GIT_STATUS: &str = "get_git_status";
 pub(crate) const METHOD_INIT_GIT_REPO: &str = "init_git_repo";
 pub(crate) const METHOD_CREATE_GITHUB_REPO: &str = "create_github_repo";
@@ -34,22 +37,22 @@ pub(crate) const METHOD_CREATE_GIT_BRANCH: &str = "create_git_branch";
 pub(crate) const METHOD_GENERATE_COMMIT_MESSAGE: &str = "generate_commit_message";
 
 #[allow(dead_code)]
-pub(crate) fn to_params<T: Serialize>(request: &T) -> Result<Value, String> {
-    serde_json::to_value(request).map_err(|err| err.to_string())
+pub(crate) fn to_params<T: Serialize>(request: &T) -> Result<Value, CoreError> {
+    serde_json::to_value(request).map_err(|err| CoreError::internal(err.to_string()))
 }
 
 #[allow(dead_code)]
-pub(crate) fn from_params<T: DeserializeOwned>(params: &Value) -> Result<T, String> {
-    serde_json::from_value(params.clone()).map_err(|err| err.to_string())
+pub(crate) fn from_params<T: DeserializeOwned>(params: &Value) -> Result<T, CoreError> {
+    serde_json::from_value(params.clone()).map_err(|err| CoreError::invalid_params(err.to_string()))
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct WorkspaceIdRequest {
     pub(crate) workspace_id: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct InitGitRepoRequest {
     pub(crate) workspace_id: String,
@@ -57,14 +60,7 @@ pub(crate) struct InitGitRepoRequest {
     pub(crate) force: Option<bool>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub(crate) struct InitGitRepoRequiredRequest {
-    pub(crate) workspace_id: String,
-    pub(crate) branch: String,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct CreateGitHubRepoRequest {
     pub(crate) workspace_id: String,
@@ -73,64 +69,56 @@ pub(crate) struct CreateGitHubRepoRequest {
     pub(crate) branch: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub(crate) struct CreateGitHubRepoRequiredRequest {
-    pub(crate) workspace_id: String,
-    pub(crate) repo: String,
-    pub(crate) visibility: String,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct WorkspacePathRequest {
     pub(crate) workspace_id: String,
     pub(crate) path: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct ListGitRootsRequest {
     pub(crate) workspace_id: String,
     pub(crate) depth: Option<u32>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct GetGitLogRequest {
     pub(crate) workspace_id: String,
     pub(crate) limit: Option<u32>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct WorkspaceShaRequest {
     pub(crate) workspace_id: String,
     pub(crate) sha: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct WorkspaceMessageRequest {
     pub(crate) workspace_id: String,
     pub(crate) message: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct GitHubPullRequestRequest {
     pub(crate) workspace_id: String,
     pub(crate) pr_number: u64,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct WorkspaceNameRequest {
     pub(crate) workspace_id: String,
     pub(crate) name: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct GenerateCommitMessageRequest {
     pub(crate) workspace_id: String,
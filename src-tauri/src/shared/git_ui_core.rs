@@ -4,6 +4,7 @@ use std::path::{Path, PathBuf};
 use serde_json::Value;
 use tokio::sync::Mutex;
 
+use crate::shared::core_error::CoreError;
 use crate::types::{
     AppSettings, GitCommitDiff, GitFileDiff, GitHubIssuesResponse, GitHubPullRequestComment,
     GitHubPullRequestDiff, GitHubPullRequestsResponse, GitLogResponse, WorkspaceEntry,
@@ -27,18 +28,18 @@ mod tests;
 pub(crate) async fn resolve_repo_root_for_workspace_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
-) -> Result<PathBuf, String> {
+) -> Result<PathBuf, CoreError> {
     context::resolve_repo_root_for_workspace(workspaces, workspace_id).await
 }
 
-pub(crate) fn collect_workspace_diff_core(repo_root: &Path) -> Result<String, String> {
+pub(crate) fn collect_workspace_diff_core(repo_root: &Path) -> Result<String, CoreError> {
     diff::collect_workspace_diff(repo_root)
 }
 
 pub(crate) async fn get_git_status_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     diff::get_git_status_inner(workspaces, workspace_id).await
 }
 
@@ -47,7 +48,7 @@ pub(crate) async fn init_git_repo_core(
     workspace_id: String,
     branch: String,
     force: bool,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     commands::init_git_repo_inner(workspaces, workspace_id, branch, force).await
 }
 
@@ -57,7 +58,7 @@ pub(crate) async fn create_github_repo_core(
     repo: String,
     visibility: String,
     branch: Option<String>,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     commands::create_github_repo_inner(workspaces, workspace_id, repo, visibility, branch).await
 }
 
@@ -65,7 +66,7 @@ pub(crate) async fn list_git_roots_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
     depth: Option<usize>,
-) -> Result<Vec<String>, String> {
+) -> Result<Vec<String>, CoreError> {
     commands::list_git_roots_inner(workspaces, workspace_id, depth).await
 }
 
@@ -73,7 +74,7 @@ pub(crate) async fn get_git_diffs_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     app_settings: &Mutex<AppSettings>,
     workspace_id: String,
-) -> Result<Vec<GitFileDiff>, String> {
+) -> Result<Vec<GitFileDiff>, CoreError> {
     diff::get_git_diffs_inner(workspaces, app_settings, workspace_id).await
 }
 
@@ -81,7 +82,7 @@ pub(crate) async fn get_git_log_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
     limit: Option<usize>,
-) -> Result<GitLogResponse, String> {
+) -> Result<GitLogResponse, CoreError> {
     log::get_git_log_inner(workspaces, workspace_id, limit).await
 }
 
@@ -90,14 +91,14 @@ pub(crate) async fn get_git_commit_diff_core(
     app_settings: &Mutex<AppSettings>,
     workspace_id: String,
     sha: String,
-) -> Result<Vec<GitCommitDiff>, String> {
+) -> Result<Vec<GitCommitDiff>, CoreError> {
     diff::get_git_commit_diff_inner(workspaces, app_settings, workspace_id, sha).await
 }
 
 pub(crate) async fn get_git_remote_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
-) -> Result<Option<String>, String> {
+) -> Result<Option<String>, CoreError> {
     log::get_git_remote_inner(workspaces, workspace_id).await
 }
 
@@ -105,14 +106,14 @@ pub(crate) async fn stage_git_file_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
     path: String,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     commands::stage_git_file_inner(workspaces, workspace_id, path).await
 }
 
 pub(crate) async fn stage_git_all_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     commands::stage_git_all_inner(workspaces, workspace_id).await
 }
 
@@ -120,7 +121,7 @@ pub(crate) async fn unstage_git_file_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
     path: String,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     commands::unstage_git_file_inner(workspaces, workspace_id, path).await
 }
 
@@ -128,14 +129,14 @@ pub(crate) async fn revert_git_file_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
     path: String,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     commands::revert_git_file_inner(workspaces, workspace_id, path).await
 }
 
 pub(crate) async fn revert_git_all_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     commands::revert_git_all_inner(workspaces, workspace_id).await
 }
 
@@ -143,49 +144,49 @@ pub(crate) async fn commit_git_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
     message: String,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     commands::commit_git_inner(workspaces, workspace_id, message).await
 }
 
 pub(crate) async fn push_git_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     commands::push_git_inner(workspaces, workspace_id).await
 }
 
 pub(crate) async fn pull_git_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     commands::pull_git_inner(workspaces, workspace_id).await
 }
 
 pub(crate) async fn fetch_git_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     commands::fetch_git_inner(workspaces, workspace_id).await
 }
 
 pub(crate) async fn sync_git_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     commands::sync_git_inner(workspaces, workspace_id).await
 }
 
 pub(crate) async fn get_github_issues_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
-) -> Result<GitHubIssuesResponse, String> {
+) -> Result<GitHubIssuesResponse, CoreError> {
     github::get_github_issues_inner(workspaces, workspace_id).await
 }
 
 pub(crate) async fn get_github_pull_requests_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
-) -> Result<GitHubPullRequestsResponse, String> {
+) -> Result<GitHubPullRequestsResponse, CoreError> {
     github::get_github_pull_requests_inner(workspaces, workspace_id).await
 }
 
@@ -193,7 +194,7 @@ pub(crate) async fn get_github_pull_request_diff_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
     pr_number: u64,
-) -> Result<Vec<GitHubPullRequestDiff>, String> {
+) -> Result<Vec<GitHubPullRequestDiff>, CoreError> {
     github::get_github_pull_request_diff_inner(workspaces, workspace_id, pr_number).await
 }
 
@@ -201,7 +202,7 @@ pub(crate) async fn get_github_pull_request_comments_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
     pr_number: u64,
-) -> Result<Vec<GitHubPullRequestComment>, String> {
+) -> Result<Vec<GitHubPullRequestComment>, CoreError> {
     github::get_github_pull_request_comments_inner(workspaces, workspace_id, pr_number).await
 }
 
@@ -209,14 +210,14 @@ pub(crate) async fn checkout_github_pull_request_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
     pr_number: u64,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     github::checkout_github_pull_request_inner(workspaces, workspace_id, pr_number).await
 }
 
 pub(crate) async fn list_git_branches_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     commands::list_git_branches_inner(workspaces, workspace_id).await
 }
 
@@ -224,7 +225,7 @@ pub(crate) async fn checkout_git_branch_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
     name: String,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     commands::checkout_git_branch_inner(workspaces, workspace_id, name).await
 }
 
@@ -232,6 +233,6 @@ pub(crate) async fn create_git_branch_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
     name: String,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     commands::create_git_branch_inner(workspaces, workspace_id, name).await
 }
@@ -9,46 +9,37 @@ use tokio::sync::Mutex;
 use crate::git_utils::{
     checkout_branch, list_git_roots as scan_git_roots, parse_github_repo, resolve_git_root,
 };
+use crate::shared::core_error::CoreError;
+use crate::shared::git_core::{git_binary, git_command_error, git_spawn_error};
 use crate::shared::process_core::tokio_command;
 use crate::types::{BranchInfo, WorkspaceEntry};
-use crate::utils::{git_env_path, normalize_git_path, resolve_git_binary};
+use crate::utils::{git_env_path, normalize_git_path};
 
 use super::context::workspace_entry_for_id;
 
-async fn run_git_command(repo_root: &Path, args: &[&str]) -> Result<(), String> {
-    let git_bin = resolve_git_binary().map_err(|e| format!("Failed to run git: {e}"))?;
+async fn run_git_command(repo_root: &Path, args: &[&str]) -> Result<(), CoreError> {
+    let git_bin = git_binary()?;
     let output = tokio_command(git_bin)
         .args(args)
         .current_dir(repo_root)
         .env("PATH", git_env_path())
         .output()
         .await
-        .map_err(|e| format!("Failed to run git: {e}"))?;
+        .map_err(git_spawn_error)?;
 
     if output.status.success() {
         return Ok(());
     }
-
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let detail = if stderr.trim().is_empty() {
-        stdout.trim()
-    } else {
-        stderr.trim()
-    };
-    if detail.is_empty() {
-        return Err("Git command failed.".to_string());
-    }
-    Err(detail.to_string())
+    Err(git_command_error(&output.stdout, &output.stderr))
 }
 
-async fn run_gh_command(repo_root: &Path, args: &[&str]) -> Result<(String, String), String> {
+async fn run_gh_command(repo_root: &Path, args: &[&str]) -> Result<(String, String), CoreError> {
     let output = tokio_command("gh")
         .args(args)
         .current_dir(repo_root)
         .output()
         .await
-        .map_err(|e| format!("Failed to run gh: {e}"))?;
+        .map_err(|e| CoreError::internal(format!("Failed to run gh: {e}")))?;
 
     let stdout = String::from_utf8_lossy(&output.stdout).to_string();
     let stderr = String::from_utf8_lossy(&output.stderr).to_string();
@@ -62,12 +53,12 @@ async fn run_gh_command(repo_root: &Path, args: &[&str]) -> Result<(String, Stri
         stderr.trim()
     };
     if detail.is_empty() {
-        return Err("GitHub CLI command failed.".to_string());
+        return Err(CoreError::internal("GitHub CLI command failed."));
     }
-    Err(detail.to_string())
+    Err(CoreError::internal(detail))
 }
 
-async fn gh_stdout_trim(repo_root: &Path, args: &[&str]) -> Result<String, String> {
+async fn gh_stdout_trim(repo_root: &Path, args: &[&str]) -> Result<String, CoreError> {
     let (stdout, _) = run_gh_command(repo_root, args).await?;
     Ok(stdout.trim().to_string())
 }
@@ -78,8 +69,9 @@ async fn gh_git_protocol(repo_root: &Path) -> String {
         .unwrap_or_else(|_| "https".to_string())
 }
 
-fn count_effective_dir_entries(root: &Path) -> Result<usize, String> {
-    let entries = fs::read_dir(root).map_err(|err| format!("Failed to read directory: {err}"))?;
+fn count_effective_dir_entries(root: &Path) -> Result<usize, CoreError> {
+    let entries = fs::read_dir(root)
+        .map_err(|err| CoreError::internal(format!("Failed to read directory: {err}")))?;
     let mut count = 0usize;
     for entry in entries {
         let entry = entry.map_err(|err| {
@@ -98,55 +90,79 @@ fn count_effective_dir_entries(root: &Path) -> Result<usize, String> {
     Ok(count)
 }
 
-fn validate_branch_name(name: &str) -> Result<String, String> {
+fn validate_branch_name(name: &str) -> Result<String, CoreError> {
     let trimmed = name.trim();
     if trimmed.is_empty() {
-        return Err("Branch name is required.".to_string());
+        return Err(CoreError::invalid_params("Branch name is required."));
     }
     if trimmed == "." || trimmed == ".." {
-        return Err("Branch name cannot be '.' or '..'.".to_string());
+        return Err(CoreError::invalid_params(
+            "Branch name cannot be '.' or '..'.",
+        ));
     }
     if trimmed.chars().any(|ch| ch.is_whitespace()) {
-        return Err("Branch name cannot contain spaces.".to_string());
+        return Err(CoreError::invalid_params(
+            "Branch name cannot contain spaces.",
+        ));
     }
     if trimmed.starts_with('/') || trimmed.ends_with('/') {
-        return Err("Branch name cannot start or end with '/'.".to_string());
+        return Err(CoreError::invalid_params(
+            "Branch name cannot start or end with '/'.",
+        ));
     }
     if trimmed.contains("//") {
-        return Err("Branch name cannot contain '//'.".to_string());
+        return Err(CoreError::invalid_params(
+            "Branch name cannot contain '//'.",
+        ));
     }
     if trimmed.ends_with(".lock") {
-        return Err("Branch name cannot end with '.lock'.".to_string());
+        return Err(CoreError::invalid_params(
+            "Branch name cannot end with '.lock'.",
+        ));
     }
     if trimmed.contains("..") {
-        return Err("Branch name cannot contain '..'.".to_string());
+        return Err(CoreError::invalid_params(
+            "Branch name cannot contain '..'.",
+        ));
     }
     if trimmed.contains("@{") {
-        return Err("Branch name cannot contain '@{'.".to_string());
+        return Err(CoreError::invalid_params(
+            "Branch name cannot contain '@{'.",
+        ));
     }
     let invalid_chars = ['~', '^', ':', '?', '*', '[', '\\'];
     if trimmed.chars().any(|ch| invalid_chars.contains(&ch)) {
-        return Err("Branch name contains invalid characters.".to_string());
+        return Err(CoreError::invalid_params(
+            "Branch name contains invalid characters.",
+        ));
     }
     if trimmed.ends_with('.') {
-        return Err("Branch name cannot end with '.'.".to_string());
+        return Err(CoreError::invalid_params(
+            "Branch name cannot end with '.'.",
+        ));
     }
     Ok(trimmed.to_string())
 }
 
-fn validate_github_repo_name(value: &str) -> Result<String, String> {
+fn validate_github_repo_name(value: &str) -> Result<String, CoreError> {
     let trimmed = value.trim();
     if trimmed.is_empty() {
-        return Err("Repository name is required.".to_string());
+        return Err(CoreError::invalid_params("Repository name is required."));
     }
     if trimmed.chars().any(|ch| ch.is_whitespace()) {
-        return Err("Repository name cannot contain spaces.".to_string());
+        return Err(CoreError::invalid_params(
+            "Repository name cannot contain spaces.",
+        ));
     }
     if trimmed.starts_with('/') || trimmed.ends_with('/') {
-        return Err("Repository name cannot start or end with '/'.".to_string());
+        return Err(CoreError::invalid_params(
+            "Repository name cannot start or end with '/'.",
+        ));
     }
     if trimmed.contains("//") {
-        return Err("Repository name cannot contain '//'.".to_string());
+        return Err(CoreError::invalid_params(
+            "Repository name cannot contain '//'.",
+        ));
     }
     Ok(trimmed.to_string())
 }
@@ -169,12 +185,12 @@ fn normalize_repo_full_name(value: &str) -> String {
         .to_string()
 }
 
-pub(super) fn validate_normalized_repo_name(value: &str) -> Result<String, String> {
+pub(super) fn validate_normalized_repo_name(value: &str) -> Result<String, CoreError> {
     let normalized = normalize_repo_full_name(value);
     if normalized.is_empty() {
-        return Err(
-            "Repository name is empty after normalization. Use 'repo' or 'owner/repo'.".to_string(),
-        );
+        return Err(CoreError::invalid_params(
+            "Repository name is empty after normalization. Use 'repo' or 'owner/repo'.",
+        ));
     }
     Ok(normalized)
 }
@@ -213,7 +229,7 @@ async fn ensure_github_repo_exists(
     full_name: &str,
     visibility_flag: &str,
     origin_exists: bool,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     // If origin already exists, verify the remote repository is reachable first.
     // This covers the common retry case where origin is preconfigured but the
     // GitHub repository itself has not been created yet.
@@ -230,7 +246,7 @@ async fn ensure_github_repo_exists(
 
     let create_args = gh_repo_create_args(full_name, visibility_flag, origin_exists);
     if let Err(error) = run_gh_command(repo_root, &create_args).await {
-        let lower = error.to_lowercase();
+        let lower = error.message.to_lowercase();
         if !github_repo_exists_message(&lower) {
             return Err(error);
         }
@@ -311,7 +327,7 @@ fn parse_upstream_ref(name: &str) -> Option<(String, String)> {
     Some((remote.to_string(), branch.to_string()))
 }
 
-fn upstream_remote_and_branch(repo_root: &Path) -> Result<Option<(String, String)>, String> {
+fn upstream_remote_and_branch(repo_root: &Path) -> Result<Option<(String, String)>, CoreError> {
     let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
     let head = match repo.head() {
         Ok(head) => head,
@@ -336,7 +352,7 @@ fn upstream_remote_and_branch(repo_root: &Path) -> Result<Option<(String, String
     Ok(upstream_name.and_then(parse_upstream_ref))
 }
 
-async fn push_with_upstream(repo_root: &Path) -> Result<(), String> {
+async fn push_with_upstream(repo_root: &Path) -> Result<(), CoreError> {
     let upstream = upstream_remote_and_branch(repo_root)?;
     if let Some((remote, branch)) = upstream {
         let _ = run_git_command(repo_root, &["fetch", "--prune", remote.as_str()]).await;
@@ -346,7 +362,7 @@ async fn push_with_upstream(repo_root: &Path) -> Result<(), String> {
     run_git_command(repo_root, &["push"]).await
 }
 
-async fn fetch_with_default_remote(repo_root: &Path) -> Result<(), String> {
+async fn fetch_with_default_remote(repo_root: &Path) -> Result<(), CoreError> {
     let upstream = upstream_remote_and_branch(repo_root)?;
     if let Some((remote, _)) = upstream {
         return run_git_command(repo_root, &["fetch", "--prune", remote.as_str()]).await;
@@ -354,7 +370,7 @@ async fn fetch_with_default_remote(repo_root: &Path) -> Result<(), String> {
     run_git_command(repo_root, &["fetch", "--prune"]).await
 }
 
-async fn pull_with_default_strategy(repo_root: &Path) -> Result<(), String> {
+async fn pull_with_default_strategy(repo_root: &Path) -> Result<(), CoreError> {
     fn autostash_unsupported(lower: &str) -> bool {
         lower.contains("unknown option") && lower.contains("autostash")
     }
@@ -367,12 +383,12 @@ async fn pull_with_default_strategy(repo_root: &Path) -> Result<(), String> {
     match run_git_command(repo_root, &["pull", "--autostash"]).await {
         Ok(()) => Ok(()),
         Err(err) => {
-            let lower = err.to_lowercase();
+            let lower = err.message.to_lowercase();
             if autostash_unsupported(&lower) {
                 match run_git_command(repo_root, &["pull"]).await {
                     Ok(()) => Ok(()),
                     Err(no_autostash_err) => {
-                        let no_autostash_lower = no_autostash_err.to_lowercase();
+                        let no_autostash_lower = no_autostash_err.message.to_lowercase();
                         if needs_reconcile_strategy(&no_autostash_lower) {
                             return run_git_command(repo_root, &["pull", "--no-rebase"]).await;
                         }
@@ -383,7 +399,7 @@ async fn pull_with_default_strategy(repo_root: &Path) -> Result<(), String> {
                 match run_git_command(repo_root, &["pull", "--no-rebase", "--autostash"]).await {
                     Ok(()) => Ok(()),
                     Err(merge_err) => {
-                        let merge_lower = merge_err.to_lowercase();
+                        let merge_lower = merge_err.message.to_lowercase();
                         if autostash_unsupported(&merge_lower) {
                             return run_git_command(repo_root, &["pull", "--no-rebase"]).await;
                         }
@@ -401,7 +417,7 @@ pub(super) async fn stage_git_file_inner(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
     path: String,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
     let repo_root = resolve_git_root(&entry)?;
     for path in action_paths_for_file(&repo_root, &path) {
@@ -413,7 +429,7 @@ pub(super) async fn stage_git_file_inner(
 pub(super) async fn stage_git_all_inner(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
     let repo_root = resolve_git_root(&entry)?;
     run_git_command(&repo_root, &["add", "-A"]).await
@@ -423,7 +439,7 @@ pub(super) async fn unstage_git_file_inner(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
     path: String,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
     let repo_root = resolve_git_root(&entry)?;
     for path in action_paths_for_file(&repo_root, &path) {
@@ -436,7 +452,7 @@ pub(super) async fn revert_git_file_inner(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
     path: String,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
     let repo_root = resolve_git_root(&entry)?;
     for path in action_paths_for_file(&repo_root, &path) {
@@ -457,7 +473,7 @@ pub(super) async fn revert_git_file_inner(
 pub(super) async fn revert_git_all_inner(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
     let repo_root = resolve_git_root(&entry)?;
     run_git_command(
@@ -472,7 +488,7 @@ pub(super) async fn commit_git_inner(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
     message: String,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
     let repo_root = resolve_git_root(&entry)?;
     run_git_command(&repo_root, &["commit", "-m", &message]).await
@@ -481,7 +497,7 @@ pub(super) async fn commit_git_inner(
 pub(super) async fn push_git_inner(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
     let repo_root = resolve_git_root(&entry)?;
     push_with_upstream(&repo_root).await
@@ -490,7 +506,7 @@ pub(super) async fn push_git_inner(
 pub(super) async fn pull_git_inner(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
     let repo_root = resolve_git_root(&entry)?;
     pull_with_default_strategy(&repo_root).await
@@ -499,7 +515,7 @@ pub(super) async fn pull_git_inner(
 pub(super) async fn fetch_git_inner(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
     let repo_root = resolve_git_root(&entry)?;
     fetch_with_default_remote(&repo_root).await
@@ -508,7 +524,7 @@ pub(super) async fn fetch_git_inner(
 pub(super) async fn sync_git_inner(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
     let repo_root = resolve_git_root(&entry)?;
     pull_with_default_strategy(&repo_root).await?;
@@ -519,7 +535,7 @@ pub(super) async fn list_git_roots_inner(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
     depth: Option<usize>,
-) -> Result<Vec<String>, String> {
+) -> Result<Vec<String>, CoreError> {
     let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
     let root = PathBuf::from(&entry.path);
     let depth = depth.unwrap_or(2).clamp(1, 6);
@@ -531,7 +547,7 @@ pub(super) async fn init_git_repo_inner(
     workspace_id: String,
     branch: String,
     force: bool,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     const INITIAL_COMMIT_MESSAGE: &str = "Initial commit";
 
     let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
@@ -553,7 +569,7 @@ pub(super) async fn init_git_repo_inner(
         run_git_command(&repo_root, &["init", "--initial-branch", branch.as_str()]).await;
 
     if let Err(error) = init_with_branch {
-        let lower = error.to_lowercase();
+        let lower = error.message.to_lowercase();
         let unsupported = lower.contains("initial-branch")
             && (lower.contains("unknown option")
                 || lower.contains("unrecognized option")
@@ -576,9 +592,9 @@ pub(super) async fn init_git_repo_inner(
         .await
         {
             Ok(()) => None,
-            Err(err) => Some(err),
+            Err(err) => Some(err.message),
         },
-        Err(err) => Some(err),
+        Err(err) => Some(err.message),
     };
 
     if let Some(commit_error) = commit_error {
@@ -594,7 +610,7 @@ pub(super) async fn create_github_repo_inner(
     repo: String,
     visibility: String,
     branch: Option<String>,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
     let repo_root = resolve_git_root(&entry)?;
     let repo = validate_normalized_repo_name(&validate_github_repo_name(&repo)?)?;
@@ -602,11 +618,15 @@ pub(super) async fn create_github_repo_inner(
     let visibility_flag = match visibility.trim() {
         "private" => "--private",
         "public" => "--public",
-        other => return Err(format!("Invalid repo visibility: {other}")),
+        other => {
+            return Err(CoreError::invalid_params(format!(
+                "Invalid repo visibility: {other}"
+            )))
+        }
     };
 
     let local_repo = Repository::open(&repo_root)
-        .map_err(|_| "Git is not initialized in this folder yet.".to_string())?;
+        .map_err(|_| CoreError::internal("Git is not initialized in this folder yet."))?;
     let origin_url_before = local_repo
         .find_remote("origin")
         .ok()
@@ -617,7 +637,7 @@ pub(super) async fn create_github_repo_inner(
     } else {
         let owner = gh_stdout_trim(&repo_root, &["api", "user", "--jq", ".login"]).await?;
         if owner.trim().is_empty() {
-            return Err("Failed to determine GitHub username.".to_string());
+            return Err(CoreError::internal("Failed to determine GitHub username."));
         }
         format!("{owner}/{repo}")
     };
@@ -628,9 +648,9 @@ pub(super) async fn create_github_repo_inner(
                 .to_string()
         })?;
         if !github_repo_names_match(&existing_repo, &full_name) {
-            return Err(format!(
+            return Err(CoreError::internal(format!(
                 "Origin remote already points to '{existing_repo}', but '{full_name}' was requested. Remove or reconfigure origin to continue."
-            ));
+            )));
         }
     }
 
@@ -663,7 +683,7 @@ pub(super) async fn create_github_repo_inner(
         )
         .await?;
         if remote_url.trim().is_empty() {
-            return Err("Failed to resolve GitHub remote URL.".to_string());
+            return Err(CoreError::internal("Failed to resolve GitHub remote URL."));
         }
         run_git_command(&repo_root, &["remote", "add", "origin", remote_url.trim()]).await?;
     }
@@ -702,8 +722,8 @@ pub(super) async fn create_github_repo_inner(
         Ok(())
     };
 
-    let push_error = push_result.err();
-    let default_branch_error = default_branch_result.err();
+    let push_error = push_result.err().map(|err| err.message);
+    let default_branch_error = default_branch_result.err().map(|err| err.message);
 
     if push_error.is_some() || default_branch_error.is_some() {
         return Ok(json!({
@@ -725,7 +745,7 @@ pub(super) async fn create_github_repo_inner(
 pub(super) async fn list_git_branches_inner(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
     let repo_root = resolve_git_root(&entry)?;
     let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
@@ -755,18 +775,18 @@ pub(super) async fn checkout_git_branch_inner(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
     name: String,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
     let repo_root = resolve_git_root(&entry)?;
     let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
-    checkout_branch(&repo, &name).map_err(|e| e.to_string())
+    checkout_branch(&repo, &name).map_err(|e| CoreError::internal(e.to_string()))
 }
 
 pub(super) async fn create_git_branch_inner(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
     name: String,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
     let repo_root = resolve_git_root(&entry)?;
     let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
@@ -774,18 +794,20 @@ pub(super) async fn create_git_branch_inner(
     let target = head.peel_to_commit().map_err(|e| e.to_string())?;
     repo.branch(&name, &target, false)
         .map_err(|e| e.to_string())?;
-    checkout_branch(&repo, &name).map_err(|e| e.to_string())
+    checkout_branch(&repo, &name).map_err(|e| CoreError::internal(e.to_string()))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{gh_repo_create_args, validate_branch_name};
+    use super::{gh_repo_create_args, validate_branch_name, CoreError};
 
     #[test]
     fn validate_branch_name_rejects_repeated_slashes() {
         assert_eq!(
             validate_branch_name("feature//oops"),
-            Err("Branch name cannot contain '//'.".to_string())
+            Err(CoreError::invalid_params(
+                "Branch name cannot contain '//'."
+            ))
         );
     }
 
@@ -12,6 +12,7 @@ use tokio::sync::Mutex;
 use crate::git_utils::{
     diff_patch_to_string, diff_stats_for_path, image_mime_type, resolve_git_root,
 };
+use crate::shared::core_error::CoreError;
 use crate::shared::process_core::std_command;
 use crate::types::{AppSettings, GitCommitDiff, GitFileDiff, GitFileStatus, WorkspaceEntry};
 use crate::utils::{git_env_path, normalize_git_path, resolve_git_binary};
@@ -296,7 +297,7 @@ fn build_combined_diff(repo: &Repository, diff: &git2::Diff) -> String {
     combined_diff
 }
 
-pub(super) fn collect_workspace_diff(repo_root: &Path) -> Result<String, String> {
+pub(super) fn collect_workspace_diff(repo_root: &Path) -> Result<String, CoreError> {
     let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
     let head_tree = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
 
@@ -334,7 +335,7 @@ pub(super) fn collect_workspace_diff(repo_root: &Path) -> Result<String, String>
 pub(super) async fn get_git_status_inner(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
     let repo_root = resolve_git_root(&entry)?;
     let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
@@ -466,7 +467,7 @@ pub(super) async fn get_git_diffs_inner(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     app_settings: &Mutex<AppSettings>,
     workspace_id: String,
-) -> Result<Vec<GitFileDiff>, String> {
+) -> Result<Vec<GitFileDiff>, CoreError> {
     let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
     let repo_root = resolve_git_root(&entry)?;
     let ignore_whitespace_changes = {
@@ -620,7 +621,7 @@ pub(super) async fn get_git_commit_diff_inner(
     app_settings: &Mutex<AppSettings>,
     workspace_id: String,
     sha: String,
-) -> Result<Vec<GitCommitDiff>, String> {
+) -> Result<Vec<GitCommitDiff>, CoreError> {
     let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
 
     let ignore_whitespace_changes = {
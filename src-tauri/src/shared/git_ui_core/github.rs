@@ -5,6 +5,7 @@ use git2::Repository;
 use tokio::sync::Mutex;
 
 use crate::git_utils::{parse_github_repo, resolve_git_root};
+use crate::shared::core_error::CoreError;
 use crate::shared::process_core::tokio_command;
 use crate::types::{
     GitHubIssue, GitHubIssuesResponse, GitHubPullRequest, GitHubPullRequestComment,
@@ -14,7 +15,7 @@ use crate::utils::normalize_git_path;
 
 use super::context::workspace_entry_for_id;
 
-fn github_repo_from_path(path: &Path) -> Result<String, String> {
+fn github_repo_from_path(path: &Path) -> Result<String, CoreError> {
     let repo = Repository::open(path).map_err(|e| e.to_string())?;
     let remotes = repo.remotes().map_err(|e| e.to_string())?;
     let name = if remotes.iter().any(|remote| remote == Some("origin")) {
@@ -23,11 +24,14 @@ fn github_repo_from_path(path: &Path) -> Result<String, String> {
         remotes.iter().flatten().next().unwrap_or("").to_string()
     };
     if name.is_empty() {
-        return Err("No git remote configured.".to_string());
+        return Err(CoreError::internal("No git remote configured."));
     }
     let remote = repo.find_remote(&name).map_err(|e| e.to_string())?;
-    let remote_url = remote.url().ok_or("Remote has no URL configured.")?;
-    parse_github_repo(remote_url).ok_or("Remote is not a GitHub repository.".to_string())
+    let remote_url = remote
+        .url()
+        .ok_or_else(|| CoreError::internal("Remote has no URL configured."))?;
+    parse_github_repo(remote_url)
+        .ok_or_else(|| CoreError::internal("Remote is not a GitHub repository."))
 }
 
 fn parse_pr_diff(diff: &str) -> Vec<GitHubPullRequestDiff> {
@@ -125,7 +129,7 @@ fn parse_pr_diff(diff: &str) -> Vec<GitHubPullRequestDiff> {
     entries
 }
 
-fn command_failure_detail(stdout: &[u8], stderr: &[u8], fallback: &str) -> String {
+fn command_failure_detail(stdout: &[u8], stderr: &[u8], fallback: &str) -> CoreError {
     let stderr = String::from_utf8_lossy(stderr);
     let stdout = String::from_utf8_lossy(stdout);
     let detail = if stderr.trim().is_empty() {
@@ -134,9 +138,9 @@ fn command_failure_detail(stdout: &[u8], stderr: &[u8], fallback: &str) -> Strin
         stderr.trim()
     };
     if detail.is_empty() {
-        fallback.to_string()
+        CoreError::internal(fallback)
     } else {
-        detail.to_string()
+        CoreError::internal(detail)
     }
 }
 
@@ -144,7 +148,7 @@ pub(super) async fn checkout_github_pull_request_inner(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
     pr_number: u64,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
     let repo_root = resolve_git_root(&entry)?;
     let pr_number_text = pr_number.to_string();
@@ -154,7 +158,7 @@ pub(super) async fn checkout_github_pull_request_inner(
         .current_dir(&repo_root)
         .output()
         .await
-        .map_err(|e| format!("Failed to run gh: {e}"))?;
+        .map_err(|e| CoreError::internal(format!("Failed to run gh: {e}")))?;
 
     if !output.status.success() {
         return Err(command_failure_detail(
@@ -170,7 +174,7 @@ pub(super) async fn checkout_github_pull_request_inner(
 pub(super) async fn get_github_issues_inner(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
-) -> Result<GitHubIssuesResponse, String> {
+) -> Result<GitHubIssuesResponse, CoreError> {
     let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
     let repo_root = resolve_git_root(&entry)?;
     let repo_name = github_repo_from_path(&repo_root)?;
@@ -189,7 +193,7 @@ pub(super) async fn get_github_issues_inner(
         .current_dir(&repo_root)
         .output()
         .await
-        .map_err(|e| format!("Failed to run gh: {e}"))?;
+        .map_err(|e| CoreError::internal(format!("Failed to run gh: {e}")))?;
 
     if !output.status.success() {
         return Err(command_failure_detail(
@@ -227,7 +231,7 @@ pub(super) async fn get_github_issues_inner(
 pub(super) async fn get_github_pull_requests_inner(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
-) -> Result<GitHubPullRequestsResponse, String> {
+) -> Result<GitHubPullRequestsResponse, CoreError> {
     let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
     let repo_root = resolve_git_root(&entry)?;
     let repo_name = github_repo_from_path(&repo_root)?;
@@ -248,7 +252,7 @@ pub(super) async fn get_github_pull_requests_inner(
         .current_dir(&repo_root)
         .output()
         .await
-        .map_err(|e| format!("Failed to run gh: {e}"))?;
+        .map_err(|e| CoreError::internal(format!("Failed to run gh: {e}")))?;
 
     if !output.status.success() {
         return Err(command_failure_detail(
@@ -290,7 +294,7 @@ pub(super) async fn get_github_pull_request_diff_inner(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
     pr_number: u64,
-) -> Result<Vec<GitHubPullRequestDiff>, String> {
+) -> Result<Vec<GitHubPullRequestDiff>, CoreError> {
     let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
     let repo_root = resolve_git_root(&entry)?;
     let repo_name = github_repo_from_path(&repo_root)?;
@@ -308,7 +312,7 @@ pub(super) async fn get_github_pull_request_diff_inner(
         .current_dir(&repo_root)
         .output()
         .await
-        .map_err(|e| format!("Failed to run gh: {e}"))?;
+        .map_err(|e| CoreError::internal(format!("Failed to run gh: {e}")))?;
 
     if !output.status.success() {
         return Err(command_failure_detail(
@@ -326,7 +330,7 @@ pub(super) async fn get_github_pull_request_comments_inner(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
     pr_number: u64,
-) -> Result<Vec<GitHubPullRequestComment>, String> {
+) -> Result<Vec<GitHubPullRequestComment>, CoreError> {
     let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
     let repo_root = resolve_git_root(&entry)?;
     let repo_name = github_repo_from_path(&repo_root)?;
@@ -339,7 +343,7 @@ pub(super) async fn get_github_pull_request_comments_inner(
         .current_dir(&repo_root)
         .output()
         .await
-        .map_err(|e| format!("Failed to run gh: {e}"))?;
+        .map_err(|e| CoreError::internal(format!("Failed to run gh: {e}")))?;
 
     if !output.status.success() {
         return Err(command_failure_detail(
@@ -4,6 +4,7 @@ use git2::{BranchType, Repository, Sort};
 use tokio::sync::Mutex;
 
 use crate::git_utils::{commit_to_entry, resolve_git_root};
+use crate::shared::core_error::CoreError;
 use crate::types::{GitLogResponse, WorkspaceEntry};
 
 use super::context::workspace_entry_for_id;
@@ -12,7 +13,7 @@ pub(super) async fn get_git_log_inner(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
     limit: Option<usize>,
-) -> Result<GitLogResponse, String> {
+) -> Result<GitLogResponse, CoreError> {
     let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
     let repo_root = resolve_git_root(&entry)?;
     let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
@@ -103,7 +104,7 @@ pub(super) async fn get_git_log_inner(
 pub(super) async fn get_git_remote_inner(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
-) -> Result<Option<String>, String> {
+) -> Result<Option<String>, CoreError> {
     let entry = workspace_entry_for_id(workspaces, &workspace_id).await?;
     let repo_root = resolve_git_root(&entry)?;
     let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
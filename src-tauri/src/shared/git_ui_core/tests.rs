@@ -7,6 +7,7 @@ use serde_json::Value;
 use tokio::runtime::Runtime;
 use tokio::sync::Mutex;
 
+use crate::shared::core_error::CoreError;
 use crate::types::{AppSettings, WorkspaceEntry, WorkspaceKind, WorkspaceSettings};
 
 use super::commands;
@@ -94,15 +95,15 @@ fn github_repo_names_match_detects_mismatch() {
 fn validate_normalized_repo_name_rejects_empty_slug_after_normalization() {
     assert_eq!(
         commands::validate_normalized_repo_name(".git"),
-        Err(
-            "Repository name is empty after normalization. Use 'repo' or 'owner/repo'.".to_string()
-        )
+        Err(CoreError::invalid_params(
+            "Repository name is empty after normalization. Use 'repo' or 'owner/repo'."
+        ))
     );
     assert_eq!(
         commands::validate_normalized_repo_name("git@github.com:.git"),
-        Err(
-            "Repository name is empty after normalization. Use 'repo' or 'owner/repo'.".to_string()
-        )
+        Err(CoreError::invalid_params(
+            "Repository name is empty after normalization. Use 'repo' or 'owner/repo'."
+        ))
     );
 }
 
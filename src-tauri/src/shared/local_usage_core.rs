@@ -8,6 +8,7 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::Mutex;
 
 use crate::codex::home::{resolve_default_codex_home, resolve_workspace_codex_home};
+use crate::shared::core_error::CoreError;
 use crate::types::{
     LocalUsageDay, LocalUsageModel, LocalUsageSnapshot, LocalUsageTotals, WorkspaceEntry,
 };
@@ -34,7 +35,7 @@ pub(crate) async fn local_usage_snapshot_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     days: Option<u32>,
     workspace_path: Option<String>,
-) -> Result<LocalUsageSnapshot, String> {
+) -> Result<LocalUsageSnapshot, CoreError> {
     let days = days.unwrap_or(30).clamp(1, 90);
     let workspace_path = workspace_path.and_then(|value| {
         let trimmed = value.trim();
@@ -60,7 +61,7 @@ fn scan_local_usage(
     days: u32,
     workspace_path: Option<&Path>,
     sessions_roots: &[PathBuf],
-) -> Result<LocalUsageSnapshot, String> {
+) -> Result<LocalUsageSnapshot, CoreError> {
     let updated_at = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap_or_default()
@@ -184,7 +185,7 @@ fn scan_file(
     daily: &mut HashMap<String, DailyTotals>,
     model_totals: &mut HashMap<String, i64>,
     workspace_path: Option<&Path>,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     let file = match File::open(path) {
         Ok(file) => file,
         Err(_) => {
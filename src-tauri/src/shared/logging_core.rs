@@ -0,0 +1,386 @@
+//! Logging setup shared by the app backend and the daemon, built on `tracing`.
+//! Events are filtered with an `EnvFilter`, echoed to stderr, written to a
+//! daily-rotated file under `<data-dir>/logs` and kept in a small in-memory
+//! buffer that `daemon_logs_tail` reads.
+//!
+//! Log with the `tracing` macros. Tag events about a workspace with a
+//! `workspace_id` field (`tracing::warn!(workspace_id = %id, "...")`) so the
+//! tail can filter on it.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Mutex, OnceLock};
+
+use chrono::{SecondsFormat, TimeZone, Utc};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{reload, EnvFilter, Layer, Registry};
+
+use crate::shared::core_error::CoreError;
+use crate::types::{LogFormat, LoggingSettings};
+
+/// Environment variable that overrides `LoggingSettings::level`.
+pub(crate) const LOG_FILTER_ENV: &str = "CODEX_MONITOR_LOG";
+pub(crate) const LOG_DIR_NAME: &str = "logs";
+const RECENT_CAPACITY: usize = 2_000;
+const DEFAULT_TAIL_LIMIT: usize = 200;
+
+struct Logging {
+    filter: reload::Handle<EnvFilter, Registry>,
+    log_dir: Option<PathBuf>,
+    file_stem: String,
+}
+
+static LOGGING: OnceLock<Logging> = OnceLock::new();
+static RECENT: Mutex<VecDeque<LogRecord>> = Mutex::new(VecDeque::new());
+
+/// Parses an `EnvFilter` spec such as `info,codex_monitor_daemon::push=debug`.
+/// Targets without a directive log at `info`.
+pub(crate) fn parse_filter(spec: &str) -> Result<EnvFilter, CoreError> {
+    EnvFilter::builder()
+        .with_default_directive(LevelFilter::INFO.into())
+        .parse(spec)
+        .map_err(|err| CoreError::internal(format!("invalid log filter `{spec}`: {err}")))
+}
+
+/// `CODEX_MONITOR_LOG` if it parses, else `logging.level`, else `info`, plus
+/// a warning for each spec that was skipped.
+fn build_filter(settings: &LoggingSettings) -> (EnvFilter, Vec<String>) {
+    let mut warnings = Vec::new();
+    if let Ok(spec) = std::env::var(LOG_FILTER_ENV) {
+        match parse_filter(&spec) {
+            Ok(filter) => return (filter, warnings),
+            Err(err) => warnings.push(format!("ignoring {LOG_FILTER_ENV}: {err}")),
+        }
+    }
+    let filter = parse_filter(&settings.level).unwrap_or_else(|err| {
+        warnings.push(format!("ignoring logging.level: {err}"));
+        parse_filter("").expect("empty filter parses")
+    });
+    (filter, warnings)
+}
+
+fn output_layer<S, W>(format: LogFormat, writer: W) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+    W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+{
+    let layer = tracing_subscriber::fmt::layer()
+        .with_ansi(false)
+        .with_writer(writer);
+    match format {
+        LogFormat::Text => layer.boxed(),
+        LogFormat::Json => layer.json().flatten_event(true).boxed(),
+    }
+}
+
+/// Installs the global subscriber. Called once at startup, after the data dir
+/// is known; events logged before that are dropped. Without `log_dir` nothing
+/// is written to disk.
+pub(crate) fn init_logging(log_dir: Option<&Path>, file_stem: &str, settings: &LoggingSettings) {
+    let (filter, mut warnings) = build_filter(settings);
+    let (filter, handle) = reload::Layer::new(filter);
+    let file = log_dir.and_then(|dir| {
+        let appender = fs::create_dir_all(dir)
+            .map_err(|err| err.to_string())
+            .and_then(|()| {
+                RollingFileAppender::builder()
+                    .rotation(Rotation::DAILY)
+                    .filename_prefix(file_stem)
+                    .filename_suffix("log")
+                    .max_log_files(settings.max_files.max(1) as usize)
+                    .build(dir)
+                    .map_err(|err| err.to_string())
+            });
+        match appender {
+            Ok(appender) => Some(appender),
+            Err(err) => {
+                warnings.push(format!("not writing logs to {}: {err}", dir.display()));
+                None
+            }
+        }
+    });
+    let installed = tracing_subscriber::registry()
+        .with(filter)
+        .with(output_layer(settings.format, std::io::stderr))
+        .with(file.map(|file| output_layer(settings.format, file)))
+        .with(RecentLayer)
+        .try_init()
+        .is_ok();
+    if installed {
+        let _ = LOGGING.set(Logging {
+            filter: handle,
+            log_dir: log_dir.map(Path::to_path_buf),
+            file_stem: file_stem.to_string(),
+        });
+    }
+    for warning in warnings {
+        tracing::warn!("{warning}");
+    }
+}
+
+/// Applies `AppSettings::logging` whenever settings are saved. Only the filter
+/// changes at runtime; format and rotation apply on the next start.
+pub(crate) fn configure_logging(settings: &LoggingSettings) {
+    let Some(logging) = LOGGING.get() else {
+        return;
+    };
+    let (filter, warnings) = build_filter(settings);
+    if let Err(err) = logging.filter.reload(filter) {
+        tracing::warn!("failed to apply log filter: {err}");
+    }
+    for warning in warnings {
+        tracing::warn!("{warning}");
+    }
+}
+
+/// Today's log file; the appender names files `<stem>.<yyyy-mm-dd>.log` in UTC.
+pub(crate) fn log_file_path() -> Option<PathBuf> {
+    let logging = LOGGING.get()?;
+    let dir = logging.log_dir.as_ref()?;
+    Some(dir.join(format!(
+        "{}.{}.log",
+        logging.file_stem,
+        Utc::now().format("%Y-%m-%d")
+    )))
+}
+
+fn target_matches(target: &str, directive: &str) -> bool {
+    let target: Vec<&str> = target.split("::").collect();
+    let directive: Vec<&str> = directive.split("::").collect();
+    target
+        .windows(directive.len())
+        .any(|window| window == directive.as_slice())
+}
+
+fn level_name(level: Level) -> String {
+    level.as_str().to_ascii_lowercase()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct LogRecord {
+    pub(crate) timestamp_ms: i64,
+    pub(crate) level: Level,
+    pub(crate) target: String,
+    pub(crate) message: String,
+    pub(crate) workspace_id: Option<String>,
+}
+
+impl LogRecord {
+    pub(crate) fn to_json(&self) -> Value {
+        let timestamp = Utc
+            .timestamp_millis_opt(self.timestamp_ms)
+            .single()
+            .unwrap_or_default()
+            .to_rfc3339_opts(SecondsFormat::Millis, true);
+        let mut record = Map::new();
+        record.insert("ts".to_string(), json!(timestamp));
+        record.insert("level".to_string(), json!(level_name(self.level)));
+        record.insert("target".to_string(), json!(self.target));
+        record.insert("message".to_string(), json!(self.message));
+        if let Some(workspace_id) = &self.workspace_id {
+            record.insert("workspaceId".to_string(), json!(workspace_id));
+        }
+        Value::Object(record)
+    }
+}
+
+/// Collects an event's message and `workspace_id`; other fields are appended
+/// to the message as `name=value`.
+#[derive(Default)]
+struct RecordFields {
+    message: String,
+    workspace_id: Option<String>,
+    extra: Vec<String>,
+}
+
+impl RecordFields {
+    fn record(&mut self, field: &Field, value: String) {
+        match field.name() {
+            "message" => self.message = value,
+            "workspace_id" => self.workspace_id = Some(value),
+            name => self.extra.push(format!("{name}={value}")),
+        }
+    }
+}
+
+impl Visit for RecordFields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record(field, value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.record(field, format!("{value:?}"));
+    }
+}
+
+/// Keeps the last events in memory for `tail`.
+pub(crate) struct RecentLayer;
+
+impl<S: Subscriber> Layer<S> for RecentLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut fields = RecordFields::default();
+        event.record(&mut fields);
+        let mut message = fields.message;
+        for extra in fields.extra {
+            if !message.is_empty() {
+                message.push(' ');
+            }
+            message.push_str(&extra);
+        }
+        let metadata = event.metadata();
+        let record = LogRecord {
+            timestamp_ms: Utc::now().timestamp_millis(),
+            level: *metadata.level(),
+            target: metadata.target().to_string(),
+            message,
+            workspace_id: fields.workspace_id,
+        };
+        let mut recent = RECENT.lock().unwrap_or_else(|err| err.into_inner());
+        if recent.len() == RECENT_CAPACITY {
+            recent.pop_front();
+        }
+        recent.push_back(record);
+    }
+}
+
+/// Params of `daemon_logs_tail`.
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LogTailQuery {
+    /// Newest records to return, capped at the buffer size.
+    #[serde(default)]
+    pub(crate) limit: Option<usize>,
+    /// Minimum severity, e.g. `warn` returns warnings and errors.
+    #[serde(default)]
+    pub(crate) level: Option<String>,
+    /// Module path segments, e.g. `push` matches `codex_monitor_daemon::push`.
+    #[serde(default)]
+    pub(crate) target: Option<String>,
+    #[serde(default)]
+    pub(crate) workspace_id: Option<String>,
+}
+
+/// Recent records matching `query`, oldest first.
+pub(crate) fn tail(query: &LogTailQuery) -> Result<Vec<LogRecord>, CoreError> {
+    let min_level = match query.level.as_deref() {
+        Some(level) => Some(
+            Level::from_str(level)
+                .map_err(|_| CoreError::internal(format!("invalid log level `{level}`")))?,
+        ),
+        None => None,
+    };
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_TAIL_LIMIT)
+        .min(RECENT_CAPACITY);
+    let recent = RECENT.lock().unwrap_or_else(|err| err.into_inner());
+    let mut records: Vec<LogRecord> = recent
+        .iter()
+        .rev()
+        .filter(|record| min_level.is_none_or(|min_level| record.level <= min_level))
+        .filter(|record| {
+            query
+                .target
+                .as_deref()
+                .is_none_or(|target| target_matches(&record.target, target))
+        })
+        .filter(|record| query.workspace_id.is_none() || record.workspace_id == query.workspace_id)
+        .take(limit)
+        .cloned()
+        .collect();
+    records.reverse();
+    Ok(records)
+}
+
+/// `daemon_logs_tail` result: `{ entries: [...], logFile }`.
+pub(crate) fn tail_value(query: &LogTailQuery) -> Result<Value, CoreError> {
+    let entries: Vec<Value> = tail(query)?.iter().map(LogRecord::to_json).collect();
+    Ok(json!({
+        "entries": entries,
+        "logFile": log_file_path().map(|path| path.display().to_string()),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_reject_unknown_levels() {
+        assert!(parse_filter("warn,codex_monitor_daemon::push=debug").is_ok());
+        assert!(parse_filter("").is_ok());
+        assert!(parse_filter("push=loud").is_err());
+    }
+
+    #[test]
+    fn records_render_as_json() {
+        let record = LogRecord {
+            timestamp_ms: 0,
+            level: Level::WARN,
+            target: "codex_monitor_lib::backend::app_server".to_string(),
+            message: "codex stderr".to_string(),
+            workspace_id: Some("ws-1".to_string()),
+        };
+        assert_eq!(
+            record.to_json(),
+            json!({
+                "ts": "1970-01-01T00:00:00.000Z",
+                "level": "warn",
+                "target": "codex_monitor_lib::backend::app_server",
+                "message": "codex stderr",
+                "workspaceId": "ws-1",
+            })
+        );
+    }
+
+    #[test]
+    fn tail_filters_by_level_target_and_workspace() {
+        let marker = format!("tail-test-{}", uuid::Uuid::new_v4());
+        let subscriber = tracing_subscriber::registry().with(RecentLayer);
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::warn!(target: "tail_test", workspace_id = "ws-a", "{marker} a");
+            tracing::info!(target: "tail_test", workspace_id = "ws-b", attempt = 2, "{marker} b");
+            tracing::error!(target: "tail_test::inner", "{marker} c");
+        });
+
+        let query = LogTailQuery {
+            level: Some("warn".to_string()),
+            target: Some("tail_test".to_string()),
+            ..LogTailQuery::default()
+        };
+        let messages: Vec<String> = tail(&query)
+            .unwrap()
+            .into_iter()
+            .map(|record| record.message)
+            .filter(|message| message.starts_with(&marker))
+            .collect();
+        assert_eq!(messages, vec![format!("{marker} a"), format!("{marker} c")]);
+
+        let query = LogTailQuery {
+            workspace_id: Some("ws-b".to_string()),
+            limit: Some(1),
+            ..LogTailQuery::default()
+        };
+        let records = tail(&query).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].message, format!("{marker} b attempt=2"));
+        assert!(tail(&LogTailQuery {
+            level: Some("loud".to_string()),
+            ..LogTailQuery::default()
+        })
+        .is_err());
+    }
+}
@@ -2,17 +2,26 @@ pub(crate) mod account;
 pub(crate) mod agents_config_core;
 pub(crate) mod codex_aux_core;
 pub(crate) mod codex_core;
+pub(crate) mod codex_rpc;
 pub(crate) mod codex_update_core;
 pub(crate) mod config_toml_core;
+pub(crate) mod core_error;
 pub(crate) mod files_core;
 pub(crate) mod git_core;
 pub(crate) mod git_rpc;
 pub(crate) mod git_ui_core;
 pub(crate) mod local_usage_core;
+pub(crate) mod logging_core;
 pub(crate) mod process_core;
 pub(crate) mod prompts_core;
+pub(crate) mod rpc_schema;
 pub(crate) mod settings_core;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+pub(crate) mod terminal_core;
+pub(crate) mod terminal_rpc;
+pub(crate) mod tls_core;
 pub(crate) mod transcription_chatgpt_core;
+pub(crate) mod wire_compression_core;
 pub(crate) mod workspace_rpc;
 pub(crate) mod workspaces_core;
 pub(crate) mod worktree_core;
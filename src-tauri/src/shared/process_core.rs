@@ -5,6 +5,7 @@ use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
 #[cfg(windows)]
 use std::process::Stdio;
+use std::time::Duration;
 
 use tokio::process::{Child, Command};
 
@@ -50,6 +51,25 @@ pub(crate) async fn kill_child_process_tree(child: &mut Child) {
     let _ = child.kill().await;
 }
 
+/// Asks the child to exit (SIGTERM on Unix) and kills the tree if it is still
+/// running after `grace`.
+#[allow(dead_code)]
+pub(crate) async fn terminate_child_process_tree(child: &mut Child, grace: Duration) {
+    #[cfg(unix)]
+    {
+        if let Some(pid) = child.id() {
+            let _ = unsafe { libc::kill(pid as i32, libc::SIGTERM) };
+            if tokio::time::timeout(grace, child.wait()).await.is_ok() {
+                return;
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = grace;
+
+    kill_child_process_tree(child).await;
+}
+
 #[cfg(target_os = "windows")]
 pub(crate) fn resolve_windows_executable(program: &str, path_env: Option<&str>) -> Option<PathBuf> {
     let trimmed = program.trim();
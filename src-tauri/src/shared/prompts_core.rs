@@ -1,3 +1,4 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
@@ -6,9 +7,10 @@ use tokio::sync::Mutex;
 use tokio::task;
 
 use crate::codex::home::{resolve_default_codex_home, resolve_workspace_codex_home};
+use crate::shared::core_error::CoreError;
 use crate::types::WorkspaceEntry;
 
-#[derive(Deserialize, Serialize, Clone)]
+#[derive(Deserialize, Serialize, Clone, JsonSchema)]
 pub(crate) struct CustomPromptEntry {
     pub(crate) name: String,
     pub(crate) path: String,
@@ -41,21 +43,24 @@ fn default_prompts_dir_for_workspace(
 fn require_workspace_entry(
     workspaces: &HashMap<String, WorkspaceEntry>,
     workspace_id: &str,
-) -> Result<WorkspaceEntry, String> {
+) -> Result<WorkspaceEntry, CoreError> {
     workspaces
         .get(workspace_id)
         .cloned()
-        .ok_or_else(|| "workspace not found".to_string())
+        .ok_or_else(CoreError::workspace_not_found)
 }
 
-fn app_data_dir(settings_path: &Path) -> Result<PathBuf, String> {
+fn app_data_dir(settings_path: &Path) -> Result<PathBuf, CoreError> {
     settings_path
         .parent()
         .map(|path| path.to_path_buf())
-        .ok_or_else(|| "Unable to resolve app data dir.".to_string())
+        .ok_or_else(|| CoreError::internal("Unable to resolve app data dir."))
 }
 
-fn workspace_prompts_dir(settings_path: &Path, entry: &WorkspaceEntry) -> Result<PathBuf, String> {
+fn workspace_prompts_dir(
+    settings_path: &Path,
+    entry: &WorkspaceEntry,
+) -> Result<PathBuf, CoreError> {
     let data_dir = app_data_dir(settings_path)?;
     Ok(data_dir.join("workspaces").join(&entry.id).join("prompts"))
 }
@@ -64,7 +69,7 @@ fn prompt_roots_for_workspace(
     settings_path: &Path,
     workspaces: &HashMap<String, WorkspaceEntry>,
     entry: &WorkspaceEntry,
-) -> Result<Vec<PathBuf>, String> {
+) -> Result<Vec<PathBuf>, CoreError> {
     let mut roots = Vec::new();
     roots.push(workspace_prompts_dir(settings_path, entry)?);
     if let Some(global_dir) = default_prompts_dir_for_workspace(workspaces, entry) {
@@ -73,10 +78,10 @@ fn prompt_roots_for_workspace(
     Ok(roots)
 }
 
-fn ensure_path_within_roots(path: &Path, roots: &[PathBuf]) -> Result<(), String> {
+fn ensure_path_within_roots(path: &Path, roots: &[PathBuf]) -> Result<(), CoreError> {
     let canonical_path = path
         .canonicalize()
-        .map_err(|_| "Invalid prompt path.".to_string())?;
+        .map_err(|_| CoreError::invalid_params("Invalid prompt path."))?;
     for root in roots {
         if let Ok(canonical_root) = root.canonicalize() {
             if canonical_path.starts_with(&canonical_root) {
@@ -84,7 +89,9 @@ fn ensure_path_within_roots(path: &Path, roots: &[PathBuf]) -> Result<(), String
             }
         }
     }
-    Err("Prompt path is not within allowed directories.".to_string())
+    Err(CoreError::invalid_params(
+        "Prompt path is not within allowed directories.",
+    ))
 }
 
 #[cfg(unix)]
@@ -97,14 +104,14 @@ fn is_cross_device_error(_err: &std::io::Error) -> bool {
     false
 }
 
-fn move_file(src: &Path, dest: &Path) -> Result<(), String> {
+fn move_file(src: &Path, dest: &Path) -> Result<(), CoreError> {
     match fs::rename(src, dest) {
         Ok(()) => Ok(()),
         Err(err) if is_cross_device_error(&err) => {
             fs::copy(src, dest).map_err(|err| err.to_string())?;
-            fs::remove_file(src).map_err(|err| err.to_string())
+            fs::remove_file(src).map_err(|err| CoreError::internal(err.to_string()))
         }
-        Err(err) => Err(err.to_string()),
+        Err(err) => Err(CoreError::internal(err.to_string())),
     }
 }
 
@@ -208,16 +215,20 @@ fn build_prompt_contents(
     output
 }
 
-fn sanitize_prompt_name(name: &str) -> Result<String, String> {
+fn sanitize_prompt_name(name: &str) -> Result<String, CoreError> {
     let trimmed = name.trim();
     if trimmed.is_empty() {
-        return Err("Prompt name is required.".to_string());
+        return Err(CoreError::invalid_params("Prompt name is required."));
     }
     if trimmed.chars().any(|ch| ch.is_whitespace()) {
-        return Err("Prompt name cannot include whitespace.".to_string());
+        return Err(CoreError::invalid_params(
+            "Prompt name cannot include whitespace.",
+        ));
     }
     if trimmed.contains('/') || trimmed.contains('\\') {
-        return Err("Prompt name cannot include path separators.".to_string());
+        return Err(CoreError::invalid_params(
+            "Prompt name cannot include path separators.",
+        ));
     }
     Ok(trimmed.to_string())
 }
@@ -273,7 +284,7 @@ pub(crate) async fn prompts_list_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     settings_path: &Path,
     workspace_id: String,
-) -> Result<Vec<CustomPromptEntry>, String> {
+) -> Result<Vec<CustomPromptEntry>, CoreError> {
     let (workspace_dir, global_dir) = {
         let workspaces = workspaces.lock().await;
         let entry = workspaces.get(&workspace_id).cloned();
@@ -299,14 +310,14 @@ pub(crate) async fn prompts_list_core(
         out
     })
     .await
-    .map_err(|_| "prompt discovery failed".to_string())
+    .map_err(|_| CoreError::internal("prompt discovery failed"))
 }
 
 pub(crate) async fn prompts_workspace_dir_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     settings_path: &Path,
     workspace_id: String,
-) -> Result<String, String> {
+) -> Result<String, CoreError> {
     let dir = {
         let workspaces = workspaces.lock().await;
         let entry = require_workspace_entry(&workspaces, &workspace_id)?;
@@ -319,11 +330,11 @@ pub(crate) async fn prompts_workspace_dir_core(
 pub(crate) async fn prompts_global_dir_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
-) -> Result<String, String> {
+) -> Result<String, CoreError> {
     let workspaces = workspaces.lock().await;
     let entry = require_workspace_entry(&workspaces, &workspace_id)?;
     let dir = default_prompts_dir_for_workspace(&workspaces, &entry)
-        .ok_or("Unable to resolve CODEX_HOME".to_string())?;
+        .ok_or_else(|| CoreError::internal("Unable to resolve CODEX_HOME"))?;
     fs::create_dir_all(&dir).map_err(|err| err.to_string())?;
     Ok(dir.to_string_lossy().to_string())
 }
@@ -337,7 +348,7 @@ pub(crate) async fn prompts_create_core(
     description: Option<String>,
     argument_hint: Option<String>,
     content: String,
-) -> Result<CustomPromptEntry, String> {
+) -> Result<CustomPromptEntry, CoreError> {
     let name = sanitize_prompt_name(&name)?;
     let (target_dir, resolved_scope) = {
         let workspaces = workspaces.lock().await;
@@ -349,15 +360,15 @@ pub(crate) async fn prompts_create_core(
             }
             "global" => {
                 let dir = default_prompts_dir_for_workspace(&workspaces, &entry)
-                    .ok_or("Unable to resolve CODEX_HOME".to_string())?;
+                    .ok_or_else(|| CoreError::internal("Unable to resolve CODEX_HOME"))?;
                 (dir, "global")
             }
-            _ => return Err("Invalid scope.".to_string()),
+            _ => return Err(CoreError::invalid_params("Invalid scope.")),
         }
     };
     let path = target_dir.join(format!("{name}.md"));
     if path.exists() {
-        return Err("Prompt already exists.".to_string());
+        return Err(CoreError::invalid_params("Prompt already exists."));
     }
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent).map_err(|err| err.to_string())?;
@@ -383,11 +394,11 @@ pub(crate) async fn prompts_update_core(
     description: Option<String>,
     argument_hint: Option<String>,
     content: String,
-) -> Result<CustomPromptEntry, String> {
+) -> Result<CustomPromptEntry, CoreError> {
     let name = sanitize_prompt_name(&name)?;
     let target_path = PathBuf::from(&path);
     if !target_path.exists() {
-        return Err("Prompt not found.".to_string());
+        return Err(CoreError::invalid_params("Prompt not found."));
     }
     {
         let workspaces = workspaces.lock().await;
@@ -397,10 +408,12 @@ pub(crate) async fn prompts_update_core(
     }
     let dir = target_path
         .parent()
-        .ok_or("Unable to resolve prompt directory.".to_string())?;
+        .ok_or_else(|| CoreError::internal("Unable to resolve prompt directory."))?;
     let next_path = dir.join(format!("{name}.md"));
     if next_path != target_path && next_path.exists() {
-        return Err("Prompt with that name already exists.".to_string());
+        return Err(CoreError::invalid_params(
+            "Prompt with that name already exists.",
+        ));
     }
     let body = build_prompt_contents(description.clone(), argument_hint.clone(), content.clone());
     fs::write(&next_path, body).map_err(|err| err.to_string())?;
@@ -432,7 +445,7 @@ pub(crate) async fn prompts_delete_core(
     settings_path: &Path,
     workspace_id: String,
     path: String,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     let target = PathBuf::from(path);
     if !target.exists() {
         return Ok(());
@@ -443,7 +456,7 @@ pub(crate) async fn prompts_delete_core(
         let roots = prompt_roots_for_workspace(settings_path, &workspaces, &entry)?;
         ensure_path_within_roots(&target, &roots)?;
     }
-    fs::remove_file(&target).map_err(|err| err.to_string())
+    fs::remove_file(&target).map_err(|err| CoreError::internal(err.to_string()))
 }
 
 pub(crate) async fn prompts_move_core(
@@ -452,10 +465,10 @@ pub(crate) async fn prompts_move_core(
     workspace_id: String,
     path: String,
     scope: String,
-) -> Result<CustomPromptEntry, String> {
+) -> Result<CustomPromptEntry, CoreError> {
     let target_path = PathBuf::from(&path);
     if !target_path.exists() {
-        return Err("Prompt not found.".to_string());
+        return Err(CoreError::invalid_params("Prompt not found."));
     }
     let roots = {
         let workspaces = workspaces.lock().await;
@@ -466,23 +479,27 @@ pub(crate) async fn prompts_move_core(
     let file_name = target_path
         .file_name()
         .and_then(|value| value.to_str())
-        .ok_or("Invalid prompt path.".to_string())?;
+        .ok_or_else(|| CoreError::invalid_params("Invalid prompt path."))?;
     let target_dir = {
         let workspaces = workspaces.lock().await;
         let entry = require_workspace_entry(&workspaces, &workspace_id)?;
         match scope.as_str() {
             "workspace" => workspace_prompts_dir(settings_path, &entry)?,
             "global" => default_prompts_dir_for_workspace(&workspaces, &entry)
-                .ok_or("Unable to resolve CODEX_HOME".to_string())?,
-            _ => return Err("Invalid scope.".to_string()),
+                .ok_or_else(|| CoreError::internal("Unable to resolve CODEX_HOME"))?,
+            _ => return Err(CoreError::invalid_params("Invalid scope.")),
         }
     };
     let next_path = target_dir.join(file_name);
     if next_path == target_path {
-        return Err("Prompt is already in that scope.".to_string());
+        return Err(CoreError::invalid_params(
+            "Prompt is already in that scope.",
+        ));
     }
     if next_path.exists() {
-        return Err("Prompt with that name already exists.".to_string());
+        return Err(CoreError::invalid_params(
+            "Prompt with that name already exists.",
+        ));
     }
     if let Some(parent) = next_path.parent() {
         fs::create_dir_all(parent).map_err(|err| err.to_string())?;
@@ -0,0 +1,176 @@
+#![allow(dead_code)]
+
+//! Wire contract between the app, the daemon and daemonctl: the JSON-RPC
+//! envelope, the protocol version and which methods may be retried. The
+//! daemon's typed method catalog lives next to its dispatcher.
+
+use std::collections::HashSet;
+
+use serde_json::{json, Value};
+
+use super::core_error::CoreError;
+
+/// Bumped whenever a method is removed or its params change incompatibly.
+/// Adding methods or optional params does not require a bump.
+pub(crate) const PROTOCOL_VERSION: u32 = 1;
+
+pub(crate) const METHOD_RPC_DESCRIBE: &str = "rpc_describe";
+
+pub(crate) fn request(id: u64, method: &str, params: Value) -> Value {
+    json!({
+        "id": id,
+        "method": method,
+        "params": params,
+    })
+}
+
+/// Serializes a request without the trailing newline.
+pub(crate) fn request_line(id: u64, method: &str, params: Value) -> Result<String, String> {
+    serde_json::to_string(&request(id, method, params)).map_err(|err| err.to_string())
+}
+
+pub(crate) fn result_response(id: u64, result: Value) -> Value {
+    json!({ "id": id, "result": result })
+}
+
+pub(crate) fn error_response(id: u64, error: &CoreError) -> Value {
+    json!({
+        "id": id,
+        "error": error.to_value()
+    })
+}
+
+pub(crate) fn response_error(response: &Value) -> Option<CoreError> {
+    response.get("error").map(CoreError::from_value)
+}
+
+pub(crate) fn response_error_message(response: &Value) -> Option<String> {
+    response
+        .get("error")
+        .and_then(|error| error.get("message"))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+}
+
+/// Splits a response into its `result` or its error.
+pub(crate) fn response_result(response: &Value) -> Result<Value, CoreError> {
+    if let Some(error) = response_error(response) {
+        return Err(error);
+    }
+    response
+        .get("result")
+        .cloned()
+        .ok_or_else(|| CoreError::internal("daemon response missing result"))
+}
+
+/// Methods that are safe to resend on a fresh connection when the first
+/// attempt was cut off: reads, and writes that converge to the same state.
+/// The daemon's catalog reports the same flag as `retryAfterDisconnect`.
+pub(crate) const RETRY_AFTER_DISCONNECT: &[&str] = &[
+    "presence_heartbeat",
+    "push_register_device",
+    "push_unregister_device",
+    "push_device_rules_get",
+    "push_device_rules_patch",
+    "push_notification_config_get",
+    "push_notification_config_patch",
+    "push_notification_state",
+    "list_workspaces",
+    "is_workspace_path_dir",
+    "worktree_setup_status",
+    "connect_workspace",
+    "set_workspace_runtime_codex_args",
+    "list_workspace_files",
+    "read_workspace_file",
+    "file_read",
+    "read_image_as_data_url",
+    "local_usage_snapshot",
+    "get_config_model",
+    "resume_thread",
+    "thread_live_subscribe",
+    "thread_live_unsubscribe",
+    "list_threads",
+    "list_mcp_server_status",
+    "model_list",
+    "experimental_feature_list",
+    "collaboration_mode_list",
+    "get_agents_settings",
+    "read_agent_config_toml",
+    "account_rate_limits",
+    "account_read",
+    "dictation_auth_status",
+    "dictation_transcribe",
+    "skills_list",
+    "apps_list",
+    "get_git_status",
+    "list_git_roots",
+    "get_git_diffs",
+    "get_git_log",
+    "get_git_commit_diff",
+    "get_git_remote",
+    "get_github_issues",
+    "get_github_pull_requests",
+    "get_github_pull_request_diff",
+    "get_github_pull_request_comments",
+    "list_git_branches",
+    "terminal_open",
+    "terminal_resize",
+    "terminal_attach",
+    "terminal_list",
+];
+
+pub(crate) fn can_retry_after_disconnect(name: &str) -> bool {
+    RETRY_AFTER_DISCONNECT.contains(&name)
+}
+
+/// Method names from an `rpc_describe` result.
+pub(crate) fn described_method_names(catalog: &Value) -> HashSet<String> {
+    catalog
+        .get("methods")
+        .and_then(Value::as_array)
+        .map(|methods| {
+            methods
+                .iter()
+                .filter_map(|method| method.get("name").and_then(Value::as_str))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_flags_cover_reads_only() {
+        assert!(can_retry_after_disconnect("list_threads"));
+        assert!(!can_retry_after_disconnect("send_user_message"));
+        assert!(!can_retry_after_disconnect("not_a_method"));
+        let names = described_method_names(&json!({
+            "methods": [{ "name": "ping" }, { "name": METHOD_RPC_DESCRIBE }, {}],
+        }));
+        assert_eq!(names.len(), 2);
+        assert!(names.contains(METHOD_RPC_DESCRIBE));
+    }
+
+    #[test]
+    fn response_result_prefers_error_message() {
+        let boom = CoreError::internal("boom");
+        assert_eq!(response_result(&error_response(3, &boom)), Err(boom));
+        let not_connected = error_response(3, &CoreError::workspace_not_connected());
+        assert_eq!(not_connected["error"]["code"], json!(-32011));
+        assert_eq!(
+            response_error_message(&not_connected).as_deref(),
+            Some("workspace not connected")
+        );
+        assert_eq!(
+            response_result(&result_response(3, json!({ "ok": true }))),
+            Ok(json!({ "ok": true }))
+        );
+        assert_eq!(
+            response_result(&json!({ "id": 3 })).map_err(String::from),
+            Err("daemon response missing result".to_string())
+        );
+    }
+}
@@ -2,7 +2,10 @@ use std::path::PathBuf;
 
 use tokio::sync::Mutex;
 
+use crate::backend::app_server::RequestTimeouts;
 use crate::codex::config as codex_config;
+use crate::shared::core_error::CoreError;
+use crate::shared::logging_core::configure_logging;
 use crate::storage::write_settings;
 use crate::types::AppSettings;
 
@@ -43,24 +46,28 @@ pub(crate) async fn update_app_settings_core(
     settings: AppSettings,
     app_settings: &Mutex<AppSettings>,
     settings_path: &PathBuf,
-) -> Result<AppSettings, String> {
+    request_timeouts: &RequestTimeouts,
+) -> Result<AppSettings, CoreError> {
+    settings.request_timeouts.validate()?;
     let _ = codex_config::write_collaboration_modes_enabled(settings.collaboration_modes_enabled);
     let _ = codex_config::write_steer_enabled(settings.steer_enabled);
     let _ = codex_config::write_unified_exec_enabled(settings.unified_exec_enabled);
     let _ = codex_config::write_apps_enabled(settings.experimental_apps_enabled);
     let _ = codex_config::write_personality(settings.personality.as_str());
     write_settings(settings_path, &settings)?;
+    request_timeouts.update(&settings.request_timeouts);
+    configure_logging(&settings.logging);
     let mut current = app_settings.lock().await;
     *current = settings.clone();
     Ok(settings)
 }
 
-pub(crate) fn get_codex_config_path_core() -> Result<String, String> {
+pub(crate) fn get_codex_config_path_core() -> Result<String, CoreError> {
     codex_config::config_toml_path()
-        .ok_or_else(|| "Unable to resolve CODEX_HOME".to_string())
+        .ok_or_else(|| CoreError::internal("Unable to resolve CODEX_HOME"))
         .and_then(|path| {
             path.to_str()
                 .map(|value| value.to_string())
-                .ok_or_else(|| "Unable to resolve CODEX_HOME".to_string())
+                .ok_or_else(|| CoreError::internal("Unable to resolve CODEX_HOME"))
         })
 }
@@ -0,0 +1,522 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use portable_pty::{native_pty_system, CommandBuilder, PtySize};
+use tokio::sync::Mutex;
+
+use crate::backend::events::{EventSink, TerminalExit, TerminalOutput};
+use crate::shared::core_error::CoreError;
+use crate::shared::terminal_rpc::{TerminalAttachInfo, TerminalListEntry, TerminalOpenRequest};
+use crate::types::WorkspaceEntry;
+
+mod scrollback;
+
+use scrollback::{
+    list_scrollback, read_scrollback, remove_scrollback, remove_workspace_scrollback,
+    scrollback_path, Scrollback,
+};
+
+pub(crate) use scrollback::scrollback_dir;
+
+pub(crate) struct TerminalSession {
+    pub(crate) id: String,
+    pub(crate) master: Mutex<Box<dyn portable_pty::MasterPty + Send>>,
+    pub(crate) writer: Mutex<Box<dyn Write + Send>>,
+    pub(crate) child: Mutex<Box<dyn portable_pty::Child + Send>>,
+    scrollback: Mutex<Scrollback>,
+}
+
+/// Open sessions keyed by `terminal_key`. Shared with each reader thread so a
+/// session can remove itself once its shell exits.
+pub(crate) type TerminalSessions = Arc<Mutex<HashMap<String, Arc<TerminalSession>>>>;
+
+pub(crate) fn terminal_key(workspace_id: &str, terminal_id: &str) -> String {
+    format!("{workspace_id}:{terminal_id}")
+}
+
+fn is_terminal_closed_error(message: &str) -> bool {
+    let lower = message.to_ascii_lowercase();
+    lower.contains("broken pipe")
+        || lower.contains("input/output error")
+        || lower.contains("os error 5")
+        || lower.contains("eio")
+        || lower.contains("io error")
+        || lower.contains("not connected")
+        || lower.contains("closed")
+}
+
+async fn get_terminal_session(
+    sessions: &TerminalSessions,
+    key: &str,
+) -> Result<Arc<TerminalSession>, CoreError> {
+    let sessions = sessions.lock().await;
+    sessions
+        .get(key)
+        .cloned()
+        .ok_or_else(|| CoreError::internal("Terminal session not found"))
+}
+
+#[cfg(target_os = "windows")]
+fn shell_path() -> String {
+    std::env::var("COMSPEC").unwrap_or_else(|_| "powershell.exe".to_string())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn shell_path() -> String {
+    std::env::var("SHELL").unwrap_or_else(|_| "/bin/zsh".to_string())
+}
+
+#[cfg(any(target_os = "windows", test))]
+fn windows_shell_args(shell: &str) -> Vec<&'static str> {
+    let shell = shell.to_ascii_lowercase();
+    if shell.contains("powershell") || shell.ends_with("pwsh.exe") || shell.ends_with("\\pwsh") {
+        vec!["-NoLogo", "-NoExit"]
+    } else if shell.ends_with("cmd.exe") || shell.ends_with("\\cmd") {
+        vec!["/K"]
+    } else {
+        Vec::new()
+    }
+}
+
+fn unix_shell_args() -> Vec<&'static str> {
+    vec!["-i"]
+}
+
+#[cfg(target_os = "windows")]
+fn configure_shell_args(cmd: &mut CommandBuilder) {
+    for arg in windows_shell_args(&shell_path()) {
+        cmd.arg(arg);
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn configure_shell_args(cmd: &mut CommandBuilder) {
+    for arg in unix_shell_args() {
+        cmd.arg(arg);
+    }
+}
+
+fn resolve_locale() -> String {
+    let candidate = std::env::var("LC_ALL")
+        .or_else(|_| std::env::var("LANG"))
+        .unwrap_or_else(|_| "en_US.UTF-8".to_string());
+    let lower = candidate.to_lowercase();
+    if lower.contains("utf-8") || lower.contains("utf8") {
+        return candidate;
+    }
+    "en_US.UTF-8".to_string()
+}
+
+fn spawn_terminal_reader(
+    event_sink: impl EventSink,
+    sessions: TerminalSessions,
+    session: Arc<TerminalSession>,
+    workspace_id: String,
+    terminal_id: String,
+    mut reader: Box<dyn Read + Send>,
+) {
+    std::thread::spawn(move || {
+        let mut buffer = [0u8; 8192];
+        let mut pending: Vec<u8> = Vec::new();
+        // Record and emit under the scrollback lock so `terminal_attach`
+        // snapshots never race a chunk that is halfway out.
+        let emit_output = |data: String| {
+            let mut scrollback = session.scrollback.blocking_lock();
+            scrollback.push(&data);
+            event_sink.emit_terminal_output(TerminalOutput {
+                workspace_id: workspace_id.clone(),
+                terminal_id: terminal_id.clone(),
+                data,
+            });
+        };
+        loop {
+            match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(count) => {
+                    pending.extend_from_slice(&buffer[..count]);
+                    loop {
+                        match std::str::from_utf8(&pending) {
+                            Ok(decoded) => {
+                                if !decoded.is_empty() {
+                                    emit_output(decoded.to_string());
+                                }
+                                pending.clear();
+                                break;
+                            }
+                            Err(error) => {
+                                let valid_up_to = error.valid_up_to();
+                                if valid_up_to == 0 {
+                                    if error.error_len().is_none() {
+                                        break;
+                                    }
+                                    let invalid_len = error.error_len().unwrap_or(1);
+                                    pending.drain(..invalid_len.min(pending.len()));
+                                    continue;
+                                }
+                                let chunk =
+                                    String::from_utf8_lossy(&pending[..valid_up_to]).to_string();
+                                if !chunk.is_empty() {
+                                    emit_output(chunk);
+                                }
+                                pending.drain(..valid_up_to);
+                                if error.error_len().is_none() {
+                                    break;
+                                }
+                                let invalid_len = error.error_len().unwrap_or(1);
+                                pending.drain(..invalid_len.min(pending.len()));
+                            }
+                        }
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+        let key = terminal_key(&workspace_id, &terminal_id);
+        event_sink.emit_terminal_exit(TerminalExit {
+            workspace_id,
+            terminal_id,
+        });
+        let mut sessions = sessions.blocking_lock();
+        let should_remove = sessions
+            .get(&key)
+            .is_some_and(|current| Arc::ptr_eq(current, &session));
+        if should_remove {
+            sessions.remove(&key);
+        }
+    });
+}
+
+async fn get_workspace_path(
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    workspace_id: &str,
+) -> Result<PathBuf, CoreError> {
+    let workspaces = workspaces.lock().await;
+    let entry = workspaces
+        .get(workspace_id)
+        .ok_or_else(CoreError::workspace_not_found)?;
+    Ok(PathBuf::from(&entry.path))
+}
+
+async fn kill_session(session: Arc<TerminalSession>) {
+    let _ = tokio::task::spawn_blocking(move || {
+        let mut child = session.child.blocking_lock();
+        let _ = child.kill();
+    })
+    .await;
+}
+
+/// Opens a shell in the workspace root, or returns the id of the session
+/// already open under the same key. Output is appended to the scrollback
+/// left behind by earlier sessions with the same ids.
+pub(crate) async fn terminal_open_core(
+    sessions: &TerminalSessions,
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    scrollback_dir: &Path,
+    request: TerminalOpenRequest,
+    event_sink: impl EventSink,
+) -> Result<String, CoreError> {
+    let TerminalOpenRequest {
+        workspace_id,
+        terminal_id,
+        cols,
+        rows,
+    } = request;
+    if terminal_id.is_empty() {
+        return Err(CoreError::internal("Terminal id is required"));
+    }
+    let key = terminal_key(&workspace_id, &terminal_id);
+    {
+        let sessions = sessions.lock().await;
+        if let Some(existing) = sessions.get(&key) {
+            return Ok(existing.id.clone());
+        }
+    }
+
+    let cwd = get_workspace_path(workspaces, &workspace_id).await?;
+    let pty_system = native_pty_system();
+    let size = PtySize {
+        rows: rows.max(2),
+        cols: cols.max(2),
+        pixel_width: 0,
+        pixel_height: 0,
+    };
+    let pair = pty_system
+        .openpty(size)
+        .map_err(|e| CoreError::internal(format!("Failed to open pty: {e}")))?;
+
+    let mut cmd = CommandBuilder::new(shell_path());
+    cmd.cwd(cwd);
+    configure_shell_args(&mut cmd);
+    cmd.env("TERM", "xterm-256color");
+    let locale = resolve_locale();
+    cmd.env("LANG", &locale);
+    cmd.env("LC_ALL", &locale);
+    cmd.env("LC_CTYPE", &locale);
+
+    let child = pair
+        .slave
+        .spawn_command(cmd)
+        .map_err(|e| CoreError::internal(format!("Failed to spawn shell: {e}")))?;
+    let reader = pair
+        .master
+        .try_clone_reader()
+        .map_err(|e| CoreError::internal(format!("Failed to open pty reader: {e}")))?;
+    let writer = pair
+        .master
+        .take_writer()
+        .map_err(|e| CoreError::internal(format!("Failed to open pty writer: {e}")))?;
+
+    let session = Arc::new(TerminalSession {
+        id: terminal_id.clone(),
+        master: Mutex::new(pair.master),
+        writer: Mutex::new(writer),
+        child: Mutex::new(child),
+        scrollback: Mutex::new(Scrollback::open(scrollback_path(
+            scrollback_dir,
+            &workspace_id,
+            &terminal_id,
+        ))),
+    });
+    let session_id = session.id.clone();
+
+    {
+        let mut open_sessions = sessions.lock().await;
+        if let Some(existing) = open_sessions.get(&key) {
+            let id = existing.id.clone();
+            drop(open_sessions);
+            kill_session(session).await;
+            return Ok(id);
+        }
+        open_sessions.insert(key, Arc::clone(&session));
+    }
+    spawn_terminal_reader(
+        event_sink,
+        Arc::clone(sessions),
+        session,
+        workspace_id,
+        terminal_id,
+        reader,
+    );
+
+    Ok(session_id)
+}
+
+pub(crate) async fn terminal_write_core(
+    sessions: &TerminalSessions,
+    workspace_id: &str,
+    terminal_id: &str,
+    data: String,
+) -> Result<(), CoreError> {
+    let key = terminal_key(workspace_id, terminal_id);
+    let session = get_terminal_session(sessions, &key).await?;
+    let write_result = tokio::task::spawn_blocking(move || {
+        let mut writer = session.writer.blocking_lock();
+        writer
+            .write_all(data.as_bytes())
+            .map_err(|e| CoreError::internal(format!("Failed to write to pty: {e}")))?;
+        writer
+            .flush()
+            .map_err(|e| CoreError::internal(format!("Failed to flush pty: {e}")))?;
+        Ok::<(), CoreError>(())
+    })
+    .await
+    .map_err(|e| CoreError::internal(format!("Terminal write task failed: {e}")))?;
+
+    if let Err(err) = write_result {
+        if is_terminal_closed_error(&err.message) {
+            sessions.lock().await.remove(&key);
+        }
+        return Err(err);
+    }
+    Ok(())
+}
+
+pub(crate) async fn terminal_resize_core(
+    sessions: &TerminalSessions,
+    workspace_id: &str,
+    terminal_id: &str,
+    cols: u16,
+    rows: u16,
+) -> Result<(), CoreError> {
+    let key = terminal_key(workspace_id, terminal_id);
+    let session = get_terminal_session(sessions, &key).await?;
+    let size = PtySize {
+        rows: rows.max(2),
+        cols: cols.max(2),
+        pixel_width: 0,
+        pixel_height: 0,
+    };
+    let resize_result = tokio::task::spawn_blocking(move || {
+        let master = session.master.blocking_lock();
+        master
+            .resize(size)
+            .map_err(|e| CoreError::internal(format!("Failed to resize pty: {e}")))
+    })
+    .await
+    .map_err(|e| CoreError::internal(format!("Terminal resize task failed: {e}")))?;
+    if let Err(err) = resize_result {
+        if is_terminal_closed_error(&err.message) {
+            sessions.lock().await.remove(&key);
+        }
+        return Err(err);
+    }
+    Ok(())
+}
+
+/// Returns the scrollback for a terminal, followed live by its
+/// `terminal-output` events while `running` is true. Sessions that are gone,
+/// for example after a restart, still return what was saved on disk.
+pub(crate) async fn terminal_attach_core(
+    sessions: &TerminalSessions,
+    scrollback_dir: &Path,
+    workspace_id: &str,
+    terminal_id: &str,
+) -> Result<TerminalAttachInfo, CoreError> {
+    if terminal_id.is_empty() {
+        return Err(CoreError::internal("Terminal id is required"));
+    }
+    let key = terminal_key(workspace_id, terminal_id);
+    let session = sessions.lock().await.get(&key).cloned();
+    if let Some(session) = session {
+        let scrollback = session.scrollback.lock().await.snapshot();
+        return Ok(TerminalAttachInfo {
+            running: true,
+            scrollback,
+        });
+    }
+    let scrollback = scrollback_path(scrollback_dir, workspace_id, terminal_id)
+        .map(|path| read_scrollback(&path))
+        .unwrap_or_default();
+    Ok(TerminalAttachInfo {
+        running: false,
+        scrollback,
+    })
+}
+
+/// Open sessions and saved scrollback for a workspace, by terminal id.
+pub(crate) async fn terminal_list_core(
+    sessions: &TerminalSessions,
+    scrollback_dir: &Path,
+    workspace_id: &str,
+) -> Vec<TerminalListEntry> {
+    let mut running = BTreeMap::new();
+    for terminal_id in list_scrollback(scrollback_dir, workspace_id) {
+        running.insert(terminal_id, false);
+    }
+    let prefix = terminal_key(workspace_id, "");
+    for key in sessions.lock().await.keys() {
+        if let Some(terminal_id) = key.strip_prefix(&prefix) {
+            running.insert(terminal_id.to_string(), true);
+        }
+    }
+    running
+        .into_iter()
+        .map(|(terminal_id, running)| TerminalListEntry {
+            terminal_id,
+            running,
+        })
+        .collect()
+}
+
+/// Stops every shell but keeps their scrollback, so terminals reopened after a
+/// daemon restart pick up where they left off.
+#[allow(dead_code)]
+pub(crate) async fn terminal_close_all_core(sessions: &TerminalSessions) {
+    let drained: Vec<Arc<TerminalSession>> = sessions
+        .lock()
+        .await
+        .drain()
+        .map(|(_, session)| session)
+        .collect();
+    for session in drained {
+        kill_session(session).await;
+    }
+}
+
+/// Stops the shell and deletes its scrollback.
+pub(crate) async fn terminal_close_core(
+    sessions: &TerminalSessions,
+    scrollback_dir: &Path,
+    workspace_id: &str,
+    terminal_id: &str,
+) -> Result<(), CoreError> {
+    let key = terminal_key(workspace_id, terminal_id);
+    let session = sessions.lock().await.remove(&key);
+    let Some(session) = session else {
+        // A terminal left over from a previous run only has its scrollback.
+        let removed = scrollback_path(scrollback_dir, workspace_id, terminal_id)
+            .is_some_and(|path| remove_scrollback(&path));
+        if removed {
+            return Ok(());
+        }
+        return Err(CoreError::internal("Terminal session not found"));
+    };
+    session.scrollback.lock().await.discard();
+    kill_session(session).await;
+    Ok(())
+}
+
+/// Stops every shell of a removed workspace and deletes its scrollback.
+pub(crate) async fn terminal_close_workspace_core(
+    sessions: &TerminalSessions,
+    scrollback_dir: &Path,
+    workspace_id: &str,
+) {
+    let prefix = terminal_key(workspace_id, "");
+    let closed: Vec<Arc<TerminalSession>> = {
+        let mut sessions = sessions.lock().await;
+        let keys: Vec<String> = sessions
+            .keys()
+            .filter(|key| key.starts_with(&prefix))
+            .cloned()
+            .collect();
+        keys.iter().filter_map(|key| sessions.remove(key)).collect()
+    };
+    for session in closed {
+        session.scrollback.lock().await.discard();
+        kill_session(session).await;
+    }
+    remove_workspace_scrollback(scrollback_dir, workspace_id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{unix_shell_args, windows_shell_args};
+
+    #[test]
+    fn windows_shell_args_match_powershell_variants() {
+        assert_eq!(
+            windows_shell_args(r"C:\Windows\System32\WindowsPowerShell\v1.0\powershell.exe"),
+            vec!["-NoLogo", "-NoExit"]
+        );
+        assert_eq!(
+            windows_shell_args(r"C:\Program Files\PowerShell\7\pwsh.exe"),
+            vec!["-NoLogo", "-NoExit"]
+        );
+        assert_eq!(
+            windows_shell_args(r"C:\Program Files\PowerShell\7\PwSh"),
+            vec!["-NoLogo", "-NoExit"]
+        );
+    }
+
+    #[test]
+    fn windows_shell_args_match_cmd_variants() {
+        assert_eq!(
+            windows_shell_args(r"C:\Windows\System32\cmd.exe"),
+            vec!["/K"]
+        );
+        assert_eq!(windows_shell_args(r"C:\Windows\System32\CMD"), vec!["/K"]);
+    }
+
+    #[test]
+    fn windows_shell_args_are_empty_for_other_shells() {
+        assert!(windows_shell_args("nu.exe").is_empty());
+    }
+
+    #[test]
+    fn unix_shell_args_stay_interactive() {
+        assert_eq!(unix_shell_args(), vec!["-i"]);
+    }
+}
@@ -0,0 +1,236 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Output kept per terminal. The log on disk is compacted back to this size
+/// once it grows past twice the limit.
+pub(crate) const MAX_SCROLLBACK_BYTES: usize = 256 * 1024;
+
+const LOG_EXTENSION: &str = "log";
+
+pub(crate) fn scrollback_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join("terminals")
+}
+
+fn is_plain_file_name(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= 128
+        && value
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_')
+}
+
+/// `<dir>/<workspace id>/<terminal id>.log`. Ids that are not plain file
+/// names get no log and keep their scrollback in memory only.
+pub(super) fn scrollback_path(
+    dir: &Path,
+    workspace_id: &str,
+    terminal_id: &str,
+) -> Option<PathBuf> {
+    if !is_plain_file_name(workspace_id) || !is_plain_file_name(terminal_id) {
+        return None;
+    }
+    Some(
+        dir.join(workspace_id)
+            .join(terminal_id)
+            .with_extension(LOG_EXTENSION),
+    )
+}
+
+fn trim_to_tail(buffer: &mut String, limit: usize) {
+    if buffer.len() <= limit {
+        return;
+    }
+    let mut cut = buffer.len() - limit;
+    while !buffer.is_char_boundary(cut) {
+        cut += 1;
+    }
+    buffer.drain(..cut);
+}
+
+pub(super) fn read_scrollback(path: &Path) -> String {
+    let Ok(bytes) = fs::read(path) else {
+        return String::new();
+    };
+    let mut text = String::from_utf8_lossy(&bytes).into_owned();
+    trim_to_tail(&mut text, MAX_SCROLLBACK_BYTES);
+    text
+}
+
+pub(super) fn remove_scrollback(path: &Path) -> bool {
+    let removed = fs::remove_file(path).is_ok();
+    if let Some(parent) = path.parent() {
+        // Only succeeds once the workspace has no logs left.
+        let _ = fs::remove_dir(parent);
+    }
+    removed
+}
+
+/// Deletes every log kept for `workspace_id`.
+pub(super) fn remove_workspace_scrollback(dir: &Path, workspace_id: &str) {
+    if is_plain_file_name(workspace_id) {
+        let _ = fs::remove_dir_all(dir.join(workspace_id));
+    }
+}
+
+/// Terminal ids with a log on disk for `workspace_id`.
+pub(super) fn list_scrollback(dir: &Path, workspace_id: &str) -> Vec<String> {
+    if !is_plain_file_name(workspace_id) {
+        return Vec::new();
+    }
+    let Ok(entries) = fs::read_dir(dir.join(workspace_id)) else {
+        return Vec::new();
+    };
+    let mut ids: Vec<String> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == LOG_EXTENSION))
+        .filter_map(|path| {
+            path.file_stem()
+                .and_then(|stem| stem.to_str())
+                .map(str::to_string)
+        })
+        .filter(|id| is_plain_file_name(id))
+        .collect();
+    ids.sort();
+    ids
+}
+
+pub(super) struct Scrollback {
+    buffer: String,
+    path: Option<PathBuf>,
+    file: Option<File>,
+    file_len: usize,
+}
+
+impl Scrollback {
+    /// Picks up where an earlier session under the same key left off.
+    pub(super) fn open(path: Option<PathBuf>) -> Self {
+        let buffer = path.as_deref().map(read_scrollback).unwrap_or_default();
+        let mut scrollback = Self {
+            buffer,
+            path,
+            file: None,
+            file_len: 0,
+        };
+        scrollback.rewrite_file();
+        scrollback
+    }
+
+    pub(super) fn push(&mut self, data: &str) {
+        self.buffer.push_str(data);
+        trim_to_tail(&mut self.buffer, MAX_SCROLLBACK_BYTES);
+        if self.file_len + data.len() > MAX_SCROLLBACK_BYTES * 2 {
+            self.rewrite_file();
+            return;
+        }
+        let Some(file) = self.file.as_mut() else {
+            return;
+        };
+        if file.write_all(data.as_bytes()).is_ok() {
+            self.file_len += data.len();
+        } else {
+            self.file = None;
+        }
+    }
+
+    pub(super) fn snapshot(&self) -> String {
+        self.buffer.clone()
+    }
+
+    /// Deletes the log and stops writing it, for sessions that were closed.
+    pub(super) fn discard(&mut self) {
+        self.file = None;
+        if let Some(path) = self.path.take() {
+            let _ = remove_scrollback(&path);
+        }
+    }
+
+    fn rewrite_file(&mut self) {
+        self.file = None;
+        self.file_len = 0;
+        let Some(path) = self.path.as_deref() else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            if fs::create_dir_all(parent).is_err() {
+                return;
+            }
+        }
+        if fs::write(path, self.buffer.as_bytes()).is_err() {
+            return;
+        }
+        self.file = OpenOptions::new().append(true).open(path).ok();
+        self.file_len = self.buffer.len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(prefix: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "codex-monitor-{prefix}-{}-{}",
+            std::process::id(),
+            uuid::Uuid::new_v4()
+        ));
+        fs::create_dir_all(&dir).expect("create temp dir");
+        dir
+    }
+
+    #[test]
+    fn scrollback_path_rejects_ids_that_are_not_file_names() {
+        let dir = Path::new("/tmp/terminals");
+        assert_eq!(
+            scrollback_path(dir, "ws-1", "term_2"),
+            Some(PathBuf::from("/tmp/terminals/ws-1/term_2.log"))
+        );
+        assert_eq!(scrollback_path(dir, "..", "term"), None);
+        assert_eq!(scrollback_path(dir, "ws-1", "a/b"), None);
+        assert_eq!(scrollback_path(dir, "ws-1", ""), None);
+    }
+
+    #[test]
+    fn scrollback_keeps_a_bounded_tail_and_reloads_it_from_disk() {
+        let dir = temp_dir("scrollback");
+        let path = scrollback_path(&dir, "ws-1", "term-1");
+
+        let mut scrollback = Scrollback::open(path.clone());
+        let chunk = "é".repeat(1000);
+        for _ in 0..400 {
+            scrollback.push(&chunk);
+        }
+        scrollback.push("tail");
+        let snapshot = scrollback.snapshot();
+        assert!(snapshot.len() <= MAX_SCROLLBACK_BYTES);
+        assert!(snapshot.ends_with("tail"));
+        let on_disk = fs::metadata(path.as_deref().expect("path"))
+            .expect("log")
+            .len();
+        assert!(on_disk as usize <= MAX_SCROLLBACK_BYTES * 2);
+
+        let reopened = Scrollback::open(path.clone());
+        assert_eq!(reopened.snapshot(), snapshot);
+        assert_eq!(list_scrollback(&dir, "ws-1"), vec!["term-1".to_string()]);
+
+        remove_scrollback(path.as_deref().expect("path"));
+        assert!(list_scrollback(&dir, "ws-1").is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn removing_a_workspace_deletes_all_of_its_logs() {
+        let dir = temp_dir("scrollback-workspace");
+        for (workspace_id, terminal_id) in [("ws-1", "a"), ("ws-1", "b"), ("ws-2", "a")] {
+            Scrollback::open(scrollback_path(&dir, workspace_id, terminal_id)).push("$ ls\n");
+        }
+
+        remove_workspace_scrollback(&dir, "ws-1");
+        remove_workspace_scrollback(&dir, "..");
+
+        assert!(!dir.join("ws-1").exists());
+        assert_eq!(list_scrollback(&dir, "ws-2"), vec!["a".to_string()]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
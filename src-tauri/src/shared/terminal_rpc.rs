@@ -0,0 +1,81 @@
+#![allow(dead_code)]
+
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::shared::core_error::CoreError;
+
+pub(crate) const METHOD_TERMINAL_OPEN: &str = "terminal_open";
+pub(crate) const METHOD_TERMINAL_WRITE: &str = "terminal_write";
+pub(crate) const METHOD_TERMINAL_RESIZE: &str = "terminal_resize";
+pub(crate) const METHOD_TERMINAL_CLOSE: &str = "terminal_close";
+pub(crate) const METHOD_TERMINAL_ATTACH: &str = "terminal_attach";
+pub(crate) const METHOD_TERMINAL_LIST: &str = "terminal_list";
+
+pub(crate) fn to_params<T: Serialize>(request: &T) -> Result<Value, CoreError> {
+    serde_json::to_value(request).map_err(|err| CoreError::internal(err.to_string()))
+}
+
+pub(crate) fn from_params<T: DeserializeOwned>(params: &Value) -> Result<T, CoreError> {
+    serde_json::from_value(params.clone()).map_err(|err| CoreError::invalid_params(err.to_string()))
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub(crate) struct TerminalSessionInfo {
+    pub(crate) id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TerminalAttachInfo {
+    pub(crate) running: bool,
+    pub(crate) scrollback: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TerminalListEntry {
+    pub(crate) terminal_id: String,
+    pub(crate) running: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TerminalOpenRequest {
+    pub(crate) workspace_id: String,
+    pub(crate) terminal_id: String,
+    pub(crate) cols: u16,
+    pub(crate) rows: u16,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TerminalWriteRequest {
+    pub(crate) workspace_id: String,
+    pub(crate) terminal_id: String,
+    pub(crate) data: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TerminalResizeRequest {
+    pub(crate) workspace_id: String,
+    pub(crate) terminal_id: String,
+    pub(crate) cols: u16,
+    pub(crate) rows: u16,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TerminalIdRequest {
+    pub(crate) workspace_id: String,
+    pub(crate) terminal_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TerminalListRequest {
+    pub(crate) workspace_id: String,
+}
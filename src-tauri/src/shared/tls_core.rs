@@ -0,0 +1,213 @@
+#![allow(dead_code)]
+
+use std::sync::Arc;
+
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{verify_tls12_signature, verify_tls13_signature, CryptoProvider};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme};
+use sha2::{Digest, Sha256};
+
+pub(crate) fn crypto_provider() -> Arc<CryptoProvider> {
+    Arc::new(rustls::crypto::ring::default_provider())
+}
+
+/// SHA-256 of the DER certificate, formatted as colon-separated uppercase hex
+/// (the same shape `openssl x509 -fingerprint -sha256` prints).
+pub(crate) fn certificate_fingerprint(der: &[u8]) -> String {
+    Sha256::digest(der)
+        .iter()
+        .map(|byte| format!("{byte:02X}"))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Accepts fingerprints with or without separators and an optional `sha256:`
+/// prefix so users can paste whatever their tooling printed.
+pub(crate) fn normalize_certificate_fingerprint(value: &str) -> Option<String> {
+    let trimmed = value.trim();
+    let without_prefix = trimmed
+        .get(..7)
+        .filter(|prefix| prefix.eq_ignore_ascii_case("sha256:"))
+        .map(|_| &trimmed[7..])
+        .unwrap_or(trimmed);
+    let hex = without_prefix
+        .chars()
+        .filter(|ch| !matches!(ch, ':' | ' ' | '-'))
+        .map(|ch| ch.to_ascii_lowercase())
+        .collect::<String>();
+    if hex.len() != 64 || !hex.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        return None;
+    }
+    Some(hex)
+}
+
+pub(crate) fn server_name_for_host(host: &str) -> Result<ServerName<'static>, String> {
+    let trimmed = host.trim();
+    let name = if let Some(rest) = trimmed.strip_prefix('[') {
+        rest.split_once(']').map(|(name, _)| name).unwrap_or(rest)
+    } else {
+        match trimmed.rsplit_once(':') {
+            Some((name, port)) if port.chars().all(|ch| ch.is_ascii_digit()) => name,
+            _ => trimmed,
+        }
+    };
+    ServerName::try_from(name.to_string()).map_err(|_| format!("Invalid TLS server name: {name}"))
+}
+
+/// Builds a client config that either pins the server certificate by
+/// fingerprint or, when no fingerprint is configured, validates against the
+/// bundled web PKI roots.
+pub(crate) fn build_client_config(cert_fingerprint: Option<&str>) -> Result<ClientConfig, String> {
+    let provider = crypto_provider();
+    let builder = ClientConfig::builder_with_provider(Arc::clone(&provider))
+        .with_safe_default_protocol_versions()
+        .map_err(|err| err.to_string())?;
+    let pinned = cert_fingerprint
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
+    let config = match pinned {
+        Some(value) => {
+            let fingerprint = normalize_certificate_fingerprint(value)
+                .ok_or_else(|| format!("Invalid TLS certificate fingerprint: {value}"))?;
+            builder
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(PinnedCertificateVerifier {
+                    fingerprint,
+                    provider,
+                }))
+                .with_no_client_auth()
+        }
+        None => {
+            let roots = RootCertStore {
+                roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+            };
+            builder.with_root_certificates(roots).with_no_client_auth()
+        }
+    };
+    Ok(config)
+}
+
+/// Trusts exactly one leaf certificate, identified by its SHA-256 fingerprint.
+/// Hostnames and chains are ignored, which is what makes self-signed daemon
+/// certificates usable, but handshake signatures are still verified.
+#[derive(Debug)]
+struct PinnedCertificateVerifier {
+    fingerprint: String,
+    provider: Arc<CryptoProvider>,
+}
+
+impl ServerCertVerifier for PinnedCertificateVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let actual = certificate_fingerprint(end_entity.as_ref());
+        if normalize_certificate_fingerprint(&actual).as_deref() == Some(self.fingerprint.as_str())
+        {
+            Ok(ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::General(format!(
+                "certificate fingerprint mismatch (server presented {actual})"
+            )))
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        build_client_config, certificate_fingerprint, normalize_certificate_fingerprint,
+        server_name_for_host,
+    };
+
+    #[test]
+    fn fingerprint_is_colon_separated_sha256() {
+        let fingerprint = certificate_fingerprint(b"codex-monitor");
+        assert_eq!(fingerprint.len(), 32 * 3 - 1);
+        assert!(fingerprint
+            .split(':')
+            .all(|part| part.len() == 2 && part.chars().all(|ch| ch.is_ascii_hexdigit())));
+    }
+
+    #[test]
+    fn normalize_fingerprint_accepts_common_formats() {
+        let fingerprint = certificate_fingerprint(b"codex-monitor");
+        let expected = normalize_certificate_fingerprint(&fingerprint).expect("normalized");
+        let compact = fingerprint.replace(':', "").to_ascii_lowercase();
+        assert_eq!(
+            normalize_certificate_fingerprint(&compact).as_deref(),
+            Some(expected.as_str())
+        );
+        assert_eq!(
+            normalize_certificate_fingerprint(&format!("SHA256:{fingerprint}")).as_deref(),
+            Some(expected.as_str())
+        );
+        assert_eq!(normalize_certificate_fingerprint("AB:CD"), None);
+        assert_eq!(normalize_certificate_fingerprint(&"zz".repeat(32)), None);
+    }
+
+    #[test]
+    fn server_name_strips_port_and_brackets() {
+        assert_eq!(
+            server_name_for_host("daemon.example:4732")
+                .expect("dns name")
+                .to_str(),
+            "daemon.example"
+        );
+        assert_eq!(
+            server_name_for_host("[::1]:4732").expect("ipv6").to_str(),
+            "::1"
+        );
+        assert_eq!(
+            server_name_for_host("127.0.0.1:4732")
+                .expect("ipv4")
+                .to_str(),
+            "127.0.0.1"
+        );
+    }
+
+    #[test]
+    fn client_config_rejects_malformed_fingerprint() {
+        assert!(build_client_config(Some("not-a-fingerprint")).is_err());
+        assert!(build_client_config(None).is_ok());
+    }
+}
@@ -1,6 +1,7 @@
 use base64::engine::general_purpose::{URL_SAFE, URL_SAFE_NO_PAD};
 use base64::Engine as _;
 use reqwest::StatusCode;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::collections::HashMap;
@@ -8,10 +9,11 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use crate::backend::app_server::WorkspaceSession;
+use crate::shared::core_error::{CoreError, ErrorCode};
 
 const TRANSCRIBE_URL: &str = "https://chatgpt.com/backend-api/transcribe";
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct DictationAuthStatus {
     pub(crate) authenticated: bool,
@@ -69,11 +71,11 @@ pub(crate) async fn dictation_auth_status_core(
             account_id: Some(auth.account_id),
             message: None,
         },
-        Err(message) => DictationAuthStatus {
+        Err(error) => DictationAuthStatus {
             authenticated: false,
             auth_method: None,
             account_id: None,
-            message: Some(message),
+            message: Some(error.message),
         },
     }
 }
@@ -84,17 +86,24 @@ pub(crate) async fn dictation_transcribe_chatgpt_core(
     audio: Vec<u8>,
     mime_type: String,
     language: Option<String>,
-) -> Result<String, String> {
+) -> Result<String, CoreError> {
     let workspace_id = workspace_id.trim().to_string();
     if workspace_id.is_empty() {
-        return Err("workspaceId is required for ChatGPT dictation.".to_string());
+        return Err(CoreError::invalid_params(
+            "workspaceId is required for ChatGPT dictation.",
+        ));
     }
 
     let session = {
         let sessions = sessions.lock().await;
         sessions.get(&workspace_id).cloned()
     }
-    .ok_or_else(|| "Workspace is not connected to Codex.".to_string())?;
+    .ok_or_else(|| {
+        CoreError::new(
+            ErrorCode::WorkspaceNotConnected,
+            "Workspace is not connected to Codex.",
+        )
+    })?;
 
     let auth = fetch_transcription_auth(&session, &workspace_id, false).await?;
 
@@ -120,12 +129,12 @@ pub(crate) async fn dictation_transcribe_chatgpt_core(
             .await
             .map_err(|err| match err {
                 TranscriptionRequestError::Unauthorized(detail) => {
-                    format_http_error(StatusCode::UNAUTHORIZED, detail)
+                    CoreError::internal(format_http_error(StatusCode::UNAUTHORIZED, detail))
                 }
-                TranscriptionRequestError::Other(message) => message,
+                TranscriptionRequestError::Other(message) => CoreError::internal(message),
             })
         }
-        Err(TranscriptionRequestError::Other(message)) => Err(message),
+        Err(TranscriptionRequestError::Other(message)) => Err(CoreError::internal(message)),
     }
 }
 
@@ -133,7 +142,7 @@ async fn fetch_transcription_auth(
     session: &WorkspaceSession,
     workspace_id: &str,
     refresh_token: bool,
-) -> Result<TranscriptionAuth, String> {
+) -> Result<TranscriptionAuth, CoreError> {
     let response = session
         .send_request_for_workspace(
             workspace_id,
@@ -148,11 +157,13 @@ async fn fetch_transcription_auth(
     let root = response.get("result").unwrap_or(&response);
     let auth_method = read_string(root, &["authMethod", "auth_method"]).unwrap_or_default();
     if !auth_method.eq_ignore_ascii_case("chatgpt") {
-        return Err("ChatGPT authentication is required. Sign in with ChatGPT in Codex first.".to_string());
+        return Err(CoreError::internal(
+            "ChatGPT authentication is required. Sign in with ChatGPT in Codex first.",
+        ));
     }
 
     let auth_token = read_string(root, &["authToken", "auth_token"])
-        .ok_or_else(|| "Missing ChatGPT auth token from Codex app-server.".to_string())?;
+        .ok_or_else(|| CoreError::internal("Missing ChatGPT auth token from Codex app-server."))?;
 
     let account_id = decode_jwt_account_id(&auth_token)
         .or_else(|| {
@@ -166,7 +177,9 @@ async fn fetch_transcription_auth(
                 ],
             )
         })
-        .ok_or_else(|| "Unable to resolve ChatGPT account id from auth token.".to_string())?;
+        .ok_or_else(|| {
+            CoreError::internal("Unable to resolve ChatGPT account id from auth token.")
+        })?;
 
     Ok(TranscriptionAuth {
         auth_token,
@@ -0,0 +1,426 @@
+#![allow(dead_code)]
+
+use std::io;
+
+use flate2::{Compress, Compression, Decompress, FlushCompress, FlushDecompress};
+use serde_json::Value;
+use tokio::io::{
+    AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt,
+};
+use zstd::stream::raw::{Decoder as ZstdDecoder, Encoder as ZstdEncoder, Operation};
+
+/// Upper bound for one compressed frame on a line-based transport, so a
+/// corrupt length prefix cannot make the reader allocate without limit.
+pub(crate) const MAX_COMPRESSED_FRAME_BYTES: usize = 64 * 1024 * 1024;
+/// Upper bound for one message once decompressed, so a small frame cannot
+/// expand without limit.
+pub(crate) const MAX_DECOMPRESSED_MESSAGE_BYTES: usize = 64 * 1024 * 1024;
+const ZSTD_LEVEL: i32 = 3;
+const BUFFER_CHUNK: usize = 16 * 1024;
+
+/// Compression a connection can switch to after `auth` via `protocol_upgrade`.
+/// Each direction keeps one compression context for the whole connection and
+/// flushes it after every message, so repeated JSON keys compress well even in
+/// small streaming deltas.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum WireCompression {
+    Zstd,
+    Deflate,
+}
+
+impl WireCompression {
+    /// Supported algorithms, most preferred first.
+    pub(crate) const SUPPORTED: [WireCompression; 2] =
+        [WireCompression::Zstd, WireCompression::Deflate];
+
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "zstd" => Some(Self::Zstd),
+            "deflate" => Some(Self::Deflate),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Zstd => "zstd",
+            Self::Deflate => "deflate",
+        }
+    }
+
+    pub(crate) fn advertised() -> Vec<&'static str> {
+        Self::SUPPORTED
+            .iter()
+            .map(|compression| compression.as_str())
+            .collect()
+    }
+
+    /// Picks our most preferred algorithm among those the peer advertised.
+    pub(crate) fn negotiate(advertised: &Value) -> Option<Self> {
+        let advertised: Vec<Self> = advertised
+            .as_array()?
+            .iter()
+            .filter_map(Value::as_str)
+            .filter_map(Self::parse)
+            .collect();
+        Self::SUPPORTED
+            .into_iter()
+            .find(|compression| advertised.contains(compression))
+    }
+}
+
+pub(crate) struct FrameEncoder {
+    inner: EncoderInner,
+}
+
+enum EncoderInner {
+    Zstd(Box<ZstdEncoder<'static>>),
+    Deflate(Box<Compress>),
+}
+
+impl FrameEncoder {
+    pub(crate) fn new(compression: WireCompression) -> Result<Self, String> {
+        let inner = match compression {
+            WireCompression::Zstd => EncoderInner::Zstd(Box::new(
+                ZstdEncoder::new(ZSTD_LEVEL).map_err(|err| err.to_string())?,
+            )),
+            WireCompression::Deflate => {
+                EncoderInner::Deflate(Box::new(Compress::new(Compression::default(), false)))
+            }
+        };
+        Ok(Self { inner })
+    }
+
+    /// Compresses one message and flushes it so the peer can decode it without
+    /// waiting for more data.
+    pub(crate) fn encode(&mut self, message: &[u8]) -> Result<Vec<u8>, String> {
+        match &mut self.inner {
+            EncoderInner::Zstd(encoder) => zstd_encode(encoder, message),
+            EncoderInner::Deflate(compress) => deflate_encode(compress, message),
+        }
+        .map_err(|err| format!("Failed to compress message: {err}"))
+    }
+}
+
+pub(crate) struct FrameDecoder {
+    inner: DecoderInner,
+}
+
+enum DecoderInner {
+    Zstd(Box<ZstdDecoder<'static>>),
+    Deflate(Box<Decompress>),
+}
+
+impl FrameDecoder {
+    pub(crate) fn new(compression: WireCompression) -> Result<Self, String> {
+        let inner = match compression {
+            WireCompression::Zstd => {
+                DecoderInner::Zstd(Box::new(ZstdDecoder::new().map_err(|err| err.to_string())?))
+            }
+            WireCompression::Deflate => DecoderInner::Deflate(Box::new(Decompress::new(false))),
+        };
+        Ok(Self { inner })
+    }
+
+    /// Decodes one frame produced by [`FrameEncoder::encode`] into a message.
+    pub(crate) fn decode(&mut self, frame: &[u8]) -> Result<String, String> {
+        let bytes = match &mut self.inner {
+            DecoderInner::Zstd(decoder) => zstd_decode(decoder, frame),
+            DecoderInner::Deflate(decompress) => deflate_decode(decompress, frame),
+        }
+        .map_err(|err| format!("Failed to decompress message: {err}"))?;
+        String::from_utf8(bytes).map_err(|_| "Decompressed message is not UTF-8".to_string())
+    }
+}
+
+fn zstd_encode(encoder: &mut ZstdEncoder<'static>, message: &[u8]) -> io::Result<Vec<u8>> {
+    let mut output = Vec::new();
+    let mut chunk = vec![0u8; BUFFER_CHUNK];
+    let mut consumed = 0;
+    while consumed < message.len() {
+        let status = encoder.run_on_buffers(&message[consumed..], &mut chunk)?;
+        consumed += status.bytes_read;
+        output.extend_from_slice(&chunk[..status.bytes_written]);
+    }
+    loop {
+        let mut buffer = zstd::stream::raw::OutBuffer::around(chunk.as_mut_slice());
+        let remaining = encoder.flush(&mut buffer)?;
+        let written = buffer.pos();
+        output.extend_from_slice(&chunk[..written]);
+        if remaining == 0 {
+            return Ok(output);
+        }
+    }
+}
+
+fn zstd_decode(decoder: &mut ZstdDecoder<'static>, frame: &[u8]) -> io::Result<Vec<u8>> {
+    let mut output = Vec::new();
+    let mut chunk = vec![0u8; BUFFER_CHUNK];
+    let mut consumed = 0;
+    loop {
+        let status = decoder.run_on_buffers(&frame[consumed..], &mut chunk)?;
+        consumed += status.bytes_read;
+        push_decoded(&mut output, &chunk[..status.bytes_written])?;
+        // A full output buffer may mean the decoder still holds data.
+        if consumed == frame.len() && status.bytes_written < chunk.len() {
+            return Ok(output);
+        }
+    }
+}
+
+fn push_decoded(output: &mut Vec<u8>, decoded: &[u8]) -> io::Result<()> {
+    if output.len() + decoded.len() > MAX_DECOMPRESSED_MESSAGE_BYTES {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "decompressed message exceeds the limit",
+        ));
+    }
+    output.extend_from_slice(decoded);
+    Ok(())
+}
+
+fn deflate_encode(compress: &mut Compress, message: &[u8]) -> io::Result<Vec<u8>> {
+    let start = compress.total_in();
+    let mut output = Vec::with_capacity(message.len() / 2 + 64);
+    loop {
+        let consumed = (compress.total_in() - start) as usize;
+        if output.capacity() - output.len() < 64 {
+            output.reserve(BUFFER_CHUNK);
+        }
+        compress
+            .compress_vec(&message[consumed..], &mut output, FlushCompress::Sync)
+            .map_err(io::Error::other)?;
+        let consumed = (compress.total_in() - start) as usize;
+        if consumed == message.len() && output.len() < output.capacity() {
+            return Ok(output);
+        }
+    }
+}
+
+fn deflate_decode(decompress: &mut Decompress, frame: &[u8]) -> io::Result<Vec<u8>> {
+    let start = decompress.total_in();
+    let mut output = Vec::new();
+    let mut chunk = vec![0u8; BUFFER_CHUNK];
+    loop {
+        let consumed = (decompress.total_in() - start) as usize;
+        let written_before = decompress.total_out();
+        decompress
+            .decompress(&frame[consumed..], &mut chunk, FlushDecompress::Sync)
+            .map_err(io::Error::other)?;
+        let written = (decompress.total_out() - written_before) as usize;
+        push_decoded(&mut output, &chunk[..written])?;
+        let consumed_now = (decompress.total_in() - start) as usize;
+        if consumed_now == frame.len() && written < chunk.len() {
+            return Ok(output);
+        }
+        if consumed_now == consumed && written == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "truncated compressed frame",
+            ));
+        }
+    }
+}
+
+/// Writes a frame as a 4-byte big-endian length followed by the payload.
+pub(crate) async fn write_frame<W>(writer: &mut W, payload: &[u8]) -> io::Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    let len = u32::try_from(payload.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "frame too large"))?;
+    writer.write_all(&len.to_be_bytes()).await?;
+    writer.write_all(payload).await
+}
+
+/// Reads one length-prefixed frame. Returns `None` on a clean end of stream.
+pub(crate) async fn read_frame<R>(reader: &mut R) -> io::Result<Option<Vec<u8>>>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut len = [0u8; 4];
+    match reader.read_exact(&mut len).await {
+        Ok(_) => {}
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err),
+    }
+    let len = u32::from_be_bytes(len) as usize;
+    if len > MAX_COMPRESSED_FRAME_BYTES {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("compressed frame of {len} bytes exceeds the limit"),
+        ));
+    }
+    let mut payload = vec![0u8; len];
+    reader.read_exact(&mut payload).await?;
+    Ok(Some(payload))
+}
+
+/// Writes one message on a byte-stream transport: a JSON line while `encoder`
+/// is unset, a compressed length-prefixed frame afterwards.
+pub(crate) async fn write_message<W>(
+    writer: &mut W,
+    encoder: Option<&mut FrameEncoder>,
+    message: &str,
+) -> Result<(), String>
+where
+    W: AsyncWrite + Unpin,
+{
+    match encoder {
+        Some(encoder) => {
+            let frame = encoder.encode(message.as_bytes())?;
+            write_frame(writer, &frame)
+                .await
+                .map_err(|err| err.to_string())?;
+        }
+        None => {
+            writer
+                .write_all(message.as_bytes())
+                .await
+                .map_err(|err| err.to_string())?;
+            writer
+                .write_all(b"\n")
+                .await
+                .map_err(|err| err.to_string())?;
+        }
+    }
+    writer.flush().await.map_err(|err| err.to_string())
+}
+
+/// Reads the counterpart of [`write_message`]. Returns `None` at end of stream.
+pub(crate) async fn read_message<R>(
+    reader: &mut R,
+    decoder: Option<&mut FrameDecoder>,
+) -> Result<Option<String>, String>
+where
+    R: AsyncBufRead + Unpin,
+{
+    match decoder {
+        Some(decoder) => match read_frame(reader).await.map_err(|err| err.to_string())? {
+            Some(frame) => decoder.decode(&frame).map(Some),
+            None => Ok(None),
+        },
+        None => {
+            let mut line = String::new();
+            match reader.read_line(&mut line).await {
+                Ok(0) => Ok(None),
+                Ok(_) => Ok(Some(line)),
+                Err(err) => Err(err.to_string()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        read_frame, read_message, write_frame, write_message, FrameDecoder, FrameEncoder,
+        WireCompression, MAX_DECOMPRESSED_MESSAGE_BYTES,
+    };
+    use serde_json::json;
+
+    #[test]
+    fn round_trips_messages_with_a_shared_context() {
+        for compression in WireCompression::SUPPORTED {
+            let mut encoder = FrameEncoder::new(compression).expect("encoder");
+            let mut decoder = FrameDecoder::new(compression).expect("decoder");
+            let delta = json!({
+                "method": "app-server-event",
+                "params": { "workspace_id": "ws-1", "message": { "method": "item/agentMessage/delta" } },
+            })
+            .to_string();
+            let large = "x".repeat(200_000);
+            let mut sizes = Vec::new();
+            for message in [delta.as_str(), large.as_str(), "", delta.as_str()] {
+                let frame = encoder.encode(message.as_bytes()).expect("encode");
+                sizes.push(frame.len());
+                assert_eq!(decoder.decode(&frame).expect("decode"), message);
+            }
+            // The repeated delta reuses the context built up by the first one.
+            assert!(sizes[3] < sizes[0], "{compression:?}: {sizes:?}");
+        }
+    }
+
+    #[test]
+    fn frames_that_inflate_past_the_limit_are_rejected() {
+        let bomb = vec![b' '; MAX_DECOMPRESSED_MESSAGE_BYTES + 1];
+        for compression in WireCompression::SUPPORTED {
+            let mut encoder = FrameEncoder::new(compression).expect("encoder");
+            let mut decoder = FrameDecoder::new(compression).expect("decoder");
+            let frame = encoder.encode(&bomb).expect("encode");
+            assert!(frame.len() < 1024 * 1024);
+            let err = decoder.decode(&frame).expect_err("oversized message");
+            assert!(err.contains("exceeds the limit"), "{err}");
+        }
+    }
+
+    #[test]
+    fn negotiate_prefers_zstd_and_ignores_unknown_names() {
+        assert_eq!(
+            WireCompression::negotiate(&json!(["deflate", "zstd"])),
+            Some(WireCompression::Zstd)
+        );
+        assert_eq!(
+            WireCompression::negotiate(&json!(["brotli", "deflate"])),
+            Some(WireCompression::Deflate)
+        );
+        assert_eq!(WireCompression::negotiate(&json!(["brotli"])), None);
+        assert_eq!(WireCompression::negotiate(&json!(null)), None);
+    }
+
+    #[test]
+    fn frames_are_length_prefixed() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .expect("runtime");
+        runtime.block_on(async {
+            let mut buffer = Vec::new();
+            write_frame(&mut buffer, b"abc").await.expect("write");
+            write_frame(&mut buffer, b"").await.expect("write");
+            assert_eq!(&buffer[..7], &[0, 0, 0, 3, b'a', b'b', b'c']);
+
+            let mut reader = buffer.as_slice();
+            assert_eq!(
+                read_frame(&mut reader).await.expect("read"),
+                Some(b"abc".to_vec())
+            );
+            assert_eq!(
+                read_frame(&mut reader).await.expect("read"),
+                Some(Vec::new())
+            );
+            assert_eq!(read_frame(&mut reader).await.expect("read"), None);
+        });
+    }
+
+    #[test]
+    fn messages_switch_from_lines_to_frames() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .expect("runtime");
+        runtime.block_on(async {
+            let mut encoder = FrameEncoder::new(WireCompression::Deflate).expect("encoder");
+            let mut buffer = Vec::new();
+            write_message(&mut buffer, None, "{\"id\":1}")
+                .await
+                .expect("write");
+            write_message(&mut buffer, Some(&mut encoder), "{\"id\":2}")
+                .await
+                .expect("write");
+
+            let mut decoder = FrameDecoder::new(WireCompression::Deflate).expect("decoder");
+            let mut reader = tokio::io::BufReader::new(buffer.as_slice());
+            let first = read_message(&mut reader, None).await.expect("read");
+            assert_eq!(first.as_deref(), Some("{\"id\":1}\n"));
+            let second = read_message(&mut reader, Some(&mut decoder))
+                .await
+                .expect("read");
+            assert_eq!(second.as_deref(), Some("{\"id\":2}"));
+            assert_eq!(
+                read_message(&mut reader, Some(&mut decoder))
+                    .await
+                    .expect("read"),
+                None
+            );
+        });
+    }
+}
@@ -1,53 +1,63 @@
 #![allow(dead_code)]
 
+use schemars::JsonSchema;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::types::WorkspaceSettings;
+use crate::files::policy::{FileKind, FileScope};
+use crate::shared::core_error::CoreError;
+use crate::types::{AppSettings, WorkspaceSettings};
 
 #[allow(dead_code)]
-pub(crate) fn to_params<T: Serialize>(request: &T) -> Result<Value, String> {
-    serde_json::to_value(request).map_err(|err| err.to_string())
+pub(crate) fn to_params<T: Serialize>(request: &T) -> Result<Value, CoreError> {
+    serde_json::to_value(request).map_err(|err| CoreError::internal(err.to_string()))
 }
 
+/// Missing params are treated as an empty object so requests whose fields are
+/// all optional accept `null`.
 #[allow(dead_code)]
-pub(crate) fn from_params<T: DeserializeOwned>(params: &Value) -> Result<T, String> {
-    serde_json::from_value(params.clone()).map_err(|err| err.to_string())
+pub(crate) fn from_params<T: DeserializeOwned>(params: &Value) -> Result<T, CoreError> {
+    let params = if params.is_null() {
+        Value::Object(Default::default())
+    } else {
+        params.clone()
+    };
+    serde_json::from_value(params).map_err(|err| CoreError::invalid_params(err.to_string()))
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct ReadWorkspaceFileRequest {
     pub(crate) workspace_id: String,
     pub(crate) path: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct SetWorkspaceRuntimeCodexArgsRequest {
     pub(crate) workspace_id: String,
     pub(crate) codex_args: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub(crate) struct IsWorkspacePathDirRequest {
     pub(crate) path: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub(crate) struct AddWorkspaceRequest {
     pub(crate) path: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub(crate) struct AddWorkspaceFromGitUrlRequest {
     pub(crate) url: String,
     pub(crate) destination_path: String,
     pub(crate) target_folder_name: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct AddWorktreeRequest {
     pub(crate) parent_id: String,
@@ -56,24 +66,24 @@ pub(crate) struct AddWorktreeRequest {
     pub(crate) copy_agents_md: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct WorkspaceIdRequest {
     pub(crate) workspace_id: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub(crate) struct IdRequest {
     pub(crate) id: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub(crate) struct RenameWorktreeRequest {
     pub(crate) id: String,
     pub(crate) branch: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct RenameWorktreeUpstreamRequest {
     pub(crate) id: String,
@@ -81,13 +91,13 @@ pub(crate) struct RenameWorktreeUpstreamRequest {
     pub(crate) new_branch: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub(crate) struct UpdateWorkspaceSettingsRequest {
     pub(crate) id: String,
     pub(crate) settings: WorkspaceSettings,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct AddCloneRequest {
     pub(crate) source_workspace_id: String,
@@ -95,7 +105,7 @@ pub(crate) struct AddCloneRequest {
     pub(crate) copies_folder: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub(crate) struct OpenWorkspaceInRequest {
     pub(crate) path: String,
     pub(crate) app: Option<String>,
@@ -103,8 +113,42 @@ pub(crate) struct OpenWorkspaceInRequest {
     pub(crate) command: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct GetOpenAppIconRequest {
     pub(crate) app_name: String,
 }
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct FileReadRequest {
+    pub(crate) scope: FileScope,
+    pub(crate) kind: FileKind,
+    pub(crate) workspace_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct FileWriteRequest {
+    pub(crate) scope: FileScope,
+    pub(crate) kind: FileKind,
+    pub(crate) workspace_id: Option<String>,
+    pub(crate) content: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub(crate) struct ReadImageAsDataUrlRequest {
+    pub(crate) path: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub(crate) struct UpdateAppSettingsRequest {
+    pub(crate) settings: AppSettings,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LocalUsageSnapshotRequest {
+    pub(crate) days: Option<u32>,
+    pub(crate) workspace_path: Option<String>,
+}
@@ -4,6 +4,8 @@ mod git_orchestration;
 mod helpers;
 mod io;
 mod runtime_codex_args;
+mod session_pool;
+mod supervisor;
 mod worktree;
 
 pub(crate) use connect::connect_workspace_core;
@@ -20,6 +22,8 @@ pub(crate) use io::{
 pub(crate) use runtime_codex_args::{
     set_workspace_runtime_codex_args_core, WorkspaceRuntimeCodexArgsResult,
 };
+pub(crate) use session_pool::{enforce_session_pool_core, SESSION_POOL_SWEEP_INTERVAL};
+pub(crate) use supervisor::handle_session_exit_core;
 pub(crate) use worktree::{
     add_worktree_core, remove_worktree_core, rename_worktree_core, rename_worktree_upstream_core,
     worktree_setup_mark_ran_core, worktree_setup_status_core,
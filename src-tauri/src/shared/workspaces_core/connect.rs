@@ -9,6 +9,7 @@ use tokio::sync::Mutex;
 use crate::backend::app_server::WorkspaceSession;
 use crate::codex::args::resolve_workspace_codex_args;
 use crate::codex::home::resolve_workspace_codex_home;
+use crate::shared::core_error::CoreError;
 use crate::shared::process_core::kill_child_process_tree;
 use crate::types::{AppSettings, WorkspaceEntry};
 
@@ -57,10 +58,10 @@ pub(crate) async fn connect_workspace_core<F, Fut>(
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     app_settings: &Mutex<AppSettings>,
     spawn_session: F,
-) -> Result<(), String>
+) -> Result<(), CoreError>
 where
     F: Fn(WorkspaceEntry, Option<String>, Option<String>, Option<PathBuf>) -> Fut,
-    Fut: Future<Output = Result<Arc<WorkspaceSession>, String>>,
+    Fut: Future<Output = Result<Arc<WorkspaceSession>, CoreError>>,
 {
     let (entry, parent_entry) = resolve_entry_and_parent(workspaces, &workspace_id).await?;
     let _spawn_guard = workspace_session_spawn_lock().lock().await;
@@ -126,13 +127,12 @@ pub(super) async fn kill_session_by_id(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backend::test_support::session;
 
-    use std::collections::{HashMap, HashSet};
-    use std::process::Stdio;
-    use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
     use std::sync::Arc;
 
-    use tokio::process::Command;
     use tokio::sync::Mutex;
 
     use crate::types::{WorkspaceKind, WorkspaceSettings};
@@ -149,40 +149,6 @@ mod tests {
         }
     }
 
-    fn make_session(_entry: WorkspaceEntry) -> Arc<WorkspaceSession> {
-        let mut cmd = if cfg!(windows) {
-            let mut cmd = Command::new("cmd");
-            cmd.args(["/C", "more"]);
-            cmd
-        } else {
-            let mut cmd = Command::new("sh");
-            cmd.args(["-c", "cat"]);
-            cmd
-        };
-
-        cmd.stdin(Stdio::piped())
-            .stdout(Stdio::null())
-            .stderr(Stdio::null());
-
-        let mut child = cmd.spawn().expect("spawn dummy child");
-        let stdin = child.stdin.take().expect("dummy child stdin");
-
-        Arc::new(WorkspaceSession {
-            codex_args: None,
-            child: Mutex::new(child),
-            stdin: Mutex::new(stdin),
-            pending: Mutex::new(HashMap::new()),
-            request_context: Mutex::new(HashMap::new()),
-            thread_workspace: Mutex::new(HashMap::new()),
-            next_id: AtomicU64::new(0),
-            background_thread_callbacks: Mutex::new(HashMap::new()),
-            owner_workspace_id: "test-owner".to_string(),
-            workspace_ids: Mutex::new(HashSet::from(["test-owner".to_string()])),
-            workspace_roots: Mutex::new(HashMap::new()),
-            turn_start_retry_context: Mutex::new(HashMap::new()),
-        })
-    }
-
     #[test]
     fn connect_workspace_is_noop_when_already_connected() {
         tokio::runtime::Runtime::new().unwrap().block_on(async {
@@ -190,7 +156,7 @@ mod tests {
             let workspaces = Mutex::new(HashMap::from([(entry.id.clone(), entry.clone())]));
             let sessions = Mutex::new(HashMap::from([(
                 entry.id.clone(),
-                make_session(entry.clone()),
+                Arc::new(session(&entry.id)),
             )]));
             let app_settings = Mutex::new(AppSettings::default());
             let spawn_calls = Arc::new(AtomicUsize::new(0));
@@ -205,7 +171,7 @@ mod tests {
                     let spawn_calls_ref = spawn_calls_ref.clone();
                     async move {
                         spawn_calls_ref.fetch_add(1, Ordering::SeqCst);
-                        Err("should not spawn".to_string())
+                        Err(CoreError::internal("should not spawn"))
                     }
                 },
             )
@@ -238,7 +204,7 @@ mod tests {
                     let entry_for_spawn = entry_for_spawn.clone();
                     async move {
                         spawn_calls_ref.fetch_add(1, Ordering::SeqCst);
-                        Ok(make_session(entry_for_spawn))
+                        Ok(Arc::new(session(&entry_for_spawn.id)))
                     }
                 },
             )
@@ -9,6 +9,7 @@ use uuid::Uuid;
 use crate::backend::app_server::WorkspaceSession;
 use crate::codex::args::resolve_workspace_codex_args;
 use crate::codex::home::resolve_workspace_codex_home;
+use crate::shared::core_error::{CoreError, ErrorCode};
 use crate::shared::process_core::kill_child_process_tree;
 use crate::shared::{git_core, worktree_core};
 use crate::storage::write_workspaces;
@@ -24,13 +25,13 @@ pub(crate) async fn add_workspace_core<F, Fut>(
     app_settings: &Mutex<AppSettings>,
     storage_path: &PathBuf,
     spawn_session: F,
-) -> Result<WorkspaceInfo, String>
+) -> Result<WorkspaceInfo, CoreError>
 where
     F: Fn(WorkspaceEntry, Option<String>, Option<String>, Option<PathBuf>) -> Fut,
-    Fut: Future<Output = Result<Arc<WorkspaceSession>, String>>,
+    Fut: Future<Output = Result<Arc<WorkspaceSession>, CoreError>>,
 {
     if !PathBuf::from(&path).is_dir() {
-        return Err("Workspace path must be a folder.".to_string());
+        return Err(CoreError::internal("Workspace path must be a folder."));
     }
 
     let name = PathBuf::from(&path)
@@ -81,7 +82,7 @@ where
             let mut child = session.child.lock().await;
             kill_child_process_tree(&mut child).await;
         }
-        return Err(error);
+        return Err(CoreError::internal(error));
     }
 
     session
@@ -110,25 +111,25 @@ pub(crate) async fn add_clone_core<F, Fut>(
     app_settings: &Mutex<AppSettings>,
     storage_path: &PathBuf,
     spawn_session: F,
-) -> Result<WorkspaceInfo, String>
+) -> Result<WorkspaceInfo, CoreError>
 where
     F: Fn(WorkspaceEntry, Option<String>, Option<String>, Option<PathBuf>) -> Fut,
-    Fut: Future<Output = Result<Arc<WorkspaceSession>, String>>,
+    Fut: Future<Output = Result<Arc<WorkspaceSession>, CoreError>>,
 {
     let copy_name = copy_name.trim().to_string();
     if copy_name.is_empty() {
-        return Err("Copy name is required.".to_string());
+        return Err(CoreError::internal("Copy name is required."));
     }
 
     let copies_folder = copies_folder.trim().to_string();
     if copies_folder.is_empty() {
-        return Err("Copies folder is required.".to_string());
+        return Err(CoreError::internal("Copies folder is required."));
     }
     let copies_folder_path = PathBuf::from(&copies_folder);
     std::fs::create_dir_all(&copies_folder_path)
-        .map_err(|e| format!("Failed to create copies folder: {e}"))?;
+        .map_err(|e| CoreError::internal(format!("Failed to create copies folder: {e}")))?;
     if !copies_folder_path.is_dir() {
-        return Err("Copies folder must be a directory.".to_string());
+        return Err(CoreError::internal("Copies folder must be a directory."));
     }
 
     let (source_entry, inherited_group_id) = {
@@ -136,7 +137,7 @@ where
         let source_entry = workspaces
             .get(&source_workspace_id)
             .cloned()
-            .ok_or_else(|| "source workspace not found".to_string())?;
+            .ok_or_else(|| CoreError::internal("source workspace not found"))?;
         let inherited_group_id = if source_entry.kind.is_worktree() {
             source_entry
                 .parent_id
@@ -235,7 +236,7 @@ where
             kill_child_process_tree(&mut child).await;
         }
         let _ = tokio::fs::remove_dir_all(&destination_path).await;
-        return Err(error);
+        return Err(CoreError::internal(error));
     }
 
     session
@@ -269,26 +270,24 @@ fn default_repo_name_from_url(url: &str) -> Option<String> {
     }
 }
 
-fn validate_target_folder_name(value: &str) -> Result<String, String> {
+fn validate_target_folder_name(value: &str) -> Result<String, CoreError> {
     let trimmed = value.trim();
     if trimmed.is_empty() {
-        return Err("Target folder name is required.".to_string());
+        return Err(CoreError::invalid_params("Target folder name is required."));
     }
 
     if trimmed.contains('/') || trimmed.contains('\\') {
-        return Err(
-            "Target folder name must be a single relative folder name without separators or traversal."
-                .to_string(),
-        );
+        return Err(CoreError::invalid_params(
+            "Target folder name must be a single relative folder name without separators or traversal.",
+        ));
     }
 
     let path = Path::new(trimmed);
     match (path.components().next(), path.components().nth(1)) {
         (Some(Component::Normal(_)), None) => Ok(trimmed.to_string()),
-        _ => Err(
-            "Target folder name must be a single relative folder name without separators or traversal."
-                .to_string(),
-        ),
+        _ => Err(CoreError::invalid_params(
+            "Target folder name must be a single relative folder name without separators or traversal.",
+        )),
     }
 }
 
@@ -301,22 +300,24 @@ pub(crate) async fn add_workspace_from_git_url_core<F, Fut>(
     app_settings: &Mutex<AppSettings>,
     storage_path: &PathBuf,
     spawn_session: F,
-) -> Result<WorkspaceInfo, String>
+) -> Result<WorkspaceInfo, CoreError>
 where
     F: Fn(WorkspaceEntry, Option<String>, Option<String>, Option<PathBuf>) -> Fut,
-    Fut: Future<Output = Result<Arc<WorkspaceSession>, String>>,
+    Fut: Future<Output = Result<Arc<WorkspaceSession>, CoreError>>,
 {
     let url = url.trim().to_string();
     if url.is_empty() {
-        return Err("Remote Git URL is required.".to_string());
+        return Err(CoreError::internal("Remote Git URL is required."));
     }
     let destination_path = destination_path.trim().to_string();
     if destination_path.is_empty() {
-        return Err("Destination folder is required.".to_string());
+        return Err(CoreError::internal("Destination folder is required."));
     }
     let destination_parent = PathBuf::from(&destination_path);
     if !destination_parent.is_dir() {
-        return Err("Destination folder must be an existing directory.".to_string());
+        return Err(CoreError::internal(
+            "Destination folder must be an existing directory.",
+        ));
     }
 
     let folder_name = target_folder_name
@@ -325,17 +326,21 @@ where
         .filter(|value| !value.is_empty())
         .map(str::to_string)
         .or_else(|| default_repo_name_from_url(&url))
-        .ok_or_else(|| "Could not determine target folder name from URL.".to_string())?;
+        .ok_or_else(|| CoreError::internal("Could not determine target folder name from URL."))?;
     let folder_name = validate_target_folder_name(&folder_name)?;
 
     let clone_path = destination_parent.join(folder_name);
     if clone_path.exists() {
         let is_empty = std::fs::read_dir(&clone_path)
-            .map_err(|err| format!("Failed to inspect destination path: {err}"))?
+            .map_err(|err| {
+                CoreError::internal(format!("Failed to inspect destination path: {err}"))
+            })?
             .next()
             .is_none();
         if !is_empty {
-            return Err("Destination path already exists and is not empty.".to_string());
+            return Err(CoreError::internal(
+                "Destination path already exists and is not empty.",
+            ));
         }
     }
 
@@ -399,7 +404,7 @@ where
             kill_child_process_tree(&mut child).await;
         }
         let _ = tokio::fs::remove_dir_all(&clone_path).await;
-        return Err(error);
+        return Err(CoreError::internal(error));
     }
 
     session
@@ -419,31 +424,31 @@ where
     })
 }
 
-pub(crate) async fn remove_workspace_core<FRunGit, FutRunGit, FIsMissing, FRemoveDirAll>(
+pub(crate) async fn remove_workspace_core<FRunGit, FutRunGit, FRemoveDirAll>(
     id: String,
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     storage_path: &PathBuf,
     run_git_command: FRunGit,
-    is_missing_worktree_error: FIsMissing,
     remove_dir_all: FRemoveDirAll,
     require_all_children_removed_to_remove_parent: bool,
     continue_on_child_error: bool,
-) -> Result<(), String>
+) -> Result<(), CoreError>
 where
     FRunGit: Fn(&PathBuf, &[&str]) -> FutRunGit,
-    FutRunGit: Future<Output = Result<(), String>>,
-    FIsMissing: Fn(&str) -> bool,
-    FRemoveDirAll: Fn(&PathBuf) -> Result<(), String>,
+    FutRunGit: Future<Output = Result<(), CoreError>>,
+    FRemoveDirAll: Fn(&PathBuf) -> Result<(), CoreError>,
 {
     let (entry, child_worktrees) = {
         let workspaces = workspaces.lock().await;
         let entry = workspaces
             .get(&id)
             .cloned()
-            .ok_or_else(|| "workspace not found".to_string())?;
+            .ok_or_else(CoreError::workspace_not_found)?;
         if entry.kind.is_worktree() {
-            return Err("Use remove_worktree for worktree agents.".to_string());
+            return Err(CoreError::internal(
+                "Use remove_worktree for worktree agents.",
+            ));
         }
         let children = workspaces
             .values()
@@ -466,7 +471,7 @@ where
             if !repo_path_exists {
                 if let Err(fs_error) = remove_dir_all(&child_path) {
                     if continue_on_child_error {
-                        failures.push((child.id.clone(), fs_error));
+                        failures.push((child.id.clone(), fs_error.message));
                         continue;
                     }
                     return Err(fs_error);
@@ -474,11 +479,11 @@ where
             } else if let Err(error) =
                 run_git_command(&repo_path, &["worktree", "remove", "--force", &child.path]).await
             {
-                if is_missing_worktree_error(&error) {
+                if error.code == ErrorCode::MissingWorktree {
                     if child_path.exists() {
                         if let Err(fs_error) = remove_dir_all(&child_path) {
                             if continue_on_child_error {
-                                failures.push((child.id.clone(), fs_error));
+                                failures.push((child.id.clone(), fs_error.message));
                                 continue;
                             }
                             return Err(fs_error);
@@ -486,7 +491,7 @@ where
                     }
                 } else {
                     if continue_on_child_error {
-                        failures.push((child.id.clone(), error));
+                        failures.push((child.id.clone(), error.message));
                         continue;
                     }
                     return Err(error);
@@ -525,7 +530,7 @@ where
         for (child_id, error) in failures {
             message.push_str(&format!("\n- {child_id}: {error}"));
         }
-        return Err(message);
+        return Err(CoreError::internal(message));
     }
 
     Ok(())
@@ -540,15 +545,15 @@ pub(crate) async fn update_workspace_settings_core<FApplySettings, FSpawn, FutSp
     storage_path: &PathBuf,
     apply_settings_update: FApplySettings,
     _spawn_session: FSpawn,
-) -> Result<WorkspaceInfo, String>
+) -> Result<WorkspaceInfo, CoreError>
 where
     FApplySettings: Fn(
         &mut HashMap<String, WorkspaceEntry>,
         &str,
         WorkspaceSettings,
-    ) -> Result<WorkspaceEntry, String>,
+    ) -> Result<WorkspaceEntry, CoreError>,
     FSpawn: Fn(WorkspaceEntry, Option<String>, Option<String>, Option<PathBuf>) -> FutSpawn,
-    FutSpawn: Future<Output = Result<Arc<WorkspaceSession>, String>>,
+    FutSpawn: Future<Output = Result<Arc<WorkspaceSession>, CoreError>>,
 {
     settings.worktree_setup_script = normalize_setup_script(settings.worktree_setup_script);
 
@@ -557,7 +562,7 @@ where
         let previous_entry = workspaces
             .get(&id)
             .cloned()
-            .ok_or_else(|| "workspace not found".to_string())?;
+            .ok_or_else(CoreError::workspace_not_found)?;
         let previous_worktree_setup_script = previous_entry.settings.worktree_setup_script.clone();
         let entry_snapshot = apply_settings_update(&mut workspaces, &id, settings)?;
         let child_entries = workspaces
@@ -640,12 +645,12 @@ mod tests {
     fn rejects_target_folder_name_with_separators() {
         let err =
             validate_target_folder_name("nested/project").expect_err("name should be rejected");
-        assert!(err.contains("without separators"));
+        assert!(err.message.contains("without separators"));
     }
 
     #[test]
     fn rejects_target_folder_name_with_traversal() {
         let err = validate_target_folder_name("../project").expect_err("name should be rejected");
-        assert!(err.contains("without separators or traversal"));
+        assert!(err.message.contains("without separators or traversal"));
     }
 }
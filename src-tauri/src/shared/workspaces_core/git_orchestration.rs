@@ -7,6 +7,7 @@ use tokio::io::AsyncWriteExt;
 use tokio::sync::Mutex;
 
 use crate::git_utils::resolve_git_root;
+use crate::shared::core_error::CoreError;
 use crate::shared::process_core::tokio_command;
 use crate::shared::{git_core, worktree_core};
 use crate::types::WorkspaceEntry;
@@ -15,10 +16,10 @@ pub(crate) fn run_git_command_unit<F, Fut>(
     repo_path: &PathBuf,
     args: &[&str],
     run_git_command: F,
-) -> impl Future<Output = Result<(), String>>
+) -> impl Future<Output = Result<(), CoreError>>
 where
     F: Fn(PathBuf, Vec<String>) -> Fut,
-    Fut: Future<Output = Result<String, String>>,
+    Fut: Future<Output = Result<String, CoreError>>,
 {
     let repo_path = repo_path.clone();
     let args_owned = args
@@ -35,24 +36,24 @@ where
 pub(crate) async fn apply_worktree_changes_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: String,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     let (entry, parent) = {
         let workspaces = workspaces.lock().await;
         let entry = workspaces
             .get(&workspace_id)
             .cloned()
-            .ok_or_else(|| "workspace not found".to_string())?;
+            .ok_or_else(CoreError::workspace_not_found)?;
         if !entry.kind.is_worktree() {
-            return Err("Not a worktree workspace.".to_string());
+            return Err(CoreError::internal("Not a worktree workspace."));
         }
         let parent_id = entry
             .parent_id
             .clone()
-            .ok_or_else(|| "worktree parent not found".to_string())?;
+            .ok_or_else(|| CoreError::internal("worktree parent not found"))?;
         let parent = workspaces
             .get(&parent_id)
             .cloned()
-            .ok_or_else(|| "worktree parent not found".to_string())?;
+            .ok_or_else(|| CoreError::internal("worktree parent not found"))?;
         (entry, parent)
     };
 
@@ -62,17 +63,16 @@ pub(crate) async fn apply_worktree_changes_core(
 pub(super) async fn apply_worktree_changes_inner_core(
     entry: &WorkspaceEntry,
     parent: &WorkspaceEntry,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     let worktree_root = resolve_git_root(entry)?;
     let parent_root = resolve_git_root(parent)?;
 
     let parent_status =
         git_core::run_git_command_bytes(&parent_root, &["status", "--porcelain"]).await?;
     if !String::from_utf8_lossy(&parent_status).trim().is_empty() {
-        return Err(
-            "Your current branch has uncommitted changes. Please commit, stash, or discard them before applying worktree changes."
-                .to_string(),
-        );
+        return Err(CoreError::internal(
+            "Your current branch has uncommitted changes. Please commit, stash, or discard them before applying worktree changes.",
+        ));
     }
 
     let mut patch: Vec<u8> = Vec::new();
@@ -113,11 +113,10 @@ pub(super) async fn apply_worktree_changes_inner_core(
     }
 
     if String::from_utf8_lossy(&patch).trim().is_empty() {
-        return Err("No changes to apply.".to_string());
+        return Err(CoreError::internal("No changes to apply."));
     }
 
-    let git_bin =
-        crate::utils::resolve_git_binary().map_err(|e| format!("Failed to run git: {e}"))?;
+    let git_bin = git_core::git_binary()?;
     let mut child = tokio_command(git_bin)
         .args(["apply", "--3way", "--whitespace=nowarn", "-"])
         .current_dir(&parent_root)
@@ -126,19 +125,19 @@ pub(super) async fn apply_worktree_changes_inner_core(
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
         .spawn()
-        .map_err(|e| format!("Failed to run git: {e}"))?;
+        .map_err(git_core::git_spawn_error)?;
 
     if let Some(mut stdin) = child.stdin.take() {
         stdin
             .write_all(&patch)
             .await
-            .map_err(|e| format!("Failed to write git apply input: {e}"))?;
+            .map_err(|e| CoreError::internal(format!("Failed to write git apply input: {e}")))?;
     }
 
     let output = child
         .wait_with_output()
         .await
-        .map_err(|e| format!("Failed to run git: {e}"))?;
+        .map_err(git_core::git_spawn_error)?;
 
     if output.status.success() {
         return Ok(());
@@ -152,21 +151,19 @@ pub(super) async fn apply_worktree_changes_inner_core(
         stderr.trim()
     };
     if detail.is_empty() {
-        return Err("Git apply failed.".to_string());
+        return Err(CoreError::internal("Git apply failed."));
     }
 
     if detail.contains("Applied patch to") {
         if detail.contains("with conflicts") {
-            return Err(
-                "Applied with conflicts. Resolve conflicts in the parent repo before retrying."
-                    .to_string(),
-            );
+            return Err(CoreError::internal(
+                "Applied with conflicts. Resolve conflicts in the parent repo before retrying.",
+            ));
         }
-        return Err(
-            "Patch applied partially. Resolve changes in the parent repo before retrying."
-                .to_string(),
-        );
+        return Err(CoreError::internal(
+            "Patch applied partially. Resolve changes in the parent repo before retrying.",
+        ));
     }
 
-    Err(detail.to_string())
+    Err(CoreError::internal(detail))
 }
@@ -5,6 +5,7 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use crate::backend::app_server::WorkspaceSession;
+use crate::shared::core_error::CoreError;
 use crate::types::{WorkspaceEntry, WorkspaceInfo};
 
 pub(crate) const WORKTREE_SETUP_MARKERS_DIR: &str = "worktree-setup";
@@ -14,7 +15,7 @@ pub(super) const AGENTS_MD_FILE_NAME: &str = "AGENTS.md";
 pub(super) fn copy_agents_md_from_parent_to_worktree(
     parent_repo_root: &PathBuf,
     worktree_root: &PathBuf,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     let source_path = parent_repo_root.join(AGENTS_MD_FILE_NAME);
     if !source_path.is_file() {
         return Ok(());
@@ -92,12 +93,12 @@ pub(crate) async fn list_workspaces_core(
 pub(super) async fn resolve_entry_and_parent(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: &str,
-) -> Result<(WorkspaceEntry, Option<WorkspaceEntry>), String> {
+) -> Result<(WorkspaceEntry, Option<WorkspaceEntry>), CoreError> {
     let workspaces = workspaces.lock().await;
     let entry = workspaces
         .get(workspace_id)
         .cloned()
-        .ok_or_else(|| "workspace not found".to_string())?;
+        .ok_or_else(CoreError::workspace_not_found)?;
     let parent_entry = entry
         .parent_id
         .as_ref()
@@ -109,12 +110,12 @@ pub(super) async fn resolve_entry_and_parent(
 pub(super) async fn resolve_workspace_root(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: &str,
-) -> Result<PathBuf, String> {
+) -> Result<PathBuf, CoreError> {
     let workspaces = workspaces.lock().await;
     let entry = workspaces
         .get(workspace_id)
         .cloned()
-        .ok_or_else(|| "workspace not found".to_string())?;
+        .ok_or_else(CoreError::workspace_not_found)?;
     Ok(PathBuf::from(entry.path))
 }
 
@@ -5,6 +5,7 @@ use std::path::PathBuf;
 
 use tokio::sync::Mutex;
 
+use crate::shared::core_error::CoreError;
 use crate::shared::process_core::tokio_command;
 #[cfg(target_os = "windows")]
 use crate::shared::process_core::{build_cmd_c_command, resolve_windows_executable};
@@ -17,7 +18,7 @@ pub(crate) async fn open_workspace_in_core(
     app: Option<String>,
     args: Vec<String>,
     command: Option<String>,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     fn output_snippet(bytes: &[u8]) -> Option<String> {
         const MAX_CHARS: usize = 240;
         let text = String::from_utf8_lossy(bytes).trim().replace('\n', "\\n");
@@ -42,7 +43,7 @@ pub(crate) async fn open_workspace_in_core(
     let output = if let Some(command) = command {
         let trimmed = command.trim();
         if trimmed.is_empty() {
-            return Err("Missing app or command".to_string());
+            return Err(CoreError::internal("Missing app or command"));
         }
 
         #[cfg(target_os = "windows")]
@@ -78,13 +79,13 @@ pub(crate) async fn open_workspace_in_core(
             cmd
         };
 
-        cmd.output()
-            .await
-            .map_err(|error| format!("Failed to open app ({target_label}): {error}"))?
+        cmd.output().await.map_err(|error| {
+            CoreError::internal(format!("Failed to open app ({target_label}): {error}"))
+        })?
     } else if let Some(app) = app {
         let trimmed = app.trim();
         if trimmed.is_empty() {
-            return Err("Missing app or command".to_string());
+            return Err(CoreError::internal("Missing app or command"));
         }
 
         #[cfg(target_os = "macos")]
@@ -104,11 +105,11 @@ pub(crate) async fn open_workspace_in_core(
             cmd
         };
 
-        cmd.output()
-            .await
-            .map_err(|error| format!("Failed to open app ({target_label}): {error}"))?
+        cmd.output().await.map_err(|error| {
+            CoreError::internal(format!("Failed to open app ({target_label}): {error}"))
+        })?
     } else {
-        return Err("Missing app or command".to_string());
+        return Err(CoreError::internal("Missing app or command"));
     };
 
     if output.status.success() {
@@ -129,14 +130,14 @@ pub(crate) async fn open_workspace_in_core(
     }
 
     if details.is_empty() {
-        Err(format!(
+        Err(CoreError::internal(format!(
             "Failed to open app ({target_label} returned {exit_detail})."
-        ))
+        )))
     } else {
-        Err(format!(
+        Err(CoreError::internal(format!(
             "Failed to open app ({target_label} returned {exit_detail}; {}).",
             details.join("; ")
-        ))
+        )))
     }
 }
 
@@ -144,7 +145,7 @@ pub(crate) async fn open_workspace_in_core(
 pub(crate) async fn get_open_app_icon_core<F>(
     app_name: String,
     icon_loader: F,
-) -> Result<Option<String>, String>
+) -> Result<Option<String>, CoreError>
 where
     F: Fn(&str) -> Option<String> + Send + Sync + 'static,
 {
@@ -162,7 +163,7 @@ where
 pub(crate) async fn get_open_app_icon_core<F>(
     app_name: String,
     icon_loader: F,
-) -> Result<Option<String>, String>
+) -> Result<Option<String>, CoreError>
 where
     F: Fn(&str) -> Option<String> + Send + Sync + 'static,
 {
@@ -175,7 +176,7 @@ pub(crate) async fn list_workspace_files_core<F>(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: &str,
     list_files: F,
-) -> Result<Vec<String>, String>
+) -> Result<Vec<String>, CoreError>
 where
     F: Fn(&PathBuf) -> Vec<String>,
 {
@@ -188,9 +189,9 @@ pub(crate) async fn read_workspace_file_core<F, T>(
     workspace_id: &str,
     path: &str,
     read_file: F,
-) -> Result<T, String>
+) -> Result<T, CoreError>
 where
-    F: Fn(&PathBuf, &str) -> Result<T, String>,
+    F: Fn(&PathBuf, &str) -> Result<T, CoreError>,
 {
     let root = resolve_workspace_root(workspaces, workspace_id).await?;
     read_file(&root, path)
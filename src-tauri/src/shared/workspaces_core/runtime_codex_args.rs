@@ -3,19 +3,21 @@ use std::future::Future;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tokio::sync::Mutex;
 
 use crate::backend::app_server::WorkspaceSession;
 use crate::codex::args::resolve_workspace_codex_args;
 use crate::codex::home::resolve_workspace_codex_home;
+use crate::shared::core_error::CoreError;
 use crate::shared::process_core::kill_child_process_tree;
 use crate::types::{AppSettings, WorkspaceEntry};
 
 use super::connect::workspace_session_spawn_lock;
 use super::helpers::resolve_entry_and_parent;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct WorkspaceRuntimeCodexArgsResult {
     pub(crate) applied_codex_args: Option<String>,
@@ -29,10 +31,10 @@ pub(crate) async fn set_workspace_runtime_codex_args_core<F, Fut>(
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     app_settings: &Mutex<AppSettings>,
     spawn_session: F,
-) -> Result<WorkspaceRuntimeCodexArgsResult, String>
+) -> Result<WorkspaceRuntimeCodexArgsResult, CoreError>
 where
     F: Fn(WorkspaceEntry, Option<String>, Option<String>, Option<PathBuf>) -> Fut,
-    Fut: Future<Output = Result<Arc<WorkspaceSession>, String>>,
+    Fut: Future<Output = Result<Arc<WorkspaceSession>, CoreError>>,
 {
     let (entry, parent_entry) = resolve_entry_and_parent(workspaces, &workspace_id).await?;
     let _spawn_guard = workspace_session_spawn_lock().lock().await;
@@ -128,12 +130,9 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backend::test_support::session;
 
-    use std::process::Stdio;
-    use std::collections::HashSet;
-    use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
-
-    use tokio::process::Command;
+    use std::sync::atomic::{AtomicUsize, Ordering};
 
     use crate::types::{WorkspaceKind, WorkspaceSettings};
 
@@ -149,37 +148,10 @@ mod tests {
         }
     }
 
-    fn make_session(_entry: WorkspaceEntry, codex_args: Option<String>) -> WorkspaceSession {
-        let mut cmd = if cfg!(windows) {
-            let mut cmd = Command::new("cmd");
-            cmd.args(["/C", "more"]);
-            cmd
-        } else {
-            let mut cmd = Command::new("sh");
-            cmd.args(["-c", "cat"]);
-            cmd
-        };
-
-        cmd.stdin(Stdio::piped())
-            .stdout(Stdio::null())
-            .stderr(Stdio::null());
-
-        let mut child = cmd.spawn().expect("spawn dummy child");
-        let stdin = child.stdin.take().expect("dummy child stdin");
-
+    fn make_session(entry: WorkspaceEntry, codex_args: Option<String>) -> WorkspaceSession {
         WorkspaceSession {
             codex_args,
-            child: Mutex::new(child),
-            stdin: Mutex::new(stdin),
-            pending: Mutex::new(HashMap::new()),
-            request_context: Mutex::new(HashMap::new()),
-            thread_workspace: Mutex::new(HashMap::new()),
-            next_id: AtomicU64::new(0),
-            background_thread_callbacks: Mutex::new(HashMap::new()),
-            owner_workspace_id: "test-owner".to_string(),
-            workspace_ids: Mutex::new(HashSet::from(["test-owner".to_string()])),
-            workspace_roots: Mutex::new(HashMap::new()),
-            turn_start_retry_context: Mutex::new(HashMap::new()),
+            ..session(&entry.id)
         }
     }
 
@@ -0,0 +1,268 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::json;
+use tokio::sync::Mutex;
+
+use crate::backend::app_server::WorkspaceSession;
+use crate::backend::events::{AppServerEvent, EventSink};
+use crate::types::AppSettings;
+
+use super::connect::kill_session_by_id;
+
+/// How often the app and daemon call `enforce_session_pool_core` on their own.
+pub(crate) const SESSION_POOL_SWEEP_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EvictionReason {
+    Idle,
+    PoolLimit,
+}
+
+impl EvictionReason {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Idle => "idle",
+            Self::PoolLimit => "poolLimit",
+        }
+    }
+}
+
+/// One app-server process and the workspaces routed to it, sorted by id.
+struct LiveSession {
+    session: Arc<WorkspaceSession>,
+    workspace_ids: Vec<String>,
+}
+
+async fn live_sessions(
+    sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
+) -> Vec<LiveSession> {
+    let sessions = sessions.lock().await;
+    let mut live: Vec<LiveSession> = Vec::new();
+    for (workspace_id, session) in sessions.iter() {
+        match live
+            .iter_mut()
+            .find(|candidate| Arc::ptr_eq(&candidate.session, session))
+        {
+            Some(candidate) => candidate.workspace_ids.push(workspace_id.clone()),
+            None => live.push(LiveSession {
+                session: Arc::clone(session),
+                workspace_ids: vec![workspace_id.clone()],
+            }),
+        }
+    }
+    for candidate in &mut live {
+        candidate.workspace_ids.sort();
+    }
+    live
+}
+
+async fn put_to_sleep<E: EventSink>(
+    sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
+    event_sink: &E,
+    workspace_id: &str,
+    reason: EvictionReason,
+    idle: Duration,
+) {
+    kill_session_by_id(sessions, workspace_id).await;
+    event_sink.emit_app_server_event(AppServerEvent {
+        workspace_id: workspace_id.to_string(),
+        message: json!({
+            "method": "codex/sleeping",
+            "params": {
+                "workspaceId": workspace_id,
+                "reason": reason.as_str(),
+                "idleSecs": idle.as_secs(),
+            }
+        }),
+    });
+}
+
+/// Applies `AppSettings::session_pool`. Workspaces idle longer than the idle
+/// timeout are disconnected first; if more app-server processes than allowed
+/// are still running, the least recently used ones are shut down. Workspaces
+/// with a request in flight or a turn running are left alone. Each evicted
+/// workspace gets a `codex/sleeping` event and reconnects through
+/// `connect_workspace_core` on next use. Returns the evicted workspace ids.
+pub(crate) async fn enforce_session_pool_core<E: EventSink>(
+    sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
+    app_settings: &Mutex<AppSettings>,
+    event_sink: &E,
+) -> Vec<String> {
+    let pool = app_settings.lock().await.session_pool.clone();
+    let mut evicted = Vec::new();
+
+    if let Some(idle_timeout) = pool.idle_timeout() {
+        for live in live_sessions(sessions).await {
+            for workspace_id in &live.workspace_ids {
+                let idle = live.session.supervision.idle_for(workspace_id).await;
+                if idle < idle_timeout || live.session.is_busy(workspace_id).await {
+                    continue;
+                }
+                put_to_sleep(
+                    sessions,
+                    event_sink,
+                    workspace_id,
+                    EvictionReason::Idle,
+                    idle,
+                )
+                .await;
+                evicted.push(workspace_id.clone());
+            }
+        }
+    }
+
+    let Some(max_live_sessions) = pool.max_live_sessions() else {
+        return evicted;
+    };
+    let live = live_sessions(sessions).await;
+    if live.len() <= max_live_sessions {
+        return evicted;
+    }
+    let mut candidates = Vec::new();
+    for live in live {
+        let mut idle = Duration::MAX;
+        let mut busy = false;
+        for workspace_id in &live.workspace_ids {
+            idle = idle.min(live.session.supervision.idle_for(workspace_id).await);
+            busy |= live.session.is_busy(workspace_id).await;
+        }
+        candidates.push((idle, busy, live));
+    }
+    // Most idle first.
+    candidates.sort_by(|(left, ..), (right, ..)| right.cmp(left));
+    let mut excess = candidates.len() - max_live_sessions;
+    for (_, busy, live) in candidates {
+        if excess == 0 {
+            break;
+        }
+        if busy {
+            continue;
+        }
+        for workspace_id in &live.workspace_ids {
+            let idle = live.session.supervision.idle_for(workspace_id).await;
+            put_to_sleep(
+                sessions,
+                event_sink,
+                workspace_id,
+                EvictionReason::PoolLimit,
+                idle,
+            )
+            .await;
+            evicted.push(workspace_id.clone());
+        }
+        excess -= 1;
+    }
+    evicted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::backend::test_support::{session, RecordingSink};
+    use crate::types::SessionPoolSettings;
+
+    fn settings(pool: SessionPoolSettings) -> Mutex<AppSettings> {
+        Mutex::new(AppSettings {
+            session_pool: pool,
+            ..AppSettings::default()
+        })
+    }
+
+    async fn child_exited(session: &WorkspaceSession) -> bool {
+        matches!(session.child.lock().await.try_wait(), Ok(Some(_)))
+    }
+
+    #[test]
+    fn idle_workspaces_are_put_to_sleep() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("runtime");
+        runtime.block_on(async {
+            let idle = Arc::new(session("ws-idle"));
+            let active = Arc::new(session("ws-active"));
+            let sessions = Mutex::new(HashMap::from([
+                ("ws-idle".to_string(), Arc::clone(&idle)),
+                ("ws-active".to_string(), Arc::clone(&active)),
+            ]));
+            let app_settings = settings(SessionPoolSettings {
+                max_live_sessions: 0,
+                idle_timeout_secs: 1,
+            });
+            let sink = RecordingSink::default();
+
+            tokio::time::sleep(Duration::from_millis(1100)).await;
+            active.supervision.record_activity("ws-active").await;
+            let evicted = enforce_session_pool_core(&sessions, &app_settings, &sink).await;
+
+            assert_eq!(evicted, vec!["ws-idle".to_string()]);
+            assert!(child_exited(&idle).await);
+            assert!(!child_exited(&active).await);
+            let remaining: Vec<String> = sessions.lock().await.keys().cloned().collect();
+            assert_eq!(remaining, vec!["ws-active".to_string()]);
+            let sleeping = sink.with_method("codex/sleeping");
+            assert_eq!(sleeping.len(), 1);
+            assert_eq!(sleeping[0].0, "ws-idle");
+            assert_eq!(sleeping[0].1["reason"], "idle");
+            assert_eq!(sleeping[0].1["idleSecs"], 1);
+
+            let mut child = active.child.lock().await;
+            crate::shared::process_core::kill_child_process_tree(&mut child).await;
+        });
+    }
+
+    #[test]
+    fn pool_limit_evicts_least_recently_used_idle_sessions() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("runtime");
+        runtime.block_on(async {
+            let busy = Arc::new(session("ws-busy"));
+            let oldest = Arc::new(session("ws-oldest"));
+            let shared = Arc::new(session("ws-a"));
+            let sessions = Mutex::new(HashMap::from([
+                ("ws-busy".to_string(), Arc::clone(&busy)),
+                ("ws-oldest".to_string(), Arc::clone(&oldest)),
+                ("ws-a".to_string(), Arc::clone(&shared)),
+                ("ws-b".to_string(), Arc::clone(&shared)),
+            ]));
+            busy.supervision
+                .record_turn_event("turn/started", "thread-1", "ws-busy")
+                .await;
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            shared.supervision.record_activity("ws-b").await;
+            let app_settings = settings(SessionPoolSettings {
+                max_live_sessions: 2,
+                idle_timeout_secs: 0,
+            });
+            let sink = RecordingSink::default();
+
+            let evicted = enforce_session_pool_core(&sessions, &app_settings, &sink).await;
+
+            assert_eq!(evicted, vec!["ws-oldest".to_string()]);
+            assert!(child_exited(&oldest).await);
+            let mut remaining: Vec<String> = sessions.lock().await.keys().cloned().collect();
+            remaining.sort();
+            assert_eq!(remaining, vec!["ws-a", "ws-b", "ws-busy"]);
+            assert_eq!(
+                sink.with_method("codex/sleeping")[0].1["reason"],
+                "poolLimit"
+            );
+
+            busy.supervision
+                .record_turn_event("turn/completed", "thread-1", "ws-busy")
+                .await;
+            app_settings.lock().await.session_pool.max_live_sessions = 1;
+            let evicted = enforce_session_pool_core(&sessions, &app_settings, &sink).await;
+            assert_eq!(evicted, vec!["ws-busy".to_string()]);
+            assert!(!child_exited(&shared).await);
+
+            let mut child = shared.child.lock().await;
+            crate::shared::process_core::kill_child_process_tree(&mut child).await;
+        });
+    }
+}
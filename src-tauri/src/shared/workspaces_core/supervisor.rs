@@ -0,0 +1,328 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::json;
+use tokio::sync::Mutex;
+use tokio::time::sleep;
+
+use crate::backend::app_server::{SessionExit, WorkspaceSession};
+use crate::backend::events::{AppServerEvent, EventSink};
+use crate::shared::core_error::CoreError;
+use crate::shared::process_core::kill_child_process_tree;
+use crate::types::{AppSettings, WorkspaceEntry};
+
+use super::connect::connect_workspace_core;
+
+const MAX_CRASH_RESTARTS: u32 = 5;
+const RESTART_BACKOFF_BASE: Duration = Duration::from_secs(1);
+const RESTART_BACKOFF_MAX: Duration = Duration::from_secs(30);
+/// A session that stayed up this long starts the backoff over.
+const STABLE_UPTIME: Duration = Duration::from_secs(60);
+
+/// Immediate first restart, then 1s, 2s, 4s, ... capped at 30s.
+fn restart_backoff(attempt: u32) -> Duration {
+    if attempt == 0 {
+        return Duration::ZERO;
+    }
+    RESTART_BACKOFF_BASE
+        .saturating_mul(1 << (attempt - 1).min(5))
+        .min(RESTART_BACKOFF_MAX)
+}
+
+/// Removes `session` from the map and returns the workspaces that were still
+/// using it. Sessions stopped on purpose are removed before their child is
+/// killed, so an empty result means there is nothing to restart.
+async fn detach_exited_session(
+    sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
+    session: &Arc<WorkspaceSession>,
+) -> Vec<String> {
+    let mut sessions = sessions.lock().await;
+    let mut workspace_ids: Vec<String> = sessions
+        .iter()
+        .filter(|(_, candidate)| Arc::ptr_eq(candidate, session))
+        .map(|(workspace_id, _)| workspace_id.clone())
+        .collect();
+    sessions.retain(|_, candidate| !Arc::ptr_eq(candidate, session));
+    workspace_ids.sort();
+    workspace_ids
+}
+
+fn emit_disconnected<E: EventSink>(
+    event_sink: &E,
+    workspace_ids: &[String],
+    exit: &SessionExit,
+    will_restart: bool,
+    error: Option<&str>,
+) {
+    for workspace_id in workspace_ids {
+        event_sink.emit_app_server_event(AppServerEvent {
+            workspace_id: workspace_id.clone(),
+            message: json!({
+                "method": "codex/disconnected",
+                "params": {
+                    "workspaceId": workspace_id,
+                    "exitCode": exit.exit_code,
+                    "stderrTail": exit.stderr_tail,
+                    "willRestart": will_restart,
+                    "error": error,
+                }
+            }),
+        });
+    }
+}
+
+async fn reconnect_workspaces<F, Fut>(
+    workspace_ids: &[String],
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
+    app_settings: &Mutex<AppSettings>,
+    spawn_session: &F,
+) -> Result<(), CoreError>
+where
+    F: Fn(WorkspaceEntry, Option<String>, Option<String>, Option<PathBuf>) -> Fut,
+    Fut: Future<Output = Result<Arc<WorkspaceSession>, CoreError>>,
+{
+    for workspace_id in workspace_ids {
+        // Workspaces removed while we were backing off stay disconnected.
+        if !workspaces.lock().await.contains_key(workspace_id) {
+            continue;
+        }
+        connect_workspace_core(
+            workspace_id.clone(),
+            workspaces,
+            sessions,
+            app_settings,
+            spawn_session,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+async fn resume_loaded_threads(
+    threads: Vec<(String, String)>,
+    sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
+) {
+    for (thread_id, workspace_id) in threads {
+        let session = sessions.lock().await.get(&workspace_id).cloned();
+        let Some(session) = session else {
+            continue;
+        };
+        let _ = session
+            .send_request_for_workspace(
+                &workspace_id,
+                "thread/resume",
+                json!({ "threadId": thread_id }),
+            )
+            .await;
+    }
+}
+
+/// Handles an app-server child going away. Crashes emit `codex/disconnected`
+/// with the exit code and last stderr lines, respawn the session through
+/// `spawn_session` with backoff and resume the threads that were loaded.
+pub(crate) async fn handle_session_exit_core<E, F, Fut>(
+    exit: SessionExit,
+    workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
+    sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
+    app_settings: &Mutex<AppSettings>,
+    event_sink: &E,
+    spawn_session: F,
+) where
+    E: EventSink,
+    F: Fn(WorkspaceEntry, Option<String>, Option<String>, Option<PathBuf>) -> Fut,
+    Fut: Future<Output = Result<Arc<WorkspaceSession>, CoreError>>,
+{
+    let workspace_ids = detach_exited_session(sessions, &exit.session).await;
+    if workspace_ids.is_empty() {
+        return;
+    }
+    {
+        // stdout can close before the process is gone; never leave it running
+        // next to its replacement.
+        let mut child = exit.session.child.lock().await;
+        kill_child_process_tree(&mut child).await;
+    }
+
+    let supervision = &exit.session.supervision;
+    let prior_restarts = if supervision.uptime() >= STABLE_UPTIME {
+        0
+    } else {
+        supervision.crash_restarts.load(Ordering::SeqCst)
+    };
+    let will_restart = prior_restarts < MAX_CRASH_RESTARTS;
+    emit_disconnected(event_sink, &workspace_ids, &exit, will_restart, None);
+    if !will_restart {
+        return;
+    }
+
+    let loaded_threads = supervision.loaded_threads_snapshot().await;
+    let mut last_error = String::new();
+    for attempt in prior_restarts..MAX_CRASH_RESTARTS {
+        sleep(restart_backoff(attempt)).await;
+        match reconnect_workspaces(
+            &workspace_ids,
+            workspaces,
+            sessions,
+            app_settings,
+            &spawn_session,
+        )
+        .await
+        {
+            Ok(()) => {
+                let restarted = {
+                    let sessions = sessions.lock().await;
+                    workspace_ids
+                        .iter()
+                        .find_map(|workspace_id| sessions.get(workspace_id).cloned())
+                };
+                if let Some(restarted) = restarted {
+                    restarted
+                        .supervision
+                        .crash_restarts
+                        .store(attempt + 1, Ordering::SeqCst);
+                }
+                resume_loaded_threads(loaded_threads, sessions).await;
+                return;
+            }
+            Err(error) => last_error = error.message,
+        }
+    }
+    emit_disconnected(
+        event_sink,
+        &workspace_ids,
+        &exit,
+        false,
+        Some(&format!(
+            "Gave up restarting Codex app-server: {last_error}"
+        )),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::atomic::AtomicUsize;
+
+    use crate::backend::test_support::{session, RecordingSink};
+    use crate::types::{WorkspaceKind, WorkspaceSettings};
+
+    fn make_workspace_entry(id: &str) -> WorkspaceEntry {
+        WorkspaceEntry {
+            id: id.to_string(),
+            name: id.to_string(),
+            path: "/tmp".to_string(),
+            kind: WorkspaceKind::Main,
+            parent_id: None,
+            worktree: None,
+            settings: WorkspaceSettings::default(),
+        }
+    }
+
+    fn session_exit(session: &Arc<WorkspaceSession>) -> SessionExit {
+        SessionExit {
+            session: Arc::clone(session),
+            exit_code: Some(101),
+            stderr_tail: vec!["thread 'main' panicked".to_string()],
+        }
+    }
+
+    #[test]
+    fn restart_backoff_grows_and_caps() {
+        assert_eq!(restart_backoff(0), Duration::ZERO);
+        assert_eq!(restart_backoff(1), Duration::from_secs(1));
+        assert_eq!(restart_backoff(3), Duration::from_secs(4));
+        assert_eq!(restart_backoff(20), RESTART_BACKOFF_MAX);
+    }
+
+    #[test]
+    fn crashed_session_is_reported_and_respawned() {
+        tokio::runtime::Runtime::new().unwrap().block_on(async {
+            let entry = make_workspace_entry("ws-1");
+            let workspaces = Mutex::new(HashMap::from([(entry.id.clone(), entry.clone())]));
+            let crashed = Arc::new(session("test-owner"));
+            let sessions = Mutex::new(HashMap::from([(entry.id.clone(), Arc::clone(&crashed))]));
+            let app_settings = Mutex::new(AppSettings::default());
+            let sink = RecordingSink::default();
+            let spawn_calls = Arc::new(AtomicUsize::new(0));
+            let spawn_calls_ref = spawn_calls.clone();
+
+            handle_session_exit_core(
+                session_exit(&crashed),
+                &workspaces,
+                &sessions,
+                &app_settings,
+                &sink,
+                move |_entry, _default_bin, _codex_args, _codex_home| {
+                    let spawn_calls_ref = spawn_calls_ref.clone();
+                    async move {
+                        spawn_calls_ref.fetch_add(1, Ordering::SeqCst);
+                        Ok(Arc::new(session("test-owner")))
+                    }
+                },
+            )
+            .await;
+
+            assert_eq!(spawn_calls.load(Ordering::SeqCst), 1);
+            let restarted = sessions
+                .lock()
+                .await
+                .get("ws-1")
+                .cloned()
+                .expect("respawned");
+            assert!(!Arc::ptr_eq(&restarted, &crashed));
+            assert_eq!(
+                restarted.supervision.crash_restarts.load(Ordering::SeqCst),
+                1
+            );
+
+            let events = sink.events();
+            assert_eq!(events.len(), 1);
+            assert_eq!(events[0].workspace_id, "ws-1");
+            assert_eq!(events[0].message["method"], "codex/disconnected");
+            assert_eq!(events[0].message["params"]["exitCode"], 101);
+            assert_eq!(
+                events[0].message["params"]["stderrTail"],
+                json!(["thread 'main' panicked"])
+            );
+            assert_eq!(events[0].message["params"]["willRestart"], true);
+
+            let mut child = restarted.child.lock().await;
+            kill_child_process_tree(&mut child).await;
+        });
+    }
+
+    #[test]
+    fn stopped_session_is_left_alone() {
+        tokio::runtime::Runtime::new().unwrap().block_on(async {
+            let entry = make_workspace_entry("ws-1");
+            let workspaces = Mutex::new(HashMap::from([(entry.id.clone(), entry.clone())]));
+            let stopped = Arc::new(session("test-owner"));
+            let sessions = Mutex::new(HashMap::<String, Arc<WorkspaceSession>>::new());
+            let app_settings = Mutex::new(AppSettings::default());
+            let sink = RecordingSink::default();
+
+            handle_session_exit_core(
+                session_exit(&stopped),
+                &workspaces,
+                &sessions,
+                &app_settings,
+                &sink,
+                |_entry, _default_bin, _codex_args, _codex_home| async {
+                    Err::<Arc<WorkspaceSession>, CoreError>(CoreError::internal("should not spawn"))
+                },
+            )
+            .await;
+
+            assert!(sessions.lock().await.is_empty());
+            assert!(sink.events().is_empty());
+            let mut child = stopped.child.lock().await;
+            kill_child_process_tree(&mut child).await;
+        });
+    }
+}
@@ -9,6 +9,7 @@ use uuid::Uuid;
 use crate::backend::app_server::WorkspaceSession;
 use crate::codex::args::resolve_workspace_codex_args;
 use crate::codex::home::resolve_workspace_codex_home;
+use crate::shared::core_error::{CoreError, ErrorCode};
 use crate::storage::write_workspaces;
 use crate::types::{
     AppSettings, WorkspaceEntry, WorkspaceInfo, WorkspaceKind, WorkspaceSettings, WorktreeInfo,
@@ -25,13 +26,13 @@ pub(crate) async fn worktree_setup_status_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: &str,
     data_dir: &PathBuf,
-) -> Result<WorktreeSetupStatus, String> {
+) -> Result<WorktreeSetupStatus, CoreError> {
     let entry = {
         let workspaces = workspaces.lock().await;
         workspaces
             .get(workspace_id)
             .cloned()
-            .ok_or_else(|| "workspace not found".to_string())?
+            .ok_or_else(CoreError::workspace_not_found)?
     };
 
     let script = normalize_setup_script(entry.settings.worktree_setup_script.clone());
@@ -49,28 +50,32 @@ pub(crate) async fn worktree_setup_mark_ran_core(
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     workspace_id: &str,
     data_dir: &PathBuf,
-) -> Result<(), String> {
+) -> Result<(), CoreError> {
     let entry = {
         let workspaces = workspaces.lock().await;
         workspaces
             .get(workspace_id)
             .cloned()
-            .ok_or_else(|| "workspace not found".to_string())?
+            .ok_or_else(CoreError::workspace_not_found)?
     };
     if !entry.kind.is_worktree() {
-        return Err("Not a worktree workspace.".to_string());
+        return Err(CoreError::invalid_params("Not a worktree workspace."));
     }
     let marker_path = worktree_setup_marker_path(data_dir, &entry.id);
     if let Some(parent) = marker_path.parent() {
-        std::fs::create_dir_all(parent)
-            .map_err(|err| format!("Failed to prepare worktree marker directory: {err}"))?;
+        std::fs::create_dir_all(parent).map_err(|err| {
+            CoreError::internal(format!(
+                "Failed to prepare worktree marker directory: {err}"
+            ))
+        })?;
     }
     let ran_at = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .map(|duration| duration.as_secs())
         .unwrap_or(0);
-    std::fs::write(&marker_path, format!("ran_at={ran_at}\n"))
-        .map_err(|err| format!("Failed to write worktree setup marker: {err}"))?;
+    std::fs::write(&marker_path, format!("ran_at={ran_at}\n")).map_err(|err| {
+        CoreError::internal(format!("Failed to write worktree setup marker: {err}"))
+    })?;
     Ok(())
 }
 
@@ -101,22 +106,22 @@ pub(crate) async fn add_worktree_core<
     git_find_remote_tracking_branch: Option<FFindRemoteTracking>,
     run_git_command: FRunGit,
     spawn_session: FSpawn,
-) -> Result<WorkspaceInfo, String>
+) -> Result<WorkspaceInfo, CoreError>
 where
     FSpawn: Fn(WorkspaceEntry, Option<String>, Option<String>, Option<PathBuf>) -> FutSpawn,
-    FutSpawn: Future<Output = Result<Arc<WorkspaceSession>, String>>,
+    FutSpawn: Future<Output = Result<Arc<WorkspaceSession>, CoreError>>,
     FSanitize: Fn(&str) -> String,
-    FUniquePath: Fn(&PathBuf, &str) -> Result<PathBuf, String>,
+    FUniquePath: Fn(&PathBuf, &str) -> Result<PathBuf, CoreError>,
     FBranchExists: Fn(&PathBuf, &str) -> FutBranchExists,
-    FutBranchExists: Future<Output = Result<bool, String>>,
+    FutBranchExists: Future<Output = Result<bool, CoreError>>,
     FFindRemoteTracking: Fn(&PathBuf, &str) -> FutFindRemoteTracking,
-    FutFindRemoteTracking: Future<Output = Result<Option<String>, String>>,
+    FutFindRemoteTracking: Future<Output = Result<Option<String>, CoreError>>,
     FRunGit: Fn(&PathBuf, &[&str]) -> FutRunGit,
-    FutRunGit: Future<Output = Result<(), String>>,
+    FutRunGit: Future<Output = Result<(), CoreError>>,
 {
     let branch = branch.trim().to_string();
     if branch.is_empty() {
-        return Err("Branch name is required.".to_string());
+        return Err(CoreError::internal("Branch name is required."));
     }
     let name = name
         .map(|value| value.trim().to_string())
@@ -127,16 +132,19 @@ where
         workspaces
             .get(&parent_id)
             .cloned()
-            .ok_or_else(|| "parent workspace not found".to_string())?
+            .ok_or_else(|| CoreError::internal("parent workspace not found"))?
     };
 
     if parent_entry.kind.is_worktree() {
-        return Err("Cannot create a worktree from another worktree.".to_string());
+        return Err(CoreError::internal(
+            "Cannot create a worktree from another worktree.",
+        ));
     }
 
     let worktree_root = data_dir.join("worktrees").join(&parent_entry.id);
-    std::fs::create_dir_all(&worktree_root)
-        .map_err(|err| format!("Failed to create worktree directory: {err}"))?;
+    std::fs::create_dir_all(&worktree_root).map_err(|err| {
+        CoreError::internal(format!("Failed to create worktree directory: {err}"))
+    })?;
 
     let safe_name = sanitize_worktree_name(&branch);
     let worktree_path = unique_worktree_path(&worktree_root, &safe_name)?;
@@ -181,7 +189,7 @@ where
 
     if copy_agents_md {
         if let Err(error) = copy_agents_md_from_parent_to_worktree(&repo_path, &worktree_path) {
-            eprintln!(
+            tracing::warn!(
                 "add_worktree: optional {} copy failed for {}: {}",
                 AGENTS_MD_FILE_NAME,
                 worktree_path.display(),
@@ -245,38 +253,36 @@ where
     })
 }
 
-pub(crate) async fn remove_worktree_core<FRunGit, FutRunGit, FIsMissing, FRemoveDirAll>(
+pub(crate) async fn remove_worktree_core<FRunGit, FutRunGit, FRemoveDirAll>(
     id: String,
     workspaces: &Mutex<HashMap<String, WorkspaceEntry>>,
     sessions: &Mutex<HashMap<String, Arc<WorkspaceSession>>>,
     storage_path: &PathBuf,
     run_git_command: FRunGit,
-    is_missing_worktree_error: FIsMissing,
     remove_dir_all: FRemoveDirAll,
-) -> Result<(), String>
+) -> Result<(), CoreError>
 where
     FRunGit: Fn(&PathBuf, &[&str]) -> FutRunGit,
-    FutRunGit: Future<Output = Result<(), String>>,
-    FIsMissing: Fn(&str) -> bool,
-    FRemoveDirAll: Fn(&PathBuf) -> Result<(), String>,
+    FutRunGit: Future<Output = Result<(), CoreError>>,
+    FRemoveDirAll: Fn(&PathBuf) -> Result<(), CoreError>,
 {
     let (entry, parent) = {
         let workspaces = workspaces.lock().await;
         let entry = workspaces
             .get(&id)
             .cloned()
-            .ok_or_else(|| "workspace not found".to_string())?;
+            .ok_or_else(CoreError::workspace_not_found)?;
         if !entry.kind.is_worktree() {
-            return Err("Not a worktree workspace.".to_string());
+            return Err(CoreError::invalid_params("Not a worktree workspace."));
         }
         let parent_id = entry
             .parent_id
             .clone()
-            .ok_or_else(|| "worktree parent not found".to_string())?;
+            .ok_or_else(|| CoreError::internal("worktree parent not found"))?;
         let parent = workspaces
             .get(&parent_id)
             .cloned()
-            .ok_or_else(|| "worktree parent not found".to_string())?;
+            .ok_or_else(|| CoreError::internal("worktree parent not found"))?;
         (entry, parent)
     };
 
@@ -294,7 +300,7 @@ where
         )
         .await
         {
-            if is_missing_worktree_error(&error) {
+            if error.code == ErrorCode::MissingWorktree {
                 if entry_path.exists() {
                     remove_dir_all(&entry_path)?;
                 }
@@ -341,21 +347,21 @@ pub(crate) async fn rename_worktree_core<
     unique_worktree_path_for_rename: FUniqueRenamePath,
     run_git_command: FRunGit,
     _spawn_session: FSpawn,
-) -> Result<WorkspaceInfo, String>
+) -> Result<WorkspaceInfo, CoreError>
 where
     FSpawn: Fn(WorkspaceEntry, Option<String>, Option<String>, Option<PathBuf>) -> FutSpawn,
-    FutSpawn: Future<Output = Result<Arc<WorkspaceSession>, String>>,
-    FResolveGitRoot: Fn(&WorkspaceEntry) -> Result<PathBuf, String>,
+    FutSpawn: Future<Output = Result<Arc<WorkspaceSession>, CoreError>>,
+    FResolveGitRoot: Fn(&WorkspaceEntry) -> Result<PathBuf, CoreError>,
     FUniqueBranch: Fn(&PathBuf, &str) -> FutUniqueBranch,
-    FutUniqueBranch: Future<Output = Result<String, String>>,
+    FutUniqueBranch: Future<Output = Result<String, CoreError>>,
     FSanitize: Fn(&str) -> String,
-    FUniqueRenamePath: Fn(&PathBuf, &str, &PathBuf) -> Result<PathBuf, String>,
+    FUniqueRenamePath: Fn(&PathBuf, &str, &PathBuf) -> Result<PathBuf, CoreError>,
     FRunGit: Fn(&PathBuf, &[&str]) -> FutRunGit,
-    FutRunGit: Future<Output = Result<(), String>>,
+    FutRunGit: Future<Output = Result<(), CoreError>>,
 {
     let trimmed = branch.trim();
     if trimmed.is_empty() {
-        return Err("Branch name is required.".to_string());
+        return Err(CoreError::internal("Branch name is required."));
     }
 
     let (entry, parent) = {
@@ -363,18 +369,18 @@ where
         let entry = workspaces
             .get(&id)
             .cloned()
-            .ok_or_else(|| "workspace not found".to_string())?;
+            .ok_or_else(CoreError::workspace_not_found)?;
         if !entry.kind.is_worktree() {
-            return Err("Not a worktree workspace.".to_string());
+            return Err(CoreError::invalid_params("Not a worktree workspace."));
         }
         let parent_id = entry
             .parent_id
             .clone()
-            .ok_or_else(|| "worktree parent not found".to_string())?;
+            .ok_or_else(|| CoreError::internal("worktree parent not found"))?;
         let parent = workspaces
             .get(&parent_id)
             .cloned()
-            .ok_or_else(|| "worktree parent not found".to_string())?;
+            .ok_or_else(|| CoreError::internal("worktree parent not found"))?;
         (entry, parent)
     };
 
@@ -382,22 +388,23 @@ where
         .worktree
         .as_ref()
         .map(|worktree| worktree.branch.clone())
-        .ok_or_else(|| "worktree metadata missing".to_string())?;
+        .ok_or_else(|| CoreError::internal("worktree metadata missing"))?;
     if old_branch == trimmed {
-        return Err("Branch name is unchanged.".to_string());
+        return Err(CoreError::internal("Branch name is unchanged."));
     }
 
     let parent_root = resolve_git_root(&parent)?;
     let final_branch = unique_branch_name(&parent_root, trimmed).await?;
     if final_branch == old_branch {
-        return Err("Branch name is unchanged.".to_string());
+        return Err(CoreError::internal("Branch name is unchanged."));
     }
 
     run_git_command(&parent_root, &["branch", "-m", &old_branch, &final_branch]).await?;
 
     let worktree_root = data_dir.join("worktrees").join(&parent.id);
-    std::fs::create_dir_all(&worktree_root)
-        .map_err(|err| format!("Failed to create worktree directory: {err}"))?;
+    std::fs::create_dir_all(&worktree_root).map_err(|err| {
+        CoreError::internal(format!("Failed to create worktree directory: {err}"))
+    })?;
 
     let safe_name = sanitize_worktree_name(&final_branch);
     let current_path = PathBuf::from(&entry.path);
@@ -420,7 +427,7 @@ where
         let mut workspaces = workspaces.lock().await;
         let entry = match workspaces.get_mut(&id) {
             Some(entry) => entry,
-            None => return Err("workspace not found".to_string()),
+            None => return Err(CoreError::workspace_not_found()),
         };
         if entry.name.trim() == old_branch {
             entry.name = final_branch.clone();
@@ -484,27 +491,27 @@ pub(crate) async fn rename_worktree_upstream_core<
     git_remote_exists: FRemoteExists,
     git_remote_branch_exists: FRemoteBranchExists,
     run_git_command: FRunGit,
-) -> Result<(), String>
+) -> Result<(), CoreError>
 where
-    FResolveGitRoot: Fn(&WorkspaceEntry) -> Result<PathBuf, String>,
+    FResolveGitRoot: Fn(&WorkspaceEntry) -> Result<PathBuf, CoreError>,
     FBranchExists: Fn(&PathBuf, &str) -> FutBranchExists,
-    FutBranchExists: Future<Output = Result<bool, String>>,
+    FutBranchExists: Future<Output = Result<bool, CoreError>>,
     FFindRemote: Fn(&PathBuf, &str) -> FutFindRemote,
-    FutFindRemote: Future<Output = Result<Option<String>, String>>,
+    FutFindRemote: Future<Output = Result<Option<String>, CoreError>>,
     FRemoteExists: Fn(&PathBuf, &str) -> FutRemoteExists,
-    FutRemoteExists: Future<Output = Result<bool, String>>,
+    FutRemoteExists: Future<Output = Result<bool, CoreError>>,
     FRemoteBranchExists: Fn(&PathBuf, &str, &str) -> FutRemoteBranchExists,
-    FutRemoteBranchExists: Future<Output = Result<bool, String>>,
+    FutRemoteBranchExists: Future<Output = Result<bool, CoreError>>,
     FRunGit: Fn(&PathBuf, &[&str]) -> FutRunGit,
-    FutRunGit: Future<Output = Result<(), String>>,
+    FutRunGit: Future<Output = Result<(), CoreError>>,
 {
     let old_branch = old_branch.trim().to_string();
     let new_branch = new_branch.trim().to_string();
     if old_branch.is_empty() || new_branch.is_empty() {
-        return Err("Branch name is required.".to_string());
+        return Err(CoreError::internal("Branch name is required."));
     }
     if old_branch == new_branch {
-        return Err("Branch name is unchanged.".to_string());
+        return Err(CoreError::internal("Branch name is unchanged."));
     }
 
     let (_entry, parent) = {
@@ -512,24 +519,24 @@ where
         let entry = workspaces
             .get(&id)
             .cloned()
-            .ok_or_else(|| "workspace not found".to_string())?;
+            .ok_or_else(CoreError::workspace_not_found)?;
         if !entry.kind.is_worktree() {
-            return Err("Not a worktree workspace.".to_string());
+            return Err(CoreError::invalid_params("Not a worktree workspace."));
         }
         let parent_id = entry
             .parent_id
             .clone()
-            .ok_or_else(|| "worktree parent not found".to_string())?;
+            .ok_or_else(|| CoreError::internal("worktree parent not found"))?;
         let parent = workspaces
             .get(&parent_id)
             .cloned()
-            .ok_or_else(|| "worktree parent not found".to_string())?;
+            .ok_or_else(|| CoreError::internal("worktree parent not found"))?;
         (entry, parent)
     };
 
     let parent_root = resolve_git_root(&parent)?;
     if !git_branch_exists(&parent_root, &new_branch).await? {
-        return Err("Local branch not found.".to_string());
+        return Err(CoreError::internal("Local branch not found."));
     }
 
     let remote_for_old = git_find_remote_for_branch(&parent_root, &old_branch).await?;
@@ -539,13 +546,15 @@ where
             if git_remote_exists(&parent_root, "origin").await? {
                 "origin".to_string()
             } else {
-                return Err("No git remote configured for this worktree.".to_string());
+                return Err(CoreError::internal(
+                    "No git remote configured for this worktree.",
+                ));
             }
         }
     };
 
     if git_remote_branch_exists(&parent_root, &remote_name, &new_branch).await? {
-        return Err("Remote branch already exists.".to_string());
+        return Err(CoreError::internal("Remote branch already exists."));
     }
 
     if remote_for_old.is_some() {
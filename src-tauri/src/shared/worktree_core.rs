@@ -1,5 +1,7 @@
 use std::path::PathBuf;
 
+use crate::shared::core_error::CoreError;
+
 fn sanitize_name(value: &str, fallback: &str) -> String {
     let mut result = String::new();
     for ch in value.chars() {
@@ -45,7 +47,7 @@ pub(crate) fn unique_worktree_path_best_effort(base_dir: &PathBuf, name: &str) -
 pub(crate) fn unique_worktree_path_strict(
     base_dir: &PathBuf,
     name: &str,
-) -> Result<PathBuf, String> {
+) -> Result<PathBuf, CoreError> {
     let candidate = base_dir.join(name);
     if !candidate.exists() {
         return Ok(candidate);
@@ -56,17 +58,17 @@ pub(crate) fn unique_worktree_path_strict(
             return Ok(next);
         }
     }
-    Err(format!(
+    Err(CoreError::internal(format!(
         "Failed to find an available worktree path under {}.",
         base_dir.display()
-    ))
+    )))
 }
 
 pub(crate) fn unique_worktree_path_for_rename(
     base_dir: &PathBuf,
     name: &str,
     current_path: &PathBuf,
-) -> Result<PathBuf, String> {
+) -> Result<PathBuf, CoreError> {
     let candidate = base_dir.join(name);
     if candidate == *current_path {
         return Ok(candidate);
@@ -80,10 +82,10 @@ pub(crate) fn unique_worktree_path_for_rename(
             return Ok(next);
         }
     }
-    Err(format!(
+    Err(CoreError::internal(format!(
         "Failed to find an available worktree path under {}.",
         base_dir.display()
-    ))
+    )))
 }
 
 pub(crate) fn build_clone_destination_path(copies_folder: &PathBuf, copy_name: &str) -> PathBuf {
@@ -5,8 +5,10 @@ use tauri::{AppHandle, Manager};
 use tokio::process::Child;
 use tokio::sync::Mutex;
 
+use crate::backend::app_server::{RequestTimeouts, SessionExitSender};
 use crate::dictation::DictationState;
 use crate::shared::codex_core::CodexLoginCancelState;
+use crate::shared::logging_core::{init_logging, LOG_DIR_NAME};
 use crate::storage::{read_settings, read_workspaces};
 use crate::types::{AppSettings, TcpDaemonState, TcpDaemonStatus, WorkspaceEntry};
 
@@ -33,8 +35,16 @@ impl Default for TcpDaemonRuntime {
 pub(crate) struct AppState {
     pub(crate) workspaces: Mutex<HashMap<String, WorkspaceEntry>>,
     pub(crate) sessions: Mutex<HashMap<String, Arc<crate::codex::WorkspaceSession>>>,
-    pub(crate) terminal_sessions: Mutex<HashMap<String, Arc<crate::terminal::TerminalSession>>>,
-    pub(crate) remote_backend: Mutex<Option<crate::remote_backend::RemoteBackend>>,
+    pub(crate) terminal_sessions:
+        Arc<Mutex<HashMap<String, Arc<crate::terminal::TerminalSession>>>>,
+    pub(crate) session_exits: SessionExitSender,
+    pub(crate) request_timeouts: RequestTimeouts,
+    /// Open daemon connections. `None` is the active remote; aggregated
+    /// remotes are keyed by backend id.
+    pub(crate) remote_backends:
+        Mutex<HashMap<Option<String>, crate::remote_backend::RemoteBackend>>,
+    pub(crate) remote_event_cursors:
+        std::sync::Mutex<HashMap<Option<String>, Arc<crate::remote_backend::RemoteEventCursor>>>,
     pub(crate) storage_path: PathBuf,
     pub(crate) settings_path: PathBuf,
     pub(crate) app_settings: Mutex<AppSettings>,
@@ -44,7 +54,7 @@ pub(crate) struct AppState {
 }
 
 impl AppState {
-    pub(crate) fn load(app: &AppHandle) -> Self {
+    pub(crate) fn load(app: &AppHandle, session_exits: SessionExitSender) -> Self {
         let data_dir = app
             .path()
             .app_data_dir()
@@ -53,11 +63,20 @@ impl AppState {
         let settings_path = data_dir.join("settings.json");
         let workspaces = read_workspaces(&storage_path).unwrap_or_default();
         let app_settings = read_settings(&settings_path).unwrap_or_default();
+        let request_timeouts = RequestTimeouts::new(&app_settings.request_timeouts);
+        init_logging(
+            Some(&data_dir.join(LOG_DIR_NAME)),
+            "app",
+            &app_settings.logging,
+        );
         Self {
             workspaces: Mutex::new(workspaces),
             sessions: Mutex::new(HashMap::new()),
-            terminal_sessions: Mutex::new(HashMap::new()),
-            remote_backend: Mutex::new(None),
+            terminal_sessions: Arc::new(Mutex::new(HashMap::new())),
+            session_exits,
+            request_timeouts,
+            remote_backends: Mutex::new(HashMap::new()),
+            remote_event_cursors: std::sync::Mutex::new(HashMap::new()),
             storage_path,
             settings_path,
             app_settings: Mutex::new(app_settings),
@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::types::{AppSettings, WorkspaceEntry};
 use serde_json::Value;
@@ -24,6 +24,35 @@ pub(crate) fn write_workspaces(path: &PathBuf, entries: &[WorkspaceEntry]) -> Re
     std::fs::write(path, data).map_err(|e| e.to_string())
 }
 
+/// Writes a file only its owner can read (0600 on Unix), for secrets such as
+/// token hashes and private keys. Only the daemon binaries write secrets.
+#[allow(dead_code)]
+pub(crate) fn write_private_file(path: &Path, contents: &[u8]) -> Result<(), String> {
+    #[cfg(unix)]
+    {
+        use std::io::Write;
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(path)
+            .map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+        // `mode` only applies on create; tighten files left by older builds.
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))
+            .map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+        file.write_all(contents)
+            .map_err(|err| format!("Failed to write {}: {err}", path.display()))
+    }
+    #[cfg(not(unix))]
+    {
+        std::fs::write(path, contents)
+            .map_err(|err| format!("Failed to write {}: {err}", path.display()))
+    }
+}
+
 pub(crate) fn read_settings(path: &PathBuf) -> Result<AppSettings, String> {
     if !path.exists() {
         return Ok(AppSettings::default());
@@ -66,7 +95,13 @@ fn sanitize_remote_settings_for_tcp_only(value: &mut Value) {
             entry_obj.retain(|key, _| {
                 matches!(
                     key.as_str(),
-                    "id" | "name" | "provider" | "host" | "token" | "lastConnectedAtMs"
+                    "id" | "name"
+                        | "provider"
+                        | "host"
+                        | "token"
+                        | "lastConnectedAtMs"
+                        | "tls"
+                        | "tlsCertFingerprint"
                 )
             });
         }
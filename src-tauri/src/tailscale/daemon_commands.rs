@@ -145,7 +145,9 @@ pub(super) async fn tailscale_daemon_start(
 
             let force_kill_allowed = can_force_stop_daemon(auth_ok, info.as_ref());
             let pid_for_control = pid;
-            if let Err(shutdown_error) = request_daemon_shutdown(&listen_addr, Some(token)).await {
+            if let Err(shutdown_error) =
+                request_daemon_shutdown(&listen_addr, Some(token), true).await
+            {
                 if !force_kill_allowed {
                     return Err(format!(
                         "{}; automatic restart aborted because daemon ownership could not be verified: {}",
@@ -170,7 +172,7 @@ pub(super) async fn tailscale_daemon_start(
                 }
             }
 
-            if !wait_for_daemon_shutdown(&listen_addr, Some(token)).await {
+            if !wait_for_daemon_shutdown(&listen_addr, Some(token), pid).await {
                 if !force_kill_allowed {
                     return Err(format!(
                         "{}; daemon acknowledged shutdown but is still reachable",
@@ -261,6 +263,7 @@ pub(super) async fn tailscale_daemon_stop(
                 if let Err(shutdown_error) = request_daemon_shutdown(
                     &configured_listen_addr,
                     settings.remote_backend_token.as_deref(),
+                    false,
                 )
                 .await
                 {
@@ -283,6 +286,7 @@ pub(super) async fn tailscale_daemon_stop(
                 } else if !wait_for_daemon_shutdown(
                     &configured_listen_addr,
                     settings.remote_backend_token.as_deref(),
+                    info.as_ref().and_then(|entry| entry.pid),
                 )
                 .await
                 {
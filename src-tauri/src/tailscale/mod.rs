@@ -415,6 +415,17 @@ async fn kill_pid_gracefully(_pid: u32) -> Result<(), String> {
     Err("Stopping external daemon by pid is not supported on this platform.".to_string())
 }
 
+/// True once `pid` is gone, or when there is no pid to check.
+#[cfg(unix)]
+fn daemon_process_exited(pid: Option<u32>) -> bool {
+    pid.is_none_or(|pid| !is_pid_running(pid))
+}
+
+#[cfg(not(unix))]
+fn daemon_process_exited(_pid: Option<u32>) -> bool {
+    true
+}
+
 #[tauri::command]
 pub(crate) async fn tailscale_status() -> Result<TailscaleStatus, String> {
     #[cfg(any(target_os = "android", target_os = "ios"))]
@@ -1,6 +1,11 @@
 use super::*;
+use crate::shared::core_error::CoreError;
+use crate::shared::rpc_schema;
 
 const DAEMON_RPC_TIMEOUT: Duration = Duration::from_millis(700);
+/// Covers the daemon's default 30s wait for running turns plus stopping its
+/// app-servers.
+const DAEMON_SHUTDOWN_WAIT: Duration = Duration::from_secs(40);
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(super) struct DaemonInfo {
@@ -24,19 +29,6 @@ pub(super) enum DaemonProbe {
 
 type DaemonLines = tokio::io::Lines<BufReader<OwnedReadHalf>>;
 
-fn parse_daemon_error_message(response: &Value) -> Option<String> {
-    response
-        .get("error")
-        .and_then(|error| error.get("message"))
-        .and_then(Value::as_str)
-        .map(str::to_string)
-}
-
-fn is_auth_error_message(message: &str) -> bool {
-    let lower = message.to_ascii_lowercase();
-    lower.contains("unauthorized") || lower.contains("invalid token")
-}
-
 fn parse_daemon_info(value: &Value) -> Result<DaemonInfo, String> {
     let name = value
         .get("name")
@@ -85,12 +77,7 @@ async fn send_rpc_request(
     method: &str,
     params: Value,
 ) -> Result<(), String> {
-    let mut payload = serde_json::to_string(&json!({
-        "id": id,
-        "method": method,
-        "params": params,
-    }))
-    .map_err(|err| err.to_string())?;
+    let mut payload = rpc_schema::request_line(id, method, params)?;
     payload.push('\n');
     writer
         .write_all(payload.as_bytes())
@@ -130,16 +117,10 @@ async fn send_and_expect_result(
     id: u64,
     method: &str,
     params: Value,
-) -> Result<Value, String> {
+) -> Result<Value, CoreError> {
     send_rpc_request(writer, id, method, params).await?;
     let response = read_rpc_response(lines, id).await?;
-    if let Some(message) = parse_daemon_error_message(&response) {
-        return Err(message);
-    }
-    response
-        .get("result")
-        .cloned()
-        .ok_or_else(|| "daemon response missing result".to_string())
+    rpc_schema::response_result(&response)
 }
 
 async fn request_daemon_info(
@@ -171,7 +152,7 @@ pub(super) async fn probe_daemon(listen_addr: &str, token: Option<&str>) -> Daem
             info: request_daemon_info(&mut writer, &mut lines, 2).await.ok(),
         },
         Err(message) => {
-            if !is_auth_error_message(&message) {
+            if !message.code.is_auth_failure() {
                 return DaemonProbe::NotDaemon;
             }
 
@@ -214,7 +195,7 @@ pub(super) async fn probe_daemon(listen_addr: &str, token: Option<&str>) -> Daem
                     }
                 }
                 Err(auth_error) => {
-                    if is_auth_error_message(&auth_error) {
+                    if auth_error.code.is_auth_failure() {
                         DaemonProbe::Running {
                             auth_ok: false,
                             auth_error: Some(format!(
@@ -231,9 +212,12 @@ pub(super) async fn probe_daemon(listen_addr: &str, token: Option<&str>) -> Daem
     }
 }
 
+/// With `restart`, the daemon hands its live threads to the next process
+/// started in the same data dir.
 pub(super) async fn request_daemon_shutdown(
     listen_addr: &str,
     token: Option<&str>,
+    restart: bool,
 ) -> Result<(), String> {
     let Some(connect_addr) = daemon_connect_addr(listen_addr) else {
         return Err("invalid daemon listen address".to_string());
@@ -249,7 +233,7 @@ pub(super) async fn request_daemon_shutdown(
 
     match send_and_expect_result(&mut writer, &mut lines, 1, "ping", json!({})).await {
         Ok(_) => {}
-        Err(message) if is_auth_error_message(&message) => {
+        Err(message) if message.code.is_auth_failure() => {
             let auth_token = token
                 .map(str::trim)
                 .filter(|value| !value.is_empty())
@@ -271,21 +255,38 @@ pub(super) async fn request_daemon_shutdown(
         }
     }
 
-    send_and_expect_result(&mut writer, &mut lines, 3, "daemon_shutdown", json!({}))
-        .await
-        .map(|_| ())
-        .map_err(|err| format!("Daemon shutdown request failed: {err}"))
+    send_and_expect_result(
+        &mut writer,
+        &mut lines,
+        3,
+        "daemon_shutdown",
+        json!({ "restart": restart }),
+    )
+    .await
+    .map(|_| ())
+    .map_err(|err| format!("Daemon shutdown request failed: {err}"))
 }
 
-pub(super) async fn wait_for_daemon_shutdown(listen_addr: &str, token: Option<&str>) -> bool {
-    for _ in 0..20 {
-        if matches!(
-            probe_daemon(listen_addr, token).await,
-            DaemonProbe::NotReachable
-        ) {
+/// The daemon stops listening as soon as it starts draining, so this also
+/// waits for `pid` to exit when it is known.
+pub(super) async fn wait_for_daemon_shutdown(
+    listen_addr: &str,
+    token: Option<&str>,
+    pid: Option<u32>,
+) -> bool {
+    let deadline = Instant::now() + DAEMON_SHUTDOWN_WAIT;
+    loop {
+        if daemon_process_exited(pid)
+            && matches!(
+                probe_daemon(listen_addr, token).await,
+                DaemonProbe::NotReachable
+            )
+        {
             return true;
         }
+        if Instant::now() >= deadline {
+            return false;
+        }
         sleep(Duration::from_millis(100)).await;
     }
-    false
 }
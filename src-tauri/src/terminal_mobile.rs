@@ -1,60 +1,136 @@
-use serde::Serialize;
 use tauri::{AppHandle, State};
 
+use crate::remote_backend;
+use crate::shared::terminal_rpc::{
+    TerminalAttachInfo, TerminalIdRequest, TerminalListEntry, TerminalListRequest,
+    TerminalOpenRequest, TerminalResizeRequest, TerminalSessionInfo, TerminalWriteRequest,
+};
 use crate::state::AppState;
+use crate::terminal_remote;
 
-const UNSUPPORTED_MESSAGE: &str = "Terminal is not available on mobile builds.";
+const UNSUPPORTED_MESSAGE: &str =
+    "Terminal is only available on mobile builds when connected to a remote daemon.";
 
 pub(crate) struct TerminalSession {
     pub(crate) id: String,
 }
 
-#[derive(Debug, Serialize, Clone)]
-pub(crate) struct TerminalSessionInfo {
-    id: String,
-}
-
 #[tauri::command]
 pub(crate) async fn terminal_open(
-    _workspace_id: String,
+    workspace_id: String,
     terminal_id: String,
-    _cols: u16,
-    _rows: u16,
-    _state: State<'_, AppState>,
-    _app: AppHandle,
+    cols: u16,
+    rows: u16,
+    state: State<'_, AppState>,
+    app: AppHandle,
 ) -> Result<TerminalSessionInfo, String> {
     if terminal_id.trim().is_empty() {
         return Err("Terminal id is required".to_string());
     }
-    Err(UNSUPPORTED_MESSAGE.to_string())
+    if !remote_backend::is_remote_mode(&*state).await {
+        return Err(UNSUPPORTED_MESSAGE.to_string());
+    }
+    let request = TerminalOpenRequest {
+        workspace_id,
+        terminal_id,
+        cols,
+        rows,
+    };
+    terminal_remote::terminal_open(&*state, app, request).await
 }
 
 #[tauri::command]
 pub(crate) async fn terminal_write(
-    _workspace_id: String,
-    _terminal_id: String,
-    _data: String,
-    _state: State<'_, AppState>,
+    workspace_id: String,
+    terminal_id: String,
+    data: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
 ) -> Result<(), String> {
-    Err(UNSUPPORTED_MESSAGE.to_string())
+    if !remote_backend::is_remote_mode(&*state).await {
+        return Err(UNSUPPORTED_MESSAGE.to_string());
+    }
+    let request = TerminalWriteRequest {
+        workspace_id,
+        terminal_id,
+        data,
+    };
+    terminal_remote::terminal_write(&*state, app, request).await
 }
 
 #[tauri::command]
 pub(crate) async fn terminal_resize(
-    _workspace_id: String,
-    _terminal_id: String,
-    _cols: u16,
-    _rows: u16,
-    _state: State<'_, AppState>,
+    workspace_id: String,
+    terminal_id: String,
+    cols: u16,
+    rows: u16,
+    state: State<'_, AppState>,
+    app: AppHandle,
 ) -> Result<(), String> {
-    Err(UNSUPPORTED_MESSAGE.to_string())
+    if !remote_backend::is_remote_mode(&*state).await {
+        return Err(UNSUPPORTED_MESSAGE.to_string());
+    }
+    let request = TerminalResizeRequest {
+        workspace_id,
+        terminal_id,
+        cols,
+        rows,
+    };
+    terminal_remote::terminal_resize(&*state, app, request).await
 }
 
 #[tauri::command]
 pub(crate) async fn terminal_close(
-    _workspace_id: String,
-    _terminal_id: String,
-    _state: State<'_, AppState>,
+    workspace_id: String,
+    terminal_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
 ) -> Result<(), String> {
-    Err(UNSUPPORTED_MESSAGE.to_string())
+    if !remote_backend::is_remote_mode(&*state).await {
+        return Err(UNSUPPORTED_MESSAGE.to_string());
+    }
+    let request = TerminalIdRequest {
+        workspace_id,
+        terminal_id,
+    };
+    terminal_remote::terminal_close(&*state, app, request).await
+}
+
+/// Mobile builds run no local shells; the daemon cleans up its own.
+pub(crate) async fn close_workspace_terminals(
+    _state: &AppState,
+    _app: &AppHandle,
+    _workspace_ids: &[String],
+) -> Result<(), String> {
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn terminal_attach(
+    workspace_id: String,
+    terminal_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<TerminalAttachInfo, String> {
+    if !remote_backend::is_remote_mode(&*state).await {
+        return Err(UNSUPPORTED_MESSAGE.to_string());
+    }
+    let request = TerminalIdRequest {
+        workspace_id,
+        terminal_id,
+    };
+    terminal_remote::terminal_attach(&*state, app, request).await
+}
+
+#[tauri::command]
+pub(crate) async fn terminal_list(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Vec<TerminalListEntry>, String> {
+    if !remote_backend::is_remote_mode(&*state).await {
+        return Ok(Vec::new());
+    }
+    let request = TerminalListRequest { workspace_id };
+    terminal_remote::terminal_list(&*state, app, request).await
 }
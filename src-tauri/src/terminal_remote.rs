@@ -0,0 +1,97 @@
+use tauri::AppHandle;
+
+use crate::remote_backend;
+use crate::shared::terminal_rpc::{
+    self, TerminalAttachInfo, TerminalListEntry, TerminalSessionInfo,
+};
+use crate::state::AppState;
+
+pub(crate) async fn terminal_open(
+    state: &AppState,
+    app: AppHandle,
+    request: terminal_rpc::TerminalOpenRequest,
+) -> Result<TerminalSessionInfo, String> {
+    let response = remote_backend::call_remote(
+        state,
+        app,
+        terminal_rpc::METHOD_TERMINAL_OPEN,
+        terminal_rpc::to_params(&request)?,
+    )
+    .await?;
+    serde_json::from_value(response).map_err(|err| err.to_string())
+}
+
+pub(crate) async fn terminal_write(
+    state: &AppState,
+    app: AppHandle,
+    request: terminal_rpc::TerminalWriteRequest,
+) -> Result<(), String> {
+    remote_backend::call_remote(
+        state,
+        app,
+        terminal_rpc::METHOD_TERMINAL_WRITE,
+        terminal_rpc::to_params(&request)?,
+    )
+    .await
+    .map(|_| ())
+}
+
+pub(crate) async fn terminal_resize(
+    state: &AppState,
+    app: AppHandle,
+    request: terminal_rpc::TerminalResizeRequest,
+) -> Result<(), String> {
+    remote_backend::call_remote(
+        state,
+        app,
+        terminal_rpc::METHOD_TERMINAL_RESIZE,
+        terminal_rpc::to_params(&request)?,
+    )
+    .await
+    .map(|_| ())
+}
+
+pub(crate) async fn terminal_close(
+    state: &AppState,
+    app: AppHandle,
+    request: terminal_rpc::TerminalIdRequest,
+) -> Result<(), String> {
+    remote_backend::call_remote(
+        state,
+        app,
+        terminal_rpc::METHOD_TERMINAL_CLOSE,
+        terminal_rpc::to_params(&request)?,
+    )
+    .await
+    .map(|_| ())
+}
+
+pub(crate) async fn terminal_attach(
+    state: &AppState,
+    app: AppHandle,
+    request: terminal_rpc::TerminalIdRequest,
+) -> Result<TerminalAttachInfo, String> {
+    let response = remote_backend::call_remote(
+        state,
+        app,
+        terminal_rpc::METHOD_TERMINAL_ATTACH,
+        terminal_rpc::to_params(&request)?,
+    )
+    .await?;
+    serde_json::from_value(response).map_err(|err| err.to_string())
+}
+
+pub(crate) async fn terminal_list(
+    state: &AppState,
+    app: AppHandle,
+    request: terminal_rpc::TerminalListRequest,
+) -> Result<Vec<TerminalListEntry>, String> {
+    let response = remote_backend::call_remote(
+        state,
+        app,
+        terminal_rpc::METHOD_TERMINAL_LIST,
+        terminal_rpc::to_params(&request)?,
+    )
+    .await?;
+    serde_json::from_value(response).map_err(|err| err.to_string())
+}
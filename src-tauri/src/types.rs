@@ -1,6 +1,9 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub(crate) struct GitFileStatus {
     pub(crate) path: String,
     pub(crate) status: String,
@@ -8,7 +11,7 @@ pub(crate) struct GitFileStatus {
     pub(crate) deletions: i64,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub(crate) struct GitFileDiff {
     pub(crate) path: String,
     pub(crate) diff: String,
@@ -30,7 +33,7 @@ pub(crate) struct GitFileDiff {
     pub(crate) new_image_mime: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub(crate) struct GitCommitDiff {
     pub(crate) path: String,
     pub(crate) status: String,
@@ -53,7 +56,7 @@ pub(crate) struct GitCommitDiff {
     pub(crate) new_image_mime: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub(crate) struct GitLogEntry {
     pub(crate) sha: String,
     pub(crate) summary: String,
@@ -61,7 +64,7 @@ pub(crate) struct GitLogEntry {
     pub(crate) timestamp: i64,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub(crate) struct GitLogResponse {
     pub(crate) total: usize,
     pub(crate) entries: Vec<GitLogEntry>,
@@ -77,7 +80,7 @@ pub(crate) struct GitLogResponse {
     pub(crate) upstream: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub(crate) struct GitHubIssue {
     pub(crate) number: u64,
     pub(crate) title: String,
@@ -86,18 +89,18 @@ pub(crate) struct GitHubIssue {
     pub(crate) updated_at: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub(crate) struct GitHubIssuesResponse {
     pub(crate) total: usize,
     pub(crate) issues: Vec<GitHubIssue>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub(crate) struct GitHubPullRequestAuthor {
     pub(crate) login: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub(crate) struct GitHubPullRequest {
     pub(crate) number: u64,
     pub(crate) title: String,
@@ -117,21 +120,21 @@ pub(crate) struct GitHubPullRequest {
     pub(crate) author: Option<GitHubPullRequestAuthor>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub(crate) struct GitHubPullRequestsResponse {
     pub(crate) total: usize,
     #[serde(rename = "pullRequests")]
     pub(crate) pull_requests: Vec<GitHubPullRequest>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub(crate) struct GitHubPullRequestDiff {
     pub(crate) path: String,
     pub(crate) status: String,
     pub(crate) diff: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub(crate) struct GitHubPullRequestComment {
     pub(crate) id: u64,
     #[serde(default)]
@@ -144,7 +147,7 @@ pub(crate) struct GitHubPullRequestComment {
     pub(crate) author: Option<GitHubPullRequestAuthor>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct LocalUsageDay {
     pub(crate) day: String,
@@ -158,7 +161,7 @@ pub(crate) struct LocalUsageDay {
     pub(crate) agent_runs: i64,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct LocalUsageTotals {
     pub(crate) last7_days_tokens: i64,
@@ -169,7 +172,7 @@ pub(crate) struct LocalUsageTotals {
     pub(crate) peak_day_tokens: i64,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct LocalUsageModel {
     pub(crate) model: String,
@@ -177,7 +180,7 @@ pub(crate) struct LocalUsageModel {
     pub(crate) share_percent: f64,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct LocalUsageSnapshot {
     pub(crate) updated_at: i64,
@@ -187,7 +190,7 @@ pub(crate) struct LocalUsageSnapshot {
     pub(crate) top_models: Vec<LocalUsageModel>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub(crate) enum TcpDaemonState {
     Stopped,
@@ -195,7 +198,7 @@ pub(crate) enum TcpDaemonState {
     Error,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct TcpDaemonStatus {
     pub(crate) state: TcpDaemonState,
@@ -209,7 +212,7 @@ pub(crate) struct TcpDaemonStatus {
     pub(crate) listen_addr: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct TailscaleStatus {
     pub(crate) installed: bool,
@@ -231,7 +234,7 @@ pub(crate) struct TailscaleStatus {
     pub(crate) message: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct TailscaleDaemonCommandPreview {
     pub(crate) command: String,
@@ -240,13 +243,13 @@ pub(crate) struct TailscaleDaemonCommandPreview {
     pub(crate) token_configured: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub(crate) struct BranchInfo {
     pub(crate) name: String,
     pub(crate) last_commit: i64,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub(crate) struct WorkspaceEntry {
     pub(crate) id: String,
     pub(crate) name: String,
@@ -261,7 +264,7 @@ pub(crate) struct WorkspaceEntry {
     pub(crate) settings: WorkspaceSettings,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub(crate) struct WorkspaceInfo {
     pub(crate) id: String,
     pub(crate) name: String,
@@ -277,7 +280,7 @@ pub(crate) struct WorkspaceInfo {
     pub(crate) settings: WorkspaceSettings,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub(crate) enum WorkspaceKind {
     Main,
@@ -296,12 +299,12 @@ impl WorkspaceKind {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub(crate) struct WorktreeInfo {
     pub(crate) branch: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub(crate) struct WorkspaceGroup {
     pub(crate) id: String,
     pub(crate) name: String,
@@ -311,7 +314,7 @@ pub(crate) struct WorkspaceGroup {
     pub(crate) copies_folder: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, JsonSchema)]
 pub(crate) struct WorkspaceSettings {
     #[serde(default, rename = "sidebarCollapsed")]
     pub(crate) sidebar_collapsed: bool,
@@ -331,7 +334,7 @@ pub(crate) struct WorkspaceSettings {
     pub(crate) worktree_setup_script: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub(crate) struct LaunchScriptEntry {
     pub(crate) id: String,
     pub(crate) script: String,
@@ -340,14 +343,14 @@ pub(crate) struct LaunchScriptEntry {
     pub(crate) label: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub(crate) struct WorktreeSetupStatus {
     #[serde(rename = "shouldRun")]
     pub(crate) should_run: bool,
     pub(crate) script: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub(crate) struct OpenAppTarget {
     pub(crate) id: String,
     pub(crate) label: String,
@@ -360,7 +363,7 @@ pub(crate) struct OpenAppTarget {
     pub(crate) args: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub(crate) struct RemoteBackendTarget {
     pub(crate) id: String,
     pub(crate) name: String,
@@ -371,9 +374,16 @@ pub(crate) struct RemoteBackendTarget {
     pub(crate) token: Option<String>,
     #[serde(default, rename = "lastConnectedAtMs")]
     pub(crate) last_connected_at_ms: Option<i64>,
+    /// Connect over TLS instead of plaintext TCP.
+    #[serde(default)]
+    pub(crate) tls: bool,
+    /// SHA-256 fingerprint of the daemon certificate to pin; when unset the
+    /// certificate is validated against the web PKI roots instead.
+    #[serde(default, rename = "tlsCertFingerprint")]
+    pub(crate) tls_cert_fingerprint: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub(crate) struct AppSettings {
     #[serde(default, rename = "codexBin")]
     pub(crate) codex_bin: Option<String>,
@@ -391,6 +401,10 @@ pub(crate) struct AppSettings {
     pub(crate) remote_backends: Vec<RemoteBackendTarget>,
     #[serde(default, rename = "activeRemoteBackendId")]
     pub(crate) active_remote_backend_id: Option<String>,
+    /// Connect to every entry in `remote_backends` at once instead of only the
+    /// active one. Workspace ids are then namespaced by backend id.
+    #[serde(default, rename = "aggregateRemoteBackends")]
+    pub(crate) aggregate_remote_backends: bool,
     #[serde(default, rename = "keepDaemonRunningAfterAppClose")]
     pub(crate) keep_daemon_running_after_app_close: bool,
     #[serde(default = "default_access_mode", rename = "defaultAccessMode")]
@@ -641,9 +655,116 @@ pub(crate) struct AppSettings {
     pub(crate) open_app_targets: Vec<OpenAppTarget>,
     #[serde(default = "default_selected_open_app_id", rename = "selectedOpenAppId")]
     pub(crate) selected_open_app_id: String,
+    #[serde(default, rename = "requestTimeouts")]
+    pub(crate) request_timeouts: RequestTimeoutSettings,
+    #[serde(default, rename = "sessionPool")]
+    pub(crate) session_pool: SessionPoolSettings,
+    #[serde(default)]
+    pub(crate) logging: LoggingSettings,
+}
+
+/// Backend log output for the app and the daemon. `level` is a `tracing`
+/// filter such as `info,codex_monitor_daemon::push=debug`; the
+/// `CODEX_MONITOR_LOG` environment variable overrides it.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema)]
+pub(crate) struct LoggingSettings {
+    #[serde(default = "default_log_level")]
+    pub(crate) level: String,
+    #[serde(default)]
+    pub(crate) format: LogFormat,
+    /// Log files rotate daily; this many days are kept.
+    #[serde(default = "default_log_max_files", rename = "maxFiles")]
+    pub(crate) max_files: u32,
+}
+
+impl Default for LoggingSettings {
+    fn default() -> Self {
+        Self {
+            level: default_log_level(),
+            format: LogFormat::default(),
+            max_files: default_log_max_files(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// Limits on live `codex app-server` processes. Zero turns a limit off.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, JsonSchema)]
+pub(crate) struct SessionPoolSettings {
+    #[serde(default, rename = "maxLiveSessions")]
+    pub(crate) max_live_sessions: u32,
+    /// Workspaces without a request or event for this long are put to sleep.
+    #[serde(default, rename = "idleTimeoutSecs")]
+    pub(crate) idle_timeout_secs: u64,
+}
+
+impl SessionPoolSettings {
+    pub(crate) fn max_live_sessions(&self) -> Option<usize> {
+        (self.max_live_sessions > 0).then_some(self.max_live_sessions as usize)
+    }
+
+    pub(crate) fn idle_timeout(&self) -> Option<Duration> {
+        (self.idle_timeout_secs > 0).then(|| Duration::from_secs(self.idle_timeout_secs))
+    }
+}
+
+/// How long app-server and remote daemon requests may wait for a response.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema)]
+pub(crate) struct RequestTimeoutSettings {
+    #[serde(default = "default_request_timeout_secs", rename = "defaultSecs")]
+    pub(crate) default_secs: u64,
+    /// Per-method overrides, keyed by app-server method (`thread/list`) or
+    /// daemon RPC method (`list_threads`).
+    #[serde(default)]
+    pub(crate) methods: BTreeMap<String, u64>,
+    /// Send `$/cancelRequest` to the app-server when a caller abandons a
+    /// request. Off by default; only enable it for app-servers that accept it.
+    #[serde(default, rename = "sendCancelNotifications")]
+    pub(crate) send_cancel_notifications: bool,
+}
+
+impl Default for RequestTimeoutSettings {
+    fn default() -> Self {
+        Self {
+            default_secs: default_request_timeout_secs(),
+            methods: BTreeMap::new(),
+            send_cancel_notifications: false,
+        }
+    }
+}
+
+impl RequestTimeoutSettings {
+    pub(crate) fn timeout_for(&self, method: &str) -> Duration {
+        let secs = self
+            .methods
+            .get(method)
+            .copied()
+            .unwrap_or(self.default_secs);
+        Duration::from_secs(secs)
+    }
+
+    /// Rejects zero, which would time every request out at once.
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.default_secs == 0 {
+            return Err("`requestTimeouts.defaultSecs` must be at least 1".to_string());
+        }
+        match self.methods.iter().find(|(_, secs)| **secs == 0) {
+            Some((method, _)) => Err(format!(
+                "`requestTimeouts.methods.{method}` must be at least 1"
+            )),
+            None => Ok(()),
+        }
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub(crate) enum BackendMode {
     Local,
@@ -656,7 +777,7 @@ impl Default for BackendMode {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub(crate) enum RemoteBackendProvider {
     Tcp,
@@ -692,6 +813,18 @@ fn default_remote_backends() -> Vec<RemoteBackendTarget> {
     Vec::new()
 }
 
+fn default_request_timeout_secs() -> u64 {
+    300
+}
+
+fn default_log_level() -> String {
+    "info".to_string()
+}
+
+fn default_log_max_files() -> u32 {
+    5
+}
+
 fn default_ui_scale() -> f64 {
     1.0
 }
@@ -1124,6 +1257,7 @@ impl Default for AppSettings {
             remote_backend_token: None,
             remote_backends: default_remote_backends(),
             active_remote_backend_id: None,
+            aggregate_remote_backends: false,
             keep_daemon_running_after_app_close: false,
             default_access_mode: "current".to_string(),
             review_delivery_mode: default_review_delivery_mode(),
@@ -1191,16 +1325,29 @@ impl Default for AppSettings {
             workspace_groups: default_workspace_groups(),
             open_app_targets: default_open_app_targets(),
             selected_open_app_id: default_selected_open_app_id(),
+            request_timeouts: RequestTimeoutSettings::default(),
+            session_pool: SessionPoolSettings::default(),
+            logging: LoggingSettings::default(),
         }
     }
 }
 
+impl AppSettings {
+    pub(crate) fn active_remote_backend(&self) -> Option<&RemoteBackendTarget> {
+        let active_id = self.active_remote_backend_id.as_deref()?;
+        self.remote_backends
+            .iter()
+            .find(|target| target.id == active_id)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
-        AppSettings, BackendMode, RemoteBackendProvider, WorkspaceEntry, WorkspaceGroup,
-        WorkspaceKind, WorkspaceSettings,
+        AppSettings, BackendMode, LogFormat, RemoteBackendProvider, RequestTimeoutSettings,
+        WorkspaceEntry, WorkspaceGroup, WorkspaceKind, WorkspaceSettings,
     };
+    use std::time::Duration;
 
     #[test]
     fn app_settings_defaults_from_empty_json() {
@@ -1223,6 +1370,7 @@ mod tests {
         assert!(settings.remote_backend_token.is_none());
         assert!(settings.remote_backends.is_empty());
         assert!(settings.active_remote_backend_id.is_none());
+        assert!(!settings.aggregate_remote_backends);
         assert!(!settings.keep_daemon_running_after_app_close);
         assert_eq!(settings.default_access_mode, "current");
         assert_eq!(settings.review_delivery_mode, "inline");
@@ -1358,6 +1506,42 @@ mod tests {
         assert_eq!(settings.selected_open_app_id, expected_open_id);
         assert_eq!(settings.open_app_targets.len(), 6);
         assert_eq!(settings.open_app_targets[0].id, "vscode");
+        assert_eq!(settings.request_timeouts, RequestTimeoutSettings::default());
+        assert!(settings.session_pool.max_live_sessions().is_none());
+        assert!(settings.session_pool.idle_timeout().is_none());
+        assert_eq!(settings.logging.level, "info");
+        assert_eq!(settings.logging.format, LogFormat::Text);
+    }
+
+    #[test]
+    fn request_timeouts_prefer_method_overrides() {
+        let timeouts: RequestTimeoutSettings = serde_json::from_str(
+            r#"{"defaultSecs":120,"methods":{"thread/list":10,"turn/start":0}}"#,
+        )
+        .expect("timeouts deserialize");
+        assert_eq!(timeouts.timeout_for("thread/list"), Duration::from_secs(10));
+        assert_eq!(
+            timeouts.timeout_for("thread/start"),
+            Duration::from_secs(120)
+        );
+        assert!(!timeouts.send_cancel_notifications);
+        assert_eq!(
+            timeouts.validate(),
+            Err("`requestTimeouts.methods.turn/start` must be at least 1".to_string())
+        );
+        assert_eq!(
+            RequestTimeoutSettings::default().timeout_for("thread/list"),
+            Duration::from_secs(300)
+        );
+        assert!(RequestTimeoutSettings::default().validate().is_ok());
+        let zero_default = RequestTimeoutSettings {
+            default_secs: 0,
+            ..RequestTimeoutSettings::default()
+        };
+        assert_eq!(
+            zero_default.validate(),
+            Err("`requestTimeouts.defaultSecs` must be at least 1".to_string())
+        );
     }
 
     #[test]
@@ -7,7 +7,7 @@ use tauri::{AppHandle, Manager, State};
 use super::files::{list_workspace_files_inner, read_workspace_file_inner, WorkspaceFileResponse};
 use super::git::{
     git_branch_exists, git_find_remote_for_branch, git_remote_branch_exists, git_remote_exists,
-    is_missing_worktree_error, run_git_command_owned, unique_branch_name,
+    run_git_command_owned, unique_branch_name,
 };
 #[cfg(target_os = "macos")]
 use super::macos::get_open_app_icon_inner;
@@ -18,8 +18,10 @@ use super::worktree::{
 
 use crate::backend::app_server::WorkspaceSession;
 use crate::codex::spawn_workspace_session;
+use crate::event_sink::TauriEventSink;
 use crate::git_utils::resolve_git_root;
 use crate::remote_backend;
+use crate::shared::core_error::CoreError;
 use crate::shared::{workspace_rpc, workspaces_core};
 use crate::state::AppState;
 use crate::types::{WorkspaceEntry, WorkspaceInfo, WorkspaceSettings, WorktreeSetupStatus};
@@ -30,12 +32,12 @@ fn spawn_with_app(
     default_bin: Option<String>,
     codex_args: Option<String>,
     codex_home: Option<PathBuf>,
-) -> impl std::future::Future<Output = Result<Arc<WorkspaceSession>, String>> {
+) -> impl std::future::Future<Output = Result<Arc<WorkspaceSession>, CoreError>> {
     spawn_workspace_session(entry, default_bin, codex_args, app.clone(), codex_home)
 }
 
 fn workspace_remote_params<T: serde::Serialize>(request: &T) -> Result<serde_json::Value, String> {
-    workspace_rpc::to_params(request)
+    workspace_rpc::to_params(request).map_err(String::from)
 }
 
 fn workspace_remote_empty_params() -> serde_json::Value {
@@ -68,6 +70,7 @@ pub(crate) async fn read_workspace_file(
         |root, rel_path| read_workspace_file_inner(root, rel_path),
     )
     .await
+    .map_err(String::from)
 }
 
 #[tauri::command]
@@ -120,6 +123,7 @@ pub(crate) async fn set_workspace_runtime_codex_args(
         |entry, default_bin, args, home| spawn_with_app(&app, entry, default_bin, args, home),
     )
     .await
+    .map_err(String::from)
 }
 
 #[tauri::command]
@@ -172,6 +176,7 @@ pub(crate) async fn add_workspace(
         },
     )
     .await
+    .map_err(String::from)
 }
 
 #[tauri::command]
@@ -212,6 +217,7 @@ pub(crate) async fn add_workspace_from_git_url(
         },
     )
     .await
+    .map_err(String::from)
 }
 
 #[tauri::command]
@@ -235,6 +241,7 @@ pub(crate) async fn add_clone(
         },
     )
     .await
+    .map_err(String::from)
 }
 
 #[tauri::command]
@@ -286,7 +293,7 @@ pub(crate) async fn add_worktree(
             let branch = branch.to_string();
             async move { git_branch_exists(&root, &branch).await }
         },
-        None::<fn(&PathBuf, &str) -> std::future::Ready<Result<Option<String>, String>>>,
+        None::<fn(&PathBuf, &str) -> std::future::Ready<Result<Option<String>, CoreError>>>,
         |root, args| {
             workspaces_core::run_git_command_unit(root, args, |repo, args_owned| {
                 run_git_command_owned(repo, args_owned)
@@ -297,6 +304,7 @@ pub(crate) async fn add_worktree(
         },
     )
     .await
+    .map_err(String::from)
 }
 
 #[tauri::command]
@@ -321,7 +329,9 @@ pub(crate) async fn worktree_setup_status(
         .path()
         .app_data_dir()
         .map_err(|err| format!("Failed to resolve app data dir: {err}"))?;
-    workspaces_core::worktree_setup_status_core(&state.workspaces, &workspace_id, &data_dir).await
+    workspaces_core::worktree_setup_status_core(&state.workspaces, &workspace_id, &data_dir)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -346,7 +356,37 @@ pub(crate) async fn worktree_setup_mark_ran(
         .path()
         .app_data_dir()
         .map_err(|err| format!("Failed to resolve app data dir: {err}"))?;
-    workspaces_core::worktree_setup_mark_ran_core(&state.workspaces, &workspace_id, &data_dir).await
+    workspaces_core::worktree_setup_mark_ran_core(&state.workspaces, &workspace_id, &data_dir)
+        .await
+        .map_err(String::from)
+}
+
+async fn workspace_and_children(state: &AppState, id: &str) -> Vec<String> {
+    let workspaces = state.workspaces.lock().await;
+    workspaces
+        .values()
+        .filter(|entry| entry.id == id || entry.parent_id.as_deref() == Some(id))
+        .map(|entry| entry.id.clone())
+        .collect()
+}
+
+/// Stops shells and deletes scrollback for every id in `candidates` that is
+/// no longer registered.
+async fn close_removed_workspace_terminals(
+    state: &AppState,
+    app: &AppHandle,
+    candidates: Vec<String>,
+) {
+    let removed: Vec<String> = {
+        let workspaces = state.workspaces.lock().await;
+        candidates
+            .into_iter()
+            .filter(|workspace_id| !workspaces.contains_key(workspace_id))
+            .collect()
+    };
+    if let Err(err) = crate::terminal::close_workspace_terminals(state, app, &removed).await {
+        tracing::warn!("failed to clean up terminals of removed workspaces: {err}");
+    }
 }
 
 #[tauri::command]
@@ -367,7 +407,8 @@ pub(crate) async fn remove_workspace(
         return Ok(());
     }
 
-    workspaces_core::remove_workspace_core(
+    let candidates = workspace_and_children(&state, &id).await;
+    let result = workspaces_core::remove_workspace_core(
         id,
         &state.workspaces,
         &state.sessions,
@@ -377,15 +418,17 @@ pub(crate) async fn remove_workspace(
                 run_git_command_owned(repo, args_owned)
             })
         },
-        |error| is_missing_worktree_error(error),
         |path| {
-            std::fs::remove_dir_all(path)
-                .map_err(|err| format!("Failed to remove worktree folder: {err}"))
+            std::fs::remove_dir_all(path).map_err(|err| {
+                CoreError::internal(format!("Failed to remove worktree folder: {err}"))
+            })
         },
         true,
         true,
     )
-    .await
+    .await;
+    close_removed_workspace_terminals(&state, &app, candidates).await;
+    result.map_err(String::from)
 }
 
 #[tauri::command]
@@ -406,7 +449,8 @@ pub(crate) async fn remove_worktree(
         return Ok(());
     }
 
-    workspaces_core::remove_worktree_core(
+    let candidates = vec![id.clone()];
+    let result = workspaces_core::remove_worktree_core(
         id,
         &state.workspaces,
         &state.sessions,
@@ -416,13 +460,15 @@ pub(crate) async fn remove_worktree(
                 run_git_command_owned(repo, args_owned)
             })
         },
-        |error| is_missing_worktree_error(error),
         |path| {
-            std::fs::remove_dir_all(path)
-                .map_err(|err| format!("Failed to remove worktree folder: {err}"))
+            std::fs::remove_dir_all(path).map_err(|err| {
+                CoreError::internal(format!("Failed to remove worktree folder: {err}"))
+            })
         },
     )
-    .await
+    .await;
+    close_removed_workspace_terminals(&state, &app, candidates).await;
+    result.map_err(String::from)
 }
 
 #[tauri::command]
@@ -457,7 +503,7 @@ pub(crate) async fn rename_worktree(
         &state.sessions,
         &state.app_settings,
         &state.storage_path,
-        |entry| resolve_git_root(entry),
+        |entry| Ok(resolve_git_root(entry)?),
         |root, name| {
             let root = root.clone();
             let name = name.to_string();
@@ -479,6 +525,7 @@ pub(crate) async fn rename_worktree(
         },
     )
     .await
+    .map_err(String::from)
 }
 
 #[tauri::command]
@@ -510,7 +557,7 @@ pub(crate) async fn rename_worktree_upstream(
         old_branch,
         new_branch,
         &state.workspaces,
-        |entry| resolve_git_root(entry),
+        |entry| Ok(resolve_git_root(entry)?),
         |root, branch| {
             let root = root.clone();
             let branch = branch.to_string();
@@ -539,6 +586,7 @@ pub(crate) async fn rename_worktree_upstream(
         },
     )
     .await
+    .map_err(String::from)
 }
 
 #[tauri::command]
@@ -546,7 +594,9 @@ pub(crate) async fn apply_worktree_changes(
     workspace_id: String,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
-    workspaces_core::apply_worktree_changes_core(&state.workspaces, workspace_id).await
+    workspaces_core::apply_worktree_changes_core(&state.workspaces, workspace_id)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -583,6 +633,7 @@ pub(crate) async fn update_workspace_settings(
         },
     )
     .await
+    .map_err(String::from)
 }
 
 #[tauri::command]
@@ -612,7 +663,14 @@ pub(crate) async fn connect_workspace(
             spawn_with_app(&app, entry, default_bin, codex_args, codex_home)
         },
     )
-    .await
+    .await?;
+    workspaces_core::enforce_session_pool_core(
+        &state.sessions,
+        &state.app_settings,
+        &TauriEventSink::new(app.clone()),
+    )
+    .await;
+    Ok(())
 }
 
 #[tauri::command]
@@ -637,6 +695,7 @@ pub(crate) async fn list_workspace_files(
         list_workspace_files_inner(root, usize::MAX)
     })
     .await
+    .map_err(String::from)
 }
 
 #[tauri::command]
@@ -646,7 +705,9 @@ pub(crate) async fn open_workspace_in(
     args: Vec<String>,
     command: Option<String>,
 ) -> Result<(), String> {
-    workspaces_core::open_workspace_in_core(path, app, args, command).await
+    workspaces_core::open_workspace_in_core(path, app, args, command)
+        .await
+        .map_err(String::from)
 }
 
 #[tauri::command]
@@ -661,6 +722,8 @@ pub(crate) async fn get_open_app_icon(app_name: String) -> Result<Option<String>
 
     #[cfg(not(target_os = "macos"))]
     {
-        workspaces_core::get_open_app_icon_core(app_name, |_name| None).await
+        workspaces_core::get_open_app_icon_core(app_name, |_name| None)
+            .await
+            .map_err(String::from)
     }
 }
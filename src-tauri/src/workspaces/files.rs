@@ -5,6 +5,7 @@ use std::path::PathBuf;
 use ignore::WalkBuilder;
 use serde::{Deserialize, Serialize};
 
+use crate::shared::core_error::CoreError;
 use crate::utils::normalize_git_path;
 
 fn should_skip_dir(name: &str) -> bool {
@@ -69,34 +70,36 @@ pub(crate) struct WorkspaceFileResponse {
 pub(crate) fn read_workspace_file_inner(
     root: &PathBuf,
     relative_path: &str,
-) -> Result<WorkspaceFileResponse, String> {
+) -> Result<WorkspaceFileResponse, CoreError> {
     let canonical_root = root
         .canonicalize()
-        .map_err(|err| format!("Failed to resolve workspace root: {err}"))?;
+        .map_err(|err| CoreError::internal(format!("Failed to resolve workspace root: {err}")))?;
     let candidate = canonical_root.join(relative_path);
     let canonical_path = candidate
         .canonicalize()
-        .map_err(|err| format!("Failed to open file: {err}"))?;
+        .map_err(|err| CoreError::internal(format!("Failed to open file: {err}")))?;
     if !canonical_path.starts_with(&canonical_root) {
-        return Err("Invalid file path".to_string());
+        return Err(CoreError::invalid_params("Invalid file path"));
     }
     let metadata = std::fs::metadata(&canonical_path)
-        .map_err(|err| format!("Failed to read file metadata: {err}"))?;
+        .map_err(|err| CoreError::internal(format!("Failed to read file metadata: {err}")))?;
     if !metadata.is_file() {
-        return Err("Path is not a file".to_string());
+        return Err(CoreError::invalid_params("Path is not a file"));
     }
 
-    let file = File::open(&canonical_path).map_err(|err| format!("Failed to open file: {err}"))?;
+    let file = File::open(&canonical_path)
+        .map_err(|err| CoreError::internal(format!("Failed to open file: {err}")))?;
     let mut buffer = Vec::new();
     file.take(MAX_WORKSPACE_FILE_BYTES + 1)
         .read_to_end(&mut buffer)
-        .map_err(|err| format!("Failed to read file: {err}"))?;
+        .map_err(|err| CoreError::internal(format!("Failed to read file: {err}")))?;
 
     let truncated = buffer.len() > MAX_WORKSPACE_FILE_BYTES as usize;
     if truncated {
         buffer.truncate(MAX_WORKSPACE_FILE_BYTES as usize);
     }
 
-    let content = String::from_utf8(buffer).map_err(|_| "File is not valid UTF-8".to_string())?;
+    let content =
+        String::from_utf8(buffer).map_err(|_| CoreError::internal("File is not valid UTF-8"))?;
     Ok(WorkspaceFileResponse { content, truncated })
 }
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 
+use crate::shared::core_error::CoreError;
 #[cfg(test)]
 use crate::types::WorkspaceInfo;
 use crate::types::{WorkspaceEntry, WorkspaceSettings};
@@ -21,12 +22,12 @@ pub(crate) fn apply_workspace_settings_update(
     workspaces: &mut HashMap<String, WorkspaceEntry>,
     id: &str,
     settings: WorkspaceSettings,
-) -> Result<WorkspaceEntry, String> {
+) -> Result<WorkspaceEntry, CoreError> {
     match workspaces.get_mut(id) {
         Some(entry) => {
             entry.settings = settings.clone();
             Ok(entry.clone())
         }
-        None => Err("workspace not found".to_string()),
+        None => Err(CoreError::workspace_not_found()),
     }
 }
@@ -8,6 +8,7 @@ use super::worktree::{
     build_clone_destination_path, sanitize_clone_dir_name, sanitize_worktree_name,
 };
 use crate::backend::app_server::WorkspaceSession;
+use crate::shared::core_error::CoreError;
 use crate::shared::workspaces_core::{
     remove_workspace_core, remove_worktree_core, rename_worktree_core,
 };
@@ -309,7 +310,7 @@ fn rename_worktree_preserves_custom_name() {
             |_, _, current| Ok(current.to_path_buf()),
             |_root, _args| async move { Ok(()) },
             |_entry, _default_bin, _codex_args, _codex_home| async move {
-                Err("spawn not expected".to_string())
+                Err(CoreError::internal("spawn not expected"))
             },
         )
         .await
@@ -380,7 +381,7 @@ fn rename_worktree_updates_name_when_unmodified() {
             |_, _, current| Ok(current.to_path_buf()),
             |_root, _args| async move { Ok(()) },
             |_entry, _default_bin, _codex_args, _codex_home| async move {
-                Err("spawn not expected".to_string())
+                Err(CoreError::internal("spawn not expected"))
             },
         )
         .await
@@ -433,8 +434,9 @@ fn remove_workspace_succeeds_when_parent_repo_folder_is_missing() {
             |_root, _args| async move {
                 panic!("git should not run when parent repo folder is missing");
             },
-            |_error| false,
-            |path| std::fs::remove_dir_all(path).map_err(|err| err.to_string()),
+            |path| {
+                std::fs::remove_dir_all(path).map_err(|err| CoreError::internal(err.to_string()))
+            },
             true,
             true,
         )
@@ -490,8 +492,9 @@ fn remove_worktree_succeeds_when_parent_repo_folder_is_missing() {
             |_root, _args| async move {
                 panic!("git should not run when parent repo folder is missing");
             },
-            |_error| false,
-            |path| std::fs::remove_dir_all(path).map_err(|err| err.to_string()),
+            |path| {
+                std::fs::remove_dir_all(path).map_err(|err| CoreError::internal(err.to_string()))
+            },
         )
         .await
         .expect("remove worktree");
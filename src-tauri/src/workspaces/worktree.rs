@@ -1,5 +1,6 @@
 use std::path::PathBuf;
 
+use crate::shared::core_error::CoreError;
 use crate::shared::worktree_core;
 
 pub(crate) fn sanitize_worktree_name(branch: &str) -> String {
@@ -19,7 +20,7 @@ pub(crate) fn unique_worktree_path_for_rename(
     base_dir: &PathBuf,
     name: &str,
     current_path: &PathBuf,
-) -> Result<PathBuf, String> {
+) -> Result<PathBuf, CoreError> {
     worktree_core::unique_worktree_path_for_rename(base_dir, name, current_path)
 }
 
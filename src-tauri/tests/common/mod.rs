@@ -0,0 +1,362 @@
+//! Daemon and client harness shared by the integration tests. The daemon runs
+//! with `codex_monitor_mock_app_server` standing in for `codex app-server`.
+
+#![allow(dead_code)]
+
+use std::collections::VecDeque;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+
+pub const TOKEN: &str = "integration-token";
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(20);
+pub const READ_TIMEOUT: Duration = Duration::from_secs(20);
+
+fn temp_dir(prefix: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "codex-monitor-{prefix}-{}-{}",
+        std::process::id(),
+        uuid::Uuid::new_v4()
+    ));
+    fs::create_dir_all(&dir).expect("create temp dir");
+    dir
+}
+
+fn free_local_addr() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind ephemeral port");
+    listener.local_addr().expect("local addr").to_string()
+}
+
+/// Daemon process backed by the mock app-server; killed on drop.
+pub struct TestDaemon {
+    child: Child,
+    addr: String,
+    root: PathBuf,
+    workspace_path: PathBuf,
+}
+
+impl TestDaemon {
+    /// The mock answers with `mock_script`, or its default replies.
+    pub fn start(mock_script: Option<Value>) -> Self {
+        Self::spawn(|command, root| {
+            if let Some(script) = mock_script {
+                let script_path = root.join("mock-script.json");
+                fs::write(&script_path, script.to_string()).expect("write mock script");
+                command.env("CODEX_MONITOR_MOCK_SCRIPT", script_path);
+            }
+        })
+    }
+
+    /// The mock plays back `tests/fixtures/<fixture>`.
+    pub fn replaying(fixture: &str) -> Self {
+        let recording = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(fixture);
+        assert!(
+            recording.is_file(),
+            "missing fixture {}",
+            recording.display()
+        );
+        Self::spawn(|command, _| {
+            command.env("CODEX_MONITOR_REPLAY_FILE", recording);
+        })
+    }
+
+    fn spawn(configure_mock: impl FnOnce(&mut Command, &Path)) -> Self {
+        let root = temp_dir("daemon-it");
+        let data_dir = root.join("data");
+        let codex_home = root.join("codex-home");
+        let workspace_path = root.join("workspace");
+        for dir in [&data_dir, &codex_home, &workspace_path] {
+            fs::create_dir_all(dir).expect("create dir");
+        }
+        fs::write(
+            data_dir.join("settings.json"),
+            json!({ "codexBin": env!("CARGO_BIN_EXE_codex_monitor_mock_app_server") }).to_string(),
+        )
+        .expect("write settings");
+
+        let addr = free_local_addr();
+        let mut command = Command::new(env!("CARGO_BIN_EXE_codex_monitor_daemon"));
+        command
+            .args(["--listen", &addr, "--token", TOKEN, "--data-dir"])
+            .arg(&data_dir)
+            .env("CODEX_HOME", &codex_home)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        configure_mock(&mut command, &root);
+        let child = command.spawn().expect("spawn daemon");
+        Self {
+            child,
+            addr,
+            root,
+            workspace_path,
+        }
+    }
+
+    pub fn connect(&self) -> TestClient {
+        let deadline = Instant::now() + STARTUP_TIMEOUT;
+        loop {
+            match TcpStream::connect(&self.addr) {
+                Ok(stream) => return TestClient::new(stream),
+                Err(_) if Instant::now() < deadline => {
+                    thread::sleep(Duration::from_millis(50));
+                }
+                Err(err) => panic!("daemon never listened on {}: {err}", self.addr),
+            }
+        }
+    }
+
+    pub fn connect_authenticated(&self) -> TestClient {
+        let mut client = self.connect();
+        let auth = client.call("auth", json!({ "token": TOKEN }));
+        assert_eq!(auth["result"]["ok"], json!(true), "auth failed: {auth}");
+        client
+    }
+
+    pub fn workspace_path(&self) -> &Path {
+        &self.workspace_path
+    }
+
+    pub fn data_dir(&self) -> PathBuf {
+        self.root.join("data")
+    }
+
+    /// Another empty directory to add as a workspace.
+    pub fn extra_workspace_path(&self, name: &str) -> PathBuf {
+        let path = self.root.join(name);
+        fs::create_dir_all(&path).expect("create workspace dir");
+        path
+    }
+}
+
+impl Drop for TestDaemon {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+pub struct TestClient {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    next_id: u64,
+    notifications: VecDeque<Value>,
+}
+
+impl TestClient {
+    fn new(stream: TcpStream) -> Self {
+        stream
+            .set_read_timeout(Some(READ_TIMEOUT))
+            .expect("set read timeout");
+        let writer = stream.try_clone().expect("clone stream");
+        Self {
+            reader: BufReader::new(stream),
+            writer,
+            next_id: 0,
+            notifications: VecDeque::new(),
+        }
+    }
+
+    fn read_message(&mut self) -> Value {
+        loop {
+            let mut line = String::new();
+            let read = self.reader.read_line(&mut line).expect("read from daemon");
+            assert!(read > 0, "daemon closed the connection");
+            if !line.trim().is_empty() {
+                return serde_json::from_str(&line).expect("daemon sent json");
+            }
+        }
+    }
+
+    /// Returns the whole response so callers can assert on `error` too.
+    pub fn call(&mut self, method: &str, params: Value) -> Value {
+        self.next_id += 1;
+        let id = self.next_id;
+        let line = json!({ "id": id, "method": method, "params": params }).to_string();
+        writeln!(self.writer, "{line}").expect("write to daemon");
+        loop {
+            let message = self.read_message();
+            if message.get("method").is_some() {
+                self.notifications.push_back(message);
+                continue;
+            }
+            if message.get("id").and_then(Value::as_u64) == Some(id) {
+                return message;
+            }
+        }
+    }
+
+    pub fn result(&mut self, method: &str, params: Value) -> Value {
+        let response = self.call(method, params);
+        assert!(
+            response.get("error").is_none(),
+            "`{method}` failed: {response}"
+        );
+        response["result"].clone()
+    }
+
+    /// App-server messages for `workspace_id` until (and including) the first
+    /// `method` message.
+    pub fn app_server_messages_until(&mut self, workspace_id: &str, method: &str) -> Vec<Value> {
+        self.app_server_events_until(|routed_to, message| {
+            routed_to == workspace_id && message["method"] == method
+        })
+        .into_iter()
+        .filter(|(routed_to, _)| routed_to == workspace_id)
+        .map(|(_, message)| message)
+        .collect()
+    }
+
+    /// `(workspace_id, message)` of every app-server event, across
+    /// workspaces, until (and including) the first one `done` accepts.
+    pub fn app_server_events_until(
+        &mut self,
+        done: impl Fn(&str, &Value) -> bool,
+    ) -> Vec<(String, Value)> {
+        let mut events = Vec::new();
+        loop {
+            let notification = match self.notifications.pop_front() {
+                Some(notification) => notification,
+                None => self.read_message(),
+            };
+            if notification["method"] != "app-server-event" {
+                continue;
+            }
+            let workspace_id = notification["params"]["workspace_id"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string();
+            let message = notification["params"]["message"].clone();
+            let finished = done(&workspace_id, &message);
+            events.push((workspace_id, message));
+            if finished {
+                return events;
+            }
+        }
+    }
+}
+
+/// A request received by [`start_http_stand_in`]. Header names are lowercase.
+pub struct CapturedRequest {
+    pub path: String,
+    headers: Vec<(String, String)>,
+    pub raw_body: Vec<u8>,
+    pub body: Value,
+}
+
+impl CapturedRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Answers every request with `200 OK` and hands it to the test. Returns the
+/// base URL, without a trailing slash.
+pub fn start_http_stand_in() -> (String, mpsc::Receiver<CapturedRequest>) {
+    start_flaky_http_stand_in(0)
+}
+
+/// Like [`start_http_stand_in`], but answers the first `failures` requests
+/// with `503 Service Unavailable`.
+pub fn start_flaky_http_stand_in(failures: usize) -> (String, mpsc::Receiver<CapturedRequest>) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind stand-in");
+    let url = format!("http://{}", listener.local_addr().expect("stand-in addr"));
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for (index, stream) in listener.incoming().enumerate() {
+            let Ok(stream) = stream else {
+                continue;
+            };
+            let status = if index < failures {
+                "503 Service Unavailable"
+            } else {
+                "200 OK"
+            };
+            let Some(request) = read_http_request(stream, status) else {
+                continue;
+            };
+            if tx.send(request).is_err() {
+                return;
+            }
+        }
+    });
+    (url, rx)
+}
+
+pub fn start_push_relay() -> (String, mpsc::Receiver<Value>) {
+    let (base_url, requests) = start_http_stand_in();
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for request in requests {
+            if tx.send(request.body).is_err() {
+                return;
+            }
+        }
+    });
+    (format!("{base_url}/push"), rx)
+}
+
+fn read_http_request(stream: TcpStream, status: &str) -> Option<CapturedRequest> {
+    let mut reader = BufReader::new(stream.try_clone().ok()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).ok()?;
+    let path = request_line.split_whitespace().nth(1)?.to_string();
+    let mut headers = Vec::new();
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).ok()?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            let name = name.trim().to_ascii_lowercase();
+            let value = value.trim().to_string();
+            if name == "content-length" {
+                content_length = value.parse().ok()?;
+            }
+            headers.push((name, value));
+        }
+    }
+    let mut raw_body = vec![0; content_length];
+    reader.read_exact(&mut raw_body).ok()?;
+    let mut stream = stream;
+    let response = format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+    let _ = stream.write_all(response.as_bytes());
+    let body = serde_json::from_slice(&raw_body).ok()?;
+    Some(CapturedRequest {
+        path,
+        headers,
+        raw_body,
+        body,
+    })
+}
+
+pub fn add_and_connect_workspace(client: &mut TestClient, path: &Path) -> String {
+    let workspace = client.result("add_workspace", json!({ "path": path }));
+    let workspace_id = workspace["id"].as_str().expect("workspace id").to_string();
+    client.result("connect_workspace", json!({ "id": workspace_id }));
+    workspace_id
+}
+
+pub fn start_thread(client: &mut TestClient, workspace_id: &str) -> String {
+    let started = client.result("start_thread", json!({ "workspaceId": workspace_id }));
+    started["result"]["thread"]["id"]
+        .as_str()
+        .expect("thread id")
+        .to_string()
+}
@@ -0,0 +1,397 @@
+//! Drives `codex_monitor_daemon` over TCP with `codex_monitor_mock_app_server`
+//! standing in for `codex app-server`.
+
+mod common;
+
+use std::fs;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use common::*;
+use hmac::{Hmac, Mac};
+use serde_json::{json, Value};
+use sha2::Sha256;
+
+#[test]
+fn daemon_requires_auth_before_other_methods() {
+    let daemon = TestDaemon::start(None);
+    let mut client = daemon.connect();
+
+    let unauthenticated = client.call("list_workspaces", json!({}));
+    assert_eq!(unauthenticated["error"]["message"], "unauthorized");
+    assert_eq!(unauthenticated["error"]["code"], json!(-32001));
+    let rejected = client.call("auth", json!({ "token": "wrong" }));
+    assert_eq!(rejected["error"]["message"], "invalid token");
+    assert_eq!(rejected["error"]["code"], json!(-32002));
+    let accepted = client.call("auth", json!({ "token": TOKEN }));
+    assert_eq!(accepted["result"]["role"], "admin");
+    assert_eq!(client.result("list_workspaces", json!({})), json!([]));
+}
+
+#[test]
+fn daemon_streams_a_mock_turn_and_pushes_its_completion() {
+    let daemon = TestDaemon::start(None);
+    let (relay_url, pushes) = start_push_relay();
+    let mut client = daemon.connect_authenticated();
+    client.result(
+        "push_notification_config_patch",
+        json!({ "relayUrl": relay_url }),
+    );
+    client.result(
+        "push_register_device",
+        json!({ "deviceId": "phone-1", "platform": "ios", "token": "apns-token" }),
+    );
+
+    let workspace_id = add_and_connect_workspace(&mut client, daemon.workspace_path());
+    let workspaces = client.result("list_workspaces", json!({}));
+    assert_eq!(workspaces[0]["connected"], json!(true));
+    let thread_id = start_thread(&mut client, &workspace_id);
+    let listed = client.result(
+        "list_threads",
+        json!({ "workspaceId": workspace_id, "cursor": null, "limit": 10 }),
+    );
+    assert_eq!(listed["result"]["data"][0]["id"], json!(thread_id));
+
+    client.result(
+        "send_user_message",
+        json!({ "workspaceId": workspace_id, "threadId": thread_id, "text": "hello" }),
+    );
+    let messages = client.app_server_messages_until(&workspace_id, "turn/completed");
+
+    let reply: String = messages
+        .iter()
+        .filter(|message| message["method"] == "item/agentMessage/delta")
+        .filter_map(|message| message["params"]["delta"].as_str())
+        .collect();
+    assert_eq!(reply, "Hello from the mock app-server.");
+    assert!(messages
+        .iter()
+        .all(|message| message["params"]["threadId"].is_null()
+            || message["params"]["threadId"] == json!(thread_id)));
+
+    let push = pushes
+        .recv_timeout(READ_TIMEOUT)
+        .expect("relay received a push");
+    assert_eq!(push["kind"], "turn.completed");
+    assert_eq!(push["workspaceId"], json!(workspace_id));
+    assert_eq!(push["threadId"], json!(thread_id));
+    assert_eq!(push["preview"], "Hello from the mock app-server.");
+    assert_eq!(push["devices"][0]["token"], "apns-token");
+}
+
+#[test]
+fn daemon_pushes_scripted_turn_errors() {
+    let daemon = TestDaemon::start(Some(json!({ "turnError": "quota exceeded" })));
+    let (relay_url, pushes) = start_push_relay();
+    let mut client = daemon.connect_authenticated();
+    client.result(
+        "push_notification_config_patch",
+        json!({ "relayUrl": relay_url }),
+    );
+    client.result(
+        "push_register_device",
+        json!({ "deviceId": "phone-1", "platform": "android", "token": "fcm-token" }),
+    );
+    let workspace_id = add_and_connect_workspace(&mut client, daemon.workspace_path());
+    let thread_id = start_thread(&mut client, &workspace_id);
+
+    client.result(
+        "send_user_message",
+        json!({ "workspaceId": workspace_id, "threadId": thread_id, "text": "hello" }),
+    );
+    let messages = client.app_server_messages_until(&workspace_id, "turn/completed");
+
+    assert!(messages.iter().any(|message| message["method"] == "error"
+        && message["params"]["error"]["message"] == "quota exceeded"));
+    let push = pushes
+        .recv_timeout(READ_TIMEOUT)
+        .expect("relay received a push");
+    assert_eq!(push["kind"], "turn.error");
+    assert_eq!(push["preview"], "quota exceeded");
+}
+
+#[test]
+fn daemon_pushes_approval_requests_that_can_be_answered_from_the_payload() {
+    let daemon = TestDaemon::start(Some(json!({ "approvalCommand": "cargo test" })));
+    let (relay_url, pushes) = start_push_relay();
+    let mut client = daemon.connect_authenticated();
+    client.result(
+        "push_notification_config_patch",
+        json!({ "relayUrl": relay_url }),
+    );
+    client.result(
+        "push_register_device",
+        json!({ "deviceId": "phone-1", "platform": "android", "token": "fcm-token" }),
+    );
+    let workspace_id = add_and_connect_workspace(&mut client, daemon.workspace_path());
+    let thread_id = start_thread(&mut client, &workspace_id);
+
+    client.result(
+        "send_user_message",
+        json!({ "workspaceId": workspace_id, "threadId": thread_id, "text": "run the tests" }),
+    );
+    client.app_server_messages_until(&workspace_id, "item/commandExecution/requestApproval");
+
+    let push = pushes
+        .recv_timeout(READ_TIMEOUT)
+        .expect("relay received a push");
+    assert_eq!(push["kind"], "turn.approvalRequired");
+    assert_eq!(push["threadId"], json!(thread_id));
+    assert_eq!(push["preview"], "Run `cargo test`");
+    client.result(
+        "respond_to_server_request",
+        json!({
+            "workspaceId": push["workspaceId"],
+            "requestId": push["requestId"],
+            "result": { "decision": "accept" },
+        }),
+    );
+    let messages = client.app_server_messages_until(&workspace_id, "turn/completed");
+
+    assert!(messages
+        .iter()
+        .any(|message| message["method"] == "item/completed"
+            && message["params"]["item"]["status"] == "completed"));
+    let push = pushes
+        .recv_timeout(READ_TIMEOUT)
+        .expect("relay received a push");
+    assert_eq!(push["kind"], "turn.completed");
+}
+
+#[test]
+fn daemon_delivers_to_webhook_ntfy_and_gotify_without_registered_devices() {
+    let daemon = TestDaemon::start(None);
+    let (base_url, requests) = start_http_stand_in();
+    let mut client = daemon.connect_authenticated();
+    let config = client.result(
+        "push_notification_config_patch",
+        json!({
+            "webhook": { "url": format!("{base_url}/hook"), "secret": "hook-secret" },
+            "ntfy": { "url": base_url, "topic": "codex", "token": "ntfy-token" },
+            "gotify": { "url": base_url, "token": "gotify-token" },
+        }),
+    );
+    assert_eq!(
+        config["channels"],
+        json!({
+            "webhook": { "url": format!("{base_url}/hook"), "hasSecret": true },
+            "ntfy": { "url": base_url, "topic": "codex", "hasToken": true },
+            "gotify": { "url": base_url },
+        })
+    );
+    let workspace_id = add_and_connect_workspace(&mut client, daemon.workspace_path());
+    let thread_id = start_thread(&mut client, &workspace_id);
+
+    client.result(
+        "send_user_message",
+        json!({ "workspaceId": workspace_id, "threadId": thread_id, "text": "hello" }),
+    );
+    client.app_server_messages_until(&workspace_id, "turn/completed");
+
+    let mut received = (0..3)
+        .map(|_| {
+            requests
+                .recv_timeout(READ_TIMEOUT)
+                .expect("channel received a push")
+        })
+        .collect::<Vec<_>>();
+    received.sort_by(|left, right| left.path.cmp(&right.path));
+    let [ntfy, webhook, gotify] = <[CapturedRequest; 3]>::try_from(received)
+        .unwrap_or_else(|_| unreachable!("three requests"));
+
+    assert_eq!(webhook.path, "/hook");
+    assert_eq!(
+        webhook.header("x-codexmonitor-event"),
+        Some("turn.completed")
+    );
+    let mut mac = Hmac::<Sha256>::new_from_slice(b"hook-secret").expect("hmac key");
+    mac.update(&webhook.raw_body);
+    let signature = webhook
+        .header("x-codexmonitor-signature")
+        .and_then(|value| value.strip_prefix("sha256="))
+        .expect("signature header");
+    let expected = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    assert_eq!(signature, expected);
+    assert_eq!(webhook.body["kind"], "turn.completed");
+    assert_eq!(webhook.body["workspaceId"], json!(workspace_id));
+    assert_eq!(webhook.body["threadId"], json!(thread_id));
+    assert_eq!(webhook.body["preview"], "Hello from the mock app-server.");
+
+    assert_eq!(ntfy.path, "/");
+    assert_eq!(ntfy.header("authorization"), Some("Bearer ntfy-token"));
+    assert_eq!(ntfy.body["topic"], "codex");
+    assert_eq!(ntfy.body["message"], "Hello from the mock app-server.");
+    assert_eq!(ntfy.body["priority"], json!(3));
+
+    assert_eq!(gotify.path, "/message");
+    assert_eq!(gotify.header("x-gotify-key"), Some("gotify-token"));
+    assert_eq!(gotify.body["message"], "Hello from the mock app-server.");
+    assert_eq!(
+        gotify.body["extras"]["codexmonitor::event"]["threadId"],
+        json!(thread_id)
+    );
+
+    let config = client.result(
+        "push_notification_config_patch",
+        json!({ "webhook": { "url": "" }, "ntfy": { "topic": "" } }),
+    );
+    assert_eq!(config["channels"]["webhook"], Value::Null);
+    assert_eq!(config["channels"]["ntfy"], Value::Null);
+    assert_eq!(config["channels"]["gotify"]["url"], json!(base_url));
+}
+
+#[test]
+fn daemon_applies_per_device_push_rules() {
+    let daemon = TestDaemon::start(None);
+    let (relay_url, pushes) = start_push_relay();
+    let mut client = daemon.connect_authenticated();
+    client.result(
+        "push_notification_config_patch",
+        json!({ "relayUrl": relay_url }),
+    );
+    for (device_id, token) in [("phone-1", "token-1"), ("phone-2", "token-2")] {
+        client.result(
+            "push_register_device",
+            json!({ "deviceId": device_id, "platform": "android", "token": token }),
+        );
+    }
+    let rules = client.result(
+        "push_device_rules_patch",
+        json!({ "deviceId": "phone-1", "rules": { "kinds": { "completed": false } } }),
+    );
+    assert_eq!(
+        rules["kinds"],
+        json!({ "completed": false, "error": true, "approval": true })
+    );
+    client.result(
+        "push_register_device",
+        json!({ "deviceId": "phone-1", "platform": "android", "token": "token-1b" }),
+    );
+    let rules = client.result("push_device_rules_get", json!({ "deviceId": "phone-1" }));
+    assert_eq!(rules["kinds"]["completed"], json!(false));
+    let unknown = client.call("push_device_rules_get", json!({ "deviceId": "tablet" }));
+    assert_eq!(unknown["error"]["message"], "unknown device `tablet`");
+
+    let workspace_id = add_and_connect_workspace(&mut client, daemon.workspace_path());
+    let thread_id = start_thread(&mut client, &workspace_id);
+    client.result(
+        "send_user_message",
+        json!({ "workspaceId": workspace_id, "threadId": thread_id, "text": "hello" }),
+    );
+    client.app_server_messages_until(&workspace_id, "turn/completed");
+
+    let push = pushes
+        .recv_timeout(READ_TIMEOUT)
+        .expect("relay received a push");
+    assert_eq!(push["kind"], "turn.completed");
+    assert_eq!(
+        push["devices"],
+        json!([{ "deviceId": "phone-2", "platform": "android", "token": "token-2", "label": null }])
+    );
+}
+
+#[test]
+fn daemon_queues_relay_pushes_and_retries_them_with_backoff() {
+    let daemon = TestDaemon::start(None);
+    let (base_url, requests) = start_flaky_http_stand_in(1);
+    let mut client = daemon.connect_authenticated();
+    client.result(
+        "push_notification_config_patch",
+        json!({ "relayUrl": format!("{base_url}/push") }),
+    );
+    client.result(
+        "push_register_device",
+        json!({ "deviceId": "phone-1", "platform": "android", "token": "fcm-token" }),
+    );
+    let workspace_id = add_and_connect_workspace(&mut client, daemon.workspace_path());
+    let thread_id = start_thread(&mut client, &workspace_id);
+
+    client.result(
+        "send_user_message",
+        json!({ "workspaceId": workspace_id, "threadId": thread_id, "text": "hello" }),
+    );
+    client.app_server_messages_until(&workspace_id, "turn/completed");
+
+    let rejected = requests
+        .recv_timeout(READ_TIMEOUT)
+        .expect("relay received a push");
+    let retried = requests
+        .recv_timeout(READ_TIMEOUT)
+        .expect("relay received the retry");
+    assert_eq!(retried.body, rejected.body);
+    assert_eq!(retried.body["kind"], "turn.completed");
+
+    let deadline = Instant::now() + READ_TIMEOUT;
+    let device = loop {
+        let state = client.result("push_notification_state", json!({}));
+        let device = state["devices"][0].clone();
+        if device["lastDeliveredAtMs"].is_i64() || Instant::now() > deadline {
+            break device;
+        }
+        thread::sleep(Duration::from_millis(50));
+    };
+    assert!(device["lastDeliveredAtMs"].is_i64());
+    assert!(device["lastFailedAtMs"].is_i64());
+    assert_eq!(device["lastError"], "relay status 503");
+    let outbox = fs::read_to_string(daemon.data_dir().join("push_outbox.json")).expect("outbox");
+    assert_eq!(
+        serde_json::from_str::<Value>(&outbox).expect("json"),
+        json!([])
+    );
+}
+
+#[test]
+fn daemon_batches_completions_into_one_digest_push() {
+    let daemon = TestDaemon::start(None);
+    let (relay_url, pushes) = start_push_relay();
+    let mut client = daemon.connect_authenticated();
+    let too_long = client.call(
+        "push_notification_config_patch",
+        json!({ "digestWindowMs": 3_600_001 }),
+    );
+    assert_eq!(
+        too_long["error"]["message"],
+        "`digestWindowMs` must be at most 3600000"
+    );
+    let config = client.result(
+        "push_notification_config_patch",
+        json!({ "relayUrl": relay_url, "digestWindowMs": 1_000 }),
+    );
+    assert_eq!(config["digestWindowMs"], 1_000);
+    client.result(
+        "push_register_device",
+        json!({ "deviceId": "phone-1", "platform": "android", "token": "fcm-token" }),
+    );
+    let workspace_id = add_and_connect_workspace(&mut client, daemon.workspace_path());
+    let mut last_thread_id = String::new();
+    for _ in 0..2 {
+        last_thread_id = start_thread(&mut client, &workspace_id);
+        client.result(
+            "send_user_message",
+            json!({ "workspaceId": workspace_id, "threadId": last_thread_id, "text": "hello" }),
+        );
+        client.app_server_messages_until(&workspace_id, "turn/completed");
+    }
+
+    let push = pushes
+        .recv_timeout(READ_TIMEOUT)
+        .expect("relay received the digest");
+    let workspace_name = daemon
+        .workspace_path()
+        .file_name()
+        .and_then(|name| name.to_str())
+        .expect("workspace dir name");
+    assert_eq!(push["kind"], "turn.digest");
+    assert_eq!(push["title"], format!("Agent Digest — {workspace_name}"));
+    assert_eq!(
+        push["body"],
+        format!("2 agents finished in {workspace_name}")
+    );
+    assert_eq!(push["threadId"], last_thread_id.as_str());
+    assert!(pushes.recv_timeout(Duration::from_millis(1_500)).is_err());
+}
@@ -0,0 +1,158 @@
+//! Drives `codex_monitor_daemon` against app-server traffic recorded in
+//! `tests/fixtures`, played back by `codex_monitor_mock_app_server
+//! --recording`. Each recording must match what the daemon sends, in order;
+//! a divergence kills the app-server and the test times out waiting.
+
+mod common;
+
+use common::*;
+use serde_json::{json, Value};
+
+const ROUTING_PARENT_THREAD: &str = "019a3f2e-7c41-7d30-9e8b-2f6a1c0d4b11";
+const ROUTING_SUBAGENT_THREAD: &str = "019a3f2f-0a12-7b55-8c3d-6e9f2a7b1c22";
+const ROUTING_STRAY_THREAD: &str = "019a3e90-44d1-7f02-a1b3-5c8d9e0f6a33";
+
+fn thread_id_of(message: &Value) -> Option<&str> {
+    message["params"]["threadId"]
+        .as_str()
+        .or_else(|| message["params"]["thread"]["id"].as_str())
+}
+
+fn relay_with_device(client: &mut TestClient) -> std::sync::mpsc::Receiver<Value> {
+    let (relay_url, pushes) = start_push_relay();
+    client.result(
+        "push_notification_config_patch",
+        json!({ "relayUrl": relay_url }),
+    );
+    client.result(
+        "push_register_device",
+        json!({ "deviceId": "phone-1", "platform": "ios", "token": "apns-token" }),
+    );
+    pushes
+}
+
+#[test]
+fn replayed_subagent_threads_route_to_the_parent_workspace() {
+    let daemon = TestDaemon::replaying("routing_subagent_thread.jsonl");
+    let mut client = daemon.connect_authenticated();
+    let pushes = relay_with_device(&mut client);
+    let first_workspace = add_and_connect_workspace(&mut client, daemon.workspace_path());
+    let second_path = daemon.extra_workspace_path("second-workspace");
+    let second_workspace = add_and_connect_workspace(&mut client, &second_path);
+
+    let thread_id = start_thread(&mut client, &second_workspace);
+    assert_eq!(thread_id, ROUTING_PARENT_THREAD);
+    client.result(
+        "send_user_message",
+        json!({ "workspaceId": second_workspace, "threadId": thread_id, "text": "Fix the failing tests" }),
+    );
+    let events = client.app_server_events_until(|_, message| {
+        message["method"] == "turn/completed"
+            && thread_id_of(message) == Some(ROUTING_PARENT_THREAD)
+    });
+
+    let thread_events: Vec<&(String, Value)> = events
+        .iter()
+        .filter(|(_, message)| thread_id_of(message).is_some())
+        .collect();
+    assert!(
+        thread_events
+            .iter()
+            .all(|(workspace_id, _)| *workspace_id == second_workspace),
+        "thread events belong to the second workspace, not {first_workspace}: {thread_events:?}"
+    );
+    let subagent_reply: String = events
+        .iter()
+        .map(|(_, message)| message)
+        .filter(|message| message["method"] == "item/agentMessage/delta")
+        .filter(|message| thread_id_of(message) == Some(ROUTING_SUBAGENT_THREAD))
+        .filter_map(|message| message["params"]["delta"].as_str())
+        .collect();
+    assert_eq!(subagent_reply, "Found 3 failing tests in auth/.");
+    assert!(events
+        .iter()
+        .all(|(_, message)| thread_id_of(message) != Some(ROUTING_STRAY_THREAD)));
+
+    let push = loop {
+        let push = pushes
+            .recv_timeout(READ_TIMEOUT)
+            .expect("relay received a push");
+        assert_eq!(push["workspaceId"], json!(second_workspace));
+        if push["threadId"] == ROUTING_PARENT_THREAD {
+            break push;
+        }
+    };
+    assert_eq!(push["kind"], "turn.completed");
+    assert_eq!(push["preview"], "Fixed the three failing auth tests.");
+}
+
+#[test]
+fn replayed_websocket_drop_retries_turn_start_once() {
+    let daemon = TestDaemon::replaying("turn_start_websocket_retry.jsonl");
+    let mut client = daemon.connect_authenticated();
+    let pushes = relay_with_device(&mut client);
+    let workspace_id = add_and_connect_workspace(&mut client, daemon.workspace_path());
+    let thread_id = start_thread(&mut client, &workspace_id);
+
+    client.result(
+        "send_user_message",
+        json!({ "workspaceId": workspace_id, "threadId": thread_id, "text": "Bump the lockfile" }),
+    );
+    let messages = client.app_server_messages_until(&workspace_id, "turn/completed");
+
+    let errors: Vec<&Value> = messages
+        .iter()
+        .filter(|message| message["method"] == "error")
+        .collect();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0]["params"]["willRetry"], json!(true));
+    let completed = messages.last().expect("turn/completed");
+    assert_eq!(
+        completed["params"]["turn"]["id"],
+        "019a4012-6a81-7cb2-ad03-e4f5a6b7c806"
+    );
+    assert_eq!(completed["params"]["turn"]["status"], "completed");
+
+    let push = pushes
+        .recv_timeout(READ_TIMEOUT)
+        .expect("relay received a push");
+    assert_eq!(push["kind"], "turn.completed");
+    assert_eq!(push["threadId"], json!(thread_id));
+    assert_eq!(push["preview"], "Updated Cargo.lock and reran the build.");
+}
+
+#[test]
+fn replayed_usage_limit_is_not_retried_and_pushes_the_final_error() {
+    let daemon = TestDaemon::replaying("turn_error_usage_limit.jsonl");
+    let mut client = daemon.connect_authenticated();
+    let pushes = relay_with_device(&mut client);
+    let workspace_id = add_and_connect_workspace(&mut client, daemon.workspace_path());
+    let thread_id = start_thread(&mut client, &workspace_id);
+
+    client.result(
+        "send_user_message",
+        json!({ "workspaceId": workspace_id, "threadId": thread_id, "text": "Proofread the guide" }),
+    );
+    let messages = client.app_server_messages_until(&workspace_id, "turn/completed");
+
+    let will_retry: Vec<&Value> = messages
+        .iter()
+        .filter(|message| message["method"] == "error")
+        .map(|message| &message["params"]["willRetry"])
+        .collect();
+    assert_eq!(will_retry, [&json!(true), &json!(false)]);
+    assert_eq!(
+        messages.last().expect("turn/completed")["params"]["turn"]["status"],
+        "failed"
+    );
+
+    let push = pushes
+        .recv_timeout(READ_TIMEOUT)
+        .expect("relay received a push");
+    assert_eq!(push["kind"], "turn.error");
+    assert_eq!(push["threadId"], json!(thread_id));
+    assert_eq!(
+        push["preview"],
+        "You've hit your usage limit. Try again in 2 hours."
+    );
+}
@@ -108,6 +108,7 @@ import { useGitCommitController } from "@app/hooks/useGitCommitController";
 import { effectiveCommitMessageModelId } from "@/features/git/utils/commitMessageModelSelection";
 import { WorkspaceHome } from "@/features/workspaces/components/WorkspaceHome";
 import { MobileServerSetupWizard } from "@/features/mobile/components/MobileServerSetupWizard";
+import { useMobileEventSubscription } from "@/features/mobile/hooks/useMobileEventSubscription";
 import { useMobileServerSetup } from "@/features/mobile/hooks/useMobileServerSetup";
 import { useWorkspaceHome } from "@/features/workspaces/hooks/useWorkspaceHome";
 import { useWorkspaceAgentMd } from "@/features/workspaces/hooks/useWorkspaceAgentMd";
@@ -237,6 +238,7 @@ function MainApp() {
     addWorktreeAgent,
     connectWorkspace,
     markWorkspaceConnected,
+    markWorkspaceSleeping,
     updateWorkspaceSettings,
     createWorkspaceGroup,
     renameWorkspaceGroup,
@@ -672,6 +674,7 @@ function MainApp() {
   } = useThreads({
     activeWorkspace,
     onWorkspaceConnected: markWorkspaceConnected,
+    onWorkspaceSleeping: markWorkspaceSleeping,
     onDebug: addDebugEntry,
     model: resolvedModel,
     effort: resolvedEffort,
@@ -973,6 +976,10 @@ function MainApp() {
     remoteBackendHost: appSettings.remoteBackendHost,
     remoteBackendToken: appSettings.remoteBackendToken,
   });
+  useMobileEventSubscription({
+    backendMode: appSettings.backendMode,
+    activeWorkspaceId,
+  });
 
   const {
     activeAccount,
@@ -101,14 +101,18 @@ export function WorkspaceCard({
         </div>
         {!workspace.connected && (
           <span
-            className="connect"
-            title="Connect workspace context to the shared Codex server"
+            className={`connect${workspace.sleeping ? " sleeping" : ""}`}
+            title={
+              workspace.sleeping
+                ? "Codex app-server was stopped while idle; it restarts on next use"
+                : "Connect workspace context to the shared Codex server"
+            }
             onClick={(event) => {
               event.stopPropagation();
               onConnectWorkspace(workspace);
             }}
           >
-            connect
+            {workspace.sleeping ? "asleep" : "connect"}
           </span>
         )}
       </div>
@@ -78,14 +78,18 @@ export function WorktreeCard({
               </button>
               {!worktree.connected && (
                 <span
-                  className="connect"
-                  title="Connect workspace context to the shared Codex server"
+                  className={`connect${worktree.sleeping ? " sleeping" : ""}`}
+                  title={
+                    worktree.sleeping
+                      ? "Codex app-server was stopped while idle; it restarts on next use"
+                      : "Connect workspace context to the shared Codex server"
+                  }
                   onClick={(event) => {
                     event.stopPropagation();
                     onConnectWorkspace(worktree);
                   }}
                 >
-                  connect
+                  {worktree.sleeping ? "asleep" : "connect"}
                 </span>
               )}
             </>
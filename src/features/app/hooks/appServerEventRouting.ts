@@ -28,6 +28,7 @@ export type AgentCompleted = {
 
 export type AppServerEventHandlers = {
   onWorkspaceConnected?: (workspaceId: string) => void;
+  onWorkspaceSleeping?: (workspaceId: string) => void;
   onThreadStarted?: (workspaceId: string, thread: Record<string, unknown>) => void;
   onThreadNameUpdated?: (
     workspaceId: string,
@@ -100,6 +101,7 @@ export const METHODS_ROUTED_IN_USE_APP_SERVER_EVENTS = [
   "account/updated",
   "codex/backgroundThread",
   "codex/connected",
+  "codex/sleeping",
   "error",
   "item/agentMessage/delta",
   "item/commandExecution/outputDelta",
@@ -218,6 +220,9 @@ const ROUTED_METHOD_HANDLERS = {
   "codex/connected": ({ workspaceId, handlers }) => {
     handlers.onWorkspaceConnected?.(workspaceId);
   },
+  "codex/sleeping": ({ workspaceId, handlers }) => {
+    handlers.onWorkspaceSleeping?.(workspaceId);
+  },
   "item/tool/requestUserInput": ({ workspaceId, requestId, params, handlers }) => {
     if (requestId === null) {
       return;
@@ -47,6 +47,7 @@ describe("useAppServerEvents", () => {
     const handlers: Handlers = {
       onAppServerEvent: vi.fn(),
       onWorkspaceConnected: vi.fn(),
+      onWorkspaceSleeping: vi.fn(),
       onThreadStarted: vi.fn(),
       onThreadNameUpdated: vi.fn(),
       onThreadStatusChanged: vi.fn(),
@@ -73,6 +74,17 @@ describe("useAppServerEvents", () => {
     });
     expect(handlers.onWorkspaceConnected).toHaveBeenCalledWith("ws-1");
 
+    act(() => {
+      listener?.({
+        workspace_id: "ws-1",
+        message: {
+          method: "codex/sleeping",
+          params: { workspaceId: "ws-1", reason: "idle", idleSecs: 900 },
+        },
+      });
+    });
+    expect(handlers.onWorkspaceSleeping).toHaveBeenCalledWith("ws-1");
+
     act(() => {
       listener?.({
         workspace_id: "ws-1",
@@ -0,0 +1,55 @@
+// @vitest-environment jsdom
+import { renderHook, waitFor } from "@testing-library/react";
+import { beforeEach, describe, expect, it, vi } from "vitest";
+import { useMobileEventSubscription } from "./useMobileEventSubscription";
+
+const subscribeEventsMock = vi.fn();
+
+let mobilePlatform = true;
+
+vi.mock("@/services/tauri", () => ({
+  subscribeEvents: (...args: unknown[]) => subscribeEventsMock(...args),
+}));
+
+vi.mock("@/utils/platformPaths", () => ({
+  isMobilePlatform: () => mobilePlatform,
+}));
+
+describe("useMobileEventSubscription", () => {
+  beforeEach(() => {
+    subscribeEventsMock.mockReset();
+    subscribeEventsMock.mockResolvedValue({});
+    mobilePlatform = true;
+  });
+
+  it("follows the active workspace in remote mode", async () => {
+    const { rerender } = renderHook(
+      ({ activeWorkspaceId }: { activeWorkspaceId: string | null }) =>
+        useMobileEventSubscription({ backendMode: "remote", activeWorkspaceId }),
+      { initialProps: { activeWorkspaceId: "ws-1" as string | null } },
+    );
+
+    await waitFor(() => {
+      expect(subscribeEventsMock).toHaveBeenCalledWith({ workspaceIds: ["ws-1"] });
+    });
+
+    rerender({ activeWorkspaceId: "ws-1" });
+    rerender({ activeWorkspaceId: null });
+    await waitFor(() => {
+      expect(subscribeEventsMock).toHaveBeenLastCalledWith({ workspaceIds: null });
+    });
+    expect(subscribeEventsMock).toHaveBeenCalledTimes(2);
+  });
+
+  it("leaves desktop and local mode unfiltered", () => {
+    renderHook(() =>
+      useMobileEventSubscription({ backendMode: "local", activeWorkspaceId: "ws-1" }),
+    );
+    mobilePlatform = false;
+    renderHook(() =>
+      useMobileEventSubscription({ backendMode: "remote", activeWorkspaceId: "ws-1" }),
+    );
+
+    expect(subscribeEventsMock).not.toHaveBeenCalled();
+  });
+});
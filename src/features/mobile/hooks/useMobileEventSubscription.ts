@@ -0,0 +1,38 @@
+import { useEffect, useRef } from "react";
+import { subscribeEvents } from "@/services/tauri";
+import { isMobilePlatform } from "@/utils/platformPaths";
+
+type Params = {
+  backendMode: "local" | "remote";
+  activeWorkspaceId: string | null;
+};
+
+/**
+ * On mobile, limits the daemon's event stream to the workspace on screen so
+ * other workspaces' deltas stay off the network. Their completions and
+ * approvals still arrive as push notifications.
+ */
+export function useMobileEventSubscription({
+  backendMode,
+  activeWorkspaceId,
+}: Params) {
+  const mobile = isMobilePlatform();
+  const lastSubscribedRef = useRef<string | null>(null);
+
+  useEffect(() => {
+    if (!mobile || backendMode !== "remote") {
+      lastSubscribedRef.current = null;
+      return;
+    }
+    const workspaceIds = activeWorkspaceId ? [activeWorkspaceId] : null;
+    const key = JSON.stringify(workspaceIds);
+    if (lastSubscribedRef.current === key) {
+      return;
+    }
+    lastSubscribedRef.current = key;
+    void subscribeEvents({ workspaceIds }).catch(() => {
+      // Best-effort; the next workspace change retries.
+      lastSubscribedRef.current = null;
+    });
+  }, [activeWorkspaceId, backendMode, mobile]);
+}
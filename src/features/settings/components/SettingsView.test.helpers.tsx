@@ -78,6 +78,7 @@ export const baseSettings: AppSettings = {
     },
   ],
   activeRemoteBackendId: "remote-default",
+  aggregateRemoteBackends: false,
   keepDaemonRunningAfterAppClose: false,
   defaultAccessMode: "current",
   reviewDeliveryMode: "inline",
@@ -154,6 +155,20 @@ export const baseSettings: AppSettings = {
     },
   ],
   selectedOpenAppId: "vscode",
+  requestTimeouts: {
+    defaultSecs: 300,
+    methods: {},
+    sendCancelNotifications: false,
+  },
+  sessionPool: {
+    maxLiveSessions: 0,
+    idleTimeoutSecs: 0,
+  },
+  logging: {
+    level: "info",
+    format: "text",
+    maxFiles: 5,
+  },
 };
 
 export const createDoctorResult = () => ({
@@ -323,6 +323,31 @@ export function SettingsServerSection({
           {remoteNameError && <div className="settings-help settings-help-error">{remoteNameError}</div>}
         </div>
 
+        {!isMobileSimplified && (
+          <div className="settings-toggle-row">
+            <div>
+              <div className="settings-toggle-title">Connect to all saved remotes</div>
+              <div className="settings-toggle-subtitle">
+                Shows workspaces from every saved remote in one sidebar. New workspaces are added
+                on the active remote.
+              </div>
+            </div>
+            <button
+              type="button"
+              className={`settings-toggle ${appSettings.aggregateRemoteBackends ? "on" : ""}`}
+              onClick={() =>
+                void onUpdateAppSettings({
+                  ...appSettings,
+                  aggregateRemoteBackends: !appSettings.aggregateRemoteBackends,
+                })
+              }
+              aria-pressed={appSettings.aggregateRemoteBackends}
+            >
+              <span className="settings-toggle-knob" />
+            </button>
+          </div>
+        )}
+
         {!isMobileSimplified && (
           <div className="settings-toggle-row">
             <div>
@@ -79,6 +79,8 @@ function normalizeRemoteBackends(settings: AppSettings): {
         typeof entry.lastConnectedAtMs === "number" && Number.isFinite(entry.lastConnectedAtMs)
           ? entry.lastConnectedAtMs
           : null,
+      tls: entry.tls === true,
+      tlsCertFingerprint: entry.tlsCertFingerprint?.trim() || null,
     };
   });
 
@@ -143,6 +145,7 @@ function buildDefaultSettings(): AppSettings {
     remoteBackendToken: null,
     remoteBackends: [defaultRemote],
     activeRemoteBackendId: defaultRemote.id,
+    aggregateRemoteBackends: false,
     keepDaemonRunningAfterAppClose: false,
     defaultAccessMode: "current",
     reviewDeliveryMode: "inline",
@@ -208,6 +211,20 @@ function buildDefaultSettings(): AppSettings {
     workspaceGroups: [],
     openAppTargets: DEFAULT_OPEN_APP_TARGETS,
     selectedOpenAppId: DEFAULT_OPEN_APP_ID,
+    requestTimeouts: {
+      defaultSecs: 300,
+      methods: {},
+      sendCancelNotifications: false,
+    },
+    sessionPool: {
+      maxLiveSessions: 0,
+      idleTimeoutSecs: 0,
+    },
+    logging: {
+      level: "info",
+      format: "text",
+      maxFiles: 5,
+    },
   };
 }
 
@@ -120,6 +120,14 @@ const validateRemoteHost = (value: string): string | null => {
   if (!trimmed) {
     return "Host is required.";
   }
+  if (/^wss?:\/\//i.test(trimmed)) {
+    try {
+      new URL(trimmed);
+      return null;
+    } catch {
+      return "Enter a valid WebSocket URL (for example `wss://proxy.example/codex-monitor`).";
+    }
+  }
   const match = trimmed.match(/^([^:\s]+|\[[^\]]+\]):([0-9]{1,5})$/);
   if (!match) {
     return "Use host:port (for example `macbook.tailnet.ts.net:4732`).";
@@ -204,6 +212,8 @@ export const useSettingsServerSection = ({
       typeof entry.lastConnectedAtMs === "number" && Number.isFinite(entry.lastConnectedAtMs)
         ? entry.lastConnectedAtMs
         : null,
+    tls: entry.tls === true,
+    tlsCertFingerprint: entry.tlsCertFingerprint?.trim() || null,
   });
 
   const buildSettingsFromRemoteBackends = useCallback(
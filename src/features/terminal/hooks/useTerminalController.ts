@@ -1,6 +1,6 @@
 import { useCallback, useEffect, useRef, useState } from "react";
 import type { DebugEntry, WorkspaceInfo } from "../../../types";
-import { closeTerminalSession } from "../../../services/tauri";
+import { closeTerminalSession, listTerminalSessions } from "../../../services/tauri";
 import { buildErrorDebugEntry } from "../../../utils/debugEntries";
 import { useTerminalSession } from "./useTerminalSession";
 import { useTerminalTabs } from "./useTerminalTabs";
@@ -52,6 +52,7 @@ export function useTerminalController({
     activeTerminalId,
     createTerminal,
     ensureTerminalWithTitle,
+    restoreTerminals,
     closeTerminal,
     setActiveTerminal,
     ensureTerminal,
@@ -59,12 +60,50 @@ export function useTerminalController({
     activeWorkspaceId,
     onCloseTerminal: handleTerminalClose,
   });
+  const [restoredWorkspaceIds, setRestoredWorkspaceIds] = useState<Set<string>>(
+    () => new Set(),
+  );
 
   useEffect(() => {
-    if (terminalOpen && activeWorkspaceId) {
+    if (!terminalOpen || !activeWorkspaceId) {
+      return;
+    }
+    if (restoredWorkspaceIds.has(activeWorkspaceId)) {
       ensureTerminal(activeWorkspaceId);
+      return;
     }
-  }, [activeWorkspaceId, ensureTerminal, terminalOpen]);
+    // Bring back tabs whose shell or scrollback outlived the last app run
+    // before falling back to a fresh terminal.
+    const workspaceId = activeWorkspaceId;
+    let cancelled = false;
+    listTerminalSessions(workspaceId)
+      .then((sessions) => {
+        if (!cancelled) {
+          restoreTerminals(
+            workspaceId,
+            sessions.map((session) => session.terminalId),
+          );
+        }
+      })
+      .catch((error) => {
+        onDebug(buildErrorDebugEntry("terminal list error", error));
+      })
+      .finally(() => {
+        if (!cancelled) {
+          setRestoredWorkspaceIds((prev) => new Set(prev).add(workspaceId));
+        }
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [
+    activeWorkspaceId,
+    ensureTerminal,
+    onDebug,
+    restoreTerminals,
+    restoredWorkspaceIds,
+    terminalOpen,
+  ]);
 
   const terminalState = useTerminalSession({
     activeWorkspace,
@@ -12,12 +12,15 @@ import {
   type TerminalOutputEvent,
 } from "../../../services/events";
 import {
+  attachTerminalSession,
   openTerminalSession,
   resizeTerminalSession,
   writeTerminalSession,
 } from "../../../services/tauri";
 
 const MAX_BUFFER_CHARS = 200_000;
+const PREVIOUS_SHELL_ENDED_NOTICE =
+  "\r\n\x1b[2m[previous shell has exited; output above was restored]\x1b[0m\r\n";
 
 type UseTerminalSessionOptions = {
   activeWorkspace: WorkspaceInfo | null;
@@ -333,15 +336,33 @@ export function useTerminalSession({
     const openSession = async () => {
       setStatus("connecting");
       setMessage("Starting terminal session...");
+      let restoredScrollback = false;
       if (!openedSessionsRef.current.has(key)) {
-        await openTerminalSession(activeWorkspace.id, activeTerminalId, cols, rows);
+        // Pick up a shell that outlived the app, or at least its output.
+        // Older daemons don't know terminal_attach, so fall back to opening.
+        const attached = await attachTerminalSession(
+          activeWorkspace.id,
+          activeTerminalId,
+        ).catch(() => null);
+        if (attached?.scrollback) {
+          // Local shells exit with the app, so restored output usually
+          // belongs to a shell that is gone; say so before the new prompt.
+          const scrollback = attached.running
+            ? attached.scrollback
+            : `${attached.scrollback}${PREVIOUS_SHELL_ENDED_NOTICE}`;
+          outputBuffersRef.current.set(key, appendBuffer(undefined, scrollback));
+          restoredScrollback = true;
+        }
+        if (!attached?.running) {
+          await openTerminalSession(activeWorkspace.id, activeTerminalId, cols, rows);
+        }
         openedSessionsRef.current.add(key);
       }
       setStatus("ready");
       setMessage("Terminal ready.");
       setHasSession(true);
       setReadyKey(key);
-      if (renderedKeyRef.current !== key) {
+      if (restoredScrollback || renderedKeyRef.current !== key) {
         syncActiveBuffer(key);
         renderedKeyRef.current = key;
       } else {
@@ -127,3 +127,24 @@ describe("useTerminalTabs auto-named tabs", () => {
     ]);
   });
 });
+
+describe("useTerminalTabs.restoreTerminals", () => {
+  it("adds restored tabs once and activates the first", () => {
+    const { result } = renderHook(() =>
+      useTerminalTabs({ activeWorkspaceId: "workspace-1" }),
+    );
+
+    act(() => {
+      result.current.restoreTerminals("workspace-1", ["term-a", "term-b"]);
+    });
+    act(() => {
+      result.current.restoreTerminals("workspace-1", ["term-b"]);
+    });
+
+    expect(result.current.terminals).toEqual([
+      { id: "term-a", title: "Terminal 1" },
+      { id: "term-b", title: "Terminal 2" },
+    ]);
+    expect(result.current.activeTerminalId).toBe("term-a");
+  });
+});
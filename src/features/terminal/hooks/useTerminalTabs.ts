@@ -105,6 +105,29 @@ export function useTerminalTabs({
     [],
   );
 
+  const restoreTerminals = useCallback((workspaceId: string, terminalIds: string[]) => {
+    if (terminalIds.length === 0) {
+      return;
+    }
+    setTabsByWorkspace((prev) => {
+      const existing = prev[workspaceId] ?? [];
+      const known = new Set(existing.map((tab) => tab.id));
+      const restored = terminalIds
+        .filter((id) => !known.has(id))
+        .map((id) => ({ id, title: "", autoNamed: true }));
+      if (restored.length === 0) {
+        return prev;
+      }
+      return {
+        ...prev,
+        [workspaceId]: renumberAutoNamedTabs([...existing, ...restored]),
+      };
+    });
+    setActiveTerminalIdByWorkspace((prev) =>
+      prev[workspaceId] ? prev : { ...prev, [workspaceId]: terminalIds[0] },
+    );
+  }, []);
+
   const closeTerminal = useCallback(
     (workspaceId: string, terminalId: string) => {
       setTabsByWorkspace((prev) => {
@@ -172,6 +195,7 @@ export function useTerminalTabs({
     activeTerminalId,
     createTerminal,
     ensureTerminalWithTitle,
+    restoreTerminals,
     closeTerminal,
     setActiveTerminal,
     ensureTerminal,
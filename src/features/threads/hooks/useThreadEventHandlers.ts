@@ -33,6 +33,7 @@ type ThreadEventHandlersOptions = {
   pushThreadErrorMessage: (threadId: string, message: string) => void;
   onDebug?: (entry: DebugEntry) => void;
   onWorkspaceConnected: (workspaceId: string) => void;
+  onWorkspaceSleeping?: (workspaceId: string) => void;
   applyCollabThreadLinks: (
     workspaceId: string,
     threadId: string,
@@ -60,6 +61,7 @@ export function useThreadEventHandlers({
   pushThreadErrorMessage,
   onDebug,
   onWorkspaceConnected,
+  onWorkspaceSleeping,
   applyCollabThreadLinks,
   onReviewExited,
   approvalAllowlistRef,
@@ -153,6 +155,7 @@ export function useThreadEventHandlers({
   const handlers = useMemo(
     () => ({
       onWorkspaceConnected,
+      onWorkspaceSleeping,
       onApprovalRequest,
       onRequestUserInput,
       onBackgroundThreadAction,
@@ -183,6 +186,7 @@ export function useThreadEventHandlers({
     }),
     [
       onWorkspaceConnected,
+      onWorkspaceSleeping,
       onApprovalRequest,
       onRequestUserInput,
       onBackgroundThreadAction,
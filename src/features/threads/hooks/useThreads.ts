@@ -37,6 +37,7 @@ import { getSubagentDescendantThreadIds } from "@threads/utils/subagentTree";
 type UseThreadsOptions = {
   activeWorkspace: WorkspaceInfo | null;
   onWorkspaceConnected: (id: string) => void;
+  onWorkspaceSleeping?: (id: string) => void;
   onDebug?: (entry: DebugEntry) => void;
   ensureWorkspaceRuntimeCodexArgs?: (
     workspaceId: string,
@@ -69,6 +70,7 @@ const CASCADE_ARCHIVE_SKIP_TTL_MS = 120_000;
 export function useThreads({
   activeWorkspace,
   onWorkspaceConnected,
+  onWorkspaceSleeping,
   onDebug,
   ensureWorkspaceRuntimeCodexArgs,
   model,
@@ -401,6 +403,7 @@ export function useThreads({
     pushThreadErrorMessage,
     onDebug,
     onWorkspaceConnected: handleWorkspaceConnected,
+    onWorkspaceSleeping,
     applyCollabThreadLinks,
     onReviewExited: handleReviewExited,
     approvalAllowlistRef,
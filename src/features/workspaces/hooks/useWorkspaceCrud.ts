@@ -49,7 +49,16 @@ export function useWorkspaceCrud({
   const refreshWorkspaces = useCallback(async () => {
     try {
       const entries = await listWorkspaces();
-      setWorkspaces(entries);
+      setWorkspaces((prev) => {
+        const sleepingIds = new Set(
+          prev.filter((entry) => entry.sleeping).map((entry) => entry.id),
+        );
+        return entries.map((entry) =>
+          !entry.connected && sleepingIds.has(entry.id)
+            ? { ...entry, sleeping: true }
+            : entry,
+        );
+      });
       setActiveWorkspaceId((prev) => {
         if (!prev) {
           return prev;
@@ -260,7 +269,7 @@ export function useWorkspaceCrud({
         setWorkspaces((prev) =>
           prev.map((workspace) =>
             workspace.id === entry.id
-              ? { ...workspace, connected: true }
+              ? { ...workspace, connected: true, sleeping: false }
               : workspace,
           ),
         );
@@ -281,7 +290,20 @@ export function useWorkspaceCrud({
   const markWorkspaceConnected = useCallback(
     (id: string) => {
       setWorkspaces((prev) =>
-        prev.map((entry) => (entry.id === id ? { ...entry, connected: true } : entry)),
+        prev.map((entry) =>
+          entry.id === id ? { ...entry, connected: true, sleeping: false } : entry,
+        ),
+      );
+    },
+    [setWorkspaces],
+  );
+
+  const markWorkspaceSleeping = useCallback(
+    (id: string) => {
+      setWorkspaces((prev) =>
+        prev.map((entry) =>
+          entry.id === id ? { ...entry, connected: false, sleeping: true } : entry,
+        ),
       );
     },
     [setWorkspaces],
@@ -390,6 +412,7 @@ export function useWorkspaceCrud({
     connectWorkspace,
     filterWorkspacePaths,
     markWorkspaceConnected,
+    markWorkspaceSleeping,
     refreshWorkspaces,
     removeWorkspace,
     updateWorkspaceSettings,
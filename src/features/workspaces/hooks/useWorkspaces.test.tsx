@@ -289,6 +289,57 @@ describe("useWorkspaces.connectWorkspace", () => {
   });
 });
 
+describe("useWorkspaces.markWorkspaceSleeping", () => {
+  it("marks the workspace asleep and wakes it when selected", async () => {
+    const listWorkspacesMock = vi.mocked(listWorkspaces);
+    const connectWorkspaceMock = vi.mocked(connectWorkspaceService);
+    listWorkspacesMock.mockResolvedValue([workspaceOne, workspaceTwo]);
+    connectWorkspaceMock.mockResolvedValue(undefined);
+
+    const { result } = renderHook(() => useWorkspaces());
+
+    await act(async () => {
+      await Promise.resolve();
+    });
+
+    act(() => {
+      result.current.markWorkspaceSleeping(workspaceOne.id);
+    });
+
+    const asleep = result.current.workspaces.find(
+      (entry) => entry.id === workspaceOne.id,
+    );
+    expect(asleep?.connected).toBe(false);
+    expect(asleep?.sleeping).toBe(true);
+
+    listWorkspacesMock.mockResolvedValue([
+      { ...workspaceOne, connected: false },
+      workspaceTwo,
+    ]);
+    await act(async () => {
+      await result.current.refreshWorkspaces();
+    });
+    expect(
+      result.current.workspaces.find((entry) => entry.id === workspaceOne.id)
+        ?.sleeping,
+    ).toBe(true);
+
+    await act(async () => {
+      result.current.setActiveWorkspaceId(workspaceOne.id);
+    });
+    await act(async () => {
+      await Promise.resolve();
+    });
+
+    expect(connectWorkspaceMock).toHaveBeenCalledWith(workspaceOne.id);
+    const awake = result.current.workspaces.find(
+      (entry) => entry.id === workspaceOne.id,
+    );
+    expect(awake?.connected).toBe(true);
+    expect(awake?.sleeping).toBe(false);
+  });
+});
+
 describe("useWorkspaces.addWorkspacesFromPaths", () => {
   it("adds multiple workspaces, activates the first, and returns structured result", async () => {
     const listWorkspacesMock = vi.mocked(listWorkspaces);
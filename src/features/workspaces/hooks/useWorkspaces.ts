@@ -57,6 +57,7 @@ export type UseWorkspacesResult = {
   ) => Promise<WorkspaceInfo | null>;
   connectWorkspace: (entry: WorkspaceInfo) => Promise<void>;
   markWorkspaceConnected: (id: string) => void;
+  markWorkspaceSleeping: (id: string) => void;
   updateWorkspaceSettings: (workspaceId: string, patch: Partial<WorkspaceSettings>) => Promise<WorkspaceInfo>;
   createWorkspaceGroup: (name: string) => Promise<WorkspaceGroup | null>;
   renameWorkspaceGroup: (groupId: string, name: string) => Promise<true | null>;
@@ -77,6 +78,8 @@ export function useWorkspaces(options: UseWorkspacesOptions = {}): UseWorkspaces
   const [activeWorkspaceId, setActiveWorkspaceId] = useState<string | null>(null);
   const [hasLoaded, setHasLoaded] = useState(false);
   const workspaceSettingsRef = useRef<Map<string, WorkspaceSettings>>(new Map());
+  const workspacesRef = useRef<WorkspaceInfo[]>([]);
+  workspacesRef.current = workspaces;
   const { onDebug, appSettings, onUpdateAppSettings } = options;
 
   const {
@@ -86,6 +89,7 @@ export function useWorkspaces(options: UseWorkspacesOptions = {}): UseWorkspaces
     connectWorkspace,
     filterWorkspacePaths,
     markWorkspaceConnected,
+    markWorkspaceSleeping,
     refreshWorkspaces,
     removeWorkspace,
     updateWorkspaceSettings,
@@ -110,6 +114,21 @@ export function useWorkspaces(options: UseWorkspacesOptions = {}): UseWorkspaces
     workspaceSettingsRef.current = next;
   }, [workspaces]);
 
+  // Selecting a sleeping workspace wakes it. One that falls asleep while
+  // selected stays asleep until it is selected again or used.
+  const selectWorkspace = useCallback(
+    (workspaceId: string | null) => {
+      setActiveWorkspaceId(workspaceId);
+      const target = workspacesRef.current.find((entry) => entry.id === workspaceId);
+      if (target?.sleeping) {
+        void connectWorkspace(target).catch(() => {
+          // Silent: connection errors show in debug panel.
+        });
+      }
+    },
+    [connectWorkspace],
+  );
+
   const activeWorkspace = useMemo(
     () => workspaces.find((entry) => entry.id === activeWorkspaceId) ?? null,
     [activeWorkspaceId, workspaces],
@@ -174,7 +193,7 @@ export function useWorkspaces(options: UseWorkspacesOptions = {}): UseWorkspaces
     ungroupedLabel: RESERVED_GROUP_NAME,
     activeWorkspace,
     activeWorkspaceId,
-    setActiveWorkspaceId,
+    setActiveWorkspaceId: selectWorkspace,
     addWorkspaceFromPath,
     addWorkspaceFromGitUrl,
     addWorkspacesFromPaths,
@@ -183,6 +202,7 @@ export function useWorkspaces(options: UseWorkspacesOptions = {}): UseWorkspaces
     addWorktreeAgent,
     connectWorkspace,
     markWorkspaceConnected,
+    markWorkspaceSleeping,
     updateWorkspaceSettings,
     createWorkspaceGroup,
     renameWorkspaceGroup,
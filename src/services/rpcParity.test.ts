@@ -48,7 +48,7 @@ function parseAppInvokeHandlers(repoRoot) {
 
 function parseSharedMethodConstants(repoRoot) {
   const sharedDir = path.join(repoRoot, "src-tauri/src/shared");
-  const files = fs.readdirSync(sharedDir).filter((file) => file.endsWith("_rpc.rs"));
+  const files = fs.readdirSync(sharedDir).filter((file) => file.endsWith("_rpc.rs") || file === "rpc_schema.rs");
   const result = new Map();
   for (const file of files) {
     const moduleName = path.basename(file, ".rs");
@@ -89,6 +89,14 @@ function parseDaemonRpcMethods(repoRoot) {
       methods.add(method);
     }
   }
+  // Connection-level methods are answered by the transport, not the dispatcher.
+  const transport = fs.readFileSync(
+    path.join(repoRoot, "src-tauri/src/bin/codex_monitor_daemon/transport.rs"),
+    "utf8",
+  );
+  for (const match of transport.matchAll(/method\s*==\s*"([a-z0-9_]+)"/g)) {
+    methods.add(match[1]);
+  }
   return methods;
 }
 
@@ -113,20 +121,20 @@ const APP_ONLY_FRONTEND_METHODS = sorted([
   "tailscale_daemon_status",
   "tailscale_daemon_stop",
   "tailscale_status",
-  "terminal_close",
-  "terminal_open",
-  "terminal_resize",
-  "terminal_write",
   "write_text_file",
 ]);
 
 const DAEMON_ONLY_METHODS = sorted([
+  "auth",
   "daemon_info",
+  "daemon_metrics",
   "daemon_shutdown",
   "dictation_transcribe",
   "ping",
   "presence_heartbeat",
+  "protocol_upgrade",
   "push_notification_state",
+  "rpc_describe",
 ]);
 
 const APP_ONLY_REGISTERED_METHODS = sorted(["push_notification_state"]);
@@ -3,12 +3,14 @@ import { open, save } from "@tauri-apps/plugin-dialog";
 import type { Options as NotificationOptions } from "@tauri-apps/plugin-notification";
 import type {
   AppSettings,
+  BackendLogTail,
   CodexUpdateResult,
   CodexDoctorResult,
   DictationAuthStatus,
   DictationModelStatus,
   DictationSessionState,
   LocalUsageSnapshot,
+  LogLevel,
   TcpDaemonStatus,
   TailscaleDaemonCommandPreview,
   TailscaleStatus,
@@ -717,6 +719,15 @@ export async function localUsageSnapshot(
   return invoke("local_usage_snapshot", payload);
 }
 
+export async function daemonLogsTail(options?: {
+  limit?: number;
+  level?: LogLevel;
+  target?: string;
+  workspaceId?: string;
+}): Promise<BackendLogTail> {
+  return invoke<BackendLogTail>("daemon_logs_tail", { ...options });
+}
+
 export async function getModelList(workspaceId: string) {
   return invoke<unknown>("model_list", { workspaceId });
 }
@@ -884,6 +895,25 @@ export type PushNotificationConfig = {
   relayUrl: string | null;
   hasRelayAuthToken: boolean;
   registeredDeviceCount: number;
+  channels: {
+    webhook: { url: string; hasSecret: boolean } | null;
+    ntfy: { url: string; topic: string; hasToken: boolean } | null;
+    gotify: { url: string } | null;
+  };
+  digestWindowMs: number | null;
+};
+
+// An empty `url` (`topic` for ntfy) removes the channel; omit it to keep it.
+export type PushChannelsPatch = {
+  webhook?: { url: string; secret?: string | null };
+  ntfy?: { url: string; topic: string; token?: string | null };
+  gotify?: { url: string; token?: string };
+};
+
+// `digestWindowMs` batches completions and errors into one summary push per
+// window; `0` turns it off, omit it to keep the current setting.
+export type PushDigestPatch = {
+  digestWindowMs?: number;
 };
 
 export async function getPushNotificationConfig(): Promise<PushNotificationConfig> {
@@ -893,10 +923,16 @@ export async function getPushNotificationConfig(): Promise<PushNotificationConfi
 export async function patchPushNotificationConfig(
   relayUrl: string | null,
   relayAuthToken: string | null,
+  channels: PushChannelsPatch = {},
+  digest: PushDigestPatch = {},
 ): Promise<PushNotificationConfig> {
   return invoke<PushNotificationConfig>("push_notification_config_patch", {
     relayUrl,
     relayAuthToken,
+    webhook: channels.webhook ?? null,
+    ntfy: channels.ntfy ?? null,
+    gotify: channels.gotify ?? null,
+    digestWindowMs: digest.digestWindowMs ?? null,
   });
 }
 
@@ -914,6 +950,37 @@ export async function registerPushDevice(
   });
 }
 
+export type PushDeviceRules = {
+  workspaceIds: string[] | null;
+  kinds: { completed: boolean; error: boolean; approval: boolean };
+  quietHours: { start: string; end: string; timeZone: string } | null;
+  minTurnDurationMs: number | null;
+};
+
+// Omitted fields are left alone; `null` clears a rule.
+export type PushDeviceRulesPatch = {
+  workspaceIds?: string[] | null;
+  kinds?: Partial<PushDeviceRules["kinds"]>;
+  quietHours?: PushDeviceRules["quietHours"];
+  minTurnDurationMs?: number | null;
+};
+
+export async function getPushDeviceRules(
+  deviceId: string,
+): Promise<PushDeviceRules> {
+  return invoke<PushDeviceRules>("push_device_rules_get", { deviceId });
+}
+
+export async function patchPushDeviceRules(
+  deviceId: string,
+  rules: PushDeviceRulesPatch,
+): Promise<PushDeviceRules> {
+  return invoke<PushDeviceRules>("push_device_rules_patch", {
+    deviceId,
+    rules,
+  });
+}
+
 export async function unregisterPushDevice(deviceId: string): Promise<void> {
   await invoke("push_unregister_device", { deviceId });
 }
@@ -1146,6 +1213,19 @@ export async function closeTerminalSession(
   return invoke("terminal_close", { workspaceId, terminalId });
 }
 
+export async function attachTerminalSession(
+  workspaceId: string,
+  terminalId: string,
+): Promise<{ running: boolean; scrollback: string }> {
+  return invoke("terminal_attach", { workspaceId, terminalId });
+}
+
+export async function listTerminalSessions(
+  workspaceId: string,
+): Promise<{ terminalId: string; running: boolean }[]> {
+  return invoke("terminal_list", { workspaceId });
+}
+
 export async function listThreads(
   workspaceId: string,
   cursor?: string | null,
@@ -1175,6 +1255,21 @@ export async function threadLiveUnsubscribe(workspaceId: string, threadId: strin
   return invoke<unknown>("thread_live_unsubscribe", { workspaceId, threadId });
 }
 
+// `null` leaves a dimension unfiltered; method patterns accept `*` globs.
+export type EventSubscriptionFilter = {
+  workspaceIds?: string[] | null;
+  threadIds?: string[] | null;
+  methods?: string[] | null;
+};
+
+export async function subscribeEvents(filter: EventSubscriptionFilter) {
+  return invoke<unknown>("subscribe_events", {
+    workspaceIds: filter.workspaceIds ?? null,
+    threadIds: filter.threadIds ?? null,
+    methods: filter.methods ?? null,
+  });
+}
+
 export async function archiveThread(workspaceId: string, threadId: string) {
   return invoke<unknown>("archive_thread", { workspaceId, threadId });
 }
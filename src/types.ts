@@ -51,6 +51,8 @@ export type WorkspaceInfo = {
   name: string;
   path: string;
   connected: boolean;
+  /** Set after `codex/sleeping`: the session pool stopped the app-server and the next use reconnects. */
+  sleeping?: boolean;
   kind?: WorkspaceKind;
   parentId?: string | null;
   worktree?: WorktreeInfo | null;
@@ -170,6 +172,8 @@ export type RemoteBackendTarget = {
   host: string;
   token: string | null;
   lastConnectedAtMs?: number | null;
+  tls?: boolean;
+  tlsCertFingerprint?: string | null;
 };
 export type ThemePreference = "system" | "light" | "dark" | "dim";
 export type PersonalityPreference = "friendly" | "pragmatic";
@@ -202,6 +206,38 @@ export type OpenAppTarget = {
   args: string[];
 };
 
+export type RequestTimeoutSettings = {
+  defaultSecs: number;
+  methods: Record<string, number>;
+  sendCancelNotifications: boolean;
+};
+
+export type SessionPoolSettings = {
+  maxLiveSessions: number;
+  idleTimeoutSecs: number;
+};
+
+export type LogLevel = "error" | "warn" | "info" | "debug" | "trace";
+
+export type LoggingSettings = {
+  level: string;
+  format: "text" | "json";
+  maxFiles: number;
+};
+
+export type BackendLogEntry = {
+  ts: string;
+  level: LogLevel;
+  target: string;
+  message: string;
+  workspaceId?: string;
+};
+
+export type BackendLogTail = {
+  entries: BackendLogEntry[];
+  logFile: string | null;
+};
+
 export type AppSettings = {
   codexBin: string | null;
   codexArgs: string | null;
@@ -211,6 +247,7 @@ export type AppSettings = {
   remoteBackendToken: string | null;
   remoteBackends: RemoteBackendTarget[];
   activeRemoteBackendId: string | null;
+  aggregateRemoteBackends: boolean;
   keepDaemonRunningAfterAppClose: boolean;
   defaultAccessMode: AccessMode;
   reviewDeliveryMode: "inline" | "detached";
@@ -276,6 +313,9 @@ export type AppSettings = {
   workspaceGroups: WorkspaceGroup[];
   openAppTargets: OpenAppTarget[];
   selectedOpenAppId: string;
+  requestTimeouts: RequestTimeoutSettings;
+  sessionPool: SessionPoolSettings;
+  logging: LoggingSettings;
 };
 
 export type CodexFeatureStage =
@@ -8,6 +8,7 @@ export const SUPPORTED_APP_SERVER_METHODS = [
   "codex/backgroundThread",
   "codex/connected",
   "codex/event/skills_update_available",
+  "codex/sleeping",
   "error",
   "item/agentMessage/delta",
   "item/commandExecution/outputDelta",